@@ -277,6 +277,7 @@ impl WorkloadSpec {
                     name: None,
                     inner: Ok(nom_sql::CacheInner::Statement(Box::new(stmt))),
                     always: false,
+                    options: Default::default(),
                 };
 
                 let _ = conn
@@ -193,6 +193,7 @@ impl ArbitraryQueryParameters {
             name: Some("q".into()),
             inner: Ok(nom_sql::CacheInner::Statement(Box::new(stmt))),
             always: false,
+            options: Default::default(),
         };
 
         // FIXME: Use correct dialect.
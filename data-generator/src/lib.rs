@@ -11,7 +11,7 @@ use rand::distributions::Standard;
 use rand::prelude::Distribution;
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng, RngCore};
-use readyset_data::{DfType, DfValue, Dialect};
+use readyset_data::{DfType, DfValue, Dialect, Range, RangeBound};
 use rust_decimal::Decimal;
 use zipf::ZipfDistribution;
 
@@ -404,6 +404,40 @@ impl NonRepeatingGenerator {
     }
 }
 
+/// Generate a value of the given range [`SqlType`] that includes the `lower`th and excludes the
+/// `upper`th element of the range's element type, counting up from a fixed starting point.
+///
+/// Panics if `typ` is not a range type.
+fn range_of_type(typ: &SqlType, lower: u32, upper: u32) -> DfValue {
+    let element = |n: u32| -> DfValue {
+        match typ {
+            SqlType::Int4Range => (n.min(i32::MAX as u32) as i32).into(),
+            SqlType::Int8Range => (n as i64).into(),
+            SqlType::NumRange => Decimal::from(n).into(),
+            SqlType::TsRange => (NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0)
+                + Duration::minutes(n as _))
+            .into(),
+            SqlType::TsTzRange => DfValue::from(
+                FixedOffset::west(18_000).ymd(2020, 1, 1).and_hms(0, 0, 0)
+                    + Duration::minutes(n as _),
+            ),
+            SqlType::DateRange => {
+                DfValue::from(NaiveDate::from_ymd(1000, 1, 1) + Duration::days(n as _))
+            }
+            _ => panic!("{typ:?} is not a range type"),
+        }
+    };
+
+    // The bounds are generated in order, so this can't fail
+    #[allow(clippy::unwrap_used)]
+    Range::new(
+        RangeBound::Inclusive(element(lower)),
+        RangeBound::Exclusive(element(upper)),
+    )
+    .unwrap()
+    .into()
+}
+
 /// Generate a constant value with the given [`SqlType`]
 ///
 /// The following SqlTypes do not have a representation as a [`DfValue`] and will panic if passed:
@@ -470,6 +504,12 @@ pub fn value_of_type(typ: &SqlType) -> DfValue {
             DfValue::from(BitVec::with_capacity(size_opt.unwrap_or(1) as usize))
         }
         SqlType::VarBit(_) => DfValue::from(BitVec::new()),
+        SqlType::Int4Range
+        | SqlType::Int8Range
+        | SqlType::NumRange
+        | SqlType::TsRange
+        | SqlType::TsTzRange
+        | SqlType::DateRange => range_of_type(typ, 1, 10),
         SqlType::Array(_) => unimplemented!(),
         SqlType::Other(_) => unimplemented!(),
     }
@@ -609,6 +649,15 @@ where
         }
         SqlType::Serial => (rng.gen::<u32>() + 1).into(),
         SqlType::BigSerial => (rng.gen::<u64>() + 1).into(),
+        SqlType::Int4Range
+        | SqlType::Int8Range
+        | SqlType::NumRange
+        | SqlType::TsRange
+        | SqlType::TsTzRange
+        | SqlType::DateRange => {
+            let lower = rng.gen_range(0..1000);
+            range_of_type(typ, lower, lower + rng.gen_range(1..100))
+        }
         SqlType::Array(_) => unimplemented!(),
         SqlType::Other(_) => unimplemented!(),
     }
@@ -739,6 +788,12 @@ pub fn unique_value_of_type(typ: &SqlType, idx: u32) -> DfValue {
         }
         SqlType::Serial => (idx + 1).into(),
         SqlType::BigSerial => ((idx + 1) as u64).into(),
+        SqlType::Int4Range
+        | SqlType::Int8Range
+        | SqlType::NumRange
+        | SqlType::TsRange
+        | SqlType::TsTzRange
+        | SqlType::DateRange => range_of_type(typ, idx, idx.saturating_add(1)),
         SqlType::Array(_) => unimplemented!(),
         SqlType::Other(_) => unimplemented!(),
     }
@@ -69,16 +69,37 @@ pub struct UpstreamConfig {
     #[serde(default = "default_replicator_restart_timeout")]
     pub replicator_restart_timeout: Duration,
 
+    /// A comma-separated list of tables to replicate. If not set, all tables are replicated.
+    /// Tables may be glob patterns, such as `public.*` or `public.user_*`.
     #[clap(long, env = "REPLICATION_TABLES")]
     #[serde(default)]
     pub replication_tables: Option<RedactedString>,
 
+    /// A comma-separated list of tables to exclude from replication, even if they would otherwise
+    /// be included by --replication-tables. Tables may be glob patterns, such as `public.tmp_*`.
+    #[clap(long, env = "REPLICATION_TABLES_IGNORE")]
+    #[serde(default)]
+    pub replication_tables_ignore: Option<RedactedString>,
+
     /// Sets the time (in seconds) between reports of progress snapshotting the database. A value
     /// of 0 disables reporting.
     #[clap(long, default_value = "30")]
     #[serde(default = "default_snapshot_report_interval_secs")]
     pub snapshot_report_interval_secs: u16,
 
+    /// The approximate number of rows in each chunk when snapshotting large tables. Tables with
+    /// a single-column integer primary key and more rows than this are split into primary key
+    /// ranges which are copied concurrently, and whose progress is persisted so that an
+    /// interrupted snapshot can resume where it left off. A value of 0 disables chunking.
+    #[clap(long, env = "SNAPSHOT_CHUNK_SIZE", default_value = "1000000")]
+    #[serde(default = "default_snapshot_chunk_size")]
+    pub snapshot_chunk_size: usize,
+
+    /// The maximum number of chunks of a single table to copy concurrently while snapshotting.
+    #[clap(long, env = "SNAPSHOT_CHUNK_PARALLELISM", default_value = "4")]
+    #[serde(default = "default_snapshot_chunk_parallelism")]
+    pub snapshot_chunk_parallelism: usize,
+
     /// Sets the connection count for the pool that is used for replication and snapshotting.
     #[clap(long, default_value = "50")]
     #[serde(default)]
@@ -120,6 +141,14 @@ fn default_snapshot_report_interval_secs() -> u16 {
     UpstreamConfig::default().snapshot_report_interval_secs
 }
 
+fn default_snapshot_chunk_size() -> usize {
+    UpstreamConfig::default().snapshot_chunk_size
+}
+
+fn default_snapshot_chunk_parallelism() -> usize {
+    UpstreamConfig::default().snapshot_chunk_parallelism
+}
+
 fn duration_from_seconds(i: &str) -> Result<Duration, ParseIntError> {
     i.parse::<u64>().map(Duration::from_secs)
 }
@@ -133,7 +162,10 @@ impl Default for UpstreamConfig {
             replication_server_id: Default::default(),
             replicator_restart_timeout: Duration::from_secs(30),
             replication_tables: Default::default(),
+            replication_tables_ignore: Default::default(),
             snapshot_report_interval_secs: 30,
+            snapshot_chunk_size: 1_000_000,
+            snapshot_chunk_parallelism: 4,
             ssl_root_cert: None,
             replication_pool_size: 50,
         }
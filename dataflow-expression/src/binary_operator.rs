@@ -110,6 +110,17 @@ pub enum BinaryOperator {
 
     /// PostgreSQL `#-` operator to remove from JSONB values via a key/index.
     JsonSubtractPath,
+
+    /// PostgreSQL `@>` operator with a range on the left-hand side, to check whether a range
+    /// contains either an element or another range.
+    RangeContains,
+
+    /// PostgreSQL `<@` operator with a range on the right-hand side, to check whether an element
+    /// or a range is contained in a range.
+    RangeContainedIn,
+
+    /// PostgreSQL `&&` operator, to check whether two ranges overlap.
+    RangeOverlaps,
 }
 
 impl BinaryOperator {
@@ -119,7 +130,7 @@ impl BinaryOperator {
         op: SqlBinaryOperator,
         dialect: Dialect,
         left_type: &DfType,
-        _right_type: &DfType,
+        right_type: &DfType,
     ) -> ReadySetResult<(Self, bool)> {
         use SqlBinaryOperator::*;
         match op {
@@ -174,8 +185,14 @@ impl BinaryOperator {
             }
             HashArrow1 => Ok((Self::JsonKeyPathExtract, false)),
             HashArrow2 => Ok((Self::JsonKeyPathExtractText, false)),
+            AtArrowRight if left_type.is_range() => Ok((Self::RangeContains, false)),
+            AtArrowLeft if right_type.is_range() => Ok((Self::RangeContainedIn, false)),
             AtArrowRight => Ok((Self::JsonContains, false)),
             AtArrowLeft => Ok((Self::JsonContainedIn, false)),
+            DoubleAmpersand if dialect.engine() != SqlEngine::PostgreSQL => {
+                unsupported!("''{op}' not available in {}'", dialect.engine())
+            }
+            DoubleAmpersand => Ok((Self::RangeOverlaps, false)),
         }
     }
 
@@ -273,6 +290,25 @@ impl BinaryOperator {
             JsonPathExtract | JsonPathExtractUnquote => {
                 unsupported!("'{self}' operator not implemented yet for MySQL")
             }
+
+            // The non-range side of the range operators is either a range of the same type, or an
+            // element of that range
+            RangeContains => match left_type {
+                DfType::Range(_) if right_type.is_range() => Ok((None, None)),
+                DfType::Range(element_type) => Ok((None, Some((**element_type).clone()))),
+                _ => error(Left, "a range type"),
+            },
+            RangeContainedIn => match right_type {
+                DfType::Range(_) if left_type.is_range() => Ok((None, None)),
+                DfType::Range(element_type) => Ok((Some((**element_type).clone()), None)),
+                _ => error(Right, "a range type"),
+            },
+            RangeOverlaps => match (left_type, right_type) {
+                (DfType::Range(_), DfType::Range(_)) => Ok((None, None)),
+                (DfType::Range(_), _) => Ok((None, Some(left_type.clone()))),
+                (_, DfType::Range(_)) => Ok((Some(right_type.clone()), None)),
+                _ => error(Left, "a range type"),
+            },
         }
     }
 
@@ -299,7 +335,10 @@ impl BinaryOperator {
             | Self::JsonAnyExists
             | Self::JsonAllExists
             | Self::JsonContains
-            | Self::JsonContainedIn => Ok(DfType::Bool),
+            | Self::JsonContainedIn
+            | Self::RangeContains
+            | Self::RangeContainedIn
+            | Self::RangeOverlaps => Ok(DfType::Bool),
 
             Self::JsonPathExtractUnquote
             | Self::JsonKeyExtractText
@@ -336,8 +375,9 @@ impl fmt::Display for BinaryOperator {
             Self::JsonPathExtractUnquote | Self::JsonKeyExtractText => "->>",
             Self::JsonKeyPathExtract => "#>",
             Self::JsonKeyPathExtractText => "#>>",
-            Self::JsonContains => "@>",
-            Self::JsonContainedIn => "<@",
+            Self::JsonContains | Self::RangeContains => "@>",
+            Self::JsonContainedIn | Self::RangeContainedIn => "<@",
+            Self::RangeOverlaps => "&&",
         };
         f.write_str(op)
     }
@@ -361,6 +401,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn range_operator_lowering() {
+        let range_type = DfType::Range(Box::new(DfType::Int));
+        assert_eq!(
+            BinaryOperator::from_sql_op(
+                SqlBinaryOperator::AtArrowRight,
+                Dialect::DEFAULT_POSTGRESQL,
+                &range_type,
+                &DfType::Int
+            )
+            .unwrap(),
+            (BinaryOperator::RangeContains, false)
+        );
+        assert_eq!(
+            BinaryOperator::from_sql_op(
+                SqlBinaryOperator::AtArrowLeft,
+                Dialect::DEFAULT_POSTGRESQL,
+                &DfType::Unknown,
+                &range_type
+            )
+            .unwrap(),
+            (BinaryOperator::RangeContainedIn, false)
+        );
+        assert_eq!(
+            BinaryOperator::from_sql_op(
+                SqlBinaryOperator::AtArrowRight,
+                Dialect::DEFAULT_POSTGRESQL,
+                &DfType::Jsonb,
+                &DfType::Jsonb
+            )
+            .unwrap(),
+            (BinaryOperator::JsonContains, false)
+        );
+        assert_eq!(
+            BinaryOperator::RangeContains
+                .argument_type_coercions(&range_type, &DfType::Unknown)
+                .unwrap(),
+            (None, Some(DfType::Int))
+        );
+        assert_eq!(
+            BinaryOperator::RangeOverlaps
+                .argument_type_coercions(&DfType::Unknown, &range_type)
+                .unwrap(),
+            (Some(range_type), None)
+        );
+    }
+
     mod output_type {
         use super::*;
 
@@ -131,6 +131,26 @@ fn eval_binary_op(op: BinaryOperator, left: &DfValue, right: &DfValue) -> ReadyS
             let child = left.to_json()?;
             Ok(json::json_contains(&right.to_json()?, &child).into())
         }
+        RangeContains => {
+            let range = non_null!(left).as_range()?;
+            Ok(match non_null!(right) {
+                DfValue::Range(other) => range.contains_range(other),
+                element => range.contains_value(element),
+            }
+            .into())
+        }
+        RangeContainedIn => {
+            let range = non_null!(right).as_range()?;
+            Ok(match non_null!(left) {
+                DfValue::Range(other) => range.contains_range(other),
+                element => range.contains_value(element),
+            }
+            .into())
+        }
+        RangeOverlaps => Ok(non_null!(left)
+            .as_range()?
+            .overlaps(non_null!(right).as_range()?)
+            .into()),
         JsonConcat => {
             let mut left_json = left.to_json()?;
             let mut right_json = right.to_json()?;
@@ -1240,6 +1260,38 @@ mod tests {
         assert_eq!(result, expected.into())
     }
 
+    #[test]
+    fn eval_range_operators() {
+        for (expr, expected) in [
+            ("'[1,10)'::int4range @> 5", true),
+            ("'[1,10)'::int4range @> 10", false),
+            ("'(1,10]'::int4range @> '[2,10]'::int4range", true),
+            ("'[2,3]'::int4range <@ '[1,10)'::int4range", true),
+            ("5 <@ 'empty'::int4range", false),
+            ("'[1,10)'::int4range && '[9,20)'::int4range", true),
+            ("'[1,10)'::int4range && '[10,20)'", false),
+            (
+                "'[2023-01-01,2023-02-01)'::tstzrange @> '2023-01-15 12:00:00'::timestamptz",
+                true,
+            ),
+            (
+                "'[2023-01-01,2023-02-01)'::daterange @> '2023-02-01'::date",
+                false,
+            ),
+        ] {
+            assert_eq!(
+                eval_expr(expr, PostgreSQL),
+                expected.into(),
+                "incorrect result for `{expr}`"
+            );
+        }
+
+        assert_eq!(
+            eval_expr("'[1,10)'::int4range @> NULL::int", PostgreSQL),
+            DfValue::None
+        );
+    }
+
     /// Tests evaluation of `JsonContains` and `JsonContainedIn` binary ops.
     mod json_contains {
         use super::*;
@@ -730,8 +730,13 @@ impl Expr {
                     ty,
                 })
             }
-            AstExpr::Exists(_) => unsupported!("EXISTS not currently supported"),
+            AstExpr::Exists(_) => {
+                unsupported!("EXISTS is only supported as a top-level predicate in WHERE clauses")
+            }
             AstExpr::Variable(_) => unsupported!("Variables not currently supported"),
+            AstExpr::WindowFunction { .. } => unsupported!(
+                "Window functions are only supported as top-level expressions in the SELECT clause"
+            ),
             AstExpr::Between { .. } | AstExpr::NestedSelect(_) | AstExpr::In { .. } => {
                 internal!(
                     "Expression should have been desugared earlier: {}",
@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 /// Representation of an aggregate function
 // TODO(grfn): It would be really nice to deduplicate this somehow with the grouped operator itself
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
-pub enum PostLookupAggregateFunction {
+pub enum PostLookupAggregateFunction<Column = usize> {
     /// Add together all the input numbers
     ///
     /// Note that this encapsulates both `SUM` *and* `COUNT` in base SQL, as re-aggregating counts
@@ -26,6 +26,77 @@ pub enum PostLookupAggregateFunction {
     Max,
     /// Take the minimum input value
     Min,
+    /// Take the bitwise AND of all the input numbers
+    BitAnd,
+    /// Take the bitwise OR of all the input numbers
+    BitOr,
+    /// Take the bitwise XOR of all the input numbers
+    BitXor,
+    /// True if all the input values are true
+    BoolAnd,
+    /// True if any of the input values are true
+    BoolOr,
+    /// Compute the variance (or the standard deviation, if `stddev` is true) of either a sample
+    /// (if `sample` is true) or the whole population, from the count, sum, and sum of squares of
+    /// the values, which are re-aggregated in the given columns
+    Variance {
+        sample: bool,
+        stddev: bool,
+        count: Column,
+        sum: Column,
+        sum_sq: Column,
+    },
+}
+
+/// Apply the given bitwise operation to the two's complement representations of two integer
+/// values, ignoring NULLs
+fn apply_bitwise<F>(val1: &DfValue, val2: &DfValue, op: F) -> ReadySetResult<DfValue>
+where
+    F: Fn(u64, u64) -> u64,
+{
+    match (val1, val2) {
+        (DfValue::None, v) | (v, DfValue::None) => Ok(v.clone()),
+        _ => Ok(DfValue::from(op(
+            i128::try_from(val1)? as u64,
+            i128::try_from(val2)? as u64,
+        ))),
+    }
+}
+
+impl<Column> PostLookupAggregateFunction<Column> {
+    /// Transform all column references in self by applying a function
+    pub fn map_columns<F, C2, E>(self, mut f: F) -> Result<PostLookupAggregateFunction<C2>, E>
+    where
+        F: FnMut(Column) -> Result<C2, E>,
+    {
+        use PostLookupAggregateFunction::*;
+
+        Ok(match self {
+            Sum => Sum,
+            Product => Product,
+            GroupConcat { separator } => GroupConcat { separator },
+            Max => Max,
+            Min => Min,
+            BitAnd => BitAnd,
+            BitOr => BitOr,
+            BitXor => BitXor,
+            BoolAnd => BoolAnd,
+            BoolOr => BoolOr,
+            Variance {
+                sample,
+                stddev,
+                count,
+                sum,
+                sum_sq,
+            } => Variance {
+                sample,
+                stddev,
+                count: f(count)?,
+                sum: f(sum)?,
+                sum_sq: f(sum_sq)?,
+            },
+        })
+    }
 }
 
 impl PostLookupAggregateFunction {
@@ -45,6 +116,57 @@ impl PostLookupAggregateFunction {
             .into()),
             PostLookupAggregateFunction::Max => Ok(cmp::max(val1, val2).clone()),
             PostLookupAggregateFunction::Min => Ok(cmp::min(val1, val2).clone()),
+            PostLookupAggregateFunction::BitAnd => apply_bitwise(val1, val2, |v1, v2| v1 & v2),
+            PostLookupAggregateFunction::BitOr => apply_bitwise(val1, val2, |v1, v2| v1 | v2),
+            PostLookupAggregateFunction::BitXor => apply_bitwise(val1, val2, |v1, v2| v1 ^ v2),
+            PostLookupAggregateFunction::BoolAnd | PostLookupAggregateFunction::BoolOr => {
+                match (val1, val2) {
+                    (DfValue::None, v) | (v, DfValue::None) => Ok(v.clone()),
+                    _ if *self == PostLookupAggregateFunction::BoolAnd => {
+                        Ok((val1.is_truthy() && val2.is_truthy()).into())
+                    }
+                    _ => Ok((val1.is_truthy() || val2.is_truthy()).into()),
+                }
+            }
+            // Recomputed by `finish` once all the values of the group have been aggregated
+            PostLookupAggregateFunction::Variance { .. } => Ok(val1.clone()),
+        }
+    }
+
+    /// Compute the final value of this aggregate function for a row whose values have all been
+    /// aggregated, for functions that can't be re-aggregated from their own values, or return
+    /// `None` otherwise
+    pub fn finish(&self, row: &[DfValue]) -> ReadySetResult<Option<DfValue>> {
+        match *self {
+            PostLookupAggregateFunction::Variance {
+                sample,
+                stddev,
+                count,
+                sum,
+                sum_sq,
+            } => {
+                let value = |col: usize| -> ReadySetResult<f64> {
+                    match row.get(col) {
+                        Some(DfValue::None) => Ok(0.0),
+                        Some(v) => f64::try_from(v),
+                        None => internal!("Aggregated column {col} not in row"),
+                    }
+                };
+                let (count, sum, sum_sq) = (value(count)?, value(sum)?, value(sum_sq)?);
+                let divisor = if sample { count - 1.0 } else { count };
+                if divisor <= 0.0 {
+                    return Ok(Some(DfValue::None));
+                }
+
+                // Subtracting the squared mean can introduce small negative rounding errors
+                let variance = ((sum_sq - sum * sum / count) / divisor).max(0.0);
+                Ok(Some(DfValue::Double(if stddev {
+                    variance.sqrt()
+                } else {
+                    variance
+                })))
+            }
+            _ => Ok(None),
         }
     }
 }
@@ -55,7 +177,7 @@ pub struct PostLookupAggregate<Column = usize> {
     /// The column index in the result set containing the already-aggregated values
     pub column: Column,
     /// The aggregate function to perform
-    pub function: PostLookupAggregateFunction,
+    pub function: PostLookupAggregateFunction<Column>,
 }
 
 impl<Column> PostLookupAggregate<Column> {
@@ -66,7 +188,7 @@ impl<Column> PostLookupAggregate<Column> {
     {
         Ok(PostLookupAggregate {
             column: f(self.column)?,
-            function: self.function,
+            function: self.function.map_columns(&mut f)?,
         })
     }
 }
@@ -167,7 +289,10 @@ pub struct PostLookup {
     ///
     /// If an empty `Vec` is specified, rows are sorted in lexicographic order.
     pub order_by: Option<Vec<(usize, OrderType)>>,
-    /// Maximum number of records to return
+    /// Maximum number of records to return.
+    ///
+    /// For queries with a parametrized `LIMIT`, the limit is instead provided with each lookup,
+    /// and this is the maximum limit that a lookup may request (if any).
     pub limit: Option<usize>,
     /// Indices of the columns requested in the query. Reader will filter out all other projected
     /// columns
@@ -0,0 +1,165 @@
+//! Persistent storage for the keys that were read most recently from partially materialized
+//! readers, which are replayed into the readers after a restart so that they don't start out
+//! empty.
+//!
+//! The keys of each reader are stored in a RocksDB database of their own, next to the databases
+//! of the [`PersistentState`](crate::PersistentState) of base tables.
+
+use std::fs;
+use std::path::PathBuf;
+
+use bincode::Options;
+use readyset_data::DfValue;
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+
+use crate::persistent_state::{base_options, Error, Result};
+use crate::{DurabilityMode, PersistenceParameters};
+
+/// The first byte of the RocksDB keys the hot keys are stored under, which is followed by the
+/// rank of the key (0 for the most recently read key) so that iterating returns them in order
+const HOT_KEY_PREFIX: u8 = b'k';
+
+/// The RocksDB key the serialization version of the stored keys is stored under
+const SERDE_VERSION_KEY: &[u8] = b"serde_version";
+
+fn hot_key(rank: usize) -> Vec<u8> {
+    let mut key = vec![HOT_KEY_PREFIX];
+    key.extend((rank as u64).to_be_bytes());
+    key
+}
+
+/// The most recently read keys of a partially materialized reader, stored in RocksDB
+pub struct HotKeys {
+    db: DB,
+    path: PathBuf,
+}
+
+impl HotKeys {
+    /// Opens the database for the hot keys of the reader with the given name, creating it if it
+    /// doesn't exist yet, or returns `None` if state isn't persisted across restarts with the
+    /// given parameters.
+    ///
+    /// The `name` should be unique to the reader, and is formatted the same way as the names of
+    /// the [`PersistentState`](crate::PersistentState) of base tables.
+    pub fn open(name: &str, params: &PersistenceParameters) -> Result<Option<Self>> {
+        if params.mode != DurabilityMode::Permanent {
+            return Ok(None);
+        }
+
+        let mut path = params.db_dir.clone().unwrap_or_else(|| ".".into());
+        if !path.is_dir() {
+            fs::create_dir_all(&path)?;
+        }
+        path.push(format!("{name}.hot_keys.db"));
+
+        let db = DB::open(&base_options(params), &path)?;
+        Ok(Some(Self { db, path }))
+    }
+
+    /// Atomically replaces the stored keys with `keys`, which should be ordered from most to least
+    /// recently read
+    pub fn save(&self, keys: &[Vec<DfValue>]) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        batch.delete_range(vec![HOT_KEY_PREFIX], vec![HOT_KEY_PREFIX + 1]);
+        batch.put(SERDE_VERSION_KEY, [DfValue::SERDE_VERSION]);
+        for (rank, key) in keys.iter().enumerate() {
+            batch.put(hot_key(rank), bincode::options().serialize(key).unwrap());
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Returns the stored keys, ordered from most to least recently read.
+    ///
+    /// Returns no keys if the keys were stored with a different serialization version than ours.
+    pub fn load(&self) -> Result<Vec<Vec<DfValue>>> {
+        if self.db.get(SERDE_VERSION_KEY)?.as_deref() != Some(&[DfValue::SERDE_VERSION][..]) {
+            return Ok(vec![]);
+        }
+
+        let mut keys = vec![];
+        for kv in self
+            .db
+            .iterator(IteratorMode::From(&[HOT_KEY_PREFIX], Direction::Forward))
+        {
+            let (key, value) = kv?;
+            if key.first() != Some(&HOT_KEY_PREFIX) {
+                break;
+            }
+            keys.push(
+                bincode::options()
+                    .deserialize(&value)
+                    .map_err(|_| Error::BadDbFormat)?,
+            );
+        }
+        Ok(keys)
+    }
+
+    /// Closes the database and deletes it from disk
+    pub fn destroy(self) -> Result<()> {
+        let Self { db, path } = self;
+        drop(db);
+        fs::remove_dir_all(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(mode: DurabilityMode, dir: &std::path::Path) -> PersistenceParameters {
+        PersistenceParameters {
+            mode,
+            db_filename_prefix: "hot_keys".into(),
+            db_dir: Some(dir.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_when_permanent() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            HotKeys::open("q", &params(DurabilityMode::DeleteOnExit, dir.path()))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            HotKeys::open("q", &params(DurabilityMode::Permanent, dir.path()))
+                .unwrap()
+                .is_some()
+        );
+        assert!(dir.path().join("q.hot_keys.db").is_dir());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = params(DurabilityMode::Permanent, dir.path());
+        let keys = vec![
+            vec![DfValue::from(1), DfValue::from("a")],
+            vec![DfValue::from(2), DfValue::None],
+            vec![DfValue::from(3), DfValue::from("c")],
+        ];
+
+        {
+            let hot_keys = HotKeys::open("q", &params).unwrap().unwrap();
+            assert_eq!(hot_keys.load().unwrap(), Vec::<Vec<DfValue>>::new());
+            hot_keys.save(&keys).unwrap();
+            assert_eq!(hot_keys.load().unwrap(), keys);
+
+            // Saving fewer keys doesn't leave any of the previous ones behind
+            hot_keys.save(&keys[1..2]).unwrap();
+            assert_eq!(hot_keys.load().unwrap(), &keys[1..2]);
+            hot_keys.save(&keys).unwrap();
+        }
+
+        // The keys survive reopening the database, as they would a restart
+        let hot_keys = HotKeys::open("q", &params).unwrap().unwrap();
+        assert_eq!(hot_keys.load().unwrap(), keys);
+
+        hot_keys.destroy().unwrap();
+        assert!(!dir.path().join("q.hot_keys.db").exists());
+    }
+}
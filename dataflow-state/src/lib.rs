@@ -1,5 +1,6 @@
 #![feature(stmt_expr_attributes, bound_map, iter_order_by, bound_as_ref)]
 
+mod hot_keys;
 mod key;
 mod keyed_state;
 mod memory_state;
@@ -25,6 +26,7 @@ use readyset_client::{KeyComparison, KeyCount};
 use readyset_data::DfValue;
 use readyset_errors::ReadySetResult;
 
+pub use crate::hot_keys::HotKeys;
 pub use crate::key::{PointKey, RangeKey};
 pub use crate::memory_state::MemoryState;
 pub use crate::persistent_state::{
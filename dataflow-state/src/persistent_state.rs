@@ -1053,7 +1053,7 @@ fn deserialize_row<T: AsRef<[u8]>>(bytes: T) -> Vec<DfValue> {
 ///
 /// This will construct the set of options that *all* column families should have regardless of
 /// index type.
-fn base_options(params: &PersistenceParameters) -> rocksdb::Options {
+pub(crate) fn base_options(params: &PersistenceParameters) -> rocksdb::Options {
     let mut opts = rocksdb::Options::default();
     opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
     opts.create_if_missing(true);
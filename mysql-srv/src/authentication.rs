@@ -1,22 +1,78 @@
-//! Implementation of MySQL's [Secure Password Authentication][0] authentication method.
+//! Implementation of MySQL's [Secure Password Authentication][0] and [SHA-2 Pluggable
+//! Authentication][1] authentication methods.
 //!
-//! The way the authentication scheme works:
+//! The way the `mysql_native_password` authentication scheme works:
 //!
 //! 1. The server sends 20-bytes of [random data](AuthData) along with the initial handshake packet
 //! 2. The client returns a 20-byte random response based on the algorithm in [`hash_password`]
 //! 3. The server runs the same algorithm, and checks the response against the result
 //!
+//! The `caching_sha2_password` authentication scheme starts the same way, except the client
+//! returns a 32-byte response based on the algorithm in [`scramble_sha256`]. If the server has
+//! previously fully authenticated the user with the same password (see [`FastAuthCache`]), it
+//! checks the response against the result ("fast auth"). Otherwise, the server asks the client to
+//! perform "full auth", where the client sends the password in cleartext if the connection is
+//! secured using TLS, or encrypted using the server's RSA public key (which the client can
+//! request) otherwise.
+//!
 //! [0]: https://dev.mysql.com/doc/internals/en/secure-password-authentication.html
+//! [1]: https://dev.mysql.com/doc/refman/8.0/en/caching-sha2-pluggable-authentication.html
+
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Mutex;
 
 use getrandom::getrandom;
+use openssl::pkey::Private;
+use openssl::rsa::{Padding, Rsa};
 use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use tokio::sync::OnceCell;
 
 use crate::error::MsqlSrvError;
 
 pub type AuthData = [u8; 20];
 
-/// The name of the (currently only) supported auth plugin
-pub const AUTH_PLUGIN_NAME: &str = "mysql_native_password";
+/// An authentication plugin supported by the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthPlugin {
+    /// The `mysql_native_password` auth plugin, based on SHA-1
+    MysqlNativePassword,
+    /// The `caching_sha2_password` auth plugin, based on SHA-256. This is the default
+    /// authentication plugin as of MySQL 8.0.
+    CachingSha2Password,
+}
+
+impl AuthPlugin {
+    /// Returns the name of this auth plugin, as sent over the wire
+    pub fn name(self) -> &'static str {
+        match self {
+            AuthPlugin::MysqlNativePassword => "mysql_native_password",
+            AuthPlugin::CachingSha2Password => "caching_sha2_password",
+        }
+    }
+
+    /// Returns the auth plugin with the given name, or `None` if the plugin is not supported
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mysql_native_password" => Some(AuthPlugin::MysqlNativePassword),
+            "caching_sha2_password" => Some(AuthPlugin::CachingSha2Password),
+            _ => None,
+        }
+    }
+}
+
+/// Status byte sent by the server in an AuthMoreData packet to indicate that
+/// `caching_sha2_password` fast auth succeeded
+pub const FAST_AUTH_SUCCESS: u8 = 0x03;
+
+/// Status byte sent by the server in an AuthMoreData packet to ask the client to perform
+/// `caching_sha2_password` full auth
+pub const PERFORM_FULL_AUTHENTICATION: u8 = 0x04;
+
+/// Byte sent by the client during `caching_sha2_password` full auth to request the server's RSA
+/// public key
+pub const REQUEST_PUBLIC_KEY: u8 = 0x02;
 
 /// Bytewise-XOR b1 with b2 in-place
 fn xor_slice_mut<const N: usize>(b1: &mut [u8; N], b2: &[u8; N]) {
@@ -58,6 +114,157 @@ pub fn hash_password(password: &[u8], auth_data: &AuthData) -> [u8; 20] {
     res
 }
 
+fn sha256(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
+/// Hash a password alongside random challenge data per the `caching_sha2_password` [fast auth
+/// algorithm][0].
+///
+/// The algorithm is:
+///
+/// ```notrust
+/// SHA256(password) XOR SHA256(SHA256(SHA256(password)) <concat> "20-bytes random data from server")
+/// ```
+///
+/// [0]: https://dev.mysql.com/doc/dev/mysql-server/latest/page_caching_sha2_authentication_exchanges.html
+pub fn scramble_sha256(password: &[u8], auth_data: &AuthData) -> [u8; 32] {
+    let mut res = sha256(password);
+    let mut salted = [0u8; 52];
+    salted[..32].clone_from_slice(&sha256(&res));
+    salted[32..].clone_from_slice(auth_data);
+    xor_slice_mut(&mut res, &sha256(&salted));
+    res
+}
+
+/// The maximum number of users [`FastAuthCache`] holds entries for. Once it's full, the entry of
+/// the user who least recently authenticated is evicted to make room for a new one.
+const FAST_AUTH_CACHE_CAPACITY: usize = 1024;
+
+/// The entries in a [`FastAuthCache`]
+struct FastAuthEntries {
+    /// A double SHA-256 hash of the password each user last completed full auth with, along with
+    /// the value of `clock` when they last authenticated
+    entries: BTreeMap<String, ([u8; 32], u64)>,
+    /// Incremented every time a user authenticates, to keep track of which entry to evict
+    clock: u64,
+    capacity: usize,
+}
+
+impl FastAuthEntries {
+    const fn new(capacity: usize) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            clock: 0,
+            capacity,
+        }
+    }
+
+    fn contains(&mut self, username: &str, password: &[u8]) -> bool {
+        let Some((hash, last_used)) = self.entries.get_mut(username) else {
+            return false;
+        };
+        if *hash != sha256(&sha256(password)) {
+            // The user's password has changed since they completed full auth
+            self.entries.remove(username);
+            return false;
+        }
+        self.clock += 1;
+        *last_used = self.clock;
+        true
+    }
+
+    fn insert(&mut self, username: &str, password: &[u8]) {
+        if !self.entries.contains_key(username) && self.entries.len() >= self.capacity {
+            let least_recent = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(username, _)| username.clone());
+            if let Some(least_recent) = least_recent {
+                self.entries.remove(&least_recent);
+            }
+        }
+        self.clock += 1;
+        self.entries
+            .insert(username.to_owned(), (sha256(&sha256(password)), self.clock));
+    }
+}
+
+/// Cache of users which have successfully completed `caching_sha2_password` full auth, and are
+/// therefore allowed to use fast auth on subsequent connections.
+///
+/// Like the cache in MySQL itself, this is shared by all connections to the server. Each user's
+/// entry holds a double SHA-256 hash of their password rather than the password itself, and is
+/// removed once their password changes. The cache holds entries for at most
+/// [`FAST_AUTH_CACHE_CAPACITY`] users.
+pub struct FastAuthCache;
+
+static FAST_AUTH_CACHE: Mutex<FastAuthEntries> =
+    Mutex::new(FastAuthEntries::new(FAST_AUTH_CACHE_CAPACITY));
+
+impl FastAuthCache {
+    /// Returns true if the given user has previously completed full auth with the given password
+    pub fn contains(username: &str, password: &[u8]) -> bool {
+        FAST_AUTH_CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(username, password)
+    }
+
+    /// Record that the given user has completed full auth with the given password
+    pub fn insert(username: &str, password: &[u8]) {
+        FAST_AUTH_CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(username, password)
+    }
+}
+
+/// The RSA key pair used to exchange passwords during `caching_sha2_password` full auth over
+/// insecure connections. Generated once, the first time it's needed.
+static RSA_KEY: OnceCell<Rsa<Private>> = OnceCell::const_new();
+
+/// Returns the server's RSA key pair, generating it if necessary
+pub async fn rsa_key() -> Result<&'static Rsa<Private>, MsqlSrvError> {
+    RSA_KEY
+        .get_or_try_init(|| async {
+            // Generating the key can take hundreds of milliseconds, so don't block the runtime
+            Ok(tokio::task::spawn_blocking(|| Rsa::generate(2048))
+                .await
+                .map_err(io::Error::from)??)
+        })
+        .await
+}
+
+/// Decrypt a password sent by the client during `caching_sha2_password` full auth, which is the
+/// null-terminated password XORed with the (repeated) auth challenge data, encrypted with our RSA
+/// public key using OAEP padding.
+pub fn decrypt_password(
+    encrypted: &[u8],
+    auth_data: &AuthData,
+    key: &Rsa<Private>,
+) -> Result<Vec<u8>, MsqlSrvError> {
+    let mut password = vec![0u8; key.size() as usize];
+    let len = key.private_decrypt(encrypted, &mut password, Padding::PKCS1_OAEP)?;
+    password.truncate(len);
+    password
+        .iter_mut()
+        .zip(auth_data.iter().cycle())
+        .for_each(|(x, y)| *x ^= y);
+    Ok(strip_null_terminator(password))
+}
+
+/// Strip the trailing null terminator, if any, from a password sent by the client
+pub fn strip_null_terminator(mut password: Vec<u8>) -> Vec<u8> {
+    if password.last() == Some(&0) {
+        password.pop();
+    }
+    password
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,4 +282,66 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn scramble_sha256_works() {
+        let auth_data: AuthData = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
+        let password = b"password";
+        let result = scramble_sha256(password, &auth_data);
+        assert_eq!(
+            result,
+            mysql_common::scramble::scramble_sha256(&auth_data, password).unwrap()
+        );
+    }
+
+    #[test]
+    fn fast_auth_cache_password_change() {
+        let mut cache = FastAuthEntries::new(10);
+        cache.insert("alice", b"password");
+        assert!(cache.contains("alice", b"password"));
+        assert!(!cache.contains("bob", b"password"));
+
+        assert!(!cache.contains("alice", b"new password"));
+        // Changing the password removes the user's entry
+        assert!(!cache.contains("alice", b"password"));
+        assert!(cache.entries.is_empty());
+
+        cache.insert("alice", b"new password");
+        assert!(cache.contains("alice", b"new password"));
+    }
+
+    #[test]
+    fn fast_auth_cache_is_bounded() {
+        let mut cache = FastAuthEntries::new(2);
+        cache.insert("alice", b"a");
+        cache.insert("bob", b"b");
+        assert!(cache.contains("alice", b"a"));
+
+        cache.insert("carol", b"c");
+        assert_eq!(cache.entries.len(), 2);
+        assert!(cache.contains("alice", b"a"));
+        assert!(!cache.contains("bob", b"b"));
+        assert!(cache.contains("carol", b"c"));
+    }
+
+    #[test]
+    fn decrypt_password_round_trips() {
+        let auth_data: AuthData = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
+        let key = Rsa::generate(2048).unwrap();
+        let mut plaintext = b"a password longer than the auth data\0".to_vec();
+        plaintext
+            .iter_mut()
+            .zip(auth_data.iter().cycle())
+            .for_each(|(x, y)| *x ^= y);
+        let mut encrypted = vec![0u8; key.size() as usize];
+        let len = key
+            .public_encrypt(&plaintext, &mut encrypted, Padding::PKCS1_OAEP)
+            .unwrap();
+        encrypted.truncate(len);
+
+        assert_eq!(
+            decrypt_password(&encrypted, &auth_data, &key).unwrap(),
+            b"a password longer than the auth data"
+        );
+    }
 }
@@ -52,6 +52,21 @@ fn null_terminated_string(i: &[u8]) -> IResult<&[u8], &str> {
     Ok((i, res))
 }
 
+/// Length of an SSLRequest packet, which is a truncated HandshakeResponse containing only the
+/// capability flags, max packet size, character set, and filler.
+const SSL_REQUEST_LEN: usize = 4 + 4 + 1 + 23;
+
+/// Returns true if the given client handshake response packet is an SSLRequest, indicating that
+/// the client wants to upgrade the connection to TLS before sending its actual HandshakeResponse.
+///
+/// <https://dev.mysql.com/doc/internals/en/connection-phase-packets.html#packet-Protocol::SSLRequest>
+pub fn is_ssl_request(i: &[u8]) -> bool {
+    i.len() == SSL_REQUEST_LEN
+        && le_u32::<_, nom::error::Error<_>>(i).map_or(false, |(_, capabilities)| {
+            CapabilityFlags::from_bits_truncate(capabilities).contains(CapabilityFlags::CLIENT_SSL)
+        })
+}
+
 /// <https://dev.mysql.com/doc/internals/en/connection-phase-packets.html#packet-Protocol::HandshakeResponse41>
 pub fn client_handshake(i: &[u8]) -> IResult<&[u8], ClientHandshake<'_>> {
     let (i, capabilities) = map(le_u32, CapabilityFlags::from_bits_truncate)(i)?;
@@ -215,6 +230,22 @@ mod tests {
         assert_eq!(handshake.maxps, 16777216);
     }
 
+    #[test]
+    fn it_detects_ssl_request() {
+        let mut ssl_request = vec![0x00, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x21];
+        ssl_request.extend([0x00; 23]);
+        assert!(is_ssl_request(&ssl_request));
+
+        // A full handshake response with the same capabilities is not an SSLRequest
+        let mut handshake = ssl_request.clone();
+        handshake.extend(b"jon\0\0");
+        assert!(!is_ssl_request(&handshake));
+
+        // Neither is a packet of the right length without CLIENT_SSL set
+        ssl_request[1] = 0x02;
+        assert!(!is_ssl_request(&ssl_request));
+    }
+
     #[tokio::test]
     async fn it_parses_request() {
         let data = &[
@@ -43,6 +43,10 @@ pub enum MsqlSrvError {
     /// generation of random data for the auth challenge failed.
     #[error("Failed to generate random bytes for auth challenge data")]
     GetRandomError,
+    /// Used to propagate errors from OpenSSL while generating the RSA key pair used for
+    /// `caching_sha2_password` authentication, or decrypting a password with it.
+    #[error("RSA error: {0}")]
+    RsaError(#[from] openssl::error::ErrorStack),
     /// Error returned when attempting to read from an invalid index.
     #[error("Failed to retrieve data from index")]
     IndexingError,
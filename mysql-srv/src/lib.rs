@@ -164,17 +164,24 @@ use std::io;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use constants::{CLIENT_PLUGIN_AUTH, PROTOCOL_41, RESERVED, SECURE_CONNECTION};
+use constants::{CLIENT_PLUGIN_AUTH, PROTOCOL_41, RESERVED, SECURE_CONNECTION, SSL};
 use error::{other_error, OtherErrorKind};
 use mysql_common::constants::CapabilityFlags;
 use readyset_data::DfType;
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, WriteHalf};
 use tokio::net;
+use tokio_native_tls::{TlsAcceptor, TlsStream};
 use tracing::{debug, info, trace};
 use writers::write_err;
 
-use crate::authentication::{generate_auth_data, hash_password, AUTH_PLUGIN_NAME};
+pub use crate::authentication::AuthPlugin;
+use crate::authentication::{
+    decrypt_password, generate_auth_data, hash_password, rsa_key, scramble_sha256,
+    strip_null_terminator, AuthData, FastAuthCache, FAST_AUTH_SUCCESS, PERFORM_FULL_AUTHENTICATION,
+    REQUEST_PUBLIC_KEY,
+};
 pub use crate::myc::constants::{ColumnFlags, ColumnType, StatusFlags};
+pub use crate::packet::PrefixedStream;
 pub use crate::writers::prepare_column_definitions;
 
 mod authentication;
@@ -284,6 +291,14 @@ pub trait MySqlShim<W: AsyncWrite + Unpin + Send> {
     fn require_authentication(&self) -> bool {
         true
     }
+
+    /// The authentication plugin to ask clients to use in the initial handshake.
+    ///
+    /// Clients which respond to the handshake using a different supported authentication plugin
+    /// are authenticated using that plugin instead.
+    fn default_auth_plugin(&self) -> AuthPlugin {
+        AuthPlugin::MysqlNativePassword
+    }
 }
 
 /// Stores a preencoded result schema for a prepared MySQL statement
@@ -323,6 +338,86 @@ impl<B: MySqlShim<net::tcp::OwnedWriteHalf> + Send>
         let (reader, writer) = stream.into_split();
         MySqlIntermediary::run_on(shim, reader, writer, enable_statement_logging).await
     }
+
+    /// Create a new server over a TCP stream, optionally upgrading the connection to TLS during
+    /// the handshake, and process client commands until the client disconnects or an error
+    /// occurs.
+    ///
+    /// If `tls_acceptor` is provided, the server advertises `CLIENT_SSL` to the client, and if the
+    /// client responds with an `SSLRequest` the rest of the connection (including authentication)
+    /// happens over TLS. If `require_tls` is true, clients that do not request TLS are rejected
+    /// before authentication.
+    pub async fn run_on_tcp_with_tls(
+        shim: B,
+        stream: net::TcpStream,
+        enable_statement_logging: bool,
+        tls_acceptor: Option<Arc<TlsAcceptor>>,
+        require_tls: bool,
+    ) -> Result<(), io::Error>
+    where
+        B: MySqlShim<WriteHalf<TlsStream<PrefixedStream<net::TcpStream>>>>,
+    {
+        stream.set_nodelay(true)?;
+        let (reader, writer) = stream.into_split();
+        let mut mi = MySqlIntermediary::new(shim, reader, writer, enable_statement_logging);
+
+        let auth_data =
+            generate_auth_data().map_err(|_| other_error(OtherErrorKind::AuthDataErr))?;
+        let capabilities = if tls_acceptor.is_some() {
+            CAPABILITIES | SSL
+        } else {
+            CAPABILITIES
+        };
+        mi.write_handshake(&auth_data, capabilities).await?;
+        let (seq, handshake) = mi.read_handshake_response().await?;
+
+        if !commands::is_ssl_request(&handshake) {
+            if require_tls {
+                debug!("Client did not request TLS, which is required; returning error");
+                mi.writer.set_seq(seq + 1);
+                writers::write_err(
+                    ErrorKind::ER_ACCESS_DENIED_ERROR,
+                    b"Connections using insecure transport are prohibited",
+                    &mut mi.writer,
+                )
+                .await?;
+                mi.writer.flush().await?;
+                return Ok(());
+            }
+            return mi.serve(seq, &handshake, &auth_data, false).await;
+        }
+
+        let Some(tls_acceptor) = tls_acceptor else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "client requested TLS, which was not offered by the server",
+            ));
+        };
+
+        let MySqlIntermediary {
+            shim,
+            reader,
+            writer,
+            ..
+        } = mi;
+        // The client may have sent the start of the TLS handshake right behind the SSLRequest, in
+        // which case the packet reader has already read it from the stream
+        let (buffered, reader) = reader.into_inner();
+        let stream = reader
+            .reunite(writer.into_inner())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let stream = PrefixedStream::new(buffered, stream);
+        let tls_stream = tls_acceptor
+            .accept(stream)
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::ConnectionAborted, e))?;
+        debug!("Established TLS connection");
+
+        let (reader, writer) = tokio::io::split(tls_stream);
+        let mut mi = MySqlIntermediary::new(shim, reader, writer, enable_statement_logging);
+        let (seq, handshake) = mi.read_handshake_response().await?;
+        mi.serve(seq, &handshake, &auth_data, true).await
+    }
 }
 
 impl<B: MySqlShim<S> + Send, S: AsyncRead + AsyncWrite + Clone + Unpin + Send>
@@ -369,67 +464,105 @@ impl<B: MySqlShim<W> + Send, R: AsyncRead + Unpin, W: AsyncWrite + Unpin + Send>
         writer: W,
         enable_statement_logging: bool,
     ) -> Result<(), io::Error> {
-        let r = packet::PacketReader::new(reader);
-        let w = packet::PacketWriter::new(writer);
-        let mut mi = MySqlIntermediary {
+        let mut mi = MySqlIntermediary::new(shim, reader, writer, enable_statement_logging);
+        let auth_data =
+            generate_auth_data().map_err(|_| other_error(OtherErrorKind::AuthDataErr))?;
+        mi.write_handshake(&auth_data, CAPABILITIES).await?;
+        let (seq, handshake) = mi.read_handshake_response().await?;
+        mi.serve(seq, &handshake, &auth_data, false).await
+    }
+
+    fn new(shim: B, reader: R, writer: W, enable_statement_logging: bool) -> Self {
+        MySqlIntermediary {
             shim,
-            reader: r,
-            writer: w,
+            reader: packet::PacketReader::new(reader),
+            writer: packet::PacketWriter::new(writer),
             schema_cache: HashMap::new(),
             enable_statement_logging,
-        };
-        if let (true, database) = mi.init().await? {
+        }
+    }
+
+    /// Authenticate the client given the HandshakeResponse packet it sent us, then process
+    /// client commands until the client disconnects or an error occurs.
+    ///
+    /// `secure` indicates whether the connection to the client is secured using TLS.
+    async fn serve(
+        mut self,
+        seq: u8,
+        handshake: &[u8],
+        auth_data: &AuthData,
+        secure: bool,
+    ) -> Result<(), io::Error> {
+        if let (true, database) = self.authenticate(seq, handshake, auth_data, secure).await? {
             if let Some(database) = database {
-                mi.shim.on_init(&database, None).await?;
+                self.shim.on_init(&database, None).await?;
             }
-            mi.run().await?;
+            self.run().await?;
         }
         Ok(())
     }
 
-    /// Handle the client handshake messages for establishing capabilities and handling
-    /// authentication.
-    ///
-    /// First build a HandshakeV10 packet to send to the client, then attempt to receieve and parse
-    /// the HandshakeResponse packet that the client should send back to us. More packets may be
-    /// sent and received as needed to complete authentication.
-    ///
-    /// If no errors are encountered, the return value contains a tuple of a boolean to indicate
-    /// whether authentication was successful, and a database name if one was specified by the
-    /// client in the handshake response.
-    async fn init(&mut self) -> Result<(bool, Option<String>), io::Error> {
-        let auth_data =
-            generate_auth_data().map_err(|_| other_error(OtherErrorKind::AuthDataErr))?;
-
+    /// Build and send the initial HandshakeV10 packet to the client, advertising the given
+    /// capability flags.
+    async fn write_handshake(
+        &mut self,
+        auth_data: &AuthData,
+        capabilities: u32,
+    ) -> Result<(), io::Error> {
+        let auth_plugin_name = self.shim.default_auth_plugin().name();
         let mut init_packet = Vec::with_capacity(
-            1 + 16 + 4 + 8 + 1 + 2 + 1 + 2 + 2 + 1 + 6 + 4 + 12 + 1 + AUTH_PLUGIN_NAME.len() + 1,
+            1 + 16 + 4 + 8 + 1 + 2 + 1 + 2 + 2 + 1 + 6 + 4 + 12 + 1 + auth_plugin_name.len() + 1,
         );
         init_packet.extend_from_slice(&[10]); // protocol 10
         init_packet.extend_from_slice(self.shim.version().as_bytes());
         init_packet.extend_from_slice(&[0x08, 0x00, 0x00, 0x00]); // TODO: connection ID
         init_packet.extend_from_slice(&auth_data[..8]);
         init_packet.push(0);
-        init_packet.extend_from_slice(&CAPABILITIES.to_le_bytes()[..2]);
+        init_packet.extend_from_slice(&capabilities.to_le_bytes()[..2]);
         init_packet.extend_from_slice(&[0x21]); // UTF8_GENERAL_CI
         init_packet.extend_from_slice(&[0x00, 0x00]); // status flags
-        init_packet.extend_from_slice(&CAPABILITIES.to_le_bytes()[2..]);
+        init_packet.extend_from_slice(&capabilities.to_le_bytes()[2..]);
         init_packet.extend_from_slice(&[auth_data.len() as u8]);
         init_packet.extend_from_slice(&[0x00; 10][..]); // filler
         init_packet.extend_from_slice(&auth_data[8..]);
         init_packet.push(0);
-        init_packet.extend_from_slice(AUTH_PLUGIN_NAME.as_bytes());
+        init_packet.extend_from_slice(auth_plugin_name.as_bytes());
         init_packet.push(0);
 
         self.writer.write_packet(&init_packet).await?;
-        self.writer.flush().await?;
+        self.writer.flush().await
+    }
 
-        let (seq, handshake_bytes) = self.reader.next().await?.ok_or_else(|| {
+    /// Receive the client's response to the initial handshake packet, which is either a
+    /// HandshakeResponse or an SSLRequest, along with its sequence number.
+    async fn read_handshake_response(&mut self) -> Result<(u8, Vec<u8>), io::Error> {
+        let (seq, handshake) = self.reader.next().await?.ok_or_else(|| {
             io::Error::new(
                 io::ErrorKind::ConnectionAborted,
                 "peer terminated connection",
             )
         })?;
-        let handshake = commands::client_handshake(&handshake_bytes)
+        Ok((seq, handshake.to_vec()))
+    }
+
+    /// Handle the client's HandshakeResponse packet, for establishing capabilities and handling
+    /// authentication.
+    ///
+    /// Attempt to parse the HandshakeResponse packet that the client sent in response to our
+    /// HandshakeV10 packet. More packets may be sent and received as needed to complete
+    /// authentication.
+    ///
+    /// If no errors are encountered, the return value contains a tuple of a boolean to indicate
+    /// whether authentication was successful, and a database name if one was specified by the
+    /// client in the handshake response.
+    async fn authenticate(
+        &mut self,
+        seq: u8,
+        handshake_bytes: &[u8],
+        auth_data: &AuthData,
+        secure: bool,
+    ) -> Result<(bool, Option<String>), io::Error> {
+        let handshake = commands::client_handshake(handshake_bytes)
             .map_err(|e| match e {
                 nom::Err::Incomplete(_) => io::Error::new(
                     io::ErrorKind::UnexpectedEof,
@@ -459,75 +592,84 @@ impl<B: MySqlShim<W> + Send, R: AsyncRead + Unpin, W: AsyncWrite + Unpin + Send>
         let database = handshake.database.map(String::from);
         let client_auth_plugin = handshake.auth_plugin_name.map(|s| s.to_owned());
 
-        let handshake_password = if client_auth_plugin.iter().all(|apn| apn != AUTH_PLUGIN_NAME)
+        let (auth_plugin, handshake_password) = match client_auth_plugin
+            .as_deref()
+            .and_then(AuthPlugin::from_name)
+        {
             // Some clients (at the very least certain versions of PHP's MySQL PDO library) send an
             // empty password response in the initial handshake, even if the auth plugin is set and
             // correct. We want to send a switch-authentication request in that case too
-            || password.is_empty()
-        {
-            // Authentication mismatch - try to switch auth plugins
-
-            if !handshake
-                .capabilities
-                .contains(CapabilityFlags::CLIENT_SECURE_CONNECTION)
-            {
-                debug!("Client does not support SECURE_CONNECTION, returning authentication error");
-                writers::write_err(
-                    ErrorKind::ER_NOT_SUPPORTED_AUTH_MODE,
-                    b"Client does not support authentication protocol requested by server; \
-                      consider upgrading MySQL client",
-                    &mut self.writer,
-                )
-                .await?;
-                return Ok((false, database));
-            }
-
-            debug!(
-                ?client_auth_plugin,
-                "Client offered incorrect authentication plugin, sending switch request",
-            );
-
-            let mut auth_switch_request_packet =
-                Vec::with_capacity(1 + AUTH_PLUGIN_NAME.len() + 1 + auth_data.len() + 1);
-            auth_switch_request_packet.push(0xfe);
-            auth_switch_request_packet.extend_from_slice(AUTH_PLUGIN_NAME.as_bytes());
-            auth_switch_request_packet.push(0);
-            auth_switch_request_packet.extend_from_slice(&auth_data);
-            auth_switch_request_packet.push(0);
-            self.writer
-                .write_packet(&auth_switch_request_packet)
-                .await?;
-            self.writer.flush().await?;
+            Some(auth_plugin) if !password.is_empty() => (auth_plugin, password),
+            _ => {
+                // Authentication mismatch - try to switch auth plugins
+
+                if !handshake
+                    .capabilities
+                    .contains(CapabilityFlags::CLIENT_SECURE_CONNECTION)
+                {
+                    debug!(
+                        "Client does not support SECURE_CONNECTION, returning authentication error"
+                    );
+                    writers::write_err(
+                        ErrorKind::ER_NOT_SUPPORTED_AUTH_MODE,
+                        b"Client does not support authentication protocol requested by server; \
+                          consider upgrading MySQL client",
+                        &mut self.writer,
+                    )
+                    .await?;
+                    return Ok((false, database));
+                }
 
-            let (seq, auth_switch_response) = self.reader.next().await?.ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::ConnectionAborted,
-                    "peer terminated connection",
-                )
-            })?;
-            self.writer.set_seq(seq + 1);
+                debug!(
+                    ?client_auth_plugin,
+                    "Client offered incorrect authentication plugin, sending switch request",
+                );
+
+                let auth_plugin = self.shim.default_auth_plugin();
+                let auth_plugin_name = auth_plugin.name();
+                let mut auth_switch_request_packet =
+                    Vec::with_capacity(1 + auth_plugin_name.len() + 1 + auth_data.len() + 1);
+                auth_switch_request_packet.push(0xfe);
+                auth_switch_request_packet.extend_from_slice(auth_plugin_name.as_bytes());
+                auth_switch_request_packet.push(0);
+                auth_switch_request_packet.extend_from_slice(auth_data);
+                auth_switch_request_packet.push(0);
+                self.writer
+                    .write_packet(&auth_switch_request_packet)
+                    .await?;
+                self.writer.flush().await?;
 
-            auth_switch_response.to_vec()
-        } else {
-            password
+                (auth_plugin, self.read_auth_response().await?)
+            }
         };
 
         let auth_success = !self.shim.require_authentication()
-            || self
-                .shim
-                .password_for_username(&username)
-                .map_or(false, |password| {
-                    let expected = hash_password(&password, &auth_data);
-                    let actual = handshake_password.as_slice();
-                    trace!(?expected, ?actual);
-                    expected == actual
-                });
+            || match auth_plugin {
+                AuthPlugin::MysqlNativePassword => self
+                    .shim
+                    .password_for_username(&username)
+                    .map_or(false, |password| {
+                        let expected = hash_password(&password, auth_data);
+                        let actual = handshake_password.as_slice();
+                        trace!(?expected, ?actual);
+                        expected == actual
+                    }),
+                AuthPlugin::CachingSha2Password => {
+                    self.caching_sha2_password_auth(
+                        &username,
+                        &handshake_password,
+                        auth_data,
+                        secure,
+                    )
+                    .await?
+                }
+            };
 
         if auth_success {
             debug!(%username, "Successfully authenticated client");
             writers::write_ok_packet(&mut self.writer, 0, 0, StatusFlags::empty()).await?;
         } else {
-            debug!(%username, ?client_auth_plugin, "Received incorrect password");
+            debug!(%username, ?auth_plugin, "Received incorrect password");
             writers::write_err(
                 ErrorKind::ER_ACCESS_DENIED_ERROR,
                 format!("Access denied for user {}", username).as_bytes(),
@@ -540,6 +682,82 @@ impl<B: MySqlShim<W> + Send, R: AsyncRead + Unpin, W: AsyncWrite + Unpin + Send>
         Ok((auth_success, database))
     }
 
+    /// Read the next packet sent by the client during authentication
+    async fn read_auth_response(&mut self) -> Result<Vec<u8>, io::Error> {
+        let (seq, response) = self.reader.next().await?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "peer terminated connection",
+            )
+        })?;
+        self.writer.set_seq(seq + 1);
+        Ok(response.to_vec())
+    }
+
+    /// Perform the rest of the `caching_sha2_password` authentication exchange, given the
+    /// scrambled password sent by the client, and return whether authentication was successful.
+    ///
+    /// If the user has previously completed full auth with their current password, we check the
+    /// scrambled password ("fast auth"). Otherwise, we ask the client to perform full auth, where
+    /// the client sends us their password either in cleartext (if the connection is `secure`) or
+    /// encrypted with our RSA public key.
+    async fn caching_sha2_password_auth(
+        &mut self,
+        username: &str,
+        scramble: &[u8],
+        auth_data: &AuthData,
+        secure: bool,
+    ) -> Result<bool, io::Error> {
+        let password = self.shim.password_for_username(username);
+
+        if let Some(password) = password
+            .as_deref()
+            .filter(|password| FastAuthCache::contains(username, password))
+        {
+            let expected = scramble_sha256(password, auth_data);
+            trace!(?expected, actual = ?scramble);
+            if expected != scramble {
+                return Ok(false);
+            }
+            debug!(%username, "caching_sha2_password fast auth succeeded");
+            self.writer.write_packet(&[0x01, FAST_AUTH_SUCCESS]).await?;
+            return Ok(true);
+        }
+
+        debug!(%username, "Requesting caching_sha2_password full auth");
+        self.writer
+            .write_packet(&[0x01, PERFORM_FULL_AUTHENTICATION])
+            .await?;
+        self.writer.flush().await?;
+        let mut response = self.read_auth_response().await?;
+
+        let actual = if secure {
+            strip_null_terminator(response)
+        } else {
+            let key = rsa_key().await?;
+            if response == [REQUEST_PUBLIC_KEY] {
+                let mut public_key_packet = vec![0x01];
+                public_key_packet.extend(key.public_key_to_pem().map_err(MsqlSrvError::from)?);
+                self.writer.write_packet(&public_key_packet).await?;
+                self.writer.flush().await?;
+                response = self.read_auth_response().await?;
+            }
+            match decrypt_password(&response, auth_data, key) {
+                Ok(password) => password,
+                Err(error) => {
+                    debug!(%error, "Failed to decrypt password sent by client");
+                    return Ok(false);
+                }
+            }
+        };
+
+        let success = password.map_or(false, |password| password == actual);
+        if success {
+            FastAuthCache::insert(username, &actual);
+        }
+        Ok(success)
+    }
+
     async fn run(mut self) -> Result<(), io::Error> {
         use crate::commands::Command;
 
@@ -1,7 +1,9 @@
 use std::io::{self, IoSlice};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 
 use crate::error::{other_error, OtherErrorKind};
 use crate::resultset::{MAX_POOL_ROWS, MAX_POOL_ROW_CAPACITY};
@@ -89,6 +91,12 @@ impl<W: AsyncWrite + Unpin> PacketWriter<W> {
         self.seq = seq;
     }
 
+    /// Consumes the packet writer, returning the underlying writer. Any packets that have been
+    /// enqueued but not yet written are discarded, so the writer should be flushed first.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
     /// Flushes the writer. This function *must* be called before dropping the internal writer
     /// or writes may be lossed.
     pub async fn flush(&mut self) -> Result<(), tokio::io::Error> {
@@ -243,6 +251,90 @@ impl<R> PacketReader<R> {
             r,
         }
     }
+
+    /// Consumes the packet reader, returning the bytes it has read from the underlying reader
+    /// that have not yet been returned as packets, along with the underlying reader itself.
+    ///
+    /// Anything that goes on to read from the underlying reader should read the returned bytes
+    /// first, eg by wrapping it in a [`PrefixedStream`].
+    pub fn into_inner(mut self) -> (Vec<u8>, R) {
+        let start = self.bytes.len() - self.remaining;
+        (self.bytes.split_off(start), self.r)
+    }
+}
+
+/// A stream that returns the given prefix of bytes from reads before reading from the stream it
+/// wraps. Writes go straight to the wrapped stream.
+///
+/// Used to hand a stream over to something else (such as a TLS handshake) after reading from it
+/// through a buffer that may have read past what we handled ourselves.
+pub struct PrefixedStream<S> {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: S,
+}
+
+impl<S> PrefixedStream<S> {
+    pub fn new(prefix: Vec<u8>, inner: S) -> Self {
+        PrefixedStream {
+            prefix,
+            pos: 0,
+            inner,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if let Some(rest) = this.prefix.get(this.pos..).filter(|rest| !rest.is_empty()) {
+            let n = rest.len().min(buf.remaining());
+            #[allow(clippy::indexing_slicing)] // n <= rest.len()
+            buf.put_slice(&rest[..n]);
+            this.pos += n;
+            if this.pos == this.prefix.len() {
+                // Free the prefix as soon as we're done with it, as the stream may be long-lived
+                this.prefix = Vec::new();
+                this.pos = 0;
+            }
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
 }
 
 impl<R: AsyncRead + Unpin> PacketReader<R> {
@@ -502,4 +594,19 @@ mod tests {
 
         assert!(reader.next().await.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn into_inner_keeps_unhandled_bytes() {
+        let mut data = vec![0x01, 0, 0, 0, 0x10];
+        data.extend(b"more");
+        let mut reader = PacketReader::new(&data[..]);
+        assert_eq!(&*reader.next().await.unwrap().unwrap().1, &[0x10][..]);
+
+        let (buffered, inner) = reader.into_inner();
+        let mut stream = PrefixedStream::new(buffered, &b" and the rest"[..]);
+        let mut read = String::new();
+        stream.read_to_string(&mut read).await.unwrap();
+        assert_eq!(read, "more and the rest");
+        assert!(inner.is_empty());
+    }
 }
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, OptsBuilder, SslOpts};
+use mysql_srv::{
+    AuthPlugin, CachedSchema, InitWriter, MySqlIntermediary, MySqlShim, ParamParser,
+    QueryResultWriter, StatementMetaWriter,
+};
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+use tokio_native_tls::{native_tls, TlsAcceptor};
+
+struct TestBackend {
+    auth_plugin: AuthPlugin,
+}
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send + 'static> MySqlShim<W> for TestBackend {
+    async fn on_prepare(
+        &mut self,
+        _query: &str,
+        _info: StatementMetaWriter<'_, W>,
+        _schema_cache: &mut HashMap<u32, CachedSchema>,
+    ) -> io::Result<()> {
+        panic!() // never called
+    }
+
+    async fn on_execute(
+        &mut self,
+        _id: u32,
+        _params: ParamParser<'_>,
+        _results: QueryResultWriter<'_, W>,
+        _schema_cache: &mut HashMap<u32, CachedSchema>,
+    ) -> io::Result<()> {
+        panic!() // never called
+    }
+
+    async fn on_close(&mut self, _stmt: u32) {}
+
+    async fn on_query(
+        &mut self,
+        _query: &str,
+        results: QueryResultWriter<'_, W>,
+    ) -> io::Result<()> {
+        // Dummy response
+        results.completed(0, 0, None).await
+    }
+
+    async fn on_init(&mut self, _: &str, w: Option<InitWriter<'_, W>>) -> io::Result<()> {
+        w.unwrap().ok().await
+    }
+
+    fn password_for_username(&self, _username: &str) -> Option<Vec<u8>> {
+        Some(b"password".to_vec())
+    }
+
+    fn default_auth_plugin(&self) -> AuthPlugin {
+        self.auth_plugin
+    }
+
+    fn version(&self) -> String {
+        "8.0.26-readyset\0".to_string()
+    }
+}
+
+fn tls_acceptor() -> Arc<TlsAcceptor> {
+    // Load the identity file as bytes (using relative path)
+    let identity_file = include_bytes!("tls_certs/keyStore.p12");
+    // Test identify file does not require password
+    let identity = native_tls::Identity::from_pkcs12(identity_file, "").unwrap();
+    Arc::new(TlsAcceptor::from(
+        native_tls::TlsAcceptor::new(identity).unwrap(),
+    ))
+}
+
+/// Start a server accepting connections in the background, returning the port it's listening on
+async fn start_server(auth_plugin: AuthPlugin, tls_acceptor: Option<Arc<TlsAcceptor>>) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            tokio::spawn(MySqlIntermediary::run_on_tcp_with_tls(
+                TestBackend { auth_plugin },
+                socket,
+                false,
+                tls_acceptor.clone(),
+                false,
+            ));
+        }
+    });
+    port
+}
+
+fn opts(port: u16, user: &str, password: &str) -> OptsBuilder {
+    OptsBuilder::default()
+        .ip_or_hostname("127.0.0.1")
+        .tcp_port(port)
+        .user(Some(user))
+        .pass(Some(password))
+        .prefer_socket(false)
+}
+
+async fn connect(opts: OptsBuilder) -> mysql_async::Result<()> {
+    let mut conn = Conn::new(opts).await?;
+    conn.query_drop("FAKE QUERY").await?;
+    conn.disconnect().await
+}
+
+// Each test uses a different username, since users who have completed full auth are cached for
+// the lifetime of the process.
+
+#[tokio::test(flavor = "multi_thread")]
+async fn caching_sha2_password_full_auth_with_rsa() {
+    let port = start_server(AuthPlugin::CachingSha2Password, None).await;
+
+    // The first connection performs full auth, exchanging the password using RSA
+    connect(opts(port, "rsa_user", "password")).await.unwrap();
+    // The second connection can use fast auth
+    connect(opts(port, "rsa_user", "password")).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn caching_sha2_password_full_auth_with_tls() {
+    let port = start_server(AuthPlugin::CachingSha2Password, Some(tls_acceptor())).await;
+    // The test certificate is self signed, which by default the client rejects
+    let ssl_opts = SslOpts::default().with_danger_accept_invalid_certs(true);
+
+    // The first connection performs full auth, sending the password in cleartext over TLS
+    connect(opts(port, "tls_user", "password").ssl_opts(ssl_opts.clone()))
+        .await
+        .unwrap();
+    // The second connection can use fast auth
+    connect(opts(port, "tls_user", "password").ssl_opts(ssl_opts))
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn caching_sha2_password_bad_password() {
+    let port = start_server(AuthPlugin::CachingSha2Password, None).await;
+
+    // Full auth fails with the wrong password
+    connect(opts(port, "bad_password_user", "wrong"))
+        .await
+        .unwrap_err();
+    connect(opts(port, "bad_password_user", "password"))
+        .await
+        .unwrap();
+    // So does fast auth
+    connect(opts(port, "bad_password_user", "wrong"))
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mysql_native_password() {
+    let port = start_server(AuthPlugin::MysqlNativePassword, None).await;
+    connect(opts(port, "native_user", "password"))
+        .await
+        .unwrap();
+    connect(opts(port, "native_user", "wrong"))
+        .await
+        .unwrap_err();
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mysql::prelude::Queryable;
+use mysql::{OptsBuilder, SslOpts};
+use mysql_srv::{
+    CachedSchema, ErrorKind, InitWriter, MySqlIntermediary, MySqlShim, ParamParser,
+    QueryResultWriter, StatementMetaWriter,
+};
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+use tokio_native_tls::{native_tls, TlsAcceptor};
+
+struct TestBackend;
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send + 'static> MySqlShim<W> for TestBackend {
+    async fn on_prepare(
+        &mut self,
+        _query: &str,
+        _info: StatementMetaWriter<'_, W>,
+        _schema_cache: &mut HashMap<u32, CachedSchema>,
+    ) -> io::Result<()> {
+        panic!() // never called
+    }
+
+    async fn on_execute(
+        &mut self,
+        _id: u32,
+        _params: ParamParser<'_>,
+        _results: QueryResultWriter<'_, W>,
+        _schema_cache: &mut HashMap<u32, CachedSchema>,
+    ) -> io::Result<()> {
+        panic!() // never called
+    }
+
+    async fn on_close(&mut self, _stmt: u32) {}
+
+    async fn on_query(
+        &mut self,
+        _query: &str,
+        results: QueryResultWriter<'_, W>,
+    ) -> io::Result<()> {
+        // Dummy response
+        results.completed(0, 0, None).await
+    }
+
+    async fn on_init(&mut self, _: &str, w: Option<InitWriter<'_, W>>) -> io::Result<()> {
+        w.unwrap().ok().await
+    }
+
+    fn password_for_username(&self, _username: &str) -> Option<Vec<u8>> {
+        Some(b"password".to_vec())
+    }
+
+    fn version(&self) -> String {
+        "8.0.26-readyset\0".to_string()
+    }
+}
+
+fn tls_acceptor() -> Arc<TlsAcceptor> {
+    // Load the identity file as bytes (using relative path)
+    let identity_file = include_bytes!("tls_certs/keyStore.p12");
+    // Test identify file does not require password
+    let identity = native_tls::Identity::from_pkcs12(identity_file, "").unwrap();
+    Arc::new(TlsAcceptor::from(
+        native_tls::TlsAcceptor::new(identity).unwrap(),
+    ))
+}
+
+/// Start a server accepting a single connection, returning the port it's listening on
+async fn start_server(tls_acceptor: Option<Arc<TlsAcceptor>>, require_tls: bool) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let _ = MySqlIntermediary::run_on_tcp_with_tls(
+            TestBackend,
+            socket,
+            false,
+            tls_acceptor,
+            require_tls,
+        )
+        .await;
+    });
+    port
+}
+
+fn opts(port: u16) -> OptsBuilder {
+    OptsBuilder::new()
+        .ip_or_hostname(Some("127.0.0.1"))
+        .tcp_port(port)
+        .user(Some("user"))
+        .pass(Some("password"))
+}
+
+fn ssl_opts() -> SslOpts {
+    // The test certificate is self signed, which by default the client rejects
+    SslOpts::default().with_danger_accept_invalid_certs(true)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect() {
+    let port = start_server(Some(tls_acceptor()), false).await;
+    tokio::task::spawn_blocking(move || {
+        let mut conn = mysql::Conn::new(opts(port).ssl_opts(Some(ssl_opts()))).unwrap();
+        conn.query_drop("FAKE QUERY").unwrap();
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_without_tls_when_not_required() {
+    let port = start_server(Some(tls_acceptor()), false).await;
+    tokio::task::spawn_blocking(move || {
+        let mut conn = mysql::Conn::new(opts(port)).unwrap();
+        conn.query_drop("FAKE QUERY").unwrap();
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reject_insecure_connection_when_tls_required() {
+    let port = start_server(Some(tls_acceptor()), true).await;
+    let res = tokio::task::spawn_blocking(move || mysql::Conn::new(opts(port)))
+        .await
+        .unwrap();
+    match res {
+        Err(mysql::Error::MySqlError(err)) => {
+            assert_eq!(err.code, u16::from(ErrorKind::ER_ACCESS_DENIED_ERROR));
+        }
+        Err(err) => panic!("Not a mysql error: {:?}", err),
+        Ok(_) => panic!("Connection without TLS should have been rejected"),
+    }
+}
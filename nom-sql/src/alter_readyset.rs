@@ -0,0 +1,136 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::value;
+use nom_locate::LocatedSpan;
+use serde::{Deserialize, Serialize};
+
+use crate::common::statement_terminator;
+use crate::table::replicator_table_list;
+use crate::whitespace::whitespace1;
+use crate::{Dialect, NomSqlResult, Relation};
+
+/// ALTER READYSET statements
+///
+/// This is a non-standard ReadySet-specific extension to SQL, used to change the set of tables
+/// being replicated from the upstream database without restarting ReadySet.
+///
+/// Tables are specified in the same format as the `--replication-tables` option, and may be glob
+/// patterns such as `public.*` or `public.user_*`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum AlterReadysetStatement {
+    /// Begin replicating all tables matching any of the given patterns
+    AddTables(Vec<Relation>),
+    /// Stop replicating all tables matching any of the given patterns, dropping them and any
+    /// caches that depend on them
+    DropTables(Vec<Relation>),
+}
+
+impl Display for AlterReadysetStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER READYSET ")?;
+        let (op, tables) = match self {
+            AlterReadysetStatement::AddTables(tables) => ("ADD", tables),
+            AlterReadysetStatement::DropTables(tables) => ("DROP", tables),
+        };
+        write!(
+            f,
+            "{op} TABLES {}",
+            tables.iter().map(|t| t.display_unquoted()).join(", ")
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TablesOp {
+    Add,
+    Drop,
+}
+
+pub(crate) fn alter_readyset_statement(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], AlterReadysetStatement> {
+    move |i| {
+        let (i, _) = tag_no_case("alter")(i)?;
+        let (i, _) = whitespace1(i)?;
+        let (i, _) = tag_no_case("readyset")(i)?;
+        let (i, _) = whitespace1(i)?;
+        let (i, op) = alt((
+            value(TablesOp::Add, tag_no_case("add")),
+            value(TablesOp::Drop, tag_no_case("drop")),
+        ))(i)?;
+        let (i, _) = whitespace1(i)?;
+        let (i, _) = tag_no_case("tables")(i)?;
+        let (i, _) = whitespace1(i)?;
+        let (i, tables) = replicator_table_list(dialect)(i)?;
+        let (i, _) = statement_terminator(i)?;
+
+        Ok((
+            i,
+            match op {
+                TablesOp::Add => AlterReadysetStatement::AddTables(tables),
+                TablesOp::Drop => AlterReadysetStatement::DropTables(tables),
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_tables() {
+        let res = alter_readyset_statement(Dialect::PostgreSQL)(LocatedSpan::new(
+            b"ALTER READYSET ADD TABLES public.t1, public.user_*, audit.*;",
+        ))
+        .unwrap()
+        .1;
+        assert_eq!(
+            res,
+            AlterReadysetStatement::AddTables(vec![
+                Relation {
+                    schema: Some("public".into()),
+                    name: "t1".into(),
+                },
+                Relation {
+                    schema: Some("public".into()),
+                    name: "user_*".into(),
+                },
+                Relation {
+                    schema: Some("audit".into()),
+                    name: "*".into(),
+                },
+            ])
+        );
+        assert_eq!(
+            res.to_string(),
+            "ALTER READYSET ADD TABLES public.t1, public.user_*, audit.*"
+        );
+    }
+
+    #[test]
+    fn drop_tables() {
+        let res = alter_readyset_statement(Dialect::MySQL)(LocatedSpan::new(
+            b"alter readyset drop tables *.tmp_?",
+        ))
+        .unwrap()
+        .1;
+        assert_eq!(
+            res,
+            AlterReadysetStatement::DropTables(vec![Relation {
+                schema: Some("*".into()),
+                name: "tmp_?".into(),
+            }])
+        );
+        assert_eq!(res.to_string(), "ALTER READYSET DROP TABLES *.tmp_?");
+    }
+
+    #[test]
+    fn requires_tables() {
+        alter_readyset_statement(Dialect::MySQL)(LocatedSpan::new(b"ALTER READYSET ADD TABLES"))
+            .unwrap_err();
+    }
+}
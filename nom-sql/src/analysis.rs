@@ -83,6 +83,16 @@ impl<'a> ReferredColumnsIter<'a> {
             }),
             Expr::NestedSelect(_) => None,
             Expr::Variable(_) => None,
+            Expr::WindowFunction {
+                function,
+                partition_by,
+                order_by,
+            } => {
+                self.exprs_to_visit.extend(partition_by);
+                self.exprs_to_visit
+                    .extend(order_by.iter().map(|(expr, _)| expr));
+                self.visit_function_expression(function)
+            }
         }
     }
 
@@ -97,6 +107,10 @@ impl<'a> ReferredColumnsIter<'a> {
             Max(arg) => self.visit_expr(arg),
             Min(arg) => self.visit_expr(arg),
             GroupConcat { expr, .. } => self.visit_expr(expr),
+            Stddev { expr, .. } | Variance { expr, .. } => self.visit_expr(expr),
+            BitAnd(arg) | BitOr(arg) | BitXor(arg) | BoolAnd(arg) | BoolOr(arg) => {
+                self.visit_expr(arg)
+            }
             Call { arguments, .. } => arguments.first().and_then(|first_arg| {
                 if arguments.len() >= 2 {
                     self.exprs_to_visit.extend(arguments.iter().skip(1));
@@ -192,6 +206,16 @@ impl<'a> ReferredColumnsMut<'a> {
             }),
             Expr::NestedSelect(_) => None,
             Expr::Variable(_) => None,
+            Expr::WindowFunction {
+                function,
+                partition_by,
+                order_by,
+            } => {
+                self.exprs_to_visit.extend(partition_by);
+                self.exprs_to_visit
+                    .extend(order_by.iter_mut().map(|(expr, _)| expr));
+                self.visit_function_expression(function)
+            }
         }
     }
 
@@ -206,6 +230,10 @@ impl<'a> ReferredColumnsMut<'a> {
             Max(arg) => self.visit_expr(arg),
             Min(arg) => self.visit_expr(arg),
             GroupConcat { expr, .. } => self.visit_expr(expr),
+            Stddev { expr, .. } | Variance { expr, .. } => self.visit_expr(expr),
+            BitAnd(arg) | BitOr(arg) | BitXor(arg) | BoolAnd(arg) | BoolOr(arg) => {
+                self.visit_expr(arg)
+            }
             Call { arguments, .. } => arguments.split_first_mut().and_then(|(first_arg, args)| {
                 self.exprs_to_visit.extend(args);
                 self.visit_expr(first_arg)
@@ -338,7 +366,14 @@ pub fn is_aggregate(function: &FunctionExpr) -> bool {
         | FunctionExpr::Sum { .. }
         | FunctionExpr::Max(_)
         | FunctionExpr::Min(_)
-        | FunctionExpr::GroupConcat { .. } => true,
+        | FunctionExpr::GroupConcat { .. }
+        | FunctionExpr::Stddev { .. }
+        | FunctionExpr::Variance { .. }
+        | FunctionExpr::BitAnd(_)
+        | FunctionExpr::BitOr(_)
+        | FunctionExpr::BitXor(_)
+        | FunctionExpr::BoolAnd(_)
+        | FunctionExpr::BoolOr(_) => true,
         FunctionExpr::Substring { .. }
         // For now, assume all "generic" function calls are not aggregates
         | FunctionExpr::Call { .. } => false,
@@ -382,9 +417,62 @@ pub fn contains_aggregate(expr: &Expr) -> bool {
         }
         Expr::Array(exprs) => exprs.iter().any(contains_aggregate),
         Expr::Variable(_) => false,
+        // Aggregate functions called as window functions are computed over the window rather
+        // than over the group, so only their arguments can contain (group) aggregates
+        Expr::WindowFunction {
+            function,
+            partition_by,
+            order_by,
+        } => {
+            function.arguments().any(contains_aggregate)
+                || partition_by.iter().any(contains_aggregate)
+                || order_by.iter().any(|(expr, _)| contains_aggregate(expr))
+        }
+    }
+}
+
+/// Names of the generic functions whose result can differ between two calls with the same
+/// arguments, compared case-insensitively
+const NONDETERMINISTIC_FUNCTIONS: &[&str] = &[
+    "curdate",
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "curtime",
+    "gen_random_uuid",
+    "localtime",
+    "localtimestamp",
+    "now",
+    "rand",
+    "random",
+    "sysdate",
+    "unix_timestamp",
+    "utc_date",
+    "utc_time",
+    "utc_timestamp",
+    "uuid",
+    "uuid_short",
+];
+
+/// Returns true if the given [`FunctionExpr`] can return different results when called twice with
+/// the same arguments, such as `RAND()` or `NOW()`
+pub fn is_nondeterministic(function: &FunctionExpr) -> bool {
+    match function {
+        FunctionExpr::Call { name, .. } => NONDETERMINISTIC_FUNCTIONS
+            .iter()
+            .any(|f| name.eq_ignore_ascii_case(f)),
+        _ => false,
     }
 }
 
+/// Returns true if the given [`Expr`] or any of its recursive subexpressions calls a
+/// non-deterministic function
+pub fn contains_nondeterministic(expr: &Expr) -> bool {
+    iter::once(expr)
+        .chain(expr.recursive_subexpressions())
+        .any(|e| matches!(e, Expr::Call(f) if is_nondeterministic(f)))
+}
+
 pub struct Subexpressions<'a> {
     subexpr_iterators: VecDeque<Box<dyn Iterator<Item = &'a Expr> + 'a>>,
 }
@@ -467,6 +555,16 @@ impl Expr {
                 ..
             } => Box::new(iter::once(lhs.as_ref())) as _,
             Expr::Array(exprs) => Box::new(exprs.iter()),
+            Expr::WindowFunction {
+                function,
+                partition_by,
+                order_by,
+            } => Box::new(
+                function
+                    .arguments()
+                    .chain(partition_by)
+                    .chain(order_by.iter().map(|(expr, _)| expr)),
+            ) as _,
         }
     }
 
@@ -485,6 +583,37 @@ mod tests {
     use super::*;
     use crate::BinaryOperator;
 
+    #[test]
+    fn nondeterministic() {
+        let call = |name: &str, arguments| {
+            Expr::Call(FunctionExpr::Call {
+                name: name.into(),
+                arguments,
+            })
+        };
+
+        assert!(contains_nondeterministic(&call("RAND", vec![])));
+        assert!(contains_nondeterministic(&Expr::BinaryOp {
+            lhs: Box::new(Expr::Column(Column::from("t.a"))),
+            op: BinaryOperator::Add,
+            rhs: Box::new(call("now", vec![])),
+        }));
+        assert!(contains_nondeterministic(&call(
+            "ifnull",
+            vec![Expr::Column(Column::from("t.a")), call("uuid", vec![])]
+        )));
+        assert!(!contains_nondeterministic(&call(
+            "ifnull",
+            vec![
+                Expr::Column(Column::from("t.a")),
+                Expr::Column(Column::from("t.b"))
+            ]
+        )));
+        assert!(!contains_nondeterministic(&Expr::Column(Column::from(
+            "t.rand"
+        ))));
+    }
+
     mod referred_columns {
         use Expr::{Call, Column as ColExpr, Literal as LitExpr};
 
@@ -16,15 +16,15 @@ use crate::select::LimitClause;
 use crate::set::Variable;
 use crate::transaction::{CommitStatement, RollbackStatement, StartTransactionStatement};
 use crate::{
-    AlterColumnOperation, AlterTableDefinition, AlterTableStatement, CacheInner, CaseWhenBranch,
-    Column, ColumnConstraint, ColumnSpecification, CommonTableExpr, CompoundSelectStatement,
-    CreateCacheStatement, CreateTableStatement, CreateViewStatement, DeleteStatement,
-    DropAllCachesStatement, DropCacheStatement, DropTableStatement, DropViewStatement,
-    ExplainStatement, Expr, FieldDefinitionExpr, FieldReference, FunctionExpr, GroupByClause,
-    InValue, InsertStatement, JoinClause, JoinConstraint, JoinRightSide, Literal, OrderClause,
-    Relation, SelectSpecification, SelectStatement, SetNames, SetPostgresParameter, SetStatement,
-    SetVariables, ShowStatement, SqlIdentifier, SqlQuery, SqlType, TableExpr, TableExprInner,
-    TableKey, UpdateStatement, UseStatement,
+    AlterColumnOperation, AlterReadysetStatement, AlterTableDefinition, AlterTableStatement,
+    CacheInner, CaseWhenBranch, Column, ColumnConstraint, ColumnSpecification, CommonTableExpr,
+    CompoundSelectStatement, CreateCacheStatement, CreateTableStatement, CreateViewStatement,
+    DeleteStatement, DropAllCachesStatement, DropCacheStatement, DropTableStatement,
+    DropViewStatement, ExplainStatement, Expr, FieldDefinitionExpr, FieldReference, FunctionExpr,
+    GroupByClause, InValue, InsertStatement, JoinClause, JoinConstraint, JoinRightSide, Literal,
+    OrderClause, Relation, SelectSpecification, SelectStatement, SetNames, SetPostgresParameter,
+    SetStatement, SetVariables, ShowStatement, SqlIdentifier, SqlQuery, SqlType, TableExpr,
+    TableExprInner, TableKey, UpdateStatement, UseStatement,
 };
 
 /// Each method of the `Visitor` trait is a hook to be potentially overridden when recursively
@@ -396,6 +396,13 @@ pub trait Visitor<'ast>: Sized {
         Ok(())
     }
 
+    fn visit_alter_readyset_statement(
+        &mut self,
+        _alter_readyset_statement: &'ast AlterReadysetStatement,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     fn visit_sql_query(&mut self, sql_query: &'ast SqlQuery) -> Result<(), Self::Error> {
         walk_sql_query(self, sql_query)
     }
@@ -453,6 +460,20 @@ pub fn walk_expr<'ast, V: Visitor<'ast>>(
             Ok(())
         }
         Expr::Variable(var) => visitor.visit_variable(var),
+        Expr::WindowFunction {
+            function,
+            partition_by,
+            order_by,
+        } => {
+            visitor.visit_function_expr(function)?;
+            for expr in partition_by {
+                visitor.visit_expr(expr)?;
+            }
+            for (expr, _) in order_by {
+                visitor.visit_expr(expr)?;
+            }
+            Ok(())
+        }
     }
 }
 
@@ -476,6 +497,14 @@ pub fn walk_function_expr<'ast, V: Visitor<'ast>>(
         FunctionExpr::Max(expr) => visitor.visit_expr(expr.as_ref()),
         FunctionExpr::Min(expr) => visitor.visit_expr(expr.as_ref()),
         FunctionExpr::GroupConcat { expr, .. } => visitor.visit_expr(expr.as_ref()),
+        FunctionExpr::Stddev { expr, .. } | FunctionExpr::Variance { expr, .. } => {
+            visitor.visit_expr(expr.as_ref())
+        }
+        FunctionExpr::BitAnd(expr)
+        | FunctionExpr::BitOr(expr)
+        | FunctionExpr::BitXor(expr)
+        | FunctionExpr::BoolAnd(expr)
+        | FunctionExpr::BoolOr(expr) => visitor.visit_expr(expr.as_ref()),
         FunctionExpr::Call { arguments, .. } => {
             for arg in arguments {
                 visitor.visit_expr(arg)?;
@@ -1111,6 +1140,7 @@ pub fn walk_sql_query<'a, V: Visitor<'a>>(
         SqlQuery::Use(statement) => visitor.visit_use_statement(statement),
         SqlQuery::Show(statement) => visitor.visit_show_statement(statement),
         SqlQuery::Explain(statement) => visitor.visit_explain_statement(statement),
+        SqlQuery::AlterReadySet(statement) => visitor.visit_alter_readyset_statement(statement),
     }
 }
 
@@ -432,6 +432,96 @@ fn agg_fx_args(
     move |i| delimited(tag("("), agg_function_arguments(dialect), tag(")"))(i)
 }
 
+/// Parses the single parenthesized argument to an aggregate function which doesn't support
+/// `DISTINCT`
+fn single_agg_fx_arg(dialect: Dialect) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], Expr> {
+    move |i| {
+        delimited(
+            terminated(tag("("), whitespace0),
+            expression(dialect),
+            preceded(whitespace0, tag(")")),
+        )(i)
+    }
+}
+
+/// Parses the statistical (`STDDEV` and `VARIANCE`) and bitwise/boolean (`BIT_AND`, `BOOL_OR`,
+/// etc.) aggregate functions
+fn statistical_or_bitwise_agg(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], FunctionExpr> {
+    move |i| {
+        // The bare `STDDEV` and `VARIANCE` aggregates compute the population statistic in MySQL,
+        // but the sample statistic in PostgreSQL
+        let bare_is_sample = dialect == Dialect::PostgreSQL;
+        let stddev = |sample| {
+            move |expr| FunctionExpr::Stddev {
+                expr: Box::new(expr),
+                sample,
+            }
+        };
+        let variance = |sample| {
+            move |expr| FunctionExpr::Variance {
+                expr: Box::new(expr),
+                sample,
+            }
+        };
+
+        alt((
+            map(
+                preceded(tag_no_case("stddev_pop"), single_agg_fx_arg(dialect)),
+                stddev(false),
+            ),
+            map(
+                preceded(tag_no_case("stddev_samp"), single_agg_fx_arg(dialect)),
+                stddev(true),
+            ),
+            map(
+                preceded(tag_no_case("stddev"), single_agg_fx_arg(dialect)),
+                stddev(bare_is_sample),
+            ),
+            map(
+                preceded(tag_no_case("std"), single_agg_fx_arg(dialect)),
+                stddev(bare_is_sample),
+            ),
+            map(
+                preceded(tag_no_case("var_pop"), single_agg_fx_arg(dialect)),
+                variance(false),
+            ),
+            map(
+                preceded(tag_no_case("var_samp"), single_agg_fx_arg(dialect)),
+                variance(true),
+            ),
+            map(
+                preceded(tag_no_case("variance"), single_agg_fx_arg(dialect)),
+                variance(bare_is_sample),
+            ),
+            map(
+                preceded(tag_no_case("bit_and"), single_agg_fx_arg(dialect)),
+                |expr| FunctionExpr::BitAnd(Box::new(expr)),
+            ),
+            map(
+                preceded(tag_no_case("bit_or"), single_agg_fx_arg(dialect)),
+                |expr| FunctionExpr::BitOr(Box::new(expr)),
+            ),
+            map(
+                preceded(tag_no_case("bit_xor"), single_agg_fx_arg(dialect)),
+                |expr| FunctionExpr::BitXor(Box::new(expr)),
+            ),
+            map(
+                preceded(
+                    alt((tag_no_case("bool_and"), tag_no_case("every"))),
+                    single_agg_fx_arg(dialect),
+                ),
+                |expr| FunctionExpr::BoolAnd(Box::new(expr)),
+            ),
+            map(
+                preceded(tag_no_case("bool_or"), single_agg_fx_arg(dialect)),
+                |expr| FunctionExpr::BoolOr(Box::new(expr)),
+            ),
+        ))(i)
+    }
+}
+
 fn delim_fx_args(
     dialect: Dialect,
 ) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], Vec<Expr>> {
@@ -568,6 +658,7 @@ pub fn function_expr(
                     }
                 },
             ),
+            statistical_or_bitwise_agg(dialect),
             substring(dialect),
             function_call(dialect),
             function_call_without_parens,
@@ -883,6 +974,79 @@ mod tests {
         assert_eq!(res.unwrap().1, expected);
     }
 
+    #[test]
+    fn statistical_aggregates() {
+        let x = || Box::new(Expr::Column(Column::from("x")));
+        for (dialect, q, expected) in [
+            (
+                Dialect::MySQL,
+                &b"stddev(x)"[..],
+                FunctionExpr::Stddev {
+                    expr: x(),
+                    sample: false,
+                },
+            ),
+            (
+                Dialect::PostgreSQL,
+                b"stddev(x)",
+                FunctionExpr::Stddev {
+                    expr: x(),
+                    sample: true,
+                },
+            ),
+            (
+                Dialect::MySQL,
+                b"std(x)",
+                FunctionExpr::Stddev {
+                    expr: x(),
+                    sample: false,
+                },
+            ),
+            (
+                Dialect::MySQL,
+                b"STDDEV_SAMP( x )",
+                FunctionExpr::Stddev {
+                    expr: x(),
+                    sample: true,
+                },
+            ),
+            (
+                Dialect::PostgreSQL,
+                b"variance(x)",
+                FunctionExpr::Variance {
+                    expr: x(),
+                    sample: true,
+                },
+            ),
+            (
+                Dialect::MySQL,
+                b"var_pop(x)",
+                FunctionExpr::Variance {
+                    expr: x(),
+                    sample: false,
+                },
+            ),
+            (Dialect::MySQL, b"bit_xor(x)", FunctionExpr::BitXor(x())),
+            (Dialect::PostgreSQL, b"every(x)", FunctionExpr::BoolAnd(x())),
+            (
+                Dialect::PostgreSQL,
+                b"bool_or(x)",
+                FunctionExpr::BoolOr(x()),
+            ),
+        ] {
+            let res = test_parse!(function_expr(dialect), q);
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn statistical_aggregate_round_trip() {
+        for q in ["stddev_samp(x)", "var_pop(x)", "bit_and(x)", "bool_and(x)"] {
+            let res = test_parse!(function_expr(Dialect::PostgreSQL), q.as_bytes());
+            assert_eq!(res.display(Dialect::PostgreSQL).to_string(), q);
+        }
+    }
+
     #[test]
     fn simple_generic_function() {
         let qlist = [
@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::time::Duration;
 use std::{fmt, str};
 
 use derive_more::From;
@@ -16,7 +17,8 @@ use serde::{Deserialize, Serialize};
 use crate::column::{column_specification, Column, ColumnSpecification};
 use crate::common::{
     column_identifier_no_alias, debug_print, if_not_exists, parse_fallible, statement_terminator,
-    until_statement_terminator, ws_sep_comma, IndexType, ReferentialAction, TableKey,
+    until_statement_terminator, ws_sep_comma, ws_sep_equals, IndexType, ReferentialAction,
+    TableKey,
 };
 use crate::compound_select::{nested_compound_selection, CompoundSelectStatement};
 use crate::create_table_options::{table_options, CreateTableOption};
@@ -203,7 +205,239 @@ impl CacheInner {
     }
 }
 
-/// `CREATE CACHE [ALWAYS] [<name>] FROM ...`
+/// The priority of a cached query when evicting from caches to free up memory. Caches with a
+/// lower priority are evicted from before caches with a higher priority.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum CachePriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl fmt::Display for CachePriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CachePriority::Low => write!(f, "low"),
+            CachePriority::Normal => write!(f, "normal"),
+            CachePriority::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Options for a cached query, given in the `WITH (...)` clause of a [`CreateCacheStatement`]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct CacheOptions {
+    /// If set, entries in the cache are evicted once they have been in the cache for longer than
+    /// this duration, even if they've been kept up to date through replication.
+    ///
+    /// `WITH (ttl = '60s')`
+    pub ttl: Option<Duration>,
+    /// If set, all entries in the cache are evicted with this period, so that they're recomputed
+    /// from scratch the next time they're read.
+    ///
+    /// `WITH (refresh = '10m')`
+    pub refresh: Option<Duration>,
+    /// If set, the maximum number of bytes the cache can use. Entries are evicted from the cache
+    /// whenever it grows larger than this, regardless of the memory used by any other caches.
+    ///
+    /// `WITH (memory_limit = '2GB')`
+    pub memory_limit: Option<u64>,
+    /// If set, the priority of the cache when evicting to stay within the memory limit of the
+    /// whole process. Defaults to [`CachePriority::Normal`].
+    ///
+    /// `WITH (priority = high)`
+    pub priority: Option<CachePriority>,
+}
+
+impl CacheOptions {
+    /// Returns true if none of the options are set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for CacheOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let durations = [("ttl", self.ttl), ("refresh", self.refresh)]
+            .into_iter()
+            .filter_map(|(name, duration)| {
+                let duration = duration?;
+                Some(if duration.subsec_nanos() == 0 {
+                    format!("{name} = '{}s'", duration.as_secs())
+                } else {
+                    format!("{name} = '{}ms'", duration.as_millis())
+                })
+            });
+        let memory_limit = self
+            .memory_limit
+            .map(|bytes| format!("memory_limit = '{}'", display_memory_size(bytes)));
+        let priority = self
+            .priority
+            .map(|priority| format!("priority = {priority}"));
+        write!(
+            f,
+            "WITH ({})",
+            durations.chain(memory_limit).chain(priority).join(", ")
+        )
+    }
+}
+
+/// Parse a duration given as a number followed by a unit, one of `ms`, `s`, `m` or `h`, such as
+/// `500ms` or `60s`
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Missing unit in duration: {s}"))?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount = amount
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid duration: {s}"))?;
+    let duration = match unit.trim().to_lowercase().as_str() {
+        "ms" => Some(Duration::from_millis(amount)),
+        "s" => Some(Duration::from_secs(amount)),
+        "m" => amount.checked_mul(60).map(Duration::from_secs),
+        "h" => amount.checked_mul(60 * 60).map(Duration::from_secs),
+        _ => return Err(format!("Invalid unit in duration: {s}")),
+    }
+    .ok_or_else(|| format!("Invalid duration: {s}"))?;
+    // A zero TTL or refresh period would evict every key as soon as it's added
+    if duration.is_zero() {
+        return Err(format!("Duration must be greater than zero: {s}"));
+    }
+    Ok(duration)
+}
+
+/// Units for memory sizes, along with the number of bytes in each
+const MEMORY_UNITS: [(&str, u64); 5] = [
+    ("TB", 1 << 40),
+    ("GB", 1 << 30),
+    ("MB", 1 << 20),
+    ("KB", 1 << 10),
+    ("B", 1),
+];
+
+/// Parse a memory size given as a number followed by a unit, one of `B`, `KB`, `MB`, `GB` or `TB`
+/// (each 1024 times the previous one), such as `512MB` or `2GB`, into a number of bytes
+fn parse_memory_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Missing unit in memory size: {s}"))?;
+    let (amount, unit) = s.split_at(unit_start);
+    let amount = amount
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid memory size: {s}"))?;
+    let unit = unit.trim();
+    let (_, multiplier) = MEMORY_UNITS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+        .ok_or_else(|| format!("Invalid unit in memory size: {s}"))?;
+    amount
+        .checked_mul(*multiplier)
+        .ok_or_else(|| format!("Memory size too large: {s}"))
+}
+
+/// Format a number of bytes as a memory size in the largest unit that represents it exactly, such
+/// that it can be parsed back with [`parse_memory_size`]
+fn display_memory_size(bytes: u64) -> String {
+    let (name, multiplier) = MEMORY_UNITS
+        .iter()
+        .find(|(_, multiplier)| bytes % multiplier == 0)
+        .unwrap_or(&("B", 1));
+    format!("{}{name}", bytes / multiplier)
+}
+
+/// A single option in the `WITH (...)` clause of a [`CreateCacheStatement`]
+enum CacheOption {
+    Ttl(Duration),
+    Refresh(Duration),
+    MemoryLimit(u64),
+    Priority(CachePriority),
+}
+
+fn cache_priority(i: LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], CachePriority> {
+    alt((
+        map(tag_no_case("low"), |_| CachePriority::Low),
+        map(tag_no_case("normal"), |_| CachePriority::Normal),
+        map(tag_no_case("high"), |_| CachePriority::High),
+    ))(i)
+}
+
+fn cache_option(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], CacheOption> {
+    move |i| {
+        let duration = || {
+            map_res(map_res(dialect.string_literal(), String::from_utf8), |s| {
+                parse_duration(&s)
+            })
+        };
+        alt((
+            map(
+                preceded(terminated(tag_no_case("ttl"), ws_sep_equals), duration()),
+                CacheOption::Ttl,
+            ),
+            map(
+                preceded(
+                    terminated(tag_no_case("refresh"), ws_sep_equals),
+                    duration(),
+                ),
+                CacheOption::Refresh,
+            ),
+            map(
+                preceded(
+                    terminated(tag_no_case("memory_limit"), ws_sep_equals),
+                    map_res(map_res(dialect.string_literal(), String::from_utf8), |s| {
+                        parse_memory_size(&s)
+                    }),
+                ),
+                CacheOption::MemoryLimit,
+            ),
+            map(
+                preceded(
+                    terminated(tag_no_case("priority"), ws_sep_equals),
+                    cache_priority,
+                ),
+                CacheOption::Priority,
+            ),
+        ))(i)
+    }
+}
+
+/// Parse the `WITH (...)` clause of a [`CreateCacheStatement`]
+fn cache_options(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], CacheOptions> {
+    move |i| {
+        let (i, _) = tag_no_case("with")(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, options) = delimited(
+            terminated(tag("("), whitespace0),
+            separated_list1(ws_sep_comma, cache_option(dialect)),
+            preceded(whitespace0, tag(")")),
+        )(i)?;
+        Ok((
+            i,
+            options
+                .into_iter()
+                .fold(CacheOptions::default(), |mut options, option| {
+                    match option {
+                        CacheOption::Ttl(ttl) => options.ttl = Some(ttl),
+                        CacheOption::Refresh(refresh) => options.refresh = Some(refresh),
+                        CacheOption::MemoryLimit(limit) => options.memory_limit = Some(limit),
+                        CacheOption::Priority(priority) => options.priority = Some(priority),
+                    }
+                    options
+                }),
+        ))
+    }
+}
+
+/// `CREATE CACHE [ALWAYS] [<name>] [WITH (<option> = <value>, ...)] FROM ...`
 ///
 /// This is a non-standard ReadySet specific extension to SQL
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -216,6 +450,7 @@ pub struct CreateCacheStatement {
     /// that could not be parsed.
     pub inner: Result<CacheInner, String>,
     pub always: bool,
+    pub options: CacheOptions,
 }
 
 impl CreateCacheStatement {
@@ -228,6 +463,9 @@ impl CreateCacheStatement {
             if let Some(name) = &self.name {
                 write!(f, "{} ", name.display(dialect))?;
             }
+            if !self.options.is_empty() {
+                write!(f, "{} ", self.options)?;
+            }
             write!(f, "FROM ")?;
             match &self.inner {
                 Ok(inner) => write!(f, "{}", inner.display(dialect)),
@@ -779,6 +1017,7 @@ pub fn create_cached_query(
         let (i, _) = whitespace1(i)?;
         let (i, always) = opt(terminated(tag_no_case("always"), whitespace1))(i)?;
         let (i, name) = opt(terminated(relation(dialect), whitespace1))(i)?;
+        let (i, options) = opt(terminated(cache_options(dialect), whitespace0))(i)?;
         let (i, _) = tag_no_case("from")(i)?;
         let (i, _) = whitespace1(i)?;
         let (i, inner) =
@@ -789,6 +1028,7 @@ pub fn create_cached_query(
                 name,
                 inner,
                 always: always.is_some(),
+                options: options.unwrap_or_default(),
             },
         ))
     }
@@ -1585,6 +1825,115 @@ mod tests {
             );
         }
 
+        #[test]
+        fn create_cached_query_with_options() {
+            let res = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE foo WITH (ttl = '60s', refresh='10m') FROM SELECT id FROM users WHERE name = ?"
+            );
+            assert_eq!(res.name, Some("foo".into()));
+            assert_eq!(
+                res.options,
+                CacheOptions {
+                    ttl: Some(Duration::from_secs(60)),
+                    refresh: Some(Duration::from_secs(600)),
+                    ..Default::default()
+                }
+            );
+            assert!(res.inner.is_ok());
+        }
+
+        #[test]
+        fn create_cached_query_with_memory_limit_and_priority() {
+            let res = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE login WITH (memory_limit = '2GB', priority = high) FROM SELECT id FROM users WHERE name = ?"
+            );
+            assert_eq!(
+                res.options,
+                CacheOptions {
+                    memory_limit: Some(2 * 1024 * 1024 * 1024),
+                    priority: Some(CachePriority::High),
+                    ..Default::default()
+                }
+            );
+
+            let res = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE WITH (PRIORITY = Low, memory_limit = '512kb') FROM SELECT id FROM users"
+            );
+            assert_eq!(res.options.memory_limit, Some(512 * 1024));
+            assert_eq!(res.options.priority, Some(CachePriority::Low));
+        }
+
+        #[test]
+        fn create_cached_query_with_options_without_name() {
+            let res = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE ALWAYS WITH (TTL = '1500ms') FROM q_0123456789ABCDEF"
+            );
+            assert!(res.always);
+            assert!(res.name.is_none());
+            assert_eq!(res.options.ttl, Some(Duration::from_millis(1500)));
+            assert_eq!(res.options.refresh, None);
+        }
+
+        #[test]
+        fn create_cached_query_with_invalid_options() {
+            for options in [
+                "WITH (ttl = '60')",
+                "WITH (ttl = '60 years')",
+                "WITH (ttl = 60)",
+                "WITH (ttl = '0s')",
+                "WITH (refresh = '0ms')",
+                "WITH (refresh = '18446744073709551615h')",
+                "WITH (unknown = '60s')",
+                "WITH (memory_limit = '2')",
+                "WITH (memory_limit = '2 PB')",
+                "WITH (memory_limit = 2048)",
+                "WITH (priority = urgent)",
+                "WITH ()",
+            ] {
+                let query = format!("CREATE CACHE foo {options} FROM SELECT id FROM users");
+                create_cached_query(Dialect::MySQL)(LocatedSpan::new(query.as_bytes()))
+                    .unwrap_err();
+            }
+        }
+
+        #[test]
+        fn display_create_query_cache_with_options() {
+            let stmt = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE foo WITH (refresh = '1h', ttl = '250ms') FROM SELECT id FROM users"
+            );
+            let res = stmt.display(Dialect::MySQL).to_string();
+            assert_eq!(
+                res,
+                "CREATE CACHE `foo` WITH (ttl = '250ms', refresh = '3600s') FROM SELECT `id` FROM `users`"
+            );
+            assert_eq!(
+                test_parse!(create_cached_query(Dialect::MySQL), res.as_bytes()),
+                stmt
+            );
+        }
+
+        #[test]
+        fn display_create_query_cache_with_memory_limit_and_priority() {
+            let stmt = test_parse!(
+                create_cached_query(Dialect::MySQL),
+                b"CREATE CACHE foo WITH (priority = low, memory_limit = '1536MB', ttl = '1m') FROM SELECT id FROM users"
+            );
+            let res = stmt.display(Dialect::MySQL).to_string();
+            assert_eq!(
+                res,
+                "CREATE CACHE `foo` WITH (ttl = '60s', memory_limit = '1536MB', priority = low) FROM SELECT `id` FROM `users`"
+            );
+            assert_eq!(
+                test_parse!(create_cached_query(Dialect::MySQL), res.as_bytes()),
+                stmt
+            );
+        }
+
         #[test]
         fn lobsters_indexes() {
             let qstring = "CREATE TABLE `comments` (
@@ -2045,6 +2394,20 @@ mod tests {
             );
         }
 
+        #[test]
+        fn create_cached_query_with_options() {
+            let res = test_parse!(
+                create_cached_query(Dialect::PostgreSQL),
+                b"CREATE CACHE WITH (ttl = '30s') FROM SELECT id FROM users WHERE name = $1"
+            );
+            assert!(res.name.is_none());
+            assert_eq!(res.options.ttl, Some(Duration::from_secs(30)));
+            assert_eq!(
+                res.display(Dialect::PostgreSQL).to_string(),
+                "CREATE CACHE WITH (ttl = '30s') FROM SELECT \"id\" FROM \"users\" WHERE (\"name\" = $1)"
+            );
+        }
+
         #[test]
         fn lobsters_indexes() {
             let qstring = "CREATE TABLE \"comments\" (
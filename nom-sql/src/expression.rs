@@ -8,7 +8,7 @@ use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
 use nom::character::complete::char;
 use nom::combinator::{complete, map, opt, value};
-use nom::multi::{many0, many1, separated_list0};
+use nom::multi::{many0, many1, separated_list0, separated_list1};
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::Parser;
 use nom_locate::LocatedSpan;
@@ -19,11 +19,14 @@ use test_strategy::Arbitrary;
 
 use crate::common::{column_identifier_no_alias, function_expr, ws_sep_comma};
 use crate::literal::literal;
+use crate::order::order_type;
 use crate::select::nested_selection;
 use crate::set::{variable_scope_prefix, Variable};
 use crate::sql_type::{mysql_int_cast_targets, type_identifier};
 use crate::whitespace::{whitespace0, whitespace1};
-use crate::{Column, Dialect, Literal, NomSqlResult, SelectStatement, SqlIdentifier, SqlType};
+use crate::{
+    Column, Dialect, Literal, NomSqlResult, OrderType, SelectStatement, SqlIdentifier, SqlType,
+};
 
 /// Function call expressions
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
@@ -49,6 +52,29 @@ pub enum FunctionExpr {
     /// `GROUP_CONCAT` aggregation. The second argument is the separator
     GroupConcat { expr: Box<Expr>, separator: String },
 
+    /// `STDDEV_POP` or `STDDEV_SAMP` aggregation (or one of their aliases, such as `STDDEV`). The
+    /// boolean argument is `true` for the sample standard deviation
+    Stddev { expr: Box<Expr>, sample: bool },
+
+    /// `VAR_POP` or `VAR_SAMP` aggregation (or one of their aliases, such as `VARIANCE`). The
+    /// boolean argument is `true` for the sample variance
+    Variance { expr: Box<Expr>, sample: bool },
+
+    /// `BIT_AND` aggregation
+    BitAnd(Box<Expr>),
+
+    /// `BIT_OR` aggregation
+    BitOr(Box<Expr>),
+
+    /// `BIT_XOR` aggregation
+    BitXor(Box<Expr>),
+
+    /// `BOOL_AND` (or `EVERY`) aggregation
+    BoolAnd(Box<Expr>),
+
+    /// `BOOL_OR` aggregation
+    BoolOr(Box<Expr>),
+
     /// The SQL `SUBSTRING`/`SUBSTR` function.
     ///
     /// The supported syntax is one of:
@@ -82,7 +108,14 @@ impl FunctionExpr {
             | FunctionExpr::Sum { expr: arg, .. }
             | FunctionExpr::Max(arg)
             | FunctionExpr::Min(arg)
-            | FunctionExpr::GroupConcat { expr: arg, .. } => {
+            | FunctionExpr::GroupConcat { expr: arg, .. }
+            | FunctionExpr::Stddev { expr: arg, .. }
+            | FunctionExpr::Variance { expr: arg, .. }
+            | FunctionExpr::BitAnd(arg)
+            | FunctionExpr::BitOr(arg)
+            | FunctionExpr::BitXor(arg)
+            | FunctionExpr::BoolAnd(arg)
+            | FunctionExpr::BoolOr(arg) => {
                 concrete_iter!(iter::once(arg.as_ref()))
             }
             FunctionExpr::CountStar => concrete_iter!(iter::empty()),
@@ -125,6 +158,23 @@ impl FunctionExpr {
                     separator
                 )
             }
+            FunctionExpr::Stddev { expr, sample } => write!(
+                f,
+                "stddev_{}({})",
+                if *sample { "samp" } else { "pop" },
+                expr.display(dialect)
+            ),
+            FunctionExpr::Variance { expr, sample } => write!(
+                f,
+                "var_{}({})",
+                if *sample { "samp" } else { "pop" },
+                expr.display(dialect)
+            ),
+            FunctionExpr::BitAnd(expr) => write!(f, "bit_and({})", expr.display(dialect)),
+            FunctionExpr::BitOr(expr) => write!(f, "bit_or({})", expr.display(dialect)),
+            FunctionExpr::BitXor(expr) => write!(f, "bit_xor({})", expr.display(dialect)),
+            FunctionExpr::BoolAnd(expr) => write!(f, "bool_and({})", expr.display(dialect)),
+            FunctionExpr::BoolOr(expr) => write!(f, "bool_or({})", expr.display(dialect)),
             FunctionExpr::Call { name, arguments } => {
                 write!(
                     f,
@@ -265,6 +315,11 @@ pub enum BinaryOperator {
     /// Postgres-specific JSONB operator. Behaves like [`BinaryOperator::AtArrowRight`] with
     /// switched sides for the operands.
     AtArrowLeft,
+
+    /// `&&`
+    ///
+    /// Postgres-specific range operator. Determines whether two ranges have any values in common.
+    DoubleAmpersand,
 }
 
 impl BinaryOperator {
@@ -320,6 +375,7 @@ impl Display for BinaryOperator {
             Self::HashArrow2 => "#>>",
             Self::AtArrowRight => "@>",
             Self::AtArrowLeft => "<@",
+            Self::DoubleAmpersand => "&&",
         };
         f.write_str(op)
     }
@@ -477,6 +533,13 @@ pub enum Expr {
 
     /// A variable reference
     Variable(Variable),
+
+    /// A window function call, eg `ROW_NUMBER() OVER (PARTITION BY x ORDER BY y DESC)`
+    WindowFunction {
+        function: FunctionExpr,
+        partition_by: Vec<Expr>,
+        order_by: Vec<(Expr, OrderType)>,
+    },
 }
 
 impl Expr {
@@ -596,6 +659,34 @@ impl Expr {
                 write!(f, "]")
             }
             Expr::Variable(var) => write!(f, "{}", var),
+            Expr::WindowFunction {
+                function,
+                partition_by,
+                order_by,
+            } => {
+                write!(f, "{} OVER (", function.display(dialect))?;
+                if !partition_by.is_empty() {
+                    write!(
+                        f,
+                        "PARTITION BY {}",
+                        partition_by.iter().map(|e| e.display(dialect)).join(", ")
+                    )?;
+                    if !order_by.is_empty() {
+                        write!(f, " ")?;
+                    }
+                }
+                if !order_by.is_empty() {
+                    write!(
+                        f,
+                        "ORDER BY {}",
+                        order_by
+                            .iter()
+                            .map(|(e, ot)| format!("{} {ot}", e.display(dialect)))
+                            .join(", ")
+                    )?;
+                }
+                write!(f, ")")
+            }
         })
     }
 }
@@ -739,6 +830,7 @@ fn binary_operator_no_and_or(i: LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], Binar
             map(tag("#>"), |_| BinaryOperator::HashArrow1),
         )),
         map(tag("#-"), |_| BinaryOperator::HashSubtract),
+        map(tag("&&"), |_| BinaryOperator::DoubleAmpersand),
     ))(i)
 }
 
@@ -979,6 +1071,7 @@ where
             Infix(AtArrowRight) => Affix::Infix(Precedence(8), Associativity::Left),
             Infix(AtArrowLeft) => Affix::Infix(Precedence(8), Associativity::Left),
             Infix(HashSubtract) => Affix::Infix(Precedence(8), Associativity::Left),
+            Infix(DoubleAmpersand) => Affix::Infix(Precedence(8), Associativity::Left),
         })
     }
 
@@ -1161,6 +1254,60 @@ fn exists_expr(dialect: Dialect) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<
     }
 }
 
+fn window_order_field(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], (Expr, OrderType)> {
+    move |i| {
+        let (i, expr) = expression(dialect)(i)?;
+        let (i, order_type) = opt(preceded(whitespace1, order_type))(i)?;
+        Ok((i, (expr, order_type.unwrap_or(OrderType::OrderAscending))))
+    }
+}
+
+/// Parse a window function call, eg `RANK() OVER (PARTITION BY x ORDER BY y DESC)`
+fn window_function_expr(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], Expr> {
+    move |i| {
+        let (i, function) = function_expr(dialect)(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, _) = tag_no_case("over")(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, _) = char('(')(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, partition_by) = opt(preceded(
+            tuple((
+                tag_no_case("partition"),
+                whitespace1,
+                tag_no_case("by"),
+                whitespace1,
+            )),
+            separated_list1(ws_sep_comma, expression(dialect)),
+        ))(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, order_by) = opt(preceded(
+            tuple((
+                tag_no_case("order"),
+                whitespace1,
+                tag_no_case("by"),
+                whitespace1,
+            )),
+            separated_list1(ws_sep_comma, window_order_field(dialect)),
+        ))(i)?;
+        let (i, _) = whitespace0(i)?;
+        let (i, _) = char(')')(i)?;
+
+        Ok((
+            i,
+            Expr::WindowFunction {
+                function,
+                partition_by: partition_by.unwrap_or_default(),
+                order_by: order_by.unwrap_or_default(),
+            },
+        ))
+    }
+}
+
 fn case_when_branch(
     dialect: Dialect,
 ) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], CaseWhenBranch> {
@@ -1322,6 +1469,7 @@ pub(crate) fn simple_expr(
             exists_expr(dialect),
             between_expr(dialect),
             in_expr(dialect),
+            window_function_expr(dialect),
             map(function_expr(dialect), Expr::Call),
             map(literal(dialect), Expr::Literal),
             case_when_expr(dialect),
@@ -1396,6 +1544,74 @@ mod tests {
         }
     }
 
+    mod window_functions {
+        use super::*;
+
+        #[test]
+        fn row_number_partition_order() {
+            let res = test_parse!(
+                expression(Dialect::MySQL),
+                b"ROW_NUMBER() OVER (PARTITION BY t.x, t.y ORDER BY t.z DESC, t.w)"
+            );
+            assert_eq!(
+                res,
+                Expr::WindowFunction {
+                    function: FunctionExpr::Call {
+                        name: "ROW_NUMBER".into(),
+                        arguments: vec![],
+                    },
+                    partition_by: vec![Expr::Column("t.x".into()), Expr::Column("t.y".into())],
+                    order_by: vec![
+                        (Expr::Column("t.z".into()), OrderType::OrderDescending),
+                        (Expr::Column("t.w".into()), OrderType::OrderAscending),
+                    ],
+                }
+            );
+        }
+
+        #[test]
+        fn running_sum() {
+            let res = test_parse!(expression(Dialect::PostgreSQL), b"sum(x) over (order by y)");
+            assert_eq!(
+                res,
+                Expr::WindowFunction {
+                    function: FunctionExpr::Sum {
+                        expr: Box::new(Expr::Column("x".into())),
+                        distinct: false,
+                    },
+                    partition_by: vec![],
+                    order_by: vec![(Expr::Column("y".into()), OrderType::OrderAscending)],
+                }
+            );
+        }
+
+        #[test]
+        fn empty_window() {
+            let res = test_parse!(expression(Dialect::MySQL), b"count(*) over ()");
+            assert_eq!(
+                res,
+                Expr::WindowFunction {
+                    function: FunctionExpr::CountStar,
+                    partition_by: vec![],
+                    order_by: vec![],
+                }
+            );
+        }
+
+        #[test]
+        fn display_round_trip() {
+            let expr = test_parse!(
+                expression(Dialect::MySQL),
+                b"lag(x, 2) over (partition by y order by z desc)"
+            );
+            let displayed = expr.display(Dialect::MySQL).to_string();
+            assert_eq!(
+                test_parse!(expression(Dialect::MySQL), displayed.as_bytes()),
+                expr
+            );
+        }
+    }
+
     pub mod cast {
         use super::*;
 
@@ -2238,6 +2454,24 @@ mod tests {
             use super::*;
             use crate::{to_nom_result, ItemPlaceholder};
 
+            #[test]
+            fn double_ampersand_operator() {
+                let cond = b"'[1,10)'::int4range && '[5,20)'";
+                let res = test_parse!(expression(Dialect::PostgreSQL), cond);
+                assert_eq!(
+                    res,
+                    Expr::BinaryOp {
+                        lhs: Box::new(Expr::Cast {
+                            expr: Box::new(Expr::Literal("[1,10)".into())),
+                            ty: SqlType::Int4Range,
+                            postgres_style: true,
+                        }),
+                        op: BinaryOperator::DoubleAmpersand,
+                        rhs: Box::new(Expr::Literal("[5,20)".into())),
+                    }
+                );
+            }
+
             #[test]
             fn complex_bracketing() {
                 let cond = "`read_ribbons`.`is_following` = 1 \
@@ -49,8 +49,14 @@ pub enum JoinOperator {
     LeftOuterJoin,
     #[weight(0)]
     RightJoin,
+    #[weight(0)]
+    RightOuterJoin,
     InnerJoin,
     #[weight(0)]
+    FullJoin,
+    #[weight(0)]
+    FullOuterJoin,
+    #[weight(0)]
     CrossJoin,
     #[weight(0)]
     StraightJoin,
@@ -60,6 +66,14 @@ impl JoinOperator {
     pub fn is_inner_join(&self) -> bool {
         matches!(self, JoinOperator::Join | JoinOperator::InnerJoin)
     }
+
+    pub fn is_right_join(&self) -> bool {
+        matches!(self, JoinOperator::RightJoin | JoinOperator::RightOuterJoin)
+    }
+
+    pub fn is_full_join(&self) -> bool {
+        matches!(self, JoinOperator::FullJoin | JoinOperator::FullOuterJoin)
+    }
 }
 
 impl fmt::Display for JoinOperator {
@@ -69,7 +83,10 @@ impl fmt::Display for JoinOperator {
             JoinOperator::LeftJoin => write!(f, "LEFT JOIN")?,
             JoinOperator::LeftOuterJoin => write!(f, "LEFT OUTER JOIN")?,
             JoinOperator::RightJoin => write!(f, "RIGHT JOIN")?,
+            JoinOperator::RightOuterJoin => write!(f, "RIGHT OUTER JOIN")?,
             JoinOperator::InnerJoin => write!(f, "INNER JOIN")?,
+            JoinOperator::FullJoin => write!(f, "FULL JOIN")?,
+            JoinOperator::FullOuterJoin => write!(f, "FULL OUTER JOIN")?,
             JoinOperator::CrossJoin => write!(f, "CROSS JOIN")?,
             JoinOperator::StraightJoin => write!(f, "STRAIGHT JOIN")?,
         }
@@ -107,7 +124,14 @@ pub fn join_operator(i: LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], JoinOperator>
             JoinOperator::LeftOuterJoin
         }),
         map(tag_no_case("right join"), |_| JoinOperator::RightJoin),
+        map(tag_no_case("right outer join"), |_| {
+            JoinOperator::RightOuterJoin
+        }),
         map(tag_no_case("inner join"), |_| JoinOperator::InnerJoin),
+        map(tag_no_case("full join"), |_| JoinOperator::FullJoin),
+        map(tag_no_case("full outer join"), |_| {
+            JoinOperator::FullOuterJoin
+        }),
         map(tag_no_case("cross join"), |_| JoinOperator::CrossJoin),
         map(tag_no_case("straight_join"), |_| JoinOperator::StraightJoin),
     ))(i)
@@ -152,6 +176,33 @@ mod tests {
             assert_eq!(q, expected_stmt);
             assert_eq!(expected, q.display(Dialect::MySQL).to_string());
         }
+
+        #[test]
+        fn outer_join_operators() {
+            for (qstring, operator) in [
+                ("RIGHT JOIN", JoinOperator::RightJoin),
+                ("right outer join", JoinOperator::RightOuterJoin),
+                ("FULL JOIN", JoinOperator::FullJoin),
+                ("full outer join", JoinOperator::FullOuterJoin),
+            ] {
+                let res = join_operator(LocatedSpan::new(qstring.as_bytes()));
+                assert_eq!(res.unwrap().1, operator);
+                assert_eq!(operator.to_string(), qstring.to_uppercase());
+            }
+        }
+
+        #[test]
+        fn full_outer_join() {
+            let qstring = "SELECT * FROM t1 FULL OUTER JOIN t2 ON (t1.id = t2.id)";
+            let expected = "SELECT * FROM `t1` \
+                        FULL OUTER JOIN `t2` ON (`t1`.`id` = `t2`.`id`)";
+
+            let res = selection(Dialect::MySQL)(LocatedSpan::new(qstring.as_bytes()));
+            let q = res.unwrap().1;
+            assert_eq!(q.join.len(), 1);
+            assert_eq!(q.join[0].operator, JoinOperator::FullOuterJoin);
+            assert_eq!(expected, q.display(Dialect::MySQL).to_string());
+        }
     }
 
     mod postgres {
@@ -15,12 +15,13 @@ use nom_locate::LocatedSpan;
 pub use self::alter::{
     AlterColumnOperation, AlterTableDefinition, AlterTableStatement, ReplicaIdentity,
 };
+pub use self::alter_readyset::AlterReadysetStatement;
 pub use self::column::{Column, ColumnConstraint, ColumnSpecification};
 pub use self::common::{FieldDefinitionExpr, FieldReference, IndexType, TableKey};
 pub use self::compound_select::{CompoundSelectOperator, CompoundSelectStatement};
 pub use self::create::{
-    CacheInner, CreateCacheStatement, CreateTableBody, CreateTableStatement, CreateViewStatement,
-    SelectSpecification,
+    CacheInner, CacheOptions, CachePriority, CreateCacheStatement, CreateTableBody,
+    CreateTableStatement, CreateViewStatement, SelectSpecification,
 };
 pub use self::create_table_options::CreateTableOption;
 pub use self::delete::DeleteStatement;
@@ -60,6 +61,7 @@ mod dialect;
 mod macros;
 
 mod alter;
+mod alter_readyset;
 pub mod analysis;
 mod column;
 mod common;
@@ -315,6 +315,45 @@ impl Literal {
             }
             SqlType::Serial => any::<i32>().prop_map(Self::from).boxed(),
             SqlType::BigSerial => any::<i64>().prop_map(Self::from).boxed(),
+            SqlType::Int4Range | SqlType::NumRange => (any::<i32>(), any::<i32>())
+                .prop_map(|(a, b)| Self::String(format!("[{},{})", a.min(b), a.max(b))))
+                .boxed(),
+            SqlType::Int8Range => (any::<i64>(), any::<i64>())
+                .prop_map(|(a, b)| Self::String(format!("[{},{})", a.min(b), a.max(b))))
+                .boxed(),
+            SqlType::DateRange => (
+                arbitrary_positive_naive_date(),
+                arbitrary_positive_naive_date(),
+            )
+                .prop_map(|(a, b)| {
+                    Self::String(format!(
+                        "[{},{})",
+                        a.min(b).format("%Y-%m-%d"),
+                        a.max(b).format("%Y-%m-%d")
+                    ))
+                })
+                .boxed(),
+            SqlType::TsRange => (
+                arbitrary_timestamp_naive_date_time(),
+                arbitrary_timestamp_naive_date_time(),
+            )
+                .prop_map(|(a, b)| {
+                    Self::String(format!(
+                        "[\"{}\",\"{}\")",
+                        a.min(b).format("%Y-%m-%d %H:%M:%S"),
+                        a.max(b).format("%Y-%m-%d %H:%M:%S")
+                    ))
+                })
+                .boxed(),
+            SqlType::TsTzRange => (arbitrary_date_time(), arbitrary_date_time())
+                .prop_map(|(a, b)| {
+                    Self::String(format!(
+                        "[\"{}\",\"{}\")",
+                        a.min(b).format("%Y-%m-%d %H:%M:%S %:z"),
+                        a.max(b).format("%Y-%m-%d %H:%M:%S %:z")
+                    ))
+                })
+                .boxed(),
             SqlType::Array(_) => unimplemented!("Arrays aren't implemented yet"),
             SqlType::Other(ty) => {
                 unimplemented!("Other({}) isn't implemented yet", ty.display_unquoted())
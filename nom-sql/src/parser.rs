@@ -8,6 +8,7 @@ use readyset_util::redacted::Sensitive;
 use serde::{Deserialize, Serialize};
 
 use crate::alter::{alter_table_statement, AlterTableStatement};
+use crate::alter_readyset::{alter_readyset_statement, AlterReadysetStatement};
 use crate::compound_select::{compound_selection, CompoundSelectStatement};
 use crate::create::{
     create_cached_query, create_table, key_specification, view_creation, CreateCacheStatement,
@@ -44,6 +45,7 @@ pub enum SqlQuery {
     DropCache(DropCacheStatement),
     DropAllCaches(DropAllCachesStatement),
     AlterTable(AlterTableStatement),
+    AlterReadySet(AlterReadysetStatement),
     Insert(InsertStatement),
     CompoundSelect(CompoundSelectStatement),
     Select(SelectStatement),
@@ -77,6 +79,7 @@ impl SqlQuery {
             Self::Update(update) => write!(f, "{}", update.display(dialect)),
             Self::Set(set) => write!(f, "{}", set.display(dialect)),
             Self::AlterTable(alter) => write!(f, "{}", alter.display(dialect)),
+            Self::AlterReadySet(alter) => write!(f, "{}", alter),
             Self::CompoundSelect(compound) => write!(f, "{}", compound.display(dialect)),
             Self::StartTransaction(tx) => write!(f, "{}", tx),
             Self::Commit(commit) => write!(f, "{}", commit),
@@ -114,6 +117,7 @@ impl SqlQuery {
             Self::Update(_) => "UPDATE",
             Self::Set(_) => "SET",
             Self::AlterTable(_) => "ALTER TABLE",
+            Self::AlterReadySet(_) => "ALTER READYSET",
             Self::CompoundSelect(_) => "SELECT",
             Self::StartTransaction(_) => "START TRANSACTION",
             Self::Commit(_) => "COMMIT",
@@ -149,7 +153,10 @@ pub fn sql_query(dialect: Dialect) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResul
             map(create_cached_query(dialect), SqlQuery::CreateCache),
             map(drop_cached_query(dialect), SqlQuery::DropCache),
             map(drop_all_caches, SqlQuery::DropAllCaches),
-            map(alter_table_statement(dialect), SqlQuery::AlterTable),
+            alt((
+                map(alter_table_statement(dialect), SqlQuery::AlterTable),
+                map(alter_readyset_statement(dialect), SqlQuery::AlterReadySet),
+            )),
             map(start_transaction(dialect), SqlQuery::StartTransaction),
             map(commit(dialect), SqlQuery::Commit),
             map(rollback(dialect), SqlQuery::Rollback),
@@ -194,6 +194,13 @@ impl SelectStatement {
                         | FunctionExpr::Max(_)
                         | FunctionExpr::Min(_)
                         | FunctionExpr::GroupConcat { .. }
+                        | FunctionExpr::Stddev { .. }
+                        | FunctionExpr::Variance { .. }
+                        | FunctionExpr::BitAnd(_)
+                        | FunctionExpr::BitOr(_)
+                        | FunctionExpr::BitXor(_)
+                        | FunctionExpr::BoolAnd(_)
+                        | FunctionExpr::BoolOr(_)
                 ),
                 Expr::NestedSelect(select) => select.contains_aggregate_select(),
                 _ => false,
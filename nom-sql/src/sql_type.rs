@@ -76,6 +76,12 @@ pub enum SqlType {
     Serial,
     BigSerial,
     Array(Box<SqlType>),
+    Int4Range,
+    Int8Range,
+    NumRange,
+    TsRange,
+    TsTzRange,
+    DateRange,
 
     /// Any other named type
     Other(Relation),
@@ -172,6 +178,12 @@ impl Arbitrary for SqlType {
                 Just(TimestampTz).boxed(),
                 Just(Citext).boxed(),
                 Just(QuotedChar).boxed(),
+                Just(Int4Range).boxed(),
+                Just(Int8Range).boxed(),
+                Just(NumRange).boxed(),
+                Just(TsRange).boxed(),
+                Just(TsTzRange).boxed(),
+                Just(DateRange).boxed(),
             ]);
         }
 
@@ -351,6 +363,12 @@ impl SqlType {
                 SqlType::Serial => write!(f, "SERIAL"),
                 SqlType::BigSerial => write!(f, "BIGSERIAL"),
                 SqlType::Array(ref t) => write!(f, "{}[]", t.display(dialect)),
+                SqlType::Int4Range => write!(f, "INT4RANGE"),
+                SqlType::Int8Range => write!(f, "INT8RANGE"),
+                SqlType::NumRange => write!(f, "NUMRANGE"),
+                SqlType::TsRange => write!(f, "TSRANGE"),
+                SqlType::TsTzRange => write!(f, "TSTZRANGE"),
+                SqlType::DateRange => write!(f, "DATERANGE"),
                 SqlType::Other(ref t) => write!(f, "{}", t.display(dialect)),
             }
         })
@@ -789,11 +807,25 @@ fn array_suffix(i: LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], ()> {
     Ok((i, ()))
 }
 
+// Range types need to be parsed before the rest of the types, since otherwise eg `int4range` would
+// be parsed as `int4` followed by `range`
+fn range_type(i: LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], SqlType> {
+    alt((
+        value(SqlType::Int4Range, tag_no_case("int4range")),
+        value(SqlType::Int8Range, tag_no_case("int8range")),
+        value(SqlType::NumRange, tag_no_case("numrange")),
+        value(SqlType::TsRange, tag_no_case("tsrange")),
+        value(SqlType::TsTzRange, tag_no_case("tstzrange")),
+        value(SqlType::DateRange, tag_no_case("daterange")),
+    ))(i)
+}
+
 fn type_identifier_no_arrays(
     dialect: Dialect,
 ) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], SqlType> {
     move |i| {
         alt((
+            range_type,
             type_identifier_part1(dialect),
             type_identifier_part2,
             type_identifier_part3(dialect),
@@ -1015,6 +1047,28 @@ mod tests {
             assert_eq!(res, SqlType::Inet);
         }
 
+        #[test]
+        fn range_types() {
+            for (input, expected) in [
+                (&b"int4range"[..], SqlType::Int4Range),
+                (b"INT8RANGE", SqlType::Int8Range),
+                (b"numrange", SqlType::NumRange),
+                (b"tsrange", SqlType::TsRange),
+                (b"tstzrange", SqlType::TsTzRange),
+                (b"daterange", SqlType::DateRange),
+            ] {
+                let res = test_parse!(type_identifier(Dialect::PostgreSQL), input);
+                assert_eq!(res, expected);
+                assert_eq!(
+                    res.display(Dialect::PostgreSQL).to_string(),
+                    String::from_utf8_lossy(input).to_ascii_uppercase()
+                );
+            }
+
+            let res = test_parse!(type_identifier(Dialect::PostgreSQL), b"int4range[]");
+            assert_eq!(res, SqlType::Array(Box::new(SqlType::Int4Range)));
+        }
+
         #[test]
         fn uuid_type() {
             let res = test_parse!(type_identifier(Dialect::PostgreSQL), b"uuid");
@@ -3,8 +3,8 @@ use std::hash::Hash;
 use std::str;
 
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::combinator::{map, opt};
+use nom::bytes::complete::{tag, take_while1};
+use nom::combinator::{map, map_res, opt, verify};
 use nom::multi::separated_list1;
 use nom::sequence::terminated;
 use nom_locate::LocatedSpan;
@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use test_strategy::Arbitrary;
 
 use crate::common::{as_alias, ws_sep_comma};
+use crate::dialect::is_sql_identifier;
 use crate::select::nested_selection;
 use crate::whitespace::whitespace0;
 use crate::{Dialect, NomSqlResult, SelectStatement, SqlIdentifier};
@@ -217,17 +218,39 @@ pub fn table_expr_list(
     move |i| separated_list1(ws_sep_comma, table_expr(dialect))(i)
 }
 
-// Parse a reference to a named schema.table or schema.* as used by the replicator to identify
-// tables to replicate
+// Parse a glob pattern matching schema or table names, such as `*` or `user_*`, as used by the
+// replicator to identify tables to replicate. Only matches names that contain at least one `*` or
+// `?` wildcard.
+fn replicator_name_pattern(
+    dialect: Dialect,
+) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], SqlIdentifier> {
+    move |i| {
+        map_res(
+            verify(
+                take_while1(|c| is_sql_identifier(c) || c == b'*' || c == b'?'),
+                |pat: &LocatedSpan<&[u8]>| pat.iter().any(|c| *c == b'*' || *c == b'?'),
+            ),
+            |pat: LocatedSpan<&[u8]>| {
+                str::from_utf8(&pat).map(|pat| match dialect {
+                    Dialect::PostgreSQL => pat.to_ascii_lowercase().into(),
+                    Dialect::MySQL => pat.into(),
+                })
+            },
+        )(i)
+    }
+}
+
+// Parse a reference to a named schema.table, or a glob pattern such as schema.* or
+// schema.user_*, as used by the replicator to identify tables to replicate
 pub fn replicator_table_reference(
     dialect: Dialect,
 ) -> impl Fn(LocatedSpan<&[u8]>) -> NomSqlResult<&[u8], Relation> {
     move |i| {
-        let (i, schema) = opt(terminated(dialect.identifier(), tag(".")))(i)?;
-        let (i, name) = alt((
-            dialect.identifier(),
-            map(tag("*"), |_| SqlIdentifier::from("*")),
+        let (i, schema) = opt(terminated(
+            alt((replicator_name_pattern(dialect), dialect.identifier())),
+            tag("."),
         ))(i)?;
+        let (i, name) = alt((replicator_name_pattern(dialect), dialect.identifier()))(i)?;
         Ok((i, Relation { schema, name }))
     }
 }
@@ -8,7 +8,7 @@ use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use cidr::IpInet;
 use eui48::MacAddress;
 use postgres_types::{FromSql, Kind, Type};
-use readyset_data::{Array, Collation};
+use readyset_data::{Array, Collation, Range};
 use rust_decimal::prelude::FromStr;
 use rust_decimal::Decimal;
 use tokio_util::codec::Decoder;
@@ -28,6 +28,9 @@ use crate::value::Value;
 const ID_AUTHENTICATE: u8 = b'p';
 const ID_BIND: u8 = b'B';
 const ID_CLOSE: u8 = b'C';
+const ID_COPY_DATA: u8 = b'd';
+const ID_COPY_DONE: u8 = b'c';
+const ID_COPY_FAIL: u8 = b'f';
 const ID_DESCRIBE: u8 = b'D';
 const ID_EXECUTE: u8 = b'E';
 const ID_FLUSH: u8 = b'H';
@@ -42,6 +45,7 @@ const CLOSE_TYPE_PREPARED_STATEMENT: u8 = b'S';
 const DESCRIBE_TYPE_PORTAL: u8 = b'P';
 const DESCRIBE_TYPE_PREPARED_STATEMENT: u8 = b'S';
 
+const CANCEL_REQUEST_CODE: i32 = 80877102;
 const SSL_REQUEST_CODE: i32 = 80877103;
 
 const STARTUP_MESSAGE_DATABASE_PARAMETER: &str = "database";
@@ -96,6 +100,15 @@ impl<R: IntoIterator<Item: TryInto<Value, Error = BackendError>>> Decoder for Co
             let ret = match token {
                 SSL_REQUEST_CODE => Ok(Some(SSLRequest)),
 
+                CANCEL_REQUEST_CODE => {
+                    let process_id = get_i32(msg)?;
+                    let secret_key = get_i32(msg)?;
+                    Ok(Some(CancelRequest {
+                        process_id,
+                        secret_key,
+                    }))
+                }
+
                 // Parse StartupMessage
                 protocol_version => {
                     let mut user: Option<BytesStr> = None;
@@ -208,6 +221,18 @@ impl<R: IntoIterator<Item: TryInto<Value, Error = BackendError>>> Decoder for Co
                 Ok(Some(Close { name }))
             }
 
+            ID_COPY_DATA => {
+                let data = msg.clone();
+                msg.clear(); // Take the rest of the buffer
+                Ok(Some(CopyData { data }))
+            }
+
+            ID_COPY_DONE => Ok(Some(CopyDone)),
+
+            ID_COPY_FAIL => Ok(Some(CopyFail {
+                message: get_str(msg)?,
+            })),
+
             ID_DESCRIBE => {
                 let statement_type = get_u8(msg)?;
                 let name_str = get_str(msg)?;
@@ -346,6 +371,7 @@ fn get_binary_value(src: &mut Bytes, t: &Type) -> Result<Value, Error> {
 
     match t.kind() {
         Kind::Array(member_type) => Ok(Value::Array(Array::from_sql(t, buf)?, member_type.clone())),
+        Kind::Range(_) => Ok(Value::Range(Range::from_sql(t, buf)?, t.clone())),
         Kind::Enum(variants) => {
             let variant_str = str::from_utf8(buf)?;
             Ok(Value::BigInt(
@@ -479,6 +505,10 @@ fn get_text_value(src: &mut Bytes, t: &Type) -> Result<Value, Error> {
         Type::BIT => get_bitvec_from_str(text_str).map(Value::Bit),
         Type::VARBIT => get_bitvec_from_str(text_str).map(Value::VarBit),
         ref t if t.name() == "citext" => Ok(Value::Text(text_str.into())),
+        ref t if matches!(t.kind(), Kind::Range(_)) => text_str
+            .parse::<Range>()
+            .map_err(|e| DecodeError::InvalidTextRangeValue(e.to_string()))
+            .map(|r| Value::Range(r, t.clone())),
         _ => Err(Error::UnsupportedType(t.clone())),
     }
 }
@@ -488,6 +518,7 @@ mod tests {
 
     use bytes::{BufMut, BytesMut};
     use postgres_types::ToSql;
+    use readyset_data::{DfValue, RangeBound};
 
     use super::*;
     use crate::value::Value as DataValue;
@@ -527,6 +558,33 @@ mod tests {
         codec.decode(&mut buf).unwrap_err();
     }
 
+    #[test]
+    fn test_decode_cancel_request() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        let mut buf = BytesMut::new();
+        buf.put_i32(16); // size
+        buf.put_i32(80877102); // cancel request code
+        buf.put_i32(1234); // process id
+        buf.put_i32(5678); // secret key
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some(CancelRequest {
+                process_id: 1234,
+                secret_key: 5678
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_cancel_request_missing_secret_key() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        let mut buf = BytesMut::new();
+        buf.put_i32(12); // size
+        buf.put_i32(80877102); // cancel request code
+        buf.put_i32(1234); // process id
+        codec.decode(&mut buf).unwrap_err();
+    }
+
     #[test]
     fn test_decode_startup_message() {
         let mut codec = Codec::<Vec<Value>>::new();
@@ -913,6 +971,44 @@ mod tests {
         codec.decode(&mut buf).unwrap_err();
     }
 
+    #[test]
+    fn test_decode_copy_data() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        codec.set_start_up_complete();
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'd'); // message id
+        buf.put_i32(4 + 6); // size
+        buf.extend_from_slice(b"1\tabc\n");
+        let expected = Some(CopyData {
+            data: Bytes::from_static(b"1\tabc\n"),
+        });
+        assert_eq!(codec.decode(&mut buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decode_copy_done() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        codec.set_start_up_complete();
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'c'); // message id
+        buf.put_i32(4); // size
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(CopyDone));
+    }
+
+    #[test]
+    fn test_decode_copy_fail() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        codec.set_start_up_complete();
+        let mut buf = BytesMut::new();
+        buf.put_u8(b'f'); // message id
+        buf.put_i32(4 + 8); // size
+        buf.extend_from_slice(b"aborted\0");
+        let expected = Some(CopyFail {
+            message: bytes_str("aborted"),
+        });
+        assert_eq!(codec.decode(&mut buf).unwrap(), expected);
+    }
+
     #[test]
     fn test_decode_query() {
         let mut codec = Codec::<Vec<Value>>::new();
@@ -1270,6 +1366,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_binary_int4_range() {
+        let range = Range::new(
+            RangeBound::Inclusive(DfValue::from(1)),
+            RangeBound::Exclusive(DfValue::from(10)),
+        )
+        .unwrap();
+        let mut buf = BytesMut::new();
+        buf.put_i32(-1); // size (placeholder)
+        range.to_sql(&Type::INT4_RANGE, &mut buf).unwrap(); // add value
+        let value_len = buf.len() - 4;
+        let mut window = buf
+            .get_mut(0..4)
+            .ok_or_else(|| Error::InternalError("error writing message field".to_string()))
+            .unwrap();
+        window.put_i32(value_len as i32); // put the actual length
+        assert_eq!(
+            get_binary_value(&mut buf.freeze(), &Type::INT4_RANGE).unwrap(),
+            DataValue::Range(range, Type::INT4_RANGE)
+        );
+    }
+
     #[test]
     fn test_decode_text_null() {
         let mut buf = BytesMut::new();
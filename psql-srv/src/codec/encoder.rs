@@ -16,9 +16,14 @@ use crate::scram::{SCRAM_SHA_256_AUTHENTICATION_METHOD, SCRAM_SHA_256_SSL_AUTHEN
 use crate::value::Value;
 
 const ID_AUTHENTICATION_REQUEST: u8 = b'R';
+const ID_BACKEND_KEY_DATA: u8 = b'K';
 const ID_BIND_COMPLETE: u8 = b'2';
 const ID_CLOSE_COMPLETE: u8 = b'3';
 const ID_COMMAND_COMPLETE: u8 = b'C';
+const ID_COPY_DATA: u8 = b'd';
+const ID_COPY_DONE: u8 = b'c';
+const ID_COPY_IN_RESPONSE: u8 = b'G';
+const ID_COPY_OUT_RESPONSE: u8 = b'H';
 const ID_DATA_ROW: u8 = b'D';
 const ID_ERROR_RESPONSE: u8 = b'E';
 const ID_PARAMETER_DESCRIPTION: u8 = b't';
@@ -33,6 +38,7 @@ const AUTHENTICATION_SASL_REQUIRED: i32 = 10;
 const AUTHENTICATION_SASL_CHALLENGE: i32 = 11;
 const AUTHENTICATION_SASL_COMPLETED: i32 = 12;
 
+const COMMAND_COMPLETE_COPY_TAG: &str = "COPY";
 const COMMAND_COMPLETE_DELETE_TAG: &str = "DELETE";
 const COMMAND_COMPLETE_INSERT_TAG: &str = "INSERT";
 const COMMAND_COMPLETE_INSERT_LEGACY_OID: &str = "0";
@@ -131,6 +137,16 @@ where
             put_i32(AUTHENTICATION_OK_SUCCESS, dst);
         }
 
+        BackendKeyData {
+            process_id,
+            secret_key,
+        } => {
+            put_u8(ID_BACKEND_KEY_DATA, dst);
+            put_i32(LENGTH_PLACEHOLDER, dst);
+            put_i32(process_id, dst);
+            put_i32(secret_key, dst);
+        }
+
         BindComplete => {
             put_u8(ID_BIND_COMPLETE, dst);
             put_i32(LENGTH_PLACEHOLDER, dst);
@@ -147,6 +163,7 @@ where
             // Format command complete "tag" (eg "DELETE 5" to indicate 5 rows deleted).
             let mut tag_buf = [0u8; COMMAND_COMPLETE_TAG_BUF_LEN];
             match tag {
+                Copy(n) => write!(&mut tag_buf[..], "{} {}", COMMAND_COMPLETE_COPY_TAG, n)?,
                 Delete(n) => write!(&mut tag_buf[..], "{} {}", COMMAND_COMPLETE_DELETE_TAG, n)?,
                 Empty => {}
                 Insert(n) => write!(
@@ -178,6 +195,35 @@ where
             put_str(tag_str, dst);
         }
 
+        CopyData { data } => {
+            put_u8(ID_COPY_DATA, dst);
+            put_i32(LENGTH_PLACEHOLDER, dst);
+            put_slice(&data, dst);
+        }
+
+        CopyDone => {
+            put_u8(ID_COPY_DONE, dst);
+            put_i32(LENGTH_PLACEHOLDER, dst);
+        }
+
+        CopyInResponse {
+            overall_format,
+            column_formats,
+        } => {
+            put_u8(ID_COPY_IN_RESPONSE, dst);
+            put_i32(LENGTH_PLACEHOLDER, dst);
+            put_copy_response_formats(overall_format, column_formats, dst)?;
+        }
+
+        CopyOutResponse {
+            overall_format,
+            column_formats,
+        } => {
+            put_u8(ID_COPY_OUT_RESPONSE, dst);
+            put_i32(LENGTH_PLACEHOLDER, dst);
+            put_copy_response_formats(overall_format, column_formats, dst)?;
+        }
+
         DataRow {
             values,
             explicit_transfer_formats,
@@ -369,6 +415,27 @@ fn put_format(val: TransferFormat, dst: &mut BytesMut) {
     put_i16(format_code, dst)
 }
 
+/// Write the body of a `CopyInResponse` or `CopyOutResponse` message, which (unlike elsewhere in
+/// the protocol) encodes the overall format as an Int8
+fn put_copy_response_formats(
+    overall_format: TransferFormat,
+    column_formats: Vec<TransferFormat>,
+    dst: &mut BytesMut,
+) -> Result<(), Error> {
+    put_u8(
+        match overall_format {
+            Binary => 1,
+            Text => 0,
+        },
+        dst,
+    );
+    put_i16(i16::try_from(column_formats.len())?, dst);
+    for f in column_formats {
+        put_format(f, dst);
+    }
+    Ok(())
+}
+
 fn put_type(val: Type, dst: &mut BytesMut) -> Result<(), Error> {
     let oid = i32::try_from(val.oid())?;
     put_i32(oid, dst);
@@ -466,6 +533,9 @@ fn put_binary_value(val: Value, dst: &mut BytesMut) -> Result<(), Error> {
         Value::Array(arr, ty) => {
             arr.to_sql(&ty, dst)?;
         }
+        Value::Range(range, ty) => {
+            range.to_sql(&ty, dst)?;
+        }
         Value::PassThrough(p) => {
             dst.put(&p.data[..]);
         }
@@ -571,6 +641,7 @@ fn put_text_value(val: Value, dst: &mut BytesMut) -> Result<(), Error> {
                 .join("")
         )?,
         Value::Array(arr, _) => write!(dst, "{}", arr)?,
+        Value::Range(range, _) => write!(dst, "{}", range)?,
         Value::PassThrough(p) => {
             return Err(Error::InternalError(format!(
                 "Data of type {} unsupported in text mode",
@@ -590,7 +661,7 @@ mod tests {
     use std::sync::Arc;
 
     use bit_vec::BitVec;
-    use bytes::{BufMut, BytesMut};
+    use bytes::{BufMut, Bytes, BytesMut};
     use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
     use eui48::MacAddress;
     use postgres::SimpleQueryRow;
@@ -651,6 +722,69 @@ mod tests {
         assert_eq!(buf, exp);
     }
 
+    #[test]
+    fn test_encode_backend_key_data() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                BackendKeyData {
+                    process_id: 1234,
+                    secret_key: 5678,
+                },
+                &mut buf,
+            )
+            .unwrap();
+        let mut exp = BytesMut::new();
+        exp.put_u8(b'K'); // message id
+        exp.put_i32(12); // message length
+        exp.put_i32(1234); // process id
+        exp.put_i32(5678); // secret key
+        assert_eq!(buf, exp);
+    }
+
+    #[test]
+    fn test_encode_copy_out_response() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                CopyOutResponse {
+                    overall_format: Text,
+                    column_formats: vec![Text, Text],
+                },
+                &mut buf,
+            )
+            .unwrap();
+        let mut exp = BytesMut::new();
+        exp.put_u8(b'H'); // message id
+        exp.put_i32(4 + 1 + 2 + 2 + 2); // message length
+        exp.put_u8(0); // overall format
+        exp.put_i16(2); // number of columns
+        exp.put_i16(0); // column 1 format
+        exp.put_i16(0); // column 2 format
+        assert_eq!(buf, exp);
+    }
+
+    #[test]
+    fn test_encode_copy_data() {
+        let mut codec = Codec::<Vec<Value>>::new();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                CopyData {
+                    data: Bytes::from_static(b"1\tabc\n"),
+                },
+                &mut buf,
+            )
+            .unwrap();
+        let mut exp = BytesMut::new();
+        exp.put_u8(b'd'); // message id
+        exp.put_i32(4 + 6); // message length
+        exp.extend_from_slice(b"1\tabc\n");
+        assert_eq!(buf, exp);
+    }
+
     #[test]
     fn test_encode_bind_complete() {
         let mut codec = Codec::<Vec<Value>>::new();
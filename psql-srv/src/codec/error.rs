@@ -58,6 +58,9 @@ pub enum DecodeError {
     #[error("invalid text bit vector value: {0}")]
     InvalidTextBitVectorValue(String),
 
+    #[error("invalid text range value: {0}")]
+    InvalidTextRangeValue(String),
+
     #[error("unknown enum variant: {0}")]
     UnknownEnumVariant(String),
 
@@ -16,6 +16,9 @@ pub enum Error {
     #[error("no user specified in connection")]
     NoUserSpecified,
 
+    #[error("COPY from stdin failed: {0}")]
+    CopyFailed(String),
+
     #[error("decode error: {0}")]
     DecodeError(#[from] DecodeError),
 
@@ -43,6 +46,9 @@ pub enum Error {
     #[error("parse error: {0}")]
     ParseError(String),
 
+    #[error("canceling statement due to user request")]
+    QueryCanceled,
+
     #[error("unexpected message: {0}")]
     UnexpectedMessage(String),
 
@@ -73,6 +79,7 @@ impl<R> From<Error> for BackendMessage<R> {
         let sqlstate = match error {
             Error::AuthenticationFailure { .. } => SqlState::INVALID_PASSWORD,
             Error::NoUserSpecified => SqlState::INVALID_PASSWORD,
+            Error::CopyFailed(_) => SqlState::QUERY_CANCELED,
             Error::DecodeError(_) => SqlState::IO_ERROR,
             Error::EncodeError(_) => SqlState::IO_ERROR,
             Error::IncorrectFormatCount(_) => SqlState::IO_ERROR,
@@ -82,6 +89,7 @@ impl<R> From<Error> for BackendMessage<R> {
             Error::MissingPortal(_) => SqlState::UNDEFINED_PSTATEMENT,
             Error::MissingPreparedStatement(_) => SqlState::UNDEFINED_PSTATEMENT,
             Error::ParseError(_) => SqlState::INVALID_PSTATEMENT_DEFINITION,
+            Error::QueryCanceled => SqlState::QUERY_CANCELED,
             Error::Unimplemented(_) => SqlState::FEATURE_NOT_SUPPORTED,
             Error::UnexpectedMessage(_) => SqlState::PROTOCOL_VIOLATION,
             Error::Unknown(_) => SqlState::INTERNAL_ERROR,
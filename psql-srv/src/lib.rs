@@ -27,6 +27,8 @@ pub mod util;
 mod value;
 
 use std::convert::TryInto;
+use std::fmt;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -39,6 +41,7 @@ use tokio_native_tls::TlsAcceptor;
 
 pub use crate::bytes::BytesStr;
 pub use crate::error::Error;
+pub use crate::message::TransferFormat;
 pub use crate::value::Value;
 
 pub enum CredentialsNeeded {
@@ -55,6 +58,17 @@ pub enum Credentials<'a> {
     CleartextPassword(&'a str),
 }
 
+/// A key identifying a single connection to a [`Backend`], sent to the client on startup and used
+/// by the client to cancel the query currently being run by that connection from a separate
+/// connection.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CancelKey {
+    /// The (not necessarily real) process ID of the backend serving the connection
+    pub process_id: i32,
+    /// A secret key which must be provided along with the process ID to cancel a query
+    pub secret_key: i32,
+}
+
 /// A trait for implementing a SQL backend that produces responses to SQL query statements. This
 /// trait is the primary interface for the `psql-srv` crate.
 #[async_trait]
@@ -88,6 +102,22 @@ pub trait Backend {
     /// Look up authentication credentials for the given user
     fn credentials_for_user(&self, user: &str) -> Option<Credentials>;
 
+    /// Returns the key the client can use to cancel queries running on this connection, if this
+    /// backend supports query cancellation.
+    ///
+    /// If this returns `Some`, the key will be sent to the client in a `BackendKeyData` message on
+    /// startup.
+    fn cancel_key(&self) -> Option<CancelKey> {
+        None
+    }
+
+    /// Handles a request from a client, sent on a fresh connection, to cancel the query currently
+    /// running on the connection identified by `key`.
+    ///
+    /// As in PostgreSQL, no response is sent to the client regardless of whether the query was
+    /// actually canceled, and the connection is closed immediately afterwards.
+    async fn on_cancel(&mut self, _key: CancelKey) {}
+
     /// Performs the specified SQL query.
     ///
     /// * `query` - The sql query to perform.
@@ -118,6 +148,30 @@ pub trait Backend {
     ///
     /// * `statement_id` - The identifier of the prepared statement to close.
     async fn on_close(&mut self, statement_id: u32) -> Result<(), Error>;
+
+    /// Receives a chunk of data sent by the client for a `COPY ... FROM STDIN` statement, after
+    /// `on_query` has returned [`QueryResponse::CopyIn`].
+    ///
+    /// * `data` - The raw data, in the format given in the `CopyIn` response. Chunks do not
+    ///   necessarily correspond to rows.
+    async fn on_copy_data(&mut self, _data: ::bytes::Bytes) -> Result<(), Error> {
+        Err(Error::Unsupported("COPY FROM STDIN".to_string()))
+    }
+
+    /// Completes a `COPY ... FROM STDIN` statement once the client has sent all of its data.
+    ///
+    /// * returns - The number of rows copied, or an `Error` if a failure occurs.
+    async fn on_copy_done(&mut self) -> Result<u64, Error> {
+        Err(Error::Unsupported("COPY FROM STDIN".to_string()))
+    }
+
+    /// Aborts a `COPY ... FROM STDIN` statement, either at the client's request or because an
+    /// error occurred while handling the copied data.
+    ///
+    /// * `message` - The reason the copy was aborted.
+    async fn on_copy_fail(&mut self, _message: &str) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 /// A description of a column, either in the parameters to a query or in a resultset
@@ -163,6 +217,36 @@ pub enum QueryResponse<R> {
     /// commands (e.g., SELECT, INSERT, DELETE, etc.). The SimpleQuery protocol is distinct from
     /// the prepare/execute protocol.
     SimpleQuery(Vec<SimpleQueryMessage>),
+    /// The response to a `COPY ... FROM STDIN` statement. After this response is sent, data from
+    /// the client will be passed to `Backend::on_copy_data` until the client finishes the copy
+    /// (`Backend::on_copy_done`) or aborts it (`Backend::on_copy_fail`).
+    CopyIn {
+        /// The format of the data the client should send
+        overall_format: TransferFormat,
+        /// The format of each column of the data the client should send
+        column_formats: Vec<TransferFormat>,
+    },
+    /// The response to a `COPY ... TO STDOUT` statement.
+    CopyOut {
+        /// The format of the data sent to the client
+        overall_format: TransferFormat,
+        /// The format of each column of the data sent to the client
+        column_formats: Vec<TransferFormat>,
+        /// The data to send to the client
+        data: CopyOutStream,
+    },
+}
+
+/// A stream of raw data to send to the client in response to a `COPY ... TO STDOUT` statement.
+///
+/// Each item in the stream is sent to the client as a single `CopyData` message, which (in the
+/// text and CSV formats) should correspond to a single row.
+pub struct CopyOutStream(pub Pin<Box<dyn Stream<Item = Result<::bytes::Bytes, Error>> + Send>>);
+
+impl fmt::Debug for CopyOutStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CopyOutStream(..)")
+    }
 }
 
 /// Run a `Backend` on the provided bytestream until the bytestream is remotely closed.
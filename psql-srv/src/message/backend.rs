@@ -39,12 +39,28 @@ pub enum BackendMessage<R> {
         sasl_data: Bytes,
     },
     AuthenticationOk,
+    BackendKeyData {
+        process_id: i32,
+        secret_key: i32,
+    },
     BindComplete,
     CloseComplete,
     CommandComplete {
         tag: CommandCompleteTag,
     },
     PassThroughCommandComplete(Bytes),
+    CopyData {
+        data: Bytes,
+    },
+    CopyDone,
+    CopyInResponse {
+        overall_format: TransferFormat,
+        column_formats: Vec<TransferFormat>,
+    },
+    CopyOutResponse {
+        overall_format: TransferFormat,
+        column_formats: Vec<TransferFormat>,
+    },
     DataRow {
         values: R,
         explicit_transfer_formats: Option<Arc<Vec<TransferFormat>>>,
@@ -97,6 +113,7 @@ impl<R: IntoIterator<Item: TryInto<Value, Error = Error>>> BackendMessage<R> {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CommandCompleteTag {
+    Copy(u64),
     Delete(u64),
     Empty,
     Insert(u64),
@@ -33,9 +33,25 @@ pub enum FrontendMessage {
         params: Vec<Value>,
         result_transfer_formats: Vec<TransferFormat>,
     },
+    /// A request, sent on a fresh connection instead of a StartupMessage, to cancel the query
+    /// currently being run by the connection identified by `process_id` and `secret_key`
+    CancelRequest {
+        process_id: i32,
+        secret_key: i32,
+    },
     Close {
         name: StatementName,
     },
+    /// Data sent by the frontend during a `COPY ... FROM STDIN`
+    CopyData {
+        data: Bytes,
+    },
+    /// Indicates that the frontend has finished sending data for a `COPY ... FROM STDIN`
+    CopyDone,
+    /// Indicates that the frontend has aborted a `COPY ... FROM STDIN`, with an error message
+    CopyFail {
+        message: BytesStr,
+    },
     Describe {
         name: StatementName,
     },
@@ -76,7 +92,11 @@ impl fmt::Display for FrontendMessage {
         match self {
             Self::Authenticate { .. } => write!(f, "Authenticate"),
             Self::Bind { .. } => write!(f, "Bind"),
+            Self::CancelRequest { .. } => write!(f, "CancelRequest"),
             Self::Close { .. } => write!(f, "Close"),
+            Self::CopyData { .. } => write!(f, "CopyData"),
+            Self::CopyDone => write!(f, "CopyDone"),
+            Self::CopyFail { .. } => write!(f, "CopyFail"),
             Self::Describe { .. } => write!(f, "Describe"),
             Self::Execute { .. } => write!(f, "Execute"),
             Self::Parse { .. } => write!(f, "Parse"),
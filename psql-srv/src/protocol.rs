@@ -25,7 +25,7 @@ use crate::scram::{
 };
 use crate::value::Value;
 use crate::QueryResponse::*;
-use crate::{Backend, Column, Credentials, PrepareResponse};
+use crate::{Backend, CancelKey, Column, Credentials, PrepareResponse};
 
 const ATTTYPMOD_NONE: i32 = -1;
 const TRANSFER_FORMAT_PLACEHOLDER: TransferFormat = TransferFormat::Text;
@@ -67,6 +67,7 @@ pub(crate) enum SaslState {
 ///
 /// * StartingUp -> SslHandshake
 /// * SslHandshake -> StartingUp
+/// * StartingUp -> Closing
 /// * StartingUp -> Ready
 /// * StartingUp -> AuthenticatingCleartext
 /// * StartingUp -> AuthenticatingSasl
@@ -74,6 +75,10 @@ pub(crate) enum SaslState {
 /// * AuthenticatingSasl -> AuthenticatingSasl
 /// * AuthenticatingSasl -> Ready
 /// * Ready -> Extended
+/// * Ready -> CopyIn
+/// * Extended -> CopyIn
+/// * CopyIn -> Ready
+/// * CopyIn -> Extended
 /// * Extended -> Error
 /// * Error -> Ready
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -94,6 +99,18 @@ pub(crate) enum State {
     /// message, and followed by a StartupMessage.
     SslHandshake,
 
+    /// The client has sent a CancelRequest in place of a StartupMessage, and the connection should
+    /// be closed without processing any further messages
+    Closing,
+
+    /// The server is receiving data from the client for a [`COPY ... FROM STDIN`][0]
+    ///
+    /// [0]: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-COPY
+    CopyIn {
+        /// Whether the copy was started by an extended query (rather than a simple query)
+        extended: bool,
+    },
+
     /// The server is currently processing an [extended query][0]
     ///
     /// [0]: https://www.postgresql.org/docs/13/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY
@@ -200,8 +217,8 @@ impl Protocol {
         channel: &mut Channel<C, B::Row>,
     ) -> Result<Response<B::Row, B::Resultset>, Error> {
         // TODO(grfn): Discard if self.state.is_error()?
-        let get_ready_message = |version| {
-            smallvec![
+        let get_ready_message = |version, cancel_key: Option<CancelKey>| {
+            let mut messages = smallvec![
                 AuthenticationOk,
                 BackendMessage::ParameterStatus {
                     parameter_name: "client_encoding".to_owned(),
@@ -223,8 +240,19 @@ impl Protocol {
                     parameter_name: "server_version".to_owned(),
                     parameter_value: version,
                 },
-                BackendMessage::ready_for_query_idle(),
-            ]
+            ];
+            if let Some(CancelKey {
+                process_id,
+                secret_key,
+            }) = cancel_key
+            {
+                messages.push(BackendKeyData {
+                    process_id,
+                    secret_key,
+                });
+            }
+            messages.push(BackendMessage::ready_for_query_idle());
+            messages
         };
         match self.state {
             State::StartingUp => match message {
@@ -240,6 +268,22 @@ impl Protocol {
                     }
                 }
 
+                // A request to cancel the query running on another connection. This is sent on a
+                // fresh connection in place of a StartupMessage, and no response is sent.
+                CancelRequest {
+                    process_id,
+                    secret_key,
+                } => {
+                    backend
+                        .on_cancel(CancelKey {
+                            process_id,
+                            secret_key,
+                        })
+                        .await;
+                    self.state = State::Closing;
+                    Ok(Response::Empty)
+                }
+
                 // A request to start up a connection, with some metadata provided.
                 StartupMessage { database, user, .. } => {
                     let database = database
@@ -247,7 +291,7 @@ impl Protocol {
                     let response = match backend.on_init(database.borrow()).await? {
                         crate::CredentialsNeeded::None => {
                             self.state = State::Ready;
-                            get_ready_message(backend.version())
+                            get_ready_message(backend.version(), backend.cancel_key())
                         }
                         crate::CredentialsNeeded::Cleartext => {
                             self.state = State::AuthenticatingCleartext {
@@ -293,7 +337,10 @@ impl Protocol {
 
                     self.state = State::Ready;
 
-                    Ok(Response::Messages(get_ready_message(backend.version())))
+                    Ok(Response::Messages(get_ready_message(
+                        backend.version(),
+                        backend.cancel_key(),
+                    )))
                 }
 
                 m => Err(Error::UnsupportedMessage(m)),
@@ -312,7 +359,10 @@ impl Protocol {
                     }
                     Some(Credentials::Any) => {
                         self.state = State::Ready;
-                        return Ok(Response::Messages(get_ready_message(backend.version())));
+                        return Ok(Response::Messages(get_ready_message(
+                            backend.version(),
+                            backend.cancel_key(),
+                        )));
                     }
                     Some(Credentials::CleartextPassword(pw)) => pw,
                 };
@@ -408,7 +458,7 @@ impl Protocol {
                     let mut messages = vec![BackendMessage::AuthenticationSaslFinal {
                         sasl_data: server_final_message.to_string().into(),
                     }];
-                    messages.extend(get_ready_message(backend.version()));
+                    messages.extend(get_ready_message(backend.version(), backend.cancel_key()));
                     Ok(Response::Messages(messages.into()))
                 } else {
                     Err(Error::AuthenticationFailure {
@@ -417,6 +467,64 @@ impl Protocol {
                 }
             }
 
+            State::CopyIn { extended } => {
+                // The state to return to once the copy is over. After an error, a copy started
+                // by an extended query must still wait for the frontend to send a Sync.
+                let next_state = if extended {
+                    State::Extended
+                } else {
+                    State::Ready
+                };
+                match message {
+                    FrontendMessage::CopyData { data } => {
+                        if let Err(e) = backend.on_copy_data(data).await {
+                            // Abort the copy. Any further copy messages sent by the frontend
+                            // before it notices the error will be dropped.
+                            self.state = next_state;
+                            backend.on_copy_fail(&e.to_string()).await?;
+                            return Err(e);
+                        }
+                        Ok(Response::Empty)
+                    }
+
+                    FrontendMessage::CopyDone => {
+                        self.state = next_state;
+                        let n_rows = backend.on_copy_done().await?;
+                        self.state = State::Ready;
+                        let command_complete = CommandComplete {
+                            tag: CommandCompleteTag::Copy(n_rows),
+                        };
+                        if extended {
+                            Ok(Response::Message(command_complete))
+                        } else {
+                            Ok(Response::Messages(smallvec![
+                                command_complete,
+                                BackendMessage::ready_for_query_idle(),
+                            ]))
+                        }
+                    }
+
+                    FrontendMessage::CopyFail { message } => {
+                        self.state = next_state;
+                        backend.on_copy_fail(message.borrow()).await?;
+                        Err(Error::CopyFailed(message.to_string()))
+                    }
+
+                    // Flush and Sync are ignored during copy-in mode
+                    Flush | Sync => Ok(Response::Empty),
+
+                    m => {
+                        self.state = next_state;
+                        backend
+                            .on_copy_fail("unexpected message during COPY")
+                            .await?;
+                        Err(Error::UnexpectedMessage(format!(
+                            "{m} received during COPY FROM STDIN"
+                        )))
+                    }
+                }
+            }
+
             _ => match message {
                 // A request to bind parameters to a prepared statement, creating a portal.
                 Bind {
@@ -572,6 +680,31 @@ impl Protocol {
                             result_transfer_formats: Some(result_transfer_formats.clone()),
                             trailer: None,
                         })
+                    } else if let CopyIn {
+                        overall_format,
+                        column_formats,
+                    } = response
+                    {
+                        self.state = State::CopyIn { extended: true };
+                        return Ok(Response::Message(CopyInResponse {
+                            overall_format,
+                            column_formats,
+                        }));
+                    } else if let CopyOut {
+                        overall_format,
+                        column_formats,
+                        data,
+                    } = response
+                    {
+                        Ok(Response::CopyOut {
+                            header: CopyOutResponse {
+                                overall_format,
+                                column_formats,
+                            },
+                            data,
+                            overall_format,
+                            trailer: None,
+                        })
                     } else {
                         let tag = match response {
                             Insert(n) => CommandCompleteTag::Insert(n),
@@ -587,6 +720,9 @@ impl Protocol {
                                     "Received SimpleQuery response for Execute".to_string(),
                                 ));
                             }
+                            CopyIn { .. } | CopyOut { .. } => {
+                                unreachable!("COPY is handled as a special case above.")
+                            }
                         };
                         Ok(Response::Message(CommandComplete { tag }))
                     };
@@ -653,6 +789,31 @@ impl Protocol {
                         }
                         messages.push(BackendMessage::ready_for_query_idle());
                         Ok(Response::Messages(messages))
+                    } else if let CopyIn {
+                        overall_format,
+                        column_formats,
+                    } = response
+                    {
+                        self.state = State::CopyIn { extended: false };
+                        Ok(Response::Message(CopyInResponse {
+                            overall_format,
+                            column_formats,
+                        }))
+                    } else if let CopyOut {
+                        overall_format,
+                        column_formats,
+                        data,
+                    } = response
+                    {
+                        Ok(Response::CopyOut {
+                            header: CopyOutResponse {
+                                overall_format,
+                                column_formats,
+                            },
+                            data,
+                            overall_format,
+                            trailer: Some(BackendMessage::ready_for_query_idle()),
+                        })
                     } else {
                         let tag = match response {
                             Insert(n) => CommandCompleteTag::Insert(n),
@@ -666,6 +827,9 @@ impl Protocol {
                             SimpleQuery(_) => {
                                 unreachable!("SimpleQuery is handled as a special case above.")
                             }
+                            CopyIn { .. } | CopyOut { .. } => {
+                                unreachable!("COPY is handled as a special case above.")
+                            }
                         };
                         Ok(Response::Messages(smallvec![
                             CommandComplete { tag },
@@ -709,6 +873,12 @@ impl Protocol {
 
                 Flush => Ok(Response::Empty),
 
+                // Copy messages sent by the frontend after a COPY FROM STDIN has been aborted are
+                // dropped.
+                FrontendMessage::CopyData { .. }
+                | FrontendMessage::CopyDone
+                | FrontendMessage::CopyFail { .. } => Ok(Response::Empty),
+
                 // A request to terminate the connection.
                 Terminate => Ok(Response::Empty),
 
@@ -744,6 +914,11 @@ impl Protocol {
         self.state == State::SslHandshake
     }
 
+    /// Whether the `Protocol` has finished handling the connection, which should now be closed.
+    pub fn is_closing(&self) -> bool {
+        self.state == State::Closing
+    }
+
     /// Informs the `Protocol` that we have initiated a TLS connection with the client, storing the
     /// TLS server endpoint for optional use in channel binding later.
     pub fn completed_ssl_handshake(&mut self, server_end_point: Option<Vec<u8>>) {
@@ -790,6 +965,7 @@ async fn make_field_description<B: Backend>(
 ) -> Result<FieldDescription, Error> {
     let data_type_size = match col.col_type.kind() {
         Kind::Array(_) => TYPLEN_VARLENA,
+        Kind::Range(_) => TYPLEN_VARLENA,
         Kind::Enum(_) => TYPLEN_VARLENA,
         _ => match col.col_type {
             Type::BOOL => TYPLEN_1,
@@ -917,7 +1093,7 @@ mod tests {
     use std::{io, vec};
 
     use async_trait::async_trait;
-    use bytes::BytesMut;
+    use bytes::{Bytes, BytesMut};
     use futures::task::Context;
     use futures::{stream, TryStreamExt};
     use postgres::error::SqlState;
@@ -952,6 +1128,7 @@ mod tests {
     struct Backend {
         is_query_err: bool,
         is_query_read: bool,
+        is_query_copy_in: bool,
 
         is_prepare_err: bool,
 
@@ -962,6 +1139,10 @@ mod tests {
         last_execute_id: Option<u32>,
         last_execute_params: Option<Vec<DataValue>>,
         needed_credentials: Option<Credentials<'static>>,
+        cancel_key: Option<CancelKey>,
+        last_cancel: Option<CancelKey>,
+        copied_data: Vec<Bytes>,
+        copy_failure: Option<String>,
     }
 
     impl Backend {
@@ -969,6 +1150,7 @@ mod tests {
             Backend {
                 is_query_err: false,
                 is_query_read: true,
+                is_query_copy_in: false,
                 is_prepare_err: false,
                 database: None,
                 last_query: None,
@@ -977,6 +1159,10 @@ mod tests {
                 last_execute_id: None,
                 last_execute_params: None,
                 needed_credentials: None,
+                cancel_key: None,
+                last_cancel: None,
+                copied_data: vec![],
+                copy_failure: None,
             }
         }
     }
@@ -1003,10 +1189,23 @@ mod tests {
             self.needed_credentials
         }
 
+        fn cancel_key(&self) -> Option<CancelKey> {
+            self.cancel_key
+        }
+
+        async fn on_cancel(&mut self, key: CancelKey) {
+            self.last_cancel = Some(key);
+        }
+
         async fn on_query(&mut self, query: &str) -> Result<QueryResponse<Self::Resultset>, Error> {
             self.last_query = Some(query.to_string());
             if self.is_query_err {
                 Err(Error::InternalError("error requested".to_string()))
+            } else if self.is_query_copy_in {
+                Ok(QueryResponse::CopyIn {
+                    overall_format: Text,
+                    column_formats: vec![Text, Text],
+                })
             } else if self.is_query_read {
                 Ok(QueryResponse::Select {
                     schema: vec![
@@ -1098,6 +1297,20 @@ mod tests {
             self.last_close = Some(statement_id);
             Ok(())
         }
+
+        async fn on_copy_data(&mut self, data: Bytes) -> Result<(), Error> {
+            self.copied_data.push(data);
+            Ok(())
+        }
+
+        async fn on_copy_done(&mut self) -> Result<u64, Error> {
+            Ok(self.copied_data.len() as u64)
+        }
+
+        async fn on_copy_fail(&mut self, message: &str) -> Result<(), Error> {
+            self.copy_failure = Some(message.to_string());
+            Ok(())
+        }
     }
 
     // A dummy `AsyncRead + AsyncWrite` that does not read or write any data.
@@ -1203,6 +1416,63 @@ mod tests {
         assert_eq!(protocol.state, State::Ready);
     }
 
+    #[test]
+    fn startup_message_with_cancel_key() {
+        let mut protocol = Protocol::new();
+        let request = FrontendMessage::StartupMessage {
+            protocol_version: 12345,
+            user: Some(bytes_str("user_name")),
+            database: Some(bytes_str("database_name")),
+        };
+        let mut backend = Backend::new();
+        let cancel_key = CancelKey {
+            process_id: 1234,
+            secret_key: 5678,
+        };
+        backend.cancel_key = Some(cancel_key);
+        let mut channel = Channel::<NullBytestream, Vec<Value>>::new(NullBytestream);
+        // The cancel key is sent to the client immediately before the first ReadyForQuery.
+        match block_on(protocol.on_request(request, &mut backend, &mut channel)).unwrap() {
+            Response::Messages(ms) => assert_eq!(
+                &ms[ms.len() - 2..],
+                &[
+                    BackendMessage::BackendKeyData {
+                        process_id: 1234,
+                        secret_key: 5678,
+                    },
+                    BackendMessage::ready_for_query_idle()
+                ]
+            ),
+            _ => panic!(),
+        }
+        assert_eq!(protocol.state, State::Ready);
+    }
+
+    #[test]
+    fn cancel_request() {
+        let mut protocol = Protocol::new();
+        let request = FrontendMessage::CancelRequest {
+            process_id: 1234,
+            secret_key: 5678,
+        };
+        let mut backend = Backend::new();
+        let mut channel = Channel::<NullBytestream, Vec<Value>>::new(NullBytestream);
+        // A CancelRequest is forwarded to the backend, and no response is sent.
+        assert!(matches!(
+            block_on(protocol.on_request(request, &mut backend, &mut channel)).unwrap(),
+            Response::Empty
+        ));
+        assert_eq!(
+            backend.last_cancel,
+            Some(CancelKey {
+                process_id: 1234,
+                secret_key: 5678,
+            })
+        );
+        // The connection is closed after handling the CancelRequest.
+        assert!(protocol.is_closing());
+    }
+
     #[test]
     fn authentication_flow_successful() {
         let expected_username = bytes_str("user_name");
@@ -1400,6 +1670,138 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn copy_in() {
+        let mut protocol = Protocol::new();
+        let mut backend = Backend::new();
+        backend.is_query_copy_in = true;
+        let mut channel = Channel::<NullBytestream, Vec<Value>>::new(NullBytestream);
+
+        let startup_request = FrontendMessage::StartupMessage {
+            protocol_version: 12345,
+            user: Some(bytes_str("user_name")),
+            database: Some(bytes_str("database_name")),
+        };
+        protocol
+            .on_request(startup_request, &mut backend, &mut channel)
+            .await
+            .unwrap();
+
+        // A COPY FROM STDIN query puts the protocol into copy-in mode.
+        let request = FrontendMessage::Query {
+            query: bytes_str("COPY test FROM STDIN"),
+        };
+        match protocol
+            .on_request(request, &mut backend, &mut channel)
+            .await
+            .unwrap()
+        {
+            Response::Message(m) => assert_eq!(
+                m,
+                CopyInResponse {
+                    overall_format: Text,
+                    column_formats: vec![Text, Text],
+                }
+            ),
+            _ => panic!(),
+        }
+        assert_eq!(protocol.state, State::CopyIn { extended: false });
+
+        // Copied data is passed to the backend, and Sync is ignored.
+        for request in [
+            FrontendMessage::CopyData {
+                data: Bytes::from_static(b"1\ta\n"),
+            },
+            FrontendMessage::Sync,
+            FrontendMessage::CopyData {
+                data: Bytes::from_static(b"2\tb\n"),
+            },
+        ] {
+            assert!(matches!(
+                protocol
+                    .on_request(request, &mut backend, &mut channel)
+                    .await
+                    .unwrap(),
+                Response::Empty
+            ));
+        }
+        assert_eq!(
+            backend.copied_data,
+            vec![Bytes::from_static(b"1\ta\n"), Bytes::from_static(b"2\tb\n")]
+        );
+
+        // CopyDone completes the copy.
+        match protocol
+            .on_request(FrontendMessage::CopyDone, &mut backend, &mut channel)
+            .await
+            .unwrap()
+        {
+            Response::Messages(ms) => assert_eq!(
+                ms.as_ref(),
+                vec![
+                    CommandComplete {
+                        tag: CommandCompleteTag::Copy(2)
+                    },
+                    BackendMessage::ready_for_query_idle()
+                ]
+            ),
+            _ => panic!(),
+        }
+        assert_eq!(protocol.state, State::Ready);
+    }
+
+    #[tokio::test]
+    async fn copy_fail() {
+        let mut protocol = Protocol::new();
+        let mut backend = Backend::new();
+        backend.is_query_copy_in = true;
+        let mut channel = Channel::<NullBytestream, Vec<Value>>::new(NullBytestream);
+
+        let startup_request = FrontendMessage::StartupMessage {
+            protocol_version: 12345,
+            user: Some(bytes_str("user_name")),
+            database: Some(bytes_str("database_name")),
+        };
+        protocol
+            .on_request(startup_request, &mut backend, &mut channel)
+            .await
+            .unwrap();
+        let request = FrontendMessage::Query {
+            query: bytes_str("COPY test FROM STDIN"),
+        };
+        protocol
+            .on_request(request, &mut backend, &mut channel)
+            .await
+            .unwrap();
+
+        // CopyFail aborts the copy with an error.
+        let request = FrontendMessage::CopyFail {
+            message: bytes_str("client gave up"),
+        };
+        assert!(matches!(
+            protocol
+                .on_request(request, &mut backend, &mut channel)
+                .await
+                .unwrap_err(),
+            Error::CopyFailed(_)
+        ));
+        assert_eq!(backend.copy_failure.as_deref(), Some("client gave up"));
+        assert_eq!(protocol.state, State::Ready);
+
+        // Copy messages sent after the copy was aborted are dropped.
+        let request = FrontendMessage::CopyData {
+            data: Bytes::from_static(b"1\ta\n"),
+        };
+        assert!(matches!(
+            protocol
+                .on_request(request, &mut backend, &mut channel)
+                .await
+                .unwrap(),
+            Response::Empty
+        ));
+        assert!(backend.copied_data.is_empty());
+    }
+
     #[tokio::test]
     async fn query_read() {
         let mut protocol = Protocol::new();
@@ -8,11 +8,12 @@ use crate::codec::EncodeError;
 use crate::error::Error;
 use crate::message::{BackendMessage, CommandCompleteTag, TransferFormat};
 use crate::value::Value;
+use crate::CopyOutStream;
 
 /// An encapsulation of a complete response produced by a Postgresql backend in response to a
 /// request. The response will be sent to the frontend as a sequence of zero or more
 /// `BackendMessage`s.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug)]
 #[warn(variant_size_differences)]
 pub enum Response<R, S> {
     Empty,
@@ -28,6 +29,14 @@ pub enum Response<R, S> {
         result_transfer_formats: Option<Arc<Vec<TransferFormat>>>,
         trailer: Option<BackendMessage<R>>,
     },
+
+    /// `CopyOut` streams raw data to the frontend in response to a `COPY ... TO STDOUT` query.
+    CopyOut {
+        header: BackendMessage<R>,
+        data: CopyOutStream,
+        overall_format: TransferFormat,
+        trailer: Option<BackendMessage<R>>,
+    },
 }
 
 impl<R, S> Response<R, S>
@@ -95,6 +104,51 @@ where
 
                 sink.flush().await
             }
+
+            CopyOut {
+                header,
+                mut data,
+                overall_format,
+                trailer,
+            } => {
+                sink.feed(header).await?;
+
+                let mut n_messages = 0;
+                while let Some(r) = data.0.next().await {
+                    match r {
+                        Ok(data) => {
+                            sink.feed(BackendMessage::CopyData { data }).await?;
+                            n_messages += 1;
+                        }
+                        Err(e) => {
+                            // An error ends copy-out mode without a CopyDone message
+                            sink.feed(e.into()).await?;
+                            if let Some(trailer) = trailer {
+                                sink.feed(trailer).await?;
+                            }
+                            return sink.flush().await;
+                        }
+                    }
+                }
+
+                // In the binary format PostgreSQL sends the file trailer in a CopyData message of
+                // its own, so it shouldn't be counted as a row
+                let n_rows = match overall_format {
+                    TransferFormat::Binary => n_messages.saturating_sub(1),
+                    TransferFormat::Text => n_messages,
+                };
+                sink.feed(BackendMessage::CopyDone).await?;
+                sink.feed(BackendMessage::CommandComplete {
+                    tag: CommandCompleteTag::Copy(n_rows),
+                })
+                .await?;
+
+                if let Some(trailer) = trailer {
+                    sink.feed(trailer).await?;
+                }
+
+                sink.flush().await
+            }
         }
     }
 }
@@ -273,4 +327,58 @@ mod tests {
         futures::pin_mut!(validating_sink);
         block_on(response.write(&mut validating_sink)).unwrap();
     }
+
+    #[test]
+    fn write_copy_out() {
+        let response = TestResponse::CopyOut {
+            header: BackendMessage::CopyOutResponse {
+                overall_format: TransferFormat::Text,
+                column_formats: vec![TransferFormat::Text],
+            },
+            data: CopyOutStream(Box::pin(stream::iter(vec![
+                Ok(bytes::Bytes::from_static(b"1\n")),
+                Ok(bytes::Bytes::from_static(b"2\n")),
+            ]))),
+            overall_format: TransferFormat::Text,
+            trailer: Some(BackendMessage::ready_for_query_idle()),
+        };
+        let validating_sink = sink::unfold(0, |i, m: BackendMessage<Vec<Value>>| {
+            async move {
+                match i {
+                    0 => assert_eq!(
+                        m,
+                        BackendMessage::CopyOutResponse {
+                            overall_format: TransferFormat::Text,
+                            column_formats: vec![TransferFormat::Text],
+                        }
+                    ),
+                    1 => assert_eq!(
+                        m,
+                        BackendMessage::CopyData {
+                            data: bytes::Bytes::from_static(b"1\n")
+                        }
+                    ),
+                    2 => assert_eq!(
+                        m,
+                        BackendMessage::CopyData {
+                            data: bytes::Bytes::from_static(b"2\n")
+                        }
+                    ),
+                    3 => assert_eq!(m, BackendMessage::CopyDone),
+                    4 => assert_eq!(
+                        m,
+                        BackendMessage::CommandComplete {
+                            tag: CommandCompleteTag::Copy(2)
+                        }
+                    ),
+                    5 => assert_eq!(m, BackendMessage::ready_for_query_idle()),
+                    // No further messages are expected.
+                    _ => panic!(),
+                }
+                Ok::<_, EncodeError>(i + 1)
+            }
+        });
+        futures::pin_mut!(validating_sink);
+        block_on(response.write(&mut validating_sink)).unwrap();
+    }
 }
@@ -131,6 +131,10 @@ impl<B: Backend, C: AsyncRead + AsyncWrite + Unpin> Runner<B, C> {
                     if self.protocol.is_initiating_ssl_handshake() {
                         return MainLoopStatus::RestartWithTls;
                     }
+                    // The client sent a CancelRequest, after which the connection is closed
+                    if self.protocol.is_closing() {
+                        break;
+                    }
                 }
                 // Return an error message but do not exit the loop
                 Err(e) => {
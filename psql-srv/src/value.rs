@@ -2,7 +2,7 @@ use bit_vec::BitVec;
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use cidr::IpInet;
 use eui48::MacAddress;
-use readyset_data::{Array, Text};
+use readyset_data::{Array, Range, Text};
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
@@ -36,5 +36,6 @@ pub enum Value {
     Bit(BitVec),
     VarBit(BitVec),
     Array(Array, postgres_types::Type),
+    Range(Range, postgres_types::Type),
     PassThrough(readyset_data::PassThrough),
 }
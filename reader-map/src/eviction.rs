@@ -17,12 +17,26 @@
 //! LRU: evicts the least recently used keys
 //! Generational: like LRU but the count is inexact, and bucketed into
 //! generations, generation is counted as one eviction cycle.
+//!
+//! Independently of the strategy, if the map has a TTL the metadata also records when each key
+//! was added to the map, which is used to tell whether an entry in the map's expiry queue still
+//! refers to the key currently in the map.
+//!
+//! For readers with a memory limit of their own, `pick_keys_in_eviction_order` returns all of the
+//! keys in the order the strategy would evict them, so that keys can be evicted one by one until
+//! enough bytes have been freed.
+//!
+//! The metadata kept by the LRU and Generational strategies is also used by `pick_hottest_keys`
+//! to find the keys that were read most recently, which are the ones worth keeping around across
+//! restarts.
 
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::Arc;
+use std::time::Instant;
 
 use itertools::Either;
+use rand::seq::SliceRandom;
 use rand::Rng;
 
 use crate::inner::Data;
@@ -53,8 +67,11 @@ impl Default for EvictionStrategy {
 
 /// Used to store strategy specific metadata for every key in the reader map
 #[derive(Default, Clone, Debug)]
-#[repr(transparent)]
-pub struct EvictionMeta(Arc<AtomicU64>);
+pub struct EvictionMeta {
+    value: Arc<AtomicU64>,
+    /// The time the key was added to the map, only tracked for maps with a TTL
+    created: Option<Instant>,
+}
 
 #[derive(Clone, Debug)]
 pub struct RandomEviction;
@@ -139,8 +156,26 @@ where
 }
 
 impl EvictionMeta {
+    fn new(value: u64) -> Self {
+        EvictionMeta {
+            value: AtomicU64::new(value).into(),
+            created: None,
+        }
+    }
+
     pub fn value(&self) -> u64 {
-        self.0.load(Relaxed)
+        self.value.load(Relaxed)
+    }
+
+    /// Record that the key was added to the map at the given time
+    pub(crate) fn with_created(mut self, created: Instant) -> Self {
+        self.created = Some(created);
+        self
+    }
+
+    /// Returns the time the key was added to the map, if the map has a TTL
+    pub(crate) fn created(&self) -> Option<Instant> {
+        self.created
     }
 }
 
@@ -200,6 +235,59 @@ impl EvictionStrategy {
         }
     }
 
+    /// Return all the keys and values in the map, ordered from the first to the last the strategy
+    /// suggests to evict. Nothing is actually evicted following this call, but like
+    /// `pick_keys_to_evict` this counts as an eviction cycle for generational eviction.
+    pub(crate) fn pick_keys_in_eviction_order<'a, K, V, S>(
+        &self,
+        data: &'a Data<K, V, S>,
+    ) -> Vec<(&'a K, &'a Values<V>)>
+    where
+        K: Ord + Clone,
+        S: std::hash::BuildHasher,
+    {
+        let mut entries = data.iter().collect::<Vec<_>>();
+        match self {
+            EvictionStrategy::Random(_) => entries.shuffle(&mut rand::thread_rng()),
+            EvictionStrategy::LeastRecentlyUsed(_) => {
+                entries.sort_by_cached_key(|(_, v)| v.eviction_meta().value())
+            }
+            EvictionStrategy::Generational(gen) => {
+                gen.0.fetch_add(1, Relaxed);
+                entries.sort_by_cached_key(|(_, v)| v.eviction_meta().value())
+            }
+        }
+        entries
+    }
+
+    /// Return up to `nkeys` of the keys that were read most recently, most recent first. The
+    /// random strategy doesn't keep track of reads, so it never returns any keys.
+    pub(crate) fn pick_hottest_keys<'a, K, V, S>(
+        &self,
+        data: &'a Data<K, V, S>,
+        nkeys: usize,
+    ) -> Vec<&'a K>
+    where
+        K: Ord + Clone,
+        S: std::hash::BuildHasher,
+    {
+        if matches!(self, EvictionStrategy::Random(_)) || nkeys == 0 {
+            return vec![];
+        }
+
+        // For both LRU and generational eviction, a higher meta value means a more recent read
+        let mut keys = data
+            .iter()
+            .map(|(k, v)| (v.eviction_meta().value(), k))
+            .collect::<Vec<_>>();
+        if nkeys < keys.len() {
+            keys.select_nth_unstable_by(nkeys, |(v1, _), (v2, _)| v2.cmp(v1));
+            keys.truncate(nkeys);
+        }
+        keys.sort_unstable_by(|(v1, _), (v2, _)| v2.cmp(v1));
+        keys.into_iter().map(|(_, k)| k).collect()
+    }
+
     /// Returns a [`EvictRangeIter`] that iterates over groups of consecutive keys the strategy
     /// would suggest to evict. The first and last element of each group would form a range that
     /// should be evicted.
@@ -256,7 +344,7 @@ impl EvictionStrategy {
 
 impl LRUEviction {
     fn new_meta(&self) -> EvictionMeta {
-        EvictionMeta(AtomicU64::new(self.0.fetch_add(1, Relaxed)).into())
+        EvictionMeta::new(self.0.fetch_add(1, Relaxed))
     }
 
     fn on_read(&self, meta: &EvictionMeta) {
@@ -267,7 +355,7 @@ impl LRUEviction {
         // greater than the currently stored one, so it is possible for it to go
         // backwards, but this sort of accuracy is not our goal here, we prefer to
         // be (maybe) less accurate, but more performant.
-        meta.0.store(current_counter, Relaxed);
+        meta.value.store(current_counter, Relaxed);
     }
 
     fn pick_keys_to_evict<'a, K, V, S>(
@@ -380,13 +468,13 @@ impl RandomEviction {
 
 impl GenerationalEviction {
     fn new_meta(&self) -> EvictionMeta {
-        EvictionMeta(AtomicU64::new(self.0.load(Relaxed)).into())
+        EvictionMeta::new(self.0.load(Relaxed))
     }
 
     fn on_read(&self, meta: &EvictionMeta) {
         // Generational simply assigns the generation counter to the metadata
         let current_counter = self.0.load(Relaxed);
-        meta.0.store(current_counter, Relaxed);
+        meta.value.store(current_counter, Relaxed);
     }
 
     fn pick_keys_to_evict<'a, K, V, S>(
@@ -1,8 +1,9 @@
 use std::borrow::Borrow;
-use std::collections::{hash_map, HashMap};
+use std::collections::{hash_map, HashMap, VecDeque};
 use std::fmt;
 use std::hash::{BuildHasher, Hash};
 use std::ops::{Bound, RangeBounds};
+use std::time::{Duration, Instant};
 
 use itertools::Either;
 use partial_map::PartialMap;
@@ -313,6 +314,12 @@ pub(crate) struct Inner<K, V, M, T, S, I> {
     pub(crate) ready: bool,
     pub(crate) hasher: S,
     pub(crate) eviction_strategy: EvictionStrategy,
+    /// If set, keys are considered expired once they've been in the map for this long
+    pub(crate) ttl: Option<Duration>,
+    /// For maps with a TTL, the keys in the order they were added to the map, along with the time
+    /// they were added. Entries for keys that have since been removed from the map (and maybe
+    /// added again) are left in place, and skipped once they reach the front of the queue.
+    pub(crate) expiry_queue: VecDeque<(Instant, K)>,
     pub(crate) insertion_order: Option<I>,
 }
 
@@ -351,6 +358,8 @@ where
             ready: self.ready,
             hasher: self.hasher.clone(),
             eviction_strategy: self.eviction_strategy.clone(),
+            ttl: self.ttl,
+            expiry_queue: VecDeque::new(),
             insertion_order: self.insertion_order.clone(),
         }
     }
@@ -368,6 +377,7 @@ where
         timestamp: T,
         hasher: S,
         eviction_strategy: EvictionStrategy,
+        ttl: Option<Duration>,
         insertion_order: Option<I>,
     ) -> Self {
         Inner {
@@ -377,6 +387,8 @@ where
             ready: false,
             hasher,
             eviction_strategy,
+            ttl,
+            expiry_queue: VecDeque::new(),
             insertion_order,
         }
     }
@@ -386,14 +398,60 @@ where
         key: K,
         eviction_meta: &mut Option<EvictionMeta>,
     ) -> &mut Values<V> {
+        let queued_key = self.ttl.is_some().then(|| key.clone());
+        let expiry_queue = &mut self.expiry_queue;
         self.data.entry(key).or_insert_with(|| {
-            if let Some(meta) = eviction_meta.take() {
-                Values::new(meta)
+            let meta = if let Some(meta) = eviction_meta.take() {
+                meta
             } else {
-                let meta = self.eviction_strategy.new_meta();
+                let mut meta = self.eviction_strategy.new_meta();
+                if self.ttl.is_some() {
+                    meta = meta.with_created(Instant::now());
+                }
                 eviction_meta.replace(meta.clone());
-                Values::new(meta)
+                meta
+            };
+            if let (Some(key), Some(created)) = (queued_key, meta.created()) {
+                expiry_queue.push_back((created, key));
             }
+            Values::new(meta)
         })
     }
+
+    /// Returns the keys at the front of the expiry queue that were added to the map at or before
+    /// `cutoff`, along with their values if they're still in the map as they were added then.
+    pub(crate) fn expired_keys(
+        &self,
+        cutoff: Instant,
+    ) -> impl Iterator<Item = (&K, Option<&Values<V>>)> + '_ {
+        self.expiry_queue
+            .iter()
+            .take_while(move |(created, _)| *created <= cutoff)
+            .map(|(created, key)| {
+                let values = self
+                    .data
+                    .get(key)
+                    .filter(|values| values.eviction_meta().created() == Some(*created));
+                (key, values)
+            })
+    }
+
+    /// Remove all keys that were added to the map at or before `cutoff` from the map, popping them
+    /// from the front of the expiry queue
+    pub(crate) fn remove_expired(&mut self, cutoff: Instant) {
+        while self
+            .expiry_queue
+            .front()
+            .map_or(false, |(created, _)| *created <= cutoff)
+        {
+            let Some((created, key)) = self.expiry_queue.pop_front() else {
+                break;
+            };
+            if self.data.get(&key).map_or(false, |values| {
+                values.eviction_meta().created() == Some(created)
+            }) {
+                self.data.remove(&key);
+            }
+        }
+    }
 }
@@ -228,6 +228,7 @@
 use std::collections::hash_map::RandomState;
 use std::fmt;
 use std::hash::{BuildHasher, Hash};
+use std::time::Duration;
 
 pub use eviction::EvictionStrategy;
 use partial_map::InsertionOrder;
@@ -275,6 +276,7 @@ pub struct Options<M, T, S, I> {
     index_type: IndexType,
     capacity: Option<usize>,
     eviction_strategy: EvictionStrategy,
+    ttl: Option<Duration>,
     insertion_order: Option<I>,
 }
 
@@ -303,6 +305,7 @@ impl Default for Options<(), (), RandomState, DefaultInsertionOrder> {
             index_type: IndexType::BTreeMap,
             capacity: None,
             eviction_strategy: Default::default(),
+            ttl: None,
             insertion_order: None,
         }
     }
@@ -318,6 +321,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             hasher: self.hasher,
             capacity: self.capacity,
             eviction_strategy: self.eviction_strategy,
+            ttl: self.ttl,
             insertion_order: self.insertion_order,
         }
     }
@@ -331,6 +335,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             hasher: hash_builder,
             capacity: self.capacity,
             eviction_strategy: self.eviction_strategy,
+            ttl: self.ttl,
             insertion_order: self.insertion_order,
         }
     }
@@ -344,6 +349,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             hasher: self.hasher,
             capacity: Some(capacity),
             eviction_strategy: self.eviction_strategy,
+            ttl: self.ttl,
             insertion_order: self.insertion_order,
         }
     }
@@ -357,6 +363,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             hasher: self.hasher,
             capacity: self.capacity,
             eviction_strategy: self.eviction_strategy,
+            ttl: self.ttl,
             insertion_order: self.insertion_order,
         }
     }
@@ -370,6 +377,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             hasher: self.hasher,
             capacity: self.capacity,
             eviction_strategy: self.eviction_strategy,
+            ttl: self.ttl,
             insertion_order,
         }
     }
@@ -386,6 +394,13 @@ impl<M, T, S, I> Options<M, T, S, I> {
         self
     }
 
+    /// Sets a TTL for keys in the map, after which they will be returned by
+    /// [`WriteHandle::evict_expired`], regardless of the eviction strategy.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
     /// Create the map, and construct the read and write handles used to access it.
     #[allow(clippy::type_complexity)]
     pub fn construct<K, V>(self) -> (WriteHandle<K, V, I, M, T, S>, ReadHandle<K, V, I, M, T, S>)
@@ -403,6 +418,7 @@ impl<M, T, S, I> Options<M, T, S, I> {
             self.timestamp,
             self.hasher,
             self.eviction_strategy,
+            self.ttl,
             self.insertion_order,
         );
 
@@ -105,6 +105,16 @@ where
         }
     }
 
+    /// Returns up to `n` of the keys in the map that were read most recently, according to the
+    /// eviction strategy the map was created with, most recent first.
+    ///
+    /// Maps using random eviction don't keep track of reads, so this returns no keys for them.
+    pub fn hottest_keys(&self, n: usize) -> Vec<&K> {
+        self.guard
+            .eviction_strategy
+            .pick_hottest_keys(&self.guard.data, n)
+    }
+
     /// Returns the number of non-empty keys present in the map.
     pub fn len(&self) -> usize {
         self.guard.data.len()
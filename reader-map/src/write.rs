@@ -2,6 +2,7 @@ use std::collections::hash_map::RandomState;
 use std::fmt;
 use std::hash::{BuildHasher, Hash};
 use std::ops::{Bound, RangeBounds};
+use std::time::Instant;
 
 use left_right::Absorb;
 use partial_map::InsertionOrder;
@@ -294,6 +295,93 @@ where
 
         mem_freed
     }
+
+    /// Remove the value-bag for keys in the order the eviction strategy suggests, until at least
+    /// `bytes` bytes have been freed (or the map is empty), for maps that have to stay within a
+    /// memory limit of their own.
+    ///
+    /// Like [`evict_keys`](Self::evict_keys), this method immediately calls
+    /// [`publish`](Self::publish), and the result will only be visible to readers after a
+    /// following call to publish is made. The method returns the amount of memory freed, computed
+    /// using the provided closure on each (K,V) pair.
+    pub fn evict_bytes<'a, F>(&'a mut self, bytes: u64, mut mem_cnt: F) -> u64
+    where
+        F: FnMut(&K, &Values<V>) -> u64,
+    {
+        self.publish();
+
+        let inner = self
+            .r_handle
+            .handle
+            .raw_handle()
+            .expect("WriteHandle has not been dropped");
+        // safety: the writer cannot publish until 'a ends, so we know that reading from the read
+        // map is safe for the duration of 'a.
+        let inner: &'a Inner<K, V, M, T, S, I> =
+            unsafe { std::mem::transmute::<&Inner<K, V, M, T, S, I>, _>(inner.as_ref()) };
+
+        let mut mem_freed = 0;
+        // Removing an entry also marks it as a hole, so this is correct for both index types
+        for (k, v) in inner
+            .eviction_strategy
+            .pick_keys_in_eviction_order(&inner.data)
+        {
+            if mem_freed >= bytes {
+                break;
+            }
+            self.add_op(Operation::RemoveEntry(k.clone()));
+            mem_freed += mem_cnt(k, v);
+        }
+
+        mem_freed
+    }
+
+    /// Remove the value-bag for all keys that have been in the map for longer than the TTL the map
+    /// was constructed with (see [`Options::with_ttl`](crate::Options::with_ttl)). Does nothing if
+    /// the map doesn't have a TTL.
+    ///
+    /// Like [`evict_keys`](Self::evict_keys), this method immediately calls
+    /// [`publish`](Self::publish), and the result will only be visible to readers after a
+    /// following call to publish is made. The method returns the amount of memory freed, computed
+    /// using the provided closure on each (K,V) pair.
+    pub fn evict_expired<'a, F>(&'a mut self, mut mem_cnt: F) -> u64
+    where
+        F: FnMut(&K, &Values<V>) -> u64,
+    {
+        self.publish();
+
+        let inner = self
+            .r_handle
+            .handle
+            .raw_handle()
+            .expect("WriteHandle has not been dropped");
+        // safety: the writer cannot publish until 'a ends, so we know that reading from the read
+        // map is safe for the duration of 'a.
+        let inner: &'a Inner<K, V, M, T, S, I> =
+            unsafe { std::mem::transmute::<&Inner<K, V, M, T, S, I>, _>(inner.as_ref()) };
+
+        let Some(cutoff) = inner.ttl.and_then(|ttl| Instant::now().checked_sub(ttl)) else {
+            return 0;
+        };
+
+        // Keys are queued in the order they were added to the map, so this only looks at the keys
+        // that have actually expired (and any stale queue entries in between)
+        let mut mem_freed = 0;
+        let mut any_expired = false;
+        for (k, v) in inner.expired_keys(cutoff) {
+            any_expired = true;
+            if let Some(v) = v {
+                mem_freed += mem_cnt(k, v);
+            }
+        }
+
+        // Removing an entry also marks it as a hole, so this is correct for both index types
+        if any_expired {
+            self.add_op(Operation::RemoveExpired(cutoff));
+        }
+
+        mem_freed
+    }
 }
 
 impl<K, V, M, T, S, I> Absorb<Operation<K, V, M, T>> for Inner<K, V, M, T, S, I>
@@ -343,7 +431,11 @@ where
             Operation::RemoveEntry(key) => {
                 self.data.remove(key);
             }
-            Operation::Purge => self.data.clear(),
+            Operation::RemoveExpired(cutoff) => self.remove_expired(*cutoff),
+            Operation::Purge => {
+                self.data.clear();
+                self.expiry_queue.clear();
+            }
             Operation::RemoveRange(range) => self.data.remove_range(range.clone()),
             Operation::Retain(key, predicate) => {
                 if let Some(e) = self.data.get_mut(key) {
@@ -410,8 +502,12 @@ where
             Operation::RemoveEntry(key) => {
                 self.data.remove(&key);
             }
+            Operation::RemoveExpired(cutoff) => self.remove_expired(cutoff),
             Operation::RemoveRange(range) => self.data.remove_range(range),
-            Operation::Purge => self.data.clear(),
+            Operation::Purge => {
+                self.data.clear();
+                self.expiry_queue.clear();
+            }
             Operation::Retain(key, mut predicate) => {
                 if let Some(e) = self.data.get_mut(&key) {
                     let mut first = true;
@@ -436,6 +532,7 @@ where
 
     fn sync_with(&mut self, first: &Self) {
         self.data = first.data.clone();
+        self.expiry_queue = first.expiry_queue.clone();
         self.ready = first.ready;
     }
 }
@@ -470,6 +567,9 @@ pub(super) enum Operation<K, V, M, T> {
     RemoveValue(K, V, Option<usize>),
     /// Remove the value set for this key.
     RemoveEntry(K),
+    /// Remove the value set for all keys that were added to the map at or before this time,
+    /// according to the map's expiry queue.
+    RemoveExpired(Instant),
     /// Remove all entries in the given range
     RemoveRange((Bound<K>, Bound<K>)),
     /// Remove all values in the value set for this key.
@@ -504,6 +604,7 @@ where
             }
             Operation::RemoveRange(range) => f.debug_tuple("RemoveRange").field(range).finish(),
             Operation::RemoveEntry(a) => f.debug_tuple("RemoveEntry").field(a).finish(),
+            Operation::RemoveExpired(a) => f.debug_tuple("RemoveExpired").field(a).finish(),
             Operation::Clear(a, _) => f.debug_tuple("Clear").field(a).finish(),
             Operation::Purge => f.debug_tuple("Purge").finish(),
             Operation::Retain(a, b) => f.debug_tuple("Retain").field(a).field(b).finish(),
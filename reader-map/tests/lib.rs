@@ -1,6 +1,7 @@
 use std::collections::hash_map::RandomState;
 use std::hash::Hash;
 use std::ops::Bound;
+use std::time::Duration;
 
 use partial_map::InsertionOrder;
 use reader_map::handles::{ReadHandle, WriteHandle};
@@ -1158,6 +1159,200 @@ fn eviction_random() {
     }
 }
 
+#[test]
+fn eviction_expired() {
+    let (mut w, r) = reader_map::Options::default()
+        .with_index_type(IndexType::BTreeMap)
+        .with_eviction_strategy(reader_map::EvictionStrategy::new_lru())
+        .with_ttl(Some(Duration::from_millis(100)))
+        .construct();
+
+    w.insert_range(1..=3);
+    w.insert(1, "a");
+    w.insert(2, "b");
+    w.publish();
+
+    // Nothing has expired yet
+    assert_eq!(w.evict_expired(|_, _| 1), 0);
+    w.publish();
+    assert!(r.contains_key(&1));
+
+    std::thread::sleep(Duration::from_millis(100));
+    w.insert(3, "c");
+    w.publish();
+
+    // Reads don't extend the lifetime of a key
+    assert!(r.contains_key(&1));
+
+    let mut evicted = Vec::new();
+    let freed = w.evict_expired(|k, _| {
+        evicted.push(*k);
+        1
+    });
+    w.publish();
+
+    assert_eq!(freed, 2);
+    evicted.sort_unstable();
+    assert_eq!(evicted, vec![1, 2]);
+    // Expired keys become holes, rather than being empty
+    {
+        let m = r.enter().unwrap();
+        assert!(!m.contains_key(&1));
+        assert!(!m.contains_key(&2));
+        m.range(&(1..=2)).unwrap_err();
+        assert!(m.get(&3).is_some());
+    }
+}
+
+#[test]
+fn eviction_expired_readded_key() {
+    let (mut w, r) = reader_map::Options::default()
+        .with_ttl(Some(Duration::from_millis(100)))
+        .construct();
+
+    w.insert(1, "a");
+    w.insert(2, "b");
+    w.publish();
+
+    std::thread::sleep(Duration::from_millis(50));
+    // Removing and re-adding a key restarts its TTL
+    w.remove_entry(1);
+    w.publish();
+    w.insert(1, "c");
+    w.publish();
+
+    std::thread::sleep(Duration::from_millis(50));
+    let mut evicted = Vec::new();
+    assert_eq!(
+        w.evict_expired(|k, _| {
+            evicted.push(*k);
+            1
+        }),
+        1
+    );
+    w.publish();
+    assert_eq!(evicted, vec![2]);
+    assert!(r.contains_key(&1));
+    assert!(!r.contains_key(&2));
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(w.evict_expired(|_, _| 1), 1);
+    w.publish();
+    assert!(!r.contains_key(&1));
+}
+
+#[test]
+fn eviction_expired_without_ttl() {
+    let (mut w, r) = reader_map::new();
+    w.insert(1, "a");
+    w.publish();
+
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(w.evict_expired(|_, _| 1), 0);
+    w.publish();
+    assert!(r.contains_key(&1));
+}
+
+#[test]
+fn eviction_bytes_lru() {
+    let (mut w, r) = reader_map::Options::default()
+        .with_eviction_strategy(reader_map::EvictionStrategy::new_lru())
+        .construct();
+
+    for k in 'a'..='e' {
+        w.insert(k, k);
+    }
+    w.publish();
+
+    for k in ['c', 'a', 'e', 'b', 'a'] {
+        assert!(r.get(&k).unwrap().is_some());
+    }
+
+    // Each key counts as 10 bytes, so freeing 15 bytes takes the two least recently read keys
+    let mut evicted = Vec::new();
+    let freed = w.evict_bytes(15, |k, _| {
+        evicted.push(*k);
+        10
+    });
+    w.publish();
+
+    assert_eq!(freed, 20);
+    assert_eq!(evicted, ['d', 'c']);
+    assert!(!r.contains_key(&'c'));
+    assert!(!r.contains_key(&'d'));
+    for k in ['a', 'b', 'e'] {
+        assert!(r.contains_key(&k));
+    }
+
+    // Asking for more than is in the map empties it
+    assert_eq!(w.evict_bytes(100, |_, _| 10), 30);
+    w.publish();
+    assert_eq!(r.len(), 0);
+}
+
+#[test]
+fn eviction_bytes_btree() {
+    let (mut w, r) = reader_map::Options::default()
+        .with_index_type(IndexType::BTreeMap)
+        .with_eviction_strategy(reader_map::EvictionStrategy::new_generational())
+        .construct();
+
+    w.insert_range(1..=4);
+    for k in 1..=4 {
+        w.insert(k, "a");
+    }
+    w.publish();
+
+    let mut evicted = Vec::new();
+    let freed = w.evict_bytes(2, |k, _| {
+        evicted.push(*k);
+        1
+    });
+    w.publish();
+    assert_eq!(freed, 2);
+
+    // The evicted keys become holes again
+    assert_eq!(r.len(), 2);
+    for k in 1..=4 {
+        assert_eq!(
+            r.enter().unwrap().range(&(&k..=&k)).is_err(),
+            evicted.contains(&k)
+        );
+    }
+}
+
+#[test]
+fn hottest_keys_lru() {
+    let (mut w, r) = reader_map::Options::default()
+        .with_eviction_strategy(reader_map::EvictionStrategy::new_lru())
+        .construct();
+
+    for k in 'a'..='e' {
+        w.insert(k, k);
+    }
+    w.publish();
+
+    // Reads are what make a key hot, inserts alone don't count
+    for k in ['c', 'a', 'e', 'a'] {
+        assert!(r.get(&k).unwrap().is_some());
+    }
+
+    let m = r.enter().unwrap();
+    assert_eq!(m.hottest_keys(3), [&'a', &'e', &'c']);
+    assert_eq!(m.hottest_keys(10).len(), 5);
+    assert!(m.hottest_keys(0).is_empty());
+}
+
+#[test]
+fn hottest_keys_random() {
+    let (mut w, r) = reader_map::new();
+    w.insert(1, "a");
+    w.publish();
+
+    assert!(r.get(&1).unwrap().is_some());
+    assert!(r.enter().unwrap().hottest_keys(1).is_empty());
+}
+
 #[test]
 fn eviction_range_lru() -> reader_map::Result<()> {
     let (mut w, r) = reader_map::Options::default()
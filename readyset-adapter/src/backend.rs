@@ -80,7 +80,7 @@ use std::time::{Duration, Instant};
 use futures::future::{self, OptionFuture};
 use mysql_common::row::convert::{FromRow, FromRowError};
 use nom_sql::{
-    CacheInner, CreateCacheStatement, DeleteStatement, Dialect, DropCacheStatement,
+    CacheInner, CacheOptions, CreateCacheStatement, DeleteStatement, Dialect, DropCacheStatement,
     InsertStatement, Relation, SelectStatement, SetStatement, ShowStatement, SqlIdentifier,
     SqlQuery, UpdateStatement, UseStatement,
 };
@@ -1593,6 +1593,7 @@ where
         mut stmt: SelectStatement,
         override_schema_search_path: Option<Vec<SqlIdentifier>>,
         always: bool,
+        options: CacheOptions,
     ) -> ReadySetResult<noria_connector::QueryResult<'static>> {
         // If we have another query with the same name, drop that query first
         if let Some(name) = name {
@@ -1610,7 +1611,13 @@ where
         rewrite::process_query(&mut stmt, self.noria.server_supports_pagination())?;
         let migration_state = match self
             .noria
-            .handle_create_cached_query(name, &stmt, override_schema_search_path, always)
+            .handle_create_cached_query(
+                name,
+                &stmt,
+                override_schema_search_path,
+                always,
+                options,
+            )
             .await
         {
             Ok(()) => MigrationState::Successful,
@@ -1759,6 +1766,7 @@ where
                 name,
                 inner,
                 always,
+                options,
             }) => {
                 let (stmt, search_path) = match inner {
                     Ok(CacheInner::Statement(st)) => (*st.clone(), None),
@@ -1798,7 +1806,7 @@ where
                     trace!("No telemetry sender. not sending metric for CREATE CACHE");
                 }
 
-                self.create_cached_query(name.as_ref(), stmt, search_path, *always)
+                self.create_cached_query(name.as_ref(), stmt, search_path, *always, *options)
                     .await
             }
             SqlQuery::DropCache(DropCacheStatement { name }) => self.drop_cached_query(name).await,
@@ -1818,6 +1826,7 @@ where
             SqlQuery::Show(ShowStatement::ReadySetStatus) => self.noria.readyset_status().await,
             SqlQuery::Show(ShowStatement::ReadySetVersion) => readyset_version(),
             SqlQuery::Show(ShowStatement::ReadySetTables) => self.noria.table_statuses().await,
+            SqlQuery::AlterReadySet(stmt) => self.noria.change_replication_tables(stmt).await,
             SqlQuery::Show(ShowStatement::ProxiedQueries(q_id)) => {
                 // Log a telemetry event
                 if let Some(ref telemetry_sender) = self.telemetry_sender {
@@ -2111,24 +2120,33 @@ where
                             if let Some(timestamp_service) = &mut state.timestamp_client {
                                 let (query_result, identifier) =
                                     upstream.handle_ryw_write(raw_query).await?;
-
-                                // TODO(andrew): Move table name to table index conversion to
-                                // timestamp service https://app.clubhouse.io/readysettech/story/331
-                                let index = noria.node_index_of(t.name.as_str()).await?;
-                                let affected_tables = vec![WriteKey::TableIndex(index)];
-
-                                let new_timestamp = timestamp_service
-                                    .append_write(WriteId::MySqlGtid(identifier), affected_tables)
-                                    .map_err(|e| internal_err!("{e}"))?;
-
-                                // TODO(andrew, justin): solidify error handling in client
-                                // https://app.clubhouse.io/readysettech/story/366
-                                let current_ticket = state.ticket.as_ref().ok_or_else(|| {
-                                    internal_err!("RYW enabled backends must have a current ticket")
-                                })?;
-
-                                state.ticket =
-                                    Some(Timestamp::join(current_ticket, &new_timestamp));
+                                // Writes that didn't change anything leave nothing to wait for
+                                if let Some(identifier) = identifier {
+                                    // TODO(andrew): Move table name to table index conversion to
+                                    // timestamp service https://app.clubhouse.io/readysettech/story/331
+                                    let index = noria.node_index_of(t.name.as_str()).await?;
+                                    let affected_tables = vec![WriteKey::TableIndex(index)];
+
+                                    let write_id = match DB::sql_dialect() {
+                                        Dialect::MySQL => WriteId::MySqlGtid(identifier),
+                                        Dialect::PostgreSQL => WriteId::PostgresLsn(identifier),
+                                    };
+                                    let new_timestamp = timestamp_service
+                                        .append_write(write_id, affected_tables)
+                                        .map_err(|e| internal_err!("{e}"))?;
+
+                                    // TODO(andrew, justin): solidify error handling in client
+                                    // https://app.clubhouse.io/readysettech/story/366
+                                    let current_ticket =
+                                        state.ticket.as_ref().ok_or_else(|| {
+                                            internal_err!(
+                                                "RYW enabled backends must have a current ticket"
+                                            )
+                                        })?;
+
+                                    state.ticket =
+                                        Some(Timestamp::join(current_ticket, &new_timestamp));
+                                }
                                 Ok(query_result)
                             } else {
                                 upstream.query(raw_query).await
@@ -2170,6 +2188,7 @@ where
                     SqlQuery::CreateCache(_)
                     | SqlQuery::DropCache(_)
                     | SqlQuery::DropAllCaches(_)
+                    | SqlQuery::AlterReadySet(_)
                     | SqlQuery::Explain(_) => {
                         unreachable!("path returns prior")
                     }
@@ -2373,6 +2392,11 @@ where
         self.upstream.is_some()
     }
 
+    /// Returns a reference to the connection to the upstream database, if we are using fallback
+    pub fn upstream(&self) -> Option<&DB> {
+        self.upstream.as_ref()
+    }
+
     /// If we are using fallback, this will return the database that was in the original connection
     /// string, if it exists, otherwise it will return None. If we are not using fallback this will
     /// always return None.
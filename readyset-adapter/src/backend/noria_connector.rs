@@ -7,12 +7,14 @@ use std::sync::{atomic, Arc, RwLock};
 use itertools::Itertools;
 use nom_sql::analysis::visit::Visitor;
 use nom_sql::{
-    self, ColumnConstraint, DeleteStatement, Expr, InsertStatement, Literal, Relation,
-    SelectStatement, SqlIdentifier, SqlQuery, UnaryOperator, UpdateStatement,
+    self, AlterReadysetStatement, CacheOptions, ColumnConstraint, DeleteStatement, Expr,
+    InsertStatement, Literal, Relation, SelectStatement, SqlIdentifier, SqlQuery, UnaryOperator,
+    UpdateStatement,
 };
 use readyset_client::consistency::Timestamp;
 use readyset_client::internal::LocalNodeIndex;
 use readyset_client::recipe::changelist::{Change, ChangeList, IntoChanges};
+use readyset_client::replication::ReplicationTablesChange;
 use readyset_client::results::{ResultIterator, Results};
 use readyset_client::{
     ColumnSchema, ReadQuery, ReaderAddress, ReaderHandle, ReadySetHandle, SchemaType, Table,
@@ -21,8 +23,8 @@ use readyset_client::{
 use readyset_data::{DfType, DfValue, Dialect};
 use readyset_errors::ReadySetError::{self, PreparedStatementMissing};
 use readyset_errors::{
-    internal, internal_err, invalid, invariant_eq, table_err, unsupported, unsupported_err,
-    ReadySetResult,
+    internal, internal_err, invalid, invalid_err, invariant_eq, table_err, unsupported,
+    unsupported_err, ReadySetResult,
 };
 use readyset_server::worker::readers::{CallResult, ReadRequestHandler};
 use readyset_sql_passes::anonymize::anonymize_literals;
@@ -1011,6 +1013,57 @@ impl NoriaConnector {
         Ok(QueryResult::from_owned(schema, vec![Results::new(data)]))
     }
 
+    /// Change the set of tables being replicated from the upstream database, according to the
+    /// given `ALTER READYSET` statement.
+    ///
+    /// Tables without a schema are resolved against the first schema in the schema search path.
+    pub(crate) async fn change_replication_tables(
+        &mut self,
+        stmt: &AlterReadysetStatement,
+    ) -> ReadySetResult<QueryResult<'static>> {
+        let qualify = |tables: &[Relation]| -> ReadySetResult<Vec<Relation>> {
+            tables
+                .iter()
+                .map(|table| {
+                    let mut table = table.clone();
+                    if table.schema.is_none() {
+                        table.schema = Some(
+                            self.schema_search_path
+                                .first()
+                                .ok_or_else(|| {
+                                    invalid_err!(
+                                        "No schema specified for table {}, and no default schema \
+                                         is set",
+                                        table.display_unquoted()
+                                    )
+                                })?
+                                .clone(),
+                        );
+                    }
+                    Ok(table)
+                })
+                .collect()
+        };
+
+        let change = match stmt {
+            AlterReadysetStatement::AddTables(tables) => {
+                ReplicationTablesChange::Add(qualify(tables)?)
+            }
+            AlterReadysetStatement::DropTables(tables) => {
+                ReplicationTablesChange::Drop(qualify(tables)?)
+            }
+        };
+
+        noria_await!(
+            self.inner.get_mut()?,
+            self.inner
+                .get_mut()?
+                .noria
+                .change_replication_tables(change)
+        )?;
+        Ok(QueryResult::Empty)
+    }
+
     /// Set the schema search path
     pub fn set_schema_search_path(&mut self, search_path: Vec<SqlIdentifier>) {
         self.schema_search_path = search_path;
@@ -1031,6 +1084,7 @@ impl NoriaConnector {
         statement: &nom_sql::SelectStatement,
         override_schema_search_path: Option<Vec<SqlIdentifier>>,
         always: bool,
+        options: CacheOptions,
     ) -> ReadySetResult<()> {
         let name = name.cloned().unwrap_or_else(|| {
             utils::generate_query_name(statement, self.schema_search_path()).into()
@@ -1038,7 +1092,7 @@ impl NoriaConnector {
         let schema_search_path =
             override_schema_search_path.unwrap_or_else(|| self.schema_search_path.clone());
         let changelist = ChangeList::from_change(
-            Change::create_cache(name.clone(), statement.clone(), always),
+            Change::create_cache_with_options(name.clone(), statement.clone(), always, options),
             self.dialect,
         )
         .with_schema_search_path(schema_search_path.clone());
@@ -1566,7 +1620,12 @@ impl NoriaConnector {
         .await;
 
         if let Err(e) = res.as_ref() {
-            if e.is_networking_related() || e.caused_by_view_destroyed() {
+            // The reader might have been moved to another worker, in which case we need to fetch
+            // the view again to find out where it is now
+            if e.is_networking_related()
+                || e.caused_by_view_destroyed()
+                || e.caused_by_reader_not_found()
+            {
                 self.failed_views.insert(qname.into_owned());
             }
         }
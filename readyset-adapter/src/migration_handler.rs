@@ -10,7 +10,7 @@ use std::time::Instant;
 
 use dataflow_expression::Dialect;
 use metrics::{counter, register_counter, Counter};
-use nom_sql::Literal;
+use nom_sql::{CacheOptions, Literal};
 use readyset_client::query::{MigrationState, Query};
 use readyset_client::recipe::changelist::{Change, ChangeList};
 use readyset_client::{PlaceholderIdx, ReadySetHandle, ViewCreateRequest};
@@ -174,6 +174,7 @@ impl MigrationHandler {
                         &query.query().statement,
                         Some(query.query().schema_search_path.clone()),
                         false,
+                        CacheOptions::default(),
                     )
                     .await;
                 // Inform the query status cache of completed migrations
@@ -296,6 +297,7 @@ impl MigrationHandler {
                 &inlined_query,
                 Some(view_request.schema_search_path.clone()),
                 false,
+                CacheOptions::default(),
             )
             .await
     }
@@ -35,13 +35,25 @@ struct AdapterPaginationParams {
     force_paginate_in_adapter: bool,
 }
 
+/// Returns true if the given limit clause has an `OFFSET` other than a literal 0
+fn has_nonzero_offset(limit_clause: &LimitClause) -> bool {
+    !matches!(
+        limit_clause.offset(),
+        None | Some(Literal::UnsignedInteger(0))
+    )
+}
+
 /// This method checks if readyset-server is configured to handle LIMIT/OFFSET queries at the
 /// dataflow level. If not then LIMIT and OFFSET will be stripped and executed in the
 /// post-processing path.
+///
+/// A parametrized `LIMIT` is handled by readyset-server as long as there is no `OFFSET`, by passing
+/// the value of the `LIMIT` to the reader with each lookup.
 fn use_fallback_pagination(server_supports_pagination: bool, limit_clause: &LimitClause) -> bool {
     if server_supports_pagination &&
-        // Can't handle parameterized LIMIT even if support is enabled
-        !matches!(limit_clause.limit(), Some(Literal::Placeholder(_))) &&
+        // Can't handle OFFSET with a parameterized LIMIT, since the page size must be constant
+        !(matches!(limit_clause.limit(), Some(Literal::Placeholder(_)))
+            && has_nonzero_offset(limit_clause)) &&
         // Can't handle bare OFFSET
         !(limit_clause.limit().is_none() && limit_clause.offset().is_some())
     {
@@ -66,11 +78,11 @@ pub fn process_query(
 ) -> ReadySetResult<ProcessedQueryParams> {
     let reordered_placeholders = reorder_numbered_placeholders(query);
 
-    let limit_clause = mem::take(&mut query.limit_clause);
-
     let force_paginate_in_adapter =
         use_fallback_pagination(server_supports_pagination, &query.limit_clause);
 
+    let limit_clause = mem::take(&mut query.limit_clause);
+
     if !force_paginate_in_adapter {
         // If adapter pagination shouldn't be used reinstate the limit clause
         query.limit_clause.clone_from(&limit_clause);
@@ -156,6 +168,9 @@ impl ProcessedQueryParams {
 
         if *force_paginate_in_adapter || limit == Some(0) {
             Ok((limit, offset))
+        } else if matches!(limit_clause.limit(), Some(Literal::Placeholder(_))) {
+            // A parametrized limit is applied by the reader on each lookup
+            Ok((limit, None))
         } else {
             Ok((None, None))
         }
@@ -181,17 +196,19 @@ impl ProcessedQueryParams {
             force_paginate_in_adapter,
         } = &self.pagination_parameters;
 
-        if *force_paginate_in_adapter {
-            // When fallback pagination is used, remove the parameters for offset and limit from the
-            // list
-            if matches!(limit_clause.offset(), Some(Literal::Placeholder(_))) {
-                // Skip parameter for offset
-                params = &params[..params.len() - 1];
-            }
-            if matches!(limit_clause.limit(), Some(Literal::Placeholder(_))) {
-                // Skip parameter for limit
-                params = &params[..params.len() - 1];
-            }
+        // When fallback pagination is used, remove the parameters for offset and limit from the
+        // list
+        if *force_paginate_in_adapter
+            && matches!(limit_clause.offset(), Some(Literal::Placeholder(_)))
+        {
+            // Skip parameter for offset
+            params = &params[..params.len() - 1];
+        }
+        // A parametrized limit is never part of the lookup key, since it's passed to the reader
+        // separately
+        if matches!(limit_clause.limit(), Some(Literal::Placeholder(_))) {
+            // Skip parameter for limit
+            params = &params[..params.len() - 1];
         }
 
         if params.is_empty() && self.auto_parameters.is_empty() {
@@ -1458,6 +1475,99 @@ mod tests {
             assert_eq!(keys, vec![vec![1.into()]]);
         }
 
+        #[test]
+        fn range_offset_paginates_in_dataflow() {
+            let mut query = parse_select_statement(
+                "SELECT * FROM t WHERE x > ? ORDER BY x ASC LIMIT 3 OFFSET ?",
+            );
+            let processed = process_query(&mut query, true).unwrap();
+            assert_eq!(
+                query,
+                parse_select_statement(
+                    "SELECT * FROM t WHERE x > $1 ORDER BY x ASC LIMIT 3 OFFSET $2"
+                )
+            );
+            assert_eq!(
+                processed
+                    .limit_offset_params(&[1.into(), 6.into()])
+                    .unwrap(),
+                (None, None)
+            );
+        }
+
+        #[test]
+        fn range_limit_paginates_in_dataflow() {
+            let mut query =
+                parse_select_statement("SELECT * FROM t WHERE x > ? ORDER BY x ASC LIMIT 3");
+            let processed = process_query(&mut query, true).unwrap();
+            assert_eq!(
+                query,
+                parse_select_statement("SELECT * FROM t WHERE x > $1 ORDER BY x ASC LIMIT 3")
+            );
+            assert_eq!(
+                processed.limit_offset_params(&[1.into()]).unwrap(),
+                (None, None)
+            );
+        }
+
+        #[test]
+        fn equal_offset_paginates_in_dataflow() {
+            let mut query = parse_select_statement(
+                "SELECT * FROM t WHERE x = ? ORDER BY y ASC LIMIT 3 OFFSET ?",
+            );
+            process_query(&mut query, true).unwrap();
+            assert_eq!(
+                query,
+                parse_select_statement(
+                    "SELECT * FROM t WHERE x = $1 ORDER BY y ASC LIMIT 3 OFFSET $2"
+                )
+            );
+        }
+
+        #[test]
+        fn parametrized_limit_paginates_in_dataflow() {
+            let mut query =
+                parse_select_statement("SELECT * FROM t WHERE x = ? ORDER BY y ASC LIMIT ?");
+            let processed = process_query(&mut query, true).unwrap();
+            assert_eq!(
+                query,
+                parse_select_statement("SELECT * FROM t WHERE x = $1 ORDER BY y ASC LIMIT $2")
+            );
+            assert_eq!(
+                processed
+                    .limit_offset_params(&[1.into(), 10.into()])
+                    .unwrap(),
+                (Some(10), None)
+            );
+            assert_eq!(
+                processed
+                    .make_keys(&[1.into(), 10.into()])
+                    .unwrap()
+                    .into_iter()
+                    .map(|k| k.to_vec())
+                    .collect::<Vec<_>>(),
+                vec![vec![DfValue::from(1)]]
+            );
+        }
+
+        #[test]
+        fn parametrized_limit_with_offset_paginates_in_adapter() {
+            let mut query = parse_select_statement(
+                "SELECT * FROM t WHERE x = ? ORDER BY y ASC LIMIT ? OFFSET ?",
+            );
+            let processed = process_query(&mut query, true).unwrap();
+            assert_eq!(
+                query,
+                parse_select_statement("SELECT * FROM t WHERE x = $1 ORDER BY y ASC")
+            );
+            assert_eq!(
+                processed
+                    .limit_offset_params(&[1.into(), 10.into(), 20.into()])
+                    .unwrap(),
+                (Some(10), Some(20))
+            );
+        }
+
         #[test]
         fn correct_offset_limit() {
             let get_lim_off = |q: &str, p: &[DfValue]| -> (Option<usize>, Option<usize>) {
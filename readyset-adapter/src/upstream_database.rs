@@ -139,12 +139,13 @@ pub trait UpstreamDatabase: Sized + Send {
     async fn query<'a>(&'a mut self, query: &'a str) -> Result<Self::QueryResult<'a>, Self::Error>;
 
     /// Execute a raw, un-prepared write query, constructing and returning a RYW ticket for the
-    /// write
+    /// write, or `None` if the write didn't change anything, in which case there is nothing for
+    /// subsequent reads to wait for
     // TODO: newtype RYW ticket, not just String
     async fn handle_ryw_write<'a, S>(
         &'a mut self,
         query: S,
-    ) -> Result<(Self::QueryResult<'a>, String), Self::Error>
+    ) -> Result<(Self::QueryResult<'a>, Option<String>), Self::Error>
     where
         S: AsRef<str> + Send + Sync + 'a;
 
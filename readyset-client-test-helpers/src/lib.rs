@@ -59,6 +59,7 @@ pub struct TestBuilder {
     backend_builder: BackendBuilder,
     fallback: bool,
     fallback_url: Option<String>,
+    replication_tables: Option<String>,
     partial: bool,
     wait_for_backend: bool,
     read_behavior: ReadBehavior,
@@ -79,6 +80,7 @@ impl TestBuilder {
             backend_builder,
             fallback: false,
             fallback_url: None,
+            replication_tables: None,
             partial: true,
             wait_for_backend: true,
             read_behavior: ReadBehavior::Blocking,
@@ -99,6 +101,11 @@ impl TestBuilder {
         self
     }
 
+    pub fn replication_tables(mut self, replication_tables: &str) -> Self {
+        self.replication_tables = Some(replication_tables.to_owned());
+        self
+    }
+
     pub fn partial(mut self, partial: bool) -> Self {
         self.partial = partial;
         self
@@ -160,6 +167,9 @@ impl TestBuilder {
         if let Some(f) = &fallback_url {
             builder.set_replication_url(f.clone());
         }
+        if let Some(replication_tables) = self.replication_tables {
+            builder.set_replication_tables(replication_tables);
+        }
         let (mut handle, shutdown_tx) = builder.start(authority.clone()).await.unwrap();
         if self.wait_for_backend {
             handle.backend_ready().await;
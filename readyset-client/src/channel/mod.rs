@@ -80,6 +80,11 @@ impl<D, T> DomainConnectionBuilder<D, T> {
         self.sport = Some(sport);
         self
     }
+
+    /// The address of the domain this builder connects to
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
 }
 
 impl<T> DomainConnectionBuilder<Remote, T>
@@ -232,6 +237,45 @@ impl<K: Eq + Hash + Clone, T> ChannelCoordinator<K, T> {
         Ok(())
     }
 
+    /// Move the remote address and local channel (if any) registered for `from` to `to`
+    pub fn rename(&self, from: &K, to: K) -> ReadySetResult<()> {
+        #[allow(clippy::expect_used)]
+        // This can only fail if the mutex is poisoned, in which case we can't recover,
+        // so we allow to panic if that happens.
+        let mut guard = self.inner.write().expect("poisoned mutex");
+        if let Some(addr) = guard.addrs.remove(from) {
+            guard.addrs.insert(to.clone(), addr);
+        }
+        if let Some(chan) = guard.locals.remove(from) {
+            guard.locals.insert(to, chan);
+        }
+        Ok(())
+    }
+
+    /// Forget the local channel (if any) registered for `key`, so that it's only reachable at its
+    /// remote address, and return it
+    pub fn remove_local(
+        &self,
+        key: &K,
+    ) -> ReadySetResult<Option<tokio::sync::mpsc::UnboundedSender<T>>> {
+        #[allow(clippy::expect_used)]
+        // This can only fail if the mutex is poisoned, in which case we can't recover,
+        // so we allow to panic if that happens.
+        let mut guard = self.inner.write().expect("poisoned mutex");
+        Ok(guard.locals.remove(key))
+    }
+
+    /// Forget the remote address and local channel (if any) registered for `key`
+    pub fn remove(&self, key: &K) -> ReadySetResult<()> {
+        #[allow(clippy::expect_used)]
+        // This can only fail if the mutex is poisoned, in which case we can't recover,
+        // so we allow to panic if that happens.
+        let mut guard = self.inner.write().expect("poisoned mutex");
+        guard.addrs.remove(key);
+        guard.locals.remove(key);
+        Ok(())
+    }
+
     pub fn has<Q>(&self, key: &Q) -> bool
     where
         K: Borrow<Q>,
@@ -28,11 +28,14 @@ use crate::debug::stats;
 use crate::metrics::MetricsDump;
 use crate::recipe::changelist::ChangeList;
 use crate::recipe::ExtendRecipeSpec;
-use crate::replication::ReplicationOffsets;
+use crate::replication::{ReplicationOffsets, ReplicationTablesChange};
 use crate::status::ReadySetStatus;
 use crate::table::{Table, TableBuilder, TableRpc};
 use crate::view::{View, ViewBuilder, ViewRpc};
-use crate::{NodeSize, ReplicationOffset, TableStatus, ViewCreateRequest, ViewFilter, ViewRequest};
+use crate::{
+    DomainMove, NodeSize, ReplicationOffset, TableStatus, ViewCreateRequest, ViewFilter,
+    ViewRequest,
+};
 
 mod rpc;
 
@@ -191,6 +194,7 @@ impl Service<ControllerRequest> for Controller {
 /// execute any futures returned from `ReadySetHandle` (that is, you cannot just call `.wait()`
 /// on them).
 pub struct ReadySetHandle {
+    authority: Arc<Authority>,
     handle: Buffer<Controller, ControllerRequest>,
     domains: Arc<Mutex<HashMap<(SocketAddr, usize), TableRpc>>>,
     views: Arc<Mutex<HashMap<(SocketAddr, usize), ViewRpc>>>,
@@ -202,6 +206,7 @@ pub struct ReadySetHandle {
 impl Clone for ReadySetHandle {
     fn clone(&self) -> Self {
         ReadySetHandle {
+            authority: self.authority.clone(),
             handle: self.handle.clone(),
             domains: self.domains.clone(),
             views: self.views.clone(),
@@ -230,6 +235,7 @@ impl ReadySetHandle {
         let mut http_connector = HttpConnector::new();
         http_connector.set_connect_timeout(request_timeout);
         ReadySetHandle {
+            authority: authority.clone(),
             views: Default::default(),
             domains: Default::default(),
             handle: Buffer::new(
@@ -287,6 +293,11 @@ impl ReadySetHandle {
         Self::make(authority.into(), request_timeout, migration_timeout)
     }
 
+    /// Returns the authority this handle uses to locate the controller
+    pub fn authority(&self) -> &Arc<Authority> {
+        &self.authority
+    }
+
     async fn simple_get_request<R>(&mut self, path: &'static str) -> ReadySetResult<R>
     where
         R: DeserializeOwned,
@@ -466,11 +477,39 @@ impl ReadySetHandle {
     {
         let name = name.into();
         async move {
-            self.request_table(ControllerRequest::new(
-                "table_builder",
-                &name,
-                self.request_timeout,
-            )?)
+            self.request_table(
+                ControllerRequest::new("table_builder", &name, self.request_timeout)?,
+                false,
+            )
+            .await?
+            .ok_or_else(|| ReadySetError::TableNotFound {
+                name: name.name.clone().into(),
+                schema: name.schema.clone().map(Into::into),
+            })
+        }
+    }
+
+    /// Obtain a `Table` for the given base table, like [`Self::table`], which also sends all
+    /// writes to the hot standby replicas of the table's domain (if there are any).
+    ///
+    /// This should only be used by the replicator, which is the only source of writes to base
+    /// tables that standbys need to stay up to date with. Standbys that fail to apply a write are
+    /// reported to the controller, which stops them, rather than failing the write.
+    ///
+    /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
+    pub fn table_with_standbys<N>(
+        &mut self,
+        name: N,
+    ) -> impl Future<Output = ReadySetResult<Table>> + '_
+    where
+        N: Into<Relation>,
+    {
+        let name = name.into();
+        async move {
+            self.request_table(
+                ControllerRequest::new("table_builder", &name, self.request_timeout)?,
+                true,
+            )
             .await?
             .ok_or_else(|| ReadySetError::TableNotFound {
                 name: name.name.clone().into(),
@@ -484,11 +523,10 @@ impl ReadySetHandle {
     ///
     /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
     pub async fn table_by_index(&mut self, ni: NodeIndex) -> ReadySetResult<Table> {
-        self.request_table(ControllerRequest::new(
-            "table_builder_by_index",
-            ni,
-            self.request_timeout,
-        )?)
+        self.request_table(
+            ControllerRequest::new("table_builder_by_index", ni, self.request_timeout)?,
+            false,
+        )
         .await?
         .ok_or_else(|| ReadySetError::NoSuchNode(ni.index()))
     }
@@ -496,8 +534,10 @@ impl ReadySetHandle {
     fn request_table(
         &mut self,
         req: ControllerRequest,
+        with_standbys: bool,
     ) -> impl Future<Output = ReadySetResult<Option<Table>>> + '_ {
         let domains = self.domains.clone();
+        let controller = self.clone();
         async move {
             let body: hyper::body::Bytes = self
                 .handle
@@ -511,7 +551,13 @@ impl ReadySetHandle {
             Ok(
                 bincode::deserialize::<ReadySetResult<Option<TableBuilder>>>(&body)?
                     .map_err(|e| rpc_err_no_downcast("ReadySetHandle::table", e))?
-                    .map(|tb| tb.build(domains)),
+                    .map(|tb| {
+                        if with_standbys {
+                            tb.build_with_standbys(domains, controller)
+                        } else {
+                            tb.build(domains)
+                        }
+                    }),
             )
         }
     }
@@ -530,6 +576,14 @@ impl ReadySetHandle {
         self.rpc("flush_partial", (), self.request_timeout)
     }
 
+    /// Move domains between workers to even out the memory and CPU used on each worker, returning
+    /// the moves that were made.
+    ///
+    /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
+    pub fn rebalance(&mut self) -> impl Future<Output = ReadySetResult<Vec<DomainMove>>> + '_ {
+        self.rpc("rebalance", (), self.migration_timeout)
+    }
+
     /// Performs a dry-run migration with the given set of queries.
     ///
     /// `Self::poll_ready` must have returned `Async::Ready` before you call this method.
@@ -695,6 +749,30 @@ impl ReadySetHandle {
         self.rpc("replication_offsets", (), self.request_timeout)
     }
 
+    /// Tell the controller that the replicator failed to write to the hot standby replica of the
+    /// domain of the given base table whose shards run at `addrs`, so that it's stopped rather
+    /// than ever being promoted.
+    pub fn lose_standby_replica(
+        &mut self,
+        table: Relation,
+        addrs: Vec<SocketAddr>,
+    ) -> impl Future<Output = ReadySetResult<()>> + '_ {
+        self.rpc("lose_standby_replica", (table, addrs), self.request_timeout)
+    }
+
+    /// Change the set of tables being replicated from the upstream database, without restarting
+    /// the replicator.
+    ///
+    /// The change is applied asynchronously by the replicator: newly added tables are snapshotted
+    /// in the background, and dropped tables are removed along with any caches that depend on
+    /// them.
+    pub fn change_replication_tables(
+        &mut self,
+        change: ReplicationTablesChange,
+    ) -> impl Future<Output = ReadySetResult<()>> + '_ {
+        self.rpc("change_replication_tables", change, self.request_timeout)
+    }
+
     /// Get a list of all current tables node indexes that are involved in snapshotting.
     pub fn snapshotting_tables(
         &mut self,
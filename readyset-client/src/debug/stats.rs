@@ -11,7 +11,7 @@ type DomainMap = HashMap<ReplicaAddress, (DomainStats, HashMap<NodeIndex, NodeSt
 /// Statistics about a domain.
 ///
 /// All times are in nanoseconds.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainStats {
     /// Total wall-clock time elapsed while processing in this domain.
     pub total_time: u64,
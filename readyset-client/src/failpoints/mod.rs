@@ -16,3 +16,5 @@ pub const UPSTREAM: &str = "upstream";
 pub const REPLICATION_HANDLE_ACTION: &str = "replication-handle-action";
 /// Imitates a failure during `PostgresWalConnector::next_action` in replication
 pub const POSTGRES_REPLICATION_NEXT_ACTION: &str = "postgres-replication-next-action";
+/// Injects an error in `DfState::move_domain` after the new instance of the domain is started
+pub const MOVE_DOMAIN: &str = "move-domain";
@@ -409,6 +409,18 @@ pub struct ReaderAddress {
     pub shard: usize,
 }
 
+/// A domain moved from one worker to another to even out the load on each worker, as returned by
+/// [`ReadySetHandle::rebalance`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainMove {
+    /// The index of the domain that was moved
+    pub domain_index: DomainIndex,
+    /// The worker the domain was moved from
+    pub from: Url,
+    /// The worker the domain was moved to
+    pub to: Url,
+}
+
 /// Use to aggregate various node stats that describe its size
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NodeSize {
@@ -528,6 +540,7 @@ pub fn shard_by(dt: &DfValue, shards: usize) -> usize {
         | DfValue::Numeric(_)
         | DfValue::BitVector(_)
         | DfValue::Array(_)
+        | DfValue::Range(_)
         | DfValue::PassThrough(_) => {
             use std::hash::{Hash, Hasher};
             let mut hasher = ahash::AHasher::new_with_keys(0x3306, 0x6033);
@@ -34,7 +34,7 @@
 use dataflow_expression::Dialect;
 use nom_locate::LocatedSpan;
 use nom_sql::{
-    AlterTableStatement, CacheInner, CreateCacheStatement, CreateTableStatement,
+    AlterTableStatement, CacheInner, CacheOptions, CreateCacheStatement, CreateTableStatement,
     CreateViewStatement, DropTableStatement, DropViewStatement, Relation, SelectStatement,
     SqlIdentifier, SqlQuery,
 };
@@ -187,6 +187,7 @@ impl ChangeList {
                                 name,
                                 inner,
                                 always,
+                                options,
                             }) => {
                                 let statement = match inner {
                                     Ok(CacheInner::Statement(stmt)) => stmt,
@@ -208,6 +209,7 @@ impl ChangeList {
                                     name,
                                     statement,
                                     always,
+                                    options,
                                 })
                             }
                             SqlQuery::AlterTable(ats) => changes.push(Change::AlterTable(ats)),
@@ -325,6 +327,9 @@ pub enum Change {
         /// If set to `true`, execution of this cache will bypass transaction handling in the
         /// adapter
         always: bool,
+        /// Options for the cache, such as the TTL for its entries
+        #[serde(default)]
+        options: CacheOptions,
     },
     /// Alter an existing table in the graph, making changes according to the given `ALTER TABLE`
     /// statement
@@ -371,8 +376,22 @@ pub enum Change {
 
 impl Change {
     /// Creates a new [`Change::CreateCache`] from the given `name` and
-    /// [`SelectStatement`].
+    /// [`SelectStatement`], with the default [`CacheOptions`].
     pub fn create_cache<N>(name: N, statement: SelectStatement, always: bool) -> Self
+    where
+        N: Into<Relation>,
+    {
+        Self::create_cache_with_options(name, statement, always, CacheOptions::default())
+    }
+
+    /// Creates a new [`Change::CreateCache`] from the given `name`, [`SelectStatement`] and
+    /// [`CacheOptions`].
+    pub fn create_cache_with_options<N>(
+        name: N,
+        statement: SelectStatement,
+        always: bool,
+        options: CacheOptions,
+    ) -> Self
     where
         N: Into<Relation>,
     {
@@ -380,6 +399,7 @@ impl Change {
             name: Some(name.into()),
             statement: Box::new(statement),
             always,
+            options,
         }
     }
 
@@ -18,6 +18,16 @@ pub enum ReplicationOffsetState {
     Pending,
 }
 
+/// Prefix of the [`replication_log_name`](ReplicationOffset::replication_log_name) of offsets in a
+/// MySQL replication stream that's positioned using GTIDs rather than binlog files. The prefix is
+/// followed by the set of GTIDs executed before the offset.
+///
+/// Since GTIDs are preserved across the replication topology, all such offsets are considered to
+/// be within the same log regardless of their GTID set, and are ordered by their
+/// [offset](ReplicationOffset::offset), the top 64 bits of which are the number of transactions in
+/// the GTID set.
+pub const GTID_LOG_PREFIX: &str = "gtid:";
+
 /// A data type representing an offset in a replication log
 ///
 /// Replication offsets are represented by a single global [offset](ReplicationOffset::offset),
@@ -39,7 +49,9 @@ pub struct ReplicationOffset {
 
 impl fmt::Display for ReplicationOffset {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.replication_log_name.is_empty() {
+        if let Some(gtid_set) = self.replication_log_name.strip_prefix(GTID_LOG_PREFIX) {
+            write!(f, "gtid[{}]+{}", gtid_set, self.offset as u64)
+        } else if !self.replication_log_name.is_empty() {
             // Wish we could simply convert to BinlogPosition, but including it in the manifest
             // creates a cyclic dependency hell, so duplicate the code here.
             let suffix_len = (self.offset >> 123) as usize;
@@ -61,7 +73,7 @@ impl fmt::Display for ReplicationOffset {
 
 impl PartialOrd for ReplicationOffset {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if other.replication_log_name != self.replication_log_name {
+        if other.log_name() != self.log_name() {
             None
         } else {
             self.offset.partial_cmp(&other.offset)
@@ -70,6 +82,19 @@ impl PartialOrd for ReplicationOffset {
 }
 
 impl ReplicationOffset {
+    /// Returns the name of the replication log that this offset is within. Offsets are only
+    /// comparable with offsets within the same log.
+    ///
+    /// This is the [`replication_log_name`](ReplicationOffset::replication_log_name), except for
+    /// offsets positioned using GTIDs (see [`GTID_LOG_PREFIX`]), which are all within the same log.
+    pub fn log_name(&self) -> &str {
+        if self.replication_log_name.starts_with(GTID_LOG_PREFIX) {
+            GTID_LOG_PREFIX
+        } else {
+            &self.replication_log_name
+        }
+    }
+
     /// Try to mutate `other` to take the maximum of its offset and the offset of
     /// `self`. If `other` is `None`, will assign it to `Some(self.clone)`.
     ///
@@ -77,7 +102,7 @@ impl ReplicationOffset {
     /// [`ReadySetError::ReplicationOffsetLogDifferent`]
     pub fn try_max_into(&self, other: &mut Option<ReplicationOffset>) -> ReadySetResult<()> {
         if let Some(other) = other {
-            if self.replication_log_name != other.replication_log_name {
+            if self.log_name() != other.log_name() {
                 return Err(ReadySetError::ReplicationOffsetLogDifferent(
                     self.replication_log_name.clone(),
                     other.replication_log_name.clone(),
@@ -85,7 +110,7 @@ impl ReplicationOffset {
             }
 
             if self.offset > other.offset {
-                other.offset = self.offset
+                *other = self.clone()
             }
         } else {
             *other = Some(self.clone())
@@ -174,10 +199,8 @@ impl ReplicationOffsets {
     /// all replication offsets, from which streaming replication can successfully continue.
     /// Otherwise, returns `Ok(None)`.
     ///
-    /// If all replication offsets are present but any have mismatched [`replication_log_name`]s,
-    /// returns an error.
-    ///
-    /// [`replication_log_name`]: ReplicationOffset::replication_log_name
+    /// If all replication offsets are present but any are within different replication logs (see
+    /// [`ReplicationOffset::log_name`]), returns an error.
     pub fn max_offset(&self) -> ReadySetResult<Option<&ReplicationOffset>> {
         let mut res = match &self.schema {
             Some(schema_offset) => schema_offset,
@@ -188,7 +211,7 @@ impl ReplicationOffsets {
                 Some(offset) => offset,
                 None => return Ok(None),
             };
-            if res.replication_log_name != offset.replication_log_name {
+            if res.log_name() != offset.log_name() {
                 return Err(ReadySetError::ReplicationOffsetLogDifferent(
                     res.replication_log_name.clone(),
                     offset.replication_log_name.clone(),
@@ -206,15 +229,15 @@ impl ReplicationOffsets {
     /// If no offset is present _at all_, returns [`None`] (but note that unlike [`max_offset`][]
     /// this function does *not* return [`None`] if an offset is absent).
     ///
-    /// If any offsets have a different [`replication_log_name`], returns an error.
+    /// If any offsets are within a different replication log (see [`ReplicationOffset::log_name`]),
+    /// returns an error.
     ///
     /// [`max_offset`]: Self::max_offset
-    /// [`replication_log_name`]: ReplicationOffset::replication_log_name
     pub fn min_present_offset(&self) -> ReadySetResult<Option<&ReplicationOffset>> {
         let mut res: Option<&ReplicationOffset> = None;
         for offset in self.schema.iter().chain(self.tables.values().flatten()) {
             match (res, offset) {
-                (Some(off1), off2) if off1.replication_log_name != off2.replication_log_name => {
+                (Some(off1), off2) if off1.log_name() != off2.log_name() => {
                     return Err(ReadySetError::ReplicationOffsetLogDifferent(
                         off1.replication_log_name.clone(),
                         off2.replication_log_name.clone(),
@@ -242,6 +265,21 @@ impl ReplicationOffsets {
     }
 }
 
+/// A change to the set of tables being replicated from the upstream database, made at runtime
+/// via `ALTER READYSET ADD TABLES` or `ALTER READYSET DROP TABLES`.
+///
+/// Tables are identified by (potentially glob-patterned) relations, in the same format as the
+/// `--replication-tables` option.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ReplicationTablesChange {
+    /// Begin replicating all tables matching any of the given patterns, snapshotting them if they
+    /// aren't already being replicated
+    Add(Vec<Relation>),
+    /// Stop replicating all tables matching any of the given patterns, dropping them along with
+    /// any caches that depend on them
+    Drop(Vec<Relation>),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,5 +390,52 @@ mod tests {
             let res = offsets.max_offset().unwrap();
             assert!(res.is_none());
         }
+
+        #[test]
+        fn gtid_sets_not_matching() {
+            let offsets = ReplicationOffsets {
+                schema: Some(ReplicationOffset {
+                    offset: 5 << 64,
+                    replication_log_name: format!(
+                        "{GTID_LOG_PREFIX}3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5"
+                    ),
+                }),
+                tables: HashMap::from([(
+                    "t1".into(),
+                    Some(ReplicationOffset {
+                        offset: (7 << 64) + 2,
+                        replication_log_name: format!(
+                            "{GTID_LOG_PREFIX}3e11fa47-71ca-11e1-9e33-c80aa9429562:1-7"
+                        ),
+                    }),
+                )]),
+            };
+            let res = offsets.max_offset().unwrap().unwrap();
+            assert_eq!(
+                res.replication_log_name,
+                format!("{GTID_LOG_PREFIX}3e11fa47-71ca-11e1-9e33-c80aa9429562:1-7")
+            );
+
+            let res = offsets.min_present_offset().unwrap().unwrap();
+            assert_eq!(res.offset, 5 << 64);
+        }
+    }
+
+    #[test]
+    fn try_max_into_gtid() {
+        let offset = ReplicationOffset {
+            offset: 2 << 64,
+            replication_log_name: format!(
+                "{GTID_LOG_PREFIX}3e11fa47-71ca-11e1-9e33-c80aa9429562:1-2"
+            ),
+        };
+        let mut other = Some(ReplicationOffset {
+            offset: 1 << 64,
+            replication_log_name: format!(
+                "{GTID_LOG_PREFIX}3e11fa47-71ca-11e1-9e33-c80aa9429562:1"
+            ),
+        });
+        offset.try_max_into(&mut other).unwrap();
+        assert_eq!(other, Some(offset));
     }
 }
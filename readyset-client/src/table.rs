@@ -1,18 +1,18 @@
 use core::convert::TryInto;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant, SystemTime};
-use std::{fmt, iter};
+use std::{fmt, iter, mem};
 
 use async_bincode::{AsyncBincodeStream, AsyncDestination};
 use derive_more::TryInto;
 use futures_util::future::TryFutureExt;
 use futures_util::stream::futures_unordered::FuturesUnordered;
-use futures_util::stream::TryStreamExt;
+use futures_util::stream::{StreamExt, TryStreamExt};
 use futures_util::{future, ready};
 use itertools::Either;
 use nom_sql::{CreateTableBody, Relation, SqlIdentifier};
@@ -28,13 +28,13 @@ use tower::balance::p2c::Balance;
 use tower::buffer::Buffer;
 use tower::limit::concurrency::ConcurrencyLimit;
 use tower_service::Service;
-use tracing::{debug_span, error, trace, trace_span, Span};
+use tracing::{debug_span, error, trace, trace_span, warn, Span};
 use vec_map::VecMap;
 
 use crate::channel::CONNECTION_FROM_BASE;
 use crate::internal::*;
 use crate::replication::ReplicationOffset;
-use crate::{consistency, Tagged, Tagger};
+use crate::{consistency, ReadySetHandle, Tagged, Tagger};
 
 // TODO(justin): Make write propagation sample rate configurable.
 const TRACE_SAMPLE_RATE: Duration = Duration::from_secs(1);
@@ -302,6 +302,13 @@ pub struct TableStatus {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TableBuilder {
     pub txs: Vec<SocketAddr>,
+    /// For each hot standby replica of the table's domain, the index of that replica and the
+    /// address of each of its shards.
+    ///
+    /// These are only used by tables obtained with [`ReadySetHandle::table_with_standbys`], which
+    /// send all writes to every standby replica as well as the primary.
+    #[serde(default)]
+    pub standby_txs: Vec<(usize, Vec<SocketAddr>)>,
     pub ni: NodeIndex,
     pub addr: LocalNodeIndex,
     pub key_is_primary: bool,
@@ -317,6 +324,36 @@ pub struct TableBuilder {
 }
 
 impl TableBuilder {
+    /// Build a [`Table`] which writes to the hot standby replicas of the table's domain as well as
+    /// the primary, telling the controller about any standbys that fail using `controller`.
+    pub(crate) fn build_with_standbys(
+        mut self,
+        rpcs: Arc<Mutex<HashMap<(SocketAddr, usize), TableRpc>>>,
+        controller: ReadySetHandle,
+    ) -> Table {
+        let replicas = mem::take(&mut self.standby_txs)
+            .into_iter()
+            .map(|(replica, txs)| {
+                let table = TableBuilder {
+                    txs,
+                    ..self.clone()
+                }
+                .build(rpcs.clone());
+                (replica, table)
+            })
+            .collect::<Vec<_>>();
+
+        let mut table = self.build(rpcs);
+        if !replicas.is_empty() {
+            table.standbys = Some(Box::new(Standbys {
+                replicas,
+                lost: Default::default(),
+                controller,
+            }));
+        }
+        table
+    }
+
     pub(crate) fn build(self, rpcs: Arc<Mutex<HashMap<(SocketAddr, usize), TableRpc>>>) -> Table {
         let mut addrs = Vec::with_capacity(self.txs.len());
         let mut conns = Vec::with_capacity(self.txs.len());
@@ -367,6 +404,7 @@ impl TableBuilder {
             schema: self.schema,
             shard_addrs: addrs,
             shards: conns,
+            standbys: None,
             last_trace_sample: Instant::now(),
             request_timeout: self.table_request_timeout,
         }
@@ -392,6 +430,9 @@ pub struct Table {
     schema: Option<CreateTableBody>,
     shards: Vec<TableRpc>,
     shard_addrs: Vec<SocketAddr>,
+    /// The hot standby replicas of this table, if it was obtained with
+    /// [`ReadySetHandle::table_with_standbys`]
+    standbys: Option<Box<Standbys>>,
     last_trace_sample: Instant,
     request_timeout: Duration,
 }
@@ -408,10 +449,90 @@ impl fmt::Debug for Table {
             .field("table_name", &self.table_name)
             .field("schema", &self.schema)
             .field("shard_addrs", &self.shard_addrs)
+            .field(
+                "standbys",
+                &self
+                    .standbys
+                    .iter()
+                    .flat_map(|standbys| standbys.replicas.iter().map(|(replica, _)| replica))
+                    .collect::<Vec<_>>(),
+            )
             .finish()
     }
 }
 
+/// The hot standby replicas of a [`Table`], which are sent all the same requests as the table
+/// itself so that they can be promoted if the worker running the table's domain fails
+#[derive(Clone)]
+struct Standbys {
+    /// The index of each standby replica, along with a handle to that replica
+    replicas: Vec<(usize, Table)>,
+    /// Indices of standby replicas that have failed to handle a request, and so are no longer
+    /// sent any requests
+    lost: Arc<Mutex<HashSet<usize>>>,
+    /// Used to tell the controller about standby replicas that have failed
+    controller: ReadySetHandle,
+}
+
+impl Standbys {
+    /// Send `req` to all the standby replicas that haven't failed yet.
+    ///
+    /// A standby replica failing to handle the request doesn't fail the request: instead the
+    /// standby is treated as lost, which means it's no longer sent any requests, and the controller
+    /// is told to stop it so that it's never promoted.
+    fn call(&mut self, table: &Relation, req: TableRequest) -> impl Future<Output = ()> + Send {
+        {
+            #[allow(clippy::unwrap_used)] // Only fails if the mutex is poisoned
+            let lost = self.lost.lock().unwrap();
+            self.replicas.retain(|(replica, _)| !lost.contains(replica));
+        }
+
+        let requests = self
+            .replicas
+            .iter()
+            .map(|(replica, standby)| {
+                let replica = *replica;
+                let mut standby = standby.clone();
+                let req = req.clone();
+                async move {
+                    let res = match future::poll_fn(|cx| standby.poll_ready(cx)).await {
+                        Ok(()) => standby.call_primary(req).await,
+                        Err(e) => Err(e),
+                    };
+                    (replica, standby.shard_addrs, res)
+                }
+            })
+            .collect::<FuturesUnordered<_>>();
+        let lost = self.lost.clone();
+        let mut controller = self.controller.clone();
+        let table = table.clone();
+
+        async move {
+            for (replica, addrs, res) in requests.collect::<Vec<_>>().await {
+                let Err(error) = res else {
+                    continue;
+                };
+                warn!(
+                    %error,
+                    table = %table.display_unquoted(),
+                    replica,
+                    "Hot standby replica failed to handle request, it will no longer be used"
+                );
+                #[allow(clippy::unwrap_used)] // Only fails if the mutex is poisoned
+                lost.lock().unwrap().insert(replica);
+                if let Err(error) = controller.lose_standby_replica(table.clone(), addrs).await {
+                    error!(
+                        %error,
+                        table = %table.display_unquoted(),
+                        replica,
+                        "Could not tell the controller about lost hot standby replica"
+                    );
+                }
+            }
+        }
+    }
+}
+
 impl Table {
     #[allow(clippy::cognitive_complexity)]
     fn input(
@@ -637,6 +758,7 @@ impl Table {
 }
 
 /// A request to the table service.
+#[derive(Clone)]
 pub enum TableRequest {
     /// A set of operations to apply on the table.
     TableOperations(Vec<TableOperation>),
@@ -659,6 +781,30 @@ impl Service<TableRequest> for Table {
     }
 
     fn call(&mut self, req: TableRequest) -> Self::Future {
+        // Send the request to all the hot standby replicas of the table as well as the primary, so
+        // that the standbys stay up to date. Only the primary's response matters for the request -
+        // standbys that fail are dropped rather than failing the request.
+        let standbys = self
+            .standbys
+            .as_mut()
+            .map(|standbys| standbys.call(&self.table_name, req.clone()));
+        let primary = self.call_primary(req);
+        async move {
+            let res = primary.await;
+            if let Some(standbys) = standbys {
+                standbys.await;
+            }
+            res
+        }
+    }
+}
+
+impl Table {
+    /// Send a request to the primary replica of this table, excluding any standbys
+    fn call_primary(
+        &mut self,
+        req: TableRequest,
+    ) -> impl Future<Output = Result<Tagged<()>, ReadySetError>> + Send {
         // TODO(eta): error handling impl adds overhead
         let table = self.table_name.clone();
         match req {
@@ -678,9 +824,7 @@ impl Service<TableRequest> for Table {
             }
         }
     }
-}
 
-impl Table {
     /// Get the name of this base table.
     pub fn table_name(&self) -> &Relation {
         &self.table_name
@@ -1530,6 +1530,8 @@ impl ReaderHandle {
         trace!("select::lookup");
         let bogo = vec![vec1![DfValue::from(0i32)].into()];
         let mut filters = Vec::new();
+        // The number of rows to skip when looking up a page of a range query
+        let mut page_offset = None;
 
         let keys = if raw_keys.is_empty() {
             bogo
@@ -1551,6 +1553,12 @@ impl ReaderHandle {
                 });
             // The binary operator we will use to build our key if we do not have a mixed comparison
             let binop_to_use = current_binop.unwrap_or(BinaryOperator::Equal);
+            // Whether we're looking up a page of a range query, in which case the page number is
+            // part of the range as well
+            let range_pagination = (mixed_binops || binop_to_use != BinaryOperator::Equal)
+                && self.key_map().iter().any(|(placeholder, _)| {
+                    matches!(placeholder, ViewPlaceholder::PageNumber { .. })
+                });
 
             let key_types: HashMap<usize, &DfType> = self
                 .key_map()
@@ -1596,11 +1604,12 @@ impl ReaderHandle {
                 .into_iter()
                 .map(|key| {
                     let mut k = vec![];
-                    let mut bounds: Option<(Vec<DfValue>, Vec<DfValue>)> = if mixed_binops {
-                        Some((vec![], vec![]))
-                    } else {
-                        None
-                    };
+                    let mut bounds: Option<(Vec<DfValue>, Vec<DfValue>)> =
+                        if mixed_binops || range_pagination {
+                            Some((vec![], vec![]))
+                        } else {
+                            None
+                        };
                     // All ViewPlaceholder indices must be remapped using key_remap
                     for (view_placeholder, key_column_idx) in self.key_map() {
                         match view_placeholder {
@@ -1723,13 +1732,25 @@ impl ReaderHandle {
                                 let offset: u64 =
                                     remap_key(key.as_ref(), offset_placeholder, &DfType::BigInt)?
                                         .try_into()?;
-                                if offset % *limit != 0 {
-                                    unsupported!(
-                                        "OFFSET must currently be an integer multiple of LIMIT"
-                                    );
+                                if let Some((lower_bound, upper_bound)) = &mut bounds {
+                                    // Every key in the range is paginated separately, and a row's
+                                    // position within its key is never greater than its position
+                                    // in the whole result set. So we look up all the pages of
+                                    // each key up to the last one that could contain a row of the
+                                    // requested page, and skip `offset` rows of the merged
+                                    // results.
+                                    lower_bound.push(0u64.into());
+                                    upper_bound.push(((offset + *limit - 1) / *limit).into());
+                                    page_offset = Some(offset as usize);
+                                } else {
+                                    if offset % *limit != 0 {
+                                        unsupported!(
+                                            "OFFSET must currently be an integer multiple of LIMIT"
+                                        );
+                                    }
+                                    let page_number = offset / *limit;
+                                    k.push(page_number.into());
                                 }
-                                let page_number = offset / *limit;
-                                k.push(page_number.into());
                             }
                         };
                     }
@@ -1759,7 +1780,7 @@ impl ReaderHandle {
                 ty: DfType::Bool, // AND is a boolean operator
             }),
             limit,
-            offset,
+            offset: offset.or(page_offset),
             timestamp: ticket,
         }))
     }
@@ -2386,5 +2407,52 @@ mod tests {
                 .into()]
             );
         }
+
+        #[test]
+        fn paginated_with_range_key() {
+            // "SELECT t.x FROM t WHERE t.x > $1 ORDER BY t.y ASC LIMIT 3 OFFSET $2"
+            let query = make_build_query(
+                vec![Cow::Owned(vec![DfValue::from(1), DfValue::from(4)])],
+                None,
+                None,
+                &[
+                    (ViewPlaceholder::OneToOne(1, BinaryOperator::Greater), 0),
+                    (
+                        ViewPlaceholder::PageNumber {
+                            offset_placeholder: 2,
+                            limit: 3,
+                        },
+                        1,
+                    ),
+                ],
+                Dialect::MySQL,
+            );
+
+            assert_eq!(
+                query.filter,
+                Some(DfExpr::Op {
+                    left: Box::new(DfExpr::Column {
+                        index: 0,
+                        ty: DfType::Int
+                    }),
+                    op: DfBinaryOperator::Greater,
+                    right: Box::new(DfExpr::Literal {
+                        val: 1.into(),
+                        ty: DfType::Int
+                    }),
+                    ty: DfType::Bool
+                })
+            );
+
+            // Rows 4 through 6 can be anywhere in the first 3 pages of each key
+            assert_eq!(
+                query.key_comparisons,
+                vec![KeyComparison::Range((
+                    Bound::Included(vec1![DfValue::from(1), DfValue::from(0u64)]),
+                    Bound::Included(vec1![DfValue::Max, DfValue::from(2u64)])
+                ))]
+            );
+            assert_eq!(query.offset, Some(4));
+        }
     }
 }
@@ -245,7 +245,9 @@ impl ResultIterator {
                         aggregate: aggregates.clone(),
                         filter: filter.take(),
                     }),
-                    limit,
+                    // The limit and offset can only be applied once the aggregated rows are
+                    // sorted
+                    limit: None,
                     offset: None,
                     default_row: default_row.clone(),
                     non_empty: false,
@@ -262,11 +264,10 @@ impl ResultIterator {
                 });
 
                 if let Some(offset) = offset {
-                    if offset >= results.len() {
-                        results.clear();
-                    } else {
-                        results.drain(offset..);
-                    }
+                    results.drain(..offset.min(results.len()));
+                }
+                if let Some(limit) = limit {
+                    results.truncate(limit);
                 }
 
                 return ResultIterator::owned(vec![Results {
@@ -500,6 +501,7 @@ impl StreamingIterator for AggregateIterator {
         };
 
         self.advance_filtered();
+        let mut merged = false;
         while let Some(row) = self.inner.get() {
             if self
                 .aggregate
@@ -517,10 +519,19 @@ impl StreamingIterator for AggregateIterator {
                     .apply(&aggregate_row[col], &row[col])
                     .expect("no fail");
             }
+            merged = true;
 
             self.advance_filtered();
         }
 
+        if merged {
+            for agg in &self.aggregate.aggregates {
+                if let Some(val) = agg.function.finish(&aggregate_row).expect("no fail") {
+                    aggregate_row[agg.column] = val;
+                }
+            }
+        }
+
         self.out_row = Some(aggregate_row)
     }
 
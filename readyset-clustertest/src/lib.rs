@@ -367,6 +367,8 @@ pub struct ServerStartParams {
     replicator_restart_timeout_secs: Option<u64>,
     /// Number of times to replicate reader domains.
     reader_replicas: Option<usize>,
+    /// Number of times to replicate all domains, including base table domains as hot standbys.
+    all_replicas: Option<usize>,
     /// Whether or not to auto restart the server process.
     auto_restart: bool,
     /// Whether the server should wait to receive a failpoint request before proceeding with the
@@ -429,6 +431,8 @@ pub struct DeploymentBuilder {
     replicator_restart_timeout_secs: Option<u64>,
     /// Number of times to replicate reader domains
     reader_replicas: Option<usize>,
+    /// Number of times to replicate all domains, including base table domains as hot standbys
+    all_replicas: Option<usize>,
     /// If true, will automatically restart the server/adapter processes
     auto_restart: bool,
     /// Sets whether the adapter, server, both, or neither should wait to receive a failpoint
@@ -514,6 +518,7 @@ impl DeploymentBuilder {
             pass: Some(pass),
             replicator_restart_timeout_secs: None,
             reader_replicas: None,
+            all_replicas: None,
             auto_restart: false,
             wait_for_failpoint: FailpointDestination::None,
             database_type,
@@ -644,6 +649,13 @@ impl DeploymentBuilder {
         self
     }
 
+    /// Sets the number of times to replicate all domains. Replicas of base table domains beyond
+    /// the first are run as hot standbys.
+    pub fn all_replicas(mut self, replicas: usize) -> Self {
+        self.all_replicas = Some(replicas);
+        self
+    }
+
     /// Sets whether or not to restart the adapter/server processes
     pub fn auto_restart(mut self, auto_restart: bool) -> Self {
         self.auto_restart = auto_restart;
@@ -714,6 +726,7 @@ impl DeploymentBuilder {
             authority_type: self.authority.to_string(),
             replicator_restart_timeout_secs: self.replicator_restart_timeout_secs,
             reader_replicas: self.reader_replicas,
+            all_replicas: self.all_replicas,
             auto_restart: self.auto_restart,
             wait_for_failpoint,
         }
@@ -1544,6 +1557,9 @@ async fn start_server(
     if let Some(rs) = server_start_params.reader_replicas {
         builder = builder.reader_replicas(rs);
     }
+    if let Some(rs) = server_start_params.all_replicas {
+        builder = builder.all_replicas(rs);
+    }
     if server_start_params.wait_for_failpoint {
         builder = builder.wait_for_failpoint();
     }
@@ -1610,6 +1626,9 @@ async fn start_adapter(
         if let Some(rs) = server_start_params.reader_replicas {
             builder = builder.reader_replicas(rs);
         }
+        if let Some(rs) = server_start_params.all_replicas {
+            builder = builder.all_replicas(rs);
+        }
     }
 
     if let Some(interval) = params.async_migration_interval {
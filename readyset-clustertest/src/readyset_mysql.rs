@@ -934,6 +934,137 @@ async fn update_during_failure() {
     deployment.teardown().await.unwrap();
 }
 
+/// Kill the worker running the base table domain of a table that has a hot standby, and check
+/// that the standby is promoted with all the writes made before and during the failure, without
+/// losing or duplicating any of them downstream.
+#[clustertest]
+async fn hot_standby_promoted_on_worker_failure() {
+    let mut deployment = readyset_mysql("ct_hot_standby_promoted_on_worker_failure")
+        .quorum(2)
+        .add_server(ServerParams::default())
+        .add_server(ServerParams::default())
+        .all_replicas(2)
+        .start()
+        .await
+        .unwrap();
+
+    let mut upstream = deployment.upstream().await;
+    // No primary key, so that any writes duplicated on the promoted standby show up as duplicate
+    // rows
+    upstream
+        .query_drop(
+            r"CREATE TABLE t1 (
+                uid INT NOT NULL,
+                value INT NOT NULL
+            );
+            INSERT INTO t1 VALUES (1,1);
+        ",
+        )
+        .await
+        .unwrap();
+
+    let mut adapter = deployment.first_adapter().await;
+    adapter
+        .query_drop(r"CREATE CACHE FROM SELECT * FROM t1 WHERE uid = ?")
+        .await
+        .unwrap();
+
+    let mut before_failure = EventuallyConsistentResults::new();
+    before_failure.write(&[(1, 1)]);
+    assert!(
+        query_until_expected_from_noria(
+            &mut adapter,
+            deployment.metrics(),
+            QueryExecution::PrepareExecute("SELECT * FROM t1 WHERE uid = ?", (1,)),
+            &before_failure,
+            PROPAGATION_DELAY_TIMEOUT,
+        )
+        .await
+    );
+
+    let (_, t1) = deployment
+        .leader_handle()
+        .tables()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|(name, _)| name.name == "t1")
+        .unwrap();
+    let base_replica_workers = deployment
+        .leader_handle()
+        .get_info()
+        .await
+        .unwrap()
+        .workers
+        .into_iter()
+        .filter_map(|(worker, domains)| {
+            domains
+                .into_iter()
+                .find(|(_, nodes)| nodes.contains(&t1))
+                .map(|(replica_address, _)| (replica_address.replica, worker))
+        })
+        .collect::<HashMap<_, _>>();
+    assert_eq!(base_replica_workers.len(), 2);
+    let primary = base_replica_workers[&0].clone();
+    let standby = base_replica_workers[&1].clone();
+
+    deployment.kill_server(&primary, false).await.unwrap();
+    upstream
+        .query_drop("INSERT INTO t1 VALUES (2,2);")
+        .await
+        .unwrap();
+
+    // The standby becomes the only (and so the first) replica of the base table domain
+    eventually! {
+        deployment
+            .leader_handle()
+            .get_info()
+            .await
+            .ok()
+            .and_then(|info| info.get(&standby).cloned())
+            .map_or(false, |domains| {
+                domains.iter().any(|(replica_address, nodes)| {
+                    replica_address.replica == 0 && nodes.contains(&t1)
+                })
+            })
+    }
+
+    let mut after_failure = EventuallyConsistentResults::new();
+    after_failure.write(&[(2, 2)]);
+    for (key, results) in [(1, &before_failure), (2, &after_failure)] {
+        assert!(
+            query_until_expected_from_noria(
+                &mut adapter,
+                deployment.metrics(),
+                QueryExecution::PrepareExecute("SELECT * FROM t1 WHERE uid = ?", (key,)),
+                results,
+                PROPAGATION_DELAY_TIMEOUT,
+            )
+            .await
+        );
+    }
+
+    // The promoted standby keeps receiving writes from the replicator
+    upstream
+        .query_drop("INSERT INTO t1 VALUES (3,3);")
+        .await
+        .unwrap();
+    let mut after_promotion = EventuallyConsistentResults::new();
+    after_promotion.write(&[(3, 3)]);
+    assert!(
+        query_until_expected_from_noria(
+            &mut adapter,
+            deployment.metrics(),
+            QueryExecution::PrepareExecute("SELECT * FROM t1 WHERE uid = ?", (3,)),
+            &after_promotion,
+            PROPAGATION_DELAY_TIMEOUT,
+        )
+        .await
+    );
+
+    deployment.teardown().await.unwrap();
+}
+
 #[clustertest]
 async fn upquery_to_failed_reader_domain() {
     let mut deployment = readyset_mysql("ct_upquery_failed_domain_immediately")
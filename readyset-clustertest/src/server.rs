@@ -181,6 +181,10 @@ impl ReadysetServerBuilder {
         self.push_arg_kv("--reader-replicas", &num_replicas.to_string())
     }
 
+    pub fn all_replicas(self, num_replicas: usize) -> Self {
+        self.push_arg_kv("--all-replicas", &num_replicas.to_string())
+    }
+
     pub fn auto_restart(mut self, auto_restart: bool) -> Self {
         self.auto_restart = auto_restart;
         self
@@ -334,6 +338,10 @@ impl AdapterBuilder {
         self.push_arg_kv("--reader-replicas", &num_replicas.to_string())
     }
 
+    pub fn all_replicas(self, num_replicas: usize) -> Self {
+        self.push_arg_kv("--all-replicas", &num_replicas.to_string())
+    }
+
     pub fn enable_experimental_placeholder_inlining(self) -> Self {
         self.push_arg("--experimental-placeholder-inlining")
     }
@@ -1,4 +1,4 @@
-//! This binary generates a "reference" row of `DfValue`s serialized using bincode to a file, for
+//! This binary generates "reference" rows of `DfValue`s serialized using bincode to files, for
 //! use in testing backwards compatibility of `DfValue`'s `serde::Serialize` impl.
 //!
 //! See `tests::serialize_backwards_compatibility` in `src/serde.rs` for more information.
@@ -12,12 +12,12 @@ use std::path::PathBuf;
 
 use readyset_data::DfValue;
 
-fn main() -> anyhow::Result<()> {
+fn write_row(file_name: &str, row: &[DfValue]) -> anyhow::Result<()> {
     let out_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
-        .join("serialized-row.bincode");
+        .join(file_name);
 
-    let serialized = bincode::serialize(&DfValue::example_row())?;
+    let serialized = bincode::serialize(row)?;
 
     eprintln!("Writing serialized row to {}", out_path.display());
     OpenOptions::new()
@@ -29,3 +29,11 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn main() -> anyhow::Result<()> {
+    write_row("serialized-row.bincode", &DfValue::example_row())?;
+    write_row(
+        "serialized-range-row.bincode",
+        &DfValue::example_range_row(),
+    )
+}
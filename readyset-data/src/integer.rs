@@ -184,7 +184,8 @@ where
         | DfType::Uuid
         | DfType::Bit(_)
         | DfType::VarBit(_)
-        | DfType::Array(_) => Err(ReadySetError::DfValueConversionError {
+        | DfType::Array(_)
+        | DfType::Range(_) => Err(ReadySetError::DfValueConversionError {
             src_type: from_ty.to_string(),
             target_type: to_ty.to_string(),
             details: "Not allowed".to_string(),
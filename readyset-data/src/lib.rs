@@ -36,6 +36,7 @@ pub mod dialect;
 mod r#enum;
 mod float;
 mod integer;
+mod range;
 mod serde;
 mod text;
 mod timestamp;
@@ -47,6 +48,7 @@ pub use crate::array::Array;
 pub use crate::collation::Collation;
 pub use crate::dialect::Dialect;
 pub use crate::r#type::{DfType, PgEnumMetadata, PgTypeCategory};
+pub use crate::range::{Range, RangeBound};
 pub use crate::text::{Text, TinyText};
 pub use crate::timestamp::{TimestampTz, TIMESTAMP_FORMAT, TIMESTAMP_PARSE_FORMAT};
 
@@ -108,6 +110,8 @@ pub enum DfValue {
     BitVector(Arc<BitVec>),
     /// An array of [`DfValue`]s.
     Array(Arc<Array>),
+    /// A range of [`DfValue`]s.
+    Range(Arc<Range>),
     /// Container type for arbitrary unserialized, unsupported types
     PassThrough(Arc<PassThrough>),
     /// A sentinel maximal value.
@@ -158,6 +162,7 @@ impl fmt::Display for DfValue {
                 )
             }
             DfValue::Array(ref arr) => write!(f, "{}", arr),
+            DfValue::Range(ref range) => write!(f, "{}", range),
             DfValue::PassThrough(ref p) => {
                 write!(f, "[{}:{:x?}]", p.ty.name(), p.data)
             }
@@ -217,6 +222,7 @@ impl DfValue {
             DfValue::Numeric(_) => DfValue::from(Decimal::MIN),
             DfValue::BitVector(_) => DfValue::from(BitVec::new()),
             DfValue::Array(_) => DfValue::empty_array(),
+            DfValue::Range(_) => DfValue::from(Range::Empty),
             DfValue::PassThrough(p) => DfValue::PassThrough(Arc::new(PassThrough {
                 ty: p.ty.clone(),
                 data: [].into(),
@@ -245,6 +251,7 @@ impl DfValue {
             | DfValue::ByteArray(_)
             | DfValue::BitVector(_)
             | DfValue::Array(_)
+            | DfValue::Range(_)
             | DfValue::PassThrough(_)
             | DfValue::Max => DfValue::Max,
         }
@@ -323,6 +330,7 @@ impl DfValue {
             // pick whatever we want here - but it makes the most sense to try to limit falsiness to
             // only the things that mysql considers falsey
             DfValue::Array(_) | DfValue::PassThrough(_) => true,
+            DfValue::Range(ref range) => !range.is_empty(),
         }
     }
 
@@ -390,6 +398,17 @@ impl DfValue {
             Self::Array(vs) => Some(SqlType::Array(Box::new(
                 vs.values().find_map(|v| v.sql_type())?,
             ))),
+            // Integer values don't know their width, so integer ranges are always reported as
+            // `int8range`, the same way integers are reported as `BIGINT`. Like arrays, empty
+            // ranges (and ranges with only infinite bounds) have no values to infer a type from.
+            Self::Range(range) => match range.values().find(|v| !v.is_none())? {
+                Self::Int(_) | Self::UnsignedInt(_) => Some(Int8Range),
+                Self::Numeric(_) | Self::Float(_) | Self::Double(_) => Some(NumRange),
+                Self::TimestampTz(ts) if ts.has_date_only() => Some(DateRange),
+                Self::TimestampTz(ts) if ts.has_timezone() => Some(TsTzRange),
+                Self::TimestampTz(_) => Some(TsRange),
+                _ => None,
+            },
         }
     }
 
@@ -427,6 +446,13 @@ impl DfValue {
                     .find(DfType::is_known)
                     .unwrap_or_default(),
             )),
+            Self::Range(range) => Range(Box::new(
+                range
+                    .values()
+                    .map(Self::infer_dataflow_type)
+                    .find(DfType::is_known)
+                    .unwrap_or_default(),
+            )),
         }
     }
 
@@ -529,6 +555,20 @@ impl DfValue {
                 )),
                 _ => Err(mk_err()),
             },
+            DfValue::Range(range) => match to_ty {
+                DfType::Range(t) => {
+                    let from_element_ty = match from_ty {
+                        DfType::Range(t) => t,
+                        _ => from_ty,
+                    };
+                    Ok(DfValue::from(range.coerce_to(t, from_element_ty)?))
+                }
+                DfType::Text(collation) => Ok(DfValue::from_str_and_collation(
+                    &range.to_string(),
+                    *collation,
+                )),
+                _ => Err(mk_err()),
+            },
             _ if is_clone_coercible() => Ok(self.clone()),
             DfValue::Text(t) => t.coerce_to(to_ty, from_ty),
             DfValue::TinyText(tt) => tt.coerce_to(to_ty, from_ty),
@@ -627,6 +667,22 @@ impl DfValue {
         }
     }
 
+    /// If `self` is [`DfValue::Range`], return a reference to the underlying [`Range`], otherwise
+    /// return a [`ReadySetError::DfValueConversionError`] for all other [`DfValue`] variants.
+    pub fn as_range(&self) -> ReadySetResult<&Range> {
+        match self {
+            DfValue::Range(range) => Ok(range),
+            _ => Err(ReadySetError::DfValueConversionError {
+                src_type: match self.sql_type() {
+                    Some(ty) => ty.display(nom_sql::Dialect::MySQL).to_string(),
+                    None => "Null".to_string(),
+                },
+                target_type: "Range".to_string(),
+                details: "".to_string(),
+            }),
+        }
+    }
+
     /// If `self` is [`DfValue::Text`], [`DfValue::TinyText`] or [`DfValue::ByteArray`], return a
     /// reference to the underlying [`Array`], otherwise return a
     /// [`ReadySetError::DfValueConversionError`] for all other [`DfValue`] variants.
@@ -836,6 +892,7 @@ impl PartialEq for DfValue {
                 bits_a.as_ref() == bits_b.as_ref()
             }
             (DfValue::Array(vs_a), DfValue::Array(vs_b)) => vs_a == vs_b,
+            (DfValue::Range(r_a), DfValue::Range(r_b)) => r_a == r_b,
             (&DfValue::None, &DfValue::None) => true,
             (&DfValue::Max, &DfValue::Max) => true,
             _ => false,
@@ -988,6 +1045,7 @@ impl Ord for DfValue {
             (DfValue::ByteArray(array_a), DfValue::ByteArray(array_b)) => array_a.cmp(array_b),
             (DfValue::BitVector(bits_a), DfValue::BitVector(bits_b)) => bits_a.cmp(bits_b),
             (DfValue::Array(vs_a), DfValue::Array(vs_b)) => vs_a.cmp(vs_b),
+            (DfValue::Range(r_a), DfValue::Range(r_b)) => r_a.cmp(r_b),
 
             // for all other kinds of data types, just compare the variants in order
             (_, _) => DfValueKind::from(self).cmp(&DfValueKind::from(other)),
@@ -1022,6 +1080,7 @@ impl Hash for DfValue {
             DfValue::Numeric(ref d) => d.hash(state),
             DfValue::BitVector(ref bits) => bits.hash(state),
             DfValue::Array(ref vs) => vs.hash(state),
+            DfValue::Range(ref range) => range.hash(state),
             DfValue::PassThrough(ref p) => p.hash(state),
         }
     }
@@ -1257,6 +1316,7 @@ impl TryFrom<DfValue> for Literal {
             DfValue::Numeric(ref d) => Ok(Literal::Numeric(d.mantissa(), d.scale())),
             DfValue::BitVector(ref bits) => Ok(Literal::BitVector(bits.as_ref().to_bytes())),
             DfValue::Array(_) => unsupported!("Arrays not implemented yet"),
+            DfValue::Range(_) => unsupported!("Ranges not implemented yet"),
             DfValue::PassThrough(_) => internal!("PassThrough has no representation as a literal"),
             DfValue::Max => internal!("MAX has no representation as a literal"),
         }
@@ -1580,6 +1640,12 @@ impl From<&[u8]> for DfValue {
     }
 }
 
+impl From<Range> for DfValue {
+    fn from(range: Range) -> Self {
+        Self::Range(Arc::new(range))
+    }
+}
+
 impl From<Array> for DfValue {
     fn from(arr: Array) -> Self {
         Self::Array(Arc::new(arr))
@@ -1768,6 +1834,7 @@ impl ToSql for DfValue {
             (Self::ByteArray(ref array), _) => array.as_ref().to_sql(ty, out),
             (Self::BitVector(ref bits), _) => bits.as_ref().to_sql(ty, out),
             (Self::Array(ref array), _) => array.as_ref().to_sql(ty, out),
+            (Self::Range(ref range), _) => range.as_ref().to_sql(ty, out),
             (Self::PassThrough(p), _) => p.data.as_ref().to_sql(&p.ty, out),
         }
     }
@@ -1794,6 +1861,7 @@ impl<'a> FromSql<'a> for DfValue {
         }
         match ty.kind() {
             Kind::Array(_) => mk_from_sql!(Array),
+            Kind::Range(_) => mk_from_sql!(Range),
             Kind::Enum(variants) => {
                 let variant_str = str::from_utf8(raw)?;
                 Ok(DfValue::from(
@@ -1957,6 +2025,7 @@ impl TryFrom<&DfValue> for mysql_common::value::Value {
             }
             DfValue::BitVector(_) => internal!("MySQL does not support bit vector types"),
             DfValue::Array(_) => internal!("MySQL does not support array types"),
+            DfValue::Range(_) => internal!("MySQL does not support range types"),
         }
     }
 }
@@ -2126,6 +2195,7 @@ impl Arbitrary for DfValue {
                 .prop_map(|bs| DfValue::BitVector(Arc::new(BitVec::from_bytes(&bs))))
                 .boxed(),
             Some(DfValueKind::Array) => any::<Array>().prop_map(DfValue::from).boxed(),
+            Some(DfValueKind::Range) => any::<Range>().prop_map(DfValue::from).boxed(),
             Some(DfValueKind::PassThrough) => any::<(u32, Vec<u8>)>()
                 .prop_map(|(oid, data)| {
                     DfValue::PassThrough(Arc::new(PassThrough {
@@ -3292,6 +3362,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn range_sql_type() {
+        let range = |lower: DfValue, upper: DfValue| {
+            DfValue::from(
+                Range::new(RangeBound::Inclusive(lower), RangeBound::Exclusive(upper)).unwrap(),
+            )
+        };
+        let date = NaiveDate::from_ymd(2023, 1, 1);
+        let datetime = date.and_hms(12, 0, 0);
+        let next_datetime = date.succ().and_hms(12, 0, 0);
+
+        assert_eq!(
+            range(DfValue::from(1), DfValue::from(10)).sql_type(),
+            Some(SqlType::Int8Range)
+        );
+        assert_eq!(
+            range(
+                DfValue::from(Decimal::new(15, 1)),
+                DfValue::from(Decimal::new(25, 1))
+            )
+            .sql_type(),
+            Some(SqlType::NumRange)
+        );
+        assert_eq!(
+            range(DfValue::from(date), DfValue::from(date.succ())).sql_type(),
+            Some(SqlType::DateRange)
+        );
+        assert_eq!(
+            range(DfValue::from(datetime), DfValue::from(next_datetime)).sql_type(),
+            Some(SqlType::TsRange)
+        );
+        let offset = FixedOffset::east(3600);
+        assert_eq!(
+            range(
+                DfValue::from(offset.from_utc_datetime(&datetime)),
+                DfValue::from(offset.from_utc_datetime(&next_datetime))
+            )
+            .sql_type(),
+            Some(SqlType::TsTzRange)
+        );
+        assert_eq!(
+            DfValue::from(
+                Range::new(
+                    RangeBound::Unbounded,
+                    RangeBound::Exclusive(DfValue::from(10))
+                )
+                .unwrap()
+            )
+            .sql_type(),
+            Some(SqlType::Int8Range)
+        );
+        assert_eq!(DfValue::from(Range::Empty).sql_type(), None);
+    }
+
     mod coerce_to {
         use readyset_util::arbitrary::{
             arbitrary_naive_date, arbitrary_naive_date_time, arbitrary_naive_time,
@@ -0,0 +1,652 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use bytes::BytesMut;
+use chrono::Duration;
+use proptest::arbitrary::Arbitrary;
+use readyset_errors::{invalid_err, ReadySetError, ReadySetResult};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, Kind, ToSql, Type};
+
+use crate::{DfType, DfValue};
+
+/// One end of a [`Range`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RangeBound {
+    /// The range includes the bound value
+    Inclusive(DfValue),
+    /// The range excludes the bound value
+    Exclusive(DfValue),
+    /// The range extends infinitely in this direction
+    Unbounded,
+}
+
+impl RangeBound {
+    /// Returns a reference to the value of this bound, or `None` if the bound is
+    /// [`RangeBound::Unbounded`]
+    pub fn value(&self) -> Option<&DfValue> {
+        match self {
+            RangeBound::Inclusive(v) | RangeBound::Exclusive(v) => Some(v),
+            RangeBound::Unbounded => None,
+        }
+    }
+
+    fn try_map<F>(&self, f: F) -> ReadySetResult<Self>
+    where
+        F: FnOnce(&DfValue) -> ReadySetResult<DfValue>,
+    {
+        Ok(match self {
+            RangeBound::Inclusive(v) => RangeBound::Inclusive(f(v)?),
+            RangeBound::Exclusive(v) => RangeBound::Exclusive(f(v)?),
+            RangeBound::Unbounded => RangeBound::Unbounded,
+        })
+    }
+}
+
+/// Compare two range bounds, each of which is either the lower or the upper bound of its range.
+///
+/// This follows the semantics of postgresql's `range_cmp_bounds`: at an equal value, an exclusive
+/// lower bound sorts just *after* the value and an exclusive upper bound sorts just *before* it.
+fn cmp_bounds(a: &RangeBound, a_is_lower: bool, b: &RangeBound, b_is_lower: bool) -> Ordering {
+    use RangeBound::*;
+
+    fn offset(bound: &RangeBound, is_lower: bool) -> i8 {
+        match (bound, is_lower) {
+            (Exclusive(_), true) => 1,
+            (Exclusive(_), false) => -1,
+            _ => 0,
+        }
+    }
+
+    match (a, b) {
+        (Unbounded, Unbounded) if a_is_lower == b_is_lower => Ordering::Equal,
+        (Unbounded, _) if a_is_lower => Ordering::Less,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) if b_is_lower => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+        (Inclusive(av) | Exclusive(av), Inclusive(bv) | Exclusive(bv)) => av
+            .cmp(bv)
+            .then_with(|| offset(a, a_is_lower).cmp(&offset(b, b_is_lower))),
+    }
+}
+
+/// Internal representation of PostgreSQL [range types][pg-docs]
+///
+/// The bounds of a range are stored as [`DfValue`]s, which are expected to all be of the range's
+/// element type. Ranges of discrete types (`int4range`, `int8range` and `daterange`) are
+/// canonicalized to the `[lower,upper)` form when coerced to their element type via
+/// [`DfValue::coerce_to`], as they are by postgresql.
+///
+/// [pg-docs]: https://www.postgresql.org/docs/current/rangetypes.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Range {
+    /// The empty range, which contains no values
+    Empty,
+    /// A range with at least one value
+    NonEmpty {
+        /// The lower bound of the range
+        lower: RangeBound,
+        /// The upper bound of the range
+        upper: RangeBound,
+    },
+}
+
+impl Range {
+    /// Construct a new [`Range`] from the given lower and upper bounds.
+    ///
+    /// Returns [`Range::Empty`] if the bounds have the same value but are not both inclusive, and
+    /// an error if the lower bound is greater than the upper bound.
+    pub fn new(lower: RangeBound, upper: RangeBound) -> ReadySetResult<Self> {
+        if let (Some(l), Some(u)) = (lower.value(), upper.value()) {
+            match l.cmp(u) {
+                Ordering::Greater => {
+                    return Err(invalid_err!(
+                        "range lower bound must be less than or equal to range upper bound"
+                    ))
+                }
+                Ordering::Equal
+                    if !matches!(
+                        (&lower, &upper),
+                        (RangeBound::Inclusive(_), RangeBound::Inclusive(_))
+                    ) =>
+                {
+                    return Ok(Range::Empty)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Range::NonEmpty { lower, upper })
+    }
+
+    /// Returns `true` if the range contains no values
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Range::Empty)
+    }
+
+    /// Returns an iterator over references to the values of the bounds of this range, lower bound
+    /// first.
+    pub fn values(&self) -> impl Iterator<Item = &DfValue> + '_ {
+        let (lower, upper) = match self {
+            Range::Empty => (None, None),
+            Range::NonEmpty { lower, upper } => (lower.value(), upper.value()),
+        };
+        lower.into_iter().chain(upper)
+    }
+
+    /// Returns `true` if the given value is within this range (the `@>` operator with an element
+    /// on the right-hand side)
+    pub fn contains_value(&self, value: &DfValue) -> bool {
+        let (lower, upper) = match self {
+            Range::Empty => return false,
+            Range::NonEmpty { lower, upper } => (lower, upper),
+        };
+
+        let above_lower = match lower {
+            RangeBound::Inclusive(l) => l <= value,
+            RangeBound::Exclusive(l) => l < value,
+            RangeBound::Unbounded => true,
+        };
+        let below_upper = match upper {
+            RangeBound::Inclusive(u) => value <= u,
+            RangeBound::Exclusive(u) => value < u,
+            RangeBound::Unbounded => true,
+        };
+
+        above_lower && below_upper
+    }
+
+    /// Returns `true` if every value in `other` is also within this range (the `@>` operator with
+    /// a range on the right-hand side)
+    pub fn contains_range(&self, other: &Range) -> bool {
+        match (self, other) {
+            (_, Range::Empty) => true,
+            (Range::Empty, _) => false,
+            (
+                Range::NonEmpty { lower, upper },
+                Range::NonEmpty {
+                    lower: other_lower,
+                    upper: other_upper,
+                },
+            ) => {
+                cmp_bounds(lower, true, other_lower, true).is_le()
+                    && cmp_bounds(upper, false, other_upper, false).is_ge()
+            }
+        }
+    }
+
+    /// Returns `true` if this range has any values in common with `other` (the `&&` operator)
+    pub fn overlaps(&self, other: &Range) -> bool {
+        match (self, other) {
+            (Range::Empty, _) | (_, Range::Empty) => false,
+            (
+                Range::NonEmpty { lower, upper },
+                Range::NonEmpty {
+                    lower: other_lower,
+                    upper: other_upper,
+                },
+            ) => {
+                cmp_bounds(lower, true, other_upper, false).is_le()
+                    && cmp_bounds(other_lower, true, upper, false).is_le()
+            }
+        }
+    }
+
+    /// Coerce the bounds of this range to the given new element type, canonicalizing the bounds
+    /// if the new element type is discrete.
+    pub(crate) fn coerce_to(
+        &self,
+        new_element_type: &DfType,
+        from_element_type: &DfType,
+    ) -> ReadySetResult<Self> {
+        let (lower, upper) = match self {
+            Range::Empty => return Ok(Range::Empty),
+            Range::NonEmpty { lower, upper } => (lower, upper),
+        };
+
+        let coerce = |v: &DfValue| v.coerce_to(new_element_type, from_element_type);
+        let mut lower = lower.try_map(coerce)?;
+        let mut upper = upper.try_map(coerce)?;
+
+        if matches!(
+            new_element_type,
+            DfType::Int | DfType::BigInt | DfType::Date
+        ) {
+            if let RangeBound::Exclusive(v) = lower {
+                lower = RangeBound::Inclusive(successor(&v, new_element_type)?);
+            }
+            if let RangeBound::Inclusive(v) = upper {
+                upper = RangeBound::Exclusive(successor(&v, new_element_type)?);
+            }
+        }
+
+        Self::new(lower, upper)
+    }
+}
+
+/// Returns the value immediately after `value` in the given discrete element type
+fn successor(value: &DfValue, ty: &DfType) -> ReadySetResult<DfValue> {
+    let out_of_range = || invalid_err!("{} out of range", ty);
+    match (value, ty) {
+        (DfValue::Int(i), DfType::Int) if *i < i64::from(i32::MAX) => Ok(DfValue::Int(i + 1)),
+        (DfValue::Int(i), DfType::BigInt) => {
+            i.checked_add(1).map(DfValue::Int).ok_or_else(out_of_range)
+        }
+        (DfValue::TimestampTz(ts), DfType::Date) => ts
+            .to_chrono()
+            .naive_local()
+            .date()
+            .checked_add_signed(Duration::days(1))
+            .map(DfValue::from)
+            .ok_or_else(out_of_range),
+        _ => Err(out_of_range()),
+    }
+}
+
+impl Ord for Range {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Range::Empty, Range::Empty) => Ordering::Equal,
+            (Range::Empty, _) => Ordering::Less,
+            (_, Range::Empty) => Ordering::Greater,
+            (
+                Range::NonEmpty { lower, upper },
+                Range::NonEmpty {
+                    lower: other_lower,
+                    upper: other_upper,
+                },
+            ) => cmp_bounds(lower, true, other_lower, true)
+                .then_with(|| cmp_bounds(upper, false, other_upper, false)),
+        }
+    }
+}
+
+impl PartialOrd for Range {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_bound_value(f: &mut fmt::Formatter<'_>, bound: &RangeBound) -> fmt::Result {
+            let value = match bound.value() {
+                Some(v) => v,
+                None => return Ok(()),
+            };
+            let s = match value.as_str() {
+                Some(s) => s.to_owned(),
+                None => value.to_string(),
+            };
+
+            if s.is_empty()
+                || s.chars().any(|c| {
+                    matches!(c, '"' | '\\' | ',' | '(' | ')' | '[' | ']') || c.is_whitespace()
+                })
+            {
+                write!(f, "\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                f.write_str(&s)
+            }
+        }
+
+        match self {
+            Range::Empty => f.write_str("empty"),
+            Range::NonEmpty { lower, upper } => {
+                f.write_str(if matches!(lower, RangeBound::Inclusive(_)) {
+                    "["
+                } else {
+                    "("
+                })?;
+                write_bound_value(f, lower)?;
+                f.write_str(",")?;
+                write_bound_value(f, upper)?;
+                f.write_str(if matches!(upper, RangeBound::Inclusive(_)) {
+                    "]"
+                } else {
+                    ")"
+                })
+            }
+        }
+    }
+}
+
+/// Parse a range from the postgresql text representation, eg `[1,10)` or `empty`.
+///
+/// The bounds of the resulting range are always text values - to get a range of the correct
+/// element type, coerce the result via [`DfValue::coerce_to`], which also validates the order of
+/// the bounds.
+impl FromStr for Range {
+    type Err = ReadySetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mk_err = |message: &str| ReadySetError::RangeParseError {
+            input: s.to_owned(),
+            message: message.to_owned(),
+        };
+
+        let trimmed = s.trim();
+        if trimmed.eq_ignore_ascii_case("empty") {
+            return Ok(Range::Empty);
+        }
+
+        let mut chars = trimmed.chars().peekable();
+        let lower_inclusive = match chars.next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => return Err(mk_err("Missing left parenthesis or bracket")),
+        };
+
+        // Returns the bound value, or `None` if the bound was omitted, along with the character
+        // that terminated it
+        let mut parse_bound_value = || -> Result<(Option<String>, char), ReadySetError> {
+            let mut value = String::new();
+            let mut present = false;
+            let mut in_quotes = false;
+            loop {
+                let c = chars
+                    .next()
+                    .ok_or_else(|| mk_err("Unexpected end of input"))?;
+                match c {
+                    '\\' => {
+                        value.push(
+                            chars
+                                .next()
+                                .ok_or_else(|| mk_err("Unexpected end of input"))?,
+                        );
+                        present = true;
+                    }
+                    '"' if in_quotes && chars.peek() == Some(&'"') => {
+                        chars.next();
+                        value.push('"');
+                    }
+                    '"' => {
+                        in_quotes = !in_quotes;
+                        present = true;
+                    }
+                    ',' | ')' | ']' if !in_quotes => {
+                        return Ok((present.then_some(value), c));
+                    }
+                    '(' | '[' if !in_quotes => return Err(mk_err("Unexpected character")),
+                    c => {
+                        value.push(c);
+                        present = true;
+                    }
+                }
+            }
+        };
+
+        let (lower, sep) = parse_bound_value()?;
+        if sep != ',' {
+            return Err(mk_err("Too few elements"));
+        }
+        let (upper, end) = parse_bound_value()?;
+        if end == ',' {
+            return Err(mk_err("Too many elements"));
+        }
+        if chars.next().is_some() {
+            return Err(mk_err("Junk after right parenthesis or bracket"));
+        }
+
+        let mk_bound = |value: Option<String>, inclusive: bool| match value {
+            None => RangeBound::Unbounded,
+            Some(v) if inclusive => RangeBound::Inclusive(DfValue::from(v)),
+            Some(v) => RangeBound::Exclusive(DfValue::from(v)),
+        };
+
+        Ok(Range::NonEmpty {
+            lower: mk_bound(lower, lower_inclusive),
+            upper: mk_bound(upper, end == ']'),
+        })
+    }
+}
+
+impl Arbitrary for Range {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        fn bound(value: i32) -> impl Strategy<Value = RangeBound> {
+            prop_oneof![
+                Just(RangeBound::Inclusive(DfValue::from(value))),
+                Just(RangeBound::Exclusive(DfValue::from(value))),
+                Just(RangeBound::Unbounded),
+            ]
+        }
+
+        prop_oneof![
+            Just(Range::Empty),
+            (any::<i32>(), any::<i32>())
+                .prop_flat_map(|(a, b)| (bound(a.min(b)), bound(a.max(b))))
+                .prop_map(|(lower, upper)| Range::new(lower, upper).unwrap()),
+        ]
+        .boxed()
+    }
+}
+
+fn read_bound(
+    bound: postgres_protocol::types::RangeBound<Option<&[u8]>>,
+    element_type: &Type,
+) -> Result<RangeBound, Box<dyn Error + Sync + Send>> {
+    let value = |raw: Option<&[u8]>| match raw {
+        Some(raw) => DfValue::from_sql(element_type, raw),
+        None => Err("Unexpected NULL range bound".into()),
+    };
+
+    Ok(match bound {
+        postgres_protocol::types::RangeBound::Inclusive(raw) => RangeBound::Inclusive(value(raw)?),
+        postgres_protocol::types::RangeBound::Exclusive(raw) => RangeBound::Exclusive(value(raw)?),
+        postgres_protocol::types::RangeBound::Unbounded => RangeBound::Unbounded,
+    })
+}
+
+fn write_bound(
+    bound: &RangeBound,
+    element_type: &Type,
+    out: &mut BytesMut,
+) -> Result<
+    postgres_protocol::types::RangeBound<postgres_protocol::IsNull>,
+    Box<dyn Error + Sync + Send>,
+> {
+    let write_value = |v: &DfValue, out: &mut BytesMut| {
+        Ok::<_, Box<dyn Error + Sync + Send>>(match v.to_sql(element_type, out)? {
+            IsNull::Yes => postgres_protocol::IsNull::Yes,
+            IsNull::No => postgres_protocol::IsNull::No,
+        })
+    };
+
+    Ok(match bound {
+        RangeBound::Inclusive(v) => {
+            postgres_protocol::types::RangeBound::Inclusive(write_value(v, out)?)
+        }
+        RangeBound::Exclusive(v) => {
+            postgres_protocol::types::RangeBound::Exclusive(write_value(v, out)?)
+        }
+        RangeBound::Unbounded => postgres_protocol::types::RangeBound::Unbounded,
+    })
+}
+
+impl<'a> FromSql<'a> for Range {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let element_type = match ty.kind() {
+            Kind::Range(element) => element,
+            _ => panic!("Expected range type"),
+        };
+
+        match postgres_protocol::types::range_from_sql(raw)? {
+            postgres_protocol::types::Range::Empty => Ok(Range::Empty),
+            postgres_protocol::types::Range::Nonempty(lower, upper) => Ok(Range::NonEmpty {
+                lower: read_bound(lower, element_type)?,
+                upper: read_bound(upper, element_type)?,
+            }),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Range(element) => <DfValue as FromSql>::accepts(element),
+            _ => false,
+        }
+    }
+}
+
+impl ToSql for Range {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>>
+    where
+        Self: Sized,
+    {
+        let element_type = match ty.kind() {
+            Kind::Range(element) => element,
+            _ => panic!("Expected range type"),
+        };
+
+        let (lower, upper) = match self {
+            Range::Empty => {
+                postgres_protocol::types::empty_range_to_sql(out);
+                return Ok(IsNull::No);
+            }
+            Range::NonEmpty { lower, upper } => (lower, upper),
+        };
+
+        postgres_protocol::types::range_to_sql(
+            |out| write_bound(lower, element_type, out),
+            |out| write_bound(upper, element_type, out),
+            out,
+        )?;
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool
+    where
+        Self: Sized,
+    {
+        match ty.kind() {
+            Kind::Range(element) => <DfValue as ToSql>::accepts(element),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use readyset_util::ord_laws;
+    use test_strategy::proptest;
+
+    use super::*;
+
+    ord_laws!(Range);
+
+    fn int_range(s: &str) -> Range {
+        s.parse::<Range>()
+            .unwrap()
+            .coerce_to(&DfType::Int, &DfType::Unknown)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_and_print() {
+        for (input, expected) in [
+            ("[1,10)", "[1,10)"),
+            ("(1,10]", "[2,11)"),
+            ("[,5]", "(,6)"),
+            ("(,)", "(,)"),
+            ("[3,3)", "empty"),
+            ("  EMPTY ", "empty"),
+        ] {
+            assert_eq!(int_range(input).to_string(), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn parse_quoted_bounds() {
+        let range: Range = r#"["a\"b","c d")"#.parse().unwrap();
+        assert_eq!(
+            range,
+            Range::NonEmpty {
+                lower: RangeBound::Inclusive(DfValue::from("a\"b")),
+                upper: RangeBound::Exclusive(DfValue::from("c d")),
+            }
+        );
+        assert_eq!(range.to_string(), r#"["a\"b","c d")"#);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for input in ["1,10", "[1,10", "[1)", "[1,2,3)", "[1,10)x"] {
+            input.parse::<Range>().unwrap_err();
+        }
+        "[10,1)"
+            .parse::<Range>()
+            .unwrap()
+            .coerce_to(&DfType::Int, &DfType::Unknown)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn canonicalize_date_range() {
+        let range = "(2023-01-01,2023-01-31]"
+            .parse::<Range>()
+            .unwrap()
+            .coerce_to(&DfType::Date, &DfType::Unknown)
+            .unwrap();
+        assert_eq!(
+            range,
+            Range::NonEmpty {
+                lower: RangeBound::Inclusive(DfValue::from(
+                    NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()
+                )),
+                upper: RangeBound::Exclusive(DfValue::from(
+                    NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn contains_value() {
+        let range = int_range("[1,10)");
+        assert!(range.contains_value(&DfValue::from(1)));
+        assert!(range.contains_value(&DfValue::from(9)));
+        assert!(!range.contains_value(&DfValue::from(10)));
+        assert!(!range.contains_value(&DfValue::from(0)));
+        assert!(int_range("(,)").contains_value(&DfValue::from(i32::MIN)));
+        assert!(!Range::Empty.contains_value(&DfValue::from(1)));
+    }
+
+    #[test]
+    fn contains_range() {
+        assert!(int_range("[1,10)").contains_range(&int_range("[2,9]")));
+        assert!(int_range("[1,10)").contains_range(&Range::Empty));
+        assert!(!int_range("[1,10)").contains_range(&int_range("[2,10]")));
+        assert!(int_range("[1,)").contains_range(&int_range("[2,10]")));
+        assert!(!int_range("[1,10)").contains_range(&int_range("[2,)")));
+    }
+
+    #[test]
+    fn overlaps() {
+        assert!(int_range("[1,10)").overlaps(&int_range("[9,20)")));
+        assert!(!int_range("[1,10)").overlaps(&int_range("[10,20)")));
+        assert!(int_range("(,)").overlaps(&int_range("[10,20)")));
+        assert!(!int_range("[1,10)").overlaps(&Range::Empty));
+    }
+
+    #[proptest]
+    fn display_parse_round_trip(range: Range) {
+        let parsed = range
+            .to_string()
+            .parse::<Range>()
+            .unwrap()
+            .coerce_to(&DfType::BigInt, &DfType::Unknown)
+            .unwrap();
+        let expected = range.coerce_to(&DfType::BigInt, &DfType::Unknown).unwrap();
+        assert_eq!(parsed, expected);
+    }
+}
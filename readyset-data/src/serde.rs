@@ -12,7 +12,7 @@ use serde_bytes::{ByteBuf, Bytes};
 use strum::VariantNames;
 use strum_macros::{EnumString, EnumVariantNames, FromRepr};
 
-use crate::{Array, Collation, DfValue, Text, TimestampTz, TinyText};
+use crate::{Array, Collation, DfValue, Range, RangeBound, Text, TimestampTz, TinyText};
 
 impl DfValue {
     /// Version number for the current implementations of [`serde::Deserialize`] and
@@ -58,6 +58,23 @@ impl DfValue {
             DfValue::Max,
         ]
     }
+
+    /// Reference example "row" of [`DfValue::Range`]s to check against for backwards compatible
+    /// deserialization.
+    ///
+    /// This is kept separate from [`DfValue::example_row`] so that the reference payload for
+    /// variants that existed before ranges doesn't need to be regenerated.
+    ///
+    /// This is only exported so it can be used by `examples/make_serialized_row.rs`
+    pub fn example_range_row() -> Vec<DfValue> {
+        vec![DfValue::Range(Arc::new(
+            Range::new(
+                RangeBound::Inclusive(DfValue::Int(1)),
+                RangeBound::Exclusive(DfValue::Int(10)),
+            )
+            .unwrap(),
+        ))]
+    }
 }
 
 #[derive(EnumVariantNames, EnumString, FromRepr, Clone, Copy)]
@@ -74,6 +91,9 @@ enum Variant {
     TimestampTz,
     Array,
     Max,
+    // NOTE: new variants must be added *after* `Max`, to keep the serialized variant indices of
+    // existing variants stable
+    Range,
 }
 
 enum TextOrTinyText {
@@ -132,6 +152,7 @@ impl serde::ser::Serialize for DfValue {
                 serialize_variant(serializer, Variant::TimestampTz, &(ts, extra))
             }
             DfValue::Array(vs) => serialize_variant(serializer, Variant::Array, &vs),
+            DfValue::Range(range) => serialize_variant(serializer, Variant::Range, &range),
             DfValue::PassThrough(v) => Err(serde::ser::Error::custom(format_args!(
                 "PassThrough value of type {} not supported in dataflow graph",
                 v.ty
@@ -269,6 +290,9 @@ impl<'de> Deserialize<'de> for DfValue {
                     (Variant::Max, variant) => {
                         VariantAccess::unit_variant(variant).map(|_| DfValue::Max)
                     }
+                    (Variant::Range, variant) => {
+                        VariantAccess::newtype_variant(variant).map(DfValue::Range)
+                    }
                 }
             }
         }
@@ -394,6 +418,13 @@ mod tests {
                 .unwrap(),
             DfValue::example_row()
         );
+        assert_eq!(
+            bincode::deserialize::<Vec<DfValue>>(include_bytes!(
+                "../tests/serialized-range-row.bincode"
+            ))
+            .unwrap(),
+            DfValue::example_range_row()
+        );
     }
 
     #[proptest]
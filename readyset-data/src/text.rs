@@ -9,7 +9,7 @@ use std::sync::atomic::Ordering::Relaxed;
 use cidr::IpInet;
 use readyset_errors::{ReadySetError, ReadySetResult};
 
-use crate::{Array, Collation, DfType, DfValue};
+use crate::{Array, Collation, DfType, DfValue, Range};
 
 pub(crate) const TINYTEXT_WIDTH: usize = 14;
 
@@ -537,6 +537,12 @@ pub(crate) trait TextCoerce: Sized + Clone + Into<DfValue> {
             )
             .coerce_to(to_ty, from_ty),
 
+            DfType::Range(_) => DfValue::from(
+                str.parse::<Range>()
+                    .map_err(|e| Self::coerce_err(to_ty, e))?,
+            )
+            .coerce_to(to_ty, from_ty),
+
             DfType::Enum { ref variants, .. } => {
                 if let Some(i) = variants.iter().position(|variant| variant == str) {
                     // MySQL enums use 1-based indexing since a value of 0 is reserved for string
@@ -76,7 +76,7 @@ impl TimestampTz {
 
     /// Returns true if should be displayed as date only
     #[inline(always)]
-    fn has_date_only(&self) -> bool {
+    pub(crate) fn has_date_only(&self) -> bool {
         self.extra[2] & TimestampTz::DATE_FLAG != 0
     }
 
@@ -407,7 +407,8 @@ impl TimestampTz {
             | DfType::Uuid
             | DfType::Bit(_)
             | DfType::VarBit(_)
-            | DfType::Array(_) => Err(ReadySetError::DfValueConversionError {
+            | DfType::Array(_)
+            | DfType::Range(_) => Err(ReadySetError::DfValueConversionError {
                 src_type: "DfValue::TimestampTz".to_string(),
                 target_type: format!("{:?}", to_ty),
                 details: "Not allowed".to_string(),
@@ -39,6 +39,10 @@ pub enum DfType {
     /// [PostgreSQL `T[]`](https://www.postgresql.org/docs/current/arrays.html).
     Array(Box<DfType>),
 
+    /// [PostgreSQL range types](https://www.postgresql.org/docs/current/rangetypes.html), with
+    /// the given element type.
+    Range(Box<DfType>),
+
     /// [PostgreSQL boolean](https://www.postgresql.org/docs/current/datatype-boolean.html)
     /// or alias to `tinyint(1)` in MySQL.
     ///
@@ -229,6 +233,17 @@ impl DfType {
                 resolve_custom_type,
             )?)),
 
+            Int4Range => Self::Range(Box::new(Self::Int)),
+            Int8Range => Self::Range(Box::new(Self::BigInt)),
+            NumRange => Self::Range(Box::new(Self::DEFAULT_NUMERIC)),
+            TsRange => Self::Range(Box::new(Self::Timestamp {
+                subsecond_digits: dialect.default_subsecond_digits(),
+            })),
+            TsTzRange => Self::Range(Box::new(Self::TimestampTz {
+                subsecond_digits: dialect.default_subsecond_digits(),
+            })),
+            DateRange => Self::Range(Box::new(Self::Date)),
+
             Enum(ref variants) => Self::Enum {
                 // PERF: Cloning variants is O(1).
                 variants: variants.clone(),
@@ -328,6 +343,7 @@ impl DfType {
         match self {
             DfType::Unknown => PgTypeCategory::Unknown,
             DfType::Array(_) => PgTypeCategory::Array,
+            DfType::Range(_) => PgTypeCategory::Range,
             DfType::Bool => PgTypeCategory::Boolean,
             DfType::Int
             | DfType::UnsignedInt
@@ -394,7 +410,7 @@ impl DfType {
     pub fn is_strictly_known(&self) -> bool {
         match self {
             Self::Unknown => false,
-            Self::Array(ty) => ty.is_strictly_known(),
+            Self::Array(ty) | Self::Range(ty) => ty.is_strictly_known(),
             _ => true,
         }
     }
@@ -475,6 +491,12 @@ impl DfType {
         matches!(self, Self::Array { .. })
     }
 
+    /// Returns `true` if this is any PostgreSQL range type.
+    #[inline]
+    pub fn is_range(&self) -> bool {
+        matches!(self, Self::Range(_))
+    }
+
     /// Returns `true` if this is any MySQL binary type.
     #[inline]
     pub fn is_binary(&self) -> bool {
@@ -624,6 +646,8 @@ impl fmt::Display for DfType {
 
             Self::Array(ref ty) => write!(f, "{ty}[]"),
 
+            Self::Range(ref ty) => write!(f, "{kind:?}({ty})"),
+
             Self::Char(n, ..)
             | Self::VarChar(n, ..)
             | Self::Binary(n)
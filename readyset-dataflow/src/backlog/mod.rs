@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::ops::Bound;
 use std::sync::Arc;
+use std::time::Duration;
 
 use ahash::RandomState;
 use common::SizeOf;
@@ -35,7 +36,14 @@ pub(crate) fn new(
     index: Index,
     reader_processing: ReaderProcessing,
 ) -> (SingleReadHandle, WriteHandle) {
-    new_inner(cols, index, None, EvictionKind::Random, reader_processing)
+    new_inner(
+        cols,
+        index,
+        None,
+        EvictionKind::Random,
+        None,
+        reader_processing,
+    )
 }
 
 /// Allocate a new partially materialized end-user facing result table.
@@ -47,6 +55,8 @@ pub(crate) fn new(
 /// * `cols` - the number of columns in this table
 /// * `index` - the index for the reader
 /// * `trigger` - function to call to trigger an upquery and replay
+/// * `ttl` - if set, how long keys can be in the table before they're returned by
+///   [`WriteHandle::evict_expired`]
 ///
 /// # Invariants:
 ///
@@ -56,6 +66,7 @@ pub(crate) fn new_partial<F>(
     index: Index,
     trigger: F,
     eviction_kind: EvictionKind,
+    ttl: Option<Duration>,
     reader_processing: ReaderProcessing,
 ) -> (SingleReadHandle, WriteHandle)
 where
@@ -66,6 +77,7 @@ where
         index,
         Some(Arc::new(trigger)),
         eviction_kind,
+        ttl,
         reader_processing,
     )
 }
@@ -78,6 +90,7 @@ fn new_inner(
     index: Index,
     trigger: Option<Arc<dyn Trigger>>,
     eviction_kind: EvictionKind,
+    ttl: Option<Duration>,
     reader_processing: ReaderProcessing,
 ) -> (SingleReadHandle, WriteHandle) {
     let contiguous = {
@@ -115,6 +128,7 @@ fn new_inner(
                 .with_hasher(RandomState::default())
                 .with_index_type(index.index_type)
                 .with_eviction_strategy(eviction_strategy)
+                .with_ttl(ttl)
                 .with_insertion_order(Some(pre_processing.clone()))
                 .construct();
             // If we're fully materialized, we never miss, so we can insert a single interval to
@@ -285,10 +299,21 @@ impl WriteHandle {
         self.handle.refresh();
     }
 
+    /// Returns up to `n` of the keys in the reader that were read most recently, according to the
+    /// reader's eviction strategy. Returns no keys if the strategy doesn't keep track of reads.
+    pub(crate) fn hottest_keys(&self, n: usize) -> Vec<Vec<DfValue>> {
+        self.handle.read().hottest_keys(n)
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.handle.read().len()
     }
 
+    /// Returns all the keys in the reader
+    pub(crate) fn keys(&self) -> Vec<Vec<DfValue>> {
+        self.handle.read().keys()
+    }
+
     /// Add a new set of records to the backlog.
     ///
     /// These will be made visible to readers after the next call to `swap()`.
@@ -333,6 +358,36 @@ impl WriteHandle {
         bytes_to_be_freed
     }
 
+    /// Evict keys, in the order suggested by the reader's eviction strategy, until the reader takes
+    /// up no more than `limit` bytes, returning the number of bytes freed
+    pub(crate) fn evict_to_limit(&mut self, limit: usize) -> u64 {
+        if self.mem_size <= limit {
+            return 0;
+        }
+
+        let bytes_freed = self.handle.evict_bytes((self.mem_size - limit) as u64);
+        self.mem_size = self.mem_size.saturating_sub(bytes_freed as usize);
+        bytes_freed
+    }
+
+    /// Evict all keys that have been in the state for longer than its TTL, returning the number
+    /// of bytes freed
+    pub(crate) fn evict_expired(&mut self) -> u64 {
+        let bytes_freed = self.handle.evict_expired();
+        self.mem_size = self.mem_size.saturating_sub(bytes_freed as usize);
+        bytes_freed
+    }
+
+    /// Evict all keys from the state, returning the number of bytes freed
+    pub(crate) fn evict_all(&mut self) -> u64 {
+        debug_assert!(
+            self.partial,
+            "Can't evict all keys from a fully materialized reader"
+        );
+        self.handle.purge();
+        std::mem::take(&mut self.mem_size) as u64
+    }
+
     pub(crate) fn mark_hole(&mut self, key: &KeyComparison) -> ReadySetResult<()> {
         if let Some(len) = key.len() {
             invariant_eq!(len, self.index.len());
@@ -717,6 +772,7 @@ mod tests {
             Index::hash_map(vec![0]),
             |_: &mut dyn Iterator<Item = KeyComparison>| true,
             EvictionKind::Random,
+            None,
             ReaderProcessing::default(),
         );
         w.swap();
@@ -732,6 +788,107 @@ mod tests {
         }
     }
 
+    #[test]
+    fn evict_expired_and_all() {
+        let (r, mut w) = new_partial(
+            1,
+            Index::hash_map(vec![0]),
+            |_: &mut dyn Iterator<Item = KeyComparison>| true,
+            EvictionKind::LRU,
+            Some(Duration::from_millis(50)),
+            ReaderProcessing::default(),
+        );
+        w.swap();
+
+        let a = vec1![DfValue::from(1)];
+        let b = vec1![DfValue::from(2)];
+        w.mark_filled(a.clone().into()).unwrap();
+        w.add(vec![Record::Positive(vec![1.into()])]);
+        w.swap();
+
+        std::thread::sleep(Duration::from_millis(50));
+        w.mark_filled(b.clone().into()).unwrap();
+        w.add(vec![Record::Positive(vec![2.into()])]);
+        w.swap();
+
+        assert!(w.evict_expired() > 0);
+        w.swap();
+        assert!(r.get(&a).err().unwrap().is_miss());
+        assert_eq!(r.get(&b).unwrap().len(), 1);
+
+        w.evict_all();
+        w.swap();
+        assert!(r.get(&b).err().unwrap().is_miss());
+        assert_eq!(w.mem_size, 0);
+    }
+
+    #[test]
+    fn hottest_keys() {
+        let (r, mut w) = new_partial(
+            1,
+            Index::hash_map(vec![0]),
+            |_: &mut dyn Iterator<Item = KeyComparison>| true,
+            EvictionKind::LRU,
+            None,
+            ReaderProcessing::default(),
+        );
+        w.swap();
+
+        for i in 1..=3 {
+            w.mark_filled(vec1![DfValue::from(i)].into()).unwrap();
+            w.add(vec![Record::Positive(vec![i.into()])]);
+        }
+        w.swap();
+
+        for i in [3, 1] {
+            assert_eq!(r.get(&[i.into()]).unwrap().len(), 1);
+        }
+
+        assert_eq!(
+            w.hottest_keys(2),
+            vec![vec![DfValue::from(1)], vec![DfValue::from(3)]]
+        );
+    }
+
+    #[test]
+    fn evict_to_limit() {
+        let (r, mut w) = new_partial(
+            1,
+            Index::hash_map(vec![0]),
+            |_: &mut dyn Iterator<Item = KeyComparison>| true,
+            EvictionKind::LRU,
+            None,
+            ReaderProcessing::default(),
+        );
+        w.swap();
+
+        for i in 1..=4 {
+            w.mark_filled(vec1![DfValue::from(i)].into()).unwrap();
+            w.add(vec![Record::Positive(vec![i.into()])]);
+        }
+        w.swap();
+        let size = w.deep_size_of() as usize;
+
+        // Nothing to do while within the limit
+        assert_eq!(w.evict_to_limit(size), 0);
+
+        for i in [4, 2, 3] {
+            assert_eq!(r.get(&[i.into()]).unwrap().len(), 1);
+        }
+
+        // The least recently read keys are evicted first
+        let freed = w.evict_to_limit(size / 2);
+        w.swap();
+        assert!(freed > 0);
+        assert!(w.deep_size_of() as usize <= size / 2);
+        assert!(!w
+            .contains(&KeyComparison::from(vec1![DfValue::from(1)]))
+            .unwrap());
+        assert!(w
+            .contains(&KeyComparison::from(vec1![DfValue::from(3)]))
+            .unwrap());
+    }
+
     mod mark_filled {
         use super::*;
 
@@ -742,6 +899,7 @@ mod tests {
                 Index::hash_map(vec![0]),
                 |_: &mut dyn Iterator<Item = KeyComparison>| true,
                 EvictionKind::Random,
+                None,
                 ReaderProcessing::default(),
             );
             w.swap();
@@ -761,6 +919,7 @@ mod tests {
                 Index::btree_map(vec![0]),
                 |_: &mut dyn Iterator<Item = KeyComparison>| true,
                 EvictionKind::Random,
+                None,
                 ReaderProcessing::default(),
             );
             w.swap();
@@ -791,6 +950,7 @@ mod tests {
                 Index::btree_map(vec![0]),
                 |_: &mut dyn Iterator<Item = KeyComparison>| true,
                 EvictionKind::Random,
+                None,
                 ReaderProcessing::default(),
             );
             w.swap();
@@ -812,6 +972,7 @@ mod tests {
                 Index::btree_map(vec![0]),
                 |_: &mut dyn Iterator<Item = KeyComparison>| true,
                 EvictionKind::Random,
+                None,
                 ReaderProcessing::default(),
             );
             w.swap();
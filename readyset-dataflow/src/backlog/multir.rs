@@ -109,6 +109,25 @@ impl Handle {
         }
     }
 
+    /// Returns up to `n` of the keys that were read most recently, most recent first
+    pub(super) fn hottest_keys(&self, n: usize) -> Vec<Vec<DfValue>> {
+        match *self {
+            Handle::Single(ref h) => h.enter().map_or_else(
+                |_| vec![],
+                |map| {
+                    map.hottest_keys(n)
+                        .into_iter()
+                        .map(|k| vec![k.clone()])
+                        .collect()
+                },
+            ),
+            Handle::Many(ref h) => h.enter().map_or_else(
+                |_| vec![],
+                |map| map.hottest_keys(n).into_iter().cloned().collect(),
+            ),
+        }
+    }
+
     fn get_multi_single_handle<'a, T, F: Fn() -> T>(
         handle: &HandleSingle,
         keys: &'a [KeyComparison],
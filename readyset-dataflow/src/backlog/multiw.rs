@@ -103,6 +103,46 @@ impl Handle {
         }
     }
 
+    /// Evict keys in the order suggested by the assigned eviction strategy until at least `bytes`
+    /// bytes have been freed, and return the number of bytes freed
+    pub fn evict_bytes(&mut self, bytes: u64) -> u64 {
+        let base_value_size = self.base_value_size() as u64;
+        match *self {
+            Handle::Single(ref mut h) => h.evict_bytes(bytes, |k, v| {
+                k.deep_size_of() + v.iter().map(|r| r.deep_size_of()).sum::<u64>() + base_value_size
+            }),
+            Handle::Many(ref mut h) => h.evict_bytes(bytes, |k, v| {
+                k.deep_size_of() + v.iter().map(|r| r.deep_size_of()).sum::<u64>() + base_value_size
+            }),
+        }
+    }
+
+    /// Evict keys that have been in the state for longer than its TTL, and return the number of
+    /// bytes freed
+    pub fn evict_expired(&mut self) -> u64 {
+        let base_value_size = self.base_value_size() as u64;
+        match *self {
+            Handle::Single(ref mut h) => h.evict_expired(|k, v| {
+                k.deep_size_of() + v.iter().map(|r| r.deep_size_of()).sum::<u64>() + base_value_size
+            }),
+            Handle::Many(ref mut h) => h.evict_expired(|k, v| {
+                k.deep_size_of() + v.iter().map(|r| r.deep_size_of()).sum::<u64>() + base_value_size
+            }),
+        }
+    }
+
+    /// Remove all keys from the state
+    pub fn purge(&mut self) {
+        match *self {
+            Handle::Single(ref mut h) => {
+                h.purge();
+            }
+            Handle::Many(ref mut h) => {
+                h.purge();
+            }
+        }
+    }
+
     pub fn refresh(&mut self) {
         match *self {
             Handle::Single(ref mut h) => {
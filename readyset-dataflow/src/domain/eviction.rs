@@ -0,0 +1,207 @@
+use std::cmp::{self, Reverse};
+use std::collections::BTreeMap;
+
+use nom_sql::CachePriority;
+
+/// Decides how many of `num_bytes` bytes to evict from each of the given candidates, which are
+/// either domains or nodes within a domain, given as the number of bytes of partial state they
+/// have and the [`CachePriority`] of that state.
+///
+/// Lower priority candidates are evicted from first. Within each priority, we don't want to
+/// _empty_ any candidate if we can avoid it, so the eviction is spread across the (up to) 3
+/// largest candidates, evicting at most half of the state of each of them but the last one. That
+/// is repeated with whichever candidates are then the largest until either enough has been
+/// evicted or all the state with that priority is gone, and only then is the rest evicted from
+/// the candidates with the next priority.
+///
+/// Returns the number of bytes to evict from each candidate, in the order they should be evicted
+/// from.
+pub fn plan_eviction<K>(
+    candidates: impl IntoIterator<Item = (K, usize, CachePriority)>,
+    mut num_bytes: usize,
+) -> Vec<(K, usize)> {
+    struct Candidate<K> {
+        candidate: K,
+        remaining: usize,
+        evicted: usize,
+        /// When we first evicted from this candidate, to return the plan in that order
+        first_evicted: Option<usize>,
+    }
+
+    let mut by_priority: BTreeMap<CachePriority, Vec<Candidate<K>>> = BTreeMap::new();
+    for (candidate, size, priority) in candidates {
+        if size > 0 {
+            by_priority.entry(priority).or_default().push(Candidate {
+                candidate,
+                remaining: size,
+                evicted: 0,
+                first_evicted: None,
+            });
+        }
+    }
+    let highest_priority = by_priority.keys().next_back().copied();
+
+    let mut plan = Vec::new();
+    let mut num_evictions = 0;
+    for (priority, mut group) in by_priority {
+        if num_bytes == 0 {
+            break;
+        }
+
+        // Keep evicting from this priority until we've evicted enough or its state runs out
+        while num_bytes > 0 {
+            // largest first. The sort is stable so that candidates of the same size keep being
+            // evicted from in the order they were given.
+            group.sort_by_key(|c| Reverse(c.remaining));
+            let max = match group.first() {
+                Some(c) if c.remaining > 0 => c.remaining,
+                _ => break,
+            };
+
+            // TODO: be smarter than 3 here
+            // don't evict from tiny things (< 10% of max)
+            let n_largest = group
+                .iter()
+                .take(3)
+                .take_while(|c| c.remaining > 0 && c.remaining >= max / 10)
+                .count();
+
+            let mut n = n_largest;
+            // rev to start with the smallest of the n candidates
+            for c in group.iter_mut().take(n_largest).rev() {
+                // TODO: should this be evenly divided, or weighted by the size of the candidates?
+                let share = (num_bytes + n - 1) / n;
+                // we're only willing to evict at most half the state in each candidate but the
+                // last one
+                let evict = if n > 1 {
+                    cmp::min(c.remaining / 2, share)
+                } else {
+                    cmp::min(c.remaining, share)
+                };
+                if evict > 0 && c.first_evicted.is_none() {
+                    c.first_evicted = Some(num_evictions);
+                    num_evictions += 1;
+                }
+                c.remaining -= evict;
+                c.evicted += evict;
+                num_bytes -= evict;
+                n -= 1;
+            }
+        }
+
+        let mut evicted = group
+            .into_iter()
+            .filter_map(|c| Some((c.first_evicted?, c.candidate, c.evicted)))
+            .collect::<Vec<_>>();
+        evicted.sort_by_key(|(i, _, _)| *i);
+
+        // If there's no higher priority state to spill over to, the last candidate in the plan
+        // takes the rest
+        if Some(priority) == highest_priority {
+            if let Some((_, _, last)) = evicted.last_mut() {
+                *last += num_bytes;
+                num_bytes = 0;
+            }
+        }
+
+        plan.extend(evicted.into_iter().map(|(_, c, n)| (c, n)));
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+
+    #[test]
+    fn spreads_across_largest() {
+        let plan = plan_eviction(
+            [
+                ("a", 1000, CachePriority::Normal),
+                ("b", 50, CachePriority::Normal),
+                ("c", 400, CachePriority::Normal),
+                ("d", 600, CachePriority::Normal),
+            ],
+            300,
+        );
+        assert_eq!(plan, [("c", 100), ("d", 100), ("a", 100)]);
+    }
+
+    #[test]
+    fn last_candidate_takes_the_rest() {
+        let plan = plan_eviction(
+            [
+                ("a", 1000, CachePriority::Normal),
+                ("b", 100, CachePriority::Normal),
+            ],
+            600,
+        );
+        assert_eq!(plan, [("b", 50), ("a", 550)]);
+    }
+
+    #[test]
+    fn lowest_priority_first() {
+        let plan = plan_eviction(
+            [
+                ("a", 1000, CachePriority::High),
+                ("b", 1000, CachePriority::Normal),
+                ("c", 1000, CachePriority::Low),
+            ],
+            300,
+        );
+        assert_eq!(plan, [("c", 300)]);
+    }
+
+    #[test]
+    fn spills_over_to_higher_priorities() {
+        let plan = plan_eviction(
+            [
+                ("a", 1000, CachePriority::High),
+                ("b", 1000, CachePriority::Normal),
+                ("c", 100, CachePriority::Low),
+                ("d", 0, CachePriority::Low),
+            ],
+            300,
+        );
+        assert_eq!(plan, [("c", 100), ("b", 200)]);
+
+        let plan = plan_eviction(
+            [
+                ("a", 1000, CachePriority::High),
+                ("b", 100, CachePriority::Normal),
+                ("c", 100, CachePriority::Low),
+            ],
+            300,
+        );
+        assert_eq!(plan, [("c", 100), ("b", 100), ("a", 100)]);
+    }
+
+    #[test]
+    fn exhausts_lower_priority_before_spilling_over() {
+        let candidates = || {
+            (0..10)
+                .map(|i| (format!("low{i}"), 100, CachePriority::Low))
+                .chain(iter::once(("high".to_owned(), 1000, CachePriority::High)))
+        };
+
+        let plan = plan_eviction(candidates(), 500);
+        assert!(plan.iter().all(|(c, _)| c.starts_with("low")));
+        assert_eq!(plan.iter().map(|(_, n)| n).sum::<usize>(), 500);
+
+        let plan = plan_eviction(candidates(), 1200);
+        assert_eq!(plan.len(), 11);
+        assert!(plan[..10]
+            .iter()
+            .all(|(c, n)| c.starts_with("low") && *n == 100));
+        assert_eq!(plan[10], ("high".to_owned(), 200));
+    }
+
+    #[test]
+    fn nothing_to_evict() {
+        assert!(plan_eviction([("a", 0, CachePriority::Low)], 300).is_empty());
+        assert!(plan_eviction([("a", 1000, CachePriority::Low)], 0).is_empty());
+    }
+}
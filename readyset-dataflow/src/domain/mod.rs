@@ -1,8 +1,9 @@
 mod domain_metrics;
+mod eviction;
 mod replay_paths;
 
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
 use std::ops::Bound;
@@ -12,7 +13,7 @@ use std::{cell, cmp, mem, process, time};
 
 use ahash::RandomState;
 use dataflow_state::{
-    EvictBytesResult, MaterializedNodeState, PointKey, RangeKey, RangeLookupResult,
+    EvictBytesResult, HotKeys, MaterializedNodeState, PointKey, RangeKey, RangeLookupResult,
 };
 use failpoint_macros::failpoint;
 use futures_util::future::FutureExt;
@@ -20,6 +21,7 @@ use futures_util::stream::StreamExt;
 use futures_util::TryFutureExt;
 pub use internal::{DomainIndex, ReplicaAddress};
 use merging_interval_tree::IntervalTreeSet;
+use nom_sql::{CacheOptions, CachePriority, Relation};
 use petgraph::graph::NodeIndex;
 use readyset_client::internal::Index;
 use readyset_client::replication::ReplicationOffsetState;
@@ -32,9 +34,10 @@ use serde::{Deserialize, Serialize};
 use timekeeper::{RealTime, SimpleTracker, ThreadTime, Timer, TimerSet};
 use tokio::sync::mpsc::Sender;
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::{debug, error, trace, warn, Instrument};
+use tracing::{debug, error, info, trace, warn, Instrument};
 use vec1::Vec1;
 
+pub use self::eviction::plan_eviction;
 pub(crate) use self::replay_paths::ReplayPath;
 use self::replay_paths::{Destination, ReplayPathSpec, ReplayPaths, Target};
 use crate::node::special::EgressTx;
@@ -62,6 +65,22 @@ pub struct Config {
 
     #[serde(default)]
     pub eviction_kind: crate::EvictionKind,
+
+    /// The maximum number of the most recently read keys of each partial reader to periodically
+    /// save alongside the persisted base table state, so that they can be replayed into the reader
+    /// when it's recreated after a restart. Only used with [`DurabilityMode::Permanent`] and an
+    /// eviction kind that keeps track of reads. Set to 0 to disable.
+    #[serde(default)]
+    pub warm_restart_keys: usize,
+
+    /// How often the hottest keys of partial readers are saved, if at all (see
+    /// [`Config::warm_restart_keys`])
+    #[serde(default = "default_hot_keys_save_interval")]
+    pub hot_keys_save_interval: time::Duration,
+}
+
+fn default_hot_keys_save_interval() -> time::Duration {
+    time::Duration::from_secs(60)
 }
 
 const BATCH_SIZE: usize = 256;
@@ -364,6 +383,24 @@ pub struct DomainBuilder {
     pub persistence_parameters: PersistenceParameters,
     /// Configuration parameters for the domain.
     pub config: Config,
+    /// State handed over from the previous instance of the domain, if the domain is being moved
+    /// from another worker
+    #[serde(default)]
+    pub moved_from: Option<MovedDomainState>,
+}
+
+/// State handed over to a domain that's being moved to another worker from the instance of the
+/// domain running on the old worker
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MovedDomainState {
+    /// The tags of the replay paths of the old instance.
+    ///
+    /// Replays requested by the old instance can still be in flight when the new instance starts,
+    /// and are dropped by the new instance rather than treated as replays along an unknown path.
+    pub replay_tags: HashSet<Tag>,
+    /// All the keys in the partial readers of the old instance, which are replayed into the
+    /// readers of the new instance once they're ready
+    pub reader_keys: NodeMap<Vec<Vec<DfValue>>>,
 }
 
 impl DomainBuilder {
@@ -384,7 +421,7 @@ impl DomainBuilder {
         self,
         readers: Readers,
         channel_coordinator: Arc<ChannelCoordinator>,
-        state_size: Arc<AtomicUsize>,
+        state_size: Arc<StateSize>,
         init_state_tx: Sender<MaterializedState>,
     ) -> Domain {
         // initially, all nodes are not ready
@@ -395,11 +432,12 @@ impl DomainBuilder {
             .collect();
 
         let address = self.address();
+        let moved_from = self.moved_from.unwrap_or_default();
         Domain {
             index: self.index,
             shard: self.shard,
             replica: self.replica,
-            _nshards: self.nshards,
+            nshards: self.nshards,
 
             persistence_parameters: self.persistence_parameters,
             nodes: self.nodes,
@@ -410,6 +448,7 @@ impl DomainBuilder {
             waiting: Default::default(),
             reader_triggered: Default::default(),
             replay_paths: Default::default(),
+            previous_replay_tags: moved_from.replay_tags,
 
             ingress_inject: Default::default(),
 
@@ -417,10 +456,21 @@ impl DomainBuilder {
             channel_coordinator,
 
             timed_purges: Default::default(),
+            reader_expiries: Default::default(),
+            reader_expiry_deadlines: Default::default(),
+            reader_memory_limits: Default::default(),
+            next_reader_memory_limits_check: None,
+            warm_restart_keys: self.config.warm_restart_keys,
+            hot_keys_save_interval: self.config.hot_keys_save_interval,
+            hot_keys: Default::default(),
+            next_hot_keys_save: time::Instant::now() + self.config.hot_keys_save_interval,
+            reader_prefills: Default::default(),
+            moved_reader_keys: moved_from.reader_keys,
 
             delayed_for_self: Default::default(),
 
             state_size,
+            eviction_priorities: Default::default(),
             total_time: Timer::new(),
             total_ptime: Timer::new(),
             wait_time: Timer::new(),
@@ -450,6 +500,134 @@ struct TimedPurge {
     keys: HashSet<KeyComparison>,
 }
 
+/// The size of the partial state of a domain, which is shared with the worker running the domain
+/// so that it can decide which domains to evict from when it runs out of memory
+#[derive(Debug, Default)]
+pub struct StateSize {
+    /// The size of the partial state in the domain with each [`CachePriority`], in bytes, indexed
+    /// by the priority as a number
+    bytes: [AtomicUsize; CACHE_PRIORITIES.len()],
+}
+
+/// All [`CachePriority`]s, ordered by their value as a number
+const CACHE_PRIORITIES: [CachePriority; 3] = [
+    CachePriority::Low,
+    CachePriority::Normal,
+    CachePriority::High,
+];
+
+impl StateSize {
+    /// Returns the total size of all the partial state in the domain, in bytes
+    pub fn bytes(&self) -> usize {
+        self.bytes.iter().map(|b| b.load(Ordering::Acquire)).sum()
+    }
+
+    /// Returns the size of the partial state in the domain with each [`CachePriority`], in bytes
+    pub fn bytes_by_priority(&self) -> impl Iterator<Item = (CachePriority, usize)> + '_ {
+        CACHE_PRIORITIES
+            .into_iter()
+            .zip(&self.bytes)
+            .map(|(priority, bytes)| (priority, bytes.load(Ordering::Acquire)))
+    }
+
+    fn at(&self, priority: CachePriority) -> &AtomicUsize {
+        #[allow(clippy::indexing_slicing)] // there's an entry for every priority
+        &self.bytes[priority as usize]
+    }
+}
+
+/// Returns the priority of the state of the given node when evicting: the priority of the cache for
+/// readers, and the priority the controller gave the node (based on the caches it feeds) for the
+/// state of all other nodes
+fn eviction_priority(node: &Node, eviction_priorities: &NodeMap<CachePriority>) -> CachePriority {
+    match node.as_reader() {
+        Some(r) => r.cache_options().priority.unwrap_or_default(),
+        None => eviction_priorities
+            .get(node.local_addr())
+            .copied()
+            .unwrap_or_default(),
+    }
+}
+
+/// How long to keep replaying the keys a partial reader had before a restart or a move into the
+/// reader. Keys that still haven't been filled by then (for example because they were evicted
+/// again right after being filled) are given up on, so that the hottest keys of the reader can be
+/// saved again.
+const READER_PREFILL_EXPIRY: time::Duration = time::Duration::from_secs(5 * 60);
+
+/// How long after handling a packet readers with a memory limit are checked against their limit
+const READER_MEMORY_LIMITS_CHECK_INTERVAL: time::Duration = time::Duration::from_millis(100);
+
+/// The minimum interval with which readers with a TTL are checked for expired keys
+const MIN_EXPIRY_CHECK_INTERVAL: time::Duration = time::Duration::from_millis(100);
+
+/// Schedule for evicting keys from a partial reader created with a TTL or periodic refresh (see
+/// [`CacheOptions`])
+#[derive(Clone, Debug)]
+struct ReaderExpiry {
+    options: CacheOptions,
+    /// The next time the reader should be checked for keys that have outlived the TTL. Readers are
+    /// checked with a period of a quarter of the TTL, so keys are evicted at most 25% later than
+    /// they expire.
+    next_expiry_check: Option<time::Instant>,
+    /// The next time all keys should be evicted from the reader
+    next_refresh: Option<time::Instant>,
+}
+
+impl ReaderExpiry {
+    fn new(options: CacheOptions) -> Self {
+        let mut expiry = ReaderExpiry {
+            options,
+            next_expiry_check: None,
+            next_refresh: None,
+        };
+        expiry.schedule(time::Instant::now(), true);
+        expiry
+    }
+
+    /// Schedule the next expiry check after `now`, and the next refresh too if `refresh` is true
+    fn schedule(&mut self, now: time::Instant, refresh: bool) {
+        self.next_expiry_check = self
+            .options
+            .ttl
+            .map(|ttl| now + cmp::max(ttl / 4, MIN_EXPIRY_CHECK_INTERVAL));
+        if refresh {
+            self.next_refresh = self.options.refresh.map(|refresh| now + refresh);
+        }
+    }
+
+    /// Returns the next time anything needs to be evicted from the reader
+    fn deadline(&self) -> Option<time::Instant> {
+        self.next_expiry_check
+            .into_iter()
+            .chain(self.next_refresh)
+            .min()
+    }
+}
+
+/// Keys that were read most recently from a partial reader before a restart or a move, which
+/// are replayed into the reader in the background once it's ready so that it doesn't start out
+/// empty
+#[derive(Debug)]
+struct ReaderPrefill {
+    /// A handle to the reader, used to trigger replays for the keys
+    handle: backlog::SingleReadHandle,
+    /// The keys that haven't been filled in the reader yet
+    keys: Vec<KeyComparison>,
+    /// When we stop keeping track of the keys that haven't been filled yet
+    expires: time::Instant,
+}
+
+/// Converts keys read from a partial reader keyed on `key_len` columns into keys to replay into
+/// the reader, skipping any keys that don't fit the reader
+fn prefill_keys(keys: Vec<Vec<DfValue>>, key_len: usize) -> impl Iterator<Item = KeyComparison> {
+    keys.into_iter()
+        // The reader might have been recreated for a different query with the same name
+        .filter(move |key| key.len() == key_len)
+        .filter_map(|key| Vec1::try_from(key).ok())
+        .map(KeyComparison::Equal)
+}
+
 /// Mapping, for nodes which [generate columns][], from *upstream* keys, to downstream keys which
 /// have remapped to those upstream keys.
 ///
@@ -564,7 +742,7 @@ pub struct Domain {
     index: DomainIndex,
     shard: Option<usize>,
     replica: usize,
-    _nshards: usize,
+    nshards: usize,
 
     /// Map of nodes managed by this domain
     ///
@@ -600,6 +778,10 @@ pub struct Domain {
     /// Replay paths that go through this domain
     replay_paths: ReplayPaths,
 
+    /// The tags of the replay paths of the previous instance of this domain, if it was moved from
+    /// another worker
+    previous_replay_tags: HashSet<Tag>,
+
     /// Map from node ID to an interval tree of the keys of all current pending upqueries to that
     /// node
     reader_triggered: NodeMap<RequestedKeys>,
@@ -613,12 +795,71 @@ pub struct Domain {
     /// * Each node referenced by a `view` of a TimedPurge must be a reader node
     timed_purges: VecDeque<TimedPurge>,
 
+    /// Schedules for evicting keys from partial readers with a TTL or periodic refresh
+    ///
+    /// # Invariants
+    ///
+    /// * Each node in this map must be a partial reader node in `self.reader_write_handles`
+    reader_expiries: NodeMap<ReaderExpiry>,
+
+    /// The deadlines of the [`ReaderExpiry`]s in `reader_expiries`, earliest first, so that we
+    /// don't have to look at every reader to find the ones that are due.
+    ///
+    /// Entries for readers that have since been removed, or whose deadline has since changed, are
+    /// skipped once they're popped.
+    reader_expiry_deadlines: BinaryHeap<cmp::Reverse<(time::Instant, LocalNodeIndex)>>,
+
+    /// The maximum size in bytes of partial readers created with a memory limit (see
+    /// [`CacheOptions::memory_limit`])
+    ///
+    /// # Invariants
+    ///
+    /// * Each node in this map must be a partial reader node in `self.reader_write_handles`
+    reader_memory_limits: NodeMap<usize>,
+
+    /// If set, the next time the readers in `reader_memory_limits` should be checked against
+    /// their limits. Set when the domain handles a packet, which might have filled the readers.
+    next_reader_memory_limits_check: Option<time::Instant>,
+
+    /// The maximum number of keys of each partial reader to save for warm restarts (see
+    /// [`Config::warm_restart_keys`])
+    warm_restart_keys: usize,
+
+    /// How often to save the hottest keys of partial readers (see
+    /// [`Config::hot_keys_save_interval`])
+    hot_keys_save_interval: time::Duration,
+
+    /// The persistent storage the hottest keys of partial readers are periodically saved to
+    ///
+    /// # Invariants
+    ///
+    /// * Each node in this map must be a partial reader node in `self.reader_write_handles`
+    hot_keys: NodeMap<Arc<HotKeys>>,
+
+    /// The next time the hottest keys of partial readers should be saved
+    next_hot_keys_save: time::Instant,
+
+    /// Keys saved before a restart or a move that are still being replayed into partial readers
+    ///
+    /// # Invariants
+    ///
+    /// * Each node in this map must be a partial reader node in `self.reader_write_handles`
+    reader_prefills: NodeMap<ReaderPrefill>,
+
+    /// The keys in the partial readers of the previous instance of this domain, if it was moved
+    /// from another worker, which haven't been turned into [`ReaderPrefill`]s yet
+    moved_reader_keys: NodeMap<Vec<Vec<DfValue>>>,
+
     readers: Readers,
     channel_coordinator: Arc<ChannelCoordinator>,
 
     delayed_for_self: VecDeque<Box<Packet>>,
 
-    state_size: Arc<AtomicUsize>,
+    state_size: Arc<StateSize>,
+    /// The priority to evict the partial state of non-reader nodes with, which is the highest
+    /// priority of any of the caches the node feeds. Nodes not in this map have the default
+    /// priority.
+    eviction_priorities: NodeMap<CachePriority>,
     total_time: Timer<SimpleTracker, RealTime>,
     total_ptime: Timer<SimpleTracker, ThreadTime>,
     wait_time: Timer<SimpleTracker, RealTime>,
@@ -1137,6 +1378,13 @@ impl Domain {
                 return Ok(());
             }
 
+            if n.is_base() && self.replica != 0 {
+                // Replicas of base table domains other than the first are hot standbys, which keep
+                // their base table state up to date so they can be promoted if the first replica
+                // fails, but never send anything downstream
+                return Ok(());
+            }
+
             // normally, we ignore misses during regular forwarding.
             // however, we have to be a little careful in the case of joins.
             let evictions = if n.is_internal() && n.is_join()? && !misses.is_empty() {
@@ -1278,26 +1526,29 @@ impl Domain {
     ) -> ReadySetResult<()> {
         let me = message.dst();
 
-        let message = {
+        let (message, is_standby) = {
             let mut n = self
                 .nodes
                 .get(me)
                 .ok_or_else(|| ReadySetError::NoSuchNode(me.id()))?
                 .borrow_mut();
-            n.process_timestamp(
+            let message = n.process_timestamp(
                 message,
                 self.shard,
                 self.replica,
                 &mut self.reader_write_handles,
                 executor,
-            )?
+            )?;
+            (message, n.is_base() && self.replica != 0)
         };
 
-        let message = if let Some(m) = message {
-            m
-        } else {
-            // no message to send, so no need to run through children
-            return Ok(());
+        let message = match message {
+            Some(m) if !is_standby => m,
+            _ => {
+                // no message to send (or this is a hot standby replica of a base table domain,
+                // which never sends anything downstream), so no need to run through children
+                return Ok(());
+            }
         };
 
         #[allow(clippy::indexing_slicing)] // Already checked the node exists
@@ -1361,6 +1612,18 @@ impl Domain {
                         state.tear_down()?;
                     };
                     self.reader_write_handles.remove(node);
+                    self.reader_expiries.remove(node);
+                    self.reader_memory_limits.remove(node);
+                    self.reader_prefills.remove(node);
+                    if let Some(hot_keys) = self.hot_keys.remove(node) {
+                        // A save might still be in progress, in which case the keys are left
+                        // behind, to be overwritten if the reader is ever recreated
+                        if let Ok(hot_keys) = Arc::try_unwrap(hot_keys) {
+                            if let Err(error) = hot_keys.destroy() {
+                                debug!(%error, "could not remove hot keys of reader");
+                            }
+                        }
+                    }
                     self.metrics.set_node_state_size(node, 0);
                     trace!(local = node.id(), "node removed");
                 }
@@ -1626,6 +1889,7 @@ impl Domain {
                         #[allow(clippy::unwrap_used)] // checked it was a reader above
                         let r = n.as_mut_reader().unwrap();
 
+                        let key_len = index.columns.len();
                         let (r_part, w_part) = backlog::new_partial(
                             num_columns,
                             index,
@@ -1659,12 +1923,35 @@ impl Domain {
                                 }
                             },
                             self.eviction_kind,
+                            r.cache_options().ttl,
                             r.reader_processing().clone(),
                         );
+                        let cache_options = *r.cache_options();
 
                         let shard = *self.shard.as_ref().unwrap_or(&0);
-                        // TODO(ENG-838): Don't recreate every single node on leader failure.
-                        // This requires us to overwrite the existing reader.
+                        let hot_keys = self.open_hot_keys(&name);
+                        // If this domain was just moved, the keys of the reader it replaces
+                        // supersede the ones saved before a restart
+                        let prefill = match self.moved_reader_keys.remove(node) {
+                            Some(keys) => prefill_keys(keys, key_len).collect(),
+                            None => hot_keys
+                                .as_deref()
+                                .map(|hot_keys| self.load_hot_keys(hot_keys, key_len))
+                                .unwrap_or_default(),
+                        };
+                        if !prefill.is_empty() {
+                            self.reader_prefills.insert(
+                                node,
+                                ReaderPrefill {
+                                    handle: r_part.clone(),
+                                    keys: prefill,
+                                    expires: time::Instant::now() + READER_PREFILL_EXPIRY,
+                                },
+                            );
+                        }
+
+                        // The worker may still have a reader for this node from before the
+                        // domain was recreated, which we need to overwrite.
                         #[allow(clippy::unwrap_used)] // lock poisoning is unrecoverable
                         if self
                             .readers
@@ -1689,6 +1976,24 @@ impl Domain {
                         }
 
                         self.reader_write_handles.insert(node, w_part);
+                        if cache_options.ttl.is_some() || cache_options.refresh.is_some() {
+                            let expiry = ReaderExpiry::new(cache_options);
+                            if let Some(deadline) = expiry.deadline() {
+                                self.reader_expiry_deadlines
+                                    .push(cmp::Reverse((deadline, node)));
+                            }
+                            self.reader_expiries.insert(node, expiry);
+                        }
+                        if let Some(memory_limit) = cache_options.memory_limit {
+                            // The limit is for the whole cache, and each shard of the reader
+                            // holds (roughly) an equal part of it
+                            let memory_limit: usize = memory_limit.try_into().unwrap_or(usize::MAX);
+                            self.reader_memory_limits
+                                .insert(node, memory_limit / cmp::max(self.nshards, 1));
+                        }
+                        if let Some(hot_keys) = hot_keys {
+                            self.hot_keys.insert(node, hot_keys);
+                        }
                     }
                     PrepareStateKind::FullReader {
                         node_index,
@@ -1709,12 +2014,18 @@ impl Domain {
                                     expected_type: NodeType::Reader,
                                 })?;
 
+                        // The controller refuses to fully materialize readers with these options
+                        invariant!(
+                            r.cache_options().ttl.is_none() && r.cache_options().refresh.is_none(),
+                            "TTL and refresh are not supported for fully materialized readers"
+                        );
+
                         let (r_part, w_part) =
                             backlog::new(num_columns, index, r.reader_processing().clone());
 
                         let shard = *self.shard.as_ref().unwrap_or(&0);
-                        // TODO(ENG-838): Don't recreate every single node on leader failure.
-                        // This requires us to overwrite the existing reader.
+                        // The worker may still have a reader for this node from before the
+                        // domain was recreated, which we need to overwrite.
                         #[allow(clippy::unwrap_used)] // lock poisoning is unrecoverable
                         if self
                             .readers
@@ -1837,6 +2148,12 @@ impl Domain {
                     debug!(%from, "attempted to start a replay, but node is not ready yet");
                     return Ok(None);
                 }
+                // standby replicas of base tables never send anything downstream; the primary's
+                // replay is fanned out to every replica of the target domain
+                if self.replica != 0 && self.nodes[from].borrow().is_base() {
+                    debug!(%from, "not starting replay from standby replica");
+                    return Ok(None);
+                }
                 use std::thread;
                 invariant_eq!(
                     self.replay_paths
@@ -2026,6 +2343,13 @@ impl Domain {
                                 node_name.name,
                                 self.shard.unwrap_or(0),
                             );
+                            // Hot standby replicas of base table domains might be running on the
+                            // same machine as the primary, so they need their own files
+                            let base_name = if self.replica == 0 {
+                                base_name
+                            } else {
+                                format!("{}-{}", base_name, self.replica)
+                            };
 
                             let persistence_params = self.persistence_parameters.clone();
                             let init_state_tx = self.init_state_tx.clone();
@@ -2075,6 +2399,17 @@ impl Domain {
                     trace!(local = node_idx.id(), "readying empty node");
                 }
 
+                // now that the reader's replay paths are set up, start replaying the keys it had
+                // before a restart
+                if let Some(prefill) = self.reader_prefills.get(node_idx) {
+                    debug!(
+                        local = %node_idx,
+                        keys = prefill.keys.len(),
+                        "prefilling reader with hot keys"
+                    );
+                    prefill.handle.trigger(prefill.keys.iter().cloned());
+                }
+
                 // swap replayed reader nodes to expose new state
                 if let Some(state) = self.reader_write_handles.get_mut(node_idx) {
                     trace!(local = %node_idx, "swapping state");
@@ -2173,6 +2508,10 @@ impl Domain {
                 self.update_state_sizes();
                 Ok(None)
             }
+            DomainRequest::SetEvictionPriority { node, priority } => {
+                self.eviction_priorities.insert(node, priority);
+                Ok(None)
+            }
             DomainRequest::RequestReplicationOffsets => {
                 Ok(Some(bincode::serialize(&self.replication_offsets())?))
             }
@@ -2222,6 +2561,24 @@ impl Domain {
             DomainRequest::IsReady { node } => {
                 Ok(Some(bincode::serialize(&!self.not_ready.contains(&node))?))
             }
+            DomainRequest::SetReplica { replica } => {
+                info!(from = self.replica, to = replica, "changing replica index");
+                self.replica = replica;
+                Ok(None)
+            }
+            DomainRequest::ReaderKeys => {
+                let keys = self
+                    .reader_write_handles
+                    .iter()
+                    .filter(|(_, wh)| wh.is_partial())
+                    .map(|(node, wh)| (node, wh.keys()))
+                    .collect::<NodeMap<_>>();
+                Ok(Some(bincode::serialize(&keys)?))
+            }
+            DomainRequest::PendingReaderPrefills => {
+                let pending = self.update_reader_prefills();
+                Ok(Some(bincode::serialize(&pending)?))
+            }
         };
         // What we just did might have done things like insert into `self.delayed_for_self`, so
         // run the event loop before returning to make sure that gets processed.
@@ -2410,6 +2767,194 @@ impl Domain {
         Ok(())
     }
 
+    /// Evict keys that have outlived their TTL from readers whose expiry check is due, and all keys
+    /// from readers whose periodic refresh is due
+    fn handle_reader_expiries(&mut self) -> ReadySetResult<()> {
+        let now = time::Instant::now();
+        while let Some(&cmp::Reverse((deadline, node))) = self.reader_expiry_deadlines.peek() {
+            if deadline > now {
+                break;
+            }
+            self.reader_expiry_deadlines.pop();
+
+            let Some(expiry) = self.reader_expiries.get_mut(node) else {
+                continue;
+            };
+            if expiry.deadline() != Some(deadline) {
+                // Stale entry, the reader has been rescheduled since
+                continue;
+            }
+            let refresh = expiry.next_refresh.map_or(false, |t| t <= now);
+            expiry.schedule(now, refresh);
+            let priority = expiry.options.priority.unwrap_or_default();
+            if let Some(deadline) = expiry.deadline() {
+                self.reader_expiry_deadlines
+                    .push(cmp::Reverse((deadline, node)));
+            }
+
+            let Some(wh) = self.reader_write_handles.get_mut(node) else {
+                continue;
+            };
+            let freed = if refresh {
+                trace!(node = node.id(), "refreshing reader");
+                wh.evict_all()
+            } else {
+                wh.evict_expired()
+            };
+            wh.swap();
+            if freed > 0 {
+                wh.notify_readers_of_eviction()?;
+                debug!(%freed, node = node.id(), "evicted expired keys from reader");
+                self.state_size
+                    .at(priority)
+                    .fetch_sub(freed as usize, Ordering::AcqRel);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evict keys from readers which have grown larger than their memory limit, until they're back
+    /// within the limit
+    fn enforce_reader_memory_limits(&mut self) -> ReadySetResult<()> {
+        self.next_reader_memory_limits_check = None;
+        for (node, memory_limit) in self.reader_memory_limits.iter() {
+            let Some(wh) = self.reader_write_handles.get_mut(node) else {
+                continue;
+            };
+
+            let freed = wh.evict_to_limit(*memory_limit);
+            if freed > 0 {
+                wh.swap();
+                wh.notify_readers_of_eviction()?;
+                debug!(
+                    %freed,
+                    node = node.id(),
+                    memory_limit,
+                    "evicted from reader over its memory limit"
+                );
+                let priority = self
+                    .nodes
+                    .get(node)
+                    .map(|n| eviction_priority(&n.borrow(), &self.eviction_priorities))
+                    .unwrap_or_default();
+                self.state_size
+                    .at(priority)
+                    .fetch_sub(freed as usize, Ordering::AcqRel);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Opens the persistent storage for the hottest keys of the partial reader with the given
+    /// name, if the hottest keys of partial readers are saved at all
+    fn open_hot_keys(&self, name: &Relation) -> Option<Arc<HotKeys>> {
+        if self.warm_restart_keys == 0 || self.eviction_kind == EvictionKind::Random {
+            return None;
+        }
+
+        // Named the same way as the persistent state of base tables
+        let mut hot_keys_name = format!(
+            "{}-{}{}-{}",
+            self.persistence_parameters
+                .db_filename_prefix
+                .replace('-', "_"),
+            match &name.schema {
+                Some(schema) => format!("{schema}-"),
+                _ => "".into(),
+            },
+            name.name,
+            self.shard.unwrap_or(0),
+        );
+        if self.replica != 0 {
+            hot_keys_name.push_str(&format!("-{}", self.replica));
+        }
+
+        match HotKeys::open(&hot_keys_name, &self.persistence_parameters) {
+            Ok(hot_keys) => hot_keys.map(Arc::new),
+            Err(error) => {
+                warn!(%error, name = %hot_keys_name, "Failed to open hot keys of reader");
+                None
+            }
+        }
+    }
+
+    /// Load the keys saved for a partial reader keyed on `key_len` columns before a restart, to be
+    /// replayed into the reader once it's ready
+    fn load_hot_keys(&self, hot_keys: &HotKeys, key_len: usize) -> Vec<KeyComparison> {
+        match hot_keys.load() {
+            Ok(keys) => prefill_keys(keys, key_len)
+                .take(self.warm_restart_keys)
+                .collect(),
+            Err(error) => {
+                warn!(%error, "Failed to load hot keys of reader");
+                vec![]
+            }
+        }
+    }
+
+    /// Forget about the keys being replayed into partial readers after a restart or a move that
+    /// have been filled since we last checked, along with any readers whose prefill has expired,
+    /// and return the number of keys that are still being replayed
+    fn update_reader_prefills(&mut self) -> usize {
+        let now = time::Instant::now();
+        let mut pending = 0;
+        let mut done = Vec::new();
+        for (node, prefill) in self.reader_prefills.iter_mut() {
+            if let Some(wh) = self.reader_write_handles.get(node) {
+                prefill
+                    .keys
+                    .retain(|key| !wh.contains(key).unwrap_or(false));
+            }
+            if prefill.keys.is_empty() {
+                trace!(local = %node, "finished prefilling reader");
+                done.push(node);
+            } else if prefill.expires <= now {
+                debug!(
+                    local = %node,
+                    keys = prefill.keys.len(),
+                    "giving up on prefilling reader"
+                );
+                done.push(node);
+            } else {
+                pending += prefill.keys.len();
+            }
+        }
+        for node in done {
+            self.reader_prefills.remove(node);
+        }
+        pending
+    }
+
+    /// Save the hottest keys of all partial readers in the background
+    fn save_hot_keys(&mut self) {
+        self.next_hot_keys_save = time::Instant::now() + self.hot_keys_save_interval;
+
+        self.update_reader_prefills();
+        let saves = self
+            .hot_keys
+            .iter()
+            // Don't overwrite the keys saved before a restart while they're still being replayed
+            .filter(|(node, _)| !self.reader_prefills.contains_key(*node))
+            .filter_map(|(node, hot_keys)| {
+                let wh = self.reader_write_handles.get(node)?;
+                Some((hot_keys.clone(), wh.hottest_keys(self.warm_restart_keys)))
+            })
+            .collect::<Vec<_>>();
+        if saves.is_empty() {
+            return;
+        }
+
+        tokio::task::spawn_blocking(move || {
+            for (hot_keys, keys) in saves {
+                if let Err(error) = hot_keys.save(&keys) {
+                    warn!(%error, "Failed to save hot keys of reader");
+                }
+            }
+        });
+    }
+
     fn seed_row(&self, source: LocalNodeIndex, row: Cow<[DfValue]>) -> ReadySetResult<Record> {
         if let Some(&(start, ref defaults)) = self.ingress_inject.get(source) {
             let mut v = Vec::with_capacity(start + defaults.len());
@@ -2654,6 +3199,12 @@ impl Domain {
         let tag = m
             .tag()
             .ok_or_else(|| internal_err!("handle_replay called on an invalid message"))?;
+        if self.replay_paths.get(tag).is_none() && self.previous_replay_tags.contains(&tag) {
+            // This replay was requested by the instance of this domain that ran on another worker
+            // before the domain was moved, so nothing here is waiting for it
+            debug!(%tag, "Dropping replay for the previous instance of this domain");
+            return Ok(());
+        }
         #[allow(clippy::indexing_slicing)]
         // tag came from an internal data structure that guarantees it exists
         if self.nodes[self.replay_paths[tag].last_segment().node]
@@ -3798,7 +4349,8 @@ impl Domain {
         match m {
             Packet::Evict {
                 node,
-                mut num_bytes,
+                priority,
+                num_bytes,
             } => {
                 let start = std::time::Instant::now();
                 self.metrics.inc_eviction_requests();
@@ -3807,75 +4359,46 @@ impl Domain {
                 let nodes = if let Some(node) = node {
                     vec![(node, num_bytes)]
                 } else {
-                    let mut candidates: Vec<_> = self
-                        .nodes
-                        .values()
-                        .filter_map(|nd| {
-                            let n = &*nd.borrow();
-                            let local_index = n.local_addr();
-
-                            if let Some(wh) = self.reader_write_handles.get(local_index) {
-                                if wh.is_partial() {
-                                    Some(wh.deep_size_of())
-                                } else {
-                                    None
-                                }
+                    let candidates = self.nodes.values().filter_map(|nd| {
+                        let n = &*nd.borrow();
+                        let local_index = n.local_addr();
+
+                        if let Some(wh) = self.reader_write_handles.get(local_index) {
+                            if wh.is_partial() {
+                                Some(wh.deep_size_of())
                             } else {
-                                self.state
-                                    .get(local_index)
-                                    .filter(|state| state.is_partial())
-                                    .map(|state| state.deep_size_of())
+                                None
                             }
-                            .map(|s| (local_index, s))
+                        } else {
+                            self.state
+                                .get(local_index)
+                                .filter(|state| state.is_partial())
+                                .map(|state| state.deep_size_of())
+                        }
+                        .map(|s| {
+                            (
+                                local_index,
+                                s as usize,
+                                eviction_priority(n, &self.eviction_priorities),
+                            )
                         })
-                        .filter(|&(_, s)| s > 0)
-                        .map(|(x, s)| (x, s as usize))
-                        .collect();
-
-                    // we want to spread the eviction across the nodes,
-                    // rather than emptying out one node completely.
-                    // -1* so we sort in descending order
-                    // TODO: be smarter than 3 here
-                    candidates.sort_unstable_by_key(|&(_, s)| -(s as i64));
-                    candidates.truncate(3);
-
-                    // don't evict from tiny things (< 10% of max)
-                    #[allow(clippy::indexing_slicing)]
-                    // candidates must be at least nodes.len(), and nodes can't be empty
-                    if let Some(too_small_i) = candidates
-                        .iter()
-                        .position(|&(_, s)| s < candidates[0].1 / 10)
-                    {
-                        // everything beyond this is smaller, so also too small
-                        candidates.truncate(too_small_i);
-                    }
+                        .filter(|(_, _, p)| priority.map_or(true, |priority| *p == priority))
+                    });
 
-                    let mut n = candidates.len();
-                    // rev to start with the smallest of the n domains
-                    for (_, size) in candidates.iter_mut().rev() {
-                        // TODO: should this be evenly divided, or weighted by the size of the
-                        // domains?
-                        let share = (num_bytes + n - 1) / n;
-                        // we're only willing to evict at most half the state in each node
-                        // unless this is the only node left to evict from
-                        *size = if n > 1 {
-                            cmp::min(*size / 2, share)
-                        } else {
-                            assert_eq!(share, num_bytes);
-                            share
-                        };
-                        num_bytes -= *size;
-                        trace!(bytes = *size, node = ?n, "chose to evict from node");
-                        n -= 1;
+                    // we want to spread the eviction across the nodes, rather than emptying out
+                    // one node completely, and to evict from lower priority caches first
+                    let nodes = plan_eviction(candidates, num_bytes);
+                    for (node, bytes) in &nodes {
+                        trace!(bytes = *bytes, node = ?node, "chose to evict from node");
                     }
-
-                    candidates
+                    nodes
                 };
 
                 for (node, num_bytes) in nodes {
                     let mut freed = 0u64;
                     #[allow(clippy::indexing_slicing)] // we got the node from self.nodes
                     let n = self.nodes[node].borrow_mut();
+                    let node_priority = eviction_priority(&n, &self.eviction_priorities);
 
                     if n.is_dropped() {
                         continue; // Node was dropped. Skip.
@@ -3922,7 +4445,9 @@ impl Domain {
                     }
 
                     debug!(%freed, node = ?n, "evicted from node");
-                    self.state_size.fetch_sub(freed as usize, Ordering::AcqRel);
+                    self.state_size
+                        .at(node_priority)
+                        .fetch_sub(freed as usize, Ordering::AcqRel);
                     total_freed += freed;
                 }
 
@@ -4017,6 +4542,7 @@ impl Domain {
 
     pub fn update_state_sizes(&mut self) {
         let mut reader_size: u64 = 0;
+        let mut by_priority = [0u64; CACHE_PRIORITIES.len()];
         let total: u64 = self
             .nodes
             .values()
@@ -4024,7 +4550,7 @@ impl Domain {
                 let n = &*nd.borrow();
                 let local_index = n.local_addr();
 
-                if n.is_reader() {
+                let size = if n.is_reader() {
                     // We are a reader, which has its own kind of state
                     let mut size = 0;
                     if let Some(wh) = self.reader_write_handles.get(local_index) {
@@ -4041,7 +4567,12 @@ impl Domain {
                         .filter(|state| state.is_partial())
                         .map(|s| s.deep_size_of())
                         .unwrap_or(0)
+                };
+                #[allow(clippy::indexing_slicing)] // there's an entry for every priority
+                {
+                    by_priority[eviction_priority(n, &self.eviction_priorities) as usize] += size;
                 }
+                size
             })
             .sum();
 
@@ -4062,7 +4593,11 @@ impl Domain {
             total_node_state + reader_size,
         );
 
-        self.state_size.store(total as usize, Ordering::Release);
+        for (priority, size) in CACHE_PRIORITIES.into_iter().zip(by_priority) {
+            self.state_size
+                .at(priority)
+                .store(size as usize, Ordering::Release);
+        }
         // no response sent, as worker will read the atomic
     }
 
@@ -4095,18 +4630,24 @@ impl Domain {
             .collect()
     }
 
-    /// If there is a pending timed purge, return the duration until it needs
-    /// to happen
+    /// If there is a pending timed purge, reader expiry or save of the hottest keys of readers,
+    /// return the duration until it needs to happen
     pub fn next_poll_duration(&mut self) -> Option<time::Duration> {
         // when do we need to be woken up again?
         let now = time::Instant::now();
-        self.timed_purges.front().map(|tp| {
-            if tp.time > now {
-                tp.time - now
-            } else {
-                time::Duration::from_millis(0)
-            }
-        })
+        self.timed_purges
+            .front()
+            .map(|tp| tp.time)
+            .into_iter()
+            .chain(
+                self.reader_expiry_deadlines
+                    .peek()
+                    .map(|cmp::Reverse((deadline, _))| *deadline),
+            )
+            .chain(self.next_reader_memory_limits_check)
+            .chain((!self.hot_keys.is_empty()).then_some(self.next_hot_keys_save))
+            .min()
+            .map(|deadline| deadline.saturating_duration_since(now))
     }
 
     /// Handle a single message for this domain
@@ -4128,6 +4669,21 @@ impl Domain {
             self.handle(message, executor)?;
         }
 
+        // Checking readers against their memory limits after every packet would add up, so only
+        // do it every so often
+        if !self.reader_memory_limits.is_empty() {
+            match self.next_reader_memory_limits_check {
+                Some(deadline) if deadline <= time::Instant::now() => {
+                    self.enforce_reader_memory_limits()?
+                }
+                Some(_) => {}
+                None => {
+                    self.next_reader_memory_limits_check =
+                        Some(time::Instant::now() + READER_MEMORY_LIMITS_CHECK_INTERVAL)
+                }
+            }
+        }
+
         if self.aggressively_update_state_sizes {
             self.update_state_sizes();
         }
@@ -4149,6 +4705,21 @@ impl Domain {
             self.handle_timed_purges()?;
         }
 
+        if !self.reader_expiry_deadlines.is_empty() {
+            self.handle_reader_expiries()?;
+        }
+
+        if self
+            .next_reader_memory_limits_check
+            .map_or(false, |deadline| deadline <= time::Instant::now())
+        {
+            self.enforce_reader_memory_limits()?;
+        }
+
+        if !self.hot_keys.is_empty() && self.next_hot_keys_save <= time::Instant::now() {
+            self.save_hot_keys();
+        }
+
         if self.aggressively_update_state_sizes {
             self.update_state_sizes();
         }
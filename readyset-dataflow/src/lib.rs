@@ -63,7 +63,9 @@ pub use dataflow_state::{
     DurabilityMode, MaterializedNodeState, PersistenceParameters, PersistentState,
 };
 
-pub use crate::domain::{Domain, DomainBuilder, DomainIndex};
+pub use crate::domain::{
+    plan_eviction, Domain, DomainBuilder, DomainIndex, MovedDomainState, StateSize,
+};
 pub use crate::node_map::NodeMap;
 pub use crate::payload::{DomainRequest, Packet, PacketDiscriminants};
 pub use crate::processing::LookupIndex;
@@ -312,6 +312,12 @@ impl Node {
             .map_or(false, Ingredient::requires_full_materialization)
     }
 
+    /// Returns true if this operator needs to read the materialized state of its parents directly
+    pub fn requires_materialized_parents(&self) -> bool {
+        self.as_internal()
+            .map_or(false, Ingredient::requires_materialized_parents)
+    }
+
     pub fn can_query_through(&self) -> bool {
         self.as_internal()
             .map_or(false, Ingredient::can_query_through)
@@ -88,6 +88,10 @@ impl Clone for Egress {
 
 impl Egress {
     pub fn add_tx(&mut self, tx: EgressTx) {
+        // A tx may be added again for the same ingress node when the domain containing that node
+        // is recreated during recovery, in which case it replaces the existing one
+        self.txs
+            .retain(|existing| existing.node != tx.node || existing.shard != tx.shard);
         self.txs.push(tx);
     }
 
@@ -3,6 +3,7 @@ use std::time::SystemTime;
 use dataflow_expression::ReaderProcessing;
 use failpoint_macros::failpoint;
 use metrics::histogram;
+use nom_sql::CacheOptions;
 use readyset_client::metrics::recorded;
 use readyset_client::{KeyColumnIdx, ViewPlaceholder};
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,10 @@ pub struct Reader {
     ///
     /// The data is stored in this manner instead of in a Hashmap to support ordered iteration.
     placeholder_map: Vec<(ViewPlaceholder, KeyColumnIdx)>,
+
+    /// Options for the cache this reader was created for, such as the TTL for its entries
+    #[serde(default)]
+    cache_options: CacheOptions,
 }
 
 impl Clone for Reader {
@@ -34,6 +39,7 @@ impl Clone for Reader {
             reader_processing: self.reader_processing.clone(),
             index: self.index.clone(),
             placeholder_map: self.placeholder_map.clone(),
+            cache_options: self.cache_options,
         }
     }
 }
@@ -45,6 +51,7 @@ impl Reader {
             reader_processing,
             index: None,
             placeholder_map: Default::default(),
+            cache_options: Default::default(),
         }
     }
 
@@ -60,6 +67,7 @@ impl Reader {
             reader_processing: self.reader_processing.clone(),
             index: self.index.clone(),
             placeholder_map: self.placeholder_map.clone(),
+            cache_options: self.cache_options,
         }
     }
 
@@ -207,6 +215,16 @@ impl Reader {
         }
     }
 
+    /// Sets the options for the cache this reader was created for
+    pub fn set_cache_options(&mut self, cache_options: CacheOptions) {
+        self.cache_options = cache_options;
+    }
+
+    /// Returns the options for the cache this reader was created for
+    pub fn cache_options(&self) -> &CacheOptions {
+        &self.cache_options
+    }
+
     /// Get a reference to the reader's post lookup.
     pub fn reader_processing(&self) -> &ReaderProcessing {
         &self.reader_processing
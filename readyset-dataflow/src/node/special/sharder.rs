@@ -115,8 +115,10 @@ impl Sharder {
         num_shards: usize,
         replication: SenderReplication,
     ) {
-        debug_assert_eq!(self.txs.len(), 0);
         // TODO: add support for "shared" sharder?
+        // The child may be added again when the domain containing it is recreated during
+        // recovery, in which case it replaces the existing one
+        self.txs.clear();
         self.txs.extend((0..num_shards).map(|shard| SharderTx {
             node: dst_node,
             domain_index: dst_domain,
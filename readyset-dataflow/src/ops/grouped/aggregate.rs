@@ -22,6 +22,24 @@ pub enum Aggregation {
     Avg,
     /// Concatenates using the given separator between values.
     GroupConcat { separator: String },
+    /// Standard deviation of the `over` column, either of the sample (if `sample` is `true`) or of
+    /// the whole population. Maintains count, sum, and sum of squares in HashMap
+    Stddev { sample: bool },
+    /// Variance of the `over` column, either of the sample (if `sample` is `true`) or of the whole
+    /// population. Maintains count, sum, and sum of squares in HashMap
+    Variance { sample: bool },
+    /// Bitwise AND of the `over` column. Maintains a count of the set bits in HashMap
+    BitAnd,
+    /// Bitwise OR of the `over` column. Maintains a count of the set bits in HashMap
+    BitOr,
+    /// Bitwise XOR of the `over` column. Maintains a count of the set bits in HashMap
+    BitXor,
+    /// True if the `over` column is true for all records in the group. Maintains counts of true
+    /// and false values in HashMap
+    BoolAnd,
+    /// True if the `over` column is true for any record in the group. Maintains counts of true and
+    /// false values in HashMap
+    BoolOr,
 }
 
 impl Aggregation {
@@ -50,6 +68,11 @@ impl Aggregation {
                 }
             }
             Aggregation::GroupConcat { .. } => DfType::Text(/* TODO */ Collation::default()),
+            Aggregation::Stddev { .. } | Aggregation::Variance { .. } => DfType::Double,
+            Aggregation::BitAnd | Aggregation::BitOr | Aggregation::BitXor => {
+                DfType::UnsignedBigInt
+            }
+            Aggregation::BoolAnd | Aggregation::BoolOr => DfType::Bool,
         };
 
         Ok(GroupedOperator::new(
@@ -59,6 +82,9 @@ impl Aggregation {
                 over,
                 group: group_by.into(),
                 count_sum_map: RefCell::new(Default::default()),
+                variance_map: RefCell::new(Default::default()),
+                bit_count_map: RefCell::new(Default::default()),
+                bool_count_map: RefCell::new(Default::default()),
                 over_else: None,
                 out_ty,
             },
@@ -87,6 +113,15 @@ pub struct Aggregator {
     // We skip serde since we don't want the state, just the configuration.
     #[serde(skip)]
     count_sum_map: RefCell<HashMap<GroupHash, AverageDataPair>>,
+    // only needed for STDDEV and VARIANCE
+    #[serde(skip)]
+    variance_map: RefCell<HashMap<GroupHash, VarianceData>>,
+    // only needed for BIT_AND, BIT_OR and BIT_XOR
+    #[serde(skip)]
+    bit_count_map: RefCell<HashMap<GroupHash, BitCounts>>,
+    // only needed for BOOL_AND and BOOL_OR
+    #[serde(skip)]
+    bool_count_map: RefCell<HashMap<GroupHash, BoolCounts>>,
     over_else: Option<Literal>,
     // Output type of this column
     out_ty: DfType,
@@ -130,6 +165,143 @@ impl AverageDataPair {
     }
 }
 
+/// For storing (Count, Sum, Sum of squares) in additional state for Stddev and Variance.
+#[derive(Debug, Clone, Default)]
+struct VarianceData {
+    count: u64,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl VarianceData {
+    fn apply_diff(&mut self, d: NumericalDiff) -> ReadySetResult<()> {
+        let value = f64::try_from(&d.value)?;
+        if d.positive {
+            self.count += 1;
+            self.sum += value;
+            self.sum_sq += value * value;
+        } else {
+            self.count = self.count.saturating_sub(1);
+            self.sum -= value;
+            self.sum_sq -= value * value;
+        }
+        Ok(())
+    }
+
+    /// Returns the variance of the values seen so far, or NULL if there aren't enough values
+    fn variance(&self, sample: bool) -> DfValue {
+        let divisor = if sample {
+            self.count.saturating_sub(1)
+        } else {
+            self.count
+        };
+        if divisor == 0 {
+            return DfValue::None;
+        }
+
+        let count = self.count as f64;
+        // Subtracting the squared mean can introduce small negative rounding errors
+        let variance = ((self.sum_sq - self.sum * self.sum / count) / divisor as f64).max(0.0);
+        DfValue::Double(variance)
+    }
+}
+
+/// For storing the number of non-null values, and the number of those values with each bit set,
+/// in additional state for BitAnd, BitOr and BitXor.
+#[derive(Debug, Clone)]
+struct BitCounts {
+    count: u64,
+    set: [u64; 64],
+}
+
+impl Default for BitCounts {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            set: [0; 64],
+        }
+    }
+}
+
+impl BitCounts {
+    fn apply_diff(&mut self, d: NumericalDiff) -> ReadySetResult<()> {
+        // Operate on the two's complement representation of the value, converting non-integer
+        // values to integers first
+        let bits = match i128::try_from(&d.value) {
+            Ok(v) => v as u64,
+            Err(_) => i64::try_from(&d.value.coerce_to(&DfType::BigInt, &DfType::Unknown)?)? as u64,
+        };
+        let update = |n: &mut u64| {
+            if d.positive {
+                *n += 1
+            } else {
+                *n = n.saturating_sub(1)
+            }
+        };
+
+        update(&mut self.count);
+        for (i, n) in self.set.iter_mut().enumerate() {
+            if bits & (1 << i) != 0 {
+                update(n);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the result of the given bitwise aggregation over the values seen so far, which is
+    /// the identity of the operation if there are no values
+    fn result(&self, op: &Aggregation) -> DfValue {
+        let bit_is_set = |n: u64| match op {
+            Aggregation::BitAnd => n == self.count,
+            Aggregation::BitOr => n > 0,
+            Aggregation::BitXor => n % 2 == 1,
+            _ => false,
+        };
+        let bits = self
+            .set
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| bit_is_set(**n))
+            .fold(0u64, |bits, (i, _)| bits | (1 << i));
+        DfValue::from(bits)
+    }
+}
+
+/// For storing the counts of true and false values in additional state for BoolAnd and BoolOr.
+#[derive(Debug, Clone, Default)]
+struct BoolCounts {
+    true_count: u64,
+    false_count: u64,
+}
+
+impl BoolCounts {
+    fn apply_diff(&mut self, d: NumericalDiff) {
+        let n = if d.value.is_truthy() {
+            &mut self.true_count
+        } else {
+            &mut self.false_count
+        };
+        if d.positive {
+            *n += 1;
+        } else {
+            *n = n.saturating_sub(1);
+        }
+    }
+
+    /// Returns the result of the given boolean aggregation over the values seen so far, or NULL if
+    /// there are no values
+    fn result(&self, op: &Aggregation) -> DfValue {
+        if self.true_count + self.false_count == 0 {
+            return DfValue::None;
+        }
+
+        match op {
+            Aggregation::BoolAnd => (self.false_count == 0).into(),
+            _ => (self.true_count > 0).into(),
+        }
+    }
+}
+
 impl Aggregator {
     fn group_hash(&self, rec: &[DfValue]) -> GroupHash {
         let mut hasher = DefaultHasher::new();
@@ -144,6 +316,26 @@ impl Aggregator {
     }
 
     fn new_data(&self) -> ReadySetResult<DfValue> {
+        // These aggregations compute their value from additional state, and are NULL until
+        // they've seen a non-null value
+        if matches!(
+            self.op,
+            Aggregation::Stddev { .. }
+                | Aggregation::Variance { .. }
+                | Aggregation::BoolAnd
+                | Aggregation::BoolOr
+        ) {
+            return Ok(DfValue::None);
+        }
+        // The bitwise aggregations are the identity of their operation until they've seen a
+        // non-null value
+        if matches!(
+            self.op,
+            Aggregation::BitAnd | Aggregation::BitOr | Aggregation::BitXor
+        ) {
+            return Ok(BitCounts::default().result(&self.op));
+        }
+
         match &self.out_ty {
             DfType::BigInt => Ok(DfValue::Int(Default::default())),
             DfType::Double => Ok(DfValue::Double(Default::default())),
@@ -215,21 +407,53 @@ impl GroupedOperation for Aggregator {
                 .apply_diff(diff)
         };
 
-        let apply_diff =
-            |curr: ReadySetResult<DfValue>, diff: Self::Diff| -> ReadySetResult<DfValue> {
-                if diff.value.is_none() {
-                    return curr;
-                }
+        let apply_variance = |diff: Self::Diff| -> ReadySetResult<DfValue> {
+            let mut variance_map = self.variance_map.borrow_mut();
+            let data = variance_map.entry(diff.group_hash).or_default();
+            data.apply_diff(diff)?;
+            Ok(match self.op {
+                Aggregation::Stddev { sample } => match data.variance(sample) {
+                    DfValue::Double(variance) => DfValue::Double(variance.sqrt()),
+                    v => v,
+                },
+                Aggregation::Variance { sample } => data.variance(sample),
+                _ => internal!("Not a variance aggregation"),
+            })
+        };
 
-                match self.op {
-                    Aggregation::Count { .. } => apply_count(curr?, diff),
-                    Aggregation::Sum => apply_sum(curr?, diff),
-                    Aggregation::Avg => apply_avg(curr?, diff),
-                    Aggregation::GroupConcat { separator: _ } => internal!(
-                        "GroupConcats are separate from the other aggregations in the dataflow."
-                    ),
-                }
-            };
+        let apply_bits = |diff: Self::Diff| -> ReadySetResult<DfValue> {
+            let mut bit_count_map = self.bit_count_map.borrow_mut();
+            let counts = bit_count_map.entry(diff.group_hash).or_default();
+            counts.apply_diff(diff)?;
+            Ok(counts.result(&self.op))
+        };
+
+        let apply_bool = |diff: Self::Diff| -> ReadySetResult<DfValue> {
+            let mut bool_count_map = self.bool_count_map.borrow_mut();
+            let counts = bool_count_map.entry(diff.group_hash).or_default();
+            counts.apply_diff(diff);
+            Ok(counts.result(&self.op))
+        };
+
+        let apply_diff = |curr: ReadySetResult<DfValue>,
+                          diff: Self::Diff|
+         -> ReadySetResult<DfValue> {
+            if diff.value.is_none() {
+                return curr;
+            }
+
+            match self.op {
+                Aggregation::Count { .. } => apply_count(curr?, diff),
+                Aggregation::Sum => apply_sum(curr?, diff),
+                Aggregation::Avg => apply_avg(curr?, diff),
+                Aggregation::Stddev { .. } | Aggregation::Variance { .. } => apply_variance(diff),
+                Aggregation::BitAnd | Aggregation::BitOr | Aggregation::BitXor => apply_bits(diff),
+                Aggregation::BoolAnd | Aggregation::BoolOr => apply_bool(diff),
+                Aggregation::GroupConcat { separator: _ } => internal!(
+                    "GroupConcats are separate from the other aggregations in the dataflow."
+                ),
+            }
+        };
 
         diffs
             .fold(Ok(current.cloned().unwrap_or(self.new_data()?)), apply_diff)
@@ -245,6 +469,13 @@ impl GroupedOperation for Aggregator {
                 Aggregation::GroupConcat { separator: ref s } => {
                     format!("||({})", s)
                 }
+                Aggregation::Stddev { .. } => "σ".to_owned(),
+                Aggregation::Variance { .. } => "σ²".to_owned(),
+                Aggregation::BitAnd => "&".to_owned(),
+                Aggregation::BitOr => "|".to_owned(),
+                Aggregation::BitXor => "^".to_owned(),
+                Aggregation::BoolAnd => "∧".to_owned(),
+                Aggregation::BoolOr => "∨".to_owned(),
             };
         }
 
@@ -253,6 +484,19 @@ impl GroupedOperation for Aggregator {
             Aggregation::Sum => format!("𝛴({})", self.over),
             Aggregation::Avg => format!("Avg({})", self.over),
             Aggregation::GroupConcat { separator: ref s } => format!("||({}, {})", s, self.over),
+            Aggregation::Stddev { sample } => format!(
+                "Stddev{}({})",
+                if sample { "Samp" } else { "Pop" },
+                self.over
+            ),
+            Aggregation::Variance { sample } => {
+                format!("Var{}({})", if sample { "Samp" } else { "Pop" }, self.over)
+            }
+            Aggregation::BitAnd => format!("&({})", self.over),
+            Aggregation::BitOr => format!("|({})", self.over),
+            Aggregation::BitXor => format!("^({})", self.over),
+            Aggregation::BoolAnd => format!("∧({})", self.over),
+            Aggregation::BoolOr => format!("∨({})", self.over),
         };
         let group_cols = self
             .group
@@ -1116,4 +1360,150 @@ mod tests {
             .into()
         );
     }
+
+    /// Returns the aggregated value in the positive record emitted by an aggregation
+    fn emitted_value(out: Records) -> DfValue {
+        out.into_iter()
+            .find_map(|r| match r {
+                Record::Positive(r) => Some(r[1].clone()),
+                Record::Negative(_) => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn stddev_and_variance_forwards() {
+        let mut stddev_pop = setup(Aggregation::Stddev { sample: false }, true);
+        let mut var_samp = setup(Aggregation::Variance { sample: true }, true);
+
+        let mut last = (DfValue::None, DfValue::None);
+        for y in [2, 4, 4, 4, 5, 5, 7, 9] {
+            last = (
+                emitted_value(stddev_pop.narrow_one_row(vec![1.into(), y.into()], true)),
+                emitted_value(var_samp.narrow_one_row(vec![1.into(), y.into()], true)),
+            );
+        }
+        assert_eq!(last.0, DfValue::Double(2.0));
+        assert_eq!(last.1, DfValue::Double(32.0 / 7.0));
+
+        // Retracting values should give the statistic over the remaining values
+        let out = stddev_pop.narrow_one_row((vec![1.into(), 9.into()], false), true);
+        let expected =
+            (((2 * 2 + 3 * 4 * 4 + 2 * 5 * 5 + 7 * 7) as f64 - 961.0 / 7.0) / 7.0).sqrt();
+        match emitted_value(out) {
+            DfValue::Double(v) => assert!((v - expected).abs() < 1e-9),
+            v => panic!("Unexpected value {v:?}"),
+        }
+    }
+
+    #[test]
+    fn sample_variance_of_one_value_is_null() {
+        let mut c = setup(Aggregation::Variance { sample: true }, true);
+        let out = c.narrow_one_row(vec![1.into(), 1.into()], true);
+        assert_eq!(emitted_value(out), DfValue::None);
+    }
+
+    #[test]
+    fn bitwise_forwards() {
+        let mut and = setup(Aggregation::BitAnd, true);
+        let mut or = setup(Aggregation::BitOr, true);
+        let mut xor = setup(Aggregation::BitXor, true);
+
+        let mut apply = |y: i32, positive: bool| {
+            (
+                emitted_value(and.narrow_one_row((vec![1.into(), y.into()], positive), true)),
+                emitted_value(or.narrow_one_row((vec![1.into(), y.into()], positive), true)),
+                emitted_value(xor.narrow_one_row((vec![1.into(), y.into()], positive), true)),
+            )
+        };
+
+        apply(0b1100, true);
+        assert_eq!(
+            apply(0b1010, true),
+            (
+                DfValue::from(0b1000u64),
+                DfValue::from(0b1110u64),
+                DfValue::from(0b0110u64)
+            )
+        );
+        assert_eq!(
+            apply(0b1100, false),
+            (
+                DfValue::from(0b1010u64),
+                DfValue::from(0b1010u64),
+                DfValue::from(0b1010u64)
+            )
+        );
+    }
+
+    #[test]
+    fn bitwise_negative_values() {
+        let mut and = setup(Aggregation::BitAnd, true);
+        let mut or = setup(Aggregation::BitOr, true);
+        let mut xor = setup(Aggregation::BitXor, true);
+
+        let mut apply = |y: i32| {
+            (
+                emitted_value(and.narrow_one_row(vec![1.into(), y.into()], true)),
+                emitted_value(or.narrow_one_row(vec![1.into(), y.into()], true)),
+                emitted_value(xor.narrow_one_row(vec![1.into(), y.into()], true)),
+            )
+        };
+
+        assert_eq!(
+            apply(-2),
+            (
+                DfValue::from(u64::MAX - 1),
+                DfValue::from(u64::MAX - 1),
+                DfValue::from(u64::MAX - 1)
+            )
+        );
+        assert_eq!(
+            apply(5),
+            (
+                DfValue::from(4u64),
+                DfValue::from(u64::MAX),
+                DfValue::from((u64::MAX - 1) ^ 5)
+            )
+        );
+    }
+
+    #[test]
+    fn bitwise_of_only_nulls() {
+        let mut and = setup(Aggregation::BitAnd, true);
+        let mut or = setup(Aggregation::BitOr, true);
+        let mut xor = setup(Aggregation::BitXor, true);
+
+        let row = vec![1.into(), DfValue::None];
+        assert_eq!(
+            emitted_value(and.narrow_one_row(row.clone(), true)),
+            DfValue::from(u64::MAX)
+        );
+        assert_eq!(
+            emitted_value(or.narrow_one_row(row.clone(), true)),
+            DfValue::from(0u64)
+        );
+        assert_eq!(
+            emitted_value(xor.narrow_one_row(row, true)),
+            DfValue::from(0u64)
+        );
+    }
+
+    #[test]
+    fn boolean_forwards() {
+        let mut and = setup(Aggregation::BoolAnd, true);
+        let mut or = setup(Aggregation::BoolOr, true);
+
+        let mut apply = |y: bool, positive: bool| {
+            (
+                emitted_value(and.narrow_one_row((vec![1.into(), y.into()], positive), true)),
+                emitted_value(or.narrow_one_row((vec![1.into(), y.into()], positive), true)),
+            )
+        };
+
+        assert_eq!(apply(true, true), (true.into(), true.into()));
+        assert_eq!(apply(false, true), (false.into(), true.into()));
+        // Retracting the only false value makes BOOL_AND true again
+        assert_eq!(apply(false, false), (true.into(), true.into()));
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 
+use dataflow_expression::Expr;
 use dataflow_state::PointKey;
 use itertools::Itertools;
 use readyset_client::KeyComparison;
@@ -23,6 +24,8 @@ pub enum JoinType {
     Left,
     /// Inner join between two views
     Inner,
+    /// Full outer join between two views
+    Full,
 }
 
 /// Where to source a join column
@@ -36,7 +39,7 @@ pub enum JoinSource {
     B(usize, usize),
 }
 
-/// Join provides a left outer join between two views.
+/// Join provides an inner, left outer, or full outer join between two views.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Join {
     left: IndexPair,
@@ -60,6 +63,11 @@ pub struct Join {
     generated_column_buffer: HashMap<(Vec<usize>, Side), Records>,
 
     kind: JoinType,
+
+    /// Additional condition, evaluated on the emitted rows, that a pair of rows which are equal on
+    /// the join key must satisfy to match. For left joins, rows in the left with no matches that
+    /// satisfy this condition are emitted padded with nulls.
+    residual: Option<Expr>,
 }
 
 impl Join {
@@ -132,6 +140,23 @@ impl Join {
             in_place_right_emit,
             generated_column_buffer: Default::default(),
             kind,
+            residual: None,
+        }
+    }
+
+    /// Evaluate the given expression, in terms of the columns emitted by this join, on each pair
+    /// of rows that are equal on the join key, and only consider them to match if it is truthy.
+    pub fn with_residual(mut self, residual: Expr) -> Self {
+        self.residual = Some(residual);
+        self
+    }
+
+    /// Returns true if rows on the given side of this join with no matches on the other side are
+    /// emitted padded with nulls
+    fn preserves(&self, side: Side) -> bool {
+        match side {
+            Side::Left => matches!(self.kind, JoinType::Left | JoinType::Full),
+            Side::Right => self.kind == JoinType::Full,
         }
     }
 
@@ -153,6 +178,15 @@ impl Join {
             .collect()
     }
 
+    /// Returns true if the pair of `left` and `right` rows (which must be equal on the join key)
+    /// satisfies the residual condition of this join, if any
+    fn residual_matches(&self, left: &[DfValue], right: &[DfValue]) -> ReadySetResult<bool> {
+        match &self.residual {
+            Some(residual) => Ok(residual.eval(&self.generate_row(left, right))?.is_truthy()),
+            None => Ok(true),
+        }
+    }
+
     fn handle_replay_for_generated(
         &self,
         left: Records,
@@ -173,7 +207,9 @@ impl Join {
                 .iter()
                 .filter(|r| rec.indices(from_key.clone()) == r.indices(other_key.clone()))
             {
-                ret.push(Record::Positive(self.generate_row(&rec, other_rec.row())))
+                if self.residual_matches(&rec, other_rec.row())? {
+                    ret.push(Record::Positive(self.generate_row(&rec, other_rec.row())))
+                }
             }
         }
         Ok(ret.into())
@@ -193,6 +229,82 @@ impl Join {
             .collect()
     }
 
+    /// Generate a row for the given row from the right, with all columns from the left set to
+    /// null (including join key columns, which are always emitted from the left)
+    fn generate_null_left(&self, right: &[DfValue]) -> Vec<DfValue> {
+        self.emit
+            .iter()
+            .map(|&(side, col)| {
+                if side == Side::Right {
+                    right[col].clone()
+                } else {
+                    DfValue::None
+                }
+            })
+            .collect()
+    }
+
+    /// Generate a null-padded row for a row from the given side with no matches on the other side
+    fn generate_null_for(&self, side: Side, row: &[DfValue]) -> Vec<DfValue> {
+        match side {
+            Side::Left => self.generate_null(row),
+            Side::Right => self.generate_null_left(row),
+        }
+    }
+
+    /// Returns all the rows in the right parent that have no matches in the left parent, padded
+    /// with nulls. Used to emit the right-hand side of a full outer join at the end of a full
+    /// replay, which always comes from the left parent.
+    fn unmatched_right_rows(
+        &self,
+        nodes: &DomainNodes,
+        state: &StateMap,
+    ) -> ReadySetResult<Vec<Record>> {
+        let right_state = state.get(*self.right).ok_or_else(|| {
+            internal_err!("Right parent of a full outer join must be materialized")
+        })?;
+
+        let mut ret = vec![];
+        for right in right_state.cloned_records() {
+            let key = self
+                .on_right()
+                .into_iter()
+                .map(|c| right[c].clone())
+                .collect::<Vec<_>>();
+            // See [note: null-join-keys]
+            let left_rows = if key.iter().any(|v| v.is_none()) {
+                vec![]
+            } else {
+                match self.lookup(
+                    *self.left,
+                    &self.on_left(),
+                    &PointKey::from(key.into_iter()),
+                    nodes,
+                    state,
+                    LookupMode::Strict,
+                )? {
+                    IngredientLookupResult::Records(rs) => rs.collect::<Result<Vec<_>, _>>()?,
+                    IngredientLookupResult::Miss => {
+                        internal!("Lookup into fully materialized left parent of join missed")
+                    }
+                }
+            };
+
+            let mut matched = false;
+            for left in &left_rows {
+                if self.residual_matches(left, &right)? {
+                    matched = true;
+                    break;
+                }
+            }
+            if !matched {
+                ret.push(Record::Positive(self.generate_null_left(&right)));
+            }
+        }
+
+        Ok(ret)
+    }
+
     fn resolve_col(&self, col: usize) -> (Option<usize>, Option<usize>) {
         let (side, pcol) = self.emit[col];
 
@@ -237,6 +349,11 @@ impl Ingredient for Join {
         Some(Some(self.left.as_global()).into_iter().collect())
     }
 
+    fn requires_materialized_parents(&self) -> bool {
+        // We scan the right parent for unmatched rows at the end of full replays
+        self.kind == JoinType::Full
+    }
+
     fn on_connected(&mut self, _g: &Graph) {}
 
     impl_replace_sibling!(left, right);
@@ -344,14 +461,32 @@ impl Ingredient for Join {
             }
         };
 
+        // Full replays only ever come from the left parent (see `must_replay_among`), so at the
+        // end of one we have to emit the rows in the right parent of a full outer join that never
+        // matched any of the replayed rows
+        let unmatched_right_rows = if self.kind == JoinType::Full
+            && matches!(replay, ReplayContext::Full { last: true })
+        {
+            self.unmatched_right_rows(nodes, state)?
+        } else {
+            vec![]
+        };
+
         if rs.is_empty() {
             return Ok(ProcessingResult {
-                results: rs,
+                results: unmatched_right_rows.into(),
                 ..Default::default()
             });
         }
 
-        let mut ret: Vec<Record> = Vec::with_capacity(rs.len());
+        let mut ret: Vec<Record> = Vec::with_capacity(rs.len() + unmatched_right_rows.len());
+        ret.extend(unmatched_right_rows);
+
+        let (from_side, other_side) = if from_left {
+            (Side::Left, Side::Right)
+        } else {
+            (Side::Right, Side::Left)
+        };
 
         let grouped_records = rs
             .into_iter()
@@ -370,6 +505,8 @@ impl Ingredient for Join {
         };
 
         for (join_key, group) in grouped_records.into_iter() {
+            let group = group.collect::<Vec<_>>();
+
             // [note: null-join-keys]
             // The semantics of NULL in SQL are tri-state - while obviously `1 = 1`, it is *not* the
             // case that `null = null`. Usually this is irrelevant for lookups into state since it's
@@ -380,15 +517,24 @@ impl Ingredient for Join {
             // type.
             let nulls = join_key.iter().any(|v| v.is_none());
 
-            // The difference between a left join and an inner join, is that for the former we must
-            // emit rows with nulls even if we later get no match in the other side.
-
-            let mut new_right_count = None;
-
-            if self.kind == JoinType::Left && !from_left {
+            // The difference between an outer join and an inner join, is that for the former we
+            // must emit rows with nulls even if we later get no match in the other
+            // side.
+
+            let mut new_from_count = None;
+            // If we have a residual condition, whether a row in the other side has any matches
+            // depends on the row itself, so we need all the rows in this side rather than just
+            // their count
+            let mut new_from_rows = None;
+
+            // During a full replay we can't tell which rows on the other side used to have matches,
+            // since the whole state of this side is already there - but in that case the other
+            // side is the right, and its unmatched rows are emitted at the end of the replay
+            // instead.
+            if self.preserves(other_side) && !matches!(replay, ReplayContext::Full { .. }) {
                 let rc = self.lookup(
-                    *self.right,
-                    &self.on_right(),
+                    from,
+                    &from_key,
                     &PointKey::from(join_key.iter().cloned()),
                     nodes,
                     state,
@@ -399,8 +545,8 @@ impl Ingredient for Join {
                     IngredientLookupResult::Records(rc) => {
                         if replay_key_cols.is_some() && !nulls {
                             lookups.push(Lookup {
-                                on: *self.right,
-                                cols: self.on_right(),
+                                on: from,
+                                cols: from_key.clone(),
                                 key: join_key
                                     .clone()
                                     .try_into()
@@ -408,11 +554,14 @@ impl Ingredient for Join {
                             });
                         }
 
-                        let rc = rc.count();
-                        new_right_count = Some(rc);
+                        if self.residual.is_some() {
+                            new_from_rows = Some(rc.collect::<Result<Vec<_>, _>>()?);
+                        } else {
+                            new_from_count = Some(rc.count());
+                        }
                     }
                     IngredientLookupResult::Miss => {
-                        // we got something from right, but that row's key is not in right??
+                        // we got something from this side, but that row's key is not in this side??
                         //
                         // this *can* happen! imagine if you have two partial indices on right,
                         // one on column a and one on column b. imagine that a is the join key.
@@ -441,7 +590,7 @@ impl Ingredient for Join {
             let other_records = match other_lookup.take() {
                 IngredientLookupResult::Records(recs) => recs,
                 IngredientLookupResult::Miss => {
-                    misses.extend(group.map(|record| {
+                    misses.extend(group.into_iter().map(|record| {
                         Miss::builder()
                             .on(other)
                             .lookup_idx(other_key.clone())
@@ -468,38 +617,76 @@ impl Ingredient for Join {
             let other_rows = other_records.collect::<Result<Vec<_>, _>>()?;
 
             let mut rc_diff = 0isize;
-            for r in group {
-                let (row, positive) = r.extract();
+            for r in &group {
+                let (row, positive) = (r.rec(), r.is_positive());
 
                 rc_diff += if positive { 1 } else { -1 };
 
-                if other_rows.is_empty() {
-                    if self.kind == JoinType::Left && from_left {
-                        // left join, got a thing from left, no rows in right == NULL
-                        ret.push((self.generate_null(&row), positive).into());
-                    }
-                } else {
-                    for other in other_rows.iter() {
-                        if from == *self.left {
-                            ret.push((self.generate_row(&row, other), positive).into());
-                        } else {
-                            ret.push((self.generate_row(other, &row), positive).into());
-                        }
+                let mut matched = false;
+                for other in other_rows.iter() {
+                    let (left, right) = if from_left {
+                        (row, &**other)
+                    } else {
+                        (&**other, row)
+                    };
+                    if self.residual_matches(left, right)? {
+                        matched = true;
+                        ret.push((self.generate_row(left, right), positive).into());
                     }
                 }
+
+                if !matched && self.preserves(from_side) {
+                    // outer join, got a thing from a preserved side, no matching rows in the other
+                    // side == NULL
+                    ret.push((self.generate_null_for(from_side, row), positive).into());
+                }
             }
 
-            // For a left join with updates from the right side, we also have to emit/delete NULL
-            // rows if row count changed to/from zero
-            if let Some(new_rc) = new_right_count {
+            // For an outer join with updates from the side opposite a preserved one, we also have
+            // to emit/delete NULL rows if row count changed to/from zero
+            if let Some(new_rc) = new_from_count {
                 let old_rc = new_rc as isize - rc_diff;
                 if new_rc == 0 && old_rc != 0 {
                     for other in other_rows.iter() {
-                        ret.push((self.generate_null(other), true).into());
+                        ret.push((self.generate_null_for(other_side, other), true).into());
                     }
                 } else if new_rc != 0 && old_rc == 0 {
                     for other in other_rows.iter() {
-                        ret.push((self.generate_null(other), false).into());
+                        ret.push((self.generate_null_for(other_side, other), false).into());
+                    }
+                }
+            }
+
+            // Same as above, but with the number of matching rows counted separately for each row
+            // in the other side
+            if let Some(from_rows) = new_from_rows {
+                let residual_matches = |from_row: &[DfValue], other_row: &[DfValue]| {
+                    if from_left {
+                        self.residual_matches(from_row, other_row)
+                    } else {
+                        self.residual_matches(other_row, from_row)
+                    }
+                };
+
+                for other in other_rows.iter() {
+                    let mut new_rc = 0usize;
+                    for from_row in &from_rows {
+                        if residual_matches(from_row, other)? {
+                            new_rc += 1;
+                        }
+                    }
+                    let mut rc_diff = 0isize;
+                    for r in &group {
+                        if residual_matches(r.rec(), other)? {
+                            rc_diff += if r.is_positive() { 1 } else { -1 };
+                        }
+                    }
+
+                    let old_rc = new_rc as isize - rc_diff;
+                    if new_rc == 0 && old_rc != 0 {
+                        ret.push((self.generate_null_for(other_side, other), true).into());
+                    } else if new_rc != 0 && old_rc == 0 {
+                        ret.push((self.generate_null_for(other_side, other), false).into());
                     }
                 }
             }
@@ -534,6 +721,7 @@ impl Ingredient for Join {
             return String::from(match self.kind {
                 JoinType::Left => "⋉",
                 JoinType::Inner => "⋈",
+                JoinType::Full => "⟗",
             });
         }
 
@@ -553,10 +741,17 @@ impl Ingredient for Join {
         let op = match self.kind {
             JoinType::Left => "⋉",
             JoinType::Inner => "⋈",
+            JoinType::Full => "⟗",
         };
 
+        let residual = self
+            .residual
+            .as_ref()
+            .map(|residual| format!(" σ[{}]", residual))
+            .unwrap_or_default();
+
         format!(
-            "[{}] {}:({}) {} {}:({})",
+            "[{}] {}:({}) {} {}:({}){}",
             emit,
             self.left.as_global().index(),
             self.on_left().into_iter().map(|i| i.to_string()).join(", "),
@@ -565,7 +760,8 @@ impl Ingredient for Join {
             self.on_right()
                 .into_iter()
                 .map(|i| i.to_string())
-                .join(", ")
+                .join(", "),
+            residual
         )
     }
 
@@ -693,6 +889,15 @@ impl Ingredient for Join {
         // means "get me all rows in a not in b" (i.e. a \ b), not "get me rows in b where col
         // is NULL" (which is what this function would do).
 
+        if self.kind == JoinType::Full {
+            // Rows in the right with no matches in the left can't be found by replaying from the
+            // left, and vice versa, so we can't be partial
+            return ColumnSource::RequiresFullReplay(vec1![
+                self.left.as_global(),
+                self.right.as_global()
+            ]);
+        }
+
         // column indices in the left parent
         let mut left_cols = vec![];
         // column indices in the right parent
@@ -1252,4 +1457,262 @@ mod tests {
             );
         }
     }
+
+    mod residual {
+        use dataflow_expression::utils::column_with_type;
+        use dataflow_expression::BinaryOperator;
+        use readyset_data::DfType;
+
+        use super::*;
+
+        fn setup(kind: JoinType) -> (ops::test::MockGraph, IndexPair, IndexPair) {
+            let mut g = ops::test::MockGraph::new();
+            let l = g.add_base("left", &["l0", "l1"]);
+            let r = g.add_base("right", &["r0", "r1"]);
+
+            use self::JoinSource::*;
+            // j1 < j2
+            let j = Join::new(
+                l.as_global(),
+                r.as_global(),
+                kind,
+                vec![B(0, 0), L(1), R(1)],
+            )
+            .with_residual(Expr::Op {
+                left: Box::new(column_with_type(1, DfType::Int)),
+                op: BinaryOperator::Less,
+                right: Box::new(column_with_type(2, DfType::Int)),
+                ty: DfType::Bool,
+            });
+
+            g.set_op("join", &["j0", "j1", "j2"], j, false);
+            (g, l, r)
+        }
+
+        #[test]
+        fn inner_join_filters_matches() {
+            let (mut j, l, r) = setup(JoinType::Inner);
+
+            j.seed(r, vec![1.into(), 5.into()]);
+            j.seed(r, vec![1.into(), 20.into()]);
+
+            j.seed(l, vec![1.into(), 10.into()]);
+            let rs = j.one_row(l, vec![1.into(), 10.into()], false);
+            assert_eq!(
+                rs,
+                vec![(vec![1.into(), 10.into(), 20.into()], true)].into()
+            );
+
+            j.seed(r, vec![1.into(), 7.into()]);
+            let rs = j.one_row(r, vec![1.into(), 7.into()], false);
+            assert!(rs.is_empty());
+        }
+
+        #[test]
+        fn left_join_from_left() {
+            let (mut j, l, r) = setup(JoinType::Left);
+
+            j.seed(r, vec![1.into(), 5.into()]);
+
+            // Matches on the join key, but not the residual
+            j.seed(l, vec![1.into(), 10.into()]);
+            let rs = j.one_row(l, vec![1.into(), 10.into()], false);
+            assert_eq!(
+                rs,
+                vec![(vec![1.into(), 10.into(), DfValue::None], true)].into()
+            );
+
+            j.seed(l, vec![1.into(), 3.into()]);
+            let rs = j.one_row(l, vec![1.into(), 3.into()], false);
+            assert_eq!(rs, vec![(vec![1.into(), 3.into(), 5.into()], true)].into());
+        }
+
+        #[test]
+        fn left_join_from_right() {
+            let (mut j, l, r) = setup(JoinType::Left);
+
+            j.seed(l, vec![1.into(), 10.into()]);
+            j.seed(l, vec![1.into(), 3.into()]);
+
+            // Only matches one of the rows in the left
+            j.seed(r, vec![1.into(), 5.into()]);
+            let rs = j.one_row(r, vec![1.into(), 5.into()], false);
+            assert_eq!(rs.len(), 2);
+            assert!(rs.has_positive(&[1.into(), 3.into(), 5.into()][..]));
+            assert!(rs.has_negative(&[1.into(), 3.into(), DfValue::None][..]));
+
+            // Matches both rows in the left, but only one of them had nulls
+            j.seed(r, vec![1.into(), 20.into()]);
+            let rs = j.one_row(r, vec![1.into(), 20.into()], false);
+            assert_eq!(rs.len(), 3);
+            assert!(rs.has_positive(&[1.into(), 3.into(), 20.into()][..]));
+            assert!(rs.has_positive(&[1.into(), 10.into(), 20.into()][..]));
+            assert!(rs.has_negative(&[1.into(), 10.into(), DfValue::None][..]));
+
+            // The row in the left still has a match after this is removed, so it shouldn't get
+            // nulls
+            j.unseed(r);
+            j.seed(r, vec![1.into(), 20.into()]);
+            let rs = j.one_row(r, (vec![1.into(), 5.into()], false), false);
+            assert_eq!(rs, vec![(vec![1.into(), 3.into(), 5.into()], false)].into());
+
+            j.unseed(r);
+            let rs = j.one_row(r, (vec![1.into(), 20.into()], false), false);
+            assert_eq!(rs.len(), 4);
+            assert!(rs.has_negative(&[1.into(), 3.into(), 20.into()][..]));
+            assert!(rs.has_negative(&[1.into(), 10.into(), 20.into()][..]));
+            assert!(rs.has_positive(&[1.into(), 3.into(), DfValue::None][..]));
+            assert!(rs.has_positive(&[1.into(), 10.into(), DfValue::None][..]));
+        }
+    }
+
+    mod full {
+        use super::*;
+
+        fn setup() -> (ops::test::MockGraph, IndexPair, IndexPair) {
+            let mut g = ops::test::MockGraph::new();
+            let l = g.add_base("left", &["l0", "l1"]);
+            let r = g.add_base("right", &["r0", "r1"]);
+
+            use self::JoinSource::*;
+            let j = Join::new(
+                l.as_global(),
+                r.as_global(),
+                JoinType::Full,
+                vec![B(0, 0), L(1), R(0), R(1)],
+            );
+
+            g.set_op("join", &["j0", "j1", "j2", "j3"], j, false);
+            (g, l, r)
+        }
+
+        #[test]
+        fn it_describes() {
+            let (j, l, r) = setup();
+            assert_eq!(
+                j.node().description(true),
+                format!("[{}:0, {}:1, {}:0, {}:1] {}:(0) ⟗ {}:(0)", l, l, r, r, l, r)
+            );
+        }
+
+        #[test]
+        fn unmatched_rows_from_both_sides() {
+            let (mut j, l, r) = setup();
+
+            j.seed(l, vec![1.into(), "a".try_into().unwrap()]);
+            let rs = j.one_row(l, vec![1.into(), "a".try_into().unwrap()], false);
+            assert_eq!(
+                rs,
+                vec![(
+                    vec![
+                        1.into(),
+                        "a".try_into().unwrap(),
+                        DfValue::None,
+                        DfValue::None
+                    ],
+                    true
+                )]
+                .into()
+            );
+
+            j.seed(r, vec![2.into(), "x".try_into().unwrap()]);
+            let rs = j.one_row(r, vec![2.into(), "x".try_into().unwrap()], false);
+            assert_eq!(
+                rs,
+                vec![(
+                    vec![
+                        DfValue::None,
+                        DfValue::None,
+                        2.into(),
+                        "x".try_into().unwrap()
+                    ],
+                    true
+                )]
+                .into()
+            );
+        }
+
+        #[test]
+        fn matches_retract_nulls() {
+            let (mut j, l, r) = setup();
+
+            j.seed(r, vec![1.into(), "x".try_into().unwrap()]);
+
+            // A match from the left replaces the null-padded row for the right
+            j.seed(l, vec![1.into(), "a".try_into().unwrap()]);
+            let rs = j.one_row(l, vec![1.into(), "a".try_into().unwrap()], false);
+            assert_eq!(rs.len(), 2);
+            assert!(rs.has_positive(
+                &[
+                    1.into(),
+                    "a".try_into().unwrap(),
+                    1.into(),
+                    "x".try_into().unwrap()
+                ][..]
+            ));
+            assert!(rs.has_negative(
+                &[
+                    DfValue::None,
+                    DfValue::None,
+                    1.into(),
+                    "x".try_into().unwrap()
+                ][..]
+            ));
+
+            // A second match from the right doesn't touch the nulls for the left, since there
+            // weren't any
+            j.seed(r, vec![1.into(), "y".try_into().unwrap()]);
+            let rs = j.one_row(r, vec![1.into(), "y".try_into().unwrap()], false);
+            assert_eq!(
+                rs,
+                vec![(
+                    vec![
+                        1.into(),
+                        "a".try_into().unwrap(),
+                        1.into(),
+                        "y".try_into().unwrap()
+                    ],
+                    true
+                )]
+                .into()
+            );
+
+            // Removing the row in the left brings back the nulls for both rows in the right
+            j.unseed(l);
+            let rs = j.one_row(l, (vec![1.into(), "a".try_into().unwrap()], false), false);
+            assert_eq!(rs.len(), 4);
+            assert!(rs.has_negative(
+                &[
+                    1.into(),
+                    "a".try_into().unwrap(),
+                    1.into(),
+                    "x".try_into().unwrap()
+                ][..]
+            ));
+            assert!(rs.has_negative(
+                &[
+                    1.into(),
+                    "a".try_into().unwrap(),
+                    1.into(),
+                    "y".try_into().unwrap()
+                ][..]
+            ));
+            assert!(rs.has_positive(
+                &[
+                    DfValue::None,
+                    DfValue::None,
+                    1.into(),
+                    "x".try_into().unwrap()
+                ][..]
+            ));
+            assert!(rs.has_positive(
+                &[
+                    DfValue::None,
+                    DfValue::None,
+                    1.into(),
+                    "y".try_into().unwrap()
+                ][..]
+            ));
+        }
+    }
 }
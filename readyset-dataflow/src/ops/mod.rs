@@ -17,6 +17,7 @@ pub mod project;
 pub mod topk;
 pub mod union;
 pub(crate) mod utils;
+pub mod window;
 
 use crate::ops::grouped::concat::GroupConcat;
 use crate::processing::{
@@ -54,6 +55,7 @@ pub enum NodeOperator {
     Identity(identity::Identity),
     Filter(filter::Filter),
     TopK(topk::TopK),
+    Window(window::Window),
 }
 
 impl ToString for NodeOperator {
@@ -69,6 +71,7 @@ impl ToString for NodeOperator {
             NodeOperator::Identity(_) => "Identity",
             NodeOperator::Filter(_) => "Filter",
             NodeOperator::TopK(_) => "TopK",
+            NodeOperator::Window(_) => "Window",
         }
         .to_string()
     }
@@ -87,6 +90,7 @@ macro_rules! impl_ingredient_fn_mut {
             NodeOperator::Identity(ref mut i) => i.$fn($($arg),*),
             NodeOperator::Filter(ref mut i) => i.$fn($($arg),*),
             NodeOperator::TopK(ref mut i) => i.$fn($($arg),*),
+            NodeOperator::Window(ref mut i) => i.$fn($($arg),*),
         }
     }
 }
@@ -104,6 +108,7 @@ macro_rules! impl_ingredient_fn_ref {
             NodeOperator::Identity(ref i) => i.$fn($($arg),*),
             NodeOperator::Filter(ref i) => i.$fn($($arg),*),
             NodeOperator::TopK(ref i) => i.$fn($($arg),*),
+            NodeOperator::Window(ref i) => i.$fn($($arg),*),
         }
     }
 }
@@ -200,6 +205,9 @@ impl Ingredient for NodeOperator {
     fn requires_full_materialization(&self) -> bool {
         impl_ingredient_fn_ref!(self, requires_full_materialization,)
     }
+    fn requires_materialized_parents(&self) -> bool {
+        impl_ingredient_fn_ref!(self, requires_materialized_parents,)
+    }
 }
 
 #[cfg(test)]
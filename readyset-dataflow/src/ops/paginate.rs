@@ -3,12 +3,15 @@ use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 use std::convert::TryInto;
 use std::mem;
+use std::ops::Bound;
 
 use dataflow_state::PointKey;
 use itertools::Itertools;
 use nom_sql::OrderType;
+use readyset_client::KeyComparison;
 use readyset_util::Indices;
 use serde::{Deserialize, Serialize};
+use vec1::Vec1;
 
 use crate::ops::utils::Order;
 use crate::prelude::*;
@@ -340,15 +343,26 @@ impl Ingredient for Paginate {
             .position(|ci| *ci == self.page_number_column())
             .expect("handle_upquery invariant");
 
+        let remove_page_number = |mut r: Vec1<DfValue>| {
+            r.remove(page_number_column)
+                .expect("handle_upquery invariant");
+            r
+        };
+        // All the pages of a group are generated from all of its rows, so once we remove the page
+        // number from a range the groups at both of its ends have to be included in full
+        let include_group = |bound: Bound<Vec1<DfValue>>| match bound {
+            Bound::Included(r) | Bound::Excluded(r) => Bound::Included(remove_page_number(r)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
         Ok(vec![ColumnMiss {
             node: *self.our_index.unwrap(),
             column_indices: self.group_by.clone().try_into().unwrap(),
-            missed_keys: miss.missed_keys.mapped(|k| {
-                k.map_endpoints(|mut r| {
-                    r.remove(page_number_column)
-                        .expect("handle_upquery invariant");
-                    r
-                })
+            missed_keys: miss.missed_keys.mapped(|k| match k {
+                KeyComparison::Equal(r) => KeyComparison::Equal(remove_page_number(r)),
+                KeyComparison::Range((lower, upper)) => {
+                    KeyComparison::Range((include_group(lower), include_group(upper)))
+                }
             }),
         }])
     }
@@ -469,6 +483,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_upquery_for_range_page_query() {
+        let (g, _) = setup();
+        let res = g
+            .node_mut()
+            .handle_upquery(ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![1, 2],
+                missed_keys: vec1![KeyComparison::Range((
+                    Bound::Included(vec1![DfValue::from("a"), DfValue::from(0)]),
+                    Bound::Excluded(vec1![DfValue::from("c"), DfValue::from(2)]),
+                ))],
+            })
+            .unwrap();
+
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            *res.first().unwrap(),
+            ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![1],
+                missed_keys: vec1![KeyComparison::Range((
+                    Bound::Included(vec1![DfValue::from("a")]),
+                    Bound::Included(vec1![DfValue::from("c")]),
+                ))]
+            }
+        );
+    }
+
     #[test]
     fn first_row_first_page() {
         let (mut g, _) = setup();
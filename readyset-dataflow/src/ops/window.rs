@@ -0,0 +1,975 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt::{self, Display};
+use std::ops::Bound;
+
+use dataflow_state::PointKey;
+use itertools::Itertools;
+use nom_sql::OrderType;
+use readyset_client::KeyComparison;
+use readyset_data::DfType;
+use readyset_util::Indices;
+use serde::{Deserialize, Serialize};
+use vec1::Vec1;
+
+use crate::ops::utils::Order;
+use crate::prelude::*;
+use crate::processing::{ColumnMiss, LookupIndex};
+
+/// Supported window functions.
+///
+/// Aggregate window functions ([`Count`], [`CountStar`], [`Sum`], [`Min`], and [`Max`]) are
+/// computed over the default window frame - from the start of the partition up to and including
+/// all peers of the current row. If the window has no ORDER BY, every row in the partition is a
+/// peer of every other row, so the frame is the entire partition.
+///
+/// [`Count`]: WindowFunction::Count
+/// [`CountStar`]: WindowFunction::CountStar
+/// [`Sum`]: WindowFunction::Sum
+/// [`Min`]: WindowFunction::Min
+/// [`Max`]: WindowFunction::Max
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFunction {
+    /// The 1-based position of each row within its partition
+    RowNumber,
+    /// The 1-based rank of each row within its partition, with gaps after ties
+    Rank,
+    /// The 1-based rank of each row within its partition, without gaps after ties
+    DenseRank,
+    /// The value of the `over` column `offset` rows before the current row, or NULL
+    Lag { offset: usize },
+    /// The value of the `over` column `offset` rows after the current row, or NULL
+    Lead { offset: usize },
+    /// Count the number of non-null values of the `over` column in the frame
+    Count,
+    /// Count the number of rows in the frame
+    CountStar,
+    /// Sum the values of the `over` column in the frame
+    Sum,
+    /// The minimum non-null value of the `over` column in the frame
+    Min,
+    /// The maximum non-null value of the `over` column in the frame
+    Max,
+}
+
+impl WindowFunction {
+    /// Returns true if this window function takes a column argument
+    pub fn takes_argument(&self) -> bool {
+        !matches!(
+            self,
+            WindowFunction::RowNumber
+                | WindowFunction::Rank
+                | WindowFunction::DenseRank
+                | WindowFunction::CountStar
+        )
+    }
+
+    /// Returns the type of the values computed by this window function, given the type of the
+    /// column it's computed over (if any)
+    pub fn output_type(&self, over_col_ty: Option<&DfType>) -> DfType {
+        match self {
+            WindowFunction::RowNumber
+            | WindowFunction::Rank
+            | WindowFunction::DenseRank
+            | WindowFunction::Count
+            | WindowFunction::CountStar => DfType::BigInt,
+            WindowFunction::Sum => {
+                if over_col_ty.iter().any(|ty| ty.is_any_float()) {
+                    DfType::Double
+                } else {
+                    DfType::DEFAULT_NUMERIC
+                }
+            }
+            WindowFunction::Lag { .. }
+            | WindowFunction::Lead { .. }
+            | WindowFunction::Min
+            | WindowFunction::Max => over_col_ty.cloned().unwrap_or(DfType::Unknown),
+        }
+    }
+}
+
+impl Display for WindowFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowFunction::RowNumber => write!(f, "ROW_NUMBER"),
+            WindowFunction::Rank => write!(f, "RANK"),
+            WindowFunction::DenseRank => write!(f, "DENSE_RANK"),
+            WindowFunction::Lag { offset } => write!(f, "LAG[{}]", offset),
+            WindowFunction::Lead { offset } => write!(f, "LEAD[{}]", offset),
+            WindowFunction::Count => write!(f, "COUNT"),
+            WindowFunction::CountStar => write!(f, "COUNT(*)"),
+            WindowFunction::Sum => write!(f, "SUM"),
+            WindowFunction::Min => write!(f, "MIN"),
+            WindowFunction::Max => write!(f, "MAX"),
+        }
+    }
+}
+
+/// Window implements a Soup node that computes a window function over partitions of its input,
+/// appending the computed value for each row as a new column at the end of the row.
+///
+/// The value computed for a row can depend on every other row in its partition, so `Window` keeps
+/// its own output materialized, indexed by the partition columns. When records for a partition
+/// arrive, the whole partition is loaded from that state, the window function is recomputed, and
+/// the difference between the old and new output rows is emitted.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Window {
+    /// The direct Ingredient or Base ancestor of this node
+    src: IndexPair,
+    /// The index of this node. Used to look up into our own state
+    our_index: Option<IndexPair>,
+    /// The column index of the window function column emitted by this node
+    ///
+    /// This is always equal to the number of columns in the parent node (there is one more output
+    /// column than input columns)
+    ///
+    /// Set during [`Ingredient::on_connected`]
+    output_col: Option<usize>,
+    /// The window function to compute
+    function: WindowFunction,
+    /// The column the window function is computed over, for functions that take an argument
+    over: Option<usize>,
+    /// The list of column indices that we're partitioning by
+    partition_by: Vec<usize>,
+    /// The ordering of rows within each partition
+    order: Order,
+    /// The type of the values computed by the window function
+    out_ty: DfType,
+}
+
+impl Window {
+    /// Construct a new `Window` operator computing `function` over the `over` column of rows from
+    /// `src`, partitioned by the `partition_by` columns and ordered (ascending or descending, as in
+    /// SQL) by the `order` columns.
+    pub fn new(
+        src: NodeIndex,
+        function: WindowFunction,
+        over: Option<usize>,
+        partition_by: Vec<usize>,
+        order: Vec<(usize, OrderType)>,
+        over_col_ty: Option<&DfType>,
+    ) -> ReadySetResult<Self> {
+        if function.takes_argument() != over.is_some() {
+            internal!(
+                "Window function {} {} a column argument",
+                function,
+                if over.is_some() {
+                    "does not take"
+                } else {
+                    "requires"
+                }
+            );
+        }
+        let out_ty = function.output_type(over_col_ty);
+
+        Ok(Window {
+            src: src.into(),
+            our_index: None,
+            output_col: None,
+            function,
+            over,
+            partition_by,
+            order: order.into(),
+            out_ty,
+        })
+    }
+
+    /// Project the columns we are partitioning by out of the given record
+    fn project_partition<'rec, R>(&self, rec: &'rec R) -> ReadySetResult<Vec<&'rec DfValue>>
+    where
+        R: Indices<'static, usize, Output = DfValue> + ?Sized,
+    {
+        rec.indices(self.partition_by.clone())
+            .map_err(|_| ReadySetError::InvalidRecordLength)
+    }
+
+    /// Return the column index of the window function column output by this node (which will
+    /// always be the last column)
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`Ingredient::on_connected`]
+    fn output_column(&self) -> usize {
+        self.output_col
+            .expect("output_column called before Ingredient::on_connected")
+    }
+
+    /// Compare two rows of a partition, breaking ties in the window's order by comparing the rows
+    /// themselves so that the order of rows (and hence the output of functions like ROW_NUMBER) is
+    /// deterministic
+    fn cmp_rows(&self, a: &[DfValue], b: &[DfValue]) -> Ordering {
+        self.order.cmp(a, b).then_with(|| a.cmp(b))
+    }
+
+    /// Return the value of the `over` column for the given row
+    fn over_value<'a>(&self, row: &'a [DfValue]) -> ReadySetResult<&'a DfValue> {
+        let over = self
+            .over
+            .ok_or_else(|| internal_err!("{} requires a column argument", self.function))?;
+        row.get(over).ok_or(ReadySetError::InvalidRecordLength)
+    }
+
+    /// Compute the value of the running aggregate over a frame, given its value `acc` over the
+    /// start of the frame (or `None` if that's empty) and the (sorted) rows in the rest of the
+    /// frame
+    fn aggregate(&self, acc: DfValue, rows: &[Vec<DfValue>]) -> ReadySetResult<DfValue> {
+        let non_null = || {
+            rows.iter()
+                .map(|r| self.over_value(r))
+                .filter_ok(|v| !v.is_none())
+        };
+        let count_so_far = || {
+            if acc.is_none() {
+                Ok(0)
+            } else {
+                u64::try_from(&acc)
+            }
+        };
+
+        Ok(match self.function {
+            WindowFunction::CountStar => DfValue::from(count_so_far()? + rows.len() as u64),
+            WindowFunction::Count => {
+                let mut count = count_so_far()?;
+                for v in non_null() {
+                    v?;
+                    count += 1;
+                }
+                DfValue::from(count)
+            }
+            WindowFunction::Sum => {
+                let mut sum = acc;
+                for v in non_null() {
+                    let v = v?;
+                    sum = if sum.is_none() {
+                        match self.out_ty {
+                            DfType::Double => (&DfValue::Double(0.0) + v)?,
+                            _ => (&DfValue::Numeric(Default::default()) + v)?,
+                        }
+                    } else {
+                        (&sum + v)?
+                    };
+                }
+                sum
+            }
+            WindowFunction::Min => {
+                let mut min = acc;
+                for v in non_null() {
+                    let v = v?;
+                    if min.is_none() || *v < min {
+                        min = v.clone();
+                    }
+                }
+                min
+            }
+            WindowFunction::Max => {
+                let mut max = acc;
+                for v in non_null() {
+                    let v = v?;
+                    if max.is_none() || *v > max {
+                        max = v.clone();
+                    }
+                }
+                max
+            }
+            _ => internal!("{} is not an aggregate window function", self.function),
+        })
+    }
+
+    /// Compute the output rows for a partition, given all the (input) rows in that partition
+    fn compute_partition(&self, mut rows: Vec<Vec<DfValue>>) -> ReadySetResult<Vec<Vec<DfValue>>> {
+        rows.sort_by(|a, b| self.cmp_rows(a, b));
+
+        let mut values = Vec::with_capacity(rows.len());
+        // The index of the first row in the current group of peers (rows which are equal according
+        // to the window's order)
+        let mut peer_group_start = 0;
+        let mut dense_rank = 0u64;
+        // The value of the aggregate (for aggregate window functions) over the frame of the
+        // current peer group, which is kept as the frame grows so that each row is only added to
+        // it once
+        let mut running = DfValue::None;
+        for i in 0..rows.len() {
+            let new_peer_group =
+                i == 0 || self.order.cmp(&rows[i - 1], &rows[i]) != Ordering::Equal;
+            if new_peer_group {
+                peer_group_start = i;
+                dense_rank += 1;
+            }
+
+            let value = match self.function {
+                WindowFunction::RowNumber => DfValue::from(i as u64 + 1),
+                WindowFunction::Rank => DfValue::from(peer_group_start as u64 + 1),
+                WindowFunction::DenseRank => DfValue::from(dense_rank),
+                WindowFunction::Lag { offset } => match i.checked_sub(offset) {
+                    Some(j) => self.over_value(&rows[j])?.clone(),
+                    None => DfValue::None,
+                },
+                WindowFunction::Lead { offset } => match rows.get(i + offset) {
+                    Some(row) => self.over_value(row)?.clone(),
+                    None => DfValue::None,
+                },
+                WindowFunction::Count
+                | WindowFunction::CountStar
+                | WindowFunction::Sum
+                | WindowFunction::Min
+                | WindowFunction::Max => {
+                    if new_peer_group {
+                        // The frame includes every peer of the current row, so all the rows in a
+                        // peer group share the same value
+                        let peer_group_end = rows[i..]
+                            .iter()
+                            .position(|r| self.order.cmp(&rows[i], r) != Ordering::Equal)
+                            .map(|n| i + n)
+                            .unwrap_or(rows.len());
+                        running = self.aggregate(running, &rows[i..peer_group_end])?;
+                        running.clone()
+                    } else {
+                        #[allow(clippy::unwrap_used)] // Not the first row in the group
+                        values.last().cloned().unwrap()
+                    }
+                }
+            };
+            values.push(value);
+        }
+
+        Ok(rows
+            .into_iter()
+            .zip(values)
+            .map(|(mut row, value)| {
+                row.push(value);
+                row
+            })
+            .collect())
+    }
+
+    /// Recompute the window function for a partition, and push the difference between the
+    /// partition's previous output rows and its new output rows to `out`
+    ///
+    /// Negatives for rows that are no longer in the output are emitted first, followed by positives
+    /// for rows that are new to the output, each in the window's order.
+    fn post_partition(
+        &self,
+        out: &mut Vec<Record>,
+        mut previous: Vec<Vec<DfValue>>,
+        current: Vec<Vec<DfValue>>,
+    ) -> ReadySetResult<()> {
+        previous.sort_by(|a, b| self.cmp_rows(a, b));
+        let new = self.compute_partition(current)?;
+
+        // For each row in `rows`, returns whether that row does *not* appear in `other` (counting
+        // duplicates)
+        fn not_in(rows: &[Vec<DfValue>], other: &[Vec<DfValue>]) -> Vec<bool> {
+            let mut counts: HashMap<&[DfValue], usize> = HashMap::new();
+            for row in other {
+                *counts.entry(row.as_slice()).or_default() += 1;
+            }
+            rows.iter()
+                .map(|row| match counts.get_mut(row.as_slice()) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                })
+                .collect()
+        }
+
+        let removed = not_in(&previous, &new);
+        let added = not_in(&new, &previous);
+
+        out.extend(
+            previous
+                .into_iter()
+                .zip(removed)
+                .filter(|(_, removed)| *removed)
+                .map(|(row, _)| Record::Negative(row)),
+        );
+        out.extend(
+            new.into_iter()
+                .zip(added)
+                .filter(|(_, added)| *added)
+                .map(|(row, _)| Record::Positive(row)),
+        );
+
+        Ok(())
+    }
+}
+
+impl Ingredient for Window {
+    fn take(&mut self) -> NodeOperator {
+        self.clone().into()
+    }
+
+    fn ancestors(&self) -> Vec<NodeIndex> {
+        vec![self.src.as_global()]
+    }
+
+    impl_replace_sibling!(src);
+
+    fn on_connected(&mut self, graph: &Graph) {
+        self.output_col = Some(graph[self.src.as_global()].columns().len());
+    }
+
+    fn on_commit(&mut self, us: NodeIndex, remap: &HashMap<NodeIndex, IndexPair>) {
+        self.src.remap(remap);
+        self.our_index = Some(remap[&us]);
+    }
+
+    fn on_input(
+        &mut self,
+        from: LocalNodeIndex,
+        rs: Records,
+        replay: &ReplayContext,
+        _nodes: &DomainNodes,
+        state: &StateMap,
+    ) -> ReadySetResult<ProcessingResult> {
+        debug_assert_eq!(from, *self.src);
+
+        if rs.is_empty() {
+            return Ok(ProcessingResult {
+                results: rs,
+                ..Default::default()
+            });
+        }
+
+        let mut rs = Vec::from(rs)
+            .into_iter()
+            .map(|r| {
+                let key = self
+                    .project_partition(r.rec())?
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                Ok((key, r))
+            })
+            .collect::<ReadySetResult<Vec<_>>>()?;
+        // Stable sort, to preserve the order of records within each partition
+        rs.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+
+        let us = self.our_index.unwrap();
+        let db = state
+            .get(*us)
+            .ok_or_else(|| internal_err!("window must have its own state materialized"))?;
+
+        let mut out = vec![];
+        let mut lookups = vec![];
+        let mut misses = vec![];
+
+        for (partition_key, records) in &rs.into_iter().group_by(|(k, _)| k.clone()) {
+            let records = records.map(|(_, r)| r);
+
+            // Load the previous output rows for the partition into memory
+            let previous: Vec<Vec<DfValue>> =
+                match db.lookup(&self.partition_by, &PointKey::from(partition_key.clone())) {
+                    LookupResult::Some(local_records) => {
+                        if replay.is_partial() {
+                            lookups.push(Lookup {
+                                on: *us,
+                                cols: self.partition_by.clone(),
+                                key: partition_key.clone().try_into().expect("Empty partition"),
+                            });
+                        }
+                        local_records.into_iter().map(|r| r.into_owned()).collect()
+                    }
+                    LookupResult::Missing => {
+                        misses.extend(records.map(|r| {
+                            Miss::builder()
+                                .on(*us)
+                                .lookup_idx(self.partition_by.clone())
+                                .lookup_key(self.partition_by.clone())
+                                .replay(replay)
+                                .record(r.into_row())
+                                .build()
+                        }));
+                        continue;
+                    }
+                };
+
+            let mut current: Vec<Vec<DfValue>> = previous
+                .iter()
+                .map(|r| r[..self.output_column()].to_vec())
+                .collect();
+            for r in records {
+                match r {
+                    Record::Positive(r) => current.push(r),
+                    Record::Negative(r) => {
+                        if let Some(pos) = current.iter().position(|cr| *cr == r) {
+                            current.swap_remove(pos);
+                        }
+                    }
+                }
+            }
+
+            self.post_partition(&mut out, previous, current)?;
+        }
+
+        Ok(ProcessingResult {
+            results: out.into(),
+            lookups,
+            misses,
+        })
+    }
+
+    fn suggest_indexes(&self, this: NodeIndex) -> HashMap<NodeIndex, LookupIndex> {
+        HashMap::from([(
+            this,
+            LookupIndex::Strict(Index::hash_map(self.partition_by.clone())),
+        )])
+    }
+
+    fn column_source(&self, cols: &[usize]) -> ColumnSource {
+        if !cols.contains(&self.output_column()) {
+            return ColumnSource::ExactCopy(ColumnRef {
+                node: self.src.as_global(),
+                columns: cols.to_vec().try_into().unwrap(),
+            });
+        }
+
+        match Vec1::try_from(self.partition_by.clone()) {
+            // The window function value of a row depends on every row in its partition, so we can
+            // only generate it by replaying whole partitions, which we can only do if the lookup
+            // includes every partition column
+            Ok(partition_by) if partition_by.iter().all(|c| cols.contains(c)) => {
+                ColumnSource::GeneratedFromColumns(vec1![ColumnRef {
+                    node: self.our_index.unwrap().as_global(),
+                    columns: partition_by,
+                }])
+            }
+            _ => ColumnSource::RequiresFullReplay(vec1![self.src.as_global()]),
+        }
+    }
+
+    fn handle_upquery(&mut self, miss: ColumnMiss) -> ReadySetResult<Vec<ColumnMiss>> {
+        // The position of each of the partition columns within the missed columns
+        let partition_positions = self
+            .partition_by
+            .iter()
+            .map(|c| {
+                miss.column_indices
+                    .iter()
+                    .position(|ci| ci == c)
+                    .ok_or_else(|| internal_err!("Upquery on window is missing partition column"))
+            })
+            .collect::<ReadySetResult<Vec<_>>>()?;
+        let project_partition = |r: Vec1<DfValue>| -> ReadySetResult<Vec1<DfValue>> {
+            partition_positions
+                .iter()
+                .map(|i| r.get(*i).cloned().ok_or(ReadySetError::InvalidRecordLength))
+                .collect::<ReadySetResult<Vec<_>>>()?
+                .try_into()
+                .map_err(|_| internal_err!("Window must have at least one partition column"))
+        };
+        // If the partition columns come first in the missed columns, a range over the missed
+        // columns is contained in the range over the partitions at both of its ends (inclusive)
+        let partition_is_prefix = partition_positions
+            .iter()
+            .copied()
+            .eq(0..partition_positions.len());
+        let include_partition = |bound: Bound<Vec1<DfValue>>| -> ReadySetResult<_> {
+            Ok(match bound {
+                Bound::Included(r) | Bound::Excluded(r) => Bound::Included(project_partition(r)?),
+                Bound::Unbounded => Bound::Unbounded,
+            })
+        };
+
+        let missed_keys = miss.missed_keys.try_mapped(|k| -> ReadySetResult<_> {
+            Ok(match k {
+                KeyComparison::Equal(r) => KeyComparison::Equal(project_partition(r)?),
+                KeyComparison::Range(_) if !partition_is_prefix => unsupported!(
+                    "Range queries on window functions must come after all the partition columns"
+                ),
+                KeyComparison::Range((lower, upper)) => {
+                    KeyComparison::Range((include_partition(lower)?, include_partition(upper)?))
+                }
+            })
+        })?;
+
+        Ok(vec![ColumnMiss {
+            node: *self.our_index.unwrap(),
+            column_indices: self.partition_by.clone().try_into().unwrap(),
+            missed_keys,
+        }])
+    }
+
+    fn description(&self, detailed: bool) -> String {
+        if !detailed {
+            return "Window".into();
+        }
+
+        format!(
+            "Window {}{} γ[{}] o[{}]",
+            self.function,
+            self.over.map(|c| format!("({})", c)).unwrap_or_default(),
+            self.partition_by.iter().join(", "),
+            self.order
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::test::MockGraph;
+
+    fn setup(function: WindowFunction, over: Option<usize>) -> (MockGraph, IndexPair) {
+        let mut g = MockGraph::new();
+        let s = g.add_base("source", &["x", "y"]);
+
+        // <function> OVER (PARTITION BY y ORDER BY x)
+        g.set_op(
+            "window",
+            &["x", "y", "w"],
+            Window::new(
+                s.as_global(),
+                function,
+                over,
+                vec![1],
+                vec![(0, OrderType::OrderAscending)],
+                Some(&DfType::Int),
+            )
+            .unwrap(),
+            true,
+        );
+        (g, s)
+    }
+
+    fn with_value<V: Into<DfValue>>(row: &[DfValue], value: V) -> Vec<DfValue> {
+        let mut res = row.to_vec();
+        res.push(value.into());
+        res
+    }
+
+    #[test]
+    fn column_source_for_partition() {
+        let (g, s) = setup(WindowFunction::RowNumber, None);
+        let src = g.node().column_source(&[1]);
+        assert_eq!(
+            src,
+            ColumnSource::ExactCopy(ColumnRef {
+                node: s.as_global(),
+                columns: vec1![1]
+            })
+        );
+    }
+
+    #[test]
+    fn column_source_for_output_column() {
+        let (g, _) = setup(WindowFunction::RowNumber, None);
+        let src = g.node().column_source(&[1, 2]);
+        assert_eq!(
+            src,
+            ColumnSource::GeneratedFromColumns(vec1![ColumnRef {
+                node: g.node_index().as_global(),
+                columns: vec1![1],
+            }])
+        );
+    }
+
+    #[test]
+    fn column_source_for_output_column_without_partition() {
+        let (g, s) = setup(WindowFunction::RowNumber, None);
+        assert_eq!(
+            g.node().column_source(&[2]),
+            ColumnSource::RequiresFullReplay(vec1![s.as_global()])
+        );
+        assert_eq!(
+            g.node().column_source(&[0, 2]),
+            ColumnSource::RequiresFullReplay(vec1![s.as_global()])
+        );
+    }
+
+    #[test]
+    fn handle_upquery_for_output_column() {
+        let (g, _) = setup(WindowFunction::RowNumber, None);
+        let res = g
+            .node_mut()
+            .handle_upquery(ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![0, 2, 1],
+                missed_keys: vec1![
+                    vec1![DfValue::from(1), DfValue::from(1), DfValue::from("a")].into()
+                ],
+            })
+            .unwrap();
+
+        assert_eq!(
+            res,
+            vec![ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![1],
+                missed_keys: vec1![vec1![DfValue::from("a")].into()]
+            }]
+        );
+    }
+
+    #[test]
+    fn handle_upquery_for_output_column_range() {
+        let (g, _) = setup(WindowFunction::RowNumber, None);
+        let res = g
+            .node_mut()
+            .handle_upquery(ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![1, 2],
+                missed_keys: vec1![KeyComparison::Range((
+                    Bound::Excluded(vec1![DfValue::from("a"), DfValue::from(1)]),
+                    Bound::Unbounded,
+                ))],
+            })
+            .unwrap();
+
+        assert_eq!(
+            res,
+            vec![ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![1],
+                missed_keys: vec1![KeyComparison::Range((
+                    Bound::Included(vec1![DfValue::from("a")]),
+                    Bound::Unbounded,
+                ))]
+            }]
+        );
+
+        // A range on the output column before the partition column doesn't bound the partitions
+        g.node_mut()
+            .handle_upquery(ColumnMiss {
+                node: *g.node_index(),
+                column_indices: vec1![2, 1],
+                missed_keys: vec1![KeyComparison::Range((
+                    Bound::Included(vec1![DfValue::from(1), DfValue::from("a")]),
+                    Bound::Unbounded,
+                ))],
+            })
+            .unwrap_err();
+    }
+
+    #[test]
+    fn suggest_indexes() {
+        let (g, _) = setup(WindowFunction::RowNumber, None);
+        let res = g.node().suggest_indexes(g.node_index().as_global());
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[&g.node_index().as_global()],
+            LookupIndex::Strict(Index::hash_map(vec![1]))
+        );
+    }
+
+    #[test]
+    fn row_number() {
+        let (mut g, _) = setup(WindowFunction::RowNumber, None);
+
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+        let r1b: Vec<DfValue> = vec![1.into(), "b".into()];
+
+        let res = g.narrow_one(vec![r2a.clone(), r1a.clone(), r1b.clone()], true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, 1),
+                with_value(&r2a, 2),
+                with_value(&r1b, 1)
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn row_number_shifts_on_insert() {
+        let (mut g, _) = setup(WindowFunction::RowNumber, None);
+
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+        let r3a: Vec<DfValue> = vec![3.into(), "a".into()];
+
+        g.narrow_one(vec![r1a.clone(), r3a.clone()], true);
+
+        let res = g.narrow_one_row(r2a.clone(), true);
+        assert_eq!(
+            res,
+            vec![
+                (with_value(&r3a, 2), false),
+                (with_value(&r2a, 2), true),
+                (with_value(&r3a, 3), true),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn row_number_shifts_on_delete() {
+        let (mut g, _) = setup(WindowFunction::RowNumber, None);
+
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+        let r3a: Vec<DfValue> = vec![3.into(), "a".into()];
+
+        g.narrow_one(vec![r1a, r2a.clone(), r3a.clone()], true);
+
+        let res = g.narrow_one_row((r2a.clone(), false), true);
+        assert_eq!(
+            res,
+            vec![
+                (with_value(&r2a, 2), false),
+                (with_value(&r3a, 3), false),
+                (with_value(&r3a, 2), true),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn rank_and_dense_rank() {
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+        let r3a: Vec<DfValue> = vec![3.into(), "a".into()];
+        let rows = vec![r1a.clone(), r1a.clone(), r2a.clone(), r3a.clone()];
+
+        let (mut g, _) = setup(WindowFunction::Rank, None);
+        let res = g.narrow_one(rows.clone(), true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, 1),
+                with_value(&r1a, 1),
+                with_value(&r2a, 3),
+                with_value(&r3a, 4)
+            ]
+            .into()
+        );
+
+        let (mut g, _) = setup(WindowFunction::DenseRank, None);
+        let res = g.narrow_one(rows, true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, 1),
+                with_value(&r1a, 1),
+                with_value(&r2a, 2),
+                with_value(&r3a, 3)
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn lag_and_lead() {
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+        let r3a: Vec<DfValue> = vec![3.into(), "a".into()];
+        let rows = vec![r1a.clone(), r2a.clone(), r3a.clone()];
+
+        let (mut g, _) = setup(WindowFunction::Lag { offset: 1 }, Some(0));
+        let res = g.narrow_one(rows.clone(), true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, DfValue::None),
+                with_value(&r2a, 1),
+                with_value(&r3a, 2)
+            ]
+            .into()
+        );
+
+        let (mut g, _) = setup(WindowFunction::Lead { offset: 2 }, Some(0));
+        let res = g.narrow_one(rows, true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, 3),
+                with_value(&r2a, DfValue::None),
+                with_value(&r3a, DfValue::None)
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn running_count_includes_peers() {
+        let (mut g, _) = setup(WindowFunction::CountStar, None);
+
+        let r1a: Vec<DfValue> = vec![1.into(), "a".into()];
+        let r2a: Vec<DfValue> = vec![2.into(), "a".into()];
+
+        let res = g.narrow_one(vec![r1a.clone(), r1a.clone(), r2a.clone()], true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1a, 2),
+                with_value(&r1a, 2),
+                with_value(&r2a, 3)
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn running_sum_over_many_distinct_values() {
+        let mut g = MockGraph::new();
+        let s = g.add_base("source", &["x", "y"]);
+        // SUM(x) OVER (PARTITION BY y ORDER BY x)
+        g.set_op(
+            "window",
+            &["x", "y", "w"],
+            Window::new(
+                s.as_global(),
+                WindowFunction::Sum,
+                Some(0),
+                vec![1],
+                vec![(0, OrderType::OrderAscending)],
+                Some(&DfType::Double),
+            )
+            .unwrap(),
+            true,
+        );
+
+        let rows = (0..1000)
+            .map(|x| vec![DfValue::Double(x as f64), "a".into()])
+            .collect::<Vec<_>>();
+        // Insert the rows out of order
+        let res = g.narrow_one(rows.iter().rev().cloned().collect::<Vec<_>>(), true);
+        assert_eq!(
+            res,
+            rows.iter()
+                .enumerate()
+                .map(|(x, r)| with_value(r, DfValue::Double((x * (x + 1) / 2) as f64)))
+                .collect::<Vec<_>>()
+                .into()
+        );
+    }
+
+    #[test]
+    fn running_max_ignores_nulls() {
+        let mut g = MockGraph::new();
+        let s = g.add_base("source", &["x", "y", "z"]);
+        g.set_op(
+            "window",
+            &["x", "y", "z", "w"],
+            Window::new(
+                s.as_global(),
+                WindowFunction::Max,
+                Some(2),
+                vec![1],
+                vec![(0, OrderType::OrderAscending)],
+                Some(&DfType::Int),
+            )
+            .unwrap(),
+            true,
+        );
+
+        let r1: Vec<DfValue> = vec![1.into(), "a".into(), DfValue::None];
+        let r2: Vec<DfValue> = vec![2.into(), "a".into(), 5.into()];
+        let r3: Vec<DfValue> = vec![3.into(), "a".into(), 3.into()];
+
+        let res = g.narrow_one(vec![r1.clone(), r2.clone(), r3.clone()], true);
+        assert_eq!(
+            res,
+            vec![
+                with_value(&r1, DfValue::None),
+                with_value(&r2, 5),
+                with_value(&r3, 5)
+            ]
+            .into()
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::{self, Display};
 
 use dataflow_state::MaterializedNodeState;
 use itertools::Itertools;
+use nom_sql::CachePriority;
 use readyset_client::{self, KeyComparison, PacketData, PacketTrace};
 use readyset_data::DfType;
 use serde::{Deserialize, Serialize};
@@ -311,6 +312,13 @@ pub enum DomainRequest {
     /// Ask domain to log its state size
     UpdateStateSize,
 
+    /// Set the priority to evict the partial state of the given (non-reader) node with, which
+    /// should be the highest priority of any of the caches the node feeds
+    SetEvictionPriority {
+        node: LocalNodeIndex,
+        priority: CachePriority,
+    },
+
     /// Inform domain about a new replay path.
     SetupReplayPath {
         tag: Tag,
@@ -372,6 +380,24 @@ pub enum DomainRequest {
     /// Used for base table nodes, since the initialization of the persistent state for those nodes
     /// is done in a different thread.
     IsReady { node: LocalNodeIndex },
+
+    /// Change the replica index of this domain.
+    ///
+    /// This is used to promote a hot standby replica of a domain containing base tables to be the
+    /// first replica (which is the only one that sends anything downstream) when the worker
+    /// running the first replica fails.
+    SetReplica { replica: usize },
+
+    /// Request the number of keys saved by partial readers before a restart that still haven't
+    /// been replayed into the readers.
+    PendingReaderPrefills,
+
+    /// Request all the keys in each partial reader in this domain, keyed by the local index of
+    /// the reader.
+    ///
+    /// This is used to fill the readers of the new instance of a domain that's being moved to
+    /// another worker.
+    ReaderKeys,
 }
 
 /// The primary unit of communication between nodes in the dataflow graph.
@@ -409,6 +435,8 @@ pub enum Packet {
     /// Trigger an eviction from the target node.
     Evict {
         node: Option<LocalNodeIndex>,
+        /// If set, only evict from partial state with this priority
+        priority: Option<CachePriority>,
         num_bytes: usize,
     },
 
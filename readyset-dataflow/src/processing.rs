@@ -821,4 +821,11 @@ where
     fn requires_full_materialization(&self) -> bool {
         false
     }
+
+    /// Returns true if this operator needs to read all the rows in the materialized state of its
+    /// parents, rather than just looking up keys in them. Indexes for lookups into the parents of
+    /// such an operator are never pushed through query-through nodes.
+    fn requires_materialized_parents(&self) -> bool {
+        false
+    }
 }
@@ -616,6 +616,10 @@ pub enum ReadySetError {
     #[error("Malformed array literal '{}': {}", Sensitive(&input), message)]
     ArrayParseError { input: String, message: String },
 
+    /// Error when parsing a string as a literal for a range value
+    #[error("Malformed range literal '{}': {}", Sensitive(&input), message)]
+    RangeParseError { input: String, message: String },
+
     #[error("Change in DDL requires partial resnapshot")]
     ResnapshotNeeded,
 
@@ -804,6 +808,12 @@ impl ReadySetError {
         self.any_cause(|e| matches!(e, Self::ViewDestroyed))
     }
 
+    /// Returns true if the error either *is* [`ReaderNotFound`], or was *caused by*
+    /// [`ReaderNotFound`]
+    pub fn caused_by_reader_not_found(&self) -> bool {
+        self.any_cause(|e| matches!(e, Self::ReaderNotFound))
+    }
+
     /// Returns true if the error either *is* [`SerializationFailed`], or was *caused by*
     /// [`SerializationFailed`]
     pub fn caused_by_serialization_failed(&self) -> bool {
@@ -109,7 +109,8 @@ fn is_ddl(query: &SqlQuery) -> bool {
         | SqlQuery::Use(_)
         | SqlQuery::CreateCache(_)
         | SqlQuery::DropCache(_)
-        | SqlQuery::DropAllCaches(_) => true,
+        | SqlQuery::DropAllCaches(_)
+        | SqlQuery::AlterReadySet(_) => true,
     }
 }
 
@@ -250,7 +250,9 @@ impl MirGraph {
                 .collect(),
             MirNodeInner::Join { project, .. }
             | MirNodeInner::LeftJoin { project, .. }
-            | MirNodeInner::DependentJoin { project, .. } => project.clone(),
+            | MirNodeInner::FullJoin { project, .. }
+            | MirNodeInner::DependentJoin { project, .. }
+            | MirNodeInner::DependentLeftJoin { project, .. } => project.clone(),
             MirNodeInner::JoinAggregates => {
                 let cols = self
                     // see note [edge-ordering]
@@ -289,6 +291,10 @@ impl MirGraph {
                 .into_iter()
                 .chain(iter::once(MirColumn::named(&*PAGE_NUMBER_COL)))
                 .collect(),
+            MirNodeInner::Window { output_column, .. } => parent_columns()
+                .into_iter()
+                .chain(iter::once(output_column.clone()))
+                .collect(),
             MirNodeInner::Distinct { group_by } => group_by
                 .iter()
                 .cloned()
@@ -492,6 +498,7 @@ mod tests {
             MirNodeInner::Join {
                 on: vec![],
                 project: vec![],
+                residual: None,
             },
         ));
         graph.add_edge(t1, join, 0);
@@ -4,6 +4,7 @@ use common::{DfValue, IndexType};
 use dataflow::ops::grouped::aggregate::Aggregation;
 use dataflow::ops::grouped::extremum::Extremum;
 use dataflow::ops::union;
+use dataflow::ops::window::WindowFunction;
 use dataflow::PostLookupAggregates;
 use itertools::Itertools;
 use nom_sql::{BinaryOperator, ColumnSpecification, Expr, OrderType, Relation, SqlIdentifier};
@@ -113,6 +114,9 @@ pub enum MirNodeInner {
         on: Vec<(Column, Column)>,
         /// Columns (from both parents) to project in the output.
         project: Vec<Column>,
+        /// Remaining (non-equality) conditions of the join, which are evaluated on each pair of
+        /// rows that match on the join keys. Can only reference columns in `project`.
+        residual: Option<Expr>,
     },
     /// JoinAggregates is a special type of join for joining two aggregates together. This is
     /// different from other operators in that it doesn't map 1:1 to a SQL operator and there are
@@ -139,6 +143,29 @@ pub enum MirNodeInner {
         on: Vec<(Column, Column)>,
         /// Columns (from both parents) to project in the output.
         project: Vec<Column>,
+        /// Remaining (non-equality) conditions of the join, which are evaluated on each pair of
+        /// rows that match on the join keys. Rows in the left parent with no matches in the right
+        /// parent that satisfy these conditions are padded with nulls. Can only reference columns
+        /// in `project`.
+        residual: Option<Expr>,
+    },
+    /// Node which computes a *full outer* join on its two parents, emitting all pairs of rows that
+    /// match on the values in `on`, plus all rows in either parent with no matches in the other,
+    /// padded with nulls.
+    ///
+    /// Converted to [`Join`] with [`JoinType::Full`] when lowering to dataflow.
+    ///
+    /// [`Join`]: dataflow::ops::join::Join
+    /// [`JoinType::Full`]: dataflow::ops::join::JoinType::Full
+    FullJoin {
+        /// Columns to use as the join keys. Each tuple corresponds to a column in the left parent
+        /// and column in the right parent.
+        on: Vec<(Column, Column)>,
+        /// Columns (from both parents) to project in the output.
+        project: Vec<Column>,
+        /// Remaining (non-equality) conditions of the join, which are evaluated on each pair of
+        /// rows that match on the join keys. Can only reference columns in `project`.
+        residual: Option<Expr>,
     },
     /// Join where nodes in the right-hand side depend on columns in the left-hand side
     /// (referencing tables in `dependent_tables`). These are created during compilation for
@@ -157,6 +184,23 @@ pub enum MirNodeInner {
         /// Columns (from both parents) to project in the output.
         project: Vec<Column>,
     },
+    /// Left join where nodes in the right-hand side depend on columns in the left-hand side. This
+    /// is the left-join equivalent of a [`DependentJoin`], and is created during compilation for
+    /// correlated subqueries whose results must be null-padded when the subquery is empty, such as
+    /// `NOT IN` subqueries.
+    ///
+    /// Like [`DependentJoin`], these must be removed entirely by rewrite passes before lowering to
+    /// dataflow. Since filters can't be lifted past the right-hand side of a left join, only
+    /// dependent equality filters (which become join keys) can be decorrelated through this node.
+    ///
+    /// [`DependentJoin`]: MirNodeInner::DependentJoin
+    DependentLeftJoin {
+        /// Columns to use as the join keys. Each tuple corresponds to a column in the left parent
+        /// and column in the right parent.
+        on: Vec<(Column, Column)>,
+        /// Columns (from both parents) to project in the output.
+        project: Vec<Column>,
+    },
     /// Represents view key placeholders in a query that have not yet been added to the [`Leaf`][]
     /// node of the query.
     ///
@@ -235,6 +279,25 @@ pub enum MirNodeInner {
         /// LIMIT clause
         limit: usize,
     },
+    /// Node which computes a window function over partitions of its input, ordered by a set of
+    /// columns, outputting its result as an additional column at the end of each row.
+    ///
+    /// Converted to [`Window`] when lowering to dataflow.
+    ///
+    /// [`Window`]: dataflow::ops::window::Window
+    Window {
+        /// Which window function we are computing
+        function: WindowFunction,
+        /// Column to compute the window function over, for functions which take an argument
+        over: Option<Column>,
+        /// List of columns to partition by
+        partition_by: Vec<Column>,
+        /// Set of columns used for ordering rows within each partition
+        order_by: Vec<(Column, OrderType)>,
+        /// The column name to use for the result of the window function, which will always be the
+        /// last column
+        output_column: Column,
+    },
     /// Node which emits only distinct rows per some group.
     ///
     /// Converted to [`Aggregator`] with [`Aggregation::Count`] when lowering to dataflow.
@@ -313,7 +376,9 @@ impl MirNodeInner {
             }
             MirNodeInner::Join { project, .. }
             | MirNodeInner::LeftJoin { project, .. }
-            | MirNodeInner::DependentJoin { project, .. } => {
+            | MirNodeInner::FullJoin { project, .. }
+            | MirNodeInner::DependentJoin { project, .. }
+            | MirNodeInner::DependentLeftJoin { project, .. } => {
                 if !project.contains(&c) {
                     project.push(c);
                 }
@@ -341,15 +406,23 @@ impl MirNodeInner {
                 group_by.push(c);
                 Ok(true)
             }
+            MirNodeInner::Window { partition_by, .. } => {
+                partition_by.push(c);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
 
-    /// Returns `true` if self is a [`DependentJoin`].
+    /// Returns `true` if self is a [`DependentJoin`] or a [`DependentLeftJoin`].
     ///
     /// [`DependentJoin`]: MirNodeInner::DependentJoin
+    /// [`DependentLeftJoin`]: MirNodeInner::DependentLeftJoin
     pub fn is_dependent_join(&self) -> bool {
-        matches!(self, Self::DependentJoin { .. })
+        matches!(
+            self,
+            Self::DependentJoin { .. } | Self::DependentLeftJoin { .. }
+        )
     }
 
     /// Returns `true` if self is a [`ViewKey`].
@@ -374,6 +447,21 @@ impl MirNodeInner {
                     Aggregation::GroupConcat { separator: ref s } => {
                         format!("||([{}], \"{}\")", on.name.as_str(), s.as_str())
                     }
+                    Aggregation::Stddev { sample } => format!(
+                        "STDDEV_{}({})",
+                        if sample { "SAMP" } else { "POP" },
+                        on.name.as_str()
+                    ),
+                    Aggregation::Variance { sample } => format!(
+                        "VAR_{}({})",
+                        if sample { "SAMP" } else { "POP" },
+                        on.name.as_str()
+                    ),
+                    Aggregation::BitAnd => format!("BIT_AND({})", on.name.as_str()),
+                    Aggregation::BitOr => format!("BIT_OR({})", on.name.as_str()),
+                    Aggregation::BitXor => format!("BIT_XOR({})", on.name.as_str()),
+                    Aggregation::BoolAnd => format!("BOOL_AND({})", on.name.as_str()),
+                    Aggregation::BoolOr => format!("BOOL_OR({})", on.name.as_str()),
                 };
                 let group_cols = group_by
                     .iter()
@@ -429,11 +517,16 @@ impl MirNodeInner {
             MirNodeInner::Join {
                 ref on,
                 ref project,
-                ..
+                ref residual,
             } => {
                 let jc = on
                     .iter()
                     .map(|(l, r)| format!("{}:{}", l.name, r.name))
+                    .chain(
+                        residual
+                            .iter()
+                            .map(|r| r.display(nom_sql::Dialect::MySQL).to_string()),
+                    )
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!(
@@ -458,11 +551,16 @@ impl MirNodeInner {
             MirNodeInner::LeftJoin {
                 ref on,
                 ref project,
-                ..
+                ref residual,
             } => {
                 let jc = on
                     .iter()
                     .map(|(l, r)| format!("{}:{}", l.name, r.name))
+                    .chain(
+                        residual
+                            .iter()
+                            .map(|r| r.display(nom_sql::Dialect::MySQL).to_string()),
+                    )
                     .collect::<Vec<_>>()
                     .join(", ");
                 format!(
@@ -475,6 +573,31 @@ impl MirNodeInner {
                     jc
                 )
             }
+            MirNodeInner::FullJoin {
+                ref on,
+                ref project,
+                ref residual,
+            } => {
+                let jc = on
+                    .iter()
+                    .map(|(l, r)| format!("{}:{}", l.name, r.name))
+                    .chain(
+                        residual
+                            .iter()
+                            .map(|r| r.display(nom_sql::Dialect::MySQL).to_string()),
+                    )
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "⟗ [{} on {}]",
+                    project
+                        .iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    jc
+                )
+            }
             MirNodeInner::DependentJoin {
                 ref on,
                 ref project,
@@ -488,6 +611,19 @@ impl MirNodeInner {
                         .join(", ")
                 )
             }
+            MirNodeInner::DependentLeftJoin {
+                ref on,
+                ref project,
+                ..
+            } => {
+                format!(
+                    "⟕ | {} on: {}",
+                    project.iter().map(|c| &c.name).join(", "),
+                    on.iter()
+                        .map(|(l, r)| format!("{}:{}", l.name, r.name))
+                        .join(", ")
+                )
+            }
             MirNodeInner::Project {
                 ref emit,
                 ref literals,
@@ -525,6 +661,21 @@ impl MirNodeInner {
             } => {
                 format!("TopK [k: {}, {:?}]", limit, order)
             }
+            MirNodeInner::Window {
+                ref function,
+                ref over,
+                ref partition_by,
+                ref order_by,
+                ..
+            } => {
+                format!(
+                    "Window [{}({}) γ[{}], {:?}]",
+                    function,
+                    over.as_ref().map(|c| c.name.as_str()).unwrap_or_default(),
+                    partition_by.iter().map(|c| c.name.as_str()).join(", "),
+                    order_by
+                )
+            }
             MirNodeInner::Union {
                 ref emit,
                 ref duplicate_mode,
@@ -3,6 +3,7 @@ use std::iter;
 use itertools::{Either, Itertools};
 use nom_sql::analysis::ReferredColumns;
 use nom_sql::{BinaryOperator, Expr};
+use petgraph::Direction;
 use readyset_errors::{internal, invariant, unsupported, ReadySetResult};
 use tracing::{instrument, trace};
 
@@ -19,6 +20,9 @@ use crate::{Column, NodeIndex};
 /// - [`Project`], [`Join`], [`LeftJoin`], and dependent joins *other* than the one this filter
 ///   depends on are all totally commutative with filters, so can be swapped in position with those
 ///   filters with impunity
+/// - Equality filters reaching the dependent join they depend on are turned into join keys. For
+///   [`DependentLeftJoin`]s, this is the *only* kind of filter we can lift, since lifting any other
+///   filter above a left join would change the null-padded rows it emits
 /// - Grouped nodes ([`Aggregation`] and [`Extremum`]) require adding any *non* dependent columns
 ///   mentioned in the filter to the group-by of the node.
 /// - All other nodes currently return an [unsupported error][] - it *is* theoretically possible to
@@ -30,6 +34,7 @@ use crate::{Column, NodeIndex};
 /// [filter]: MirNodeInner::Filter
 /// [`Project`]: MirNodeInner::Project
 /// [`Join`]: MirNodeInner::LeftJoin
+/// [`DependentLeftJoin`]: MirNodeInner::DependentLeftJoin
 /// [`Aggregation`]: MirNodeInner::Aggregation
 /// [`Extremum`]: MirNodeInner::Extremum
 /// [unsupported error]: noria_errors::ReadySetError::Unsupported
@@ -83,7 +88,9 @@ fn push_dependent_filter(
         child_idx.index()
     );
     let should_insert = match &mut query.get_node_mut(child_idx).unwrap().inner {
-        MirNodeInner::DependentJoin { on, .. } if child_idx == dependent_join_idx => {
+        MirNodeInner::DependentJoin { on, .. } | MirNodeInner::DependentLeftJoin { on, .. }
+            if child_idx == dependent_join_idx =>
+        {
             match dependency {
                 DependentCondition::JoinKey { lhs, rhs } => {
                     on.push((lhs.clone(), rhs.clone()));
@@ -91,7 +98,19 @@ fn push_dependent_filter(
                     query.graph.add_column(child_idx, rhs)?;
                     false
                 }
-                DependentCondition::FullyDependent { .. } => true,
+                DependentCondition::FullyDependent { .. } => {
+                    // Lifting a filter above a left join would filter out the null-padded rows
+                    // emitted for left-hand rows with no matches on the right
+                    if matches!(
+                        query.get_node(child_idx).unwrap().inner,
+                        MirNodeInner::DependentLeftJoin { .. }
+                    ) {
+                        unsupported!(
+                            "Can't decorrelate non-equality dependent filters below a left join"
+                        );
+                    }
+                    true
+                }
             }
         }
         MirNodeInner::Project { .. }
@@ -99,8 +118,11 @@ fn push_dependent_filter(
         | MirNodeInner::Join { .. }
         | MirNodeInner::LeftJoin { .. }
         | MirNodeInner::DependentJoin { .. }
+        | MirNodeInner::DependentLeftJoin { .. }
         | MirNodeInner::AliasTable { .. } => true,
-        MirNodeInner::Aggregation { .. } | MirNodeInner::Extremum { .. } => {
+        MirNodeInner::Aggregation { .. }
+        | MirNodeInner::Extremum { .. }
+        | MirNodeInner::Window { .. } => {
             for col in dependency.non_dependent_columns() {
                 query.graph.add_column(child_idx, col.clone())?;
             }
@@ -196,18 +218,32 @@ pub(crate) fn eliminate_dependent_joins(query: &mut MirQuery<'_>) -> ReadySetRes
             .flat_map(|n| query.graph.columns(n))
             .collect::<Vec<_>>();
 
+        // A column referenced in the right-hand side of the join is dependent if it's a column on
+        // the left-hand side of the join, and *isn't* a column that the node referencing it can
+        // get from its own ancestors - if it is, it refers to a column in the subquery itself,
+        // which shadows the column of the same name in the outer query. This also lets the same
+        // tables be referenced on both sides of the join, which can happen if the left-hand side
+        // itself contains (already decorrelated) subqueries on the same tables.
+        let is_dependent = |node: NodeIndex, col: &nom_sql::Column| {
+            left_columns.iter().any(|c| *c == *col)
+                && !query
+                    .graph
+                    .neighbors_directed(node, Direction::Incoming)
+                    .any(|parent| query.graph.provides_column(parent, &Column::from(col)))
+        };
+
         // If a node is a dependent filter, return a description of *how* it's a dependent
         // filter
-        let dependent_condition = |inner: &MirNodeInner| {
-            if let MirNodeInner::Filter { conditions } = inner {
+        let dependent_condition = |node: NodeIndex| {
+            if let MirNodeInner::Filter { conditions } = &query.get_node(node).unwrap().inner {
                 match conditions {
                     Expr::BinaryOp {
                         lhs: box Expr::Column(left_col),
                         op: BinaryOperator::Equal,
                         rhs: box Expr::Column(right_col),
                     } => {
-                        let matches_left = left_columns.iter().any(|c| *c == *left_col);
-                        let matches_right = left_columns.iter().any(|c| *c == *right_col);
+                        let matches_left = is_dependent(node, left_col);
+                        let matches_right = is_dependent(node, right_col);
                         match (matches_left, matches_right) {
                             // Both sides are dependent
                             (true, true) => {
@@ -233,14 +269,12 @@ pub(crate) fn eliminate_dependent_joins(query: &mut MirQuery<'_>) -> ReadySetRes
                     expr => {
                         if expr
                             .referred_columns()
-                            .any(|expr_col| left_columns.iter().any(|c| *c == *expr_col))
+                            .any(|expr_col| is_dependent(node, expr_col))
                         {
                             return Some(DependentCondition::FullyDependent {
                                 non_dependent_cols: expr
                                     .referred_columns()
-                                    .filter(|expr_col| {
-                                        !left_columns.iter().any(|c| *c == **expr_col)
-                                    })
+                                    .filter(|expr_col| !is_dependent(node, expr_col))
                                     .map(|c| c.into())
                                     .collect(),
                             });
@@ -251,11 +285,11 @@ pub(crate) fn eliminate_dependent_joins(query: &mut MirQuery<'_>) -> ReadySetRes
             None
         };
 
-        let dependency = match dependent_condition(&query.get_node(right_parent).unwrap().inner) {
+        let dependency = match dependent_condition(right_parent) {
             Some(dep) => Some((right_parent, dep)),
-            None => query.topo_ancestors(right_parent)?.find_map(|n| {
-                dependent_condition(&query.get_node(n).unwrap().inner).map(|dep| (n, dep))
-            }),
+            None => query
+                .topo_ancestors(right_parent)?
+                .find_map(|n| dependent_condition(n).map(|dep| (n, dep))),
         };
 
         if let Some((node, dependency)) = dependency {
@@ -271,6 +305,12 @@ pub(crate) fn eliminate_dependent_joins(query: &mut MirQuery<'_>) -> ReadySetRes
                 MirNodeInner::DependentJoin { on, project } => MirNodeInner::Join {
                     on: on.clone(),
                     project: project.clone(),
+                    residual: None,
+                },
+                MirNodeInner::DependentLeftJoin { on, project } => MirNodeInner::LeftJoin {
+                    on: on.clone(),
+                    project: project.clone(),
+                    residual: None,
                 },
                 _ => unreachable!("Already checked is_dependent_join above"),
             };
@@ -518,6 +558,272 @@ mod tests {
         assert!(pull_result.is_ok(), "{}", pull_result.err().unwrap());
     }
 
+    #[test]
+    fn dependent_left_join() {
+        readyset_tracing::init_test_logging();
+        // query looks something like:
+        //     SELECT t1.a FROM t1 WHERE t1.b NOT IN (SELECT t2.b FROM t2 WHERE t2.a = t1.a)
+        let mut graph = MirGraph::new();
+
+        let query_name = Relation::from("q");
+
+        let make_base = |graph: &mut MirGraph, table: &str| {
+            let node = graph.add_node(MirNode::new(
+                table.into(),
+                MirNodeInner::Base {
+                    column_specs: ["a", "b"]
+                        .iter()
+                        .map(|col| ColumnSpecification {
+                            column: nom_sql::Column::from(format!("{table}.{col}").as_str()),
+                            sql_type: SqlType::Int(None),
+                            constraints: vec![],
+                            comment: None,
+                        })
+                        .collect(),
+                    primary_key: Some([Column::new(Some(table), "a")].into()),
+                    unique_keys: Default::default(),
+                },
+            ));
+            graph[node].add_owner(query_name.clone());
+            node
+        };
+
+        let t2 = make_base(&mut graph, "t2");
+
+        // -> σ[t2.a = t1.a]
+        let t2_filter = graph.add_node(MirNode::new(
+            "t2_filter".into(),
+            MirNodeInner::Filter {
+                conditions: Expr::BinaryOp {
+                    lhs: Box::new(Expr::Column("t2.a".into())),
+                    op: BinaryOperator::Equal,
+                    rhs: Box::new(Expr::Column("t1.a".into())),
+                },
+            },
+        ));
+        graph[t2_filter].add_owner(query_name.clone());
+        graph.add_edge(t2, t2_filter, 0);
+
+        let t1 = make_base(&mut graph, "t1");
+
+        // -> ⟕ on: l.b ≡ r.b
+        let left_join = graph.add_node(MirNode::new(
+            "left_join".into(),
+            MirNodeInner::DependentLeftJoin {
+                on: vec![(Column::new(Some("t1"), "b"), Column::new(Some("t2"), "b"))],
+                project: vec![
+                    Column::new(Some("t1"), "a"),
+                    Column::new(Some("t1"), "b"),
+                    Column::new(Some("t2"), "a"),
+                    Column::new(Some("t2"), "b"),
+                ],
+            },
+        ));
+        graph[left_join].add_owner(query_name.clone());
+        graph.add_edge(t1, left_join, 0);
+        graph.add_edge(t2_filter, left_join, 1);
+
+        let leaf = graph.add_node(MirNode::new(
+            "q".into(),
+            MirNodeInner::leaf(vec![], IndexType::HashMap),
+        ));
+        graph[leaf].add_owner(query_name.clone());
+        graph.add_edge(left_join, leaf, 0);
+
+        let mut query = MirQuery::new(query_name, leaf, &mut graph);
+
+        eliminate_dependent_joins(&mut query).unwrap();
+
+        match &query.graph[left_join].inner {
+            MirNodeInner::LeftJoin { on, .. } => {
+                assert_eq!(
+                    on,
+                    &[
+                        (Column::new(Some("t1"), "b"), Column::new(Some("t2"), "b")),
+                        (Column::new(Some("t1"), "a"), Column::new(Some("t2"), "a")),
+                    ]
+                );
+            }
+            _ => panic!(
+                "should have rewritten dependent left join to left join (got: {})",
+                query.graph[left_join].name().display_unquoted()
+            ),
+        }
+
+        assert!(
+            !query.graph.contains_node(t2_filter),
+            "t2_filter should be removed"
+        );
+    }
+
+    #[test]
+    fn scalar_subquery_aggregate() {
+        readyset_tracing::init_test_logging();
+        // query looks something like:
+        //     SELECT t1.a, (SELECT COUNT(t2.b) FROM t2 WHERE t2.a = t1.a) FROM t1
+        let mut graph = MirGraph::new();
+
+        let query_name = Relation::from("q");
+
+        let make_base = |graph: &mut MirGraph, table: &str| {
+            let node = graph.add_node(MirNode::new(
+                table.into(),
+                MirNodeInner::Base {
+                    column_specs: ["a", "b"]
+                        .iter()
+                        .map(|col| ColumnSpecification {
+                            column: nom_sql::Column::from(format!("{table}.{col}").as_str()),
+                            sql_type: SqlType::Int(None),
+                            constraints: vec![],
+                            comment: None,
+                        })
+                        .collect(),
+                    primary_key: Some([Column::new(Some(table), "a")].into()),
+                    unique_keys: Default::default(),
+                },
+            ));
+            graph[node].add_owner(query_name.clone());
+            node
+        };
+
+        let t2 = make_base(&mut graph, "t2");
+
+        // -> σ[t2.a = t1.a]
+        let t2_filter = graph.add_node(MirNode::new(
+            "t2_filter".into(),
+            MirNodeInner::Filter {
+                conditions: Expr::BinaryOp {
+                    lhs: Box::new(Expr::Column("t2.a".into())),
+                    op: BinaryOperator::Equal,
+                    rhs: Box::new(Expr::Column("t1.a".into())),
+                },
+            },
+        ));
+        graph[t2_filter].add_owner(query_name.clone());
+        graph.add_edge(t2, t2_filter, 0);
+
+        // -> π[t2.b, lit: 0]
+        let grp_proj = graph.add_node(MirNode::new(
+            "q_prj_hlpr".into(),
+            MirNodeInner::Project {
+                emit: vec![Column::new(Some("t2"), "b")],
+                expressions: vec![],
+                literals: vec![("grp".into(), DfValue::from(0u32))],
+            },
+        ));
+        graph[grp_proj].add_owner(query_name.clone());
+        graph.add_edge(t2_filter, grp_proj, 0);
+
+        // -> |*|(t2.b) γ[grp]
+        let t2_count = graph.add_node(MirNode::new(
+            "q_t2_count".into(),
+            MirNodeInner::Aggregation {
+                on: Column::new(Some("t2"), "b"),
+                group_by: vec![Column::named("grp")],
+                output_column: Column::named("COUNT(t2.b)"),
+                kind: Aggregation::Count,
+            },
+        ));
+        graph[t2_count].add_owner(query_name.clone());
+        graph.add_edge(grp_proj, t2_count, 0);
+
+        // -> π[COUNT(t2.b), lit: 0]
+        let value_proj = graph.add_node(MirNode::new(
+            "q_scalar_prj".into(),
+            MirNodeInner::Project {
+                emit: vec![],
+                expressions: vec![(
+                    "__scalar_subquery_0".into(),
+                    Expr::Column("COUNT(t2.b)".into()),
+                )],
+                literals: vec![("__scalar_grp".into(), DfValue::from(0u32))],
+            },
+        ));
+        graph[value_proj].add_owner(query_name.clone());
+        graph.add_edge(t2_count, value_proj, 0);
+
+        let t1 = make_base(&mut graph, "t1");
+
+        // -> π[t1.a, lit: 0]
+        let join_key_proj = graph.add_node(MirNode::new(
+            "q_scalar_join_key".into(),
+            MirNodeInner::Project {
+                emit: vec![Column::new(Some("t1"), "a")],
+                expressions: vec![],
+                literals: vec![("__scalar_join_key".into(), DfValue::from(0u32))],
+            },
+        ));
+        graph[join_key_proj].add_owner(query_name.clone());
+        graph.add_edge(t1, join_key_proj, 0);
+
+        // -> ⟕ on: l.__scalar_join_key ≡ r.__scalar_grp
+        let left_join = graph.add_node(MirNode::new(
+            "q_scalar_join".into(),
+            MirNodeInner::DependentLeftJoin {
+                on: vec![(
+                    Column::named("__scalar_join_key"),
+                    Column::named("__scalar_grp"),
+                )],
+                project: vec![
+                    Column::new(Some("t1"), "a"),
+                    Column::named("__scalar_join_key"),
+                    Column::named("__scalar_grp"),
+                    Column::named("__scalar_subquery_0"),
+                ],
+            },
+        ));
+        graph[left_join].add_owner(query_name.clone());
+        graph.add_edge(join_key_proj, left_join, 0);
+        graph.add_edge(value_proj, left_join, 1);
+
+        let leaf = graph.add_node(MirNode::new(
+            "q".into(),
+            MirNodeInner::leaf(vec![], IndexType::HashMap),
+        ));
+        graph[leaf].add_owner(query_name.clone());
+        graph.add_edge(left_join, leaf, 0);
+
+        let mut query = MirQuery::new(query_name, leaf, &mut graph);
+
+        eliminate_dependent_joins(&mut query).unwrap();
+
+        match &query.graph[t2_count].inner {
+            MirNodeInner::Aggregation { group_by, .. } => {
+                assert!(
+                    group_by.contains(&Column::new(Some("t2"), "a")),
+                    "should group the aggregate by the correlated column"
+                );
+            }
+            _ => panic!("should not have replaced the aggregate"),
+        }
+
+        match &query.graph[left_join].inner {
+            MirNodeInner::LeftJoin { on, .. } => {
+                assert_eq!(
+                    on,
+                    &[
+                        (
+                            Column::named("__scalar_join_key"),
+                            Column::named("__scalar_grp")
+                        ),
+                        (Column::new(Some("t1"), "a"), Column::new(Some("t2"), "a")),
+                    ]
+                );
+            }
+            _ => panic!(
+                "should have rewritten dependent left join to left join (got: {})",
+                query.graph[left_join].name().display_unquoted()
+            ),
+        }
+
+        assert!(
+            !query.graph.contains_node(t2_filter),
+            "t2_filter should be removed"
+        );
+
+        pull_all_required_columns(&mut query).unwrap();
+    }
+
     #[test]
     fn multiple_filters_after_agg() {
         readyset_tracing::init_test_logging();
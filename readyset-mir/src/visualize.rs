@@ -162,6 +162,17 @@ impl GraphViz for MirNodeInner {
                     AggregationKind::GroupConcat { separator: s } => {
                         format!("||({}, \"{}\")", on, s)
                     }
+                    AggregationKind::Stddev { sample } => {
+                        format!("STDDEV_{}({})", if *sample { "SAMP" } else { "POP" }, on)
+                    }
+                    AggregationKind::Variance { sample } => {
+                        format!("VAR_{}({})", if *sample { "SAMP" } else { "POP" }, on)
+                    }
+                    AggregationKind::BitAnd => format!("BIT_AND({})", on),
+                    AggregationKind::BitOr => format!("BIT_OR({})", on),
+                    AggregationKind::BitXor => format!("BIT_XOR({})", on),
+                    AggregationKind::BoolAnd => format!("BOOL_AND({})", on),
+                    AggregationKind::BoolOr => format!("BOOL_OR({})", on),
                 };
                 let group_cols = group_by.iter().join(", ");
                 write!(f, "{} | γ: {}", op_string, group_cols)
@@ -210,9 +221,18 @@ impl GraphViz for MirNodeInner {
             }
 
             MirNodeInner::Identity => write!(f, "≡"),
-            MirNodeInner::Join { ref on, .. } => {
+            MirNodeInner::Join {
+                ref on,
+                ref residual,
+                ..
+            } => {
                 let jc = on.iter().map(|(l, r)| format!("{}:{}", l, r)).join(", ");
-                write!(f, "⋈  | on: {}", jc)
+                write!(f, "⋈  | on: {}", jc)?;
+                if let Some(residual) = residual {
+                    // FIXME(ENG-2502): Use correct dialect.
+                    write!(f, "\\nσ: {}", residual.display(nom_sql::Dialect::MySQL))?;
+                }
+                Ok(())
             }
             MirNodeInner::JoinAggregates => write!(f, "AGG ⋈"),
             MirNodeInner::Leaf {
@@ -261,6 +281,12 @@ impl GraphViz for MirNodeInner {
                                     PostLookupAggregateFunction::GroupConcat { .. } => "GC",
                                     PostLookupAggregateFunction::Max => "Max",
                                     PostLookupAggregateFunction::Min => "Min",
+                                    PostLookupAggregateFunction::BitAnd => "BitAnd",
+                                    PostLookupAggregateFunction::BitOr => "BitOr",
+                                    PostLookupAggregateFunction::BitXor => "BitXor",
+                                    PostLookupAggregateFunction::BoolAnd => "BoolAnd",
+                                    PostLookupAggregateFunction::BoolOr => "BoolOr",
+                                    PostLookupAggregateFunction::Variance { .. } => "Var",
                                 },
                                 &aggregate.column
                             ))
@@ -271,9 +297,31 @@ impl GraphViz for MirNodeInner {
 
                 Ok(())
             }
-            MirNodeInner::LeftJoin { ref on, .. } => {
+            MirNodeInner::LeftJoin {
+                ref on,
+                ref residual,
+                ..
+            } => {
                 let jc = on.iter().map(|(l, r)| format!("{}:{}", l, r)).join(", ");
-                write!(f, "⋉  | on: {}", jc)
+                write!(f, "⋉  | on: {}", jc)?;
+                if let Some(residual) = residual {
+                    // FIXME(ENG-2502): Use correct dialect.
+                    write!(f, "\\nσ: {}", residual.display(nom_sql::Dialect::MySQL))?;
+                }
+                Ok(())
+            }
+            MirNodeInner::FullJoin {
+                ref on,
+                ref residual,
+                ..
+            } => {
+                let jc = on.iter().map(|(l, r)| format!("{}:{}", l, r)).join(", ");
+                write!(f, "⟗  | on: {}", jc)?;
+                if let Some(residual) = residual {
+                    // FIXME(ENG-2502): Use correct dialect.
+                    write!(f, "\\nσ: {}", residual.display(nom_sql::Dialect::MySQL))?;
+                }
+                Ok(())
             }
             MirNodeInner::DependentJoin { ref on, .. } => {
                 write!(
@@ -282,6 +330,13 @@ impl GraphViz for MirNodeInner {
                     on.iter().map(|(l, r)| format!("{}:{}", l, r)).join(", ")
                 )
             }
+            MirNodeInner::DependentLeftJoin { ref on, .. } => {
+                write!(
+                    f,
+                    "⟕ | on: {}",
+                    on.iter().map(|(l, r)| format!("{}:{}", l, r)).join(", ")
+                )
+            }
             MirNodeInner::Project {
                 ref emit,
                 ref literals,
@@ -338,6 +393,26 @@ impl GraphViz for MirNodeInner {
                     .unwrap_or_else(|| "".into());
                 write!(f, "TopK [k: {}; {}]", limit, order)
             }
+            MirNodeInner::Window {
+                ref function,
+                ref over,
+                ref partition_by,
+                ref order_by,
+                ..
+            } => {
+                let order = order_by
+                    .iter()
+                    .map(|(c, o)| format!("{}: {}", c.name.as_str(), o))
+                    .join(", ");
+                write!(
+                    f,
+                    "Window [{}({}) | γ: {}; {}]",
+                    function,
+                    over.as_ref().map(|c| c.name.as_str()).unwrap_or_default(),
+                    partition_by.iter().join(", "),
+                    order
+                )
+            }
             MirNodeInner::Union {
                 ref emit,
                 ref duplicate_mode,
@@ -150,6 +150,9 @@ async fn write_column<W: AsyncWrite + Unpin>(
         DfValue::Array(_) => {
             internal!("Cannot write MySQL column: MySQL does not support arrays")
         }
+        DfValue::Range(_) => {
+            internal!("Cannot write MySQL column: MySQL does not support ranges")
+        }
         DfValue::BitVector(_) => {
             internal!("Cannot write MySQL column: MySQL does not support bit vectors")
         }
@@ -80,6 +80,7 @@ pub(crate) fn convert_column(col: &ColumnSchema) -> ReadySetResult<mysql_srv::Co
         }
         DfType::VarBit(_) => unsupported!("MySQL does not support the bit varying type"),
         DfType::Array(_) => unsupported!("MySQL does not support arrays"),
+        DfType::Range(_) => unsupported!("MySQL does not support range types"),
     };
 
     for c in col.base.iter().flat_map(|b| &b.constraints) {
@@ -504,7 +504,7 @@ impl UpstreamDatabase for MySqlUpstream {
     async fn handle_ryw_write<'a, S>(
         &'a mut self,
         query: S,
-    ) -> Result<(Self::QueryResult<'a>, String), Error>
+    ) -> Result<(Self::QueryResult<'a>, Option<String>), Error>
     where
         S: AsRef<str> + Send + Sync + 'a,
     {
@@ -529,7 +529,7 @@ impl UpstreamDatabase for MySqlUpstream {
                 last_inserted_id: last_insert_id.unwrap_or(0),
                 status_flags,
             },
-            txid,
+            Some(txid),
         ))
     }
 
@@ -6,6 +6,7 @@ use readyset_client_metrics::QueryDestination;
 use readyset_client_test_helpers::mysql_helpers::{last_query_info, MySQLAdapter};
 use readyset_client_test_helpers::{self, sleep, TestBuilder};
 use readyset_server::Handle;
+use readyset_util::eventually;
 use readyset_util::hash::hash;
 use readyset_util::shutdown::ShutdownSender;
 use serial_test::serial;
@@ -783,6 +784,63 @@ async fn switch_database_with_use() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn alter_readyset_add_and_drop_tables() {
+    let (opts, _handle, shutdown_tx) =
+        TestBuilder::new(BackendBuilder::new().require_authentication(false))
+            .fallback(true)
+            .replication_tables("noria.t1")
+            .build::<MySQLAdapter>()
+            .await;
+    let mut conn = mysql_async::Conn::new(opts).await.unwrap();
+
+    conn.query_drop("CREATE TABLE t1 (id int)").await.unwrap();
+    conn.query_drop("CREATE TABLE t2 (id int)").await.unwrap();
+    conn.query_drop("INSERT INTO t1 VALUES (1)").await.unwrap();
+    conn.query_drop("INSERT INTO t2 VALUES (2)").await.unwrap();
+    sleep().await;
+
+    conn.query_drop("CREATE CACHE FROM SELECT id FROM t1")
+        .await
+        .unwrap();
+    conn.query_drop("CREATE CACHE FROM SELECT id FROM t2")
+        .await
+        .expect_err("t2 isn't replicated yet");
+
+    // Once the added table has been snapshotted, we can cache queries against it
+    conn.query_drop("ALTER READYSET ADD TABLES t2")
+        .await
+        .unwrap();
+    eventually! {
+        conn.query_drop("CREATE CACHE FROM SELECT id FROM t2")
+            .await
+            .is_ok()
+    }
+    let rows: Vec<i32> = conn.query("SELECT id FROM t2").await.unwrap();
+    assert_eq!(rows, vec![2]);
+    assert!(last_statement_matches("readyset", "ok", &mut conn).await);
+
+    // Dropping the table again drops the cache along with it, so queries go upstream
+    conn.query_drop("ALTER READYSET DROP TABLES t2")
+        .await
+        .unwrap();
+    eventually! {
+        let caches: Vec<(String, String, String)> = conn.query("SHOW CACHES").await.unwrap();
+        caches.len() == 1
+    }
+    conn.query_drop("CREATE CACHE FROM SELECT id FROM t2")
+        .await
+        .expect_err("t2 is no longer replicated");
+    conn.query_drop("INSERT INTO t2 VALUES (3)").await.unwrap();
+    let mut rows: Vec<i32> = conn.query("SELECT id FROM t2").await.unwrap();
+    rows.sort();
+    assert_eq!(rows, vec![2, 3]);
+    assert!(last_statement_matches("upstream", "ok", &mut conn).await);
+
+    shutdown_tx.shutdown().await;
+}
+
 #[cfg(feature = "failure_injection")]
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
@@ -1,16 +1,24 @@
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::ops::Deref;
+use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use clap::ValueEnum;
 use eui48::MacAddressFormat;
+use futures::{SinkExt, TryStreamExt};
 use psql_srv as ps;
 use readyset_adapter::backend as cl;
 use readyset_data::DfValue;
+use readyset_errors::{internal_err, unsupported_err};
 use thiserror::Error;
+use tokio_postgres::CopyInSink;
 
+use crate::cancel::{CancelSignal, Cancellation, CancellationRegistry};
+use crate::copy::{parse_copy, CopyDirection, CopyStatement};
 use crate::error::Error;
 use crate::query_handler::PostgreSqlQueryHandler;
 use crate::response::{PrepareResponse, QueryResponse};
@@ -51,6 +59,16 @@ impl FromStr for AuthenticationMethod {
 pub struct Backend {
     inner: cl::Backend<PostgreSqlUpstream, PostgreSqlQueryHandler>,
     authentication_method: AuthenticationMethod,
+    /// This connection's registration for query cancellation, if enabled
+    cancellation: Option<Cancellation>,
+    /// Registry used to cancel queries running on other connections, if enabled
+    cancellation_registry: Option<CancellationRegistry>,
+    /// The sink for a `COPY ... FROM STDIN` currently being proxied to the upstream database, if
+    /// any
+    copy_in: Option<Pin<Box<CopyInSink<Bytes>>>>,
+    /// Prepared statements which are `COPY` statements copying data to or from the client, keyed
+    /// by statement ID, along with the text of the statement
+    copy_statements: HashMap<u32, (String, CopyStatement)>,
 }
 
 impl Backend {
@@ -58,6 +76,10 @@ impl Backend {
         Self {
             inner,
             authentication_method: Default::default(),
+            cancellation: None,
+            cancellation_registry: None,
+            copy_in: None,
+            copy_statements: HashMap::new(),
         }
     }
 
@@ -67,6 +89,29 @@ impl Backend {
             ..self
         }
     }
+
+    /// Register this connection with the given [`CancellationRegistry`], allowing clients to
+    /// cancel queries running on it (and on other connections registered with the same registry)
+    /// using a `CancelRequest`
+    pub fn with_cancellation(self, registry: CancellationRegistry) -> Self {
+        // The handle stays valid if the upstream connection is reset, so it only needs to be
+        // registered once
+        let upstream = self.inner.upstream().map(|u| u.cancel_handle());
+        Self {
+            cancellation: Some(registry.register(upstream)),
+            cancellation_registry: Some(registry),
+            ..self
+        }
+    }
+
+    /// Returns a signal that fires if a cancel request is received for the query about to be run
+    /// on this connection
+    fn cancel_signal(&self) -> CancelSignal {
+        self.cancellation
+            .as_ref()
+            .map(|c| c.signal())
+            .unwrap_or_default()
+    }
 }
 
 impl Deref for Backend {
@@ -89,6 +134,54 @@ impl Backend {
     async fn execute(&mut self, id: u32, params: &[DfValue]) -> Result<QueryResponse<'_>, Error> {
         Ok(QueryResponse(self.inner.execute(id, params).await?))
     }
+
+    /// Proxy a `COPY` statement which copies data to or from the client directly to the upstream
+    /// database
+    ///
+    /// Cancel requests for a copy are forwarded to the upstream database, which ends the copy with
+    /// an error.
+    async fn copy(
+        &mut self,
+        query: &str,
+        copy: CopyStatement,
+    ) -> Result<ps::QueryResponse<Resultset>, ps::Error> {
+        let upstream = self
+            .inner
+            .upstream()
+            .ok_or_else(|| Error::from(unsupported_err!("COPY requires an upstream database")))?;
+
+        // As in PostgreSQL, every column is copied in the overall format of the copy
+        let num_columns = upstream.copy_num_columns(&copy.target).await?;
+        let column_formats = vec![copy.format; num_columns];
+
+        match copy.direction {
+            CopyDirection::In => {
+                self.copy_in = Some(Box::pin(upstream.copy_in(query).await?));
+                Ok(ps::QueryResponse::CopyIn {
+                    overall_format: copy.format,
+                    column_formats,
+                })
+            }
+            CopyDirection::Out => {
+                let data = upstream
+                    .copy_out(query)
+                    .await?
+                    .map_err(|e| ps::Error::from(Error::from(e)));
+                Ok(ps::QueryResponse::CopyOut {
+                    overall_format: copy.format,
+                    column_formats,
+                    data: ps::CopyOutStream(Box::pin(data)),
+                })
+            }
+        }
+    }
+
+    fn copy_in_sink(&mut self) -> Result<&mut Pin<Box<CopyInSink<Bytes>>>, Error> {
+        Ok(self
+            .copy_in
+            .as_mut()
+            .ok_or_else(|| internal_err!("Received COPY data with no COPY in progress"))?)
+    }
 }
 
 #[async_trait]
@@ -107,6 +200,16 @@ impl ps::Backend for Backend {
             .map(|pw| ps::Credentials::CleartextPassword(pw))
     }
 
+    fn cancel_key(&self) -> Option<ps::CancelKey> {
+        self.cancellation.as_ref().map(|c| c.key())
+    }
+
+    async fn on_cancel(&mut self, key: ps::CancelKey) {
+        if let Some(registry) = &self.cancellation_registry {
+            registry.cancel(key).await
+        }
+    }
+
     async fn on_init(&mut self, _database: &str) -> Result<ps::CredentialsNeeded, ps::Error> {
         if self.does_require_authentication() {
             match self.authentication_method {
@@ -119,12 +222,24 @@ impl ps::Backend for Backend {
     }
 
     async fn on_query(&mut self, query: &str) -> Result<ps::QueryResponse<Resultset>, ps::Error> {
-        self.query(query).await?.try_into()
+        if let Some(copy) = parse_copy(query) {
+            return self.copy(query, copy).await;
+        }
+
+        let signal = self.cancel_signal();
+        signal
+            .run(async { self.query(query).await?.try_into() })
+            .await
     }
 
     async fn on_prepare(&mut self, query: &str) -> Result<ps::PrepareResponse, ps::Error> {
         let statement_id = self.next_prepared_id(); // If prepare succeeds it will get this id
-        self.prepare(query).await?.try_into_ps(statement_id)
+        let response = self.prepare(query).await?.try_into_ps(statement_id)?;
+        if let Some(copy) = parse_copy(query) {
+            self.copy_statements
+                .insert(statement_id, (query.to_owned(), copy));
+        }
+        Ok(response)
     }
 
     async fn on_execute(
@@ -132,14 +247,40 @@ impl ps::Backend for Backend {
         statement_id: u32,
         params: &[ps::Value],
     ) -> Result<ps::QueryResponse<Resultset>, ps::Error> {
+        if let Some((query, copy)) = self.copy_statements.get(&statement_id).cloned() {
+            return self.copy(&query, copy).await;
+        }
+
         let params = params
             .iter()
             .map(|p| ParamRef(p).try_into())
             .collect::<Result<Vec<DfValue>, ps::Error>>()?;
-        self.execute(statement_id, &params).await?.try_into()
+        let signal = self.cancel_signal();
+        signal
+            .run(async { self.execute(statement_id, &params).await?.try_into() })
+            .await
+    }
+
+    async fn on_close(&mut self, statement_id: u32) -> Result<(), ps::Error> {
+        self.copy_statements.remove(&statement_id);
+        Ok(())
+    }
+
+    async fn on_copy_data(&mut self, data: Bytes) -> Result<(), ps::Error> {
+        let sink = self.copy_in_sink()?;
+        Ok(sink.send(data).await.map_err(Error::from)?)
+    }
+
+    async fn on_copy_done(&mut self) -> Result<u64, ps::Error> {
+        let mut sink = self.copy_in.take().ok_or_else(|| {
+            Error::from(internal_err!("Received COPY done with no COPY in progress"))
+        })?;
+        Ok(sink.as_mut().finish().await.map_err(Error::from)?)
     }
 
-    async fn on_close(&mut self, _statement_id: u32) -> Result<(), ps::Error> {
+    async fn on_copy_fail(&mut self, _message: &str) -> Result<(), ps::Error> {
+        // Dropping the sink without finishing it aborts the copy on the upstream database
+        self.copy_in = None;
         Ok(())
     }
 }
@@ -180,6 +321,7 @@ impl TryFrom<ParamRef<'_>> for DfValue {
             ps::Value::Json(v) | ps::Value::Jsonb(v) => Ok(DfValue::from(v.to_string())),
             ps::Value::Bit(bits) | ps::Value::VarBit(bits) => Ok(DfValue::from(bits.clone())),
             ps::Value::Array(arr, _) => Ok(DfValue::from(arr.clone())),
+            ps::Value::Range(range, _) => Ok(DfValue::from(range.clone())),
             ps::Value::PassThrough(p) => Ok(DfValue::PassThrough(Arc::new(p.clone()))),
         }
     }
@@ -0,0 +1,244 @@
+//! Support for cancelling queries via the PostgreSQL [`CancelRequest`][0] protocol.
+//!
+//! Each connection to the adapter registers itself with a [`CancellationRegistry`] shared by all
+//! connections, which assigns it a [`ps::CancelKey`] that is sent to the client on startup. When a
+//! client later sends that key in a `CancelRequest` on a fresh connection, the registry is used to
+//! look up the connection the key belongs to and forward the cancellation to that connection's
+//! upstream database (if any), which makes a query proxied there fail with the upstream's own
+//! error. Any other query the connection is waiting on, such as a read from a cache, is aborted.
+//!
+//! [0]: https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.6.7.9
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use psql_srv as ps;
+use rand::Rng;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::upstream::UpstreamCancelHandle;
+
+/// The state shared between a [`Cancellation`] and the [`CancellationRegistry`] it is registered
+/// with
+struct Registration {
+    secret_key: i32,
+    /// Notified when a query running on the connection should be cancelled
+    canceled: Arc<Notify>,
+    /// Handle to cancel the query running on the connection's upstream database, if any
+    upstream: Option<UpstreamCancelHandle>,
+}
+
+/// A registry of all the connections to a single adapter which can have their queries cancelled,
+/// keyed by the (fake) process ID sent to the client in their [`ps::CancelKey`].
+///
+/// Cloning a `CancellationRegistry` returns a handle to the same underlying registry.
+#[derive(Clone, Default)]
+pub struct CancellationRegistry {
+    connections: Arc<Mutex<HashMap<i32, Registration>>>,
+}
+
+impl CancellationRegistry {
+    /// Register a new connection, with the given handle to cancel queries on its upstream
+    /// database (if any), with the registry, returning a [`Cancellation`] containing a freshly
+    /// generated cancel key for that connection.
+    ///
+    /// The connection is removed from the registry when the returned [`Cancellation`] is dropped.
+    pub fn register(&self, upstream: Option<UpstreamCancelHandle>) -> Cancellation {
+        let mut rng = rand::thread_rng();
+        let canceled = Arc::new(Notify::new());
+        let mut connections = self.connections.lock().unwrap();
+        let key = loop {
+            let process_id = rng.gen_range(1..i32::MAX);
+            if !connections.contains_key(&process_id) {
+                break ps::CancelKey {
+                    process_id,
+                    secret_key: rng.gen(),
+                };
+            }
+        };
+        connections.insert(
+            key.process_id,
+            Registration {
+                secret_key: key.secret_key,
+                canceled: canceled.clone(),
+                upstream: upstream.clone(),
+            },
+        );
+
+        Cancellation {
+            key,
+            canceled,
+            upstream,
+            registry: self.clone(),
+        }
+    }
+
+    /// Cancel the query currently running on the connection identified by `key`, if any.
+    ///
+    /// Requests with an unknown process ID or an incorrect secret key are ignored, as in
+    /// PostgreSQL.
+    pub async fn cancel(&self, key: ps::CancelKey) {
+        let (canceled, upstream) = {
+            let connections = self.connections.lock().unwrap();
+            let Some(registration) = connections.get(&key.process_id) else {
+                debug!(
+                    process_id = key.process_id,
+                    "Ignoring cancel request for unknown connection"
+                );
+                return;
+            };
+            if registration.secret_key != key.secret_key {
+                warn!(
+                    process_id = key.process_id,
+                    "Ignoring cancel request with incorrect secret key"
+                );
+                return;
+            }
+
+            (registration.canceled.clone(), registration.upstream.clone())
+        };
+
+        // Cancel the query upstream first, so that if it's running there it's left to fail with
+        // the upstream's error rather than being aborted
+        if let Some(upstream) = upstream {
+            if let Err(error) = upstream.cancel().await {
+                warn!(%error, "Error forwarding cancel request to upstream database");
+            }
+        }
+        canceled.notify_waiters();
+    }
+}
+
+/// The registration of a single connection in a [`CancellationRegistry`], which can be used to run
+/// queries that can be cancelled by a `CancelRequest` from the client.
+pub struct Cancellation {
+    key: ps::CancelKey,
+    canceled: Arc<Notify>,
+    upstream: Option<UpstreamCancelHandle>,
+    registry: CancellationRegistry,
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        self.registry
+            .connections
+            .lock()
+            .unwrap()
+            .remove(&self.key.process_id);
+    }
+}
+
+impl Cancellation {
+    /// The key the client can use to cancel queries running on this connection
+    pub fn key(&self) -> ps::CancelKey {
+        self.key
+    }
+
+    /// Returns a [`CancelSignal`] which can be used to run a query that should be aborted if a
+    /// cancel request for this connection is received
+    pub(crate) fn signal(&self) -> CancelSignal {
+        CancelSignal {
+            canceled: Some(self.canceled.clone()),
+            upstream: self.upstream.clone(),
+        }
+    }
+}
+
+/// A signal, obtained from a [`Cancellation`], which fires when a cancel request is received for
+/// a connection. The default `CancelSignal` never fires.
+#[derive(Clone, Default)]
+pub(crate) struct CancelSignal {
+    canceled: Option<Arc<Notify>>,
+    /// Handle to the connection's upstream database, to tell whether a query is running there
+    upstream: Option<UpstreamCancelHandle>,
+}
+
+impl CancelSignal {
+    /// Run the given future to completion, unless a cancel request is received while it isn't
+    /// waiting on the upstream database, in which case the future is dropped and
+    /// [`ps::Error::QueryCanceled`] is returned.
+    ///
+    /// Queries running on the upstream database are cancelled there, so they're left to return
+    /// the error from the upstream database instead.
+    pub(crate) async fn run<F, T>(self, fut: F) -> Result<T, ps::Error>
+    where
+        F: Future<Output = Result<T, ps::Error>>,
+    {
+        let Some(canceled) = self.canceled else {
+            return fut.await;
+        };
+
+        tokio::pin!(fut);
+        loop {
+            tokio::select! {
+                res = &mut fut => return res,
+                _ = canceled.notified() => {
+                    if !self.upstream.as_ref().map_or(false, |u| u.is_running()) {
+                        return Err(ps::Error::QueryCanceled);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn cancel_running_query() {
+        let registry = CancellationRegistry::default();
+        let cancellation = registry.register(None);
+        let key = cancellation.key();
+
+        let query = tokio::spawn(
+            cancellation
+                .signal()
+                .run(futures::future::pending::<Result<(), ps::Error>>()),
+        );
+        // Give the query a chance to start waiting
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        registry.cancel(key).await;
+
+        assert!(matches!(
+            query.await.unwrap(),
+            Err(ps::Error::QueryCanceled)
+        ));
+    }
+
+    #[tokio::test]
+    async fn wrong_secret_key_is_ignored() {
+        let registry = CancellationRegistry::default();
+        let cancellation = registry.register(None);
+        let key = ps::CancelKey {
+            secret_key: cancellation.key().secret_key.wrapping_add(1),
+            ..cancellation.key()
+        };
+
+        let query = tokio::spawn(
+            cancellation
+                .signal()
+                .run(futures::future::pending::<Result<(), ps::Error>>()),
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        registry.cancel(key).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(!query.is_finished());
+        query.abort();
+    }
+
+    #[test]
+    fn unregister_on_drop() {
+        let registry = CancellationRegistry::default();
+        let cancellation = registry.register(None);
+        assert_eq!(registry.connections.lock().unwrap().len(), 1);
+        drop(cancellation);
+        assert!(registry.connections.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+//! Detection of `COPY ... FROM STDIN` and `COPY ... TO STDOUT` statements, which are proxied
+//! directly to the upstream database using the [COPY sub-protocol][0].
+//!
+//! We don't parse `COPY` statements into an AST - all we need to know is whether a statement is a
+//! `COPY` to or from the client, which table or query it copies, and which format the copied data
+//! is in, so that we can send the client the right response before handing the statement to the
+//! upstream database.
+//!
+//! [0]: https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-COPY
+
+use std::ops::Range;
+
+use psql_srv::TransferFormat;
+
+/// The direction data flows in for a `COPY` statement proxied to the upstream database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyDirection {
+    /// `COPY ... FROM STDIN`: data is sent from the client to the database
+    In,
+    /// `COPY ... TO STDOUT`: data is sent from the database to the client
+    Out,
+}
+
+/// The table or query a `COPY` statement copies data to or from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CopyTarget {
+    /// `COPY <table> [(<columns>)]`
+    Table {
+        /// The name of the table, exactly as written in the statement (including any schema and
+        /// quotes)
+        name: String,
+        /// The number of columns in the statement's column list, if it has one
+        num_columns: Option<usize>,
+    },
+    /// `COPY (<query>)`, with the text of the query
+    Query(String),
+}
+
+/// A `COPY` statement which copies data to or from the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyStatement {
+    pub(crate) direction: CopyDirection,
+    pub(crate) target: CopyTarget,
+    /// The overall format of the copied data. Both the text and CSV formats are
+    /// [`TransferFormat::Text`]
+    pub(crate) format: TransferFormat,
+}
+
+/// A single word in a query, along with the depth of parentheses it is nested within
+struct Word {
+    word: String,
+    depth: usize,
+    /// The byte offsets of the word within the query, including any quotes around it
+    span: Range<usize>,
+}
+
+/// Split `query` into lowercased words, treating each quoted string or identifier as a single
+/// word.
+///
+/// Also returns the byte offsets of the contents of each top-level parenthesized group in `query`.
+fn words(query: &str) -> (Vec<Word>, Vec<Range<usize>>) {
+    let mut words = vec![];
+    let mut groups = vec![];
+    let mut current = String::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut group_start = 0;
+    let mut quote = None;
+
+    let mut finish_word = |current: &mut String, start, end, depth| {
+        if !current.is_empty() {
+            words.push(Word {
+                word: current.to_lowercase(),
+                depth,
+                span: start..end,
+            });
+            current.clear();
+        }
+    };
+
+    for (i, c) in query.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                finish_word(&mut current, start, i + 1, depth);
+                quote = None;
+            } else {
+                current.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => {
+                finish_word(&mut current, start, i, depth);
+                quote = Some(c);
+                start = i;
+            }
+            '(' => {
+                finish_word(&mut current, start, i, depth);
+                if depth == 0 {
+                    group_start = i + 1;
+                }
+                depth += 1;
+            }
+            ')' => {
+                finish_word(&mut current, start, i, depth);
+                if depth == 1 {
+                    groups.push(group_start..i);
+                }
+                depth = depth.saturating_sub(1);
+            }
+            ',' | ';' => finish_word(&mut current, start, i, depth),
+            c if c.is_whitespace() => finish_word(&mut current, start, i, depth),
+            c => {
+                if current.is_empty() {
+                    start = i;
+                }
+                current.push(c)
+            }
+        }
+    }
+    finish_word(&mut current, start, query.len(), depth);
+
+    (words, groups)
+}
+
+/// If `query` is a `COPY` statement which copies data from (`FROM STDIN`) or to (`TO STDOUT`) the
+/// client, returns a description of that statement. Otherwise (including for `COPY` statements to
+/// or from a file or program on the database server), returns `None`.
+pub(crate) fn parse_copy(query: &str) -> Option<CopyStatement> {
+    let (words, groups) = words(query);
+    let copy = words.first().filter(|w| w.word == "copy")?;
+
+    let (direction_idx, direction) = words.windows(2).enumerate().find_map(|(i, w)| {
+        if w[0].depth != 0 || w[1].depth != 0 {
+            return None;
+        }
+        match (w[0].word.as_str(), w[1].word.as_str()) {
+            ("from", "stdin") => Some((i, CopyDirection::In)),
+            ("to", "stdout") => Some((i, CopyDirection::Out)),
+            _ => None,
+        }
+    })?;
+
+    // Everything between `COPY` and the direction is either a parenthesized query, or a table name
+    // optionally followed by a parenthesized column list
+    let direction_start = words[direction_idx].span.start;
+    let group = groups
+        .iter()
+        .find(|g| g.start > copy.span.end && g.end < direction_start);
+    let target = match group {
+        Some(group) if query[copy.span.end..group.start - 1].trim().is_empty() => {
+            CopyTarget::Query(query[group.clone()].trim().to_owned())
+        }
+        _ => CopyTarget::Table {
+            name: query[copy.span.end..group.map_or(direction_start, |g| g.start - 1)]
+                .trim()
+                .to_owned(),
+            num_columns: group.map(|g| {
+                words
+                    .iter()
+                    .filter(|w| w.depth == 1 && g.contains(&w.span.start))
+                    .count()
+            }),
+        },
+    };
+
+    // The format can be specified either with the `BINARY` keyword (in the pre-9.0 syntax) or with
+    // a `FORMAT binary` option, both of which come after the direction
+    let options = &words[direction_idx + 2..];
+    let binary = options.iter().any(|w| w.depth == 0 && w.word == "binary")
+        || options
+            .windows(2)
+            .any(|w| w[0].depth > 0 && w[0].word == "format" && w[1].word == "binary");
+
+    Some(CopyStatement {
+        direction,
+        target,
+        format: if binary {
+            TransferFormat::Binary
+        } else {
+            TransferFormat::Text
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn check(query: &str, expected: Option<(CopyDirection, TransferFormat)>) {
+        assert_eq!(
+            parse_copy(query).map(|copy| (copy.direction, copy.format)),
+            expected,
+            "{query}"
+        );
+    }
+
+    #[test]
+    fn copy_from_stdin() {
+        check(
+            "COPY t FROM STDIN",
+            Some((CopyDirection::In, TransferFormat::Text)),
+        );
+        check(
+            "copy t (a, b) from stdin with (format csv, header true);",
+            Some((CopyDirection::In, TransferFormat::Text)),
+        );
+        check(
+            "COPY t FROM STDIN WITH (FORMAT binary)",
+            Some((CopyDirection::In, TransferFormat::Binary)),
+        );
+        check(
+            "COPY t FROM STDIN BINARY",
+            Some((CopyDirection::In, TransferFormat::Binary)),
+        );
+    }
+
+    #[test]
+    fn copy_to_stdout() {
+        check(
+            "COPY public.t (a, b) TO stdout;",
+            Some((CopyDirection::Out, TransferFormat::Text)),
+        );
+        check(
+            "COPY (SELECT binary, format FROM \"from stdin\") TO STDOUT",
+            Some((CopyDirection::Out, TransferFormat::Text)),
+        );
+        check(
+            "COPY (SELECT * FROM t) TO STDOUT (FORMAT 'binary')",
+            Some((CopyDirection::Out, TransferFormat::Binary)),
+        );
+        check(
+            "COPY (SELECT * FROM t) TO STDOUT (FORMAT BINARY)",
+            Some((CopyDirection::Out, TransferFormat::Binary)),
+        );
+    }
+
+    #[track_caller]
+    fn check_target(query: &str, expected: CopyTarget) {
+        assert_eq!(parse_copy(query).unwrap().target, expected, "{query}");
+    }
+
+    #[test]
+    fn copy_targets() {
+        check_target(
+            "COPY t FROM STDIN",
+            CopyTarget::Table {
+                name: "t".into(),
+                num_columns: None,
+            },
+        );
+        check_target(
+            "copy public.t(a, \"b, c\") from stdin with (format csv);",
+            CopyTarget::Table {
+                name: "public.t".into(),
+                num_columns: Some(2),
+            },
+        );
+        check_target(
+            "COPY \"My Schema\".\"T\" (a) TO STDOUT",
+            CopyTarget::Table {
+                name: "\"My Schema\".\"T\"".into(),
+                num_columns: Some(1),
+            },
+        );
+        check_target(
+            "COPY ( SELECT f(x), y FROM t ) TO STDOUT (FORMAT binary)",
+            CopyTarget::Query("SELECT f(x), y FROM t".into()),
+        );
+    }
+
+    #[test]
+    fn not_copy_to_client() {
+        check("SELECT * FROM stdin", None);
+        check("COPY t FROM '/tmp/t.csv'", None);
+        check("COPY t TO PROGRAM 'gzip > /tmp/t.gz'", None);
+        check("COPY (SELECT 'to stdout') TO '/tmp/t'", None);
+    }
+}
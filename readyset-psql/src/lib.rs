@@ -1,5 +1,7 @@
 #![feature(box_patterns, type_alias_impl_trait)]
 mod backend;
+mod cancel;
+mod copy;
 mod error;
 mod query_handler;
 mod response;
@@ -10,7 +12,8 @@ mod upstream;
 mod value;
 
 pub use crate::backend::{AuthenticationMethod, Backend, ParamRef};
+pub use crate::cancel::{Cancellation, CancellationRegistry};
 pub use crate::error::Error;
 pub use crate::query_handler::PostgreSqlQueryHandler;
-pub use crate::upstream::PostgreSqlUpstream;
+pub use crate::upstream::{PostgreSqlUpstream, UpstreamCancelHandle};
 pub use crate::value::Value;
@@ -180,5 +180,13 @@ pub fn type_to_pgsql(col_type: &DfType) -> Result<pgsql::types::Type, Error> {
         DfType::Array(box DfType::Bit(_)) => Ok(Type::BIT_ARRAY),
         DfType::Array(box DfType::VarBit(_)) => Ok(Type::VARBIT_ARRAY),
         DfType::Array(box DfType::Array(_)) => unsupported_type!(),
+        DfType::Array(box DfType::Range(_)) => unsupported_type!(),
+        DfType::Range(box DfType::Int) => Ok(Type::INT4_RANGE),
+        DfType::Range(box DfType::BigInt) => Ok(Type::INT8_RANGE),
+        DfType::Range(box DfType::Numeric { .. }) => Ok(Type::NUM_RANGE),
+        DfType::Range(box DfType::Timestamp { .. }) => Ok(Type::TS_RANGE),
+        DfType::Range(box DfType::TimestampTz { .. }) => Ok(Type::TSTZ_RANGE),
+        DfType::Range(box DfType::Date) => Ok(Type::DATE_RANGE),
+        DfType::Range(_) => unsupported_type!(),
     }
 }
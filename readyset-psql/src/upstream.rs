@@ -4,26 +4,30 @@ use std::fmt::Debug;
 use std::os::unix::ffi::OsStrExt;
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::StreamExt;
 use nom_sql::SqlIdentifier;
 use pgsql::config::Host;
 use pgsql::types::Type;
 use pgsql::{GenericResult, ResultStream, Row, SimpleQueryMessage};
+use postgres_native_tls::MakeTlsConnector;
 use postgres_types::Kind;
 use psql_srv::Column;
 use readyset_adapter::fallback_cache::FallbackCache;
 use readyset_adapter::upstream_database::UpstreamDestination;
 use readyset_adapter::{UpstreamConfig, UpstreamDatabase, UpstreamPrepare};
 use readyset_data::DfValue;
-use readyset_errors::{internal_err, invariant_eq, unsupported, ReadySetError, ReadySetResult};
+use readyset_errors::{internal, internal_err, invariant_eq, ReadySetError, ReadySetResult};
 use tokio::process::Command;
 use tokio_postgres as pgsql;
 use tracing::{debug, info, info_span};
 use tracing_futures::Instrument;
 
+use crate::copy::CopyTarget;
 use crate::Error;
 
 /// Indicates the minimum upstream server version that we currently support. Used to error out
@@ -34,6 +38,9 @@ const MIN_UPSTREAM_VERSION: u16 = 13;
 pub struct PostgreSqlUpstream {
     /// This is the underlying (regular) PostgreSQL client
     client: pgsql::Client,
+    /// Handle used to cancel the queries running on this connection, which is kept when the
+    /// connection is reset
+    cancel_handle: UpstreamCancelHandle,
     /// A tokio task that handles the connection, required by `tokio_postgres` to operate
     _connection_handle: tokio::task::JoinHandle<Result<(), pgsql::Error>>,
     /// Map from prepared statement IDs to prepared statements
@@ -49,6 +56,116 @@ pub struct PostgreSqlUpstream {
     version: String,
 }
 
+/// A handle which can be used to cancel the query currently running on a [`PostgreSqlUpstream`]
+/// connection, from outside of that connection.
+///
+/// The handle stays valid when the connection is reset.
+///
+/// Cloning an `UpstreamCancelHandle` returns a handle to the same connection.
+#[derive(Clone)]
+pub struct UpstreamCancelHandle(Arc<UpstreamCancelHandleInner>);
+
+struct UpstreamCancelHandleInner {
+    /// The token for the current connection, which is replaced when the connection is reset
+    token: Mutex<pgsql::CancelToken>,
+    /// The TLS connector used to connect to the upstream, kept around to open a new connection
+    /// for cancelling queries
+    tls: MakeTlsConnector,
+    /// Set while a query is running on the connection
+    running: AtomicBool,
+}
+
+impl UpstreamCancelHandle {
+    fn new(token: pgsql::CancelToken, tls: MakeTlsConnector) -> Self {
+        Self(Arc::new(UpstreamCancelHandleInner {
+            token: Mutex::new(token),
+            tls,
+            running: AtomicBool::new(false),
+        }))
+    }
+
+    /// Ask the upstream database to cancel the query currently running on the connection this
+    /// handle was created for, if any.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let token = self.0.token.lock().unwrap().clone();
+        Ok(token.cancel_query(self.0.tls.clone()).await?)
+    }
+
+    /// Returns true if a query is currently running on the upstream connection, in which case
+    /// cancelling it makes it return an error
+    pub fn is_running(&self) -> bool {
+        self.0.running.load(Ordering::Acquire)
+    }
+
+    /// Mark a query as running on the upstream connection until the returned guard is dropped
+    fn start_query(&self) -> RunningQuery {
+        self.0.running.store(true, Ordering::Release);
+        RunningQuery(Arc::clone(&self.0))
+    }
+}
+
+/// Marks a query as running on an upstream connection while it's alive
+struct RunningQuery(Arc<UpstreamCancelHandleInner>);
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        self.0.running.store(false, Ordering::Release);
+    }
+}
+
+impl PostgreSqlUpstream {
+    /// Returns a handle which can be used to cancel the query currently running on this
+    /// connection
+    pub fn cancel_handle(&self) -> UpstreamCancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Run a `COPY ... FROM STDIN` statement against the upstream database, returning a sink to
+    /// write the copied data to.
+    ///
+    /// The copy must be completed by calling [`CopyInSink::finish`]; dropping the sink before then
+    /// aborts the copy.
+    ///
+    /// [`CopyInSink::finish`]: pgsql::CopyInSink::finish
+    pub async fn copy_in(&self, query: &str) -> Result<pgsql::CopyInSink<Bytes>, Error> {
+        Ok(self.client.copy_in(query).await?)
+    }
+
+    /// Run a `COPY ... TO STDOUT` statement against the upstream database, returning a stream of
+    /// the copied data.
+    pub async fn copy_out(&self, query: &str) -> Result<pgsql::CopyOutStream, Error> {
+        Ok(self.client.copy_out(query).await?)
+    }
+
+    /// Returns the number of columns copied to or from the client by a `COPY` statement with the
+    /// given target, which tokio-postgres doesn't expose from the upstream's own copy response.
+    pub(crate) async fn copy_num_columns(&self, target: &CopyTarget) -> Result<usize, Error> {
+        match target {
+            CopyTarget::Table {
+                num_columns: Some(n),
+                ..
+            } => Ok(*n),
+            // Without a column list, every column of the table except generated columns is copied
+            CopyTarget::Table {
+                name,
+                num_columns: None,
+            } => {
+                let row = self
+                    .client
+                    .query_one(
+                        "SELECT count(*) FROM pg_catalog.pg_attribute \
+                         WHERE attrelid = $1::text::regclass AND attnum > 0 \
+                         AND NOT attisdropped AND attgenerated = ''",
+                        &[name],
+                    )
+                    .await?;
+                Ok(row.get::<_, i64>(0) as usize)
+            }
+            CopyTarget::Query(query) => Ok(self.client.prepare(query).await?.columns().len()),
+        }
+    }
+}
+
 pub enum QueryResult {
     EmptyRead,
     Stream {
@@ -199,14 +316,17 @@ impl UpstreamDatabase for PostgreSqlUpstream {
             }
             builder.build().unwrap() // Never returns an error
         };
-        let tls = postgres_native_tls::MakeTlsConnector::new(connector);
+        let tls = MakeTlsConnector::new(connector);
         let span = info_span!(
             "Connecting to PostgreSQL upstream",
             host = ?pg_config.get_hosts(),
             port = ?pg_config.get_ports()
         );
         span.in_scope(|| info!("Establishing connection"));
-        let (client, connection) = pg_config.connect(tls).instrument(span.clone()).await?;
+        let (client, connection) = pg_config
+            .connect(tls.clone())
+            .instrument(span.clone())
+            .await?;
         let version = connection.parameter("server_version").ok_or_else(|| {
             ReadySetError::Internal("Upstream database failed to send server version".to_string())
         })?;
@@ -228,6 +348,7 @@ impl UpstreamDatabase for PostgreSqlUpstream {
         span.in_scope(|| info!("Established connection to upstream"));
 
         Ok(Self {
+            cancel_handle: UpstreamCancelHandle::new(client.cancel_token(), tls),
             client,
             _connection_handle,
             prepared_statements: Default::default(),
@@ -247,10 +368,12 @@ impl UpstreamDatabase for PostgreSqlUpstream {
     }
 
     async fn reset(&mut self) -> Result<(), Error> {
-        let old_self = std::mem::replace(
-            self,
-            Self::connect(self.upstream_config.clone(), None).await?,
-        );
+        let mut new_self = Self::connect(self.upstream_config.clone(), None).await?;
+        // Keep the same cancel handle, so that anything holding on to it can cancel queries on
+        // the new connection
+        *self.cancel_handle.0.token.lock().unwrap() = new_self.client.cancel_token();
+        new_self.cancel_handle = self.cancel_handle.clone();
+        let old_self = std::mem::replace(self, new_self);
         drop(old_self);
         Ok(())
     }
@@ -265,6 +388,7 @@ impl UpstreamDatabase for PostgreSqlUpstream {
         S: AsRef<str> + Send + Sync + 'a,
     {
         let query = query.as_ref();
+        let _running = self.cancel_handle.start_query();
         let statement = self.client.prepare(query).await?;
 
         let meta = StatementMeta {
@@ -289,18 +413,54 @@ impl UpstreamDatabase for PostgreSqlUpstream {
     }
 
     async fn query<'a>(&'a mut self, query: &'a str) -> Result<Self::QueryResult<'a>, Error> {
+        let _running = self.cancel_handle.start_query();
         let res = self.client.simple_query(query).await?;
         Ok(QueryResult::SimpleQuery(res))
     }
 
+    /// Executes the given write query on the upstream database, returning the WAL insert location
+    /// after the write as the RYW ticket.
+    ///
+    /// The location is read in the same simple query as the write, so that both run in the same
+    /// transaction: either the one the client has explicitly opened, or the implicit transaction
+    /// PostgreSQL runs a multi-statement simple query in. Either way, the location is read before
+    /// the transaction commits, so it is always less than or equal to the LSN of the transaction's
+    /// commit record, which is the transaction ID the replicator propagates for the write.
+    ///
+    /// If the transaction hasn't been assigned a transaction ID, it hasn't written anything, and
+    /// will not produce a commit record for the replicator to see, so no ticket is returned.
     async fn handle_ryw_write<'a, S>(
         &'a mut self,
-        _query: S,
-    ) -> Result<(Self::QueryResult<'a>, String), Error>
+        query: S,
+    ) -> Result<(Self::QueryResult<'a>, Option<String>), Error>
     where
         S: AsRef<str> + Send + Sync + 'a,
     {
-        unsupported!("Read-Your-Write not yet implemented for PostgreSQL")
+        let _running = self.cancel_handle.start_query();
+        // The newline ends any trailing line comment in the query
+        let mut res = self
+            .client
+            .simple_query(&format!(
+                "{}\n;SELECT pg_current_wal_insert_lsn()::text, pg_current_xact_id_if_assigned()",
+                query.as_ref()
+            ))
+            .await?;
+        let (Some(SimpleQueryMessage::CommandComplete(_)), Some(SimpleQueryMessage::Row(row))) =
+            (res.pop(), res.pop())
+        else {
+            internal!("Expected a single row with the WAL insert location");
+        };
+        let lsn = if row.get(1).is_some() {
+            Some(
+                row.get(0)
+                    .ok_or_else(|| internal_err!("WAL insert location was NULL"))?
+                    .to_owned(),
+            )
+        } else {
+            None
+        };
+
+        Ok((QueryResult::SimpleQuery(res), lsn))
     }
 
     async fn execute<'a>(
@@ -313,6 +473,7 @@ impl UpstreamDatabase for PostgreSqlUpstream {
             .get(&statement_id)
             .ok_or(ReadySetError::PreparedStatementMissing { statement_id })?;
 
+        let _running = self.cancel_handle.start_query();
         let mut stream = Box::pin(
             self.client
                 .generic_query_raw(
@@ -336,18 +497,21 @@ impl UpstreamDatabase for PostgreSqlUpstream {
 
     /// Handle starting a transaction with the upstream database.
     async fn start_tx<'a>(&'a mut self) -> Result<Self::QueryResult<'a>, Error> {
+        let _running = self.cancel_handle.start_query();
         self.client.query("START TRANSACTION", &[]).await?;
         Ok(QueryResult::Command)
     }
 
     /// Handle committing a transaction to the upstream database.
     async fn commit<'a>(&'a mut self) -> Result<Self::QueryResult<'a>, Error> {
+        let _running = self.cancel_handle.start_query();
         self.client.query("COMMIT", &[]).await?;
         Ok(QueryResult::Command)
     }
 
     /// Handle rolling back the ongoing transaction for this connection to the upstream db.
     async fn rollback<'a>(&'a mut self) -> Result<Self::QueryResult<'a>, Error> {
+        let _running = self.cancel_handle.start_query();
         self.client.query("ROLLBACK", &[]).await?;
         Ok(QueryResult::Command)
     }
@@ -136,6 +136,15 @@ impl TryFrom<Value> for ps::Value {
                     )))
                 }
             }
+            (t, DfValue::Range(ref range)) => {
+                if let Kind::Range(_) = t.kind() {
+                    Ok(ps::Value::Range((**range).clone(), t))
+                } else {
+                    Err(ps::Error::InternalError(format!(
+                        "Mismatched type for value: expected range type, but got {t}"
+                    )))
+                }
+            }
             (_, DfValue::PassThrough(ref p)) => Ok(ps::Value::PassThrough((**p).clone())),
             (t, val) => {
                 if let Kind::Enum(vs) = t.kind() {
@@ -1,6 +1,8 @@
 use std::panic::AssertUnwindSafe;
 
+use bytes::Bytes;
 use chrono::NaiveDate;
+use futures::{SinkExt, TryStreamExt};
 use readyset_adapter::backend::{MigrationMode, UnsupportedSetMode};
 use readyset_adapter::BackendBuilder;
 use readyset_client_test_helpers::psql_helpers::{upstream_config, PostgreSQLAdapter};
@@ -173,6 +175,45 @@ async fn prepare_execute_fallback() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn copy_from_stdin_and_to_stdout() {
+    let (config, _handle, shutdown_tx) = setup().await;
+    let client = connect(config).await;
+
+    client
+        .simple_query("CREATE TABLE copy_cats (id int PRIMARY KEY, name text)")
+        .await
+        .unwrap();
+
+    let sink = client.copy_in("COPY copy_cats FROM STDIN").await.unwrap();
+    futures::pin_mut!(sink);
+    sink.send(Bytes::from_static(b"1\tgarfield\n2\tfelix\n"))
+        .await
+        .unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 2);
+
+    let sink = client
+        .copy_in("COPY copy_cats (name, id) FROM STDIN")
+        .await
+        .unwrap();
+    futures::pin_mut!(sink);
+    sink.send(Bytes::from_static(b"tom\t3\n")).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 1);
+
+    let data = client
+        .copy_out("COPY (SELECT * FROM copy_cats ORDER BY id) TO STDOUT")
+        .await
+        .unwrap()
+        .try_collect::<Vec<Bytes>>()
+        .await
+        .unwrap()
+        .concat();
+    assert_eq!(data, b"1\tgarfield\n2\tfelix\n3\ttom\n");
+
+    shutdown_tx.shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial]
 async fn proxy_unsupported_sets() {
@@ -750,6 +791,76 @@ async fn alter_enum_rename_value() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn read_your_writes() {
+    readyset_tracing::init_test_logging();
+
+    let (config, _handle, shutdown_tx) = TestBuilder::new(
+        BackendBuilder::new()
+            .require_authentication(false)
+            .enable_ryw(true),
+    )
+    .fallback(true)
+    .build::<PostgreSQLAdapter>()
+    .await;
+    let client = connect(config).await;
+
+    client
+        .simple_query("CREATE TABLE cats (id int PRIMARY KEY, name text)")
+        .await
+        .unwrap();
+    eventually! {
+        client
+            .simple_query("CREATE CACHE FROM SELECT name FROM cats WHERE id = $1")
+            .await
+            .is_ok()
+    }
+
+    let names = |rows: Vec<tokio_postgres::Row>| {
+        rows.iter()
+            .map(|r| r.get::<_, String>(0))
+            .collect::<Vec<_>>()
+    };
+
+    // Each read has to see the write right before it, without waiting for it to be replicated
+    client
+        .simple_query("INSERT INTO cats VALUES (1, 'Bob')")
+        .await
+        .unwrap();
+    let res = client
+        .query("SELECT name FROM cats WHERE id = $1", &[&1i32])
+        .await
+        .unwrap();
+    assert_eq!(names(res), ["Bob"]);
+    assert!(last_statement_matches("readyset", "ok", &client).await);
+
+    client
+        .simple_query("UPDATE cats SET name = 'Robert' WHERE id = 1")
+        .await
+        .unwrap();
+    let res = client
+        .query("SELECT name FROM cats WHERE id = $1", &[&1i32])
+        .await
+        .unwrap();
+    assert_eq!(names(res), ["Robert"]);
+    assert!(last_statement_matches("readyset", "ok", &client).await);
+
+    // A write that doesn't change anything isn't replicated, so reads can't wait for it
+    client
+        .simple_query("UPDATE cats SET name = 'Alice' WHERE id = 2")
+        .await
+        .unwrap();
+    let res = client
+        .query("SELECT name FROM cats WHERE id = $1", &[&1i32])
+        .await
+        .unwrap();
+    assert_eq!(names(res), ["Robert"]);
+    assert!(last_statement_matches("readyset", "ok", &client).await);
+
+    shutdown_tx.shutdown().await;
+}
+
 #[allow(dead_code)]
 async fn last_statement_matches(dest: &str, status: &str, client: &Client) -> bool {
     match &client
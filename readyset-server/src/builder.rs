@@ -61,6 +61,7 @@ impl Builder {
             builder.set_memory_limit(opts.memory, Duration::from_secs(opts.memory_check_freq));
         }
         builder.set_eviction_kind(opts.eviction_kind);
+        builder.set_warm_restart_keys(opts.warm_restart_keys);
 
         builder.set_sharding(match opts.shards {
             0 | 1 => None,
@@ -84,9 +85,11 @@ impl Builder {
         builder.set_allow_topk(opts.enable_experimental_topk_support);
         builder.set_allow_paginate(opts.enable_experimental_paginate_support);
         builder.set_allow_mixed_comparisons(opts.enable_experimental_mixed_comparisons);
+        builder.set_max_parametrized_limit(opts.max_parametrized_limit);
         builder.set_worker_timeout(std::time::Duration::from_secs(
             opts.worker_request_timeout_seconds,
         ));
+        builder.set_rebalance_interval(opts.rebalance_interval_seconds.map(Duration::from_secs));
 
         builder.set_replication_strategy(opts.domain_replication_options.into());
 
@@ -197,11 +200,21 @@ impl Builder {
         self.config.mir_config.allow_mixed_comparisons = allow_mixed_comparisons;
     }
 
+    /// Set the value of [`controller::sql::Config::max_parametrized_limit`]
+    pub fn set_max_parametrized_limit(&mut self, max_parametrized_limit: Option<usize>) {
+        self.config.mir_config.max_parametrized_limit = max_parametrized_limit;
+    }
+
     /// Set the value of [`controller::sql::Config::worker_request_timeout`]
     pub fn set_worker_timeout(&mut self, worker_request_timeout: Duration) {
         self.config.worker_request_timeout = worker_request_timeout;
     }
 
+    /// Set the value of [`Config::rebalance_interval`]
+    pub fn set_rebalance_interval(&mut self, rebalance_interval: Option<Duration>) {
+        self.config.rebalance_interval = rebalance_interval;
+    }
+
     /// Set the value of [`DomainConfig::aggressively_update_state_sizes`][0]. See the documentation
     /// of that field for more information
     ///
@@ -215,6 +228,12 @@ impl Builder {
         self.config.replicator_config.upstream_db_url = Some(url.into());
     }
 
+    /// Sets the (potentially glob-patterned) tables to replicate, in the same format as the
+    /// `--replication-tables` option
+    pub fn set_replication_tables(&mut self, tables: String) {
+        self.config.replicator_config.replication_tables = Some(tables.into());
+    }
+
     /// Sets configuration for the replicator thread
     pub fn set_replicator_config(&mut self, config: UpstreamConfig) {
         self.config.replicator_config = config;
@@ -299,6 +318,18 @@ impl Builder {
         self.config.domain_config.eviction_kind = value;
     }
 
+    /// Sets the value of [`Config::domain_config::warm_restart_keys`]. See documentation of
+    /// that field for more information.
+    pub fn set_warm_restart_keys(&mut self, value: usize) {
+        self.config.domain_config.warm_restart_keys = value;
+    }
+
+    /// Sets the value of [`Config::domain_config::hot_keys_save_interval`]. See documentation of
+    /// that field for more information.
+    pub fn set_hot_keys_save_interval(&mut self, value: Duration) {
+        self.config.domain_config.hot_keys_save_interval = value;
+    }
+
     /// Assigns a telemetry reporter to this ReadySet server
     pub fn set_telemetry_sender(&mut self, value: TelemetrySender) {
         self.telemetry = value;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use array2::Array2;
 use dataflow::prelude::*;
@@ -64,6 +64,35 @@ impl DomainHandle {
         self.shards.cells().iter().any(|s| s == worker)
     }
 
+    /// Returns the replicas of each shard of this domain which aren't assigned to any of the
+    /// `failed` workers, as pairs of replica index and worker, in replica order.
+    ///
+    /// Returns `None` if that would leave any shard without any replicas, or leave shards with
+    /// different numbers of replicas.
+    pub(super) fn healthy_replicas(
+        &self,
+        failed: &HashSet<WorkerIdentifier>,
+    ) -> Option<Vec<Vec<(usize, WorkerIdentifier)>>> {
+        let shards = self
+            .shards()
+            .map(|replicas| {
+                replicas
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .filter(|(_, wi)| !failed.contains(wi))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let num_replicas = shards.first()?.len();
+        if num_replicas == 0 || shards.iter().any(|replicas| replicas.len() != num_replicas) {
+            return None;
+        }
+
+        Some(shards)
+    }
+
     pub(super) async fn send_to_healthy_shard_replica<R>(
         &self,
         shard: usize,
@@ -136,3 +165,66 @@ impl DomainHandle {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    fn worker(n: usize) -> WorkerIdentifier {
+        Url::parse(&format!("http://worker{n}:6033")).unwrap()
+    }
+
+    #[test]
+    fn healthy_replicas_renumbers_around_failed_workers() {
+        let handle = DomainHandle::new(
+            DomainIndex::from(0),
+            Array2::from_rows(vec![
+                vec![worker(0), worker(1), worker(2)],
+                vec![worker(1), worker(2), worker(0)],
+            ]),
+        );
+
+        assert_eq!(
+            handle.healthy_replicas(&HashSet::from([worker(1)])),
+            Some(vec![
+                vec![(0, worker(0)), (2, worker(2))],
+                vec![(1, worker(2)), (2, worker(0))],
+            ])
+        );
+    }
+
+    #[test]
+    fn healthy_replicas_requires_replica_of_every_shard() {
+        let handle = DomainHandle::new(
+            DomainIndex::from(0),
+            Array2::from_rows(vec![vec![worker(0), worker(1)], vec![worker(1), worker(2)]]),
+        );
+
+        assert_eq!(
+            handle.healthy_replicas(&HashSet::from([worker(1), worker(2)])),
+            None
+        );
+        assert_eq!(
+            handle.healthy_replicas(&HashSet::from([worker(1)])),
+            Some(vec![vec![(0, worker(0))], vec![(1, worker(2))]])
+        );
+    }
+
+    #[test]
+    fn healthy_replicas_requires_same_number_of_replicas_in_each_shard() {
+        let handle = DomainHandle::new(
+            DomainIndex::from(0),
+            Array2::from_rows(vec![
+                vec![worker(0), worker(1), worker(2)],
+                vec![worker(2), worker(3), worker(4)],
+            ]),
+        );
+
+        assert_eq!(handle.healthy_replicas(&HashSet::from([worker(1)])), None);
+        assert!(handle
+            .healthy_replicas(&HashSet::from([worker(2)]))
+            .is_some());
+    }
+}
@@ -8,33 +8,46 @@
 )]
 
 use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::mem;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use database_utils::UpstreamConfig;
 use failpoint_macros::failpoint;
 use hyper::Method;
-use readyset_client::consensus::Authority;
+use nom_sql::Relation;
+use readyset_client::consensus::{Authority, AuthorityControl};
 use readyset_client::internal::ReplicaAddress;
 use readyset_client::recipe::ExtendRecipeSpec;
-use readyset_client::replication::ReplicationOffset;
+use readyset_client::replication::{ReplicationOffset, ReplicationTablesChange};
 use readyset_client::status::{ReadySetStatus, SnapshotStatus};
 use readyset_client::WorkerDescriptor;
-use readyset_errors::{ReadySetError, ReadySetResult};
+use readyset_errors::{unsupported, ReadySetError, ReadySetResult};
 use readyset_telemetry_reporter::TelemetrySender;
 use readyset_util::futures::abort_on_panic;
 use readyset_util::shutdown::ShutdownReceiver;
 use readyset_version::RELEASE_VERSION;
 use reqwest::Url;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::Notify;
+use tokio::sync::{watch, Notify};
 use tracing::{debug, error, info, warn};
 
 use crate::controller::state::{DfState, DfStateHandle};
 use crate::controller::{ControllerRequest, ControllerState, Worker, WorkerIdentifier};
-use crate::coordination::DomainDescriptor;
+use crate::coordination::{DomainDescriptor, RunningDomain};
 use crate::worker::WorkerRequestKind;
 
+/// The maximum amount of time to wait for caches to be filled with the keys they had before a
+/// restart before making the recovered dataflow available
+const READER_PREFILL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The path in the authority at which the changes made at runtime to the set of tables being
+/// replicated are stored
+const REPLICATION_TABLES_CHANGES_PATH: &str = "/replication_tables_changes";
+
 /// The ReadySet leader, responsible for making control-plane decisions for the whole of a ReadySet
 /// cluster.
 ///
@@ -48,6 +61,13 @@ pub struct Leader {
     pub(super) dataflow_state_handle: DfStateHandle,
 
     pending_recovery: bool,
+    /// The domains running on each of the workers which registered while recovery was pending,
+    /// which we may be able to take over rather than recreating them
+    running_domains: HashMap<WorkerIdentifier, Vec<RunningDomain>>,
+    /// Whether partial readers recovered after a restart have finished replaying the keys they had
+    /// before the restart (or we gave up waiting for them to). Until then, the leader doesn't
+    /// report being ready, so that adapters don't route traffic to cold caches.
+    caches_warm: Arc<AtomicBool>,
 
     quorum: usize,
     controller_uri: Url,
@@ -58,6 +78,12 @@ pub struct Leader {
     replicator_statement_logging: bool,
     /// Configuration for the replicator
     pub(super) replicator_config: UpstreamConfig,
+    /// All changes made at runtime to the set of tables being replicated, in the order they were
+    /// made. The replicator re-applies these on top of `replicator_config` whenever it restarts.
+    ///
+    /// These are persisted in the authority, and loaded again when a controller becomes the
+    /// leader.
+    replication_tables_changes: watch::Sender<Vec<ReplicationTablesChange>>,
     /// A client to the current authority.
     pub(super) authority: Arc<Authority>,
 }
@@ -73,6 +99,23 @@ impl Leader {
         telemetry_sender: TelemetrySender,
         shutdown_rx: ShutdownReceiver,
     ) {
+        // Pick up the changes made to the replicated tables under any previous leaders before the
+        // replicator starts, so that it replicates the same tables they did
+        match self
+            .authority
+            .try_read::<Vec<ReplicationTablesChange>>(REPLICATION_TABLES_CHANGES_PATH)
+            .await
+        {
+            Ok(changes) => {
+                self.replication_tables_changes
+                    .send_replace(changes.unwrap_or_default());
+            }
+            Err(error) => error!(
+                %error,
+                "Could not load changes to the replicated tables from the authority"
+            ),
+        }
+
         // When the controller becomes the leader, we need to read updates
         // from the binlog.
         self.start_replication_task(
@@ -107,6 +150,7 @@ impl Leader {
         let replicator_restart_timeout = self.replicator_config.replicator_restart_timeout;
         let config = self.replicator_config.clone();
         let replicator_statement_logging = self.replicator_statement_logging;
+        let replication_tables_changes = self.replication_tables_changes.subscribe();
 
         // The replication task ideally won't panic, but if it does and we arent replicating, that
         // will mean the data we return, will be more and more stale, and the transaction logs on
@@ -128,6 +172,7 @@ impl Leader {
                         telemetry_sender.clone(),
                         server_startup,
                         replicator_statement_logging,
+                        Some(replication_tables_changes.clone()),
                     )
                     .await
                     {
@@ -182,6 +227,7 @@ impl Leader {
 
         debug!(%method, %path, "received external HTTP request");
 
+        let leader_ready = leader_ready && self.caches_warm();
         let require_leader_ready = || -> ReadySetResult<()> {
             if !leader_ready {
                 Err(ReadySetError::LeaderNotReady)
@@ -277,6 +323,26 @@ impl Leader {
                 (&Method::GET | &Method::POST, "/version") => {
                     return_serialized!(RELEASE_VERSION);
                 }
+                (&Method::POST, "/change_replication_tables") => {
+                    if self.replicator_config.upstream_db_url.is_none() {
+                        unsupported!(
+                            "Cannot change replicated tables without an upstream database"
+                        );
+                    }
+                    let change: ReplicationTablesChange = bincode::deserialize(&body)?;
+                    info!(?change, "Changing replicated tables");
+                    let changes = futures::executor::block_on(authority.read_modify_write(
+                        REPLICATION_TABLES_CHANGES_PATH,
+                        |changes: Option<Vec<ReplicationTablesChange>>| {
+                            let mut changes = changes.unwrap_or_default();
+                            changes.push(change.clone());
+                            Ok::<_, Infallible>(changes)
+                        },
+                    ))?
+                    .unwrap_or_else(|e| match e {});
+                    self.replication_tables_changes.send_replace(changes);
+                    return_serialized!(());
+                }
                 _ => {}
             }
 
@@ -529,6 +595,38 @@ impl Leader {
                 })?;
                 return_serialized!(ret);
             }
+            (&Method::POST, "/rebalance") => {
+                require_leader_ready()?;
+                let ret = futures::executor::block_on(async move {
+                    let mut writer = self.dataflow_state_handle.write().await;
+                    check_quorum!(writer.as_ref());
+                    let moves = writer.as_mut().plan_rebalance().await?;
+                    self.dataflow_state_handle.commit(writer, authority).await?;
+
+                    // Commit each move on its own, so that a failed move doesn't lose track of
+                    // the moves made before it
+                    let mut res = Vec::with_capacity(moves.len());
+                    for domain_move in moves {
+                        let mut writer = self.dataflow_state_handle.write().await;
+                        check_quorum!(writer.as_ref());
+                        let stop_old_instance = writer
+                            .as_mut()
+                            .move_domain(
+                                domain_move.domain_index,
+                                &domain_move.from,
+                                &domain_move.to,
+                            )
+                            .await?;
+                        self.dataflow_state_handle.commit(writer, authority).await?;
+                        // The old instance keeps serving reads until the readers of the new
+                        // instance are warm, which we don't need the dataflow state for
+                        tokio::spawn(stop_old_instance);
+                        res.push(domain_move);
+                    }
+                    Ok(res)
+                })?;
+                return_serialized!(ret);
+            }
             (&Method::POST, "/set_schema_replication_offset") => {
                 let body: Option<ReplicationOffset> = bincode::deserialize(&body)?;
                 let ret = futures::executor::block_on(async move {
@@ -551,6 +649,15 @@ impl Leader {
                 })?;
                 return_serialized!(ret);
             }
+            (&Method::POST, "/lose_standby_replica") => {
+                let (table, addrs): (Relation, Vec<SocketAddr>) = bincode::deserialize(&body)?;
+                let ret = futures::executor::block_on(async move {
+                    let mut writer = self.dataflow_state_handle.write().await;
+                    writer.as_mut().lose_standby_replica(&table, &addrs).await?;
+                    self.dataflow_state_handle.commit(writer, authority).await
+                })?;
+                return_serialized!(ret);
+            }
             _ => Err(ReadySetError::UnknownEndpoint),
         }
     }
@@ -603,8 +710,26 @@ impl Leader {
 
             // We currently require that any worker that enters the system has no domains.
             // If a worker has domains we are unaware of, we may try to perform a duplicate
-            // operation during a migration.
-            if let Err(e) = ws.rpc::<()>(WorkerRequestKind::ClearDomains).await {
+            // operation during a migration. The exception is when we're recovering, in which case
+            // we may be able to take over the domains started by the previous controller once we
+            // have enough workers (see `DfState::adopt_running_domains`).
+            if self.pending_recovery {
+                match ws
+                    .rpc::<Vec<RunningDomain>>(WorkerRequestKind::ListDomains)
+                    .await
+                {
+                    Ok(domains) => {
+                        self.running_domains.insert(worker_uri.clone(), domains);
+                    }
+                    Err(e) => {
+                        error!(
+                            %worker_uri,
+                            %e,
+                            "Worker could not be reached to list its domains.",
+                        );
+                    }
+                }
+            } else if let Err(e) = ws.rpc::<()>(WorkerRequestKind::ClearDomains).await {
                 error!(
                     %worker_uri,
                     %e,
@@ -636,12 +761,20 @@ impl Leader {
         if ds.workers.len() >= self.quorum && self.pending_recovery {
             self.pending_recovery = false;
             let domain_nodes = ds
-                .domain_nodes
-                .iter()
-                .map(|(k, v)| (*k, v.values().copied().collect::<HashSet<_>>()))
-                .collect::<HashMap<_, _>>();
+                .adopt_running_domains(mem::take(&mut self.running_domains))
+                .await?;
             ds.recover(&domain_nodes).await?;
             info!("Finished restoring graph configuration");
+            // Don't let adapters route traffic to caches until they're warm again, but wait for
+            // that in the background so that the dataflow state isn't locked in the meantime
+            let domains = ds.domains.keys().copied().collect::<Vec<_>>();
+            let prefills = ds.wait_for_reader_prefills(&domains, READER_PREFILL_TIMEOUT);
+            let caches_warm = self.caches_warm.clone();
+            caches_warm.store(false, Ordering::Release);
+            tokio::spawn(async move {
+                prefills.await;
+                caches_warm.store(true, Ordering::Release);
+            });
         }
 
         self.dataflow_state_handle
@@ -649,6 +782,12 @@ impl Leader {
             .await
     }
 
+    /// Returns false while partial readers recovered after a restart are still replaying the keys
+    /// they had before the restart
+    pub(super) fn caches_warm(&self) -> bool {
+        self.caches_warm.load(Ordering::Acquire)
+    }
+
     pub(super) async fn handle_failed_workers(
         &mut self,
         failed: Vec<WorkerIdentifier>,
@@ -656,10 +795,21 @@ impl Leader {
         let mut writer = self.dataflow_state_handle.write().await;
         let ds = writer.as_mut();
 
-        // first, translate from the affected workers to affected data-flow nodes
+        for wi in &failed {
+            warn!(worker = %wi, "handling failure of worker");
+            ds.workers.remove(wi);
+            self.running_domains.remove(wi);
+        }
+
+        // first, replace any replicas of base table domains on the failed workers with their hot
+        // standbys, where possible
+        let promoted = ds
+            .promote_standbys(&failed.iter().cloned().collect())
+            .await?;
+
+        // then, translate from the affected workers to the remaining affected data-flow nodes
         let mut affected_nodes = HashMap::new();
         for wi in failed {
-            warn!(worker = %wi, "handling failure of worker");
             let mut domain_nodes_on_worker = ds.nodes_on_worker(Some(&wi));
             for (domain_index, node_indices) in domain_nodes_on_worker.drain() {
                 ds.domains.remove(&domain_index);
@@ -669,9 +819,14 @@ impl Leader {
                     .or_insert_with(|| HashSet::new())
                     .extend(node_indices);
             }
-            ds.workers.remove(&wi);
         }
 
+        // the failed replicas of the domains we promoted standbys for may not have sent all of
+        // their writes downstream before they failed, so recreate everything downstream of them to
+        // replay it all again from the promoted replicas
+        let downstream = ds.downstream_domains(promoted);
+        affected_nodes.extend(ds.kill_domains(&downstream).await);
+
         ds.recover(&affected_nodes).await?;
 
         self.dataflow_state_handle
@@ -702,6 +857,8 @@ impl Leader {
         Leader {
             dataflow_state_handle,
             pending_recovery,
+            running_domains: Default::default(),
+            caches_warm: Arc::new(AtomicBool::new(true)),
 
             quorum: state.config.quorum,
 
@@ -709,6 +866,7 @@ impl Leader {
 
             replicator_statement_logging,
             replicator_config,
+            replication_tables_changes: watch::channel(vec![]).0,
             authority,
             worker_request_timeout,
         }
@@ -724,9 +882,11 @@ pub(super) fn request_type(req: &ControllerRequest) -> ControllerRequestType {
         | (&Method::POST, "/extend_recipe")
         | (&Method::POST, "/remove_query")
         | (&Method::POST, "/remove_all_queries")
+        | (&Method::POST, "/rebalance")
         | (&Method::POST, "/set_replication_offset")
         | (&Method::POST, "/replicate_readers")
-        | (&Method::POST, "/remove_node") => ControllerRequestType::Write,
+        | (&Method::POST, "/remove_node")
+        | (&Method::POST, "/lose_standby_replica") => ControllerRequestType::Write,
         (&Method::POST, "/dry_run") => ControllerRequestType::DryRun,
         _ => ControllerRequestType::Read,
     }
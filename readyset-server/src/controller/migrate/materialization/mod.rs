@@ -10,6 +10,7 @@ use std::convert::TryFrom;
 
 use dataflow::prelude::*;
 use dataflow::{DomainRequest, LookupIndex};
+use nom_sql::CachePriority;
 use petgraph::graph::NodeIndex;
 use readyset_errors::{internal, internal_err, invariant, ReadySetError, ReadySetResult};
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,7 @@ use vec1::Vec1;
 use crate::controller::keys;
 use crate::controller::migrate::DomainMigrationPlan;
 use crate::controller::state::graphviz;
+use crate::coordination::PreparedState;
 
 mod plan;
 
@@ -358,6 +360,13 @@ impl Materializations {
         // it and the nearest full materialization (because the intermediate ones haven't been
         // marked as materialized yet).
         for (ni, mut indices) in lookup_obligations {
+            // some operators need to read their parents' state directly, in which case we can't
+            // push their lookup obligations through query-through operators
+            #[allow(clippy::indexing_slicing)] // children come from graph
+            let pinned = graph
+                .neighbors_directed(ni, petgraph::EdgeDirection::Outgoing)
+                .any(|child| graph[child].requires_materialized_parents());
+
             // we want to find the closest materialization that allows lookups (i.e., counting
             // query-through operators).
             let mut mi = ni;
@@ -367,7 +376,7 @@ impl Materializations {
                 if self.have.contains_key(&mi) {
                     break;
                 }
-                if !m.is_internal() || !m.can_query_through() {
+                if pinned || !m.is_internal() || !m.can_query_through() {
                     break;
                 }
 
@@ -605,6 +614,13 @@ impl Materializations {
                 }
             } else if !graph[ni].is_base() && !self.config.allow_full_materialization {
                 unsupported!("Creation of fully materialized query is forbidden");
+            } else if graph[ni].as_reader().map_or(false, |r| {
+                r.cache_options().ttl.is_some() || r.cache_options().refresh.is_some()
+            }) {
+                // Fully materialized readers can't miss, so keys can't be evicted from them
+                unsupported!(
+                    "TTL and refresh are only supported for partially materialized caches"
+                );
             } else {
                 invariant!(
                     !graph[ni].purge,
@@ -1118,12 +1134,76 @@ impl Materializations {
             }
         }
 
+        self.set_eviction_priorities(graph, new, dmp)?;
+
         self.added.clear();
         self.pending_recovery = false;
 
         Ok(())
     }
 
+    /// Tells the domains of partially materialized non-reader nodes the priority to evict the
+    /// state of those nodes with, which is the highest priority of any of the caches downstream of
+    /// them, so that the state feeding a high priority cache isn't evicted before the readers of
+    /// lower priority caches.
+    ///
+    /// Priorities are only sent for the `new` nodes and the nodes upstream of `new` readers, since
+    /// those are the only ones whose priority can have changed.
+    fn set_eviction_priorities(
+        &self,
+        graph: &Graph,
+        new: &HashSet<NodeIndex>,
+        dmp: &mut DomainMigrationPlan,
+    ) -> ReadySetResult<()> {
+        let mut nodes = new.clone();
+        let mut stack = new
+            .iter()
+            .copied()
+            .filter(|&ni| graph[ni].is_reader())
+            .collect::<Vec<_>>();
+        while let Some(ni) = stack.pop() {
+            for parent in graph.neighbors_directed(ni, petgraph::EdgeDirection::Incoming) {
+                if nodes.insert(parent) {
+                    stack.push(parent);
+                }
+            }
+        }
+
+        for ni in nodes {
+            let n = &graph[ni];
+            if n.is_reader() || n.is_dropped() || !self.partial.contains(&ni) {
+                continue;
+            }
+
+            let mut priority: Option<CachePriority> = None;
+            let mut visited = HashSet::new();
+            let mut stack = vec![ni];
+            while let Some(ni) = stack.pop() {
+                for child in graph.neighbors_directed(ni, petgraph::EdgeDirection::Outgoing) {
+                    if !visited.insert(child) || graph[child].is_dropped() {
+                        continue;
+                    }
+                    if let Some(r) = graph[child].as_reader() {
+                        priority =
+                            priority.max(Some(r.cache_options().priority.unwrap_or_default()));
+                    } else {
+                        stack.push(child);
+                    }
+                }
+            }
+
+            dmp.add_message(
+                n.domain(),
+                DomainRequest::SetEvictionPriority {
+                    node: n.local_addr(),
+                    priority: priority.unwrap_or_default(),
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Perform all operations necessary to bring any materializations for the given node up, and
     /// then mark that node as ready to receive updates.
     fn ready_one(
@@ -1254,4 +1334,61 @@ impl Materializations {
             self.redundant_partial.remove(ni);
         }
     }
+
+    /// Forgets the replay paths to the given nodes, so that they're planned again the next time the
+    /// nodes are materialized, and returns the tags of the forgotten paths.
+    ///
+    /// This is used when the nodes are moved to a new instance of their domain, which can't use
+    /// the replay paths set up for the old instance.
+    pub(in crate::controller) fn remove_paths(
+        &mut self,
+        nodes: &HashSet<NodeIndex>,
+    ) -> HashSet<Tag> {
+        nodes
+            .iter()
+            .filter_map(|ni| self.paths.remove(ni))
+            .flat_map(|paths| paths.into_keys())
+            .collect()
+    }
+
+    /// Returns a summary of the state that would be prepared for the given node by the next
+    /// `commit()`, given the materializations computed by [`Materializations::extend`] since the
+    /// last time `commit()` was invoked, or `None` if no state would be prepared for it.
+    ///
+    /// This is used when a newly elected controller takes over domains that are already running,
+    /// to check that the state they prepared is what we'd prepare for them ourselves.
+    pub(in crate::controller) fn planned_state(
+        &self,
+        graph: &Graph,
+        ni: NodeIndex,
+    ) -> Option<PreparedState> {
+        #[allow(clippy::indexing_slicing)] // the node must be in the graph
+        let n = &graph[ni];
+        let strict_indices = if let Some(r) = n.as_reader() {
+            HashSet::from([r.index()?.clone()])
+        } else {
+            self.added.get(&ni).filter(|idxs| !idxs.is_empty())?.clone()
+        };
+
+        Some(PreparedState {
+            partial: self.partial.contains(&ni),
+            strict_indices,
+            weak_indices: if n.is_reader() {
+                Default::default()
+            } else {
+                self.added_weak.get(&ni).cloned().unwrap_or_default()
+            },
+        })
+    }
+
+    /// Treats all the materializations computed by [`Materializations::extend`] since the last
+    /// time `commit()` was invoked as already existing, without planning anything for them.
+    ///
+    /// This is used when a newly elected controller takes over domains that are already running,
+    /// in order to rebuild the materialization state for the nodes in those domains.
+    pub(in crate::controller) fn assume_committed(&mut self) {
+        self.added.clear();
+        self.added_weak.clear();
+        self.new_readers.clear();
+    }
 }
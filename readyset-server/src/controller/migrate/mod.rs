@@ -37,9 +37,9 @@ use std::time::{Duration, Instant};
 use array2::Array2;
 use dataflow::node::Column;
 use dataflow::prelude::*;
-use dataflow::{node, DomainRequest, ReaderProcessing};
+use dataflow::{node, DomainRequest, MovedDomainState, ReaderProcessing};
 use metrics::{counter, histogram};
-use nom_sql::Relation;
+use nom_sql::{CacheOptions, Relation};
 use readyset_client::metrics::recorded;
 use readyset_client::{KeyColumnIdx, ViewPlaceholder};
 use readyset_data::{DfType, Dialect};
@@ -50,6 +50,7 @@ use tracing::{debug, debug_span, error, info, info_span, instrument, trace};
 use crate::controller::migrate::materialization::InvalidEdge;
 use crate::controller::migrate::node_changes::{MigrationNodeChanges, NodeChanges};
 use crate::controller::migrate::scheduling::Scheduler;
+use crate::controller::replication::active_replicas;
 use crate::controller::state::DfState;
 use crate::controller::WorkerIdentifier;
 
@@ -187,6 +188,8 @@ pub struct PlaceRequest {
     shard_replica_workers: Array2<WorkerIdentifier>,
     /// Indices of new nodes to add.
     nodes: Vec<NodeIndex>,
+    /// State handed over from the previous instance of the domain, if it's being moved.
+    moved_from: Option<MovedDomainState>,
 }
 
 /// Runtime configuration for a domain
@@ -194,7 +197,8 @@ pub struct PlaceRequest {
 pub struct DomainSettings {
     /// The number of times the domain is sharded
     pub num_shards: usize,
-    /// The number of times each shard of the domain is replicated
+    /// The number of times each shard of the domain is replicated, not including hot standby
+    /// replicas (which don't take part in the dataflow)
     pub num_replicas: usize,
 }
 
@@ -268,7 +272,15 @@ impl DomainMigrationPlan {
                         *idx,
                         DomainSettings {
                             num_shards: hdl.num_shards(),
-                            num_replicas: hdl.num_replicas(),
+                            num_replicas: active_replicas(
+                                &mainline.ingredients,
+                                mainline
+                                    .domain_nodes
+                                    .get(idx)
+                                    .into_iter()
+                                    .flat_map(|nodes| nodes.values().copied()),
+                                hdl.num_replicas(),
+                            ),
                         },
                     )
                 })
@@ -290,11 +302,13 @@ impl DomainMigrationPlan {
         idx: DomainIndex,
         shard_replica_workers: Array2<WorkerIdentifier>,
         nodes: Vec<NodeIndex>,
+        moved_from: Option<MovedDomainState>,
     ) {
         self.place.push(PlaceRequest {
             idx,
             shard_replica_workers,
             nodes,
+            moved_from,
         });
     }
 
@@ -326,7 +340,12 @@ impl DomainMigrationPlan {
     pub async fn apply(&mut self, mainline: &mut DfState) -> ReadySetResult<()> {
         for place in self.place.drain(..) {
             let d = mainline
-                .place_domain(place.idx, place.shard_replica_workers, place.nodes)
+                .place_domain(
+                    place.idx,
+                    place.shard_replica_workers,
+                    place.nodes,
+                    place.moved_from,
+                )
                 .await?;
             mainline.domains.insert(place.idx, d);
         }
@@ -786,6 +805,50 @@ impl<'df> Migration<'df> {
         r.set_mapping(placeholder_map);
     }
 
+    /// Set the [`CacheOptions`] for the reader of `n`.
+    ///
+    /// Returns an error if the reader already existed before this migration (because a cache with
+    /// the same definition was already created) with different options, since the options of a
+    /// reader can't be changed once it's been created.
+    pub fn set_cache_options(
+        &mut self,
+        n: NodeIndex,
+        cache_options: CacheOptions,
+    ) -> ReadySetResult<()> {
+        let Some(ri) = self.readers.get(&n) else {
+            let existing_options = self
+                .dataflow_state
+                .ingredients
+                .neighbors_directed(n, petgraph::EdgeDirection::Outgoing)
+                .find_map(|child| {
+                    #[allow(clippy::indexing_slicing)] // neighbors are in the graph
+                    self.dataflow_state.ingredients[child]
+                        .as_reader()
+                        .map(|r| *r.cache_options())
+                })
+                .unwrap_or_default();
+            if existing_options != cache_options {
+                unsupported!(
+                    "Can't create a cache which reuses an existing cache created with different \
+                     options"
+                );
+            }
+            return Ok(());
+        };
+
+        #[allow(clippy::indexing_slicing)] // we made the reader
+        let r = self.dataflow_state.ingredients[*ri]
+            .as_mut_reader()
+            .ok_or_else(|| internal_err!("Node {} is not a reader", ri.index()))?;
+        if !r.cache_options().is_empty() && *r.cache_options() != cache_options {
+            unsupported!(
+                "Can't create a cache which reuses another cache created with different options"
+            );
+        }
+        r.set_cache_options(cache_options);
+        Ok(())
+    }
+
     /// Build a `MigrationPlan` for this migration, and apply it if the planning stage succeeds.
     pub(super) async fn commit(self, dry_run: bool) -> ReadySetResult<()> {
         let start = self.start;
@@ -1122,8 +1185,12 @@ fn plan_add_nodes(
             let worker_shards = scheduler.schedule_domain(domain, &nodes)?;
 
             let num_shards = worker_shards.num_rows();
-            let num_replicas = worker_shards.row_size();
-            dmp.place_domain(domain, worker_shards, nodes);
+            let num_replicas = active_replicas(
+                &dataflow_state.ingredients,
+                nodes.iter().copied(),
+                worker_shards.row_size(),
+            );
+            dmp.place_domain(domain, worker_shards, nodes, None);
             dmp.domains.insert(
                 domain,
                 DomainSettings {
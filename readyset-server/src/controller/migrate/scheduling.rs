@@ -12,8 +12,9 @@
 //! 3. Otherwise, for each replica of each shard in the domain, we first filter the set of workers
 //!    down to only workers that aren't running a different replica of the same domain shard, then
 //!    either:
-//!    a. Run the domain shard on the worker matching its [placement restrictions][], if it has any,
-//!       or
+//!    a. Run the domain shard on the worker matching its [placement restrictions][], if it has any
+//!       and this is the first replica of the shard (other replicas of domains with base tables are
+//!       hot standbys, which don't need to run next to the persisted base table data), or
 //!    b. If the domain contains base tables, run it on the worker running the smallest number of
 //!       other base tables, or otherwise
 //!    c. Run it on the worker that has the smallest number of domain shards scheduled onto it
@@ -30,6 +31,7 @@ use readyset_client::consensus::NodeTypeSchedulingRestriction;
 use readyset_client::internal::DomainIndex;
 use tracing::{instrument, trace};
 
+use crate::controller::replication::{active_replicas, ReplicationStrategy};
 use crate::controller::state::DfState;
 use crate::controller::{DomainPlacementRestriction, NodeRestrictionKey, Worker, WorkerIdentifier};
 
@@ -53,9 +55,7 @@ struct WorkerStats {
     /// The number of replicas of shards of domains with base tables that are running in this
     /// worker.
     ///
-    /// Currently there will only be 1 replica of each base table shard (because we don't have the
-    /// ability to replicate base tables yet) but this is intended to still work once we lift that
-    /// limitation.
+    /// This includes hot standby replicas of base table domain shards.
     num_base_table_domain_shard_replicas: usize,
 }
 
@@ -143,8 +143,25 @@ impl<'state> Scheduler<'state> {
             .any(|n| self.dataflow_state.ingredients[*n].is_base());
         trace!(is_reader_domain, is_base_table_domain);
 
-        if is_base_table_domain {
-            invariant_eq!(num_replicas, 1);
+        // Base table domains can only be replicated as hot standbys, since only one replica of
+        // each base table can take part in the dataflow (and be written to by clients other than
+        // the replicator)
+        if is_base_table_domain && num_replicas > 1 {
+            invariant!(
+                matches!(
+                    self.dataflow_state.replication_strategy,
+                    ReplicationStrategy::AllDomains(_)
+                ),
+                "Base table domains can only be replicated as hot standbys"
+            );
+            invariant_eq!(
+                active_replicas(
+                    &self.dataflow_state.ingredients,
+                    nodes.iter().copied(),
+                    num_replicas
+                ),
+                1
+            );
         }
 
         let workers = self.valid_workers.iter().filter(|(_, worker)| {
@@ -158,32 +175,39 @@ impl<'state> Scheduler<'state> {
         let mut res = Vec::with_capacity(num_shards);
         for shard in 0..num_shards {
             let mut replicas = Vec::with_capacity(num_replicas);
-            // Filter out any workers that have a different replica of the same domain shard, to
-            // avoid scheduling two replicas of the same shard onto the same worker
-            let available_workers = workers
-                .clone()
-                .filter(|(wi, _)| {
-                    self.scheduled_shards
-                        .get(wi)
-                        .map_or(true, |shards| !shards.contains(&(domain_index, shard)))
-                })
-                .collect::<Vec<_>>();
             for replica in 0..num_replicas {
-                // Shards of certain dataflow nodes may have restrictions that
-                // limit the workers they are placed upon.
-                let dataflow_node_restrictions = nodes
-                    .iter()
-                    .filter_map(|n| {
-                        let node_name = self.dataflow_state.ingredients[*n].name();
-                        self.dataflow_state
-                            .node_restrictions
-                            .get(&NodeRestrictionKey {
-                                node_name: node_name.clone(),
-                                shard,
-                            })
+                // Filter out any workers that have a different replica of the same domain shard, to
+                // avoid scheduling two replicas of the same shard onto the same worker
+                let available_workers = workers
+                    .clone()
+                    .filter(|(wi, _)| {
+                        self.scheduled_shards
+                            .get(wi)
+                            .map_or(true, |shards| !shards.contains(&(domain_index, shard)))
                     })
                     .collect::<Vec<_>>();
 
+                // Shards of certain dataflow nodes may have restrictions that
+                // limit the workers they are placed upon. Those restrictions only apply to the
+                // first replica, since any other replicas of domains with base tables are hot
+                // standbys
+                let dataflow_node_restrictions = if replica == 0 {
+                    nodes
+                        .iter()
+                        .filter_map(|n| {
+                            let node_name = self.dataflow_state.ingredients[*n].name();
+                            self.dataflow_state
+                                .node_restrictions
+                                .get(&NodeRestrictionKey {
+                                    node_name: node_name.clone(),
+                                    shard,
+                                })
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    vec![]
+                };
+
                 let worker_id = if dataflow_node_restrictions.is_empty() {
                     // If there are no placement restrictions, pick the node based on load-balancing
                     // heuristics
@@ -14,6 +14,7 @@ use dataflow::node::Column as DfColumn;
 use dataflow::ops::grouped::concat::GroupConcat;
 use dataflow::ops::join::{Join, JoinType};
 use dataflow::ops::project::Project;
+use dataflow::ops::window::WindowFunction;
 use dataflow::{node, ops, Expr as DfExpr, PostLookupAggregates, ReaderProcessing};
 use itertools::Itertools;
 use mir::graph::MirGraph;
@@ -161,7 +162,7 @@ pub(super) fn mir_node_to_flow_parts(
                 MirNodeInner::Join {
                     ref on,
                     ref project,
-                    ..
+                    ref residual,
                 } => {
                     invariant_eq!(ancestors.len(), 2);
                     let left = ancestors[0];
@@ -169,11 +170,13 @@ pub(super) fn mir_node_to_flow_parts(
                     Some(make_join_node(
                         graph,
                         name,
+                        mir_node,
                         left,
                         right,
                         &graph.referenced_columns(mir_node),
                         on,
                         project,
+                        residual.as_ref(),
                         JoinType::Inner,
                         custom_types,
                         mig,
@@ -192,7 +195,7 @@ pub(super) fn mir_node_to_flow_parts(
                         mig,
                     )?)
                 }
-                MirNodeInner::DependentJoin { .. } => {
+                MirNodeInner::DependentJoin { .. } | MirNodeInner::DependentLeftJoin { .. } => {
                     // See the docstring for MirNodeInner::DependentJoin
                     internal!("Encountered dependent join when lowering to dataflow")
                 }
@@ -243,7 +246,7 @@ pub(super) fn mir_node_to_flow_parts(
                 MirNodeInner::LeftJoin {
                     ref on,
                     ref project,
-                    ..
+                    ref residual,
                 } => {
                     invariant_eq!(ancestors.len(), 2);
                     let left = ancestors[0];
@@ -251,16 +254,41 @@ pub(super) fn mir_node_to_flow_parts(
                     Some(make_join_node(
                         graph,
                         name,
+                        mir_node,
                         left,
                         right,
                         &graph.columns(mir_node),
                         on,
                         project,
+                        residual.as_ref(),
                         JoinType::Left,
                         custom_types,
                         mig,
                     )?)
                 }
+                MirNodeInner::FullJoin {
+                    ref on,
+                    ref project,
+                    ref residual,
+                } => {
+                    invariant_eq!(ancestors.len(), 2);
+                    let left = ancestors[0];
+                    let right = ancestors[1];
+                    Some(make_join_node(
+                        graph,
+                        name,
+                        mir_node,
+                        left,
+                        right,
+                        &graph.columns(mir_node),
+                        on,
+                        project,
+                        residual.as_ref(),
+                        JoinType::Full,
+                        custom_types,
+                        mig,
+                    )?)
+                }
                 MirNodeInner::Project {
                     ref emit,
                     ref literals,
@@ -335,6 +363,27 @@ pub(super) fn mir_node_to_flow_parts(
                         mig,
                     )?)
                 }
+                MirNodeInner::Window {
+                    ref function,
+                    ref over,
+                    ref partition_by,
+                    ref order_by,
+                    ..
+                } => {
+                    invariant_eq!(ancestors.len(), 1);
+                    let parent = ancestors[0];
+                    Some(make_window_node(
+                        graph,
+                        name,
+                        parent,
+                        &graph.columns(mir_node),
+                        function.clone(),
+                        over.as_ref(),
+                        partition_by,
+                        order_by,
+                        mig,
+                    )?)
+                }
                 MirNodeInner::AliasTable { .. } => None,
             };
 
@@ -635,16 +684,17 @@ fn make_identity_node(
 
 /// Lower a join MIR node to dataflow
 ///
-/// See [`MirNodeInner::Join`] for documentation on what `on_left`, `on_right`, and `project` mean
-/// here
+/// See [`MirNodeInner::Join`] for documentation on what `on`, `project`, and `residual` mean here
 fn make_join_node(
     graph: &MirGraph,
     name: Relation,
+    node: MirNodeIndex,
     left: MirNodeIndex,
     right: MirNodeIndex,
     columns: &[Column],
     on: &[(Column, Column)],
     proj_cols: &[Column],
+    residual: Option<&Expr>,
     kind: JoinType,
     custom_types: &HashMap<Relation, DfType>,
     mig: &mut Migration<'_>,
@@ -710,6 +760,21 @@ fn make_join_node(
 
     set_names(&column_names(columns), &mut cols)?;
 
+    // The residual is evaluated on the rows emitted by the join, so resolve its columns in the
+    // join node itself
+    let residual = residual
+        .map(|residual| {
+            lower_expression(
+                graph,
+                node,
+                residual.clone(),
+                &cols,
+                custom_types,
+                mig.dialect,
+            )
+        })
+        .transpose()?;
+
     // If we don't have any join condition, we're making a cross join.
     // Dataflow needs a non-empty join condition, so project out a constant value on both sides to
     // use as our join key
@@ -749,7 +814,10 @@ fn make_join_node(
         ));
     }
 
-    let j = Join::new(left_na.address(), right_na.address(), kind, emit);
+    let mut j = Join::new(left_na.address(), right_na.address(), kind, emit);
+    if let Some(residual) = residual {
+        j = j.with_residual(residual);
+    }
     let n = mig.add_ingredient(name, cols, j);
 
     Ok(DfNodeIndex::new(n))
@@ -1153,6 +1221,78 @@ fn make_paginate_or_topk_node(
     Ok(DfNodeIndex::new(na))
 }
 
+fn make_window_node(
+    graph: &MirGraph,
+    name: Relation,
+    parent: MirNodeIndex,
+    columns: &[Column],
+    function: WindowFunction,
+    over: Option<&Column>,
+    partition_by: &[Column],
+    order_by: &[(Column, OrderType)],
+    mig: &mut Migration<'_>,
+) -> ReadySetResult<DfNodeIndex> {
+    invariant!(
+        !partition_by.is_empty(),
+        "need a partition column for Window without PARTITION BY"
+    );
+    let parent_na = graph.resolve_dataflow_node(parent).ok_or_else(|| {
+        ReadySetError::MirNodeMustHaveDfNodeAssigned {
+            mir_node_index: parent.index(),
+        }
+    })?;
+    let mut parent_cols = mig.dataflow_state.ingredients[parent_na.address()]
+        .columns()
+        .to_vec();
+
+    let over_indx = over
+        .map(|c| graph.column_id_for_column(parent, c))
+        .transpose()?;
+    let over_col_ty = over_indx
+        .map(|i| {
+            parent_cols
+                .get(i)
+                .map(|c| c.ty().clone())
+                .ok_or_else(|| internal_err!("Invalid index"))
+        })
+        .transpose()?;
+    let partition_by_indx = partition_by
+        .iter()
+        .map(|c| graph.column_id_for_column(parent, c))
+        .collect::<ReadySetResult<Vec<_>>>()?;
+    // Unlike Paginate and TopK, Window takes its ordering in SQL terms
+    let order_by_indx = order_by
+        .iter()
+        .map(|(c, order_type)| {
+            graph
+                .column_id_for_column(parent, c)
+                .map(|id| (id, *order_type))
+        })
+        .collect::<ReadySetResult<Vec<_>>>()?;
+
+    let window = ops::window::Window::new(
+        parent_na.address(),
+        function.clone(),
+        over_indx,
+        partition_by_indx,
+        order_by_indx,
+        over_col_ty.as_ref(),
+    )?;
+
+    // set names using MIR columns to ensure aliases are used
+    let column_names = column_names(columns);
+    #[allow(clippy::unwrap_used)] // column_names must be populated
+    parent_cols.push(DfColumn::new(
+        column_names.last().unwrap().into(),
+        function.output_type(over_col_ty.as_ref()),
+        Some(name.clone()),
+    ));
+    set_names(&column_names, &mut parent_cols)?;
+
+    let na = mig.add_ingredient(name, parent_cols, window);
+    Ok(DfNodeIndex::new(na))
+}
+
 fn make_reader_processing(
     graph: &MirGraph,
     parent: &MirNodeIndex,
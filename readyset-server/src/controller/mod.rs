@@ -45,6 +45,7 @@ mod inner;
 mod keys;
 pub(crate) mod migrate; // crate viz for tests
 mod mir_to_flow;
+mod rebalance;
 pub(crate) mod replication;
 pub(crate) mod schema;
 pub(crate) mod sql;
@@ -433,7 +434,7 @@ impl Controller {
                     && match guard.as_ref() {
                         Some(leader) => {
                             let ds = leader.dataflow_state_handle.read().await;
-                            !ds.workers.is_empty()
+                            leader.caches_warm() && !ds.workers.is_empty()
                         }
                         None => false,
                     };
@@ -587,6 +588,7 @@ impl Controller {
         );
 
         let leader_ready = self.leader_ready.clone();
+        let mut rebalance_interval = self.config.rebalance_interval.map(tokio::time::interval);
         loop {
             select! {
                 req = self.handle_rx.recv() => {
@@ -696,6 +698,38 @@ impl Controller {
                 _ = self.leader_ready_notification.notified() => {
                     self.leader_ready.store(true, Ordering::Release);
                 }
+                Some(_) = async {
+                    match rebalance_interval.as_mut() {
+                        Some(interval) => Some(interval.tick().await),
+                        None => None,
+                    }
+                } => {
+                    if leader_ready.load(Ordering::Acquire) {
+                        // Rebalancing goes through the write processing task like any other write,
+                        // so that it doesn't race with migrations
+                        let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+                        let req = ControllerRequest {
+                            method: Method::POST,
+                            path: "/rebalance".to_owned(),
+                            query: None,
+                            body: hyper::body::Bytes::new(),
+                            reply_tx,
+                        };
+                        if writer_tx.send(req).await.is_err() {
+                            if self.shutdown_rx.signal_received() {
+                                info!("Controller shutting down after shutdown signal received");
+                                break;
+                            } else {
+                                internal!("write processing handle hung up but no shutdown signal was received!")
+                            }
+                        }
+                        tokio::spawn(async move {
+                            if !matches!(reply_rx.await, Ok(Ok(Ok(_))) | Err(_)) {
+                                warn!("Failed to rebalance domains between workers");
+                            }
+                        });
+                    }
+                }
                 _ = self.shutdown_rx.recv() => {
                     info!("Controller shutting down after shutdown signal received");
                     break;
@@ -0,0 +1,294 @@
+//! Moving domains between workers to even out the load on each worker.
+//!
+//! Domains are placed on workers once, when they're created (see
+//! [`scheduling`](crate::controller::migrate::scheduling)), but the load they put on those workers
+//! changes over time as their caches fill up and as the queries they serve get more or less
+//! popular. Rebalancing compares the memory used by the domains on each worker and the CPU they
+//! used since the previous rebalance, and moves domains from the most loaded worker to the least
+//! loaded one until the two are within [`MAX_IMBALANCE`] of each other.
+//!
+//! A domain is moved by starting a new instance of it on its new worker, replaying its state there
+//! and cutting over the routing of all the other domains to the new instance, then filling the
+//! partial readers of the new instance with the keys in the readers of the old instance in the
+//! background, before tearing down the old instance. Until then, the old instance keeps serving
+//! reads to the clients that already had handles to its readers. Each move is committed on its
+//! own, and a move that fails part way through is rolled back to the old instance. Currently only
+//! domains which don't contain base tables, don't send anything to other domains, and are neither
+//! sharded nor replicated are moved.
+
+use std::collections::{BTreeMap, HashSet};
+
+use dataflow::prelude::DomainIndex;
+use readyset_client::debug::stats::DomainStats;
+
+use crate::controller::WorkerIdentifier;
+
+/// The largest difference between the load on the most loaded and the least loaded worker, as a
+/// fraction of the load on the most loaded worker, that we leave alone.
+pub(crate) const MAX_IMBALANCE: f64 = 0.2;
+
+/// The load a domain puts on the worker running it
+#[derive(Debug, Clone)]
+pub(crate) struct DomainLoad {
+    pub(crate) domain_index: DomainIndex,
+    pub(crate) worker: WorkerIdentifier,
+    /// The total size of the state of all the nodes in the domain, in bytes
+    pub(crate) memory: u64,
+    /// The fraction of the time the domain recently spent processing, rather than waiting for
+    /// work
+    pub(crate) cpu: f64,
+}
+
+impl DomainLoad {
+    /// Computes the load of a domain from its current `stats`.
+    ///
+    /// Since domain statistics are totals since the domain started, the CPU used by the domain is
+    /// computed from the difference between `stats` and the `previous` statistics of the same
+    /// domain, if given, so that it reflects how busy the domain was since then rather than since
+    /// it started. If the domain was restarted since the previous statistics were taken, its
+    /// current statistics are recent anyway and are used as is.
+    pub(crate) fn new(
+        domain_index: DomainIndex,
+        worker: WorkerIdentifier,
+        stats: &DomainStats,
+        previous: Option<&DomainStats>,
+        memory: u64,
+    ) -> Self {
+        let (busy, waiting) = match previous {
+            Some(previous)
+                if previous.total_time <= stats.total_time
+                    && previous.wait_time <= stats.wait_time =>
+            {
+                (
+                    stats.total_time - previous.total_time,
+                    stats.wait_time - previous.wait_time,
+                )
+            }
+            _ => (stats.total_time, stats.wait_time),
+        };
+        let elapsed = busy + waiting;
+        let cpu = if elapsed == 0 {
+            0.0
+        } else {
+            busy as f64 / elapsed as f64
+        };
+
+        Self {
+            domain_index,
+            worker,
+            memory,
+            cpu,
+        }
+    }
+}
+
+/// Plans which domains to move to which workers to even out the load on the given `workers`,
+/// returning the index of each domain to move, along with the worker it should be moved from and
+/// the worker it should be moved to, in the order they should be moved.
+///
+/// The load of each domain is the sum of its share of the total memory and its share of the total
+/// CPU used by all the domains. Each domain is moved at most once, and only if `can_move` returns
+/// true for it and the worker it would be moved to.
+pub(crate) fn plan_moves<F>(
+    workers: &[WorkerIdentifier],
+    domains: &[DomainLoad],
+    can_move: F,
+) -> Vec<(DomainIndex, WorkerIdentifier, WorkerIdentifier)>
+where
+    F: Fn(&DomainLoad, &WorkerIdentifier) -> bool,
+{
+    let total_memory = domains.iter().map(|d| d.memory).sum::<u64>();
+    let total_cpu = domains.iter().map(|d| d.cpu).sum::<f64>();
+    let load_of = |d: &DomainLoad| {
+        let mut load = 0.0;
+        if total_memory > 0 {
+            load += d.memory as f64 / total_memory as f64;
+        }
+        if total_cpu > 0.0 {
+            load += d.cpu / total_cpu;
+        }
+        load
+    };
+
+    let mut worker_loads = workers.iter().map(|w| (w, 0.0)).collect::<BTreeMap<_, _>>();
+    for d in domains {
+        if let Some(load) = worker_loads.get_mut(&d.worker) {
+            *load += load_of(d);
+        }
+    }
+
+    let mut moved = HashSet::new();
+    let mut moves = vec![];
+    loop {
+        let Some((hot, hot_load)) = worker_loads
+            .iter()
+            .max_by(|(_, l1), (_, l2)| l1.total_cmp(l2))
+            .map(|(w, l)| (*w, *l))
+        else {
+            break;
+        };
+        let Some((cold, cold_load)) = worker_loads
+            .iter()
+            .min_by(|(_, l1), (_, l2)| l1.total_cmp(l2))
+            .map(|(w, l)| (*w, *l))
+        else {
+            break;
+        };
+
+        let gap = hot_load - cold_load;
+        if gap <= MAX_IMBALANCE * hot_load {
+            break;
+        }
+
+        // Moving a domain with a load of less than the gap always brings the two workers closer
+        // together, and the best one to move is the one that brings them closest
+        let Some((domain, load)) = domains
+            .iter()
+            .filter(|d| &d.worker == hot && !moved.contains(&d.domain_index))
+            .map(|d| (d, load_of(d)))
+            .filter(|(d, load)| *load > 0.0 && *load < gap && can_move(d, cold))
+            .min_by(|(_, l1), (_, l2)| {
+                (gap - 2.0 * l1).abs().total_cmp(&(gap - 2.0 * l2).abs())
+            })
+        else {
+            break;
+        };
+
+        moved.insert(domain.domain_index);
+        if let Some(l) = worker_loads.get_mut(hot) {
+            *l -= load;
+        }
+        if let Some(l) = worker_loads.get_mut(cold) {
+            *l += load;
+        }
+        moves.push((domain.domain_index, hot.clone(), cold.clone()));
+    }
+
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(n: usize) -> WorkerIdentifier {
+        format!("http://worker-{n}:6033").parse().unwrap()
+    }
+
+    fn domain(n: usize) -> DomainIndex {
+        n.into()
+    }
+
+    fn load(domain_index: usize, worker_n: usize, memory: u64, cpu: f64) -> DomainLoad {
+        DomainLoad {
+            domain_index: domain(domain_index),
+            worker: worker(worker_n),
+            memory,
+            cpu,
+        }
+    }
+
+    #[test]
+    fn balanced_workers_are_left_alone() {
+        let workers = vec![worker(0), worker(1)];
+        let domains = vec![load(0, 0, 100, 0.5), load(1, 1, 100, 0.5)];
+        assert!(plan_moves(&workers, &domains, |_, _| true).is_empty());
+    }
+
+    #[test]
+    fn moves_to_new_worker() {
+        let workers = vec![worker(0), worker(1)];
+        let domains = vec![
+            load(0, 0, 100, 0.0),
+            load(1, 0, 100, 0.0),
+            load(2, 0, 100, 0.0),
+            load(3, 0, 100, 0.0),
+        ];
+        let moves = plan_moves(&workers, &domains, |_, _| true);
+        assert_eq!(moves.len(), 2);
+        assert!(moves
+            .iter()
+            .all(|(_, from, to)| *from == worker(0) && *to == worker(1)));
+    }
+
+    #[test]
+    fn moves_domain_closest_to_half_the_gap() {
+        let workers = vec![worker(0), worker(1)];
+        let domains = vec![
+            load(0, 0, 10, 0.0),
+            load(1, 0, 45, 0.0),
+            load(2, 0, 45, 0.0),
+        ];
+        let moves = plan_moves(&workers, &domains, |_, _| true);
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].0 == domain(1) || moves[0].0 == domain(2));
+    }
+
+    #[test]
+    fn respects_can_move() {
+        let workers = vec![worker(0), worker(1)];
+        let domains = vec![load(0, 0, 100, 0.2), load(1, 0, 100, 0.2)];
+        let moves = plan_moves(&workers, &domains, |d, _| d.domain_index != domain(0));
+        assert_eq!(moves, vec![(domain(1), worker(0), worker(1))]);
+    }
+
+    fn stats(total_time: u64, wait_time: u64) -> DomainStats {
+        DomainStats {
+            total_time,
+            total_ptime: total_time,
+            total_replay_time: 0,
+            total_forward_time: total_time,
+            wait_time,
+        }
+    }
+
+    #[test]
+    fn cpu_since_previous_stats() {
+        // Busy for most of the domain's lifetime, but idle since the previous stats were taken
+        let load = DomainLoad::new(
+            domain(0),
+            worker(0),
+            &stats(900, 1100),
+            Some(&stats(900, 100)),
+            0,
+        );
+        assert_eq!(load.cpu, 0.0);
+
+        let load = DomainLoad::new(
+            domain(0),
+            worker(0),
+            &stats(1000, 1000),
+            Some(&stats(200, 800)),
+            0,
+        );
+        assert_eq!(load.cpu, 0.8);
+    }
+
+    #[test]
+    fn cpu_since_start_without_previous_stats() {
+        let load = DomainLoad::new(domain(0), worker(0), &stats(250, 750), None, 0);
+        assert_eq!(load.cpu, 0.25);
+
+        // The domain was restarted since the previous stats were taken
+        let load = DomainLoad::new(
+            domain(0),
+            worker(0),
+            &stats(250, 750),
+            Some(&stats(900, 100)),
+            0,
+        );
+        assert_eq!(load.cpu, 0.25);
+    }
+
+    #[test]
+    fn takes_cpu_into_account() {
+        let workers = vec![worker(0), worker(1)];
+        let domains = vec![
+            load(0, 0, 100, 0.9),
+            load(1, 0, 0, 0.9),
+            load(2, 1, 100, 0.0),
+        ];
+        let moves = plan_moves(&workers, &domains, |_, _| true);
+        assert_eq!(moves, vec![(domain(1), worker(0), worker(1))]);
+    }
+}
@@ -14,6 +14,16 @@ pub struct ReplicationOptions {
     /// Number of times to replicate domains that don't contain base nodes
     #[clap(long, hide = true, conflicts_with = "reader_replicas")]
     non_base_replicas: Option<usize>,
+
+    /// Number of times to replicate all domains. Replicas of domains that contain base nodes
+    /// beyond the first are run as hot standbys, which are promoted if the worker running the
+    /// first replica fails
+    #[clap(
+        long,
+        hide = true,
+        conflicts_with_all = ["reader_replicas", "non_base_replicas"]
+    )]
+    all_replicas: Option<usize>,
 }
 
 /// Description for how to decide how many times a domain should be replicated
@@ -31,6 +41,13 @@ pub enum ReplicationStrategy {
     ReaderDomains(usize),
     /// Replicate domains that don't contain base nodes this many times
     NonBaseDomains(usize),
+    /// Replicate all domains this many times.
+    ///
+    /// Only the first replica of each shard of a domain containing base nodes takes part in the
+    /// dataflow - the remaining replicas are hot standbys, which receive all the same writes but
+    /// never send anything downstream, and one of which is promoted to replace the first replica
+    /// if the worker running it fails.
+    AllDomains(usize),
 }
 
 impl Default for ReplicationStrategy {
@@ -45,6 +62,8 @@ impl From<ReplicationOptions> for ReplicationStrategy {
             Self::ReaderDomains(reader_replicas)
         } else if let Some(non_base_replicas) = opts.non_base_replicas {
             Self::NonBaseDomains(non_base_replicas)
+        } else if let Some(all_replicas) = opts.all_replicas {
+            Self::AllDomains(all_replicas)
         } else {
             Self::Never
         }
@@ -52,7 +71,8 @@ impl From<ReplicationOptions> for ReplicationStrategy {
 }
 
 impl ReplicationStrategy {
-    /// Determine the number of times a domain with the given nodes should be replicated
+    /// Determine the number of times a domain with the given nodes should be replicated, including
+    /// any hot standby replicas (see [`ReplicationStrategy::AllDomains`])
     ///
     /// # Invariants
     ///
@@ -85,6 +105,28 @@ impl ReplicationStrategy {
                     num_non_base_replicas
                 }
             }
+            ReplicationStrategy::AllDomains(num_replicas) => num_replicas,
         }
     }
 }
+
+/// Returns the number of replicas of a domain with the given nodes, replicated `num_replicas`
+/// times, that take part in the dataflow.
+///
+/// Replicas of domains containing base nodes other than the first are hot standbys, so those
+/// domains only ever have one active replica.
+///
+/// # Invariants
+///
+/// * Each of the nodes in `domain_nodes` must be present in `ingredients`
+#[allow(clippy::indexing_slicing)] // Invariant
+pub(crate) fn active_replicas<I>(ingredients: &Graph, domain_nodes: I, num_replicas: usize) -> usize
+where
+    I: IntoIterator<Item = NodeIndex>,
+{
+    if domain_nodes.into_iter().any(|n| ingredients[n].is_base()) {
+        1
+    } else {
+        num_replicas
+    }
+}
@@ -6,12 +6,12 @@ use mir::{Column, NodeIndex};
 use nom_sql::analysis::ReferredColumns;
 use nom_sql::FunctionExpr::*;
 use nom_sql::{self, Expr, FieldDefinitionExpr, Relation, SqlIdentifier};
-use readyset_errors::{unsupported, ReadySetError, ReadySetResult};
+use readyset_errors::{internal_err, unsupported, ReadySetError, ReadySetResult};
 use readyset_sql_passes::is_aggregate;
 
 use crate::controller::sql::mir::join::make_joins_for_aggregates;
 use crate::controller::sql::mir::SqlToMirConverter;
-use crate::controller::sql::query_graph::QueryGraph;
+use crate::controller::sql::query_graph::{variance_helper_aggregates, QueryGraph};
 
 // Move predicates above grouped_by nodes
 pub(super) fn make_predicates_above_grouped<'a>(
@@ -21,6 +21,7 @@ pub(super) fn make_predicates_above_grouped<'a>(
     qg: &QueryGraph,
     column_to_predicates: &HashMap<nom_sql::Column, Vec<&'a Expr>>,
     parent: &mut NodeIndex,
+    anon_queries: &HashMap<Relation, NodeIndex>,
 ) -> ReadySetResult<Vec<&'a Expr>> {
     let mut created_predicates = Vec::new();
 
@@ -38,6 +39,7 @@ pub(super) fn make_predicates_above_grouped<'a>(
                     over_col,
                     *parent,
                     &mut created_predicates,
+                    anon_queries,
                 )?;
 
                 *parent = subquery_leaf;
@@ -49,8 +51,8 @@ pub(super) fn make_predicates_above_grouped<'a>(
 }
 
 /// Normally, projection happens after grouped nodes - however, if aggregates used in grouped
-/// expressions reference expressions rather than columns directly, we need to project them out
-/// before the grouped nodes.
+/// expressions reference expressions rather than columns directly, or if the query groups by
+/// expressions rather than columns, we need to project them out before the grouped nodes.
 ///
 /// This does that projection, and returns a mapping from the expressions themselves to the names of
 /// the columns they have been projected to
@@ -61,7 +63,7 @@ pub(super) fn make_expressions_above_grouped(
     qg: &QueryGraph,
     prev_node: &mut NodeIndex,
 ) -> HashMap<Expr, SqlIdentifier> {
-    let exprs: Vec<_> = qg
+    let mut exprs: Vec<_> = qg
         .aggregates
         .keys()
         .filter(|&f| is_aggregate(f))
@@ -76,6 +78,11 @@ pub(super) fn make_expressions_above_grouped(
             )
         })
         .collect();
+    for (name, expr) in &qg.group_by_exprs {
+        if !exprs.iter().any(|(n, _)| n == name) {
+            exprs.push((name.clone(), expr.clone()));
+        }
+    }
 
     if !exprs.is_empty() {
         let cols = mir_converter.columns(*prev_node).to_vec();
@@ -293,6 +300,10 @@ pub(super) fn post_lookup_aggregates(
         return Ok(None);
     }
 
+    if !query_graph.group_by_exprs.is_empty() {
+        unsupported!("Post-lookup aggregation is not supported when grouping by expressions");
+    }
+
     let mut aggregates = vec![];
     for (function, alias) in &query_graph.aggregates {
         aggregates.push(PostLookupAggregate {
@@ -301,6 +312,29 @@ pub(super) fn post_lookup_aggregates(
                 Avg { .. } => {
                     unsupported!("Average is not supported as a post-lookup aggregate")
                 }
+                Stddev { expr, sample } | Variance { expr, sample } => {
+                    let [count, sum, sum_sq] = variance_helper_aggregates(expr).map(|func| {
+                        query_graph
+                            .aggregates
+                            .get(&func)
+                            .map(|name| {
+                                Column::named(name.clone()).aliased_as_table(query_name.clone())
+                            })
+                            .ok_or_else(|| {
+                                internal_err!(
+                                    "Missing {} for post-lookup variance",
+                                    func.display(nom_sql::Dialect::MySQL)
+                                )
+                            })
+                    });
+                    PostLookupAggregateFunction::Variance {
+                        sample: *sample,
+                        stddev: matches!(function, Stddev { .. }),
+                        count: count?,
+                        sum: sum?,
+                        sum_sq: sum_sq?,
+                    }
+                }
                 // Count and sum are handled the same way, as re-aggregating counts is
                 // done by just summing the numbers together
                 Count { .. } | CountStar | Sum { .. } => PostLookupAggregateFunction::Sum,
@@ -309,6 +343,11 @@ pub(super) fn post_lookup_aggregates(
                 GroupConcat { separator, .. } => PostLookupAggregateFunction::GroupConcat {
                     separator: separator.clone(),
                 },
+                BitAnd(_) => PostLookupAggregateFunction::BitAnd,
+                BitOr(_) => PostLookupAggregateFunction::BitOr,
+                BitXor(_) => PostLookupAggregateFunction::BitXor,
+                BoolAnd(_) => PostLookupAggregateFunction::BoolAnd,
+                BoolOr(_) => PostLookupAggregateFunction::BoolOr,
                 Call { .. } | Substring { .. } => continue,
             },
         });
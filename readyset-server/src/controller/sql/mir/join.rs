@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use mir::NodeIndex;
 use nom_sql::Relation;
-use readyset_errors::{internal, internal_err, invariant, ReadySetResult};
+use readyset_errors::{internal_err, invariant, unsupported, ReadySetResult};
 
 use super::JoinKind;
 use crate::controller::sql::mir::SqlToMirConverter;
@@ -45,9 +45,11 @@ pub(super) fn make_joins(
     let mut join_chains = Vec::new();
 
     for jref in qg.join_order.iter() {
-        let (mut join_kind, jps) = match &qg.edges[&(jref.src.clone(), jref.dst.clone())] {
-            QueryGraphEdge::Join { on } => (JoinKind::Inner, on),
-            QueryGraphEdge::LeftJoin { on } => (JoinKind::Left, on),
+        let (mut join_kind, jps, residual) = match &qg.edges[&(jref.src.clone(), jref.dst.clone())]
+        {
+            QueryGraphEdge::Join { on, residual } => (JoinKind::Inner, on, residual),
+            QueryGraphEdge::LeftJoin { on, residual } => (JoinKind::Left, on, residual),
+            QueryGraphEdge::FullJoin { on, residual } => (JoinKind::Full, on, residual),
         };
 
         let (left_chain, right_chain) =
@@ -56,14 +58,16 @@ pub(super) fn make_joins(
         // TODO(fran): Use NodeIndex instead of name.
         if correlated_nodes.contains(&right_chain.last_node) {
             match join_kind {
-                JoinKind::Left => internal!(
-                    "Dependent left join not yet supported (when joining to {})",
-                    jref.dst.display_unquoted()
-                ),
+                JoinKind::Left => {
+                    join_kind = JoinKind::DependentLeft;
+                }
                 JoinKind::Inner => {
                     join_kind = JoinKind::Dependent;
                 }
-                JoinKind::Dependent => {}
+                JoinKind::Full => {
+                    unsupported!("Correlated subqueries in full outer joins are not supported")
+                }
+                JoinKind::Dependent | JoinKind::DependentLeft => {}
             }
         }
 
@@ -71,6 +75,7 @@ pub(super) fn make_joins(
             query_name,
             mir_converter.generate_label(&name),
             jps,
+            residual.clone(),
             left_chain.last_node,
             right_chain.last_node,
             join_kind,
@@ -116,6 +121,7 @@ pub(super) fn make_cross_joins(
             query_name,
             mir_converter.generate_label(&name.into()),
             &[],
+            None,
             n1,
             n2,
             join_kind,
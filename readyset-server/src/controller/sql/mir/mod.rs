@@ -8,6 +8,7 @@ use catalog_tables::is_catalog_table;
 use common::{DfValue, IndexType};
 use dataflow::ops::grouped::aggregate::Aggregation;
 use dataflow::ops::union;
+use dataflow::ops::window::WindowFunction;
 use lazy_static::lazy_static;
 use mir::graph::MirGraph;
 use mir::node::node_inner::MirNodeInner;
@@ -17,16 +18,16 @@ use mir::DfNodeIndex;
 pub use mir::{Column, NodeIndex};
 use nom_sql::analysis::ReferredColumns;
 use nom_sql::{
-    BinaryOperator, ColumnSpecification, CompoundSelectOperator, CreateTableBody, Expr,
-    FieldDefinitionExpr, FieldReference, FunctionExpr, LimitClause, Literal, OrderClause,
-    OrderType, Relation, SqlIdentifier, TableKey,
+    BinaryOperator, CaseWhenBranch, ColumnSpecification, CompoundSelectOperator, CreateTableBody,
+    Expr, FieldDefinitionExpr, FieldReference, FunctionExpr, InValue, LimitClause, Literal,
+    OrderClause, OrderType, Relation, SelectStatement, SqlIdentifier, TableKey, UnaryOperator,
 };
 use petgraph::visit::Reversed;
 use petgraph::Direction;
 use readyset_client::ViewPlaceholder;
 use readyset_errors::{
-    internal, internal_err, invalid_err, invariant, invariant_eq, unsupported, ReadySetError,
-    ReadySetResult,
+    internal, internal_err, invalid, invalid_err, invariant, invariant_eq, unsupported,
+    ReadySetError, ReadySetResult,
 };
 use readyset_sql_passes::is_correlated;
 use readyset_util::redacted::Sensitive;
@@ -38,7 +39,10 @@ use crate::controller::sql::mir::grouped::{
     post_lookup_aggregates,
 };
 use crate::controller::sql::mir::join::{make_cross_joins, make_joins};
-use crate::controller::sql::query_graph::{to_query_graph, OutputColumn, Pagination, QueryGraph};
+use crate::controller::sql::query_graph::{
+    to_query_graph, ExprColumn, LiteralColumn, OutputColumn, Pagination, QueryGraph,
+    WindowFunctionCall,
+};
 use crate::controller::sql::query_signature::Signature;
 
 mod grouped;
@@ -101,8 +105,12 @@ pub enum JoinKind {
     Inner,
     /// Left joins - see [`MirNodeInner::LeftJoin`]
     Left,
+    /// Full outer joins - see [`MirNodeInner::FullJoin`]
+    Full,
     /// Dependent joins - see [`MirNodeInner::DependentJoin`]
     Dependent,
+    /// Dependent left joins - see [`MirNodeInner::DependentLeftJoin`]
+    DependentLeft,
 }
 
 /// Specification for how to treat the leaf node of a query when converting it to MIR
@@ -154,6 +162,16 @@ pub(crate) struct Config {
     /// Enable support for mixing equality and range comparisons in a query. Support for mixed
     /// comparisons is currently unfinished, so these queries may return incorrect results.
     pub(crate) allow_mixed_comparisons: bool,
+
+    /// Upper bound on the value of a parametrized `LIMIT ?`. If set and [`allow_topk`][] is
+    /// `true`, queries with a parametrized `LIMIT` emit a [`TopK`][] node with this many rows per
+    /// key, and lookups into the reader with a larger limit return an error. Otherwise, all rows
+    /// for each key are materialized and the limit is only applied after lookup. Defaults to
+    /// `None`.
+    ///
+    /// [`allow_topk`]: Config::allow_topk
+    /// [`TopK`]: MirNodeInner::TopK
+    pub(crate) max_parametrized_limit: Option<usize>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -255,6 +273,11 @@ impl SqlToMirConverter {
         };
 
         if let Some((limit, offset)) = extract_limit_offset(limit_clause)? {
+            let Some(limit) = limit else {
+                unsupported!(
+                    "ReadySet does not support parametrized LIMIT fields in compound selects"
+                )
+            };
             let make_topk = offset.is_none();
             let paginate_name = if leaf_behavior.should_register() {
                 if make_topk {
@@ -744,6 +767,20 @@ impl SqlToMirConverter {
             };
         }
 
+        // The column to aggregate over for the given aggregate argument
+        let over_column = |expr: Box<Expr>| -> ReadySetResult<Column> {
+            match *expr {
+                Expr::Column(col) => Ok(Column::from(col)),
+                // TODO(celine): replace with ParentRef
+                expr => Ok(Column::named(
+                    projected_exprs
+                        .get(&expr)
+                        .cloned()
+                        .ok_or_else(|| mk_error!(&expr))?,
+                )),
+            }
+        };
+
         let mut out_nodes = Vec::new();
 
         let mknode = |over: Column, t: GroupedNodeType, distinct: bool| {
@@ -877,6 +914,41 @@ impl SqlToMirConverter {
                 GroupedNodeType::Aggregation(Aggregation::GroupConcat { separator }),
                 false,
             ),
+            Stddev { expr, sample } => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::Stddev { sample }),
+                false,
+            ),
+            Variance { expr, sample } => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::Variance { sample }),
+                false,
+            ),
+            BitAnd(expr) => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::BitAnd),
+                false,
+            ),
+            BitOr(expr) => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::BitOr),
+                false,
+            ),
+            BitXor(expr) => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::BitXor),
+                false,
+            ),
+            BoolAnd(expr) => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::BoolAnd),
+                false,
+            ),
+            BoolOr(expr) => mknode(
+                over_column(expr)?,
+                GroupedNodeType::Aggregation(Aggregation::BoolOr),
+                false,
+            ),
             _ => {
                 internal!("not an aggregate: {:?}", Sensitive(&function));
             }
@@ -923,6 +995,7 @@ impl SqlToMirConverter {
         query_name: &Relation,
         name: Relation,
         join_predicates: &[JoinPredicate],
+        residual: Option<Expr>,
         left_node: NodeIndex,
         right_node: NodeIndex,
         kind: JoinKind,
@@ -983,9 +1056,26 @@ impl SqlToMirConverter {
         }
 
         let inner = match kind {
-            JoinKind::Inner => MirNodeInner::Join { on, project },
-            JoinKind::Left => MirNodeInner::LeftJoin { on, project },
+            JoinKind::Inner => MirNodeInner::Join {
+                on,
+                project,
+                residual,
+            },
+            JoinKind::Left => MirNodeInner::LeftJoin {
+                on,
+                project,
+                residual,
+            },
+            JoinKind::Full => MirNodeInner::FullJoin {
+                on,
+                project,
+                residual,
+            },
+            JoinKind::Dependent | JoinKind::DependentLeft if residual.is_some() => {
+                unsupported!("Non-equality join conditions are not supported in correlated joins")
+            }
             JoinKind::Dependent => MirNodeInner::DependentJoin { on, project },
+            JoinKind::DependentLeft => MirNodeInner::DependentLeftJoin { on, project },
         };
         trace!(?inner, "Added join node");
         Ok(self.add_query_node(
@@ -1167,12 +1257,129 @@ impl SqlToMirConverter {
         Ok(nodes)
     }
 
+    /// Make a chain of nodes computing each of the given window functions, partitioned by (in
+    /// addition to the window's own partition) the given `partition_key` columns. Returns the last
+    /// node in the chain
+    fn make_window_nodes(
+        &mut self,
+        query_name: &Relation,
+        name: SqlIdentifier,
+        mut parent: NodeIndex,
+        window_functions: &[WindowFunctionCall],
+        partition_key: &[Column],
+    ) -> ReadySetResult<NodeIndex> {
+        let column_arg = |expr: &Expr| -> ReadySetResult<Column> {
+            match expr {
+                Expr::Column(col) => Ok(Column::from(col)),
+                _ => unsupported!("Only columns are supported as arguments to window functions"),
+            }
+        };
+
+        for (i, window) in window_functions.iter().enumerate() {
+            let (function, over) = match &window.function {
+                FunctionExpr::Call {
+                    name: function_name,
+                    arguments,
+                } => match (function_name.to_lowercase().as_str(), &arguments[..]) {
+                    ("row_number", []) => (WindowFunction::RowNumber, None),
+                    ("rank", []) => (WindowFunction::Rank, None),
+                    ("dense_rank", []) => (WindowFunction::DenseRank, None),
+                    (f @ ("lag" | "lead"), [arg, offset @ ..]) => {
+                        let offset = match offset {
+                            [] => 1,
+                            [Expr::Literal(Literal::UnsignedInteger(n))] => *n as usize,
+                            [Expr::Literal(Literal::Integer(n))] if *n >= 0 => *n as usize,
+                            _ => unsupported!(
+                                "The offset for {} must be a non-negative integer literal",
+                                f.to_uppercase()
+                            ),
+                        };
+                        (
+                            if f == "lag" {
+                                WindowFunction::Lag { offset }
+                            } else {
+                                WindowFunction::Lead { offset }
+                            },
+                            Some(column_arg(arg)?),
+                        )
+                    }
+                    (f, _) => unsupported!("Unsupported window function: {}", f),
+                },
+                FunctionExpr::CountStar => (WindowFunction::CountStar, None),
+                FunctionExpr::Count {
+                    expr,
+                    distinct: false,
+                } => (WindowFunction::Count, Some(column_arg(expr)?)),
+                FunctionExpr::Sum {
+                    expr,
+                    distinct: false,
+                } => (WindowFunction::Sum, Some(column_arg(expr)?)),
+                FunctionExpr::Max(expr) => (WindowFunction::Max, Some(column_arg(expr)?)),
+                FunctionExpr::Min(expr) => (WindowFunction::Min, Some(column_arg(expr)?)),
+                FunctionExpr::Count { distinct: true, .. }
+                | FunctionExpr::Sum { distinct: true, .. } => {
+                    unsupported!("DISTINCT is not supported in window functions")
+                }
+                f => unsupported!(
+                    "Unsupported window function: {}",
+                    // FIXME(ENG-2499): Use correct dialect.
+                    f.display(nom_sql::Dialect::MySQL)
+                ),
+            };
+
+            let mut partition_by = window
+                .partition_by
+                .iter()
+                .map(Column::from)
+                .chain(partition_key.iter().cloned())
+                .collect::<Vec<_>>();
+
+            // The window operator needs at least one column to partition by, so if there isn't
+            // one, add a constant column to put every row in the same partition
+            if partition_by.is_empty() {
+                let partition_col: SqlIdentifier = format!("__window_partition_{i}").into();
+                let parent_columns = self.mir_graph.columns(parent);
+                parent = self.make_project_node(
+                    query_name,
+                    format!("{}_w{}_proj", name, i).into(),
+                    parent,
+                    parent_columns,
+                    vec![],
+                    vec![(partition_col.clone(), DfValue::from(0))],
+                );
+                partition_by.push(Column::named(partition_col));
+            }
+
+            parent = self.add_query_node(
+                query_name.clone(),
+                MirNode::new(
+                    format!("{}_w{}", name, i).into(),
+                    MirNodeInner::Window {
+                        function,
+                        over,
+                        partition_by,
+                        order_by: window
+                            .order_by
+                            .iter()
+                            .map(|(col, ot)| (Column::from(col), *ot))
+                            .collect(),
+                        output_column: Column::named(window.name.clone()),
+                    },
+                ),
+                &[parent],
+            );
+        }
+
+        Ok(parent)
+    }
+
     fn make_predicate_nodes(
         &mut self,
         query_name: &Relation,
         name: Relation,
         parent: NodeIndex,
         ce: &Expr,
+        anon_queries: &HashMap<Relation, NodeIndex>,
     ) -> ReadySetResult<NodeIndex> {
         let output_cols = self.mir_graph.columns(parent);
         let leaf = match ce {
@@ -1182,9 +1389,9 @@ impl SqlToMirConverter {
                 rhs,
             } => {
                 let left_subquery_leaf =
-                    self.make_predicate_nodes(query_name, name.clone(), parent, lhs)?;
+                    self.make_predicate_nodes(query_name, name.clone(), parent, lhs, anon_queries)?;
 
-                self.make_predicate_nodes(query_name, name, left_subquery_leaf, rhs)?
+                self.make_predicate_nodes(query_name, name, left_subquery_leaf, rhs, anon_queries)?
             }
             Expr::BinaryOp {
                 lhs,
@@ -1192,9 +1399,9 @@ impl SqlToMirConverter {
                 rhs,
             } => {
                 let left_subquery_leaf =
-                    self.make_predicate_nodes(query_name, name.clone(), parent, lhs)?;
+                    self.make_predicate_nodes(query_name, name.clone(), parent, lhs, anon_queries)?;
                 let right_subquery_leaf =
-                    self.make_predicate_nodes(query_name, name.clone(), parent, rhs)?;
+                    self.make_predicate_nodes(query_name, name.clone(), parent, rhs, anon_queries)?;
 
                 debug!("Creating union node for `or` predicate");
 
@@ -1230,84 +1437,31 @@ impl SqlToMirConverter {
             ),
             Expr::Between { .. } => internal!("BETWEEN should have been removed earlier"),
             Expr::Exists(subquery) => {
-                let query_graph = to_query_graph((**subquery).clone())?;
-                let subquery_leaf = self.named_query_to_mir(
-                    query_name,
-                    &query_graph,
-                    &HashMap::new(),
-                    LeafBehavior::Anonymous,
-                )?;
-
-                // -> π[lit: 0, lit: 0]
-                let group_proj = self.make_project_node(
-                    query_name,
-                    format!("{}_prj_hlpr", name.display_unquoted()).into(),
-                    subquery_leaf,
-                    vec![],
-                    vec![],
-                    vec![
-                        ("__count_val".into(), DfValue::from(0u32)),
-                        ("__count_grp".into(), DfValue::from(0u32)),
-                    ],
-                );
-                // -> [0, 0] for each row
-
-                // -> |0| γ[1]
-                let exists_count_col = Column::named("__exists_count");
-                let exists_count_node = self.make_grouped_node(
-                    query_name,
-                    format!("{}_count", name.display_unquoted()).into(),
-                    exists_count_col,
-                    (group_proj, Column::named("__count_val")),
-                    vec![Column::named("__count_grp")],
-                    GroupedNodeType::Aggregation(Aggregation::Count),
-                );
-                // -> [0, <count>] for each row
-
-                // -> σ[c1 > 0]
-                let gt_0_filter = self.make_filter_node(
-                    query_name,
-                    format!("{}_count_gt_0", name.display_unquoted()).into(),
-                    exists_count_node,
-                    Expr::BinaryOp {
-                        lhs: Box::new(Expr::Column("__exists_count".into())),
-                        op: BinaryOperator::Greater,
-                        rhs: Box::new(Expr::Literal(Literal::Integer(0))),
-                    },
-                );
-
-                // left -> π[...left, lit: 0]
-                let parent_columns = self.mir_graph.columns(parent);
-                let left_literal_join_key_proj = self.make_project_node(
-                    query_name,
-                    format!("{}_join_key", name.display_unquoted()).into(),
-                    parent,
-                    parent_columns,
-                    vec![],
-                    vec![("__exists_join_key".into(), DfValue::from(0u32))],
-                );
-
-                // -> ⋈ on: l.__exists_join_key ≡ r.__count_grp
-                self.make_join_node(
-                    query_name,
-                    format!("{}_join", name.display_unquoted()).into(),
-                    &[JoinPredicate {
-                        left: Expr::Column("__exists_join_key".into()),
-                        right: Expr::Column("__count_grp".into()),
-                    }],
-                    left_literal_join_key_proj,
-                    gt_0_filter,
-                    if is_correlated(subquery) {
-                        JoinKind::Dependent
-                    } else {
-                        JoinKind::Inner
-                    },
-                )?
+                self.make_exists_nodes(query_name, name, parent, subquery, false, anon_queries)?
             }
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                rhs: box Expr::Exists(subquery),
+            } => self.make_exists_nodes(query_name, name, parent, subquery, true, anon_queries)?,
+            Expr::In {
+                lhs,
+                rhs: InValue::Subquery(subquery),
+                negated,
+            } => self.make_in_subquery_nodes(
+                query_name,
+                name,
+                parent,
+                lhs,
+                subquery,
+                *negated,
+                anon_queries,
+            )?,
             Expr::Call(_) => {
                 internal!("Function calls should have been handled by projection earlier")
             }
-            Expr::NestedSelect(_) => unsupported!("Nested selects not supported in filters"),
+            Expr::NestedSelect(_) => {
+                internal!("Scalar subqueries should have been extracted from filters earlier")
+            }
             _ => self.make_filter_node(
                 query_name,
                 format!(
@@ -1324,6 +1478,514 @@ impl SqlToMirConverter {
         Ok(leaf)
     }
 
+    /// Make the nodes for an `EXISTS` or `NOT EXISTS` predicate, returning the last of the nodes
+    /// created.
+    ///
+    /// The rows of the subquery are counted (in a single group), and then the parent is joined to
+    /// that count on a literal join key. For `EXISTS`, this is an inner join against the count
+    /// filtered to be nonzero (a semi-join). For `NOT EXISTS`, this is a left join against the
+    /// count, keeping only the rows for which the count is NULL, which is only the case if the
+    /// subquery returned no rows (an anti-join).
+    ///
+    /// If the subquery is correlated, the join is a dependent join, which will be removed by the
+    /// decorrelation rewrite pass.
+    fn make_exists_nodes(
+        &mut self,
+        query_name: &Relation,
+        name: Relation,
+        parent: NodeIndex,
+        subquery: &SelectStatement,
+        negated: bool,
+        anon_queries: &HashMap<Relation, NodeIndex>,
+    ) -> ReadySetResult<NodeIndex> {
+        let query_graph = to_query_graph(subquery.clone())?;
+        let subquery_leaf = self.named_query_to_mir(
+            query_name,
+            &query_graph,
+            anon_queries,
+            LeafBehavior::Anonymous,
+        )?;
+
+        // Suffix the names of all the columns we make here with the current node count, so that
+        // multiple EXISTS subqueries in the same query don't generate conflicting columns
+        let suffix = self.mir_graph.node_count();
+        let count_val_col = nom_sql::Column::from(format!("__count_val_{suffix}").as_str());
+        let count_grp_col = nom_sql::Column::from(format!("__count_grp_{suffix}").as_str());
+        let exists_count_col = nom_sql::Column::from(format!("__exists_count_{suffix}").as_str());
+        let join_key_col = nom_sql::Column::from(format!("__exists_join_key_{suffix}").as_str());
+
+        // -> π[lit: 0, lit: 0]
+        let group_proj = self.make_project_node(
+            query_name,
+            format!("{}_prj_hlpr", name.display_unquoted()).into(),
+            subquery_leaf,
+            vec![],
+            vec![],
+            vec![
+                (count_val_col.name.clone(), DfValue::from(0u32)),
+                (count_grp_col.name.clone(), DfValue::from(0u32)),
+            ],
+        );
+        // -> [0, 0] for each row
+
+        // -> |0| γ[1]
+        let exists_count_node = self.make_grouped_node(
+            query_name,
+            format!("{}_count", name.display_unquoted()).into(),
+            Column::from(&exists_count_col),
+            (group_proj, Column::from(&count_val_col)),
+            vec![Column::from(&count_grp_col)],
+            GroupedNodeType::Aggregation(Aggregation::Count),
+        );
+        // -> [0, <count>] if the subquery returns any rows
+
+        let right = if negated {
+            exists_count_node
+        } else {
+            // -> σ[c1 > 0]
+            self.make_filter_node(
+                query_name,
+                format!("{}_count_gt_0", name.display_unquoted()).into(),
+                exists_count_node,
+                Expr::BinaryOp {
+                    lhs: Box::new(Expr::Column(exists_count_col.clone())),
+                    op: BinaryOperator::Greater,
+                    rhs: Box::new(Expr::Literal(Literal::Integer(0))),
+                },
+            )
+        };
+
+        // left -> π[...left, lit: 0]
+        let parent_columns = self.mir_graph.columns(parent);
+        let left_literal_join_key_proj = self.make_project_node(
+            query_name,
+            format!("{}_join_key", name.display_unquoted()).into(),
+            parent,
+            parent_columns,
+            vec![],
+            vec![(join_key_col.name.clone(), DfValue::from(0u32))],
+        );
+
+        let join_kind = match (negated, is_correlated(subquery)) {
+            (false, false) => JoinKind::Inner,
+            (false, true) => JoinKind::Dependent,
+            (true, false) => JoinKind::Left,
+            (true, true) => JoinKind::DependentLeft,
+        };
+
+        // -> ⋈ on: l.__exists_join_key ≡ r.__count_grp
+        let join = self.make_join_node(
+            query_name,
+            format!("{}_join", name.display_unquoted()).into(),
+            &[JoinPredicate {
+                left: Expr::Column(join_key_col),
+                right: Expr::Column(count_grp_col),
+            }],
+            None,
+            left_literal_join_key_proj,
+            right,
+            join_kind,
+        )?;
+
+        if !negated {
+            return Ok(join);
+        }
+
+        // -> σ[c1 IS NULL]
+        Ok(self.make_filter_node(
+            query_name,
+            format!("{}_count_null", name.display_unquoted()).into(),
+            join,
+            Expr::BinaryOp {
+                lhs: Box::new(Expr::Column(exists_count_col)),
+                op: BinaryOperator::Is,
+                rhs: Box::new(Expr::Literal(Literal::Null)),
+            },
+        ))
+    }
+
+    /// Compile the given subquery, which must project exactly one column, and project the value of
+    /// that column (passed through `make_value`) as a new column named `value_col`, along with the
+    /// given `literals`.
+    ///
+    /// Returns the index of the new projection node.
+    fn make_subquery_value_node(
+        &mut self,
+        query_name: &Relation,
+        name: Relation,
+        subquery: &SelectStatement,
+        value_col: SqlIdentifier,
+        make_value: impl FnOnce(Expr) -> Expr,
+        literals: Vec<(SqlIdentifier, DfValue)>,
+        anon_queries: &HashMap<Relation, NodeIndex>,
+    ) -> ReadySetResult<NodeIndex> {
+        let query_graph = to_query_graph(subquery.clone())?;
+        let subquery_leaf = self.named_query_to_mir(
+            query_name,
+            &query_graph,
+            anon_queries,
+            LeafBehavior::Anonymous,
+        )?;
+
+        let returned_col = match query_graph.columns.as_slice() {
+            [OutputColumn::Data { alias, column }] => {
+                Column::from(column).aliased_as(alias.clone())
+            }
+            [OutputColumn::Expr(ExprColumn { name, .. })]
+            | [OutputColumn::Literal(LiteralColumn { name, .. })] => Column::named(name.clone()),
+            _ => invalid!("Operand should contain 1 column(s)"),
+        };
+        let subquery_col = self
+            .mir_graph
+            .columns(subquery_leaf)
+            .into_iter()
+            .find(|c| *c == returned_col)
+            .ok_or_else(|| internal_err!("Column {returned_col} not found in subquery"))?;
+
+        Ok(self.make_project_node(
+            query_name,
+            name,
+            subquery_leaf,
+            vec![],
+            vec![(
+                value_col,
+                make_value(Expr::Column(nom_sql::Column {
+                    name: subquery_col.name,
+                    table: subquery_col.table,
+                })),
+            )],
+            literals,
+        ))
+    }
+
+    /// Make the nodes for an `IN` or `NOT IN` predicate with a subquery on the right-hand side,
+    /// returning the last of the nodes created.
+    ///
+    /// `lhs IN (<subquery>)` is compiled to a semi-join: the result set of the subquery is grouped
+    /// by its single column, so that each distinct value occurs at most once, then inner-joined to
+    /// `parent` on `lhs`. Since NULL join keys never match, this gets the NULL semantics of `IN`
+    /// right for free.
+    ///
+    /// `lhs NOT IN (<subquery>)` is compiled to an anti-join: the same grouped result set is
+    /// left-joined to `parent`, and we keep only the rows that had no match. To get the NULL
+    /// semantics of `NOT IN` right, we additionally left-join to the number of NULL values in the
+    /// result set of the subquery (which is itself NULL if the subquery returns no rows), since:
+    ///
+    /// - `x NOT IN (<empty result set>)` is true, even if `x` is NULL
+    /// - `NULL NOT IN (<non-empty result set>)` is NULL
+    /// - `x NOT IN (<result set containing NULL>)` is either false or NULL
+    ///
+    /// If the subquery is correlated, all the joins we make are dependent joins, which will be
+    /// removed by the decorrelation rewrite pass.
+    fn make_in_subquery_nodes(
+        &mut self,
+        query_name: &Relation,
+        name: Relation,
+        mut parent: NodeIndex,
+        lhs: &Expr,
+        subquery: &SelectStatement,
+        negated: bool,
+        anon_queries: &HashMap<Relation, NodeIndex>,
+    ) -> ReadySetResult<NodeIndex> {
+        let correlated = is_correlated(subquery);
+        let name = name.display_unquoted().to_string();
+
+        // Suffix the names of all the columns we make here with the current node count, so that
+        // multiple IN subqueries in the same query don't generate conflicting columns
+        let suffix = self.mir_graph.node_count();
+        let make_col = |col: &str| nom_sql::Column {
+            name: format!("__in_{col}_{suffix}").into(),
+            table: None,
+        };
+        let value_col = make_col("value");
+        let count_val_col = make_col("count_val");
+        let count_col = make_col("count");
+
+        // If the left-hand side isn't just a column, project it so we can use it as a join key
+        let lhs_col = match lhs {
+            Expr::Column(col) => col.clone(),
+            expr => {
+                let lhs_col = make_col("lhs");
+                let parent_columns = self.mir_graph.columns(parent);
+                parent = self.make_project_node(
+                    query_name,
+                    format!("{name}_in_lhs").into(),
+                    parent,
+                    parent_columns,
+                    vec![(lhs_col.name.clone(), expr.clone())],
+                    vec![],
+                );
+                lhs_col
+            }
+        };
+
+        // subquery -> π[value, lit: 0]
+        let value_proj = self.make_subquery_value_node(
+            query_name,
+            format!("{name}_in_prj").into(),
+            subquery,
+            value_col.name.clone(),
+            |value| value,
+            vec![(count_val_col.name.clone(), DfValue::from(0u32))],
+            anon_queries,
+        )?;
+
+        // -> |*|(count_val) γ[value]
+        let count_node = self.make_grouped_node(
+            query_name,
+            format!("{name}_in_count").into(),
+            Column::from(&count_col),
+            (value_proj, Column::from(&count_val_col)),
+            vec![Column::from(&value_col)],
+            GroupedNodeType::Aggregation(Aggregation::Count),
+        );
+        // -> [value, <count>] for each distinct value
+
+        let join_predicates = [JoinPredicate {
+            left: Expr::Column(lhs_col.clone()),
+            right: Expr::Column(value_col),
+        }];
+
+        if !negated {
+            // -> ⋈ on: l.lhs ≡ r.value
+            return self.make_join_node(
+                query_name,
+                format!("{name}_in_join").into(),
+                &join_predicates,
+                None,
+                parent,
+                count_node,
+                if correlated {
+                    JoinKind::Dependent
+                } else {
+                    JoinKind::Inner
+                },
+            );
+        }
+
+        let join_kind = if correlated {
+            JoinKind::DependentLeft
+        } else {
+            JoinKind::Left
+        };
+
+        // left -> ⟕ on: l.lhs ≡ r.value
+        let anti_join = self.make_join_node(
+            query_name,
+            format!("{name}_in_anti_join").into(),
+            &join_predicates,
+            None,
+            parent,
+            count_node,
+            join_kind,
+        )?;
+
+        let null_col = make_col("null");
+        let grp_col = make_col("grp");
+        let nulls_col = make_col("nulls");
+        let join_key_col = make_col("join_key");
+
+        // subquery -> π[CASE WHEN value IS NULL THEN 0 END, lit: 0]
+        let null_proj = self.make_subquery_value_node(
+            query_name,
+            format!("{name}_in_null_prj").into(),
+            subquery,
+            null_col.name.clone(),
+            |value| Expr::CaseWhen {
+                branches: vec![CaseWhenBranch {
+                    condition: Expr::BinaryOp {
+                        lhs: Box::new(value),
+                        op: BinaryOperator::Is,
+                        rhs: Box::new(Expr::Literal(Literal::Null)),
+                    },
+                    body: Expr::Literal(Literal::UnsignedInteger(0)),
+                }],
+                else_expr: None,
+            },
+            vec![(grp_col.name.clone(), DfValue::from(0u32))],
+            anon_queries,
+        )?;
+
+        // -> |*|(null) γ[grp]
+        let nulls_node = self.make_grouped_node(
+            query_name,
+            format!("{name}_in_nulls").into(),
+            Column::from(&nulls_col),
+            (null_proj, Column::from(&null_col)),
+            vec![Column::from(&grp_col)],
+            GroupedNodeType::Aggregation(Aggregation::Count),
+        );
+        // -> [0, <number of nulls>] if the subquery returns any rows
+
+        // anti_join -> π[...anti_join, lit: 0]
+        let anti_join_columns = self.mir_graph.columns(anti_join);
+        let left_literal_join_key_proj = self.make_project_node(
+            query_name,
+            format!("{name}_in_join_key").into(),
+            anti_join,
+            anti_join_columns,
+            vec![],
+            vec![(join_key_col.name.clone(), DfValue::from(0u32))],
+        );
+
+        // -> ⟕ on: l.join_key ≡ r.grp
+        let nulls_join = self.make_join_node(
+            query_name,
+            format!("{name}_in_nulls_join").into(),
+            &[JoinPredicate {
+                left: Expr::Column(join_key_col),
+                right: Expr::Column(grp_col),
+            }],
+            None,
+            left_literal_join_key_proj,
+            nulls_node,
+            join_kind,
+        )?;
+
+        // -> σ[nulls IS NULL OR (count IS NULL AND lhs IS NOT NULL AND nulls = 0)]
+        let is_null = |col: nom_sql::Column, op| Expr::BinaryOp {
+            lhs: Box::new(Expr::Column(col)),
+            op,
+            rhs: Box::new(Expr::Literal(Literal::Null)),
+        };
+        let and = |lhs, rhs| Expr::BinaryOp {
+            lhs: Box::new(lhs),
+            op: BinaryOperator::And,
+            rhs: Box::new(rhs),
+        };
+        Ok(self.make_filter_node(
+            query_name,
+            format!("{name}_in_anti_join_f").into(),
+            nulls_join,
+            Expr::BinaryOp {
+                lhs: Box::new(is_null(nulls_col.clone(), BinaryOperator::Is)),
+                op: BinaryOperator::Or,
+                rhs: Box::new(and(
+                    and(
+                        is_null(count_col, BinaryOperator::Is),
+                        is_null(lhs_col, BinaryOperator::IsNot),
+                    ),
+                    Expr::BinaryOp {
+                        lhs: Box::new(Expr::Column(nulls_col)),
+                        op: BinaryOperator::Equal,
+                        rhs: Box::new(Expr::Literal(Literal::UnsignedInteger(0))),
+                    },
+                )),
+            },
+        ))
+    }
+
+    /// Make the nodes for the scalar subquery `subquery`, whose value is projected as a new column
+    /// named `value_col`, returning the last of the nodes created.
+    ///
+    /// The subquery, which must return at most one row, is left-joined to `parent` on a literal
+    /// join key, so that every row of the parent gets the value of the subquery, or NULL if the
+    /// subquery returns no rows. Since `COUNT` of no rows is 0 rather than NULL, the values of
+    /// subqueries that project a `COUNT` are additionally coalesced to 0.
+    ///
+    /// If the subquery is correlated, the join is a dependent left join, which will be removed by
+    /// the decorrelation rewrite pass (which turns the correlated columns into both join keys and
+    /// group-by columns of the subquery's aggregate).
+    fn make_scalar_subquery_nodes(
+        &mut self,
+        query_name: &Relation,
+        name: Relation,
+        parent: NodeIndex,
+        value_col: &SqlIdentifier,
+        subquery: &SelectStatement,
+        anon_queries: &HashMap<Relation, NodeIndex>,
+    ) -> ReadySetResult<NodeIndex> {
+        let name = name.display_unquoted().to_string();
+
+        // Suffix the names of all the columns we make here with the current node count, so that
+        // multiple scalar subqueries in the same query don't generate conflicting columns
+        let suffix = self.mir_graph.node_count();
+        let grp_col = nom_sql::Column::from(format!("__scalar_grp_{suffix}").as_str());
+        let join_key_col = nom_sql::Column::from(format!("__scalar_join_key_{suffix}").as_str());
+
+        // subquery -> π[value, lit: 0]
+        let value_proj = self.make_subquery_value_node(
+            query_name,
+            format!("{name}_scalar_prj").into(),
+            subquery,
+            value_col.clone(),
+            |value| value,
+            vec![(grp_col.name.clone(), DfValue::from(0u32))],
+            anon_queries,
+        )?;
+
+        // parent -> π[...parent, lit: 0]
+        let parent_columns = self.mir_graph.columns(parent);
+        let left_literal_join_key_proj = self.make_project_node(
+            query_name,
+            format!("{name}_scalar_join_key").into(),
+            parent,
+            parent_columns,
+            vec![],
+            vec![(join_key_col.name.clone(), DfValue::from(0u32))],
+        );
+
+        let join_kind = if is_correlated(subquery) {
+            JoinKind::DependentLeft
+        } else {
+            JoinKind::Left
+        };
+
+        // -> ⟕ on: l.__scalar_join_key ≡ r.__scalar_grp
+        let join = self.make_join_node(
+            query_name,
+            format!("{name}_scalar_join").into(),
+            &[JoinPredicate {
+                left: Expr::Column(join_key_col),
+                right: Expr::Column(grp_col),
+            }],
+            None,
+            left_literal_join_key_proj,
+            value_proj,
+            join_kind,
+        )?;
+
+        let is_count = matches!(
+            subquery.fields.as_slice(),
+            [FieldDefinitionExpr::Expr {
+                expr: Expr::Call(FunctionExpr::Count { .. } | FunctionExpr::CountStar),
+                ..
+            }]
+        );
+        if !is_count {
+            return Ok(join);
+        }
+
+        // -> π[...parent, coalesce(value, 0)]
+        let value_column = Column::named(value_col.clone());
+        let emit = self
+            .mir_graph
+            .columns(join)
+            .into_iter()
+            .filter(|c| *c != value_column)
+            .collect();
+        Ok(self.make_project_node(
+            query_name,
+            format!("{name}_scalar_count").into(),
+            join,
+            emit,
+            vec![(
+                value_col.clone(),
+                Expr::Call(FunctionExpr::Call {
+                    name: "coalesce".into(),
+                    arguments: vec![
+                        Expr::Column(nom_sql::Column {
+                            name: value_col.clone(),
+                            table: None,
+                        }),
+                        Expr::Literal(Literal::Integer(0)),
+                    ],
+                }),
+            )],
+            vec![],
+        ))
+    }
+
     fn predicates_above_group_by<'a>(
         &mut self,
         query_name: &Relation,
@@ -1332,6 +1994,7 @@ impl SqlToMirConverter {
         over_col: &nom_sql::Column,
         parent: NodeIndex,
         created_predicates: &mut Vec<&'a Expr>,
+        anon_queries: &HashMap<Relation, NodeIndex>,
     ) -> ReadySetResult<NodeIndex> {
         let mut leaf = parent;
 
@@ -1350,6 +2013,7 @@ impl SqlToMirConverter {
                     .into(),
                     leaf,
                     ce,
+                    anon_queries,
                 )?;
                 leaf = subquery_leaf;
                 created_predicates.push(ce);
@@ -1449,7 +2113,7 @@ impl SqlToMirConverter {
                     let subquery_leaf = self.named_query_to_mir(
                         query_name,
                         subquery,
-                        &HashMap::new(),
+                        anon_queries,
                         LeafBehavior::Anonymous,
                     )?;
                     if correlated {
@@ -1527,6 +2191,24 @@ impl SqlToMirConverter {
                 }
             };
 
+            // 1a. Join the values of any scalar subqueries onto the result of the joins, so that
+            // the rest of the query can refer to them as ordinary columns
+            for (value_col, subquery) in &query_graph.scalar_subqueries {
+                prev_node = self.make_scalar_subquery_nodes(
+                    query_name,
+                    format!(
+                        "q_{:x}_n{}",
+                        query_graph.signature().hash,
+                        self.mir_graph.node_count()
+                    )
+                    .into(),
+                    prev_node,
+                    value_col,
+                    subquery,
+                    anon_queries,
+                )?;
+            }
+
             // 2. If we're aggregating on expressions rather than directly on columns, project out
             // those expressions before the aggregate itself
             let expressions_above_grouped = make_expressions_above_grouped(
@@ -1567,6 +2249,7 @@ impl SqlToMirConverter {
                 query_graph,
                 &column_to_predicates,
                 &mut prev_node,
+                anon_queries,
             )?;
 
             // 5. Generate the necessary filter nodes for local predicates associated with each
@@ -1600,6 +2283,7 @@ impl SqlToMirConverter {
                             .into(),
                             prev_node,
                             p,
+                            anon_queries,
                         )?;
 
                         prev_node = subquery_leaf;
@@ -1632,6 +2316,7 @@ impl SqlToMirConverter {
                     .into(),
                     prev_node,
                     p,
+                    anon_queries,
                 )?;
 
                 prev_node = subquery_leaf;
@@ -1657,19 +2342,54 @@ impl SqlToMirConverter {
                     i
                 )
                 .into();
-                let subquery_leaf = self.make_predicate_nodes(query_name, hp_name, prev_node, p)?;
+                let subquery_leaf =
+                    self.make_predicate_nodes(query_name, hp_name, prev_node, p, anon_queries)?;
 
                 prev_node = subquery_leaf;
             }
 
-            // 10. Get the final node
+            // 10. Add window function nodes after HAVING predicates, since window functions are
+            // computed over the grouped and filtered rows
+            if !query_graph.window_functions.is_empty() {
+                if view_key.index_type != IndexType::HashMap {
+                    unsupported!("Window functions are not supported in range queries");
+                }
+
+                // Each lookup key needs its own set of partitions, since the window can only see
+                // the rows that match the key
+                let partition_key = view_key
+                    .columns
+                    .iter()
+                    .filter(|(col, _)| col.name != *PAGE_NUMBER_COL)
+                    .map(|(col, _)| col.clone())
+                    .collect::<Vec<_>>();
+                prev_node = self.make_window_nodes(
+                    query_name,
+                    format!(
+                        "q_{:x}_n{}",
+                        query_graph.signature().hash,
+                        self.mir_graph.node_count()
+                    )
+                    .into(),
+                    prev_node,
+                    &query_graph.window_functions,
+                    &partition_key,
+                )?;
+            }
+
+            // 11. Get the final node
             let mut final_node = prev_node;
 
-            if let Some(Pagination {
-                order,
-                limit,
-                offset,
-            }) = query_graph.pagination.as_ref()
+            // A parametrized LIMIT is materialized as a TopK with the configured maximum limit, if
+            // any. The actual limit for each lookup is applied by the reader.
+            let pagination_limit = query_graph.pagination.as_ref().and_then(|p| match p.limit {
+                Some(limit) => Some(limit),
+                None if self.config.allow_topk => self.config.max_parametrized_limit,
+                None => None,
+            });
+
+            if let (Some(Pagination { order, offset, .. }), Some(limit)) =
+                (query_graph.pagination.as_ref(), pagination_limit)
             {
                 let make_topk = offset.is_none();
                 // view key will have the offset parameter if it exists. We must filter it out
@@ -1698,14 +2418,14 @@ impl SqlToMirConverter {
                     final_node,
                     group_by,
                     order,
-                    *limit,
+                    limit,
                     make_topk,
                 )?;
                 func_nodes.extend(paginate_nodes.clone());
                 final_node = *paginate_nodes.last().unwrap();
             }
 
-            // 10. Generate leaf views that expose the query result
+            // 12. Generate leaf views that expose the query result
             let mut projected_columns: Vec<Column> = query_graph
                 .columns
                 .iter()
@@ -1886,7 +2606,7 @@ impl SqlToMirConverter {
                             order_by: query_graph.order.as_ref().map(|order| {
                                 order.iter().map(|(c, ot)| (Column::from(c), *ot)).collect()
                             }),
-                            limit: query_graph.pagination.as_ref().map(|p| p.limit),
+                            limit: pagination_limit,
                             returned_cols: Some(returned_cols),
                             default_row: query_graph.default_row.clone(),
                             aggregates,
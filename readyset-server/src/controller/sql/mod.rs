@@ -6,8 +6,9 @@ use ::mir::visualize::GraphViz;
 use ::mir::DfNodeIndex;
 use ::serde::{Deserialize, Serialize};
 use nom_sql::{
-    CompoundSelectOperator, CompoundSelectStatement, CreateTableBody, FieldDefinitionExpr,
-    Relation, SelectSpecification, SelectStatement, SqlIdentifier, SqlType, TableExpr,
+    CacheOptions, CompoundSelectOperator, CompoundSelectStatement, CreateTableBody,
+    FieldDefinitionExpr, Relation, SelectSpecification, SelectStatement, SqlIdentifier, SqlType,
+    TableExpr,
 };
 use petgraph::graph::NodeIndex;
 use readyset_client::recipe::changelist::{AlterTypeChange, Change};
@@ -288,8 +289,9 @@ impl SqlIncorporator {
                     name,
                     statement,
                     always,
+                    options,
                 } => {
-                    self.add_query(name, *statement, always, &schema_search_path, mig)?;
+                    self.add_query(name, *statement, always, options, &schema_search_path, mig)?;
                 }
                 Change::AlterTable(_) => {
                     // The only ALTER TABLE changes that can end up here (currently) are ones that
@@ -524,6 +526,7 @@ impl SqlIncorporator {
         name: Option<Relation>,
         mut stmt: SelectStatement,
         always: bool,
+        options: CacheOptions,
         schema_search_path: &[SqlIdentifier],
         mig: &mut Migration<'_>,
     ) -> ReadySetResult<Relation> {
@@ -562,6 +565,17 @@ impl SqlIncorporator {
                 if caches.is_empty() {
                     // Can't reuse anything. Return the error.
                     return Err(err);
+                } else if caches.iter().any(|cache| {
+                    !matches!(
+                        self.registry.get(cache.name()),
+                        Some(RecipeExpr::Cache { options: existing, .. }) if *existing == options
+                    )
+                }) {
+                    // The caches we'd reuse keep the options they were created with
+                    unsupported!(
+                        "Can't create a cache which reuses existing caches created with different \
+                         options"
+                    );
                 } else {
                     #[allow(clippy::unwrap_used)]
                     // we checked that caches is not empty
@@ -587,17 +601,29 @@ impl SqlIncorporator {
             name: name.clone(),
             statement: stmt,
             always,
+            options,
         })?;
         self.registry
             .insert_invalidating_tables(name.clone(), invalidating_tables)?;
 
         if aliased {
+            // The existing cache keeps the options it was created with
+            if !matches!(
+                self.registry.get(&name),
+                Some(RecipeExpr::Cache { options: existing, .. }) if *existing == options
+            ) {
+                unsupported!(
+                    "Can't create a cache which is an alias of an existing cache created with \
+                     different options"
+                );
+            }
             return Ok(name);
         }
 
         // We don't add a leaf if we're reusing a query
         if let Some(mir_query) = mir_query {
             let leaf = self.mir_to_dataflow(name.clone(), mir_query, mig)?;
+            mig.set_cache_options(leaf, options)?;
             self.leaf_addresses.insert(name.clone(), leaf);
         }
 
@@ -17,9 +17,12 @@ use nom_sql::{
 use readyset_client::{PlaceholderIdx, ViewPlaceholder};
 use readyset_errors::{
     internal, invalid, invalid_err, invariant, invariant_eq, no_table_for_col, unsupported,
-    unsupported_err, ReadySetResult,
+    unsupported_err, ReadySetError, ReadySetResult,
+};
+use readyset_sql_passes::{
+    contains_aggregate, contains_nondeterministic, is_aggregate, is_correlated, is_predicate,
+    map_aggregates, LogicalOp,
 };
-use readyset_sql_passes::{is_aggregate, is_correlated, is_predicate, map_aggregates, LogicalOp};
 use serde::{Deserialize, Serialize};
 
 use super::mir::{self, PAGE_NUMBER_COL};
@@ -192,19 +195,52 @@ pub struct QueryGraphNode {
     pub subgraph: Option<Box<QueryGraph>>,
 }
 
+/// A join between two relations in a query graph.
+///
+/// The equality predicates in `on` are used as the key for the join (an empty list of predicates
+/// makes the join a cartesian product), and the `residual` predicate, if any, contains the
+/// remaining conditions of the join, which are evaluated against each pair of rows that match on
+/// those keys.
+///
+/// Right joins are represented as left joins with the two relations swapped.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QueryGraphEdge {
-    Join { on: Vec<JoinPredicate> },
-    LeftJoin { on: Vec<JoinPredicate> },
+    Join {
+        on: Vec<JoinPredicate>,
+        residual: Option<Expr>,
+    },
+    LeftJoin {
+        on: Vec<JoinPredicate>,
+        residual: Option<Expr>,
+    },
+    FullJoin {
+        on: Vec<JoinPredicate>,
+        residual: Option<Expr>,
+    },
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Pagination {
     pub order: Option<Vec<(Expr, OrderType)>>,
-    pub limit: usize,
+    /// The value of the `LIMIT`, or `None` if the `LIMIT` is a placeholder whose value is only
+    /// known at lookup time
+    pub limit: Option<usize>,
     pub offset: Option<ViewPlaceholder>,
 }
 
+/// A window function projected by a query, eg `ROW_NUMBER() OVER (PARTITION BY x ORDER BY y)`
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowFunctionCall {
+    /// The window function itself
+    pub function: FunctionExpr,
+    /// The columns in the `PARTITION BY` clause of the window
+    pub partition_by: Vec<Column>,
+    /// The columns and directions in the `ORDER BY` clause of the window
+    pub order_by: Vec<(Column, OrderType)>,
+    /// The name of the column the result of the window function will be projected as
+    pub name: SqlIdentifier,
+}
+
 /// Description of the lookup key for a view
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ViewKey {
@@ -235,7 +271,21 @@ pub struct QueryGraph {
     /// but both will appear in `self.columns` as [`OutputColumn::Data`] referencing that alias
     pub aggregates: HashMap<FunctionExpr, SqlIdentifier>,
     /// Set of columns that appear in the GROUP BY clause
+    ///
+    /// Non-column expressions in the GROUP BY clause appear here as columns without a table,
+    /// referencing the names in `self.group_by_exprs`
     pub group_by: HashSet<Column>,
+    /// Non-column expressions that appear in the GROUP BY clause, along with the name of the
+    /// column each expression is projected as before grouping
+    pub group_by_exprs: Vec<(SqlIdentifier, Expr)>,
+    /// Window functions in the query, in the order they appear in the `SELECT` clause. These are
+    /// computed after aggregates and HAVING predicates, and their results appear in `self.columns`
+    /// as [`OutputColumn::Data`] referencing the window function's name
+    pub window_functions: Vec<WindowFunctionCall>,
+    /// Scalar subqueries in the `SELECT` list and `WHERE` clause, along with the name of the
+    /// column each subquery was replaced with. These are joined to the result of the query's
+    /// joins, so that the rest of the query can refer to their values as ordinary columns
+    pub scalar_subqueries: Vec<(SqlIdentifier, SelectStatement)>,
     /// Final set of projected columns in this query; may include literals in addition to the
     /// columns reflected in individual relations' `QueryGraphNode` structures.
     pub columns: Vec<OutputColumn>,
@@ -387,11 +437,7 @@ impl QueryGraph {
             )?;
 
             if let Some(offset) = offset {
-                if index_type == Some(IndexType::BTreeMap) {
-                    unsupported!("ReadySet does not support Pagination and range queries")
-                } else {
-                    columns.push((mir::Column::named(PAGE_NUMBER_COL.clone()), offset));
-                }
+                columns.push((mir::Column::named(PAGE_NUMBER_COL.clone()), offset));
             }
 
             #[allow(clippy::expect_used)]
@@ -428,6 +474,9 @@ impl Hash for QueryGraph {
         aggregates.hash(state);
 
         // these fields are Vecs, so already ordered
+        self.group_by_exprs.hash(state);
+        self.window_functions.hash(state);
+        self.scalar_subqueries.hash(state);
         self.columns.hash(state);
         self.fields.hash(state);
         self.default_row.hash(state);
@@ -482,11 +531,10 @@ fn classify_conditionals(
     // Handling OR and AND expressions requires some care as there are some corner cases.
     //    a) we don't support OR expressions with predicates with placeholder parameters,
     //       because these expressions are meaningless in the Soup context.
-    //    b) we don't support OR expressions with join predicates because they are weird and
-    //       too hard.
-    //    c) we don't support OR expressions between different tables (e.g table1.x = 1 OR
-    //       table2.y= 42). this is a global predicate according to finkelstein algorithm
-    //       and we don't support these yet.
+    //    b) OR expressions with join predicates can't be used as the key for a join, so they
+    //       become global predicates, which are evaluated after the join.
+    //    c) OR expressions between different tables (e.g table1.x = 1 OR table2.y= 42) are
+    //       global predicates according to finkelstein algorithm.
 
     match ce {
         Expr::BinaryOp { op, lhs, rhs } => {
@@ -548,17 +596,18 @@ fn classify_conditionals(
                         // one side of the AND might be a global predicate, so we need to keep
                         // new_global around
                         global.extend(new_global);
+                        join.extend(new_join);
                     }
                     LogicalOp::Or => {
-                        if !new_join.is_empty() {
-                            unsupported!("can't handle OR expressions between JOIN predicates")
-                        }
                         if !new_params.is_empty() {
                             unsupported!(
                                 "can't handle OR expressions between query parameter predicates"
                             );
                         }
-                        if new_local.keys().len() == 1 && new_global.is_empty() {
+                        if new_join.is_empty()
+                            && new_local.keys().len() == 1
+                            && new_global.is_empty()
+                        {
                             // OR over a single table => local predicate
                             // just checked that new_local has one entry
                             #[allow(clippy::unwrap_used)]
@@ -576,13 +625,13 @@ fn classify_conditionals(
                             let e = local.entry(t).or_default();
                             e.push(new_ce);
                         } else {
-                            // OR between different tables => global predicate
+                            // OR between different tables, or between join predicates => global
+                            // predicate
                             global.push(ce.clone())
                         }
                     }
                 }
 
-                join.extend(new_join);
                 params.extend(new_params);
             } else if is_predicate(op) {
                 // atomic selection predicate
@@ -660,7 +709,7 @@ fn classify_conditionals(
                         }
                     }
                     Expr::NestedSelect(_) => {
-                        unsupported!("nested SELECTs are unsupported")
+                        internal!("Scalar subqueries should have been extracted earlier")
                     }
                     Expr::Call(_)
                     | Expr::BinaryOp { .. }
@@ -673,7 +722,8 @@ fn classify_conditionals(
                     | Expr::Between { .. }
                     | Expr::Cast { .. }
                     | Expr::In { .. }
-                    | Expr::Variable(_) => {
+                    | Expr::Variable(_)
+                    | Expr::WindowFunction { .. } => {
                         unsupported!(
                             "Unsupported right-hand side of condition expression: {}",
                             // FIXME(ENG-2499): Use correct dialect.
@@ -722,7 +772,11 @@ fn classify_conditionals(
         Expr::In {
             rhs: InValue::Subquery(..),
             ..
-        } => unsupported!("IN with subqueries is not yet supported"),
+        } => {
+            // The subquery may be correlated with any number of tables in the outer query, so we
+            // always treat it as a global predicate
+            global.push(ce.clone())
+        }
         Expr::Call(_)
         | Expr::Literal(_)
         | Expr::UnaryOp { .. }
@@ -735,38 +789,40 @@ fn classify_conditionals(
         | Expr::Cast { .. }
         | Expr::Array(_)
         | Expr::Variable(_) => global.push(ce.clone()),
+        Expr::WindowFunction { .. } => {
+            unsupported!("Window functions are not allowed in WHERE clauses")
+        }
     }
     Ok(())
 }
 
-/// Convert the given `Expr`, which should be a set of AND-ed together direct
-/// comparison predicates, into a list of predicate expressions
-fn collect_join_predicates(cond: Expr, out: &mut Vec<JoinPredicate>) -> ReadySetResult<()> {
-    match cond {
-        Expr::BinaryOp {
-            op: BinaryOperator::Equal,
-            lhs,
-            rhs,
-        } => {
-            out.push(JoinPredicate {
-                left: *lhs,
-                right: *rhs,
-            });
-            Ok(())
-        }
-        Expr::BinaryOp {
-            lhs,
-            op: BinaryOperator::And,
-            rhs,
-        } => {
-            collect_join_predicates(*lhs, out)?;
-            collect_join_predicates(*rhs, out)?;
-            Ok(())
-        }
-        _ => {
-            unsupported!("Only direct comparisons combined with AND supported for join conditions")
+/// Split the given join condition into a list of equality comparisons between columns in different
+/// tables, which can be used as the key for the join, and a residual predicate made up of all the
+/// remaining AND-ed together conditions
+fn split_join_condition(cond: &Expr) -> (Vec<JoinPredicate>, Option<Expr>) {
+    let mut join_preds = vec![];
+    let mut residual = vec![];
+    for conjunct in split_conjunctions(iter::once(cond)) {
+        match conjunct {
+            Expr::BinaryOp {
+                lhs: box Expr::Column(left),
+                op: BinaryOperator::Equal,
+                rhs: box Expr::Column(right),
+            } if left.table != right.table => join_preds.push(JoinPredicate {
+                left: Expr::Column(left),
+                right: Expr::Column(right),
+            }),
+            conjunct => residual.push(conjunct),
         }
     }
+
+    let residual = residual.into_iter().reduce(|lhs, rhs| Expr::BinaryOp {
+        lhs: Box::new(lhs),
+        op: BinaryOperator::And,
+        rhs: Box::new(rhs),
+    });
+
+    (join_preds, residual)
 }
 
 /// Processes the provided HAVING expression by extracting aggregates, splitting predicates, and
@@ -824,11 +880,179 @@ fn extract_having_aggregates(
     having_predicates
 }
 
-/// Convert limit and offset fields to an optional constant numeric limit and optional placeholder
-/// for the offset
+/// Replace all occurrences of any of the given GROUP BY expressions within `expr` with references
+/// to the columns those expressions are projected as
+fn replace_group_by_exprs(expr: &mut Expr, group_by_exprs: &[(SqlIdentifier, Expr)]) {
+    struct GroupByExprReplacer<'a> {
+        group_by_exprs: &'a [(SqlIdentifier, Expr)],
+    }
+
+    impl<'ast, 'a> VisitorMut<'ast> for GroupByExprReplacer<'a> {
+        type Error = !;
+
+        fn visit_expr(&mut self, expr: &'ast mut Expr) -> Result<(), Self::Error> {
+            if let Some((name, _)) = self.group_by_exprs.iter().find(|(_, gbe)| gbe == expr) {
+                *expr = Expr::Column(nom_sql::Column {
+                    name: name.clone(),
+                    table: None,
+                });
+                Ok(())
+            } else {
+                walk_expr(self, expr)
+            }
+        }
+
+        fn visit_select_statement(
+            &mut self,
+            _: &'ast mut SelectStatement,
+        ) -> Result<(), Self::Error> {
+            // Don't walk into subqueries
+            Ok(())
+        }
+    }
+
+    if !group_by_exprs.is_empty() {
+        let _ = GroupByExprReplacer { group_by_exprs }.visit_expr(expr);
+    }
+}
+
+/// Replace all scalar subqueries in the `SELECT` list and `WHERE` clause of the given statement
+/// with references to columns named `__scalar_subquery_<n>`, returning the extracted subqueries
+/// along with the names of the columns they were replaced with.
+///
+/// The values of scalar subqueries are joined onto the rows of the outer query, so each subquery
+/// must produce at most one row for every row of the outer query. For now, that means we only
+/// support subqueries that project a single aggregate without a `GROUP BY` - once correlated
+/// subqueries are decorrelated, their correlated columns end up grouped by instead.
+fn extract_scalar_subqueries(
+    stmt: &mut SelectStatement,
+) -> ReadySetResult<Vec<(SqlIdentifier, SelectStatement)>> {
+    #[derive(Default)]
+    struct ScalarSubqueryExtractor {
+        result: Vec<(SqlIdentifier, SelectStatement)>,
+    }
+
+    impl<'ast> VisitorMut<'ast> for ScalarSubqueryExtractor {
+        type Error = ReadySetError;
+
+        fn visit_expr(&mut self, expr: &'ast mut Expr) -> Result<(), Self::Error> {
+            if !matches!(expr, Expr::NestedSelect(_)) {
+                return walk_expr(self, expr);
+            }
+
+            let name: SqlIdentifier = format!("__scalar_subquery_{}", self.result.len()).into();
+            let col_expr = Expr::Column(Column {
+                name: name.clone(),
+                table: None,
+            });
+            let Expr::NestedSelect(subquery) = mem::replace(expr, col_expr) else {
+                unreachable!("Checked matches above")
+            };
+
+            match subquery.fields.as_slice() {
+                [FieldDefinitionExpr::Expr {
+                    expr: Expr::Call(function),
+                    ..
+                }] if is_aggregate(function) => {}
+                [_] => {
+                    unsupported!("Only scalar subqueries that project an aggregate are supported")
+                }
+                _ => invalid!("Operand should contain 1 column(s)"),
+            }
+            if subquery.group_by.is_some() {
+                unsupported!("Scalar subqueries with GROUP BY are not supported");
+            }
+
+            self.result.push((name, *subquery));
+            Ok(())
+        }
+
+        fn visit_select_statement(
+            &mut self,
+            _: &'ast mut SelectStatement,
+        ) -> Result<(), Self::Error> {
+            // Don't walk into subqueries
+            Ok(())
+        }
+    }
+
+    if stmt
+        .having
+        .iter()
+        .flat_map(|having| iter::once(having).chain(having.recursive_subexpressions()))
+        .any(|expr| matches!(expr, Expr::NestedSelect(_)))
+    {
+        unsupported!("Subqueries are not supported in the HAVING clause");
+    }
+
+    let is_aggregated = stmt.group_by.is_some()
+        || stmt.fields.iter().any(|field| {
+            matches!(field, FieldDefinitionExpr::Expr { expr, .. } if contains_aggregate(expr))
+        });
+
+    let mut extractor = ScalarSubqueryExtractor::default();
+    for field in stmt.fields.iter_mut() {
+        if let FieldDefinitionExpr::Expr { expr, alias } = field {
+            let num_subqueries = extractor.result.len();
+            let orig_expr = alias.is_none().then(|| expr.clone());
+            extractor.visit_expr(expr)?;
+            if extractor.result.len() == num_subqueries {
+                continue;
+            }
+
+            if is_aggregated {
+                unsupported!(
+                    "Scalar subqueries in the SELECT list of aggregated queries are not supported"
+                );
+            }
+            // Keep the name of the field the same as it was before we replaced the subquery
+            if let Some(orig_expr) = orig_expr {
+                // FIXME(ENG-2499): Use correct dialect.
+                *alias = Some(
+                    orig_expr
+                        .display(nom_sql::Dialect::MySQL)
+                        .to_string()
+                        .into(),
+                );
+            }
+        }
+    }
+    if let Some(where_clause) = &mut stmt.where_clause {
+        extractor.visit_expr(where_clause)?;
+    }
+
+    Ok(extractor.result)
+}
+
+/// Returns the `COUNT`, `SUM`, and sum of squares of the given expression, which are needed to
+/// re-aggregate the standard deviation or variance of that expression after a lookup into multiple
+/// keys
+pub(crate) fn variance_helper_aggregates(expr: &Expr) -> [FunctionExpr; 3] {
+    [
+        FunctionExpr::Count {
+            expr: Box::new(expr.clone()),
+            distinct: false,
+        },
+        FunctionExpr::Sum {
+            expr: Box::new(expr.clone()),
+            distinct: false,
+        },
+        FunctionExpr::Sum {
+            expr: Box::new(Expr::BinaryOp {
+                lhs: Box::new(expr.clone()),
+                op: BinaryOperator::Multiply,
+                rhs: Box::new(expr.clone()),
+            }),
+            distinct: false,
+        },
+    ]
+}
+
+/// Convert limit and offset fields to an optional numeric limit and optional placeholder for the
+/// offset. The numeric limit is `None` if the limit is itself a placeholder
 pub(crate) fn extract_limit_offset(
     limit_clause: &LimitClause,
-) -> ReadySetResult<Option<(usize, Option<ViewPlaceholder>)>> {
+) -> ReadySetResult<Option<(Option<usize>, Option<ViewPlaceholder>)>> {
     if limit_clause.limit().is_none() && limit_clause.offset().is_some() {
         unsupported!("ReadySet does not support OFFSET without LIMIT");
     }
@@ -840,12 +1064,12 @@ pub(crate) fn extract_limit_offset(
     };
 
     let limit = match limit {
-        Literal::UnsignedInteger(val) => *val,
-        Literal::Integer(val) => u64::try_from(*val)
-            .map_err(|_| unsupported_err!("LIMIT field cannot have a negative value"))?,
-        Literal::Placeholder(_) => {
-            unsupported!("ReadySet does not support parametrized LIMIT fields")
-        }
+        Literal::UnsignedInteger(val) => Some(*val),
+        Literal::Integer(val) => Some(
+            u64::try_from(*val)
+                .map_err(|_| unsupported_err!("LIMIT field cannot have a negative value"))?,
+        ),
+        Literal::Placeholder(_) => None,
         _ => unsupported!("Invalid LIMIT statement"),
     };
 
@@ -855,19 +1079,22 @@ pub(crate) fn extract_limit_offset(
         // For now, remove offset if it is a literal 0
         .filter(|offset| !matches!(offset, Literal::UnsignedInteger(0)))
         .map(|offset| -> ReadySetResult<ViewPlaceholder> {
-            match offset {
-                Literal::Placeholder(ItemPlaceholder::DollarNumber(idx)) => {
+            match (offset, limit) {
+                (Literal::Placeholder(ItemPlaceholder::DollarNumber(idx)), Some(limit)) => {
                     Ok(ViewPlaceholder::PageNumber {
                         offset_placeholder: *idx as _,
                         limit,
                     })
                 }
+                (_, None) => {
+                    unsupported!("ReadySet does not support OFFSET with a parametrized LIMIT")
+                }
                 _ => unsupported!("Numeric OFFSETs must be parametrized"),
             }
         })
         .transpose()?;
 
-    Ok(Some((limit as _, offset)))
+    Ok(Some((limit.map(|limit| limit as _), offset)))
 }
 
 fn table_expr_name(table_expr: &TableExpr) -> ReadySetResult<Relation> {
@@ -904,6 +1131,12 @@ fn default_row_for_select(st: &SelectStatement) -> Option<Vec<DfValue>> {
                     FunctionExpr::Max(..) => DfValue::None,
                     FunctionExpr::Min(..) => DfValue::None,
                     FunctionExpr::GroupConcat { .. } => DfValue::None,
+                    FunctionExpr::BitAnd(_) => DfValue::from(u64::MAX),
+                    FunctionExpr::BitOr(_) | FunctionExpr::BitXor(_) => DfValue::from(0u64),
+                    FunctionExpr::Stddev { .. }
+                    | FunctionExpr::Variance { .. }
+                    | FunctionExpr::BoolAnd(_)
+                    | FunctionExpr::BoolOr(_) => DfValue::None,
                     FunctionExpr::Call { .. } | FunctionExpr::Substring { .. } => DfValue::None,
                 },
                 _ => DfValue::None,
@@ -913,7 +1146,9 @@ fn default_row_for_select(st: &SelectStatement) -> Option<Vec<DfValue>> {
 }
 
 #[allow(clippy::cognitive_complexity)]
-pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
+pub fn to_query_graph(mut stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
+    let scalar_subqueries = extract_scalar_subqueries(&mut stmt)?;
+
     // a handy closure for making new relation nodes
     let new_node =
         |rel: Relation, preds: Vec<Expr>, st: &SelectStatement| -> ReadySetResult<QueryGraphNode> {
@@ -1019,6 +1254,13 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                 let rel = add_table_expr(table_expr)?;
                 if jc.operator.is_inner_join() {
                     inner_join_rels.insert(rel);
+                } else if jc.operator.is_right_join() || jc.operator.is_full_join() {
+                    // Everything on the left-hand side of a right or full join can be padded with
+                    // nulls, so can't be inner joined by predicates in the WHERE clause
+                    inner_join_rels.clear();
+                    if jc.operator.is_right_join() {
+                        inner_join_rels.insert(rel);
+                    }
                 }
             }
             JoinRightSide::Tables(_) => unsupported!("JoinRightSide::Tables not yet implemented"),
@@ -1053,7 +1295,7 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
         let left_table;
         let right_table;
 
-        let join_preds = match &jc.constraint {
+        let (join_preds, residual) = match &jc.constraint {
             JoinConstraint::On(cond) => {
                 use nom_sql::analysis::ReferredTables;
 
@@ -1062,8 +1304,7 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                 let mut tables_mentioned: Vec<Relation> =
                     cond.referred_tables().into_iter().collect();
 
-                let mut join_preds = vec![];
-                collect_join_predicates(cond.clone(), &mut join_preds)?;
+                let (mut join_preds, residual) = split_join_condition(cond);
 
                 if tables_mentioned.len() == 2 {
                     // tables can appear in any order in the join predicate, but
@@ -1077,10 +1318,13 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                     }
                     left_table = tables_mentioned.remove(0);
                     right_table = tables_mentioned.remove(0);
-                } else if tables_mentioned.len() == 1 {
-                    // just one table mentioned --> this is a self-join
-                    left_table = tables_mentioned.remove(0);
-                    right_table = left_table.clone();
+                } else if tables_mentioned.len() <= 1 {
+                    // The condition doesn't compare columns in two different tables (eg `ON
+                    // b.x = 1`), so it can only be evaluated as a residual predicate on a
+                    // cartesian product
+                    invariant!(join_preds.is_empty());
+                    left_table = prev_table.clone();
+                    right_table = rhs_relation.clone();
                 } else {
                     unsupported!("more than 2 tables mentioned in join condition!");
                 };
@@ -1105,7 +1349,7 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                     }
                 }
 
-                join_preds
+                (join_preds, residual)
             }
             JoinConstraint::Using(cols) => {
                 invariant_eq!(cols.len(), 1);
@@ -1115,31 +1359,56 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                 left_table = prev_table.clone();
                 right_table = rhs_relation.clone();
 
-                vec![JoinPredicate {
-                    left: col_expr(&left_table, &col.name),
-                    right: col_expr(&right_table, &col.name),
-                }]
+                (
+                    vec![JoinPredicate {
+                        left: col_expr(&left_table, &col.name),
+                        right: col_expr(&right_table, &col.name),
+                    }],
+                    None,
+                )
             }
             JoinConstraint::Empty => {
                 left_table = prev_table.clone();
                 right_table = rhs_relation.clone();
                 // An empty predicate indicates a cartesian product is expected
-                vec![]
+                (vec![], None)
             }
         };
 
         // add edge for join
         // FIXME(eta): inefficient cloning!
+        let (left_table, right_table, join_preds) = if jc.operator.is_right_join() {
+            // A right join is a left join with the sides swapped
+            let join_preds = join_preds
+                .into_iter()
+                .map(|JoinPredicate { left, right }| JoinPredicate {
+                    left: right,
+                    right: left,
+                })
+                .collect();
+            (right_table, left_table, join_preds)
+        } else {
+            (left_table, right_table, join_preds)
+        };
         if let std::collections::hash_map::Entry::Vacant(e) =
             edges.entry((left_table.clone(), right_table.clone()))
         {
             e.insert(match jc.operator {
-                JoinOperator::LeftJoin | JoinOperator::LeftOuterJoin => {
-                    QueryGraphEdge::LeftJoin { on: join_preds }
-                }
-                JoinOperator::Join | JoinOperator::InnerJoin => {
-                    QueryGraphEdge::Join { on: join_preds }
-                }
+                JoinOperator::LeftJoin
+                | JoinOperator::LeftOuterJoin
+                | JoinOperator::RightJoin
+                | JoinOperator::RightOuterJoin => QueryGraphEdge::LeftJoin {
+                    on: join_preds,
+                    residual,
+                },
+                JoinOperator::FullJoin | JoinOperator::FullOuterJoin => QueryGraphEdge::FullJoin {
+                    on: join_preds,
+                    residual,
+                },
+                JoinOperator::Join | JoinOperator::InnerJoin => QueryGraphEdge::Join {
+                    on: join_preds,
+                    residual,
+                },
                 _ => unsupported!("join operator not supported"),
             });
         }
@@ -1203,9 +1472,14 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                             l.table.clone().ok_or_else(|| no_table_for_col())?,
                             r.table.clone().ok_or_else(|| no_table_for_col())?,
                         ))
-                        .or_insert_with(|| QueryGraphEdge::Join { on: vec![] });
+                        .or_insert_with(|| QueryGraphEdge::Join {
+                            on: vec![],
+                            residual: None,
+                        });
                     match *e {
-                        QueryGraphEdge::Join { on: ref mut preds } => preds.push(jp.clone()),
+                        QueryGraphEdge::Join {
+                            on: ref mut preds, ..
+                        } => preds.push(jp.clone()),
                         _ => internal!("Expected join edge for join condition {:#?}", jp),
                     };
                 }
@@ -1236,16 +1510,53 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
         }
     }
 
+    // Expressions in the GROUP BY clause get projected as hidden columns before the grouped
+    // nodes, named after the expression itself
+    let mut group_by = HashSet::new();
+    let mut group_by_exprs = vec![];
+    for field in stmt.group_by.iter().flat_map(|gb| &gb.fields) {
+        match field {
+            FieldReference::Numeric(_) => {
+                internal!("Numeric field references should have been removed")
+            }
+            FieldReference::Expr(Expr::Column(c)) => {
+                group_by.insert(c.clone());
+            }
+            FieldReference::Expr(expr) => {
+                if contains_aggregate(expr) {
+                    invalid!("Aggregate functions are not allowed in GROUP BY")
+                }
+                // The expression is evaluated once per row, so a non-deterministic expression
+                // would put each row in a group of its own that no lookup could ever find again
+                if contains_nondeterministic(expr) {
+                    unsupported!("Non-deterministic expressions are not supported in GROUP BY")
+                }
+                // FIXME(ENG-2499): Use correct dialect.
+                let name: SqlIdentifier = expr.display(nom_sql::Dialect::MySQL).to_string().into();
+                if group_by.insert(Column {
+                    name: name.clone(),
+                    table: None,
+                }) {
+                    group_by_exprs.push((name, expr.clone()));
+                }
+            }
+        }
+    }
+
     // Add HAVING predicates and aggregates. Note that unlike below for selected columns, we don't
     // add any found aggregate functions in the HAVING clause to qg.columns, since we don't want to
     // necessarily return these in the query results.
     let mut aggregates = HashMap::new();
-    let having_predicates = if let Some(having_expr) = stmt.having.as_ref() {
+    let mut having_predicates = if let Some(having_expr) = stmt.having.as_ref() {
         extract_having_aggregates(having_expr, &mut aggregates)
     } else {
         vec![]
     };
+    for pred in having_predicates.iter_mut() {
+        replace_group_by_exprs(pred, &group_by_exprs);
+    }
 
+    let mut window_functions = vec![];
     let mut columns = Vec::with_capacity(stmt.fields.len());
     for field in stmt.fields.iter() {
         match field {
@@ -1257,6 +1568,18 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                     .clone()
                     // FIXME(ENG-2499): Use correct dialect.
                     .unwrap_or_else(|| expr.display(nom_sql::Dialect::MySQL).to_string().into());
+                if let Some((gb_name, _)) = group_by_exprs.iter().find(|(_, gbe)| gbe == expr) {
+                    // Like aggregates, GROUP BY expressions will have already been projected by
+                    // the time we project the result set columns
+                    columns.push(OutputColumn::Data {
+                        alias: name,
+                        column: Column {
+                            name: gb_name.clone(),
+                            table: None,
+                        },
+                    });
+                    continue;
+                }
                 match expr {
                     Expr::Literal(l) => columns.push(OutputColumn::Literal(LiteralColumn {
                         name,
@@ -1289,10 +1612,73 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                             },
                         })
                     }
+                    Expr::WindowFunction {
+                        function,
+                        partition_by,
+                        order_by,
+                    } => {
+                        // Window functions are evaluated after aggregates, so the window can
+                        // refer to aggregates as well as plain columns
+                        let mut window_column = |expr: &Expr| -> ReadySetResult<Column> {
+                            match expr {
+                                Expr::Column(c) => Ok(c.clone()),
+                                Expr::Call(function) if is_aggregate(function) => Ok(Column {
+                                    name: aggregates
+                                        .entry(function.clone())
+                                        .or_insert_with(|| {
+                                            // FIXME(ENG-2499): Use correct dialect.
+                                            function
+                                                .display(nom_sql::Dialect::MySQL)
+                                                .to_string()
+                                                .into()
+                                        })
+                                        .clone(),
+                                    table: None,
+                                }),
+                                _ => unsupported!(
+                                    "Only columns and aggregates are supported in the PARTITION \
+                                     BY and ORDER BY clauses of window functions"
+                                ),
+                            }
+                        };
+
+                        let partition_by = partition_by
+                            .iter()
+                            .map(&mut window_column)
+                            .collect::<ReadySetResult<Vec<_>>>()?;
+                        let order_by = order_by
+                            .iter()
+                            .map(|(expr, ot)| Ok((window_column(expr)?, *ot)))
+                            .collect::<ReadySetResult<Vec<_>>>()?;
+
+                        window_functions.push(WindowFunctionCall {
+                            function: function.clone(),
+                            partition_by,
+                            order_by,
+                            name: name.clone(),
+                        });
+                        // Like aggregates, the values for window functions will have already been
+                        // projected by the time we project the result set columns
+                        columns.push(OutputColumn::Data {
+                            alias: name.clone(),
+                            column: Column { name, table: None },
+                        })
+                    }
                     _ => {
+                        if expr
+                            .recursive_subexpressions()
+                            .any(|e| matches!(e, Expr::WindowFunction { .. }))
+                        {
+                            unsupported!(
+                                "Window functions are only supported as top-level expressions in \
+                                 the SELECT clause"
+                            );
+                        }
+
                         let mut expr = expr.clone();
                         let aggs = map_aggregates(&mut expr);
                         aggregates.extend(aggs);
+                        replace_group_by_exprs(&mut expr, &group_by_exprs);
 
                         columns.push(OutputColumn::Expr(ExprColumn {
                             name,
@@ -1305,24 +1691,6 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
         }
     }
 
-    let group_by = if let Some(group_by_clause) = &stmt.group_by {
-        group_by_clause
-            .fields
-            .iter()
-            .map(|f| match f {
-                FieldReference::Numeric(_) => {
-                    internal!("Numeric field references should have been removed")
-                }
-                FieldReference::Expr(Expr::Column(c)) => Ok(c.clone()),
-                FieldReference::Expr(_) => {
-                    unsupported!("Only column references are currently supported in GROUP BY")
-                }
-            })
-            .collect::<ReadySetResult<HashSet<_>>>()?
-    } else {
-        Default::default()
-    };
-
     if let Some(ref order) = stmt.order {
         // For each column in the `ORDER BY` clause, check if it needs to be projected
         order
@@ -1359,6 +1727,21 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
                         func.display(nom_sql::Dialect::MySQL).to_string().into()
                     });
                 }
+                FieldReference::Expr(expr) if group_by_exprs.iter().any(|(_, gbe)| gbe == expr) => {
+                    // This is an expression in the GROUP BY clause, which has already been
+                    // projected as a column named after the expression itself
+                    // FIXME(ENG-2499): Use correct dialect.
+                    let name: SqlIdentifier =
+                        expr.display(nom_sql::Dialect::MySQL).to_string().into();
+                    if !columns.iter().any(
+                        |e| matches!(e, OutputColumn::Data { column, .. } if column.name == name),
+                    ) {
+                        columns.push(OutputColumn::Data {
+                            alias: name.clone(),
+                            column: Column { name, table: None },
+                        })
+                    }
+                }
                 FieldReference::Expr(expr) => {
                     // This is an expression that we need to add to the list of projected columns
                     columns.push(OutputColumn::Expr(ExprColumn {
@@ -1434,6 +1817,43 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
         })
         .transpose()?;
 
+    // Range queries re-aggregate the results for each key in the range after the lookup, which
+    // can't be done with the standard deviation or variance themselves, so we also compute (but
+    // don't return) the aggregates they can be computed from
+    if relations
+        .values()
+        .flat_map(|rel| &rel.parameters)
+        .any(|param| param.op != BinaryOperator::Equal)
+    {
+        let variance_exprs = aggregates
+            .keys()
+            .filter_map(|func| match func {
+                FunctionExpr::Stddev { expr, .. } | FunctionExpr::Variance { expr, .. } => {
+                    Some(expr.as_ref().clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for func in variance_exprs.iter().flat_map(variance_helper_aggregates) {
+            let name = aggregates
+                .entry(func.clone())
+                .or_insert_with(|| {
+                    // FIXME(ENG-2499): Use correct dialect.
+                    func.display(nom_sql::Dialect::MySQL).to_string().into()
+                })
+                .clone();
+            if !columns
+                .iter()
+                .any(|c| matches!(c, OutputColumn::Data { column, .. } if column.name == name))
+            {
+                columns.push(OutputColumn::Data {
+                    alias: name.clone(),
+                    column: Column { name, table: None },
+                });
+            }
+        }
+    }
+
     // create initial join order
     let join_order = {
         let mut sorted_edges: Vec<(&(Relation, Relation), &QueryGraphEdge)> =
@@ -1456,6 +1876,9 @@ pub fn to_query_graph(stmt: SelectStatement) -> ReadySetResult<QueryGraph> {
         edges,
         aggregates,
         group_by,
+        group_by_exprs,
+        window_functions,
+        scalar_subqueries,
         columns,
         fields: stmt.fields.clone(),
         default_row: default_row_for_select(&stmt),
@@ -1551,6 +1974,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn group_by_expression() {
+        let qg = make_query_graph(
+            "SELECT year(t.ts) AS y, count(*) FROM t GROUP BY year(t.ts) HAVING year(t.ts) > 2000",
+        );
+        let expr = Expr::Call(FunctionExpr::Call {
+            name: "year".into(),
+            arguments: vec![Expr::Column("t.ts".into())],
+        });
+        let name: SqlIdentifier = expr.display(Dialect::MySQL).to_string().into();
+        let column = Column {
+            name: name.clone(),
+            table: None,
+        };
+
+        assert_eq!(qg.group_by_exprs, vec![(name, expr)]);
+        assert_eq!(qg.group_by, HashSet::from([column.clone()]));
+        assert_eq!(
+            qg.columns.first().unwrap(),
+            &OutputColumn::Data {
+                alias: "y".into(),
+                column: column.clone(),
+            }
+        );
+        assert_eq!(
+            qg.having_predicates,
+            vec![Expr::BinaryOp {
+                lhs: Box::new(Expr::Column(column)),
+                op: BinaryOperator::Greater,
+                rhs: Box::new(Expr::Literal(Literal::UnsignedInteger(2000)))
+            }]
+        );
+    }
+
+    #[test]
+    fn group_by_nondeterministic_expression() {
+        for sql in [
+            "SELECT count(*) FROM t GROUP BY RAND()",
+            "SELECT count(*) FROM t GROUP BY t.x + unix_timestamp(now())",
+        ] {
+            let query = parse_select_statement(Dialect::MySQL, sql).unwrap();
+            let err = to_query_graph(query).unwrap_err();
+            assert!(err.caused_by_unsupported(), "{err}");
+        }
+    }
+
+    #[test]
+    fn window_function() {
+        let qg = make_query_graph(
+            "SELECT t.x, row_number() OVER (PARTITION BY t.y ORDER BY t.x DESC) AS rn FROM t",
+        );
+        assert_eq!(
+            qg.window_functions,
+            vec![WindowFunctionCall {
+                function: FunctionExpr::Call {
+                    name: "row_number".into(),
+                    arguments: vec![]
+                },
+                partition_by: vec!["t.y".into()],
+                order_by: vec![("t.x".into(), OrderType::OrderDescending)],
+                name: "rn".into(),
+            }]
+        );
+        assert_eq!(
+            qg.columns,
+            vec![
+                OutputColumn::Data {
+                    alias: "x".into(),
+                    column: "t.x".into()
+                },
+                OutputColumn::Data {
+                    alias: "rn".into(),
+                    column: Column::from("rn")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn window_function_in_expression() {
+        let stmt = parse_select_statement(
+            Dialect::MySQL,
+            "SELECT t.x, row_number() OVER (ORDER BY t.x) + 1 FROM t",
+        )
+        .unwrap();
+        assert!(to_query_graph(stmt).is_err());
+    }
+
     #[test]
     fn having_predicates_and_aggregates() {
         let qg = make_query_graph("select t.x from t having t.x > 2;");
@@ -1681,6 +2192,194 @@ mod tests {
         assert_eq!(qg.global_predicates, vec![Expr::Literal(1u64.into())])
     }
 
+    #[test]
+    fn join_residual() {
+        let qg = make_query_graph(
+            "SELECT t1.x FROM t1 LEFT JOIN t2 ON t1.id = t2.id AND t1.ts >= t2.start AND t2.y = 1",
+        );
+        let col = |table: &str, name: &str| {
+            Box::new(Expr::Column(Column {
+                name: name.into(),
+                table: Some(table.into()),
+            }))
+        };
+        assert_eq!(
+            qg.edges[&("t1".into(), "t2".into())],
+            QueryGraphEdge::LeftJoin {
+                on: vec![JoinPredicate {
+                    left: *col("t1", "id"),
+                    right: *col("t2", "id"),
+                }],
+                residual: Some(Expr::BinaryOp {
+                    lhs: Box::new(Expr::BinaryOp {
+                        lhs: col("t1", "ts"),
+                        op: BinaryOperator::GreaterOrEqual,
+                        rhs: col("t2", "start"),
+                    }),
+                    op: BinaryOperator::And,
+                    rhs: Box::new(Expr::BinaryOp {
+                        lhs: col("t2", "y"),
+                        op: BinaryOperator::Equal,
+                        rhs: Box::new(Expr::Literal(1u64.into())),
+                    }),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn join_on_or() {
+        let qg = make_query_graph("SELECT t1.x FROM t1 JOIN t2 ON t1.x = t2.x OR t1.y = t2.y");
+        assert_eq!(
+            qg.edges[&("t1".into(), "t2".into())],
+            QueryGraphEdge::Join {
+                on: vec![],
+                residual: Some(
+                    parse_select_statement(
+                        Dialect::MySQL,
+                        "SELECT * FROM t1 WHERE t1.x = t2.x OR t1.y = t2.y"
+                    )
+                    .unwrap()
+                    .where_clause
+                    .unwrap()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn or_join_predicates_in_where() {
+        let qg = make_query_graph("SELECT t1.x FROM t1, t2 WHERE t1.x = t2.x OR t1.y = t2.y");
+        assert!(qg.edges.is_empty());
+        assert_eq!(qg.global_predicates.len(), 1);
+    }
+
+    #[test]
+    fn right_join() {
+        let qg = make_query_graph("SELECT t1.x FROM t1 RIGHT JOIN t2 ON t1.id = t2.id");
+        assert_eq!(qg.edges.len(), 1);
+        assert_eq!(
+            qg.edges[&("t2".into(), "t1".into())],
+            QueryGraphEdge::LeftJoin {
+                on: vec![JoinPredicate {
+                    left: Expr::Column("t2.id".into()),
+                    right: Expr::Column("t1.id".into()),
+                }],
+                residual: None,
+            }
+        );
+    }
+
+    #[test]
+    fn full_join() {
+        let qg = make_query_graph("SELECT t1.x FROM t1 FULL OUTER JOIN t2 ON t2.id = t1.id");
+        assert_eq!(
+            qg.edges[&("t1".into(), "t2".into())],
+            QueryGraphEdge::FullJoin {
+                on: vec![JoinPredicate {
+                    left: Expr::Column("t1.id".into()),
+                    right: Expr::Column("t2.id".into()),
+                }],
+                residual: None,
+            }
+        );
+    }
+
+    #[test]
+    fn where_predicates_after_full_join_are_not_join_keys() {
+        let qg = make_query_graph(
+            "SELECT t1.x FROM t3, t1 FULL JOIN t2 ON t1.id = t2.id WHERE t1.y = t3.y",
+        );
+        assert_eq!(qg.edges.len(), 1);
+        assert_eq!(qg.global_predicates.len(), 1);
+    }
+
+    #[test]
+    fn scalar_subquery_in_select() {
+        let qg = make_query_graph(
+            "SELECT t1.x, (SELECT count(t2.y) FROM t2 WHERE t2.x = t1.x) AS c FROM t1",
+        );
+        assert_eq!(qg.scalar_subqueries.len(), 1);
+        let (name, subquery) = &qg.scalar_subqueries[0];
+        assert_eq!(*name, "__scalar_subquery_0");
+        assert!(is_correlated(subquery));
+        assert_eq!(
+            qg.columns[1],
+            OutputColumn::Data {
+                alias: "c".into(),
+                column: Column::from("__scalar_subquery_0"),
+            }
+        );
+    }
+
+    #[test]
+    fn scalar_subquery_in_where() {
+        let qg = make_query_graph("SELECT t1.x FROM t1 WHERE t1.y > (SELECT max(t2.y) FROM t2)");
+        assert_eq!(qg.scalar_subqueries.len(), 1);
+        assert_eq!(
+            qg.global_predicates,
+            vec![Expr::BinaryOp {
+                lhs: Box::new(Expr::Column("t1.y".into())),
+                op: BinaryOperator::Greater,
+                rhs: Box::new(Expr::Column("__scalar_subquery_0".into())),
+            }]
+        );
+    }
+
+    #[test]
+    fn unsupported_scalar_subqueries() {
+        for query in [
+            "SELECT t1.x, (SELECT t2.y FROM t2) FROM t1",
+            "SELECT t1.x, (SELECT max(t2.y) FROM t2 GROUP BY t2.x) FROM t1",
+            "SELECT count(t1.x), (SELECT max(t2.y) FROM t2) FROM t1",
+            "SELECT t1.x FROM t1 GROUP BY t1.x HAVING count(*) > (SELECT max(t2.y) FROM t2)",
+        ] {
+            let stmt = parse_select_statement(Dialect::MySQL, query).unwrap();
+            assert!(to_query_graph(stmt).is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn parametrized_limit() {
+        let qg = make_query_graph("SELECT t.x FROM t WHERE t.y = $1 ORDER BY t.x LIMIT $2");
+        let pagination = qg.pagination.as_ref().unwrap();
+        assert_eq!(pagination.limit, None);
+        assert_eq!(pagination.offset, None);
+
+        let key = qg.view_key(&Default::default()).unwrap();
+        assert_eq!(
+            key.columns,
+            vec![(
+                mir::Column::new(Some("t"), "y"),
+                ViewPlaceholder::OneToOne(1, BinaryOperator::Equal)
+            )]
+        );
+    }
+
+    #[test]
+    fn parametrized_limit_with_offset() {
+        let stmt = parse_select_statement(
+            Dialect::MySQL,
+            "SELECT t.x FROM t WHERE t.y = $1 ORDER BY t.x LIMIT $2 OFFSET $3",
+        )
+        .unwrap();
+        assert!(to_query_graph(stmt).is_err());
+    }
+
+    #[test]
+    fn range_variance_computes_helper_aggregates() {
+        let x = Expr::Column("t.x".into());
+
+        let qg = make_query_graph("SELECT stddev_pop(t.x) FROM t WHERE t.y > $1");
+        for func in variance_helper_aggregates(&x) {
+            assert!(qg.aggregates.contains_key(&func), "missing {func:?}");
+        }
+        assert_eq!(qg.aggregates.len(), 4);
+
+        let qg = make_query_graph("SELECT stddev_pop(t.x) FROM t WHERE t.y = $1");
+        assert_eq!(qg.aggregates.len(), 1);
+    }
+
     mod view_key {
         use super::*;
 
@@ -1956,5 +2655,36 @@ mod tests {
                 ]
             );
         }
+
+        #[test]
+        fn paginated_range() {
+            let qg = make_query_graph(
+                "SELECT t.x FROM t WHERE t.x > $1 ORDER BY t.y ASC LIMIT 3 OFFSET $2",
+            );
+            let key = qg
+                .view_key(&mir::Config {
+                    allow_paginate: true,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            assert_eq!(key.index_type, IndexType::BTreeMap);
+            assert_eq!(
+                key.columns,
+                vec![
+                    (
+                        mir::Column::new(Some("t"), "x"),
+                        ViewPlaceholder::OneToOne(1, BinaryOperator::Greater)
+                    ),
+                    (
+                        mir::Column::named("__page_number"),
+                        ViewPlaceholder::PageNumber {
+                            offset_placeholder: 2,
+                            limit: 3,
+                        }
+                    )
+                ]
+            );
+        }
     }
 }
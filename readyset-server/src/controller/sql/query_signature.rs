@@ -75,9 +75,12 @@ impl Signature for QueryGraph {
 
         for e in self.edges.values() {
             match e {
-                QueryGraphEdge::Join { on } | QueryGraphEdge::LeftJoin { on } => {
+                QueryGraphEdge::Join { on, residual }
+                | QueryGraphEdge::LeftJoin { on, residual }
+                | QueryGraphEdge::FullJoin { on, residual } => {
                     on.iter()
                         .flat_map(|p| vec![&p.left, &p.right])
+                        .chain(residual)
                         .flat_map(|p| p.referred_columns())
                         .for_each(&mut record_column);
                 }
@@ -108,6 +111,10 @@ impl Signature for QueryGraph {
             c.hash(&mut hasher);
         }
 
+        // Scalar subqueries only show up in the rest of the query graph as references to the
+        // columns they were replaced with, so hash the subqueries themselves too
+        self.scalar_subqueries.hash(&mut hasher);
+
         QuerySignature {
             relations: rels,
             attributes: attrs,
@@ -61,10 +61,12 @@ impl Recipe {
                 name,
                 statement,
                 always,
+                options,
             } => SqlQuery::CreateCache(CreateCacheStatement {
                 name: Some(name.clone()),
                 inner: Ok(CacheInner::Statement(Box::new(statement.clone()))),
                 always: *always,
+                options: *options,
             }),
         });
         if expr.is_none() {
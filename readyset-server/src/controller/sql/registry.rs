@@ -3,8 +3,8 @@ use std::collections::{HashMap, HashSet};
 
 use nom_sql::analysis::visit::{self, Visitor};
 use nom_sql::{
-    CreateTableBody, CreateTableStatement, CreateViewStatement, ItemPlaceholder, Literal, Relation,
-    SelectSpecification, SelectStatement, SqlType,
+    CacheOptions, CreateTableBody, CreateTableStatement, CreateViewStatement, ItemPlaceholder,
+    Literal, Relation, SelectSpecification, SelectStatement, SqlType,
 };
 use readyset_client::PlaceholderIdx;
 use readyset_errors::{internal_err, unsupported_err, ReadySetError, ReadySetResult};
@@ -36,6 +36,8 @@ pub(crate) enum RecipeExpr {
         name: Relation,
         statement: SelectStatement,
         always: bool,
+        #[serde(default)]
+        options: CacheOptions,
     },
 }
 
@@ -732,6 +734,7 @@ mod tests {
                 statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table;")
                     .unwrap(),
                 always: false,
+                options: Default::default(),
             };
 
             assert_eq!(cached_query.name(), &query_name);
@@ -762,6 +765,7 @@ mod tests {
                 statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table;")
                     .unwrap(),
                 always: false,
+                options: Default::default(),
             };
 
             let cached_query_table_refs = cached_query.table_references();
@@ -806,6 +810,7 @@ mod tests {
                     name: "test_query".into(),
                     statement: statement.clone(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
             registry
@@ -813,6 +818,7 @@ mod tests {
                     name: "test_query_alias".into(),
                     statement,
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -823,6 +829,7 @@ mod tests {
                     name: "test_query".into(),
                     statement: statement.clone(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
             registry
@@ -830,6 +837,7 @@ mod tests {
                     name: "test_query_alias".into(),
                     statement,
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -866,6 +874,7 @@ mod tests {
                 )
                 .unwrap(),
                 always: false,
+                options: Default::default(),
             };
 
             assert!(registry.add_query(expr.clone()).unwrap());
@@ -883,6 +892,7 @@ mod tests {
                 statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table;")
                     .unwrap(),
                 always: false,
+                options: Default::default(),
             };
             assert!(!registry.add_query(expr).unwrap());
 
@@ -893,7 +903,8 @@ mod tests {
                     name: "test_query".into(),
                     statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table;")
                         .unwrap(),
-                    always: false
+                    always: false,
+                    options: Default::default(),
                 }
             );
         }
@@ -1032,7 +1043,8 @@ mod tests {
                     name: "test_query".into(),
                     statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table")
                         .unwrap(),
-                    always: false
+                    always: false,
+                    options: Default::default(),
                 }
             );
             assert!(registry.get(&"test_query_alias".into()).is_none())
@@ -1078,6 +1090,7 @@ mod tests {
                     name: "test".into(),
                     statement: stmt.clone(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
             assert!(registry.contains(&stmt))
@@ -1108,6 +1121,7 @@ mod tests {
                     statement: parse_select_statement(Dialect::MySQL, "SELECT * FROM test_table")
                         .unwrap(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -1194,7 +1208,8 @@ mod tests {
                 .add_query(RecipeExpr::Cache {
                     name: "foo".into(),
                     statement: query.clone(),
-                    always: false
+                    always: false,
+                    options: Default::default(),
                 })
                 .unwrap());
 
@@ -1350,6 +1365,7 @@ mod tests {
                     name: "test_query".into(),
                     statement: statement.clone(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -1372,6 +1388,7 @@ mod tests {
                     name: "alias".into(),
                     statement,
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -1405,6 +1422,7 @@ mod tests {
                     name: "query1".into(),
                     statement: statement1.clone(),
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -1413,6 +1431,7 @@ mod tests {
                     name: "query1_alias".into(),
                     statement: statement1,
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -1421,6 +1440,7 @@ mod tests {
                     name: "query2".into(),
                     statement: statement2,
                     always: false,
+                    options: Default::default(),
                 })
                 .unwrap();
 
@@ -18,16 +18,18 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::ops::Deref;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use array2::Array2;
 use common::IndexPair;
 use dataflow::prelude::{ChannelCoordinator, DomainIndex, DomainNodes, Graph, NodeIndex};
 use dataflow::{
-    DomainBuilder, DomainConfig, DomainRequest, NodeMap, Packet, PersistenceParameters, Sharding,
+    DomainBuilder, DomainConfig, DomainRequest, MovedDomainState, NodeMap, Packet,
+    PersistenceParameters, Sharding,
 };
+use failpoint_macros::set_failpoint;
 use futures::stream::{self, StreamExt, TryStreamExt};
-use futures::{FutureExt, TryStream};
+use futures::{Future, FutureExt, TryStream};
 use lazy_static::lazy_static;
 use metrics::{gauge, histogram};
 use nom_sql::{
@@ -37,7 +39,7 @@ use petgraph::visit::Bfs;
 use readyset_client::builders::{
     ReaderHandleBuilder, ReusedReaderHandleBuilder, TableBuilder, ViewBuilder,
 };
-use readyset_client::consensus::{Authority, AuthorityControl};
+use readyset_client::consensus::{Authority, AuthorityControl, NodeTypeSchedulingRestriction};
 use readyset_client::debug::info::GraphInfo;
 use readyset_client::debug::stats::{DomainStats, GraphStats, NodeStats};
 use readyset_client::internal::{MaterializationStatus, ReplicaAddress};
@@ -46,22 +48,24 @@ use readyset_client::recipe::changelist::{Change, ChangeList};
 use readyset_client::recipe::ExtendRecipeSpec;
 use readyset_client::replication::{ReplicationOffset, ReplicationOffsetState, ReplicationOffsets};
 use readyset_client::{
-    NodeSize, TableReplicationStatus, TableStatus, ViewCreateRequest, ViewFilter, ViewRequest,
-    ViewSchema,
+    failpoints, DomainMove, NodeSize, TableReplicationStatus, TableStatus, ViewCreateRequest,
+    ViewFilter, ViewRequest, ViewSchema,
 };
-use readyset_data::Dialect;
+use readyset_data::{DfValue, Dialect};
 use readyset_errors::{
-    internal, internal_err, invariant_eq, NodeType, ReadySetError, ReadySetResult,
+    internal, internal_err, invariant, invariant_eq, set_failpoint_return_err, NodeType,
+    ReadySetError, ReadySetResult,
 };
 use regex::Regex;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard};
-use tracing::{debug, error, instrument, trace, warn};
+use tracing::{debug, error, info, instrument, trace, warn};
 use vec1::Vec1;
 
 use super::migrate::DomainSettings;
-use super::replication::ReplicationStrategy;
+use super::rebalance::{self, DomainLoad};
+use super::replication::{active_replicas, ReplicationStrategy};
 use super::sql::Recipe;
 use crate::controller::domain_handle::DomainHandle;
 use crate::controller::migrate::materialization::Materializations;
@@ -72,7 +76,7 @@ use crate::controller::{
     schema, ControllerState, DomainPlacementRestriction, NodeRestrictionKey, Worker,
     WorkerIdentifier,
 };
-use crate::coordination::{DomainDescriptor, RunDomainResponse};
+use crate::coordination::{DomainDescriptor, RunDomainResponse, RunningDomain};
 use crate::internal::LocalNodeIndex;
 use crate::worker::WorkerRequestKind;
 
@@ -80,6 +84,14 @@ use crate::worker::WorkerRequestKind;
 /// for replication offsets)
 const CONCURRENT_REQUESTS: usize = 16;
 
+/// How long to wait for the partial readers of a domain that's being moved to another worker to
+/// be filled with the keys they had before the move, before stopping the old instance anyway
+const MOVE_PREFILL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often to check whether partial readers have finished replaying the keys they had before a
+/// restart or a move
+const READER_PREFILL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// This structure holds all the dataflow state.
 /// It's meant to be handled exclusively by the [`DfStateHandle`], which is the structure
 /// that guarantees thread-safe access to it.
@@ -132,6 +144,11 @@ pub struct DfState {
     /// State between migrations
     #[serde(with = "serde_with::rust::hashmap_as_tuple_list")]
     pub(super) remap: HashMap<DomainIndex, HashMap<NodeIndex, IndexPair>>,
+
+    /// The statistics of each domain as of the last time domains were rebalanced, used to work
+    /// out how much CPU each domain used since then
+    #[serde(skip)]
+    pub(super) last_domain_stats: HashMap<ReplicaAddress, DomainStats>,
 }
 
 impl DfState {
@@ -168,6 +185,7 @@ impl DfState {
             workers: Default::default(),
             remap: Default::default(),
             replication_strategy,
+            last_domain_stats: Default::default(),
         }
     }
 
@@ -585,26 +603,36 @@ impl DfState {
                     domain_index: node.domain().index(),
                 })?;
 
-        invariant_eq!(
-            domain.num_replicas(),
-            1,
-            "Base table domains can't be replicated"
-        );
-
-        let txs = (0..domain.num_shards())
-            .map(|shard| {
-                let replica_addr = ReplicaAddress {
-                    domain_index: node.domain(),
-                    shard,
-                    replica: 0, // Base tables can't currently be replicated
-                };
-                self.channel_coordinator
-                    .get_addr(&replica_addr)
-                    .ok_or_else(|| {
-                        internal_err!("failed to get channel coordinator for {}", replica_addr)
+        // Replica 0 of each shard of a base table domain is the primary, and any other replicas are
+        // hot standbys which the replicator needs to send all the same writes to
+        let mut replica_txs = (0..domain.num_replicas())
+            .map(|replica| {
+                (0..domain.num_shards())
+                    .map(|shard| {
+                        let replica_addr = ReplicaAddress {
+                            domain_index: node.domain(),
+                            shard,
+                            replica,
+                        };
+                        self.channel_coordinator
+                            .get_addr(&replica_addr)
+                            .ok_or_else(|| {
+                                internal_err!(
+                                    "failed to get channel coordinator for {}",
+                                    replica_addr
+                                )
+                            })
                     })
+                    .collect::<ReadySetResult<Vec<_>>>()
             })
             .collect::<ReadySetResult<Vec<_>>>()?;
+        invariant!(!replica_txs.is_empty(), "Base table domain has no replicas");
+        let txs = replica_txs.remove(0);
+        let standby_txs = replica_txs
+            .into_iter()
+            .enumerate()
+            .map(|(i, txs)| (i + 1, txs))
+            .collect();
 
         let base_operator = node
             .get_base()
@@ -642,6 +670,7 @@ impl DfState {
 
         Ok(Some(TableBuilder {
             txs,
+            standby_txs,
             ni: node.global_addr(),
             addr: node.local_addr(),
             key,
@@ -865,6 +894,56 @@ impl DfState {
             .collect())
     }
 
+    /// Returns a future that waits until the partial readers in the given domains have finished
+    /// replaying the keys they had before a restart or a move, or until `timeout` has passed, so
+    /// that caches aren't cold when they start serving traffic.
+    ///
+    /// The future only holds on to copies of the handles it needs to query the domains, so it
+    /// doesn't need to be awaited while holding a lock on the dataflow state.
+    pub(super) fn wait_for_reader_prefills(
+        &self,
+        domains: &[DomainIndex],
+        timeout: Duration,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let handles = domains
+            .iter()
+            .filter_map(|di| self.domains.get(di).cloned())
+            .collect::<Vec<_>>();
+        let workers = self.workers.clone();
+        async move {
+            let start = Instant::now();
+            loop {
+                let pending = stream::iter(&handles)
+                    .map(|handle| {
+                        handle.send_to_healthy::<usize>(
+                            DomainRequest::PendingReaderPrefills,
+                            &workers,
+                        )
+                    })
+                    .buffer_unordered(CONCURRENT_REQUESTS)
+                    .try_fold(0, |acc, pending| async move {
+                        Ok(acc + pending.into_iter().flatten().sum::<usize>())
+                    })
+                    .await;
+                match pending {
+                    Ok(0) => return,
+                    Ok(pending) if start.elapsed() >= timeout => {
+                        warn!(%pending, "Timed out waiting for caches to be prefilled");
+                        return;
+                    }
+                    Ok(pending) => {
+                        debug!(%pending, "Waiting for caches to be prefilled");
+                        tokio::time::sleep(READER_PREFILL_POLL_INTERVAL).await;
+                    }
+                    Err(error) => {
+                        warn!(%error, "Could not check whether caches are prefilled");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns a list of all table names that are currently involved in snapshotting.
     pub(super) async fn snapshotting_tables(&self) -> ReadySetResult<HashSet<Relation>> {
         let domains = self.domains_with_base_tables().await?;
@@ -974,6 +1053,7 @@ impl DfState {
         idx: DomainIndex,
         shard_replica_workers: Array2<WorkerIdentifier>,
         nodes: Vec<NodeIndex>,
+        moved_from: Option<MovedDomainState>,
     ) -> ReadySetResult<DomainHandle> {
         // Reader nodes are always assigned to their own domains, so it's good enough to see
         // if any of its nodes is a reader.
@@ -1026,6 +1106,7 @@ impl DfState {
                     config: self.domain_config.clone(),
                     nodes: domain_nodes.clone(),
                     persistence_parameters: self.persistence.clone(),
+                    moved_from: moved_from.clone(),
                 };
 
                 let w = self
@@ -1058,7 +1139,12 @@ impl DfState {
                     #[allow(clippy::indexing_slicing)] // checked above
                     let node = &self.ingredients[*n];
 
-                    if node.is_base() && w.domain_scheduling_config.volume_id.is_some() {
+                    // Other replicas of base table domains are hot standbys, which don't need to
+                    // be restricted to the volume holding the primary's data
+                    if replica == 0
+                        && node.is_base()
+                        && w.domain_scheduling_config.volume_id.is_some()
+                    {
                         new_domain_restrictions.push((
                             node.name().to_owned(),
                             shard,
@@ -1221,6 +1307,7 @@ impl DfState {
                     .send_to_healthy::<()>(
                         DomainRequest::Packet(Packet::Evict {
                             node: Some(na),
+                            priority: None,
                             num_bytes: bytes as usize,
                         }),
                         workers,
@@ -1331,6 +1418,887 @@ impl DfState {
         .await
     }
 
+    /// Replace replicas of base table domains that were running on `failed` workers with their
+    /// hot standby replicas.
+    ///
+    /// For each domain containing base tables with replicas on any of the `failed` workers, if
+    /// every shard of the domain still has a replica on a healthy worker, the remaining
+    /// replicas of each shard are renumbered in order (promoting a standby to be the new first
+    /// replica if the first replica failed), and all workers are told about the new addresses
+    /// of the renumbered replicas. Any other domains with replicas on `failed` workers are left
+    /// as-is, to be recovered from scratch.
+    ///
+    /// Returns the domains in which a standby was promoted. A failed first replica may have
+    /// applied writes to its base tables that it hadn't yet sent downstream, so the caller needs
+    /// to recreate all the domains downstream of those (see [`DfState::downstream_domains`]) to
+    /// replay them from the promoted replica.
+    pub(super) async fn promote_standbys(
+        &mut self,
+        failed: &HashSet<WorkerIdentifier>,
+    ) -> ReadySetResult<HashSet<DomainIndex>> {
+        let mut promoted = HashSet::new();
+        let mut domain_addresses = vec![];
+
+        let domains = self
+            .domains
+            .iter()
+            .filter(|(_, handle)| failed.iter().any(|wi| handle.is_assigned_to_worker(wi)))
+            .map(|(domain_index, handle)| (*domain_index, handle.healthy_replicas(failed)))
+            .collect::<Vec<_>>();
+        for (domain_index, shards) in domains {
+            if !self.is_base_table_domain(domain_index) {
+                continue;
+            }
+
+            let Some(shards) = shards else {
+                if self
+                    .domains
+                    .get(&domain_index)
+                    .map_or(false, |handle| handle.num_replicas() > 1)
+                {
+                    warn!(
+                        domain = %domain_index,
+                        "No healthy standby replicas to promote for base table domain"
+                    );
+                }
+                continue;
+            };
+
+            if shards
+                .iter()
+                .any(|replicas| replicas.first().map_or(false, |(replica, _)| *replica != 0))
+            {
+                promoted.insert(domain_index);
+            }
+            domain_addresses.extend(self.renumber_replicas(domain_index, shards).await?);
+        }
+
+        self.gossip_domain_addresses(domain_addresses).await;
+
+        Ok(promoted)
+    }
+
+    /// Stop using the hot standby replica of the domain of the base table `table` whose shards
+    /// run at `addrs`, because the replicator failed to write to it.
+    ///
+    /// The standby will have missed writes, so it's stopped rather than ever being promoted, and
+    /// the remaining replicas of the domain are renumbered in order. Does nothing if no standby
+    /// of the table runs at `addrs` (eg because it has already been removed).
+    pub(super) async fn lose_standby_replica(
+        &mut self,
+        table: &Relation,
+        addrs: &[SocketAddr],
+    ) -> ReadySetResult<()> {
+        let ni = self
+            .recipe
+            .node_addr_for(table)
+            .map_err(|_| ReadySetError::TableNotFound {
+                name: table.name.clone().into(),
+                schema: table.schema.clone().map(Into::into),
+            })?;
+        let domain_index = self
+            .ingredients
+            .node_weight(ni)
+            .ok_or_else(|| ReadySetError::NodeNotFound { index: ni.index() })?
+            .domain();
+        let Some(handle) = self.domains.get(&domain_index) else {
+            return Ok(());
+        };
+
+        // Replica 0 is never a standby, so we never remove it here: if it's running at `addrs`,
+        // the standby the replicator failed to write to has since been promoted and the replicator
+        // will find out about the failure of the old primary when it next writes to it.
+        let Some(lost) = (1..handle.num_replicas()).find(|replica| {
+            (0..handle.num_shards()).any(|shard| {
+                self.channel_coordinator
+                    .get_addr(&ReplicaAddress {
+                        domain_index,
+                        shard,
+                        replica: *replica,
+                    })
+                    .map_or(false, |addr| addrs.contains(&addr))
+            })
+        }) else {
+            debug!(
+                table = %table.display_unquoted(),
+                ?addrs,
+                "Lost standby replica is no longer running"
+            );
+            return Ok(());
+        };
+        warn!(
+            table = %table.display_unquoted(),
+            domain = %domain_index,
+            replica = lost,
+            "Removing hot standby replica which missed writes"
+        );
+
+        let mut to_kill: HashMap<WorkerIdentifier, Vec<ReplicaAddress>> = HashMap::new();
+        let shards: Vec<Vec<_>> = handle
+            .shards()
+            .enumerate()
+            .map(|(shard, replicas)| {
+                replicas
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .filter(|(replica, worker)| {
+                        if *replica != lost {
+                            return true;
+                        }
+                        to_kill
+                            .entry(worker.clone())
+                            .or_default()
+                            .push(ReplicaAddress {
+                                domain_index,
+                                shard,
+                                replica: lost,
+                            });
+                        false
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for (worker, replica_addresses) in to_kill {
+            if let Some(w) = self.workers.get(&worker) {
+                if let Err(e) = w
+                    .rpc::<()>(WorkerRequestKind::KillDomains(replica_addresses))
+                    .await
+                {
+                    error!(%worker, %e, "Worker could not be reached to stop lost standby");
+                }
+            }
+        }
+
+        let domain_addresses = self.renumber_replicas(domain_index, shards).await?;
+        self.gossip_domain_addresses(domain_addresses).await;
+
+        Ok(())
+    }
+
+    /// Returns true if the given domain contains any base table nodes
+    #[allow(clippy::indexing_slicing)] // domain_nodes contains valid node indices
+    fn is_base_table_domain(&self, domain_index: DomainIndex) -> bool {
+        self.domain_nodes
+            .get(&domain_index)
+            .into_iter()
+            .flat_map(|nodes| nodes.values())
+            .any(|ni| self.ingredients[*ni].is_base())
+    }
+
+    /// Renumber the replicas of each shard of the base table domain `domain_index` to the
+    /// replicas in `shards` (given as pairs of old replica index and worker, in replica order),
+    /// telling each worker running a renumbered replica about its new replica index.
+    ///
+    /// Returns the new addresses of the renumbered replicas, which need to be gossiped to all
+    /// workers.
+    async fn renumber_replicas(
+        &mut self,
+        domain_index: DomainIndex,
+        shards: Vec<Vec<(usize, WorkerIdentifier)>>,
+    ) -> ReadySetResult<Vec<DomainDescriptor>> {
+        #[allow(clippy::indexing_slicing)] // domain_nodes contains valid node indices
+        let base_nodes = self
+            .domain_nodes
+            .get(&domain_index)
+            .into_iter()
+            .flat_map(|nodes| nodes.values())
+            .filter(|ni| self.ingredients[**ni].is_base())
+            .map(|ni| self.ingredients[*ni].name().clone())
+            .collect::<Vec<_>>();
+
+        let mut domain_addresses = vec![];
+        let mut new_domain_restrictions = vec![];
+        for (shard, replicas) in shards.iter().enumerate() {
+            for (new_replica, (replica, worker_id)) in replicas.iter().enumerate() {
+                if new_replica == *replica {
+                    continue;
+                }
+
+                let replica_address = ReplicaAddress {
+                    domain_index,
+                    shard,
+                    replica: *replica,
+                };
+                let new_address = ReplicaAddress {
+                    replica: new_replica,
+                    ..replica_address
+                };
+                if new_replica == 0 {
+                    info!(%replica_address, worker = %worker_id, "promoting standby replica");
+                } else {
+                    debug!(%replica_address, %new_address, "renumbering standby replica");
+                }
+
+                let w = self
+                    .workers
+                    .get(worker_id)
+                    .ok_or_else(|| ReadySetError::WorkerFailed {
+                        uri: worker_id.clone(),
+                    })?;
+                w.rpc::<()>(WorkerRequestKind::SetReplica {
+                    replica_address,
+                    replica: new_replica,
+                })
+                .await?;
+
+                let socket_addr = self
+                    .channel_coordinator
+                    .get_addr(&replica_address)
+                    .ok_or_else(|| ReadySetError::NoSuchReplica {
+                        domain_index: domain_index.index(),
+                        shard,
+                        replica: *replica,
+                    })?;
+                self.channel_coordinator
+                    .insert_remote(new_address, socket_addr)?;
+                domain_addresses.push(DomainDescriptor::new(new_address, socket_addr));
+
+                if new_replica == 0 && w.domain_scheduling_config.volume_id.is_some() {
+                    for node_name in &base_nodes {
+                        new_domain_restrictions.push((
+                            node_name.clone(),
+                            shard,
+                            DomainPlacementRestriction {
+                                worker_volume: w.domain_scheduling_config.volume_id.clone(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Forget the addresses of the replica indices that are no longer in use
+        let old_num_replicas = self
+            .domains
+            .get(&domain_index)
+            .map_or(0, |handle| handle.num_replicas());
+        let num_replicas = shards.first().map_or(0, Vec::len);
+        for shard in 0..shards.len() {
+            for replica in num_replicas..old_num_replicas {
+                self.channel_coordinator.remove(&ReplicaAddress {
+                    domain_index,
+                    shard,
+                    replica,
+                })?;
+            }
+        }
+
+        for (node_name, shard, restrictions) in new_domain_restrictions {
+            self.set_domain_placement_local(node_name, shard, restrictions);
+        }
+
+        self.domains.insert(
+            domain_index,
+            DomainHandle::new(
+                domain_index,
+                Array2::from_rows(
+                    shards
+                        .into_iter()
+                        .map(|replicas| replicas.into_iter().map(|(_, wi)| wi).collect())
+                        .collect(),
+                ),
+            ),
+        );
+
+        Ok(domain_addresses)
+    }
+
+    /// Tell all workers about the given (new) addresses of domain replicas
+    async fn gossip_domain_addresses(&mut self, domain_addresses: Vec<DomainDescriptor>) {
+        if domain_addresses.is_empty() {
+            return;
+        }
+
+        for (address, w) in self.workers.iter_mut() {
+            debug!(worker_uri = %w.uri, "informing worker about new domain addresses");
+            if let Err(e) = w
+                .rpc::<()>(WorkerRequestKind::GossipDomainInformation(
+                    domain_addresses.clone(),
+                ))
+                .await
+            {
+                error!(
+                    %address,
+                    error = ?e,
+                    "Worker could not be reached and will be ignored",
+                );
+            }
+        }
+    }
+
+    /// Returns all the domains downstream of any of the domains in `roots`, not including the
+    /// domains in `roots` themselves unless they're downstream of another domain in `roots`.
+    pub(super) fn downstream_domains<I>(&self, roots: I) -> HashSet<DomainIndex>
+    where
+        I: IntoIterator<Item = DomainIndex>,
+    {
+        let mut downstream = HashSet::new();
+        let mut queue = roots.into_iter().collect::<Vec<_>>();
+        while let Some(domain_index) = queue.pop() {
+            for ni in self
+                .domain_nodes
+                .get(&domain_index)
+                .into_iter()
+                .flat_map(|nodes| nodes.values())
+            {
+                for child in self
+                    .ingredients
+                    .neighbors_directed(*ni, petgraph::EdgeDirection::Outgoing)
+                {
+                    #[allow(clippy::indexing_slicing)] // child comes from the graph
+                    let child_domain = self.ingredients[child].domain();
+                    if child_domain != domain_index && downstream.insert(child_domain) {
+                        queue.push(child_domain);
+                    }
+                }
+            }
+        }
+        downstream
+    }
+
+    /// Stops all the replicas of the given domains and forgets about them, as if the workers
+    /// running them had failed.
+    ///
+    /// Returns the nodes of the stopped domains, to be passed to [`DfState::recover`].
+    pub(super) async fn kill_domains(
+        &mut self,
+        domains: &HashSet<DomainIndex>,
+    ) -> HashMap<DomainIndex, HashSet<NodeIndex>> {
+        let mut to_kill: HashMap<WorkerIdentifier, Vec<ReplicaAddress>> = HashMap::new();
+        let mut domain_nodes = HashMap::new();
+        for domain_index in domains {
+            let Some(handle) = self.domains.remove(domain_index) else {
+                continue;
+            };
+            for (shard, replicas) in handle.shards().enumerate() {
+                for (replica, worker) in replicas.iter().enumerate() {
+                    to_kill
+                        .entry(worker.clone())
+                        .or_default()
+                        .push(ReplicaAddress {
+                            domain_index: *domain_index,
+                            shard,
+                            replica,
+                        });
+                }
+            }
+
+            let nodes = self
+                .domain_nodes
+                .get(domain_index)
+                .into_iter()
+                .flat_map(|nodes| nodes.values().copied())
+                .collect::<HashSet<_>>();
+            self.materializations.remove_nodes(&nodes);
+            domain_nodes.insert(*domain_index, nodes);
+        }
+
+        for (worker, replica_addresses) in to_kill {
+            if let Some(w) = self.workers.get(&worker) {
+                if let Err(e) = w
+                    .rpc::<()>(WorkerRequestKind::KillDomains(replica_addresses))
+                    .await
+                {
+                    error!(%worker, %e, "Worker could not be reached to stop its domains");
+                }
+            }
+        }
+
+        domain_nodes
+    }
+
+    /// Takes over the domains in `running`, which workers reported as having been started by a
+    /// previous controller, wherever they match the persisted dataflow state, and stops all the
+    /// others.
+    ///
+    /// Also rebuilds the materialization state, which isn't persisted, for all the nodes in the
+    /// graph.
+    ///
+    /// Returns the nodes of the domains which need to be recreated, to be passed to
+    /// [`DfState::recover`]. These are the domains which aren't running in their entirety on the
+    /// current workers or were started with different nodes, configuration or materializations,
+    /// along with all the domains downstream of those (which would otherwise receive their state
+    /// again once it's replayed).
+    pub(super) async fn adopt_running_domains(
+        &mut self,
+        running: HashMap<WorkerIdentifier, Vec<RunningDomain>>,
+    ) -> ReadySetResult<HashMap<DomainIndex, HashSet<NodeIndex>>> {
+        let mut running_replicas: HashMap<DomainIndex, Vec<(WorkerIdentifier, RunningDomain)>> =
+            HashMap::new();
+        for (worker, domains) in running {
+            // ignore any workers which have failed since they told us about their domains
+            if !self.workers.contains_key(&worker) {
+                continue;
+            }
+            for domain in domains {
+                running_replicas
+                    .entry(domain.descriptor.domain_index())
+                    .or_default()
+                    .push((worker.clone(), domain));
+            }
+        }
+
+        // Rebuild the materialization state for all the nodes as if they'd been planned by a
+        // previous migration, so that we can check it against the state prepared by the running
+        // domains
+        let all_nodes = self
+            .domain_nodes
+            .values()
+            .flat_map(|nodes| nodes.values().copied())
+            .collect();
+        self.materializations
+            .extend(&mut self.ingredients, &all_nodes)?;
+
+        let mut adopted = HashMap::new();
+        let mut to_recover = HashSet::new();
+        for (domain_index, nodes) in &self.domain_nodes {
+            let nodes = nodes.values().copied().collect::<Vec<_>>();
+            match running_replicas
+                .get(domain_index)
+                .and_then(|replicas| self.running_domain_placement(&nodes, replicas))
+            {
+                Some(shards) => {
+                    adopted.insert(*domain_index, shards);
+                }
+                None => {
+                    to_recover.insert(*domain_index);
+                }
+            }
+        }
+
+        for domain_index in self.downstream_domains(to_recover.clone()) {
+            if adopted.remove(&domain_index).is_some() {
+                to_recover.insert(domain_index);
+            }
+        }
+
+        let mut domain_addresses = vec![];
+        for (domain_index, shards) in adopted {
+            info!(domain = %domain_index, "taking over running domain");
+            for (_, domain) in running_replicas.remove(&domain_index).into_iter().flatten() {
+                self.channel_coordinator.insert_remote(
+                    domain.descriptor.replica_address(),
+                    domain.descriptor.socket_address(),
+                )?;
+                domain_addresses.push(domain.descriptor);
+            }
+            self.domains
+                .insert(domain_index, DomainHandle::new(domain_index, shards));
+        }
+
+        // stop whatever's left, including replicas of any domains we don't know about at all
+        let mut to_kill: HashMap<WorkerIdentifier, Vec<ReplicaAddress>> = HashMap::new();
+        for (worker, domain) in running_replicas.into_values().flatten() {
+            to_kill
+                .entry(worker)
+                .or_default()
+                .push(domain.descriptor.replica_address());
+        }
+        for (worker, replica_addresses) in to_kill {
+            if let Some(w) = self.workers.get(&worker) {
+                if let Err(e) = w
+                    .rpc::<()>(WorkerRequestKind::KillDomains(replica_addresses))
+                    .await
+                {
+                    error!(%worker, %e, "Worker could not be reached to stop its domains");
+                }
+            }
+        }
+
+        let domain_nodes = to_recover
+            .into_iter()
+            .map(|domain_index| {
+                let nodes = self
+                    .domain_nodes
+                    .get(&domain_index)
+                    .into_iter()
+                    .flat_map(|nodes| nodes.values().copied())
+                    .collect::<HashSet<_>>();
+                (domain_index, nodes)
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Keep the rebuilt materialization state for the domains we took over, and forget it for
+        // the nodes we're about to recreate, just as if the workers running them had failed
+        self.materializations.assume_committed();
+        for nodes in domain_nodes.values() {
+            self.materializations.remove_nodes(nodes);
+        }
+
+        if !domain_addresses.is_empty() {
+            for (address, w) in self.workers.iter_mut() {
+                if let Err(e) = w
+                    .rpc::<()>(WorkerRequestKind::GossipDomainInformation(
+                        domain_addresses.clone(),
+                    ))
+                    .await
+                {
+                    error!(
+                        %address,
+                        error = ?e,
+                        "Worker could not be reached and will be ignored",
+                    );
+                }
+            }
+        }
+
+        Ok(domain_nodes)
+    }
+
+    /// Given all the replicas reported as running for a domain with the given nodes, returns the
+    /// worker running each replica of each shard of that domain (indexed by shard index first and
+    /// replica index second), if those replicas are exactly what we'd schedule for the domain.
+    ///
+    /// Must be called after the materializations for the nodes have been rebuilt with
+    /// [`Materializations::extend`], but before they're committed.
+    #[allow(clippy::indexing_slicing)] // nodes come from domain_nodes
+    fn running_domain_placement(
+        &self,
+        nodes: &[NodeIndex],
+        replicas: &[(WorkerIdentifier, RunningDomain)],
+    ) -> Option<Array2<WorkerIdentifier>> {
+        let num_shards = self.ingredients[*nodes.first()?]
+            .sharded_by()
+            .shards()
+            .unwrap_or(1);
+        let num_replicas = self
+            .replication_strategy
+            .replicate_domain(&self.ingredients, nodes);
+        if replicas.len() != num_shards * num_replicas {
+            return None;
+        }
+
+        let expected_nodes = nodes.iter().collect::<HashSet<_>>();
+        // Base tables get their state when they're added rather than through `PrepareState`, so
+        // we only check the materializations of other nodes
+        let expected_materializations = nodes
+            .iter()
+            .filter(|ni| !self.ingredients[**ni].is_base())
+            .filter_map(|ni| {
+                let state = self
+                    .materializations
+                    .planned_state(&self.ingredients, *ni)?;
+                Some((*ni, state))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut shards = vec![vec![None; num_replicas]; num_shards];
+        for (worker, domain) in replicas {
+            let replica_address = domain.descriptor.replica_address();
+            let assignment = shards
+                .get_mut(replica_address.shard)?
+                .get_mut(replica_address.replica)?;
+            if assignment.is_some() || domain.nodes.iter().collect::<HashSet<_>>() != expected_nodes
+            {
+                return None;
+            }
+            if domain.config != self.domain_config {
+                warn!(
+                    replica = %replica_address,
+                    "running domain was started with a different configuration"
+                );
+                return None;
+            }
+            let materializations = domain
+                .materializations
+                .iter()
+                .filter(|(ni, _)| !self.ingredients[**ni].is_base())
+                .map(|(ni, state)| (*ni, state.clone()))
+                .collect::<HashMap<_, _>>();
+            if materializations != expected_materializations {
+                warn!(
+                    replica = %replica_address,
+                    "running domain has different materializations than planned"
+                );
+                return None;
+            }
+            *assignment = Some(worker.clone());
+        }
+
+        Some(Array2::from_rows(
+            shards
+                .into_iter()
+                .map(|replicas| replicas.into_iter().collect::<Option<Vec<_>>>())
+                .collect::<Option<Vec<_>>>()?,
+        ))
+    }
+
+    /// Plans which domains to move between the healthy workers to even out the memory and CPU they
+    /// use (see [`rebalance::plan_moves`]), and returns the moves to make, in order. Each move is
+    /// then made with [`DfState::move_domain`].
+    #[allow(clippy::indexing_slicing)] // nodes come from domain_nodes
+    pub(super) async fn plan_rebalance(&mut self) -> ReadySetResult<Vec<DomainMove>> {
+        let stats = self.get_statistics().await?;
+        let workers = self
+            .workers
+            .iter()
+            .filter(|(_, w)| w.healthy)
+            .map(|(wi, _)| wi.clone())
+            .collect::<Vec<_>>();
+
+        let mut loads = Vec::with_capacity(stats.domains.len());
+        for (replica_address, (domain_stats, node_stats)) in stats.domains.iter() {
+            let Some(handle) = self.domains.get(&replica_address.domain_index) else {
+                continue;
+            };
+            let worker = handle.assignment(replica_address.shard, replica_address.replica)?;
+            let memory = node_stats.values().map(|n| n.mem_size).sum();
+            loads.push(DomainLoad::new(
+                replica_address.domain_index,
+                worker.clone(),
+                domain_stats,
+                self.last_domain_stats.get(replica_address),
+                memory,
+            ));
+        }
+        self.last_domain_stats = stats
+            .domains
+            .into_iter()
+            .map(|(replica_address, (domain_stats, _))| (replica_address, domain_stats))
+            .collect();
+
+        let movable = self
+            .domains
+            .values()
+            .filter(|handle| handle.num_shards() == 1 && handle.num_replicas() == 1)
+            .map(|handle| handle.index())
+            .filter(|domain_index| self.is_movable(*domain_index))
+            .collect::<HashSet<_>>();
+        let reader_domains = movable
+            .iter()
+            .copied()
+            .filter(|domain_index| {
+                self.domain_nodes.get(domain_index).map_or(false, |nodes| {
+                    nodes.values().any(|ni| self.ingredients[*ni].is_reader())
+                })
+            })
+            .collect::<HashSet<_>>();
+
+        let moves = rebalance::plan_moves(&workers, &loads, |load, to| {
+            if !movable.contains(&load.domain_index) {
+                return false;
+            }
+            let is_reader_domain = reader_domains.contains(&load.domain_index);
+            self.workers
+                .get(to)
+                .map_or(false, |w| match w.domain_scheduling_config.reader_nodes {
+                    NodeTypeSchedulingRestriction::None => true,
+                    NodeTypeSchedulingRestriction::OnlyWithNodeType => is_reader_domain,
+                    NodeTypeSchedulingRestriction::NeverWithNodeType => !is_reader_domain,
+                })
+        });
+
+        Ok(moves
+            .into_iter()
+            .map(|(domain_index, from, to)| DomainMove {
+                domain_index,
+                from,
+                to,
+            })
+            .collect())
+    }
+
+    /// Returns true if the given domain can be moved to another worker while it's running, which
+    /// is the case if it doesn't contain any base tables and doesn't send anything to any other
+    /// domains.
+    ///
+    /// Note that this doesn't check whether the domain is sharded or replicated, which also keeps
+    /// it from being moved (see [`DfState::move_domain`]).
+    #[allow(clippy::indexing_slicing)] // nodes come from domain_nodes
+    fn is_movable(&self, domain_index: DomainIndex) -> bool {
+        let Some(nodes) = self.domain_nodes.get(&domain_index) else {
+            return false;
+        };
+        nodes.values().all(|ni| {
+            !self.ingredients[*ni].is_base()
+                && self
+                    .ingredients
+                    .neighbors_directed(*ni, petgraph::EdgeDirection::Outgoing)
+                    .all(|child| self.ingredients[child].domain() == domain_index)
+        })
+    }
+
+    /// Moves the (unsharded and unreplicated) domain with the given index from the worker `from`
+    /// to the worker `to`.
+    ///
+    /// Only domains with a single shard and a single replica can be moved, since the domain is
+    /// recreated from scratch on its new worker.
+    ///
+    /// The old instance of the domain is retired rather than stopped while the new instance is
+    /// started and its state is replayed, so that it keeps serving reads until the new instance
+    /// is ready. Once the other domains are told about the new instance, the old instance stops
+    /// receiving updates. If the move fails part way through, the new instance is stopped and the
+    /// old instance is put back in service, so that the domain keeps running where the dataflow
+    /// state (which the caller shouldn't commit) says it is.
+    ///
+    /// All the keys in the partial readers of the old instance are replayed into the readers of
+    /// the new instance. This returns a future which waits for that to finish (up to
+    /// [`MOVE_PREFILL_TIMEOUT`]) and then stops the old instance, which doesn't need to be awaited
+    /// while holding a lock on the dataflow state.
+    pub(super) async fn move_domain(
+        &mut self,
+        domain_index: DomainIndex,
+        from: &WorkerIdentifier,
+        to: &WorkerIdentifier,
+    ) -> ReadySetResult<impl Future<Output = ()> + Send + 'static> {
+        let replica_address = ReplicaAddress {
+            domain_index,
+            shard: 0,
+            replica: 0,
+        };
+        let handle =
+            self.domains
+                .get(&domain_index)
+                .ok_or_else(|| ReadySetError::UnknownDomain {
+                    domain_index: domain_index.index(),
+                })?;
+        invariant!(
+            handle.num_shards() == 1 && handle.num_replicas() == 1,
+            "Only unsharded and unreplicated domains can be moved"
+        );
+        let reader_keys = handle
+            .send_to_healthy_shard_replica::<NodeMap<Vec<Vec<DfValue>>>>(
+                0,
+                0,
+                DomainRequest::ReaderKeys,
+                &self.workers,
+            )
+            .await?;
+        let old_addr = self
+            .channel_coordinator
+            .get_addr(&replica_address)
+            .ok_or_else(|| internal_err!("No address known for domain {}", replica_address))?;
+        let old_worker = self
+            .workers
+            .get(from)
+            .cloned()
+            .ok_or_else(|| ReadySetError::WorkerFailed { uri: from.clone() })?;
+
+        info!(domain = %domain_index, %from, %to, "moving domain");
+        if let Err(error) = self
+            .start_moved_domain(domain_index, &old_worker, to, reader_keys)
+            .await
+        {
+            warn!(domain = %domain_index, %error, "moving domain failed, rolling back");
+            self.abort_move(replica_address, old_addr, &old_worker, to)
+                .await;
+            return Err(error);
+        }
+
+        let prefills = self.wait_for_reader_prefills(&[domain_index], MOVE_PREFILL_TIMEOUT);
+        Ok(async move {
+            prefills.await;
+            if let Err(error) = old_worker
+                .rpc::<()>(WorkerRequestKind::KillDomains(vec![replica_address]))
+                .await
+            {
+                warn!(
+                    %replica_address,
+                    %error,
+                    "Could not stop the old instance of a moved domain"
+                );
+            }
+        })
+    }
+
+    /// Retires the old instance of the domain with the given index on `old_worker`, and starts a
+    /// new instance of it on the worker `to`, which is given the keys in the partial readers of the
+    /// old instance
+    async fn start_moved_domain(
+        &mut self,
+        domain_index: DomainIndex,
+        old_worker: &Worker,
+        to: &WorkerIdentifier,
+        reader_keys: NodeMap<Vec<Vec<DfValue>>>,
+    ) -> ReadySetResult<()> {
+        let replica_address = ReplicaAddress {
+            domain_index,
+            shard: 0,
+            replica: 0,
+        };
+        old_worker
+            .rpc::<()>(WorkerRequestKind::RetireDomains(vec![replica_address]))
+            .await?;
+
+        let nodes = self
+            .domain_nodes
+            .get(&domain_index)
+            .ok_or_else(|| ReadySetError::UnknownDomain {
+                domain_index: domain_index.index(),
+            })?
+            .values()
+            .copied()
+            .collect::<HashSet<_>>();
+        self.domains.remove(&domain_index);
+        self.materializations.remove_nodes(&nodes);
+        let replay_tags = self.materializations.remove_paths(&nodes);
+        self.recover_with_placement(
+            &HashMap::from([(domain_index, nodes)]),
+            HashMap::from([(
+                domain_index,
+                (
+                    Array2::from_rows(vec![vec![to.clone()]]),
+                    MovedDomainState {
+                        replay_tags,
+                        reader_keys,
+                    },
+                ),
+            )]),
+        )
+        .await?;
+
+        set_failpoint_return_err!(failpoints::MOVE_DOMAIN);
+        Ok(())
+    }
+
+    /// Undoes a move of the domain replica at `replica_address` away from `old_worker` that failed
+    /// part way through: stops the new instance on the worker `to` if it was started, and puts the
+    /// old instance back in service at its old address, `old_addr`.
+    ///
+    /// Any replay paths set up in other domains for the new instance are left behind, but are
+    /// never triggered.
+    async fn abort_move(
+        &mut self,
+        replica_address: ReplicaAddress,
+        old_addr: SocketAddr,
+        old_worker: &Worker,
+        to: &WorkerIdentifier,
+    ) {
+        if let Some(new_worker) = self.workers.get(to) {
+            if let Err(error) = new_worker
+                .rpc::<()>(WorkerRequestKind::KillDomains(vec![replica_address]))
+                .await
+            {
+                warn!(
+                    %replica_address,
+                    %error,
+                    "Could not stop the new instance of a domain after a failed move"
+                );
+            }
+        }
+        if let Err(error) = old_worker
+            .rpc::<()>(WorkerRequestKind::UnretireDomains(vec![replica_address]))
+            .await
+        {
+            error!(
+                %replica_address,
+                %error,
+                "Could not put the old instance of a domain back after a failed move"
+            );
+        }
+
+        // Route everything back to the old instance
+        if let Err(error) = self
+            .channel_coordinator
+            .insert_remote(replica_address, old_addr)
+        {
+            error!(%replica_address, %error, "Could not restore the address of a domain");
+        }
+        self.gossip_domain_addresses(vec![DomainDescriptor::new(replica_address, old_addr)])
+            .await;
+    }
+
     /// Runs all the necessary steps to recover the full [`DfState`], when said state only
     /// has the bare minimum information.
     ///
@@ -1349,6 +2317,18 @@ impl DfState {
     pub(super) async fn recover(
         &mut self,
         domain_nodes: &HashMap<DomainIndex, HashSet<NodeIndex>>,
+    ) -> ReadySetResult<()> {
+        self.recover_with_placement(domain_nodes, HashMap::new())
+            .await
+    }
+
+    /// Like [`DfState::recover`], but runs the domains being moved in `moved` on the given workers
+    /// (indexed by shard index first and replica index second) rather than scheduling them, and
+    /// hands the given state of their previous instances over to them.
+    async fn recover_with_placement(
+        &mut self,
+        domain_nodes: &HashMap<DomainIndex, HashSet<NodeIndex>>,
+        mut moved: HashMap<DomainIndex, (Array2<WorkerIdentifier>, MovedDomainState)>,
     ) -> ReadySetResult<()> {
         let mut dmp = DomainMigrationPlan::new(self);
         let domain_nodes = domain_nodes
@@ -1358,10 +2338,14 @@ impl DfState {
         {
             let mut scheduler = Scheduler::new(self, &None)?;
             for (domain, nodes) in domain_nodes.iter() {
-                let workers = scheduler.schedule_domain(*domain, &nodes[..])?;
+                let (workers, moved_from) = match moved.remove(domain) {
+                    Some((workers, moved_from)) => (workers, Some(moved_from)),
+                    None => (scheduler.schedule_domain(*domain, &nodes[..])?, None),
+                };
                 let num_shards = workers.num_rows();
-                let num_replicas = workers[0].len();
-                dmp.place_domain(*domain, workers, nodes.clone());
+                let num_replicas =
+                    active_replicas(&self.ingredients, nodes.iter().copied(), workers[0].len());
+                dmp.place_domain(*domain, workers, nodes.clone(), moved_from);
                 dmp.set_domain_settings(
                     *domain,
                     DomainSettings {
@@ -1399,6 +2383,7 @@ impl DfState {
         self.channel_coordinator = Default::default();
         self.read_addrs = Default::default();
         self.workers = Default::default();
+        self.last_domain_stats = Default::default();
 
         let mut new_materializations = Materializations::new();
         new_materializations.paths = self.materializations.paths.clone();
@@ -1,6 +1,9 @@
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 
+use dataflow::payload::PrepareStateKind;
 use dataflow::prelude::*;
+use dataflow::DomainConfig;
 use serde::{Deserialize, Serialize};
 
 /// Response to `WorkerRequestKind::RunDomain`.
@@ -40,3 +43,117 @@ impl DomainDescriptor {
         self.replica_address().shard
     }
 }
+
+/// Response to `WorkerRequestKind::ListDomains`, describing one of the domain replicas running on
+/// a worker.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RunningDomain {
+    /// How the domain replica can be reached.
+    pub(crate) descriptor: DomainDescriptor,
+    /// The (global) indices of the nodes in the domain replica.
+    pub(crate) nodes: Vec<NodeIndex>,
+    /// The configuration the domain replica was started with.
+    pub(crate) config: DomainConfig,
+    /// The state that was prepared for each of the materialized nodes in the domain replica,
+    /// keyed by global node index.
+    pub(crate) materializations: HashMap<NodeIndex, PreparedState>,
+}
+
+/// Summary of the state prepared for a materialized node in a domain, which is compared against
+/// the materializations planned by a newly elected controller before it takes over a running
+/// domain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PreparedState {
+    /// Whether the state is partially materialized.
+    pub(crate) partial: bool,
+    /// The strict indices of the state (or the index of a reader).
+    pub(crate) strict_indices: HashSet<Index>,
+    /// The weak indices of the state.
+    pub(crate) weak_indices: HashSet<Index>,
+}
+
+impl PreparedState {
+    /// Adds the indices from a `DomainRequest::PrepareState` sent for the node to this summary.
+    ///
+    /// Indices can be added to an existing materialization by later migrations, which prepare the
+    /// state of the node again with only the new indices.
+    pub(crate) fn add(&mut self, kind: &PrepareStateKind) {
+        match kind {
+            PrepareStateKind::Partial {
+                strict_indices,
+                weak_indices,
+            } => {
+                self.partial = true;
+                self.strict_indices
+                    .extend(strict_indices.iter().map(|(index, _)| index.clone()));
+                self.weak_indices.extend(weak_indices.iter().cloned());
+            }
+            PrepareStateKind::Full {
+                strict_indices,
+                weak_indices,
+            } => {
+                self.strict_indices.extend(strict_indices.iter().cloned());
+                self.weak_indices.extend(weak_indices.iter().cloned());
+            }
+            PrepareStateKind::PartialReader { index, .. } => {
+                self.partial = true;
+                self.strict_indices.insert(index.clone());
+            }
+            PrepareStateKind::FullReader { index, .. } => {
+                self.strict_indices.insert(index.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepared_state_merges_indices() {
+        let mut state = PreparedState::default();
+        state.add(&PrepareStateKind::Partial {
+            strict_indices: vec![(Index::hash_map(vec![0]), vec![Tag::new(1)])],
+            weak_indices: HashSet::from([Index::hash_map(vec![2])]),
+        });
+        state.add(&PrepareStateKind::Partial {
+            strict_indices: vec![(Index::btree_map(vec![1]), vec![Tag::new(2)])],
+            weak_indices: HashSet::new(),
+        });
+
+        assert_eq!(
+            state,
+            PreparedState {
+                partial: true,
+                strict_indices: HashSet::from([
+                    Index::hash_map(vec![0]),
+                    Index::btree_map(vec![1])
+                ]),
+                weak_indices: HashSet::from([Index::hash_map(vec![2])]),
+            }
+        );
+    }
+
+    #[test]
+    fn prepared_state_full_vs_partial() {
+        let index = Index::hash_map(vec![0]);
+        let mut full = PreparedState::default();
+        full.add(&PrepareStateKind::FullReader {
+            node_index: NodeIndex::new(1),
+            num_columns: 1,
+            index: index.clone(),
+        });
+        let mut partial = PreparedState::default();
+        partial.add(&PrepareStateKind::PartialReader {
+            node_index: NodeIndex::new(1),
+            num_columns: 1,
+            num_shards: 1,
+            index,
+            trigger_domain: DomainIndex::from(0),
+        });
+
+        assert_ne!(full, partial);
+        assert_eq!(full.strict_indices, partial.strict_indices);
+    }
+}
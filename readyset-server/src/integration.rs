@@ -973,6 +973,99 @@ async fn it_works_with_vote() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn identical_queries_with_different_cache_options() {
+    let (mut g, shutdown_tx) =
+        start_simple_unsharded("identical_queries_with_different_cache_options").await;
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE Article (aid int, PRIMARY KEY(aid));
+             CREATE CACHE aq1 WITH (ttl = '60s') FROM SELECT Article.* FROM Article WHERE Article.aid = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // Reusing the existing cache with the same options is fine...
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE CACHE aq2 WITH (ttl = '60s') FROM SELECT Article.* FROM Article WHERE Article.aid = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // ...but the options of the existing cache can't be changed
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE CACHE aq3 FROM SELECT Article.* FROM Article WHERE Article.aid = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap_err();
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE CACHE aq4 WITH (refresh = '1h') FROM SELECT Article.* FROM Article WHERE Article.aid = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap_err();
+
+    shutdown_tx.shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cache_options_for_full_materialization() {
+    let mut b = Builder::for_tests();
+    b.disable_partial();
+    b.set_sharding(None);
+    let (mut g, shutdown_tx) = b.start_local().await.unwrap();
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE Article (aid int, title text, PRIMARY KEY(aid));",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    // Keys can't be evicted from fully materialized caches
+    for options in ["ttl = '60s'", "refresh = '10m'"] {
+        g.extend_recipe(
+            ChangeList::from_str(
+                format!(
+                    "CREATE CACHE aq WITH ({options}) FROM SELECT title FROM Article WHERE aid = ?;"
+                ),
+                Dialect::DEFAULT_MYSQL,
+            )
+            .unwrap(),
+        )
+        .await
+        .unwrap_err();
+    }
+
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE CACHE aq FROM SELECT title FROM Article WHERE aid = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    shutdown_tx.shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn it_works_with_identical_queries() {
     let (mut g, shutdown_tx) = start_simple_unsharded("it_works_with_identical_queries").await;
@@ -1771,6 +1864,94 @@ async fn it_recovers_persisted_bases_w_multiple_nodes() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn it_prefills_readers_with_hot_keys_after_restart() {
+    let authority_store = Arc::new(LocalAuthorityStore::new());
+    let authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store.clone(),
+    )));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir
+        .path()
+        .join("it_prefills_readers_with_hot_keys_after_restart");
+    let persistence_parameters = PersistenceParameters::new(
+        DurabilityMode::Permanent,
+        Some(path.to_string_lossy().into()),
+        1,
+        None,
+    );
+    let builder = || {
+        let mut g = Builder::for_tests();
+        g.set_persistence(persistence_parameters.clone());
+        g.set_eviction_kind(dataflow::EvictionKind::LRU);
+        g.set_warm_restart_keys(100);
+        g.set_hot_keys_save_interval(Duration::from_millis(100));
+        g
+    };
+
+    {
+        let (mut g, shutdown_tx) = builder().start(authority.clone()).await.unwrap();
+        g.backend_ready().await;
+
+        g.extend_recipe(
+            ChangeList::from_str(
+                "CREATE TABLE t (id int, val int, PRIMARY KEY(id));
+                 CREATE CACHE q FROM SELECT val FROM t WHERE id = ?;",
+                Dialect::DEFAULT_MYSQL,
+            )
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let mut t = g.table("t").await.unwrap();
+        t.insert_many((0..10i32).map(|i| vec![DfValue::from(i), DfValue::from(i * 10)]))
+            .await
+            .unwrap();
+        sleep().await;
+
+        let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+        for i in 0..5i32 {
+            let res = q.lookup(&[i.into()], true).await.unwrap().into_vec();
+            assert_eq!(res, vec![vec![DfValue::from(i * 10)]]);
+        }
+
+        // Give the domain a chance to save the keys that were just read
+        sleep().await;
+        shutdown_tx.shutdown().await;
+        if let Authority::LocalAuthority(l) = authority.as_ref() {
+            l.delete_ephemeral();
+        }
+    }
+
+    sleep().await;
+
+    let authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store,
+    )));
+    let (mut g, shutdown_tx) = builder().start(authority.clone()).await.unwrap();
+    g.backend_ready().await;
+
+    // The keys read before the restart were replayed into the reader before the leader reported
+    // being ready, so they hit without blocking...
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    for i in 0..5i32 {
+        let res = q.lookup(&[i.into()], false).await.unwrap().into_vec();
+        assert_eq!(res, vec![vec![DfValue::from(i * 10)]]);
+    }
+
+    // ...but the ones that weren't read aren't filled
+    assert!(matches!(
+        q.lookup(&[7.into()], false).await,
+        Err(readyset_errors::ReadySetError::ReaderMissingKey)
+    ));
+    let res = q.lookup(&[7.into()], true).await.unwrap().into_vec();
+    assert_eq!(res, vec![vec![DfValue::from(70)]]);
+
+    shutdown_tx.shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn it_recovers_persisted_bases_w_multiple_nodes_and_volume_id() {
     let authority_store = Arc::new(LocalAuthorityStore::new());
@@ -3708,7 +3889,8 @@ async fn finkelstein1982_queries() {
                     inc.add_table(stmt.table, stmt.body.unwrap(), mig).unwrap();
                 }
                 SqlQuery::Select(stmt) => {
-                    inc.add_query(None, stmt, false, &[], mig).unwrap();
+                    inc.add_query(None, stmt, false, Default::default(), &[], mig)
+                        .unwrap();
                 }
                 _ => panic!("unexpected query type"),
             }
@@ -4357,6 +4539,201 @@ async fn simple_pagination() {
     shutdown_tx.shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn range_pagination() {
+    let (mut g, shutdown_tx) = start_simple_unsharded("range_pagination").await;
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (x int, y int);
+         CREATE CACHE q FROM SELECT x, y FROM t WHERE y > $1 ORDER BY x ASC LIMIT 2 OFFSET $2;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let mut t = g.table("t").await.unwrap();
+
+    t.insert_many(vec![
+        vec![DfValue::from(1), DfValue::from(1)],
+        vec![DfValue::from(2), DfValue::from(2)],
+        vec![DfValue::from(3), DfValue::from(2)],
+        vec![DfValue::from(4), DfValue::from(3)],
+        vec![DfValue::from(5), DfValue::from(3)],
+        vec![DfValue::from(6), DfValue::from(3)],
+    ])
+    .await
+    .unwrap();
+
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    assert_eq!(
+        q.key_map(),
+        &[
+            (
+                ViewPlaceholder::OneToOne(1, nom_sql::BinaryOperator::Greater),
+                1
+            ),
+            (
+                ViewPlaceholder::PageNumber {
+                    offset_placeholder: 2,
+                    limit: 2
+                },
+                2
+            )
+        ]
+    );
+
+    // y > 1 LIMIT 2 OFFSET 2, which can be found in the first two pages of each value of y
+    let rows: Vec<Vec<DfValue>> = q
+        .raw_lookup(ViewQuery {
+            key_comparisons: vec![KeyComparison::Range((
+                Bound::Included(vec1![DfValue::from(1), DfValue::from(0u64)]),
+                Bound::Included(vec1![DfValue::Max, DfValue::from(1u64)]),
+            ))],
+            block: true,
+            filter: Some(DfExpr::Op {
+                left: Box::new(DfExpr::Column {
+                    index: 1,
+                    ty: DfType::Int,
+                }),
+                op: BinaryOperator::Greater,
+                right: Box::new(DfExpr::Literal {
+                    val: 1.into(),
+                    ty: DfType::Int,
+                }),
+                ty: DfType::Bool,
+            }),
+            timestamp: None,
+            limit: None,
+            offset: Some(2),
+        })
+        .await
+        .unwrap()
+        .into_vec();
+    assert_eq!(
+        rows,
+        vec![
+            vec![DfValue::from(4), DfValue::from(3)],
+            vec![DfValue::from(5), DfValue::from(3)],
+        ]
+    );
+
+    shutdown_tx.shutdown().await;
+}
+
+async fn lookup_with_limit(
+    view: &mut readyset_client::ReaderHandle,
+    key: &str,
+    limit: usize,
+) -> readyset_errors::ReadySetResult<Vec<Vec<DfValue>>> {
+    Ok(view
+        .raw_lookup(ViewQuery {
+            key_comparisons: vec![vec1![DfValue::from(key)].into()],
+            block: true,
+            filter: None,
+            timestamp: None,
+            limit: Some(limit),
+            offset: None,
+        })
+        .await?
+        .into_vec())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parametrized_limit() {
+    let (mut g, shutdown_tx) = start_simple_unsharded("parametrized_limit").await;
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (x int, y text);
+         CREATE CACHE q FROM SELECT x, y FROM t WHERE y = $1 ORDER BY x ASC LIMIT $2;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let mut t = g.table("t").await.unwrap();
+    t.insert_many((1..=5).map(|x| vec![DfValue::from(x), DfValue::from("a")]))
+        .await
+        .unwrap();
+
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    assert_eq!(
+        q.key_map(),
+        &[(
+            ViewPlaceholder::OneToOne(1, nom_sql::BinaryOperator::Equal),
+            1
+        )]
+    );
+
+    sleep().await;
+
+    assert_eq!(
+        lookup_with_limit(&mut q, "a", 2).await.unwrap(),
+        vec![
+            vec![DfValue::from(1), DfValue::from("a")],
+            vec![DfValue::from(2), DfValue::from("a")],
+        ]
+    );
+    assert_eq!(lookup_with_limit(&mut q, "a", 10).await.unwrap().len(), 5);
+
+    shutdown_tx.shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parametrized_limit_with_max() {
+    let mut builder = Builder::for_tests();
+    builder.set_sharding(None);
+    builder.set_persistence(get_persistence_params("parametrized_limit_with_max"));
+    builder.set_allow_topk(true);
+    builder.set_max_parametrized_limit(Some(3));
+    let (mut g, shutdown_tx) = builder.start_local().await.unwrap();
+
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (x int, y text);
+         CREATE CACHE q FROM SELECT x, y FROM t WHERE y = $1 ORDER BY x ASC LIMIT $2;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let mut t = g.table("t").await.unwrap();
+    t.insert_many((1..=5).map(|x| vec![DfValue::from(x), DfValue::from("a")]))
+        .await
+        .unwrap();
+
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+
+    sleep().await;
+
+    assert_eq!(
+        lookup_with_limit(&mut q, "a", 3).await.unwrap(),
+        vec![
+            vec![DfValue::from(1), DfValue::from("a")],
+            vec![DfValue::from(2), DfValue::from("a")],
+            vec![DfValue::from(3), DfValue::from("a")],
+        ]
+    );
+    lookup_with_limit(&mut q, "a", 4).await.unwrap_err();
+
+    t.delete_row(vec![DfValue::from(1), DfValue::from("a")])
+        .await
+        .unwrap();
+    sleep().await;
+
+    assert_eq!(
+        lookup_with_limit(&mut q, "a", 1).await.unwrap(),
+        vec![vec![DfValue::from(2), DfValue::from("a")]]
+    );
+
+    shutdown_tx.shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn correct_nested_view_schema() {
     let r_txt = "CREATE TABLE votes (story int, user int);
@@ -7082,6 +7459,316 @@ async fn assign_nonreader_domains_to_nonreader_workers() {
     tokio::join!(shutdown_tx_1.shutdown(), shutdown_tx_2.shutdown());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rebalance_moves_reader_domain_with_its_keys() {
+    let authority_store = Arc::new(LocalAuthorityStore::new());
+    let w1_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store.clone(),
+    )));
+    let w2_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store,
+    )));
+    let cluster_name = "rebalance_moves_reader_domain_with_its_keys";
+
+    let (mut g, shutdown_tx_1) =
+        build_custom(cluster_name, None, true, w1_authority, false, None).await;
+    g.backend_ready().await;
+
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (id int, val int);
+             CREATE CACHE q FROM SELECT val FROM t WHERE id = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let mut t = g.table("t").await.unwrap();
+    t.insert_many((0..10i32).map(|i| vec![DfValue::from(i), DfValue::from(i * 10)]))
+        .await
+        .unwrap();
+    sleep().await;
+
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    for i in 0..5i32 {
+        let res = q.lookup(&[i.into()], true).await.unwrap().into_vec();
+        assert_eq!(res, vec![vec![DfValue::from(i * 10)]]);
+    }
+
+    // Everything runs on the first worker, so adding a second worker should move the only domain
+    // that can be moved, which is the one with the reader
+    let (_w2, shutdown_tx_2) =
+        build_custom(cluster_name, None, false, w2_authority, false, None).await;
+    sleep().await;
+
+    // The reader's domain is created after the base table's
+    let reader_domain = g
+        .get_info()
+        .await
+        .unwrap()
+        .values()
+        .flat_map(|domains| domains.keys())
+        .map(|replica_address| replica_address.domain_index)
+        .max()
+        .unwrap();
+    let moves = g.rebalance().await.unwrap();
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].domain_index, reader_domain);
+    assert_ne!(moves[0].from, moves[0].to);
+
+    // The keys read before the move were replayed into the reader at its new location, so they
+    // hit without blocking
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    for i in 0..5i32 {
+        let res = q.lookup(&[i.into()], false).await.unwrap().into_vec();
+        assert_eq!(res, vec![vec![DfValue::from(i * 10)]]);
+    }
+
+    // ...and the reader keeps receiving writes and filling new keys
+    t.insert(vec![DfValue::from(0), DfValue::from(1)])
+        .await
+        .unwrap();
+    sleep().await;
+    let res = q.lookup(&[0.into()], true).await.unwrap().into_vec();
+    assert_eq!(res.len(), 2);
+    let res = q.lookup(&[7.into()], true).await.unwrap().into_vec();
+    assert_eq!(res, vec![vec![DfValue::from(70)]]);
+
+    tokio::join!(shutdown_tx_1.shutdown(), shutdown_tx_2.shutdown());
+}
+
+#[cfg(feature = "failure_injection")]
+#[tokio::test(flavor = "multi_thread")]
+async fn rebalance_rolls_back_failed_move() {
+    use readyset_client::failpoints;
+
+    let authority_store = Arc::new(LocalAuthorityStore::new());
+    let w1_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store.clone(),
+    )));
+    let w2_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store,
+    )));
+    let cluster_name = "rebalance_rolls_back_failed_move";
+
+    let (mut g, shutdown_tx_1) =
+        build_custom(cluster_name, None, true, w1_authority, false, None).await;
+    g.backend_ready().await;
+
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (id int, val int);
+             CREATE CACHE q0 FROM SELECT val FROM t WHERE id = ?;
+             CREATE CACHE q1 FROM SELECT id FROM t WHERE val = ?;
+             CREATE CACHE q2 FROM SELECT id, val FROM t WHERE id = ?;
+             CREATE CACHE q3 FROM SELECT val, id FROM t WHERE val = ?;
+             CREATE CACHE q4 FROM SELECT id FROM t WHERE id = ?;
+             CREATE CACHE q5 FROM SELECT val FROM t WHERE val = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let mut t = g.table("t").await.unwrap();
+    t.insert_many((0..100i32).map(|i| vec![DfValue::from(i), DfValue::from(i)]))
+        .await
+        .unwrap();
+    sleep().await;
+
+    // All the caches return the id or the val of the row with the given id or val, which are equal
+    async fn check_caches(g: &mut crate::Handle, keys: std::ops::Range<i32>) {
+        for i in 0..6 {
+            let mut q = g
+                .view(format!("q{i}").as_str())
+                .await
+                .unwrap()
+                .into_reader_handle()
+                .unwrap();
+            for key in keys.clone() {
+                let res = q.lookup(&[key.into()], true).await.unwrap().into_vec();
+                assert!(
+                    res.iter().all(|row| row.iter().all(|v| *v == key.into())),
+                    "q{i}[{key}] = {res:?}"
+                );
+                assert_eq!(res.len(), 1, "q{i}[{key}] = {res:?}");
+            }
+        }
+    }
+    check_caches(&mut g, 0..100).await;
+
+    let num_domains = g
+        .get_info()
+        .await
+        .unwrap()
+        .values()
+        .map(|domains| domains.len())
+        .sum::<usize>();
+
+    // Everything runs on the first worker, so adding a second worker should move a few of the
+    // domains with the caches, but the second move fails
+    let (_w2, shutdown_tx_2) =
+        build_custom(cluster_name, None, false, w2_authority, false, None).await;
+    sleep().await;
+    g.set_failpoint(failpoints::MOVE_DOMAIN, "1*off->return")
+        .await;
+    g.rebalance().await.unwrap_err();
+    sleep().await;
+
+    // The first move is kept, and the domain of the second move is back on its old worker
+    let info = g.get_info().await.unwrap();
+    let mut domains_per_worker = info
+        .values()
+        .map(|domains| domains.len())
+        .collect::<Vec<_>>();
+    domains_per_worker.sort();
+    assert_eq!(domains_per_worker, vec![1, num_domains - 1], "{info:?}");
+    check_caches(&mut g, 0..100).await;
+
+    // ...and all the caches keep receiving writes
+    t.insert(vec![DfValue::from(100), DfValue::from(100)])
+        .await
+        .unwrap();
+    sleep().await;
+    check_caches(&mut g, 100..101).await;
+
+    g.set_failpoint(failpoints::MOVE_DOMAIN, "off").await;
+    assert!(!g.rebalance().await.unwrap().is_empty());
+    sleep().await;
+    check_caches(&mut g, 0..101).await;
+
+    tokio::join!(shutdown_tx_1.shutdown(), shutdown_tx_2.shutdown());
+}
+
+/// Starts a reader-only server, which becomes the leader, and a regular server, creates a few
+/// caches and reads some keys from them, then shuts down the leader so that the other server takes
+/// over as the leader, changing the configuration of the domains if `change_config` is set.
+///
+/// Returns the number of caches which still have the keys that were read before the failover.
+async fn caches_kept_after_leader_failover(cluster_name: &str, change_config: bool) -> usize {
+    let authority_store = Arc::new(LocalAuthorityStore::new());
+    let w1_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store.clone(),
+    )));
+    let w2_authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store,
+    )));
+
+    let mut builder = Builder::for_tests();
+    builder.set_persistence(get_persistence_params(cluster_name));
+    builder.set_sharding(None);
+    builder.as_reader_only();
+    let (mut w1, shutdown_tx_1) = builder
+        .start_local_custom(w1_authority.clone())
+        .await
+        .unwrap();
+
+    let mut builder = Builder::for_tests();
+    builder.set_persistence(get_persistence_params(cluster_name));
+    builder.set_sharding(None);
+    if change_config {
+        builder.set_view_request_timeout(Duration::from_secs(60));
+    }
+    let (mut w2, shutdown_tx_2) = builder.start(w2_authority).await.unwrap();
+    sleep().await;
+
+    // The base table can only run on the second server. The reader of the first cache is then
+    // scheduled onto the first server, which has fewer domains, and the readers of the other two
+    // caches are split between the servers
+    w1.extend_recipe(
+        ChangeList::from_str("CREATE TABLE t (id int, val int);", Dialect::DEFAULT_MYSQL).unwrap(),
+    )
+    .await
+    .unwrap();
+    for i in 0..3 {
+        w1.extend_recipe(
+            ChangeList::from_str(
+                format!("CREATE CACHE q{i} FROM SELECT val FROM t WHERE id = ?;"),
+                Dialect::DEFAULT_MYSQL,
+            )
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let mut t = w1.table("t").await.unwrap();
+    t.insert_many((0..10i32).map(|i| vec![DfValue::from(i), DfValue::from(i * 10)]))
+        .await
+        .unwrap();
+    sleep().await;
+
+    for i in 0..3 {
+        let mut q = w1
+            .view(format!("q{i}").as_str())
+            .await
+            .unwrap()
+            .into_reader_handle()
+            .unwrap();
+        for key in 0..5i32 {
+            let res = q.lookup(&[key.into()], true).await.unwrap().into_vec();
+            assert_eq!(res, vec![vec![DfValue::from(key * 10)]]);
+        }
+    }
+
+    shutdown_tx_1.shutdown().await;
+    if let Authority::LocalAuthority(l) = w1_authority.as_ref() {
+        l.delete_ephemeral();
+    }
+    drop(w1);
+    w2.backend_ready().await;
+
+    let mut kept = 0;
+    for i in 0..3 {
+        let mut q = w2
+            .view(format!("q{i}").as_str())
+            .await
+            .unwrap()
+            .into_reader_handle()
+            .unwrap();
+        let mut hits = 0;
+        for key in 0..5i32 {
+            if let Ok(res) = q.lookup(&[key.into()], false).await {
+                assert_eq!(res.into_vec(), vec![vec![DfValue::from(key * 10)]]);
+                hits += 1;
+            }
+        }
+        if hits == 5 {
+            kept += 1;
+        }
+    }
+
+    shutdown_tx_2.shutdown().await;
+    kept
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn new_leader_takes_over_running_domains() {
+    // Only the reader which ran on the same server as the base table survives the failover, with
+    // all its keys, rather than being recreated
+    assert_eq!(
+        caches_kept_after_leader_failover("new_leader_takes_over_running_domains", false).await,
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn new_leader_recreates_domains_with_different_config() {
+    // The new leader would start domains with a different configuration than the running ones,
+    // so it recreates all of them instead
+    assert_eq!(
+        caches_kept_after_leader_failover(
+            "new_leader_recreates_domains_with_different_config",
+            true
+        )
+        .await,
+        0
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn join_straddled_columns() {
     let (mut g, shutdown_tx) = start_simple_unsharded("join_straddled_columns").await;
@@ -9343,3 +10030,46 @@ async fn views_out_of_order() {
 
     shutdown_tx.shutdown().await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cache_memory_limit() {
+    let (mut g, shutdown_tx) = start_simple("cache_memory_limit").await;
+    g.extend_recipe(
+        ChangeList::from_str(
+            "CREATE TABLE t (id int, val text);
+             CREATE CACHE q WITH (memory_limit = '16KB') FROM SELECT val FROM t WHERE id = ?;",
+            Dialect::DEFAULT_MYSQL,
+        )
+        .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let val = "a".repeat(100);
+    let mut t = g.table("t").await.unwrap();
+    t.insert_many((0..1000i32).map(|i| vec![DfValue::from(i), DfValue::from(val.as_str())]))
+        .await
+        .unwrap();
+    sleep().await;
+
+    // Filling every key takes up far more than the cache's memory limit...
+    let mut q = g.view("q").await.unwrap().into_reader_handle().unwrap();
+    for i in 0..1000i32 {
+        let res = q.lookup(&[i.into()], true).await.unwrap().into_vec();
+        assert_eq!(res, vec![vec![DfValue::from(val.as_str())]]);
+    }
+    sleep().await;
+
+    // ...so most of them have been evicted again, even though the process is nowhere near its own
+    // memory limit
+    let mut hits = 0;
+    for i in 0..1000i32 {
+        if q.lookup(&[i.into()], false).await.is_ok() {
+            hits += 1;
+        }
+    }
+    assert!(hits > 0);
+    assert!(hits < 500, "{hits} keys are still cached");
+
+    shutdown_tx.shutdown().await;
+}
@@ -499,6 +499,9 @@ pub struct Config {
     /// The duration to wait before canceling a task waiting on a worker request. Worker requests
     /// are typically issued as part of migrations.
     pub(crate) worker_request_timeout: Duration,
+    /// How often to move domains between workers to even out the load on each worker, if at all.
+    #[serde(default)]
+    pub(crate) rebalance_interval: Option<Duration>,
 }
 
 impl Default for Config {
@@ -517,6 +520,8 @@ impl Default for Config {
                 // now.
                 table_request_timeout: Duration::from_millis(1800000),
                 eviction_kind: dataflow::EvictionKind::Random,
+                warm_restart_keys: 0,
+                hot_keys_save_interval: Duration::from_secs(60),
             },
             persistence: Default::default(),
             quorum: 1,
@@ -528,6 +533,7 @@ impl Default for Config {
             replication_strategy: Default::default(),
             upquery_timeout: Duration::from_millis(5000),
             worker_request_timeout: Duration::from_millis(1800000),
+            rebalance_interval: None,
         }
     }
 }
@@ -576,6 +582,12 @@ pub struct WorkerOptions {
     #[clap(long = "eviction-policy", default_value_t = dataflow::EvictionKind::LRU)]
     pub eviction_kind: dataflow::EvictionKind,
 
+    /// The maximum number of the most recently read keys of each partially materialized cache to
+    /// periodically save to disk, so that they can be filled back into the cache after a restart
+    /// before it starts serving traffic. Set to 0 to disable.
+    #[clap(long, env = "WARM_RESTART_KEYS", default_value = "10000")]
+    pub warm_restart_keys: usize,
+
     /// Disable partial
     #[clap(long = "nopartial", hide = true)]
     pub no_partial: bool,
@@ -617,6 +629,14 @@ pub struct WorkerOptions {
     #[clap(long, env = "EXPERIMENTAL_MIXED_COMPARISONS_SUPPORT", hide = true)]
     pub enable_experimental_mixed_comparisons: bool,
 
+    /// Maximum value for a parametrized `LIMIT ?` in a cached query. If set (and TopK support is
+    /// enabled), queries with a parametrized `LIMIT` only materialize this many rows per key, and
+    /// lookups with a larger `LIMIT` return an error. If not set, queries with a parametrized
+    /// `LIMIT` materialize all rows per key, and the `LIMIT` is applied to the results of each
+    /// lookup.
+    #[clap(long, env = "MAX_PARAMETRIZED_LIMIT", hide = true)]
+    pub max_parametrized_limit: Option<usize>,
+
     /// Directory in which to store replicated table data. If not specified, defaults to the
     /// current working directory.
     #[clap(long, env = "DB_DIR")]
@@ -631,6 +651,11 @@ pub struct WorkerOptions {
     /// Timeout in seconds for all requests made from the controller to workers
     #[clap(long, env = "WORKER_REQUEST_TIMEOUT_SECONDS", default_value = "1800")]
     pub worker_request_timeout_seconds: u64,
+
+    /// If set, periodically move domains between workers to even out the memory and CPU used on
+    /// each worker, waiting this many seconds between each round of moves
+    #[clap(long, env = "REBALANCE_INTERVAL_SECONDS")]
+    pub rebalance_interval_seconds: Option<u64>,
 }
 
 use std::pin::Pin;
@@ -130,6 +130,7 @@ async fn start_worker(
         state_sizes: Default::default(),
         readers,
         domains: Default::default(),
+        retired_domains: Default::default(),
         memory: MemoryTracker::new()?,
         is_evicting: Default::default(),
         domain_wait_queue: Default::default(),
@@ -1,13 +1,15 @@
-use std::cmp;
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::HashMap;
 use std::future::Future;
 use std::net::{IpAddr, SocketAddr};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use dataflow::{DomainBuilder, DomainRequest, Packet, Readers};
+use dataflow::prelude::{LocalNodeIndex, NodeIndex};
+use dataflow::{
+    plan_eviction, DomainBuilder, DomainConfig, DomainRequest, Packet, Readers, StateSize,
+};
 use enum_kinds::EnumKind;
 use futures::stream::FuturesUnordered;
 use futures::FutureExt;
@@ -32,7 +34,7 @@ use tracing::{debug, error, info, info_span, trace, warn};
 use url::Url;
 
 use self::replica::Replica;
-use crate::coordination::{DomainDescriptor, RunDomainResponse};
+use crate::coordination::{DomainDescriptor, PreparedState, RunDomainResponse, RunningDomain};
 use crate::worker::replica::WrappedDomainRequest;
 
 /// Request handlers and utilities for reading from the ReadHandle of a
@@ -60,6 +62,29 @@ pub enum WorkerRequestKind {
     /// Clear domains.
     ClearDomains,
 
+    /// List the domain replicas currently running on this worker.
+    ///
+    /// Returns a `Vec<RunningDomain>`. This is used by a newly elected controller to take over
+    /// domains started by the previous one, rather than recreating them.
+    ListDomains,
+
+    /// Stop the given domain replicas, if they are running on this worker.
+    KillDomains(Vec<ReplicaAddress>),
+
+    /// Stop routing packets to the given domain replicas through their local channels, but keep
+    /// them running (so that their readers keep serving reads) until they're killed with
+    /// `KillDomains`.
+    ///
+    /// This is used when moving domains to another worker, before starting the new instance of
+    /// the domain.
+    RetireDomains(Vec<ReplicaAddress>),
+
+    /// Route packets to the given retired domain replicas through their local channels again, as
+    /// if they had never been retired.
+    ///
+    /// This is used to roll back a move of a domain to another worker that failed.
+    UnretireDomains(Vec<ReplicaAddress>),
+
     /// A set of domains has been started elsewhere in the distributed system.
     ///
     /// The message contains information on how the domain can be reached, in order that
@@ -80,6 +105,17 @@ pub enum WorkerRequestKind {
     /// Sent to validate that a connection actually works. Provokes an empty response.
     Ping,
 
+    /// Change the replica index of one of the domain replicas running on this worker.
+    ///
+    /// This is used to promote hot standby replicas of domains containing base tables when the
+    /// worker running the first replica fails.
+    SetReplica {
+        /// The current address of the domain replica
+        replica_address: ReplicaAddress,
+        /// The new replica index for the domain replica
+        replica: usize,
+    },
+
     /// Set the memory limit for this worker
     SetMemoryLimit {
         /// The period with which eviction check will be performed
@@ -111,11 +147,65 @@ pub struct WorkerElectionState {
 /// A handle for sending messages to a domain in-process.
 pub struct DomainHandle {
     req_tx: Sender<WrappedDomainRequest>,
+    /// The address used by other domains to talk to this domain
+    external_addr: SocketAddr,
+    /// The global indices of the nodes in this domain, keyed by local index
+    nodes: HashMap<LocalNodeIndex, NodeIndex>,
+    /// The configuration this domain was started with
+    config: DomainConfig,
+    /// The state prepared for each materialized node in this domain, keyed by local index
+    materializations: HashMap<LocalNodeIndex, PreparedState>,
     /// Can be used to send an abort signal to the domain
     /// aborts automatically when dropped
     _domain_abort: oneshot::Sender<()>,
 }
 
+/// A domain which has been moved to another worker, but is kept running until the new instance of
+/// the domain is ready
+pub(crate) struct RetiredDomain {
+    handle: DomainHandle,
+    /// The channel used for in-process traffic to the domain, which is kept so that the domain
+    /// doesn't stop, and so that it can be put back if the move fails
+    local_tx: Option<tokio::sync::mpsc::UnboundedSender<Box<Packet>>>,
+    state_size: Option<Arc<StateSize>>,
+}
+
+impl DomainHandle {
+    /// Keeps track of the changes the given request makes to the nodes in the domain and their
+    /// materializations, so that they can be reported to a newly elected controller by
+    /// `WorkerRequestKind::ListDomains`
+    fn track_request(&mut self, request: &DomainRequest) {
+        match request {
+            DomainRequest::AddNode { node, .. } => {
+                self.nodes.insert(node.local_addr(), node.global_addr());
+            }
+            DomainRequest::RemoveNodes { nodes } => {
+                for node in nodes {
+                    self.nodes.remove(node);
+                    self.materializations.remove(node);
+                }
+            }
+            DomainRequest::PrepareState { node, state } => {
+                self.materializations.entry(*node).or_default().add(state);
+            }
+            _ => {}
+        }
+    }
+
+    fn running_domain(&self, replica_address: ReplicaAddress) -> RunningDomain {
+        RunningDomain {
+            descriptor: DomainDescriptor::new(replica_address, self.external_addr),
+            nodes: self.nodes.values().copied().collect(),
+            config: self.config.clone(),
+            materializations: self
+                .materializations
+                .iter()
+                .filter_map(|(local, state)| Some((*self.nodes.get(local)?, state.clone())))
+                .collect(),
+        }
+    }
+}
+
 /// Long-lived struct for tracking the currently allocated heap memory used by the current process
 /// by querying [`jemalloc_ctl`]
 #[derive(Clone, Copy)]
@@ -194,13 +284,16 @@ pub struct Worker {
     /// The IP address to expose to other domains for domain<->domain traffic.
     pub(crate) domain_external: IpAddr,
     /// A store of the current state size of each domain, used for eviction purposes.
-    pub(crate) state_sizes: Arc<Mutex<HashMap<ReplicaAddress, Arc<AtomicUsize>>>>,
+    pub(crate) state_sizes: Arc<Mutex<HashMap<ReplicaAddress, Arc<StateSize>>>>,
     /// Read handles.
     pub(crate) readers: Readers,
     /// Handles to domains currently being run by this worker.
     ///
     /// These are indexed by (domain index, shard).
     pub(crate) domains: HashMap<ReplicaAddress, DomainHandle>,
+    /// Handles to domains which have been moved to another worker, but are kept running until the
+    /// new instance of the domain is ready.
+    pub(crate) retired_domains: HashMap<ReplicaAddress, RetiredDomain>,
 
     pub(crate) memory: MemoryTracker,
     pub(crate) is_evicting: Arc<AtomicBool>,
@@ -242,12 +335,83 @@ impl Worker {
                 info!("controller requested that this worker clears its existing domains");
                 self.coord.clear();
                 self.domains.clear();
+                self.retired_domains.clear();
                 while let Some(res) = self.domain_wait_queue.next().await {
                     handle_domain_future_completion(res);
                 }
 
                 Ok(None)
             }
+            WorkerRequestKind::ListDomains => {
+                let domains = self
+                    .domains
+                    .iter()
+                    .map(|(replica_address, dh)| dh.running_domain(*replica_address))
+                    .collect::<Vec<_>>();
+                Ok(Some(bincode::serialize(&domains)?))
+            }
+            WorkerRequestKind::KillDomains(replica_addresses) => {
+                let mut state_sizes = self.state_sizes.lock().await;
+                for replica_address in replica_addresses {
+                    // dropping the handle aborts the domain
+                    if self.retired_domains.remove(&replica_address).is_some() {
+                        // the domain is already running somewhere else, so we don't want to forget
+                        // its address
+                        info!(%replica_address, "stopping retired domain");
+                        continue;
+                    }
+                    if self.domains.remove(&replica_address).is_some() {
+                        info!(%replica_address, "controller requested that this worker stops domain");
+                    }
+                    self.coord.remove(&replica_address)?;
+                    state_sizes.remove(&replica_address);
+                }
+                Ok(None)
+            }
+            WorkerRequestKind::RetireDomains(replica_addresses) => {
+                let mut state_sizes = self.state_sizes.lock().await;
+                for replica_address in replica_addresses {
+                    if let Some(handle) = self.domains.remove(&replica_address) {
+                        info!(%replica_address, "retiring domain");
+                        // other domains on this worker will keep sending packets to the domain
+                        // over TCP until they're told about its new address
+                        let local_tx = self.coord.remove_local(&replica_address)?;
+                        let state_size = state_sizes.remove(&replica_address);
+                        self.retired_domains.insert(
+                            replica_address,
+                            RetiredDomain {
+                                handle,
+                                local_tx,
+                                state_size,
+                            },
+                        );
+                    }
+                }
+                Ok(None)
+            }
+            WorkerRequestKind::UnretireDomains(replica_addresses) => {
+                let mut state_sizes = self.state_sizes.lock().await;
+                for replica_address in replica_addresses {
+                    if let Some(RetiredDomain {
+                        handle,
+                        local_tx,
+                        state_size,
+                    }) = self.retired_domains.remove(&replica_address)
+                    {
+                        info!(%replica_address, "putting retired domain back in service");
+                        if let Some(local_tx) = local_tx {
+                            self.coord.insert_local(replica_address, local_tx)?;
+                        }
+                        self.coord
+                            .insert_remote(replica_address, handle.external_addr)?;
+                        if let Some(state_size) = state_size {
+                            state_sizes.insert(replica_address, state_size);
+                        }
+                        self.domains.insert(replica_address, handle);
+                    }
+                }
+                Ok(None)
+            }
             WorkerRequestKind::RunDomain(builder) => {
                 let replica_addr = builder.address();
                 let span = info_span!("domain", address = %replica_addr);
@@ -275,7 +439,16 @@ impl Worker {
                 // buffer with a size bigger than one.
                 let (init_state_tx, init_state_rx) = tokio::sync::mpsc::channel(1);
 
-                let state_size = Arc::new(AtomicUsize::new(0));
+                let state_size = Arc::new(StateSize::default());
+                let nodes = builder
+                    .nodes
+                    .values()
+                    .map(|n| {
+                        let n = n.borrow();
+                        (n.local_addr(), n.global_addr())
+                    })
+                    .collect();
+                let config = builder.config.clone();
                 let domain = builder.build(
                     self.readers.clone(),
                     self.coord.clone(),
@@ -342,6 +515,10 @@ impl Worker {
                     replica_addr,
                     DomainHandle {
                         req_tx,
+                        external_addr: bind_external,
+                        nodes,
+                        config,
+                        materializations: HashMap::new(),
                         _domain_abort,
                     },
                 );
@@ -376,6 +553,7 @@ impl Worker {
                     replica: replica_address.replica,
                 };
                 let dh = self.domains.get_mut(&replica_address).ok_or_else(nsde)?;
+                dh.track_request(&request);
                 let (tx, rx) = oneshot::channel();
                 dh.req_tx
                     .send(WrappedDomainRequest {
@@ -386,6 +564,41 @@ impl Worker {
                     .map_err(|_| nsde())?;
                 rx.await.map_err(|_| nsde())?
             }
+            WorkerRequestKind::SetReplica {
+                replica_address,
+                replica,
+            } => {
+                let nsde = || ReadySetError::NoSuchReplica {
+                    domain_index: replica_address.domain_index.index(),
+                    shard: replica_address.shard,
+                    replica: replica_address.replica,
+                };
+                let dh = self.domains.get_mut(&replica_address).ok_or_else(nsde)?;
+                let (tx, rx) = oneshot::channel();
+                dh.req_tx
+                    .send(WrappedDomainRequest {
+                        req: DomainRequest::SetReplica { replica },
+                        done_tx: tx,
+                    })
+                    .await
+                    .map_err(|_| nsde())?;
+                rx.await.map_err(|_| nsde())??;
+
+                let new_address = ReplicaAddress {
+                    replica,
+                    ..replica_address
+                };
+                info!(from = %replica_address, to = %new_address, "changing replica address");
+                self.coord.rename(&replica_address, new_address)?;
+                let mut state_sizes = self.state_sizes.lock().await;
+                if let Some(state_size) = state_sizes.remove(&replica_address) {
+                    state_sizes.insert(new_address, state_size);
+                }
+                if let Some(dh) = self.domains.remove(&replica_address) {
+                    self.domains.insert(new_address, dh);
+                }
+                Ok(None)
+            }
             WorkerRequestKind::Ping => Ok(None),
             WorkerRequestKind::SetMemoryLimit { period, limit } => {
                 self.evict_interval = period.map(tokio::time::interval);
@@ -449,7 +662,7 @@ async fn do_eviction(
     memory_limit: Option<usize>,
     coord: Arc<ChannelCoordinator>,
     memory_tracker: MemoryTracker,
-    state_sizes: Arc<Mutex<HashMap<ReplicaAddress, Arc<AtomicUsize>>>>,
+    state_sizes: Arc<Mutex<HashMap<ReplicaAddress, Arc<StateSize>>>>,
     is_evicting: Arc<AtomicBool>,
 ) -> ReadySetResult<()> {
     if is_evicting.swap(true, Ordering::Relaxed) {
@@ -474,18 +687,28 @@ async fn do_eviction(
                 // we are! time to evict.
                 // add current state sizes (could be out of date, as packet sent below is not
                 // necessarily received immediately)
-                let (mut sizes, total_reported) = {
+                let (sizes, total_reported) = {
                     let state_sizes = state_sizes.lock().await;
                     let mut total_reported = 0;
                     let sizes = state_sizes
                         .iter()
-                        .map(|(replica_addr, size_atom)| {
-                            let size = size_atom.load(Ordering::Acquire);
-                            span.in_scope(|| {
-                                trace!("domain {} state size is {} bytes", replica_addr, size)
-                            });
-                            total_reported += size;
-                            (*replica_addr, size)
+                        .flat_map(|(replica_addr, state_size)| {
+                            state_size
+                                .bytes_by_priority()
+                                .filter(|(_, size)| *size > 0)
+                                .map(|(priority, size)| {
+                                    span.in_scope(|| {
+                                        trace!(
+                                            "domain {} state size is {} bytes at priority {}",
+                                            replica_addr,
+                                            size,
+                                            priority
+                                        )
+                                    });
+                                    total_reported += size;
+                                    ((*replica_addr, priority), size, priority)
+                                })
+                                .collect::<Vec<_>>()
                         })
                         .collect::<Vec<_>>();
                     (sizes, total_reported)
@@ -495,7 +718,7 @@ async fn do_eviction(
                 // memory usage - let's figure out proportionally how much *reported* memory we
                 // should evict
                 let actual_over = used - limit;
-                let mut proportional_over =
+                let proportional_over =
                     ((total_reported as f64 / used as f64) * actual_over as f64).round() as usize;
 
                 // here's how we're going to proceed.
@@ -503,41 +726,19 @@ async fn do_eviction(
                 // and we also need to be aware that evicting something from one place may cause a
                 // number of downstream evictions.
 
-                // we want to spread the eviction impact across multiple nodes where possible,
-                // so we distribute how much we're over the limit across the 3 largest nodes.
-                // -1* so we sort in descending order
-                // TODO: be smarter than 3 here
-                sizes.sort_unstable_by_key(|&(_, s)| -(s as i64));
-                sizes.truncate(3);
-
-                // don't evict from tiny things (< 10% of max)
-                if let Some(too_small_i) = sizes.iter().position(|&(_, s)| s < sizes[0].1 / 10) {
-                    // everything beyond this is smaller, so also too small
-                    sizes.truncate(too_small_i);
-                }
-
-                // starting with the smallest of the n domains
-                let mut n = sizes.len();
+                // we want to spread the eviction impact across multiple domains where possible,
+                // starting with the lowest priority state in each domain, so that higher priority
+                // state is only evicted from once there's not enough left to evict at lower
+                // priorities in any of the domains.
                 let mut domain_senders = HashMap::new();
-                for &(target, size) in sizes.iter().rev() {
-                    // TODO: should this be evenly divided, or weighted by the size of the domains?
-                    let share = (proportional_over + n - 1) / n;
-                    // we're only willing to evict at most half the state in each domain
-                    // unless this is the only domain left to evict from
-                    let evict = if n > 1 {
-                        cmp::min(size / 2, share)
-                    } else {
-                        assert_eq!(share, proportional_over);
-                        share
-                    };
-                    proportional_over -= evict;
-                    n -= 1;
-
+                for ((target, priority), evict) in plan_eviction(sizes, proportional_over) {
                     span.in_scope(|| {
                         debug!(
-                            "memory footprint ({} bytes) exceeds limit ({} bytes); evicting from largest domain {}",
+                            "memory footprint ({} bytes) exceeds limit ({} bytes); evicting {} bytes of {} priority state from domain {}",
                             used,
                             limit,
+                            evict,
+                            priority,
                             target.domain_index,
                         )
                     });
@@ -562,6 +763,7 @@ async fn do_eviction(
                     let r = tx
                         .send(Box::new(Packet::Evict {
                             node: None,
+                            priority: Some(priority),
                             num_bytes: evict,
                         }))
                         .await;
@@ -26,7 +26,7 @@ use readyset_client::{
     KeyComparison, LookupResult, ReadQuery, ReadReply, ReadReplyStats, ReaderAddress, Tagged,
     ViewQuery,
 };
-use readyset_errors::internal_err;
+use readyset_errors::{internal_err, unsupported_err};
 use readyset_util::shutdown::ShutdownReceiver;
 use serde::ser::Serializer;
 use serde::Serialize;
@@ -205,6 +205,15 @@ impl ReadRequestHandler {
             Err(e) => reply_with_error!(e),
         };
 
+        // Lookups can't request more records than are materialized in the reader
+        if let (Some(limit), Some(max_limit)) = (limit, reader.post_lookup.limit) {
+            if limit > max_limit {
+                reply_with_error!(unsupported_err!(
+                    "LIMIT {limit} exceeds the maximum of {max_limit} for this query"
+                ))
+            }
+        }
+
         let consistency_miss = !has_sufficient_timestamp(reader, &timestamp);
 
         let (keys_to_replay, receiver) = match reader.get_multi_with_notifier(&key_comparisons) {
@@ -30,8 +30,15 @@ use super::ChannelCoordinator;
 type DualTcpStream =
     channel::DualTcpStream<BufStream<TcpStream>, Box<Packet>, Tagged<PacketData>, AsyncDestination>;
 
-type Outputs =
-    AHashMap<ReplicaAddress, Box<dyn Sink<Box<Packet>, Error = bincode::Error> + Send + Unpin>>;
+/// Established connections to other Replicas, along with the address each connection was
+/// established to
+type Outputs = AHashMap<
+    ReplicaAddress,
+    (
+        SocketAddr,
+        Box<dyn Sink<Box<Packet>, Error = bincode::Error> + Send + Unpin>,
+    ),
+>;
 
 /// Generates a monotonically incrementing u64 value to be used as a token for our connections
 fn next_token() -> u64 {
@@ -256,7 +263,15 @@ impl Replica {
                 continue;
             }
 
-            let tx = match connections.entry(replica_address) {
+            // If the domain has moved to a different address since we connected to it, drop the
+            // connection so that we connect to it at its new address instead
+            if let Occupied(entry) = connections.entry(replica_address) {
+                if coord.get_addr(&replica_address) != Some(entry.get().0) {
+                    entry.remove();
+                }
+            }
+
+            let (_, tx) = match connections.entry(replica_address) {
                 Occupied(entry) => entry.into_mut(),
                 Vacant(entry) => {
                     // Only add  new entry if: coord.has(n) and coord.get_addr(n) is not banned or
@@ -267,14 +282,13 @@ impl Replica {
                         }
                         // If the channel is to a remote domain that has failed,
                         // drop the packets for the domain from this batch.
-                        if let Some(addr) = coord.get_addr(&replica_address) {
-                            if failed.lock().await.contains(&addr) {
-                                warn!(target = ?replica_address, "Skipping packets to domain as it may have failed");
-                                continue;
-                            }
+                        let builder = coord.builder_for(&replica_address)?;
+                        if failed.lock().await.contains(&builder.addr()) {
+                            warn!(target = ?replica_address, "Skipping packets to domain as it may have failed");
+                            continue;
                         }
 
-                        coord.builder_for(&replica_address)?.build_async()?
+                        (builder.addr(), builder.build_async()?)
                     })
                 }
             };
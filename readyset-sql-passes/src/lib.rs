@@ -30,7 +30,7 @@ mod util;
 use std::collections::{HashMap, HashSet};
 
 use dataflow_expression::Dialect;
-pub use nom_sql::analysis::{contains_aggregate, is_aggregate};
+pub use nom_sql::analysis::{contains_aggregate, contains_nondeterministic, is_aggregate};
 use nom_sql::{
     CompoundSelectStatement, CreateTableBody, CreateTableStatement, CreateViewStatement, Relation,
     SelectSpecification, SelectStatement, SqlIdentifier,
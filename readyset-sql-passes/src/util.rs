@@ -119,6 +119,9 @@ pub fn map_aggregates(expr: &mut Expr) -> Vec<(FunctionExpr, SqlIdentifier)> {
         }
         Expr::Exists(_) => {}
         Expr::NestedSelect(_) => {}
+        // Aggregate functions called as window functions are computed by the window operator, not
+        // by grouping
+        Expr::WindowFunction { .. } => {}
         Expr::Between {
             operand, min, max, ..
         } => {
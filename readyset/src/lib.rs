@@ -4,6 +4,7 @@
 pub mod mysql;
 pub mod psql;
 mod query_logger;
+pub mod tls;
 
 use std::collections::HashMap;
 use std::fs::remove_dir_all;
@@ -270,10 +271,18 @@ pub struct Options {
     #[clap(flatten)]
     pub tracing: readyset_tracing::Options,
 
+    /// readyset-mysql-specific options
+    #[clap(flatten)]
+    pub mysql_options: mysql::Options,
+
     /// readyset-psql-specific options
     #[clap(flatten)]
     pub psql_options: psql::Options,
 
+    /// Options for accepting TLS connections from clients
+    #[clap(flatten)]
+    pub tls_options: tls::Options,
+
     /// Allow executing, but ignore, unsupported `SET` statements.
     ///
     /// Takes precedence over any value passed to `--unsupported-set-mode`
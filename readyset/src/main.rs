@@ -15,6 +15,8 @@ fn main() -> anyhow::Result<()> {
             default_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 3306),
             connection_handler: MySqlHandler {
                 enable_statement_logging: options.tracing.statement_logging,
+                tls_acceptor: options.tls_options.tls_acceptor()?,
+                require_tls: options.mysql_options.mysql_require_tls,
             },
             database_type: DatabaseType::MySQL,
             parse_dialect: nom_sql::Dialect::MySQL,
@@ -27,6 +29,7 @@ fn main() -> anyhow::Result<()> {
             connection_handler: PsqlHandler::new(readyset::psql::Config {
                 options: options.psql_options.clone(),
                 enable_statement_logging: options.tracing.statement_logging,
+                tls_acceptor: options.tls_options.tls_acceptor()?,
             })?,
             database_type: DatabaseType::PostgreSQL,
             parse_dialect: nom_sql::Dialect::PostgreSQL,
@@ -1,15 +1,34 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use clap::Parser;
 use mysql_srv::MySqlIntermediary;
 use readyset_mysql::{MySqlQueryHandler, MySqlUpstream};
 use tokio::net::TcpStream;
+use tokio_native_tls::TlsAcceptor;
 use tracing::{error, instrument};
 
 use crate::ConnectionHandler;
 
-#[derive(Clone, Copy)]
+/// readyset-mysql specific options
+#[derive(Clone, Debug, Parser)]
+pub struct Options {
+    /// Require all MySQL clients to connect using TLS.
+    ///
+    /// Clients that do not request TLS during the connection handshake will be rejected before
+    /// authentication. Requires an identity file to be specified with `--readyset-identity-file`.
+    #[clap(long, env = "MYSQL_REQUIRE_TLS", requires = "readyset_identity_file")]
+    pub mysql_require_tls: bool,
+}
+
+#[derive(Clone)]
 pub struct MySqlHandler {
     /// Whether to log statements received by the client
     pub enable_statement_logging: bool,
+    /// Optional struct to accept a TLS handshake and return a `TlsConnection`.
+    pub tls_acceptor: Option<Arc<TlsAcceptor>>,
+    /// Whether to reject clients that do not request TLS
+    pub require_tls: bool,
 }
 
 #[async_trait]
@@ -23,13 +42,15 @@ impl ConnectionHandler for MySqlHandler {
         stream: TcpStream,
         backend: readyset_adapter::Backend<MySqlUpstream, MySqlQueryHandler>,
     ) {
-        if let Err(e) = MySqlIntermediary::run_on_tcp(
+        if let Err(e) = MySqlIntermediary::run_on_tcp_with_tls(
             readyset_mysql::Backend {
                 noria: backend,
                 enable_statement_logging: self.enable_statement_logging,
             },
             stream,
             self.enable_statement_logging,
+            self.tls_acceptor.clone(),
+            self.require_tls,
         )
         .await
         {
@@ -1,12 +1,13 @@
-use std::io::Read;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use clap::Parser;
 use readyset_errors::ReadySetResult;
-use readyset_psql::{AuthenticationMethod, PostgreSqlQueryHandler, PostgreSqlUpstream};
+use readyset_psql::{
+    AuthenticationMethod, CancellationRegistry, PostgreSqlQueryHandler, PostgreSqlUpstream,
+};
 use tokio::net;
-use tokio_native_tls::{native_tls, TlsAcceptor};
+use tokio_native_tls::TlsAcceptor;
 use tracing::{error, instrument};
 
 use crate::ConnectionHandler;
@@ -14,21 +15,6 @@ use crate::ConnectionHandler;
 /// readyset-psql specific options
 #[derive(Clone, Debug, Parser)]
 pub struct Options {
-    /// The pkcs12 identity file (certificate and key) used by ReadySet for establishing TLS
-    /// connections as the server.
-    ///
-    /// ReadySet will not accept TLS connections if there is no identity file specified.
-    #[clap(long, env = "READYSET_IDENTITY_FILE")]
-    readyset_identity_file: Option<String>,
-
-    /// Password for the pkcs12 identity file used by ReadySet for establishing TLS connections as
-    /// the server.
-    ///
-    /// If password is not provided, ReadySet will try using an empty string to unlock the identity
-    /// file.
-    #[clap(long, requires = "readyset_identity_file")]
-    readyset_identity_file_password: Option<String>,
-
     /// Authentication method to use for PostgreSQL clients
     #[clap(
         long,
@@ -38,10 +24,12 @@ pub struct Options {
     postgres_authentication_method: AuthenticationMethod,
 }
 
-/// Contains psql-srv specific `Options` and whether to enable statement logging.
+/// Contains psql-srv specific `Options`, whether to enable statement logging, and the optional
+/// `TlsAcceptor` used to establish TLS connections.
 pub struct Config {
     pub options: Options,
     pub enable_statement_logging: bool,
+    pub tls_acceptor: Option<Arc<TlsAcceptor>>,
 }
 
 #[derive(Clone)]
@@ -52,41 +40,18 @@ pub struct PsqlHandler {
     pub authentication_method: AuthenticationMethod,
     /// Optional struct to accept a TLS handshake and return a `TlsConnection`.
     pub tls_acceptor: Option<Arc<TlsAcceptor>>,
-}
-
-/// Load the `native_tls::Identity` from user provided `Config`.
-fn load_pkcs12_identity(options: &Options) -> ReadySetResult<Option<native_tls::Identity>> {
-    let Some(ref path) = options.readyset_identity_file else {
-        return Ok(None);
-    };
-
-    let mut identity_file = std::fs::File::open(path)?;
-    let mut identity = vec![];
-    identity_file.read_to_end(&mut identity)?;
-
-    let password = options
-        .readyset_identity_file_password
-        .clone()
-        .unwrap_or_default();
-
-    Ok(Some(native_tls::Identity::from_pkcs12(
-        &identity, &password,
-    )?))
+    /// Registry of cancel keys for all client connections, shared between connections so that a
+    /// CancelRequest sent on one connection can cancel a query running on another
+    pub cancellation_registry: CancellationRegistry,
 }
 
 impl PsqlHandler {
     pub fn new(config: Config) -> ReadySetResult<PsqlHandler> {
-        let tls_acceptor = match load_pkcs12_identity(&config.options)? {
-            Some(identity) => Some(Arc::new(TlsAcceptor::from(native_tls::TlsAcceptor::new(
-                identity,
-            )?))),
-            None => None,
-        };
-
         Ok(PsqlHandler {
             enable_statement_logging: config.enable_statement_logging,
             authentication_method: config.options.postgres_authentication_method,
-            tls_acceptor,
+            tls_acceptor: config.tls_acceptor,
+            cancellation_registry: Default::default(),
         })
     }
 }
@@ -104,7 +69,8 @@ impl ConnectionHandler for PsqlHandler {
     ) {
         psql_srv::run_backend(
             readyset_psql::Backend::new(backend)
-                .with_authentication_method(self.authentication_method),
+                .with_authentication_method(self.authentication_method)
+                .with_cancellation(self.cancellation_registry.clone()),
             stream,
             self.enable_statement_logging,
             self.tls_acceptor.clone(),
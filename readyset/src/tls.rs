@@ -0,0 +1,59 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use clap::Parser;
+use readyset_errors::ReadySetResult;
+use tokio_native_tls::{native_tls, TlsAcceptor};
+
+/// Options for accepting TLS connections from clients, shared by the MySQL and PostgreSQL
+/// adapters
+#[derive(Clone, Debug, Parser)]
+pub struct Options {
+    /// The pkcs12 identity file (certificate and key) used by ReadySet for establishing TLS
+    /// connections as the server.
+    ///
+    /// ReadySet will not accept TLS connections if there is no identity file specified.
+    #[clap(long, env = "READYSET_IDENTITY_FILE")]
+    readyset_identity_file: Option<String>,
+
+    /// Password for the pkcs12 identity file used by ReadySet for establishing TLS connections as
+    /// the server.
+    ///
+    /// If password is not provided, ReadySet will try using an empty string to unlock the identity
+    /// file.
+    #[clap(long, requires = "readyset_identity_file")]
+    readyset_identity_file_password: Option<String>,
+}
+
+impl Options {
+    /// Load the `native_tls::Identity` from the configured identity file, if any.
+    fn load_pkcs12_identity(&self) -> ReadySetResult<Option<native_tls::Identity>> {
+        let Some(ref path) = self.readyset_identity_file else {
+            return Ok(None);
+        };
+
+        let mut identity_file = std::fs::File::open(path)?;
+        let mut identity = vec![];
+        identity_file.read_to_end(&mut identity)?;
+
+        let password = self
+            .readyset_identity_file_password
+            .clone()
+            .unwrap_or_default();
+
+        Ok(Some(native_tls::Identity::from_pkcs12(
+            &identity, &password,
+        )?))
+    }
+
+    /// Build a [`TlsAcceptor`] from the configured identity file, or return `None` if no identity
+    /// file was specified.
+    pub fn tls_acceptor(&self) -> ReadySetResult<Option<Arc<TlsAcceptor>>> {
+        Ok(match self.load_pkcs12_identity()? {
+            Some(identity) => Some(Arc::new(TlsAcceptor::from(native_tls::TlsAcceptor::new(
+                identity,
+            )?))),
+            None => None,
+        })
+    }
+}
@@ -10,6 +10,7 @@ use mysql_async as mysql;
 use mysql_common::binlog;
 use mysql_common::binlog::row::BinlogRow;
 use mysql_common::binlog::value::BinlogValue;
+use mysql_common::packets::BinlogDumpFlags;
 use nom_sql::Relation;
 use readyset_client::metrics::recorded;
 use readyset_client::recipe::ChangeList;
@@ -18,7 +19,8 @@ use readyset_data::{DfValue, Dialect};
 use readyset_errors::{internal_err, ReadySetError, ReadySetResult};
 use tracing::{info, warn};
 
-use super::BinlogPosition;
+use super::gtid::{GtidSet, ServerUuid};
+use super::{BinlogPosition, MySqlPosition};
 use crate::noria_adapter::{Connector, ReplicationAction};
 
 const CHECKSUM_QUERY: &str = "SET @master_binlog_checksum='CRC32'";
@@ -47,10 +49,10 @@ pub(crate) struct MySqlBinlogConnector {
     /// if one is not assigned we will use (u32::MAX - 55)
     server_id: Option<u32>,
     /// If we just want to continue reading the binlog from a previous point
-    next_position: BinlogPosition,
-    /// The GTID of the current transaction. Table modification events will have
-    /// the current GTID attached if enabled in mysql.
-    current_gtid: Option<u64>,
+    next_position: MySqlPosition,
+    /// The server UUID and transaction number of the GTID of the current transaction. Table
+    /// modification events will have the current GTID attached if enabled in mysql.
+    current_gtid: Option<(ServerUuid, u64)>,
     /// Whether to log statements received by the connector
     enable_statement_logging: bool,
 }
@@ -170,13 +172,25 @@ impl MySqlBinlogConnector {
         Ok(())
     }
 
-    /// After we have registered as a replica, we can request the binlog
+    /// After we have registered as a replica, we can request the binlog, either from a binlog file
+    /// and offset with `COM_BINLOG_DUMP`, or with `COM_BINLOG_DUMP_GTID` from the first transaction
+    /// not in a set of executed GTIDs
     async fn request_binlog(&mut self) -> mysql::Result<()> {
-        let cmd = mysql_common::packets::ComBinlogDump::new(self.server_id())
-            .with_pos(self.next_position.position)
-            .with_filename(self.next_position.binlog_file.as_bytes());
+        match &self.next_position {
+            MySqlPosition::Binlog(pos) => {
+                let cmd = mysql_common::packets::ComBinlogDump::new(self.server_id())
+                    .with_pos(pos.position)
+                    .with_filename(pos.binlog_file.as_bytes());
+                self.connection.write_command(&cmd).await?;
+            }
+            MySqlPosition::Gtid(pos) => {
+                let cmd = mysql_common::packets::ComBinlogDumpGtid::new(self.server_id())
+                    .with_flags(BinlogDumpFlags::BINLOG_THROUGH_GTID)
+                    .with_sids(pos.executed.to_sids());
+                self.connection.write_command(&cmd).await?;
+            }
+        }
 
-        self.connection.write_command(&cmd).await?;
         self.connection.read_packet().await?;
         Ok(())
     }
@@ -196,10 +210,39 @@ impl MySqlBinlogConnector {
         true
     }
 
+    /// When resuming replication from a GTID position, check that the upstream has executed every
+    /// transaction we've already replicated.
+    ///
+    /// If it hasn't, the server we replicated those transactions from had errant transactions
+    /// which never made it to the upstream (for example if a replica was promoted to be the new
+    /// upstream), so the data we've replicated has diverged from the upstream's.
+    async fn check_gtid_position(&mut self) -> ReadySetResult<()> {
+        let MySqlPosition::Gtid(pos) = &self.next_position else {
+            return Ok(());
+        };
+
+        let executed: GtidSet = self
+            .connection
+            .query_first::<String, _>("SELECT @@GLOBAL.gtid_executed")
+            .await?
+            .unwrap_or_default()
+            .parse()?;
+        if !pos.executed.is_subset(&executed) {
+            return Err(ReadySetError::ReplicationFailed(format!(
+                "Upstream is missing transactions which have already been replicated (executed \
+                 GTID set {executed}, replication position {}), which may be caused by errant \
+                 transactions",
+                pos.executed
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Connect to a given MySQL database and subscribe to the binlog
     pub(crate) async fn connect<O: Into<mysql::Opts>>(
         mysql_opts: O,
-        next_position: BinlogPosition,
+        next_position: MySqlPosition,
         server_id: Option<u32>,
         enable_statement_logging: bool,
     ) -> ReadySetResult<Self> {
@@ -212,6 +255,7 @@ impl MySqlBinlogConnector {
             enable_statement_logging,
         };
 
+        connector.check_gtid_position().await?;
         connector.register_as_replica().await?;
         connector.request_binlog().await?;
 
@@ -238,20 +282,38 @@ impl MySqlBinlogConnector {
     pub(crate) async fn next_action_inner(
         &mut self,
         until: Option<&ReplicationOffset>,
-    ) -> mysql::Result<(ReplicationAction, &BinlogPosition)> {
+    ) -> mysql::Result<(ReplicationAction, &MySqlPosition)> {
         use mysql_common::binlog::events;
 
         loop {
             let binlog_event = self.next_event().await?;
-
-            self.next_position.position = binlog_event.header().log_pos();
-
-            match binlog_event.header().event_type().map_err(|ev| {
+            let event_type = binlog_event.header().event_type().map_err(|ev| {
                 mysql_async::Error::Other(Box::new(internal_err!(
                     "Unknown binlog event type {}",
                     ev
                 )))
-            })? {
+            })?;
+
+            match &mut self.next_position {
+                MySqlPosition::Binlog(pos) => pos.position = binlog_event.header().log_pos(),
+                MySqlPosition::Gtid(pos) => {
+                    // Events that describe the binlog stream itself rather than a transaction
+                    // aren't necessarily sent again when reading the same transaction from another
+                    // position or another server, so they don't count towards the position
+                    if !matches!(
+                        event_type,
+                        EventType::ROTATE_EVENT
+                            | EventType::FORMAT_DESCRIPTION_EVENT
+                            | EventType::PREVIOUS_GTIDS_EVENT
+                            | EventType::HEARTBEAT_EVENT
+                            | EventType::STOP_EVENT
+                    ) {
+                        pos.event += 1;
+                    }
+                }
+            }
+
+            match event_type {
                 EventType::ROTATE_EVENT => {
                     // Written when mysqld switches to a new binary log file.
                     // This occurs when someone issues a FLUSH LOGS statement or the current binary
@@ -262,14 +324,17 @@ impl MySqlBinlogConnector {
                         info!(target: "replicator_statement", "{:?}", ev);
                     }
 
-                    self.next_position = BinlogPosition {
-                        binlog_file: ev.name().to_string(),
-                        // This should never happen, but better to panic than to get the wrong
-                        // position
-                        position: u32::try_from(ev.position()).unwrap(),
-                    };
+                    // Positions identified by GTIDs don't depend on the binlog file
+                    if let MySqlPosition::Binlog(pos) = &mut self.next_position {
+                        *pos = BinlogPosition {
+                            binlog_file: ev.name().to_string(),
+                            // This should never happen, but better to panic than to get the wrong
+                            // position
+                            position: u32::try_from(ev.position()).unwrap(),
+                        };
 
-                    return Ok((ReplicationAction::LogPosition, &self.next_position));
+                        return Ok((ReplicationAction::LogPosition, &self.next_position));
+                    }
                 }
 
                 EventType::QUERY_EVENT => {
@@ -367,7 +432,7 @@ impl MySqlBinlogConnector {
                                 name: tme.table_name().into(),
                             },
                             actions: inserted_rows,
-                            txid: self.current_gtid,
+                            txid: self.current_gtid.map(|(_, gno)| gno),
                         },
                         &self.next_position,
                     ));
@@ -426,7 +491,7 @@ impl MySqlBinlogConnector {
                                 name: tme.table_name().into(),
                             },
                             actions: updated_rows,
-                            txid: self.current_gtid,
+                            txid: self.current_gtid.map(|(_, gno)| gno),
                         },
                         &self.next_position,
                     ));
@@ -470,7 +535,7 @@ impl MySqlBinlogConnector {
                                 name: tme.table_name().into(),
                             },
                             actions: deleted_rows,
-                            txid: self.current_gtid,
+                            txid: self.current_gtid.map(|(_, gno)| gno),
                         },
                         &self.next_position,
                     ));
@@ -498,7 +563,15 @@ impl MySqlBinlogConnector {
                     if self.enable_statement_logging {
                         info!(target: "replicator_statement", "{:?}", ev);
                     }
-                    self.current_gtid = Some(ev.gno());
+                    if let MySqlPosition::Gtid(pos) = &mut self.next_position {
+                        // We've seen every event of the previous transaction, so it's now part of
+                        // the set of transactions executed before the position
+                        if let Some((sid, gno)) = self.current_gtid {
+                            pos.executed.insert(sid, gno);
+                        }
+                        pos.event = 0;
+                    }
+                    self.current_gtid = Some((ev.sid(), ev.gno()));
                 }
 
                 /*
@@ -557,8 +630,9 @@ impl MySqlBinlogConnector {
             // We didn't get an actionable event, but we still need to check that we haven't reached
             // the until limit
             if let Some(limit) = until {
-                let limit = BinlogPosition::try_from(limit).expect("Valid binlog limit");
-                if self.next_position >= limit {
+                let pos = ReplicationOffset::try_from(&self.next_position)
+                    .map_err(|e| mysql_async::Error::Other(Box::new(e)))?;
+                if pos >= *limit {
                     return Ok((ReplicationAction::LogPosition, &self.next_position));
                 }
             }
@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use mysql_common::packets::{GnoInterval, Sid};
+use readyset_client::replication::{ReplicationOffset, GTID_LOG_PREFIX};
+use readyset_errors::{invalid_err, ReadySetError, ReadySetResult};
+
+/// The UUID of the server a transaction originated on, which together with a transaction number
+/// forms a GTID
+pub(crate) type ServerUuid = [u8; 16];
+
+/// A set of MySQL global transaction identifiers, such as the set of transactions executed by a
+/// server (`@@GLOBAL.gtid_executed`).
+///
+/// Each GTID is made up of the UUID of the server the transaction originated on and a transaction
+/// number, and is preserved as the transaction is replicated, so a replica that has applied the
+/// same transactions as its primary has the same set of executed GTIDs.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct GtidSet {
+    /// Sorted, non-overlapping, non-adjacent inclusive ranges of transaction numbers for each
+    /// server UUID
+    intervals: BTreeMap<ServerUuid, Vec<(u64, u64)>>,
+}
+
+impl GtidSet {
+    /// Returns the number of transactions in the set
+    pub(crate) fn len(&self) -> u64 {
+        self.intervals
+            .values()
+            .flatten()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Returns true if the set contains no transactions
+    pub(crate) fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns true if every transaction in this set is also in `other`
+    pub(crate) fn is_subset(&self, other: &GtidSet) -> bool {
+        self.intervals.iter().all(|(uuid, intervals)| {
+            let Some(other_intervals) = other.intervals.get(uuid) else {
+                return false;
+            };
+            intervals
+                .iter()
+                .all(|(start, end)| other_intervals.iter().any(|(s, e)| s <= start && end <= e))
+        })
+    }
+
+    /// Add the transaction with the given server UUID and number to the set
+    pub(crate) fn insert(&mut self, uuid: ServerUuid, gno: u64) {
+        self.insert_interval(uuid, gno, gno)
+    }
+
+    /// Add the transactions with the given server UUID and numbers from `start` to `end`
+    /// (inclusive) to the set
+    fn insert_interval(&mut self, uuid: ServerUuid, start: u64, end: u64) {
+        let intervals = self.intervals.entry(uuid).or_default();
+        // Intervals that end before the new one starts, and aren't adjacent to it, are unaffected,
+        // as are intervals that start after the new one ends
+        let first = intervals.partition_point(|(_, e)| e.saturating_add(1) < start);
+        let last = intervals.partition_point(|(s, _)| *s <= end.saturating_add(1));
+        // Everything in between overlaps or is adjacent to the new interval, so merge it all
+        let merged = intervals
+            .drain(first..last)
+            .fold((start, end), |(start, end), (s, e)| {
+                (start.min(s), end.max(e))
+            });
+        intervals.insert(first, merged);
+    }
+
+    /// Returns the set in the format expected by `COM_BINLOG_DUMP_GTID`
+    pub(crate) fn to_sids(&self) -> Vec<Sid<'static>> {
+        self.intervals
+            .iter()
+            .map(|(uuid, intervals)| {
+                intervals.iter().fold(Sid::new(*uuid), |sid, (start, end)| {
+                    // `GnoInterval`s exclude their end
+                    sid.with_interval(GnoInterval::new(*start, end + 1))
+                })
+            })
+            .collect()
+    }
+}
+
+/// A position in a MySQL replication stream identified by GTIDs rather than by binlog file and
+/// offset, which is preserved across replicas, so it remains valid if a replica is promoted to be
+/// the primary.
+///
+/// Every event belongs to a transaction, so the position of an event is identified by the set of
+/// transactions executed before the one it belongs to, along with the index of the event within
+/// that transaction.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct GtidPosition {
+    /// The set of transactions executed before the one the position is within
+    pub(crate) executed: GtidSet,
+    /// The index of the event at this position within its transaction, where the `GTID_EVENT`
+    /// starting the transaction is at index 0
+    pub(crate) event: u64,
+}
+
+impl From<&GtidPosition> for ReplicationOffset {
+    /// The executed GTID set is stored in the `replication_log_name`, after [`GTID_LOG_PREFIX`].
+    /// The top 64 bits of the offset are the number of transactions in that set, and the bottom
+    /// 64 bits are the index of the event, which orders positions as long as transactions are
+    /// applied in the same order by all servers in the replication topology.
+    fn from(value: &GtidPosition) -> Self {
+        ReplicationOffset {
+            offset: ((value.executed.len() as u128) << 64) + (value.event as u128),
+            replication_log_name: format!("{GTID_LOG_PREFIX}{}", value.executed),
+        }
+    }
+}
+
+impl TryFrom<&ReplicationOffset> for GtidPosition {
+    type Error = ReadySetError;
+
+    fn try_from(value: &ReplicationOffset) -> Result<Self, Self::Error> {
+        let executed = value
+            .replication_log_name
+            .strip_prefix(GTID_LOG_PREFIX)
+            .ok_or_else(|| invalid_err!("Replication offset {value} is not a GTID position"))?
+            .parse()?;
+        Ok(GtidPosition {
+            executed,
+            event: value.offset as u64,
+        })
+    }
+}
+
+/// Checks that the executed GTID sets of all the given offsets which are positioned using GTIDs
+/// are subsets of one another.
+///
+/// GTID offsets are ordered by the number of transactions in their sets, which is only meaningful
+/// if every set contains all of the transactions in the smaller ones. Sets which don't mean that
+/// the offsets were taken on servers with errant transactions - transactions that were executed on
+/// one server in the replication topology but never replicated to the others - so we can't tell
+/// which of the offsets is further along.
+pub(crate) fn check_gtid_offsets<'a>(
+    offsets: impl IntoIterator<Item = &'a ReplicationOffset>,
+) -> ReadySetResult<()> {
+    let mut positions = offsets
+        .into_iter()
+        .filter(|offset| offset.replication_log_name.starts_with(GTID_LOG_PREFIX))
+        .map(GtidPosition::try_from)
+        .collect::<ReadySetResult<Vec<_>>>()?;
+    positions.sort_by_key(|pos| pos.executed.len());
+
+    for (smaller, larger) in positions.iter().zip(positions.iter().skip(1)) {
+        if !smaller.executed.is_subset(&larger.executed) {
+            return Err(ReadySetError::ReplicationFailed(format!(
+                "Replication offsets have diverging GTID sets {} and {}, which may be caused by \
+                 errant transactions",
+                smaller.executed, larger.executed
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_uuid(s: &str) -> ReadySetResult<ServerUuid> {
+    let hex = s.trim().replace('-', "");
+    if hex.len() != 32 {
+        return Err(invalid_err!("Invalid server UUID in GTID set: {s}"));
+    }
+    let mut uuid = ServerUuid::default();
+    for (i, byte) in uuid.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| invalid_err!("Invalid server UUID in GTID set: {s}"))?;
+    }
+    Ok(uuid)
+}
+
+impl FromStr for GtidSet {
+    type Err = ReadySetError;
+
+    /// Parse a GTID set in the format used by MySQL, such as
+    /// `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:11,2174b383-5441-11e8-b90a-c80aa9429562:1-27`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = GtidSet::default();
+        for uuid_set in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let mut parts = uuid_set.split(':');
+            let uuid = parse_uuid(parts.next().unwrap_or_default())?;
+            for interval in parts {
+                let parse_gno = |gno: &str| {
+                    gno.trim()
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|gno| *gno > 0)
+                        .ok_or_else(|| invalid_err!("Invalid interval in GTID set: {interval}"))
+                };
+                let (start, end) = match interval.split_once('-') {
+                    Some((start, end)) => (parse_gno(start)?, parse_gno(end)?),
+                    None => (parse_gno(interval)?, parse_gno(interval)?),
+                };
+                if start > end {
+                    return Err(invalid_err!("Invalid interval in GTID set: {interval}"));
+                }
+                set.insert_interval(uuid, start, end);
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl Display for GtidSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (uuid, intervals)) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            for (j, byte) in uuid.iter().enumerate() {
+                if matches!(j, 4 | 6 | 8 | 10) {
+                    write!(f, "-")?;
+                }
+                write!(f, "{byte:02x}")?;
+            }
+            for (start, end) in intervals {
+                if start == end {
+                    write!(f, ":{start}")?;
+                } else {
+                    write!(f, ":{start}-{end}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID1: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+    const UUID2: &str = "2174b383-5441-11e8-b90a-c80aa9429562";
+
+    #[test]
+    fn parse_and_display() {
+        let s = format!("{UUID1}:1-5:11,\n{UUID2}:1-27");
+        let set = s.parse::<GtidSet>().unwrap();
+        assert_eq!(set.len(), 33);
+        // Sets are displayed ordered by UUID
+        assert_eq!(set.to_string(), format!("{UUID2}:1-27,{UUID1}:1-5:11"));
+        assert_eq!(set.to_string().parse::<GtidSet>().unwrap(), set);
+    }
+
+    #[test]
+    fn parse_empty() {
+        let set = "".parse::<GtidSet>().unwrap();
+        assert!(set.is_empty());
+        assert_eq!(set.len(), 0);
+        assert_eq!(set.to_string(), "");
+    }
+
+    #[test]
+    fn parse_overlapping_intervals() {
+        let set = format!("{UUID1}:4-8:1-5:10").parse::<GtidSet>().unwrap();
+        assert_eq!(set.to_string(), format!("{UUID1}:1-8:10"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        format!("{UUID1}:0").parse::<GtidSet>().unwrap_err();
+        format!("{UUID1}:5-1").parse::<GtidSet>().unwrap_err();
+        format!("{UUID1}:a").parse::<GtidSet>().unwrap_err();
+        "not-a-uuid:1".parse::<GtidSet>().unwrap_err();
+    }
+
+    #[test]
+    fn replication_offset_round_trip() {
+        let pos = GtidPosition {
+            executed: format!("{UUID1}:1-5:11,{UUID2}:1-27").parse().unwrap(),
+            event: 3,
+        };
+        let offset = ReplicationOffset::from(&pos);
+        assert_eq!(offset.offset, (33 << 64) + 3);
+        assert_eq!(GtidPosition::try_from(&offset).unwrap(), pos);
+
+        let next_transaction = GtidPosition {
+            executed: format!("{UUID1}:1-6:11,{UUID2}:1-27").parse().unwrap(),
+            event: 0,
+        };
+        assert!(ReplicationOffset::from(&next_transaction) > offset);
+    }
+
+    #[test]
+    fn is_subset() {
+        let set = |s: String| s.parse::<GtidSet>().unwrap();
+        let full = set(format!("{UUID1}:1-10:20,{UUID2}:1-5"));
+
+        assert!(GtidSet::default().is_subset(&full));
+        assert!(full.is_subset(&full));
+        assert!(set(format!("{UUID1}:2-4:20")).is_subset(&full));
+        assert!(set(format!("{UUID1}:1-5,{UUID2}:3")).is_subset(&full));
+
+        assert!(!full.is_subset(&GtidSet::default()));
+        assert!(!set(format!("{UUID1}:9-11")).is_subset(&full));
+        assert!(!set(format!("{UUID2}:1-6")).is_subset(&full));
+        assert!(!set(format!("{UUID1}:1,3e11fa47-71ca-11e1-9e33-c80aa9429563:1")).is_subset(&full));
+    }
+
+    #[test]
+    fn check_gtid_offsets_errant_transactions() {
+        let offset = |executed: String| {
+            ReplicationOffset::from(&GtidPosition {
+                executed: executed.parse().unwrap(),
+                event: 0,
+            })
+        };
+
+        check_gtid_offsets(&[
+            offset(format!("{UUID1}:1-7,{UUID2}:1")),
+            offset(format!("{UUID1}:1-5")),
+            offset(format!("{UUID1}:1-6")),
+        ])
+        .unwrap();
+
+        // The second offset has a transaction from UUID2 which the third is missing
+        check_gtid_offsets(&[
+            offset(format!("{UUID1}:1-5")),
+            offset(format!("{UUID1}:1-5,{UUID2}:1")),
+            offset(format!("{UUID1}:1-7")),
+        ])
+        .unwrap_err();
+    }
+
+    #[test]
+    fn insert() {
+        let uuid = parse_uuid(UUID1).unwrap();
+        let mut set = GtidSet::default();
+        set.insert(uuid, 1);
+        set.insert(uuid, 2);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-2"));
+
+        set.insert(uuid, 5);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-2:5"));
+
+        set.insert(uuid, 4);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-2:4-5"));
+
+        set.insert(uuid, 3);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-5"));
+
+        set.insert(uuid, 2);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-5"));
+        assert_eq!(set.len(), 5);
+    }
+}
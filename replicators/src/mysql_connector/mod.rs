@@ -1,7 +1,13 @@
 mod connector;
+mod gtid;
 mod snapshot;
 
+use std::convert::{TryFrom, TryInto};
+
 pub(crate) use connector::MySqlBinlogConnector;
+pub(crate) use gtid::{check_gtid_offsets, GtidPosition};
+use readyset_client::replication::{ReplicationOffset, GTID_LOG_PREFIX};
+use readyset_errors::ReadySetError;
 pub(crate) use snapshot::MySqlReplicator;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -9,3 +15,45 @@ pub struct BinlogPosition {
     pub binlog_file: String,
     pub position: u32,
 }
+
+/// A position in a MySQL replication stream, identified either by binlog file and offset, or by
+/// GTIDs if they are enabled on the upstream
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum MySqlPosition {
+    /// A binlog file name and an offset within it. These are only meaningful on the server that
+    /// wrote the binlog.
+    Binlog(BinlogPosition),
+    /// A position identified by GTIDs, which remains valid across the replication topology
+    Gtid(GtidPosition),
+}
+
+impl TryFrom<&MySqlPosition> for ReplicationOffset {
+    type Error = ReadySetError;
+
+    fn try_from(value: &MySqlPosition) -> Result<Self, Self::Error> {
+        match value {
+            MySqlPosition::Binlog(pos) => pos.try_into(),
+            MySqlPosition::Gtid(pos) => Ok(pos.into()),
+        }
+    }
+}
+
+impl TryFrom<MySqlPosition> for ReplicationOffset {
+    type Error = ReadySetError;
+
+    fn try_from(value: MySqlPosition) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl TryFrom<&ReplicationOffset> for MySqlPosition {
+    type Error = ReadySetError;
+
+    fn try_from(value: &ReplicationOffset) -> Result<Self, Self::Error> {
+        if value.replication_log_name.starts_with(GTID_LOG_PREFIX) {
+            Ok(MySqlPosition::Gtid(value.try_into()?))
+        } else {
+            Ok(MySqlPosition::Binlog(value.into()))
+        }
+    }
+}
@@ -1,18 +1,21 @@
+use std::collections::{HashMap, VecDeque};
 use std::convert::{TryFrom, TryInto};
 use std::error::Error;
 use std::fmt::{self, Display};
 use std::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::Instant;
 
-use futures::future::TryFutureExt;
-use futures::stream::FuturesUnordered;
+use futures::future::{try_join_all, TryFutureExt};
+use futures::stream::{self, FuturesUnordered};
 use futures::StreamExt;
 use itertools::Itertools;
 use metrics::register_gauge;
 use mysql::prelude::Queryable;
 use mysql::{Transaction, TxOpts};
 use mysql_async as mysql;
-use nom_sql::Relation;
+use nom_sql::{Relation, SqlIdentifier};
 use readyset_client::metrics::recorded;
 use readyset_client::recipe::changelist::{Change, ChangeList};
 use readyset_client::replication::{ReplicationOffset, ReplicationOffsets};
@@ -22,8 +25,12 @@ use tokio::task::JoinHandle;
 use tracing::{debug, error, info, info_span, warn};
 use tracing_futures::Instrument;
 
-use super::BinlogPosition;
+use super::{BinlogPosition, GtidPosition, MySqlPosition};
 use crate::db_util::DatabaseSchemas;
+use crate::snapshot_progress::{
+    chunk_key_column, ChunkTracker, ChunkingConfig, SnapshotChunk, SnapshotProgressStore,
+    TableSnapshotProgress,
+};
 use crate::table_filter::TableFilter;
 
 const BATCH_SIZE: usize = 1000; // How many queries to buffer before pushing to ReadySet
@@ -51,6 +58,12 @@ pub(crate) struct MySqlReplicator {
     pub(crate) pool: mysql::Pool,
     /// Filters out the desired tables to snapshot and replicate
     pub(crate) table_filter: TableFilter,
+    /// Whether to identify replication positions by GTID rather than by binlog file and offset
+    pub(crate) use_gtid: bool,
+    /// How to split large tables into chunks while snapshotting
+    pub(crate) chunking: ChunkingConfig,
+    /// Records progress through snapshotting chunked tables
+    pub(crate) snapshot_progress: SnapshotProgressStore,
 }
 
 /// Get the list of tables defined in the database
@@ -309,10 +322,8 @@ impl MySqlReplicator {
         Ok((tx, table_list))
     }
 
-    /// Call `SELECT * FROM table` and convert all rows into a ReadySet row
-    /// it may seem inefficient but apparently that is the correct way to
-    /// replicate a table, and `mysqldump` and `debezium` do just that
-    pub(crate) async fn dump_table(&self, table: &Relation) -> mysql::Result<TableDumper> {
+    /// Start a transaction with a consistent snapshot to read table contents from
+    async fn start_dump_transaction(&self) -> mysql::Result<Transaction<'static>> {
         let mut tx = self
             .pool
             .start_transaction(tx_opts())
@@ -324,6 +335,15 @@ impl MySqlReplicator {
             .await
             .map_err(log_err);
 
+        Ok(tx)
+    }
+
+    /// Call `SELECT * FROM table` and convert all rows into a ReadySet row
+    /// it may seem inefficient but apparently that is the correct way to
+    /// replicate a table, and `mysqldump` and `debezium` do just that
+    pub(crate) async fn dump_table(&self, table: &Relation) -> mysql::Result<TableDumper> {
+        let tx = self.start_dump_transaction().await?;
+
         let query_count = format!(
             "select count(*) from {}",
             table.display(nom_sql::Dialect::MySQL)
@@ -337,8 +357,9 @@ impl MySqlReplicator {
     }
 
     /// Use the SHOW MASTER STATUS statement to determine the current binary log
-    /// file name and position.
-    async fn get_binlog_position(&self) -> mysql::Result<BinlogPosition> {
+    /// file name and position, or the set of GTIDs executed so far if we're identifying positions
+    /// by GTID.
+    async fn get_binlog_position(&self) -> mysql::Result<MySqlPosition> {
         let mut conn = self.pool.get_conn().await?;
         let query = "SHOW MASTER STATUS";
         let pos: mysql::Row = conn.query_first(query).await?.ok_or_else(|| {
@@ -349,13 +370,27 @@ impl MySqlReplicator {
             )))
         })?;
 
+        if self.use_gtid {
+            let executed: String = pos.get(4).ok_or_else(|| {
+                mysql_async::Error::Other(Box::new(internal_err!(
+                    "Missing Executed_Gtid_Set in response for SHOW MASTER STATUS"
+                )))
+            })?;
+            return Ok(MySqlPosition::Gtid(GtidPosition {
+                executed: executed
+                    .parse()
+                    .map_err(|e| mysql_async::Error::Other(Box::new(e)))?,
+                event: 0,
+            }));
+        }
+
         let file: String = pos.get(0).expect("Binlog file name");
         let offset: u32 = pos.get(1).expect("Binlog offset");
 
-        Ok(BinlogPosition {
+        Ok(MySqlPosition::Binlog(BinlogPosition {
             binlog_file: file,
             position: offset,
-        })
+        }))
     }
 
     /// Issue a `LOCK TABLES tbl_name READ` for the table name provided
@@ -453,6 +488,148 @@ impl MySqlReplicator {
         Ok(())
     }
 
+    /// Resume snapshotting the given table from the progress recorded by an earlier, interrupted
+    /// attempt, or otherwise decide whether to split the table into chunks to copy it, based on
+    /// the number of rows it contains, and if so plan the chunks
+    async fn chunk_progress(
+        &self,
+        table: &Relation,
+        table_mutator: &mut readyset_client::Table,
+        node: usize,
+    ) -> ReadySetResult<Option<TableSnapshotProgress>> {
+        let key = table_mutator.schema().and_then(chunk_key_column);
+        if let Some(progress) = self
+            .snapshot_progress
+            .resume(table_mutator, node, key.as_ref())
+            .await?
+        {
+            return Ok(Some(progress));
+        }
+        let Some((key_column, key_index)) = key else {
+            return Ok(None);
+        };
+
+        let mut conn = self.pool.get_conn().await?;
+        let nrows: u64 = conn
+            .query_first(format!(
+                "select count(*) from {}",
+                table.display(nom_sql::Dialect::MySQL)
+            ))
+            .await
+            .map_err(log_err)?
+            .unwrap_or(0);
+        let Some(num_chunks) = self.chunking.num_chunks(nrows) else {
+            return Ok(None);
+        };
+
+        let bounds: Option<(Option<i64>, Option<i64>)> = conn
+            .query_first(format!(
+                "select min(`{key_column}`), max(`{key_column}`) from {}",
+                table.display(nom_sql::Dialect::MySQL)
+            ))
+            .await
+            .map_err(log_err)?;
+        let Some((Some(min), Some(max))) = bounds else {
+            return Ok(None);
+        };
+
+        let progress = TableSnapshotProgress::new(
+            table.clone(),
+            node,
+            key_column,
+            key_index,
+            min,
+            max,
+            num_chunks,
+        );
+        self.snapshot_progress.save(&progress).await?;
+        Ok(Some(progress))
+    }
+
+    /// Replicate the given chunks of a single table into ReadySet, using each of the provided
+    /// transactions to copy one chunk at a time until none are left, then set the table's
+    /// replication offset and take it out of snapshot mode
+    async fn replicate_chunks(
+        txs: Vec<Transaction<'static>>,
+        chunks: Vec<(usize, SnapshotChunk)>,
+        tracker: ChunkTracker,
+        key_column: SqlIdentifier,
+        repl_offset: ReplicationOffset,
+        mut table_mutator: readyset_client::Table,
+    ) -> ReadySetResult<()> {
+        let num_chunks = chunks.len();
+        info!(chunks = %num_chunks, "Replication started");
+
+        table_mutator.set_snapshot_mode(true).await?;
+        let progress_percentage_metric: metrics::Gauge = register_gauge!(
+            recorded::REPLICATOR_SNAPSHOT_PERCENT,
+            "name" => table_mutator.table_name().display(nom_sql::Dialect::MySQL).to_string(),
+        );
+
+        let table = table_mutator.table_name().clone();
+        let chunks = Mutex::new(VecDeque::from(chunks));
+        let chunks_copied = AtomicUsize::new(0);
+        let workers = txs.into_iter().map(|mut tx| {
+            let mut table_mutator = table_mutator.clone();
+            let (table, chunks, chunks_copied) = (&table, &chunks, &chunks_copied);
+            let (tracker, key_column, repl_offset) = (&tracker, &key_column, &repl_offset);
+            let progress_percentage_metric = &progress_percentage_metric;
+            async move {
+                let mut cnt = 0;
+                loop {
+                    let next = chunks.lock().expect("Chunk queue poisoned").pop_front();
+                    let Some((index, chunk)) = next else {
+                        break;
+                    };
+
+                    let query = format!(
+                        "select * from {} where {} order by `{}`",
+                        table.display(nom_sql::Dialect::MySQL),
+                        chunk.condition(&format!("`{key_column}`")),
+                        key_column
+                    );
+                    let row_stream = TableStream {
+                        query: tx.exec_iter(query.as_str(), ()).await.map_err(log_err)?,
+                    };
+                    let rows = stream::unfold(row_stream, |mut row_stream| async move {
+                        row_stream
+                            .next()
+                            .await
+                            .transpose()
+                            .map(|row| (row, row_stream))
+                    });
+                    cnt += tracker
+                        .copy_chunk(index, repl_offset, rows, &mut table_mutator)
+                        .await
+                        .map_err(log_err)?;
+
+                    let copied = chunks_copied.fetch_add(1, Ordering::Relaxed) + 1;
+                    let progress_percent = (copied as f64 / num_chunks as f64) * 100.;
+                    let progress = format!("{:.2}%", progress_percent);
+                    info!(%progress, "Snapshotting progress");
+                    progress_percentage_metric.set(progress_percent);
+                }
+                ReadySetResult::Ok(cnt)
+            }
+        });
+
+        let cnt: usize = try_join_all(workers)
+            .await
+            .map_err(|err| {
+                progress_percentage_metric.set(0.0);
+                err
+            })?
+            .into_iter()
+            .sum();
+
+        tracker.finish(&mut table_mutator).await.map_err(log_err)?;
+
+        info!(rows_replicated = %cnt, "Replication finished");
+        progress_percentage_metric.set(100.0);
+
+        Ok(())
+    }
+
     /// This function replicates an entire MySQL database into a clean
     /// ReadySet deployment.
     ///
@@ -532,17 +709,34 @@ impl MySqlReplicator {
     }
 
     /// Spawns a new tokio task that replicates a given table to noria, returning
-    /// the join handle
+    /// the join handle.
+    ///
+    /// The task returns the replication offset that should be set for the table once it's been
+    /// replicated, or `None` if the table was copied in chunks, in which case its replication
+    /// offset has already been set.
     async fn dumper_task_for_table(
         &mut self,
         noria: &mut readyset_client::ReadySetHandle,
+        nodes: &HashMap<Relation, usize>,
         table: Relation,
         snapshot_report_interval_secs: u16,
-    ) -> ReadySetResult<JoinHandle<(Relation, ReplicationOffset, ReadySetResult<()>)>> {
+    ) -> ReadySetResult<JoinHandle<(Relation, ReadySetResult<Option<ReplicationOffset>>)>> {
         let span = info_span!(
             "Snapshotting table",
             table = %table.display(nom_sql::Dialect::MySQL)
         );
+        let mut table_mutator = noria
+            .table_with_standbys(table.clone())
+            .instrument(span.clone())
+            .await?;
+        let node = *nodes
+            .get(&table)
+            .ok_or_else(|| internal_err!("Snapshotting table missing from ReadySet"))?;
+        let progress = self
+            .chunk_progress(&table, &mut table_mutator, node)
+            .instrument(span.clone())
+            .await?;
+
         span.in_scope(|| info!("Acquiring read lock"));
         let mut read_lock = self.lock_table(&table).await?;
         // We acquire the position for each table individually, since it changes from
@@ -550,23 +744,62 @@ impl MySqlReplicator {
         let repl_offset = ReplicationOffset::try_from(self.get_binlog_position().await?)?;
         span.in_scope(|| info!("Snapshotting table"));
 
-        let dumper = self.dump_table(&table).instrument(span.clone()).await?;
+        let dump = match progress {
+            Some(progress) => {
+                // Start a transaction for each chunk we'll copy concurrently, all of which will
+                // see the table at the same binlog position
+                let chunks = progress.remaining_chunks();
+                let mut txs = Vec::new();
+                for _ in 0..self.chunking.parallelism.min(chunks.len()) {
+                    txs.push(
+                        self.start_dump_transaction()
+                            .instrument(span.clone())
+                            .await?,
+                    );
+                }
+                let key_column = progress.key_column.clone();
+                let tracker = ChunkTracker::new(progress, self.snapshot_progress.clone());
+                TableDump::Chunks {
+                    txs,
+                    chunks,
+                    tracker,
+                    key_column,
+                }
+            }
+            None => TableDump::Whole(self.dump_table(&table).instrument(span.clone()).await?),
+        };
 
         // At this point we have a transaction that will see *that* table at *this* binlog
         // position, so we can drop the read lock
         read_lock.query_drop("UNLOCK TABLES").await?;
         span.in_scope(|| info!("Read lock released"));
 
-        let table_mutator = noria.table(table.clone()).instrument(span.clone()).await?;
-
         Ok(tokio::spawn(async move {
-            (
-                table,
-                repl_offset,
-                Self::replicate_table(dumper, table_mutator, snapshot_report_interval_secs)
-                    .instrument(span)
-                    .await,
-            )
+            let result = match dump {
+                TableDump::Whole(dumper) => {
+                    Self::replicate_table(dumper, table_mutator, snapshot_report_interval_secs)
+                        .instrument(span)
+                        .await
+                        .map(|()| Some(repl_offset))
+                }
+                TableDump::Chunks {
+                    txs,
+                    chunks,
+                    tracker,
+                    key_column,
+                } => Self::replicate_chunks(
+                    txs,
+                    chunks,
+                    tracker,
+                    key_column,
+                    repl_offset,
+                    table_mutator,
+                )
+                .instrument(span)
+                .await
+                .map(|()| None),
+            };
+            (table, result)
         }))
     }
 
@@ -580,6 +813,12 @@ impl MySqlReplicator {
     ) -> ReadySetResult<()> {
         let mut replication_tasks = FuturesUnordered::new();
         let mut compacting_tasks = FuturesUnordered::new();
+        let nodes = noria
+            .tables()
+            .await?
+            .into_iter()
+            .map(|(table, node)| (table, node.index()))
+            .collect::<HashMap<_, _>>();
 
         // For each table we spawn a new task to parallelize the replication process, with a limit
         // We pop front because we add the tables before the views, and the views depend on the
@@ -592,7 +831,7 @@ impl MySqlReplicator {
                 );
             } else {
                 replication_tasks.push(
-                    self.dumper_task_for_table(noria, table, snapshot_report_interval_secs)
+                    self.dumper_task_for_table(noria, &nodes, table, snapshot_report_interval_secs)
                         .await?,
                 );
             }
@@ -605,8 +844,9 @@ impl MySqlReplicator {
         while let Some(task_result) = replication_tasks.next().await {
             // The unwrap is for the join handle in that case
             match task_result.unwrap() {
-                (table, repl_offset, Ok(())) => {
-                    let mut noria_table = noria.table(table.clone()).await?;
+                (_, Ok(None)) => {}
+                (table, Ok(Some(repl_offset))) => {
+                    let mut noria_table = noria.table_with_standbys(table.clone()).await?;
                     compacting_tasks.push(tokio::spawn(async move {
                         let span = info_span!(
                             "Compacting table",
@@ -630,15 +870,20 @@ impl MySqlReplicator {
                         ReadySetResult::Ok(())
                     }));
                 }
-                (table, _, Err(err)) => {
+                (table, Err(err)) => {
                     error!(
                         table = %table.display(nom_sql::Dialect::MySQL),
                         error = %err,
                         "Replication failed, retrying"
                     );
                     replication_tasks.push(
-                        self.dumper_task_for_table(noria, table, snapshot_report_interval_secs)
-                            .await?,
+                        self.dumper_task_for_table(
+                            noria,
+                            &nodes,
+                            table,
+                            snapshot_report_interval_secs,
+                        )
+                        .await?,
                     );
                 }
             }
@@ -653,8 +898,13 @@ impl MySqlReplicator {
                     );
                 } else {
                     replication_tasks.push(
-                        self.dumper_task_for_table(noria, table, snapshot_report_interval_secs)
-                            .await?,
+                        self.dumper_task_for_table(
+                            noria,
+                            &nodes,
+                            table,
+                            snapshot_report_interval_secs,
+                        )
+                        .await?,
                     );
                 }
             }
@@ -669,6 +919,19 @@ impl MySqlReplicator {
     }
 }
 
+/// How the contents of a table will be copied into ReadySet
+enum TableDump {
+    /// Copy the whole table with a single query
+    Whole(TableDumper),
+    /// Copy the given chunks of the table, concurrently over each of the given transactions
+    Chunks {
+        txs: Vec<Transaction<'static>>,
+        chunks: Vec<(usize, SnapshotChunk)>,
+        tracker: ChunkTracker,
+        key_column: SqlIdentifier,
+    },
+}
+
 /// An intermediary struct that can be used to get a stream of ReadySet rows
 // This is required because mysql::QueryResult borrows from conn and then
 // we have some hard to solve borrowing issues
@@ -18,7 +18,9 @@ use readyset_client::consistency::Timestamp;
 use readyset_client::failpoints;
 use readyset_client::metrics::recorded::{self, SnapshotStatusTag};
 use readyset_client::recipe::changelist::{Change, ChangeList};
-use readyset_client::replication::{ReplicationOffset, ReplicationOffsets};
+use readyset_client::replication::{
+    ReplicationOffset, ReplicationOffsets, ReplicationTablesChange, GTID_LOG_PREFIX,
+};
 use readyset_client::{ReadySetHandle, Table, TableOperation};
 use readyset_data::Dialect;
 use readyset_errors::{
@@ -26,7 +28,7 @@ use readyset_errors::{
 };
 use readyset_telemetry_reporter::{TelemetryBuilder, TelemetryEvent, TelemetrySender};
 use readyset_util::select;
-use tokio::sync::Notify;
+use tokio::sync::{watch, Notify};
 use tracing::{debug, error, info, info_span, trace, warn, Instrument};
 use {mysql_async as mysql, tokio_postgres as pgsql};
 
@@ -36,6 +38,7 @@ use crate::postgres_connector::{
     drop_publication, drop_readyset_schema, drop_replication_slot, PostgresReplicator,
     PostgresWalConnector, PUBLICATION_NAME, REPLICATION_SLOT,
 };
+use crate::snapshot_progress::{ChunkingConfig, SnapshotProgressStore, TableSnapshotProgress};
 use crate::table_filter::TableFilter;
 
 /// Time to wait for requests to coalesce between snapshotting. Useful for preventing a series of
@@ -60,6 +63,11 @@ pub(crate) enum ReplicationAction {
         changes: Vec<Change>,
     },
     LogPosition,
+    /// The end of the transaction with the given id, after which every table is caught up to the
+    /// transaction, including the tables the transaction didn't write to
+    Commit {
+        txid: u64,
+    },
 }
 
 #[async_trait]
@@ -82,6 +90,46 @@ pub(crate) trait Connector {
     ) -> ReadySetResult<(ReplicationAction, ReplicationOffset)>;
 }
 
+/// Load the snapshot progress of the tables which were snapshotted in chunks at different
+/// replication offsets and haven't yet been caught up to the latest of those offsets, removing the
+/// progress of all other tables since it's no longer needed
+async fn load_snapshot_filters(
+    snapshot_progress: &SnapshotProgressStore,
+    replication_offsets: &ReplicationOffsets,
+) -> ReadySetResult<HashMap<Relation, TableSnapshotProgress>> {
+    let mut filters = HashMap::new();
+    for progress in snapshot_progress.load().await? {
+        let needs_filter = progress.is_complete()
+            && progress.needs_catch_up()
+            && matches!(
+                (replication_offsets.tables.get(&progress.table), progress.latest_offset()),
+                (Some(Some(offset)), Some(latest)) if offset < latest
+            );
+        if needs_filter {
+            filters.insert(progress.table.clone(), progress);
+        } else {
+            snapshot_progress.remove(&progress.table).await?;
+        }
+    }
+    Ok(filters)
+}
+
+/// Returns the offset replication must catch up to before all tables reflect the upstream database
+/// at a single point in time: the maximum of `max_offset` and the offsets at which the chunks of
+/// tables being filtered were copied
+fn catch_up_target<'a>(
+    max_offset: Option<&'a ReplicationOffset>,
+    snapshot_filters: &'a HashMap<Relation, TableSnapshotProgress>,
+) -> Option<&'a ReplicationOffset> {
+    snapshot_filters
+        .values()
+        .filter_map(TableSnapshotProgress::latest_offset)
+        .fold(max_offset, |max, offset| match max {
+            Some(max) if max >= offset => Some(max),
+            _ => Some(offset),
+        })
+}
+
 /// Cleans up replication related assets on the upstream database as supplied by the
 /// UpstreamConfig.
 pub async fn cleanup(config: UpstreamConfig) -> ReadySetResult<()> {
@@ -154,8 +202,20 @@ pub struct NoriaAdapter {
     replication_offsets: ReplicationOffsets,
     /// Filters out changes we are not interested in
     table_filter: TableFilter,
+    /// Receives the list of all changes made at runtime to the set of tables to replicate, if
+    /// those changes are supported
+    table_changes: Option<watch::Receiver<Vec<ReplicationTablesChange>>>,
+    /// The number of changes from `table_changes` that have already been applied to
+    /// `table_filter`
+    applied_table_changes: usize,
     /// If the connector can partially resnapshot a database
     supports_resnapshot: bool,
+    /// Persists the progress of tables snapshotted in chunks
+    snapshot_progress: SnapshotProgressStore,
+    /// The snapshot progress of tables whose chunks were copied at different replication offsets,
+    /// used to discard writes that are already reflected in the snapshot until replication has
+    /// caught up to the latest of those offsets
+    snapshot_filters: HashMap<Relation, TableSnapshotProgress>,
 }
 
 impl NoriaAdapter {
@@ -166,6 +226,7 @@ impl NoriaAdapter {
         telemetry_sender: TelemetrySender,
         server_startup: bool,
         enable_statement_logging: bool,
+        table_changes: Option<watch::Receiver<Vec<ReplicationTablesChange>>>,
     ) -> ReadySetResult<!> {
         // Resnapshot when restarting the server to apply changes that may have been made to the
         // replication-tables config parameter.
@@ -189,6 +250,7 @@ impl NoriaAdapter {
                     resnapshot,
                     &telemetry_sender,
                     enable_statement_logging,
+                    table_changes.clone(),
                 )
                 .await
             }
@@ -231,6 +293,7 @@ impl NoriaAdapter {
                     pool,
                     repl_slot_name,
                     enable_statement_logging,
+                    table_changes.clone(),
                 )
                 .await
             }
@@ -259,6 +322,7 @@ impl NoriaAdapter {
     /// * Each table is individually replicated into ReadySet
     /// * READ LOCK is released
     /// * Adapter keeps reading binlog from the next position keeping ReadySet up to date
+    #[allow(clippy::too_many_arguments)]
     async fn start_inner_mysql(
         mut mysql_options: mysql::Opts,
         mut noria: ReadySetHandle,
@@ -267,8 +331,9 @@ impl NoriaAdapter {
         resnapshot: bool,
         telemetry_sender: &TelemetrySender,
         enable_statement_logging: bool,
+        mut table_changes: Option<watch::Receiver<Vec<ReplicationTablesChange>>>,
     ) -> ReadySetResult<!> {
-        use crate::mysql_connector::BinlogPosition;
+        use crate::mysql_connector::{check_gtid_offsets, MySqlPosition};
 
         if let Some(cert_path) = config.ssl_root_cert.clone() {
             let ssl_opts = SslOpts::default().with_root_cert_path(Some(cert_path));
@@ -280,11 +345,13 @@ impl NoriaAdapter {
         // Load the replication offset for all tables and the schema from ReadySet
         let mut replication_offsets = noria.replication_offsets().await?;
 
-        let table_filter = TableFilter::try_new(
+        let mut table_filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             config.replication_tables.take(),
+            config.replication_tables_ignore.take(),
             mysql_options.db_name(),
         )?;
+        let applied_table_changes = apply_table_changes(&mut table_filter, &mut table_changes);
 
         let mut db_schemas = DatabaseSchemas::new();
 
@@ -318,9 +385,26 @@ impl NoriaAdapter {
                     .flatten()
                     .unwrap_or_else(|| "unknown".to_owned());
 
+                // Identify replication positions by GTID if the upstream has GTIDs enabled, so they
+                // remain valid if a replica is later promoted to be the upstream - unless some
+                // tables already have offsets identified by binlog file and offset
+                let use_gtid = match replication_offsets.min_present_offset()? {
+                    Some(offset) => offset.replication_log_name.starts_with(GTID_LOG_PREFIX),
+                    None => pool
+                        .get_conn()
+                        .await?
+                        .query_first::<String, _>("SELECT @@GLOBAL.gtid_mode")
+                        .await?
+                        .map_or(false, |mode| mode.eq_ignore_ascii_case("ON")),
+                };
+                info!(use_gtid, "Identifying replication positions");
+
                 let replicator = MySqlReplicator {
                     pool,
                     table_filter: table_filter.clone(),
+                    use_gtid,
+                    chunking: ChunkingConfig::from(&config),
+                    snapshot_progress: SnapshotProgressStore::new(noria.authority().clone()),
                 };
 
                 let snapshot_start = Instant::now();
@@ -366,11 +450,11 @@ impl NoriaAdapter {
                 // can do this "catching up" by just starting replication at
                 // the old offset. Note that at the very least we will
                 // always have the schema offset for the minimum.
-                let pos: BinlogPosition = replication_offsets
-                    .min_present_offset()?
-                    .expect("Minimal offset must be present after snapshot")
-                    .clone()
-                    .into();
+                let pos = MySqlPosition::try_from(
+                    replication_offsets
+                        .min_present_offset()?
+                        .expect("Minimal offset must be present after snapshot"),
+                )?;
 
                 span.in_scope(|| info!("Snapshot finished"));
                 histogram!(
@@ -390,9 +474,18 @@ impl NoriaAdapter {
 
                 pos
             }
-            (Some(pos), _) => pos.clone().into(),
+            (Some(pos), _) => MySqlPosition::try_from(pos)?,
         };
 
+        // We catch tables up by comparing their offsets, which we can't do for offsets with
+        // diverging GTID sets
+        check_gtid_offsets(
+            replication_offsets
+                .schema
+                .iter()
+                .chain(replication_offsets.tables.values().flatten()),
+        )?;
+
         // TODO: it is possible that the binlog position from noria is no longer
         // present on the primary, in which case the connection will fail, and we would
         // need to perform a new snapshot
@@ -406,6 +499,10 @@ impl NoriaAdapter {
             .await?,
         );
 
+        let snapshot_progress = SnapshotProgressStore::new(noria.authority().clone());
+        let snapshot_filters =
+            load_snapshot_filters(&snapshot_progress, &replication_offsets).await?;
+
         let mut adapter = NoriaAdapter {
             noria: noria.clone(),
             connector,
@@ -413,20 +510,27 @@ impl NoriaAdapter {
             mutator_map: HashMap::new(),
             warned_missing_tables: HashSet::new(),
             table_filter,
+            table_changes,
+            applied_table_changes,
             supports_resnapshot: true,
             dialect: Dialect::DEFAULT_MYSQL,
+            snapshot_progress,
+            snapshot_filters,
         };
 
-        let mut current_pos: ReplicationOffset = pos.try_into()?;
+        let mut current_pos = ReplicationOffset::try_from(&pos)?;
 
         // At this point it is possible that we just finished replication, but
         // our schema and our tables are taken at different position in the binlog.
         // Until our database has a consistent view of the database at a single point
         // in time, it is not safe to issue any queries. We therefore advance the binlog
-        // to the position of the most recent table we have, applying changes as needed.
-        // Only once binlog advanced to that point, can we send a ready signal to
-        // ReadySet.
-        match adapter.replication_offsets.max_offset()? {
+        // to the position of the most recent table we have (or the most recent chunk of a table
+        // we have), applying changes as needed. Only once binlog advanced to that point, can we
+        // send a ready signal to ReadySet.
+        match catch_up_target(
+            adapter.replication_offsets.max_offset()?,
+            &adapter.snapshot_filters,
+        ) {
             Some(max) if max > &current_pos => {
                 info!(start = %current_pos, end = %max, "Catching up");
                 let max = max.clone();
@@ -460,6 +564,7 @@ impl NoriaAdapter {
         pool: deadpool_postgres::Pool,
         repl_slot_name: String,
         enable_statement_logging: bool,
+        mut table_changes: Option<watch::Receiver<Vec<ReplicationTablesChange>>>,
     ) -> ReadySetResult<!> {
         macro_rules! handle_joinhandle_result {
             ($res: expr) => {
@@ -480,14 +585,41 @@ impl NoriaAdapter {
         // Attempt to retrieve the latest replication offset from ReadySet-server, if none is
         // present begin the snapshot process
         let replication_offsets = noria.replication_offsets().await?;
-        let pos = replication_offsets.max_offset()?.map(Into::into);
+        let mut pos = replication_offsets.max_offset()?.map(Into::into);
         let snapshot_report_interval_secs = config.snapshot_report_interval_secs;
+        let chunking = ChunkingConfig::from(&config);
+        let snapshot_progress = SnapshotProgressStore::new(noria.authority().clone());
+
+        // If an earlier snapshot was interrupted partway through copying some tables in chunks, we
+        // can resume it rather than starting again from scratch, as long as the replication slot
+        // we were replicating from still exists, since it's the only way to catch up the tables
+        // from the offsets their chunks were copied at. We do that by starting replication from
+        // the earliest of those offsets, which keeps the slot instead of recreating it.
+        let resuming = if pos.is_none() && !resnapshot {
+            let resume_pos = Self::snapshot_resume_position(
+                &pgsql_opts,
+                tls_connector.clone(),
+                &repl_slot_name,
+                &replication_offsets,
+                &snapshot_progress,
+            )
+            .await?;
+            if let Some(resume_pos) = &resume_pos {
+                info!(%resume_pos, "Resuming interrupted snapshot");
+            }
+            pos = resume_pos.map(Into::into);
+            pos.is_some()
+        } else {
+            false
+        };
 
-        let table_filter = TableFilter::try_new(
+        let mut table_filter = TableFilter::try_new(
             nom_sql::Dialect::PostgreSQL,
             config.replication_tables.take(),
+            config.replication_tables_ignore.take(),
             None,
         )?;
+        let applied_table_changes = apply_table_changes(&mut table_filter, &mut table_changes);
 
         // For Postgres 13, once we setup ddl replication, the following query can be rejected, so
         // run it ahead of time.
@@ -529,7 +661,7 @@ impl NoriaAdapter {
         let resnapshot_slot_name = format!("{}_{}", RESNAPSHOT_SLOT, repl_slot_name);
         let replication_slot = if let Some(slot) = &connector.replication_slot {
             Some(slot.clone())
-        } else if resnapshot || pos.is_none() {
+        } else if resnapshot || resuming || pos.is_none() {
             // This is not an initial connection but we need to resnapshot the latest schema,
             // therefore we create a new replication slot, just so we can get a consistent snapshot
             // with a WAL position attached. This is more robust than locking and allows us to reuse
@@ -565,9 +697,14 @@ impl NoriaAdapter {
                 .and_then(|row| row.try_get::<_, String>(0))
                 .unwrap_or_else(|_| "unknown".to_owned());
 
-            let mut replicator =
-                PostgresReplicator::new(&mut client, pool, &mut noria, table_filter.clone())
-                    .await?;
+            let mut replicator = PostgresReplicator::new(
+                &mut client,
+                pool,
+                &mut noria,
+                table_filter.clone(),
+                chunking,
+            )
+            .await?;
 
             select! {
                 snapshot_result = replicator.snapshot_to_noria(
@@ -578,7 +715,8 @@ impl NoriaAdapter {
                     // because only *some* tables are missing a replication offset - in that case we
                     // need to resnapshot *all* tables, because we just dropped the replication slot
                     // above, which prevents us from replicating any writes to tables we do have a
-                    // replication offset for that happened while we weren't running.
+                    // replication offset for that happened while we weren't running. This doesn't
+                    // apply if we're resuming an interrupted snapshot, since we kept the slot.
                     /* full_snapshot = */ pos.is_none()
                 ).fuse() =>  {
                     let status = if snapshot_result.is_err() {
@@ -630,8 +768,9 @@ impl NoriaAdapter {
             .min_present_offset()?
             .expect("Minimal offset must be present after snapshot")
             .clone();
-        let max_pos = replication_offsets
-            .max_offset()?
+        let snapshot_filters =
+            load_snapshot_filters(&snapshot_progress, &replication_offsets).await?;
+        let max_pos = catch_up_target(replication_offsets.max_offset()?, &snapshot_filters)
             .expect("Maximum offset must be present after snapshot")
             .clone();
 
@@ -642,8 +781,12 @@ impl NoriaAdapter {
             mutator_map: HashMap::new(),
             warned_missing_tables: HashSet::new(),
             table_filter,
+            table_changes,
+            applied_table_changes,
             supports_resnapshot: true,
             dialect: Dialect::DEFAULT_POSTGRESQL,
+            snapshot_progress,
+            snapshot_filters,
         };
 
         if min_pos != max_pos {
@@ -663,6 +806,43 @@ impl NoriaAdapter {
         unreachable!("`main_loop` will never stop with an Ok status if `until = None`");
     }
 
+    /// If there is progress recorded for an interrupted snapshot which copied tables in chunks,
+    /// and the replication slot with the given name still exists, returns the position to start
+    /// replicating from in order to resume that snapshot: the earliest of the offsets at which any
+    /// of those chunks were copied and the offsets of the schema and the other tables.
+    async fn snapshot_resume_position(
+        pgsql_opts: &pgsql::Config,
+        tls_connector: MakeTlsConnector,
+        repl_slot_name: &str,
+        replication_offsets: &ReplicationOffsets,
+        snapshot_progress: &SnapshotProgressStore,
+    ) -> ReadySetResult<Option<ReplicationOffset>> {
+        let progress = snapshot_progress.load().await?;
+        if progress.is_empty() {
+            return Ok(None);
+        }
+
+        let (client, connection) = pgsql_opts.connect(tls_connector).await?;
+        let _connection_handle = tokio::spawn(connection);
+        let slot_exists = client
+            .query_opt(
+                "SELECT 1 FROM pg_replication_slots WHERE slot_name = $1",
+                &[&repl_slot_name],
+            )
+            .await?
+            .is_some();
+        if !slot_exists {
+            return Ok(None);
+        }
+
+        Ok(progress
+            .iter()
+            .filter_map(TableSnapshotProgress::earliest_offset)
+            .chain(replication_offsets.min_present_offset()?)
+            .min_by_key(|offset| offset.offset)
+            .cloned())
+    }
+
     /// Apply a DDL string to noria with the current log position
     async fn handle_ddl_change(
         &mut self,
@@ -804,6 +984,27 @@ impl NoriaAdapter {
         Ok(())
     }
 
+    /// Advance the timestamps of all the tables to the given transaction id, so that reads waiting
+    /// for writes in that transaction don't wait on tables the transaction didn't write to
+    async fn handle_commit(&mut self, txid: u64) -> ReadySetResult<()> {
+        let tables = self
+            .replication_offsets
+            .tables
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for table in tables {
+            if let Some(table) = self.mutator_for_table(&table).await? {
+                let mut timestamp = Timestamp::default();
+                timestamp.map.insert(table.node, txid);
+                table.update_timestamp(timestamp).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Send table actions to noria tables, and update the binlog position for the table
     async fn handle_table_actions(
         &mut self,
@@ -812,6 +1013,28 @@ impl NoriaAdapter {
         txid: Option<u64>,
         pos: ReplicationOffset,
     ) -> ReadySetResult<()> {
+        // If the table was snapshotted in chunks at different offsets, discard any writes to rows
+        // in chunks that were copied after those writes happened
+        let mut caught_up_to_snapshot = false;
+        if let Some(progress) = self.snapshot_filters.get(&table) {
+            if actions
+                .iter()
+                .any(|op| matches!(op, TableOperation::Truncate))
+            {
+                // Truncating the table discards all the snapshotted rows, so every write from here
+                // on needs to be applied
+                self.snapshot_filters.remove(&table);
+                self.snapshot_progress.remove(&table).await?;
+            } else if progress
+                .latest_offset()
+                .map_or(true, |latest| pos > *latest)
+            {
+                caught_up_to_snapshot = true;
+            } else {
+                actions.retain(|op| !progress.is_reflected_in_snapshot(op, &pos));
+            }
+        }
+
         // Send the rows as are
         let table_mutator = if let Some(table) = self.mutator_for_table(&table).await? {
             table
@@ -835,16 +1058,20 @@ impl NoriaAdapter {
         table_mutator.perform_all(actions).await?;
 
         // If there was a transaction id associated, propagate the timestamp with that transaction
-        // id.
+        // id. Connectors that report the end of transactions (see [`ReplicationAction::Commit`])
+        // also propagate it to the tables the transaction didn't write to.
         // TODO(justin): Make this operation atomic with the table actions being pushed above.
-        // TODO(vlad): We have to propagate txid to every table or else we won't be able to ensure
-        // proper read after write
         if let Some(tx) = txid {
             let mut timestamp = Timestamp::default();
             timestamp.map.insert(table_mutator.node, tx);
             table_mutator.update_timestamp(timestamp).await?;
         }
 
+        if caught_up_to_snapshot {
+            self.snapshot_filters.remove(&table);
+            self.snapshot_progress.remove(&table).await?;
+        }
+
         self.replication_offsets.tables.insert(table, Some(pos));
 
         Ok(())
@@ -902,11 +1129,13 @@ impl NoriaAdapter {
                     table.schema.as_deref().ok_or_else(|| {
                         internal_err!("All tables should have a schema in the replicator")
                     })?,
-                    &table.name,
+                    table.name.as_str(),
                 ) {
                     return Ok(());
                 }
             }
+            // Timestamps only ever move forward, so there's no harm in seeing a commit again
+            ReplicationAction::Commit { .. } => {}
         }
 
         match action {
@@ -919,6 +1148,7 @@ impl NoriaAdapter {
                 txid,
             } => self.handle_table_actions(table, actions, txid, pos).await,
             ReplicationAction::LogPosition => self.handle_log_position(pos).await,
+            ReplicationAction::Commit { txid } => self.handle_commit(txid).await,
         }
     }
 
@@ -940,7 +1170,7 @@ impl NoriaAdapter {
                 return Ok(());
             }
 
-            let (action, pos) = match self.connector.next_action(position, until.as_ref()).await {
+            let (action, pos) = match self.next_action(position, until.as_ref()).await {
                 Ok(next_action) => next_action,
                 // In some cases, we may fail to replicate because of unsupported operations, stop
                 // replicating a table if we encounter this type of error.
@@ -978,6 +1208,75 @@ impl NoriaAdapter {
         }
     }
 
+    /// Wait for the next replication action from the connector. If we're not catching up to a
+    /// given position, also watch for changes to the set of tables to replicate, and apply them if
+    /// they arrive first.
+    async fn next_action(
+        &mut self,
+        position: &ReplicationOffset,
+        until: Option<&ReplicationOffset>,
+    ) -> ReadySetResult<(ReplicationAction, ReplicationOffset)> {
+        if let (None, Some(table_changes)) = (until, self.table_changes.as_mut()) {
+            let changed = select! {
+                biased;
+                changed = table_changes.changed() => changed,
+                next_action = self.connector.next_action(position, until) => return next_action,
+            };
+
+            match changed {
+                // Dropping the in-progress `next_action` future may have discarded some events,
+                // but that's ok since applying table changes always restarts replication from our
+                // last recorded replication offsets.
+                Ok(()) => return self.handle_table_changes().await,
+                Err(_) => {
+                    warn!("Sender for changes to replicated tables was dropped");
+                    self.table_changes = None;
+                }
+            }
+        }
+
+        self.connector.next_action(position, until).await
+    }
+
+    /// Apply any new changes made at runtime to the set of tables to replicate, and remove any
+    /// tables we're no longer replicating from ReadySet, along with any caches that depend on
+    /// them.
+    ///
+    /// On success, returns [`ReadySetError::ResnapshotNeeded`] so that any newly added tables get
+    /// snapshotted. Tables that already have a replication offset are skipped when resnapshotting,
+    /// so this only snapshots the tables that were added.
+    async fn handle_table_changes<T>(&mut self) -> ReadySetResult<T> {
+        let changes = match self.table_changes.as_mut() {
+            Some(table_changes) => table_changes
+                .borrow_and_update()
+                .iter()
+                .skip(self.applied_table_changes)
+                .cloned()
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        for change in &changes {
+            info!(?change, "Changing replicated tables");
+            self.table_filter.apply_change(change);
+        }
+        self.applied_table_changes += changes.len();
+
+        for table in self.noria.tables().await?.into_keys() {
+            let replicated = match table.schema.as_deref() {
+                Some(schema) => self
+                    .table_filter
+                    .should_be_processed(schema, table.name.as_str()),
+                None => true,
+            };
+            if !replicated {
+                self.remove_table_from_readyset(table).await?;
+            }
+        }
+
+        Err(ReadySetError::ResnapshotNeeded)
+    }
+
     /// When schema changes there is a risk the cached mutators will no longer be in sync
     /// and we need to drop them all
     fn clear_mutator_cache(&mut self) {
@@ -989,14 +1288,16 @@ impl NoriaAdapter {
     async fn mutator_for_table(&mut self, name: &Relation) -> ReadySetResult<Option<&mut Table>> {
         match self.mutator_map.raw_entry_mut().from_key(name) {
             hash_map::RawEntryMut::Occupied(o) => Ok(o.into_mut().as_mut()),
-            hash_map::RawEntryMut::Vacant(v) => match self.noria.table(name.clone()).await {
-                Ok(table) => Ok(v.insert(name.clone(), Some(table)).1.as_mut()),
-                Err(e) if e.caused_by_table_not_found() => {
-                    // Cache the not found result as well as the found result
-                    Ok(v.insert(name.clone(), None).1.as_mut())
+            hash_map::RawEntryMut::Vacant(v) => {
+                match self.noria.table_with_standbys(name.clone()).await {
+                    Ok(table) => Ok(v.insert(name.clone(), Some(table)).1.as_mut()),
+                    Err(e) if e.caused_by_table_not_found() => {
+                        // Cache the not found result as well as the found result
+                        Ok(v.insert(name.clone(), None).1.as_mut())
+                    }
+                    Err(e) => Err(e),
                 }
-                Err(e) => Err(e),
-            },
+            }
         }
     }
 
@@ -1009,6 +1310,8 @@ impl NoriaAdapter {
         );
         self.replication_offsets.tables.remove(&table);
         self.mutator_map.remove(&table);
+        self.snapshot_filters.remove(&table);
+        self.snapshot_progress.remove(&table).await?;
         // Dropping the table cleans up any dataflow state that may have been made as well as
         // cleaning up the base table on disk.
         let changelist = ChangeList::from_changes(
@@ -1074,6 +1377,24 @@ impl NoriaAdapter {
     }
 }
 
+/// Apply all changes made so far at runtime to the set of tables to replicate to the given
+/// filter, returning the number of changes applied
+fn apply_table_changes(
+    table_filter: &mut TableFilter,
+    table_changes: &mut Option<watch::Receiver<Vec<ReplicationTablesChange>>>,
+) -> usize {
+    match table_changes {
+        Some(table_changes) => {
+            let changes = table_changes.borrow_and_update();
+            for change in changes.iter() {
+                table_filter.apply_change(change);
+            }
+            changes.len()
+        }
+        None => 0,
+    }
+}
+
 pub async fn pg_pool(
     config: pgsql::Config,
     pool_size: usize,
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use database_utils::UpstreamConfig;
 #[cfg(feature = "failure_injection")]
@@ -7,6 +9,7 @@ use nom_sql::Relation;
 use pgsql::SimpleQueryMessage;
 use postgres_native_tls::MakeTlsConnector;
 use postgres_protocol::escape::escape_literal;
+use postgres_types::Type;
 #[cfg(feature = "failure_injection")]
 use readyset_client::failpoints;
 use readyset_client::replication::ReplicationOffset;
@@ -51,6 +54,14 @@ pub struct PostgresWalConnector {
     pub(crate) replication_slot: Option<CreatedSlot>,
     /// Whether to log statements received by the connector
     enable_statement_logging: bool,
+    /// The LSN of the commit record of the transaction currently being read, if any. This is
+    /// propagated as the transaction ID of the transaction's table actions, so that reads can wait
+    /// for writes made through the adapter to be replicated.
+    current_commit_lsn: Option<Lsn>,
+    /// Whether the table actions at the end of the transaction currently being read have been
+    /// flushed at its commit, in which case the commit is read again to report the end of the
+    /// transaction
+    flushed_at_commit: bool,
 }
 
 /// The decoded response to `IDENTIFY_SYSTEM`
@@ -112,6 +123,8 @@ impl PostgresWalConnector {
             connection_handle,
             reader: None,
             peek: None,
+            current_commit_lsn: None,
+            flushed_at_commit: false,
             next_position,
             replication_slot: None,
             enable_statement_logging,
@@ -173,6 +186,12 @@ impl PostgresWalConnector {
         Ok(())
     }
 
+    /// The transaction ID to propagate with table actions from the transaction currently being
+    /// read, which is the LSN of that transaction's commit record.
+    fn current_txid(&self) -> Option<u64> {
+        self.current_commit_lsn.map(|lsn| lsn.0 as u64)
+    }
+
     /// Waits and returns the next WAL event, while monitoring the connection
     /// handle for errors.
     async fn next_event(&mut self) -> Result<(WalEvent, Lsn), WalError> {
@@ -302,6 +321,8 @@ impl PostgresWalConnector {
             })
             .unwrap_or_default();
 
+        let domain_base_types = self.domain_base_types().await?;
+
         let inner_client = self.client.inner();
         let wal_position = self.next_position.unwrap_or_default();
         let messages_support = if version >= 140000 {
@@ -340,11 +361,53 @@ impl PostgresWalConnector {
             }
         }
 
-        self.reader = Some(WalReader::new(wal));
+        self.reader = Some(WalReader::new(wal, domain_base_types));
 
         Ok(())
     }
 
+    /// Loads a map from the OID of every domain type whose underlying base type is a built-in type
+    /// to the OID of that base type, so that values of those domains can be replicated as values of
+    /// their base type.
+    async fn domain_base_types(&mut self) -> ReadySetResult<HashMap<u32, u32>> {
+        let rows = self
+            .simple_query(
+                "WITH RECURSIVE domains(oid, base_oid) AS (
+                    SELECT oid, typbasetype FROM pg_catalog.pg_type WHERE typtype = 'd'
+                    UNION ALL
+                    SELECT domains.oid, t.typbasetype
+                    FROM domains
+                    JOIN pg_catalog.pg_type t ON domains.base_oid = t.oid
+                    WHERE t.typtype = 'd'
+                )
+                SELECT domains.oid, domains.base_oid
+                FROM domains
+                JOIN pg_catalog.pg_type t ON domains.base_oid = t.oid
+                WHERE t.typtype <> 'd'",
+            )
+            .await?;
+
+        let parse_oid = |oid: Option<&str>| {
+            oid.and_then(|oid| oid.parse::<u32>().ok()).ok_or_else(|| {
+                ReadySetError::ReplicationFailed(format!("Invalid type oid {oid:?}"))
+            })
+        };
+
+        let mut domain_base_types = HashMap::new();
+        for row in rows {
+            if let SimpleQueryMessage::Row(row) = row {
+                let domain = parse_oid(row.get(0))?;
+                let base = parse_oid(row.get(1))?;
+                if Type::from_oid(base).is_some() {
+                    domain_base_types.insert(domain, base);
+                }
+            }
+        }
+
+        trace!(?domain_base_types, "Loaded domain types");
+        Ok(domain_base_types)
+    }
+
     fn send_standy_status_update(&self, ack: PostgresPosition) -> ReadySetResult<()> {
         use bytes::{BufMut, BytesMut};
 
@@ -551,7 +614,7 @@ impl Connector for PostgresWalConnector {
                     ReplicationAction::TableAction {
                         table: cur_table,
                         actions,
-                        txid: None,
+                        txid: self.current_txid(),
                     },
                     cur_lsn.into(),
                 ));
@@ -595,7 +658,7 @@ impl Connector for PostgresWalConnector {
                                         name: name.into(),
                                     },
                                     actions,
-                                    txid: None,
+                                    txid: self.current_txid(),
                                 },
                                 PostgresPosition::from(lsn).into(),
                             ));
@@ -621,7 +684,7 @@ impl Connector for PostgresWalConnector {
                             ReplicationAction::TableAction {
                                 table: cur_table,
                                 actions,
-                                txid: None,
+                                txid: self.current_txid(),
                             },
                             cur_lsn.into(),
                         ));
@@ -641,7 +704,7 @@ impl Connector for PostgresWalConnector {
                             ReplicationAction::TableAction {
                                 table: cur_table,
                                 actions,
-                                txid: None,
+                                txid: self.current_txid(),
                             },
                             cur_lsn.into(),
                         ));
@@ -673,7 +736,7 @@ impl Connector for PostgresWalConnector {
                             ReplicationAction::TableAction {
                                 table: cur_table,
                                 actions,
-                                txid: None,
+                                txid: self.current_txid(),
                             },
                             cur_lsn.into(),
                         ));
@@ -682,20 +745,43 @@ impl Connector for PostgresWalConnector {
                 WalEvent::WantsKeepaliveResponse => {
                     self.send_standy_status_update(last_pos.into())?;
                 }
+                WalEvent::Begin { final_lsn } => {
+                    self.current_commit_lsn = Some(final_lsn);
+                    self.flushed_at_commit = false;
+                }
                 WalEvent::Commit => {
                     if !actions.is_empty() {
                         // On commit we flush, because there is no knowing when the next commit is
-                        // coming
+                        // coming. We come back to the commit once the actions are applied, to
+                        // report the end of the transaction.
+                        self.peek = Some((WalEvent::Commit, lsn));
+                        self.flushed_at_commit = true;
                         return Ok((
                             ReplicationAction::TableAction {
                                 table: cur_table,
                                 actions,
-                                txid: None,
+                                txid: self.current_txid(),
                             },
                             cur_lsn.into(),
                         ));
-                    } else {
-                        return Ok((ReplicationAction::LogPosition, cur_lsn.into()));
+                    }
+
+                    let flushed_at_commit = std::mem::take(&mut self.flushed_at_commit);
+                    match self.current_commit_lsn.take() {
+                        Some(final_lsn) => {
+                            if !flushed_at_commit {
+                                // Nothing was written at the commit, so come back to it once more
+                                // to report our position in the log
+                                self.peek = Some((WalEvent::Commit, lsn));
+                            }
+                            return Ok((
+                                ReplicationAction::Commit {
+                                    txid: final_lsn.0 as u64,
+                                },
+                                cur_lsn.into(),
+                            ));
+                        }
+                        None => return Ok((ReplicationAction::LogPosition, cur_lsn.into())),
                     }
                 }
                 WalEvent::Insert { tuple, .. } => actions.push(TableOperation::Insert(tuple)),
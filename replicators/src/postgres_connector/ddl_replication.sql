@@ -72,9 +72,26 @@ BEGIN
                 'columns', (
                     SELECT json_agg(json_build_object(
                         'name', attr.attname,
-                        'column_type', pg_catalog.format_type(
-                            attr.atttypid,
-                            attr.atttypmod
+                        -- Columns whose type is a domain are replicated as
+                        -- the domain's underlying base type
+                        'column_type', (
+                            WITH RECURSIVE base(oid, typmod) AS (
+                                SELECT attr.atttypid, attr.atttypmod
+                                UNION ALL
+                                SELECT
+                                    dt.typbasetype,
+                                    CASE WHEN base.typmod = -1
+                                    THEN dt.typtypmod
+                                    ELSE base.typmod
+                                    END
+                                FROM base
+                                JOIN pg_catalog.pg_type dt ON base.oid = dt.oid
+                                WHERE dt.typtype = 'd'
+                            )
+                            SELECT pg_catalog.format_type(base.oid, base.typmod)
+                            FROM base
+                            JOIN pg_catalog.pg_type bt ON base.oid = bt.oid
+                            WHERE bt.typtype <> 'd'
                         ),
                         'not_null', attr.attnotnull
                     ) ORDER BY attr.attnum)
@@ -5,7 +5,7 @@ use std::future;
 use std::time::Instant;
 
 use futures::future::join_all;
-use futures::{pin_mut, StreamExt, TryFutureExt};
+use futures::{pin_mut, stream, StreamExt, TryFutureExt};
 use itertools::Itertools;
 use metrics::register_gauge;
 use nom_sql::{
@@ -25,6 +25,10 @@ use tracing::{debug, info, info_span, trace, warn, Instrument};
 use super::connector::CreatedSlot;
 use super::PostgresPosition;
 use crate::db_util::CreateSchema;
+use crate::snapshot_progress::{
+    chunk_key_column, ChunkTracker, ChunkingConfig, SnapshotChunk, SnapshotProgressStore,
+    TableSnapshotProgress,
+};
 use crate::table_filter::TableFilter;
 
 const BATCH_SIZE: usize = 1024; // How many queries to buffer before pushing to ReadySet
@@ -46,6 +50,10 @@ pub struct PostgresReplicator<'a> {
     pub(crate) noria: &'a mut readyset_client::ReadySetHandle,
     /// Filters out tables we are not interested in
     pub(crate) table_filter: TableFilter,
+    /// How to split large tables into chunks while snapshotting
+    pub(crate) chunking: ChunkingConfig,
+    /// Records progress through snapshotting chunked tables
+    pub(crate) snapshot_progress: SnapshotProgressStore,
 }
 
 #[derive(Debug)]
@@ -126,21 +134,45 @@ impl<'a> FromSql<'a> for ConstraintDefinition {
     accepts!(TEXT);
 }
 
+/// Returns an unsupported error naming the column if the given `pg_type.typtype` of its type is
+/// composite (`'c'`) or multirange (`'m'`), neither of which we can replicate
+fn check_column_typtype(column: &str, typname: &str, typtype: i8) -> ReadySetResult<()> {
+    match typtype as u8 as char {
+        'c' => unsupported!("Column {column} has composite type {typname}, which is not supported"),
+        'm' => {
+            unsupported!("Column {column} has multirange type {typname}, which is not supported")
+        }
+        _ => Ok(()),
+    }
+}
+
 impl TryFrom<pgsql::Row> for ColumnEntry {
     type Error = ReadySetError;
 
     fn try_from(row: pgsql::Row) -> Result<Self, Self::Error> {
+        let name: String = row.try_get(0 /* pg_attribute.attname */)?;
         let type_oid = row.try_get(4 /* pg_type.oid */)?;
 
+        // Composite and multirange values (or arrays of them) can't be represented as `DfValue`s,
+        // so reject those columns before looking up the type - the built-in multirange types are
+        // known to `Type::from_oid`, so they'd otherwise make it into the table's schema
+        check_column_typtype(
+            &name,
+            row.try_get(2 /* pg_type.typname */)?,
+            row.try_get(5)?,
+        )?;
+        if row.try_get(7 /* is_array */)? {
+            check_column_typtype(&name, row.try_get(8)?, row.try_get(10)?)?;
+        }
+
         let typtype_to_kind = |typtype: i8| -> ReadySetResult<Kind> {
             match typtype as u8 as char {
                 'b' => Ok(Kind::Simple),
-                'c' => unsupported!("Composite types are not supported"),
                 'd' => unsupported!("Domain types are not supported"),
                 'e' => Ok(Kind::Enum(row.try_get(12 /* array_agg(e.enumlabel)... */)?)),
                 'p' => Ok(Kind::Pseudo),
                 'r' => unsupported!("Range types are not supported"),
-                'm' => unsupported!("Multirange types are not supported"),
+                c @ ('c' | 'm') => internal!("Column type with typtype '{c}' was not rejected"),
                 c => internal!("Unknown value '{c}' in pg_catalog.pg_type.typtype"),
             }
         };
@@ -168,7 +200,7 @@ impl TryFrom<pgsql::Row> for ColumnEntry {
         };
 
         Ok(ColumnEntry {
-            name: row.try_get(0 /* pg_attribute.attname */)?,
+            name,
             not_null: row.try_get(1 /* pg_attribute.attnotnull */)?,
             sql_type: row.try_get(3)?,
             pg_type,
@@ -291,7 +323,7 @@ impl TableEntry {
                 THEN format('"%s"."%s"', tn.nspname, t.typname)
                 WHEN member_t.oid IS NOT NULL AND member_t.typtype = 'e'
                 THEN format('"%s"."%s"[]', member_tn.nspname, member_t.typname)
-                ELSE pg_catalog.format_type(a.atttypid, a.atttypmod)
+                ELSE pg_catalog.format_type(base.oid, base.typmod)
                 END AS sql_type,
                 t.oid,
                 t.typtype,
@@ -305,7 +337,25 @@ impl TableEntry {
                  FROM pg_enum e
                  WHERE (member_t.oid IS NULL AND (e.enumtypid = t.oid)) OR e.enumtypid = member_t.oid)
             FROM pg_catalog.pg_attribute a
-            JOIN pg_catalog.pg_type t ON a.atttypid = t.oid
+            -- Columns whose type is a domain are replicated as the domain's underlying base type,
+            -- ignoring the domain's constraints (which are enforced by the upstream database)
+            CROSS JOIN LATERAL (
+                WITH RECURSIVE base(oid, typmod) AS (
+                    SELECT a.atttypid, a.atttypmod
+                    UNION ALL
+                    SELECT
+                        dt.typbasetype,
+                        CASE WHEN base.typmod = -1 THEN dt.typtypmod ELSE base.typmod END
+                    FROM base
+                    JOIN pg_catalog.pg_type dt ON base.oid = dt.oid
+                    WHERE dt.typtype = 'd'
+                )
+                SELECT base.oid, base.typmod
+                FROM base
+                JOIN pg_catalog.pg_type bt ON base.oid = bt.oid
+                WHERE bt.typtype <> 'd'
+            ) base
+            JOIN pg_catalog.pg_type t ON base.oid = t.oid
             JOIN pg_catalog.pg_namespace tn ON t.typnamespace = tn.oid
             LEFT JOIN pg_catalog.pg_type member_t ON t.typelem = member_t.oid
             LEFT JOIN pg_catalog.pg_namespace member_tn ON member_t.typnamespace = member_tn.oid
@@ -593,6 +643,166 @@ impl TableDescription {
 
         Ok(())
     }
+
+    /// Decide whether to split the table into chunks to copy it, based on the number of rows it
+    /// contains, and if so plan the chunks
+    async fn plan_chunks<'a>(
+        &self,
+        transaction: &'a deadpool_postgres::Transaction<'a>,
+        node: usize,
+        (key_column, key_index): (SqlIdentifier, usize),
+        chunking: ChunkingConfig,
+    ) -> ReadySetResult<Option<TableSnapshotProgress>> {
+        let nrows = transaction
+            .query_one(
+                format!(
+                    "SELECT count(*) AS nrows FROM \"{}\".\"{}\"",
+                    self.schema()?,
+                    &self.name.name,
+                )
+                .as_str(),
+                &[],
+            )
+            .await?
+            .try_get::<_, i64>("nrows")?;
+        let Some(num_chunks) = chunking.num_chunks(nrows as u64) else {
+            return Ok(None);
+        };
+
+        let bounds = transaction
+            .query_one(
+                format!(
+                    "SELECT min(\"{key_column}\")::int8, max(\"{key_column}\")::int8 \
+                     FROM \"{}\".\"{}\"",
+                    self.schema()?,
+                    &self.name.name,
+                )
+                .as_str(),
+                &[],
+            )
+            .await?;
+        let (Some(min), Some(max)) = (
+            bounds.try_get::<_, Option<i64>>(0)?,
+            bounds.try_get::<_, Option<i64>>(1)?,
+        ) else {
+            return Ok(None);
+        };
+
+        Ok(Some(TableSnapshotProgress::new(
+            self.name.clone(),
+            node,
+            key_column,
+            key_index,
+            min,
+            max,
+            num_chunks,
+        )))
+    }
+
+    /// Copy a table's contents from PostgreSQL to ReadySet in chunks, copying up to
+    /// `parallelism` chunks at once. Each chunk is copied in its own transaction, using the
+    /// snapshot with the given name.
+    #[allow(clippy::too_many_arguments)]
+    async fn dump_chunks(
+        &self,
+        pool: &deadpool_postgres::Pool,
+        mut noria_table: readyset_client::Table,
+        tracker: ChunkTracker,
+        chunks: Vec<(usize, SnapshotChunk)>,
+        key_column: &SqlIdentifier,
+        parallelism: usize,
+        snapshot_name: &str,
+        wal_position: &ReplicationOffset,
+    ) -> ReadySetResult<()> {
+        let num_chunks = chunks.len();
+        info!(chunks = %num_chunks, "Snapshotting started");
+        let progress_percentage_metric: metrics::Gauge = register_gauge!(
+            recorded::REPLICATOR_SNAPSHOT_PERCENT,
+            "schema" => self.schema()?.to_string(),
+            "name" => self.name.name.to_string()
+        );
+
+        let type_map: Vec<_> = self.columns.iter().map(|c| c.pg_type.clone()).collect();
+        let mut copies = stream::iter(chunks)
+            .map(|(index, chunk)| {
+                let mut noria_table = noria_table.clone();
+                let tracker = &tracker;
+                let type_map = &type_map;
+                async move {
+                    let mut client = pool.get().await?;
+                    let transaction = client
+                        .build_transaction()
+                        .deferrable(true)
+                        .isolation_level(pgsql::IsolationLevel::RepeatableRead)
+                        .read_only(true)
+                        .start()
+                        .await?;
+                    let query = format!("SET TRANSACTION SNAPSHOT '{}'", snapshot_name);
+                    transaction.query(query.as_str(), &[]).await?;
+
+                    let query = format!(
+                        "COPY (SELECT * FROM \"{}\".\"{}\" WHERE {} ORDER BY \"{}\") \
+                         TO stdout BINARY",
+                        self.schema()?,
+                        self.name.name,
+                        chunk.condition(&format!("\"{key_column}\"")),
+                        key_column
+                    );
+                    let rows = transaction.copy_out(query.as_str()).await?;
+                    let rows =
+                        pgsql::binary_copy::BinaryCopyOutStream::new(rows, type_map).map(|row| {
+                            let row = row?;
+                            (0..type_map.len())
+                                .map(|i| row.try_get::<DfValue>(i))
+                                .collect::<Result<Vec<_>, _>>()
+                                .map_err(|err| {
+                                    ReadySetError::ReplicationFailed(format!(
+                                        "Failed converting to DfValue, table: {}, err: {}",
+                                        self.name.display(Dialect::PostgreSQL),
+                                        err
+                                    ))
+                                })
+                        });
+
+                    tracker
+                        .copy_chunk(index, wal_position, rows, &mut noria_table)
+                        .await
+                }
+            })
+            .buffer_unordered(parallelism);
+
+        let mut cnt = 0;
+        let mut chunks_copied = 0;
+        while let Some(rows) = copies.next().await {
+            cnt += rows.map_err(|err| {
+                progress_percentage_metric.set(0.0);
+                err
+            })?;
+            chunks_copied += 1;
+
+            let progress_percent = (chunks_copied as f64 / num_chunks as f64) * 100.;
+            let progress = format!("{:.2}%", progress_percent);
+            info!(rows_replicated = %cnt, %progress, "Snapshotting progress");
+            progress_percentage_metric.set(progress_percent);
+        }
+        // Release the copies' borrows of the table and the tracker
+        drop(copies);
+
+        let span = info_span!(
+            "Setting replication offset and compacting table",
+            table = %noria_table.table_name().display(Dialect::PostgreSQL),
+        );
+        tracker
+            .finish(&mut noria_table)
+            .instrument(span.clone())
+            .await?;
+        span.in_scope(|| info!("Compacting finished"));
+
+        info!(rows_replicated = %cnt, "Snapshotting finished");
+        progress_percentage_metric.set(100.0);
+
+        Ok(())
+    }
 }
 
 impl<'a> PostgresReplicator<'a> {
@@ -601,6 +811,7 @@ impl<'a> PostgresReplicator<'a> {
         pool: deadpool_postgres::Pool,
         noria: &'a mut readyset_client::ReadySetHandle,
         table_filter: TableFilter,
+        chunking: ChunkingConfig,
     ) -> ReadySetResult<PostgresReplicator<'a>> {
         let transaction = Some(
             client
@@ -611,50 +822,100 @@ impl<'a> PostgresReplicator<'a> {
                 .await?,
         );
 
+        let snapshot_progress = SnapshotProgressStore::new(noria.authority().clone());
+
         Ok(PostgresReplicator {
             transaction,
             pool,
             noria,
             table_filter,
+            chunking,
+            snapshot_progress,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn snapshot_table(
         pool: deadpool_postgres::Pool,
         span: tracing::Span,
         table: &TableDescription,
-        noria_table: readyset_client::Table,
+        mut noria_table: readyset_client::Table,
+        node: usize,
+        chunking: ChunkingConfig,
+        snapshot_progress: SnapshotProgressStore,
         snapshot_report_interval_secs: u16,
         snapshot_name: String,
         wal_position: &ReplicationOffset,
     ) -> ReadySetResult<()> {
-        let mut client = pool.get().await?;
-
-        let transaction = client
-            .build_transaction()
-            .deferrable(true)
-            .isolation_level(pgsql::IsolationLevel::RepeatableRead)
-            .read_only(true)
-            .start()
-            .await?;
+        async {
+            let mut client = pool.get().await?;
 
-        // Ensure each table has a consistent view by using the same snapshot
-        let query = format!("SET TRANSACTION SNAPSHOT '{}'", snapshot_name);
-        transaction.query(query.as_str(), &[]).await?;
+            let transaction = client
+                .build_transaction()
+                .deferrable(true)
+                .isolation_level(pgsql::IsolationLevel::RepeatableRead)
+                .read_only(true)
+                .start()
+                .await?;
 
-        table
-            .dump(
-                &transaction,
-                noria_table,
-                snapshot_report_interval_secs,
-                wal_position,
-            )
-            .instrument(span.clone())
-            .await
-            .map_err(|e| ReadySetError::TableError {
-                table: table.name.clone(),
-                source: Box::new(e),
-            })
+            // Ensure each table has a consistent view by using the same snapshot
+            let query = format!("SET TRANSACTION SNAPSHOT '{}'", snapshot_name);
+            transaction.query(query.as_str(), &[]).await?;
+
+            let key = noria_table.schema().and_then(chunk_key_column);
+            let progress = match snapshot_progress
+                .resume(&mut noria_table, node, key.as_ref())
+                .await?
+            {
+                Some(progress) => Some(progress),
+                None => match key {
+                    Some(key) => {
+                        let progress = table.plan_chunks(&transaction, node, key, chunking).await?;
+                        if let Some(progress) = &progress {
+                            snapshot_progress.save(progress).await?;
+                        }
+                        progress
+                    }
+                    None => None,
+                },
+            };
+
+            match progress {
+                Some(progress) => {
+                    transaction.commit().await?;
+                    let chunks = progress.remaining_chunks();
+                    let key_column = progress.key_column.clone();
+                    table
+                        .dump_chunks(
+                            &pool,
+                            noria_table,
+                            ChunkTracker::new(progress, snapshot_progress),
+                            chunks,
+                            &key_column,
+                            chunking.parallelism,
+                            &snapshot_name,
+                            wal_position,
+                        )
+                        .await
+                }
+                None => {
+                    table
+                        .dump(
+                            &transaction,
+                            noria_table,
+                            snapshot_report_interval_secs,
+                            wal_position,
+                        )
+                        .await
+                }
+            }
+        }
+        .instrument(span.clone())
+        .await
+        .map_err(|e| ReadySetError::TableError {
+            table: table.name.clone(),
+            source: Box::new(e),
+        })
     }
 
     /// Snapshot the contents of the upstream database to ReadySet, starting with the DDL, followed
@@ -672,6 +933,12 @@ impl<'a> PostgresReplicator<'a> {
         let wal_position = PostgresPosition::from(replication_slot.consistent_point).into();
         self.set_snapshot(&replication_slot.snapshot_name).await?;
 
+        if full_snapshot {
+            // We're about to drop all the tables we've snapshotted so far, so any progress through
+            // snapshotting them no longer applies
+            self.snapshot_progress.clear().await?;
+        }
+
         let table_list = self.get_table_list(TableKind::RegularTable).await?;
         let view_list = self.get_table_list(TableKind::View).await?;
         let custom_types = self.get_custom_types().await?;
@@ -891,6 +1158,7 @@ impl<'a> PostgresReplicator<'a> {
         self.transaction.take().unwrap().commit().await?;
 
         // Finally copy each table into noria
+        let nodes = self.noria.tables().await?;
         let mut futs = Vec::with_capacity(tables.len());
         for table in &tables {
             let span =
@@ -898,7 +1166,7 @@ impl<'a> PostgresReplicator<'a> {
             span.in_scope(|| info!("Snapshotting table"));
             let mut noria_table = self
                 .noria
-                .table(table.name.clone())
+                .table_with_standbys(table.name.clone())
                 .instrument(span.clone())
                 .await?;
             span.in_scope(|| trace!("Setting snapshot mode"));
@@ -906,6 +1174,10 @@ impl<'a> PostgresReplicator<'a> {
             span.in_scope(|| trace!("Set snapshot mode"));
 
             let pool = self.pool.clone();
+            let node = nodes
+                .get(&table.name)
+                .ok_or_else(|| internal_err!("Snapshotting table missing from ReadySet"))?
+                .index();
 
             let snapshot_name = replication_slot.snapshot_name.clone();
             futs.push(Self::snapshot_table(
@@ -913,6 +1185,9 @@ impl<'a> PostgresReplicator<'a> {
                 span,
                 table,
                 noria_table,
+                node,
+                self.chunking,
+                self.snapshot_progress.clone(),
                 snapshot_report_interval_secs,
                 snapshot_name,
                 &wal_position,
@@ -928,6 +1203,7 @@ impl<'a> PostgresReplicator<'a> {
                     ReadySetError::TableError { ref table, .. } => {
                         warn!(%e, table=%table.display(Dialect::PostgreSQL), "Error snapshotting, table will not be used");
                         tables.retain(|t| t.name != *table);
+                        self.snapshot_progress.remove(table).await?;
                         self.noria
                             .extend_recipe_no_leader_ready(ChangeList::from_changes(
                                 vec![
@@ -1107,6 +1383,25 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn composite_and_multirange_columns_are_unsupported() {
+        let err = check_column_typtype("c", "point3d", b'c' as i8).unwrap_err();
+        assert!(matches!(err, ReadySetError::Unsupported(_)));
+        assert!(err
+            .to_string()
+            .contains("Column c has composite type point3d"));
+
+        let err = check_column_typtype("m", "int4multirange", b'm' as i8).unwrap_err();
+        assert!(matches!(err, ReadySetError::Unsupported(_)));
+        assert!(err
+            .to_string()
+            .contains("Column m has multirange type int4multirange"));
+
+        for typtype in [b'b', b'd', b'e', b'r'] {
+            check_column_typtype("x", "t", typtype as i8).unwrap();
+        }
+    }
+
     #[test]
     fn table_description_with_reserved_keywords_to_string_parses() {
         let desc = TableDescription {
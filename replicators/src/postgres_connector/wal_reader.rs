@@ -5,7 +5,7 @@ use std::sync::Arc;
 use bit_vec::BitVec;
 use mysql_time::MySqlTime;
 use postgres_types::Kind;
-use readyset_data::{Array, Collation, DfType, DfValue, Dialect};
+use readyset_data::{Array, Collation, DfType, DfValue, Dialect, Range};
 use readyset_errors::{unsupported, ReadySetError};
 use rust_decimal::prelude::FromStr;
 use rust_decimal::Decimal;
@@ -34,11 +34,17 @@ pub struct WalReader {
     relations: HashMap<i32, Relation>,
     /// Keeps track of the OIDs of all custom types we've seen
     custom_types: HashSet<u32>,
+    /// Map from the OIDs of domain types over built-in types to the OIDs of their base types
+    domain_base_types: HashMap<u32, u32>,
 }
 
 #[derive(Debug)]
 pub(crate) enum WalEvent {
     WantsKeepaliveResponse,
+    Begin {
+        /// The LSN of the commit record of the transaction
+        final_lsn: Lsn,
+    },
     Commit,
     Insert {
         schema: String,
@@ -76,10 +82,11 @@ pub(crate) enum WalEvent {
 }
 
 impl WalReader {
-    pub(crate) fn new(wal: pgsql::client::Responses) -> Self {
+    pub(crate) fn new(wal: pgsql::client::Responses, domain_base_types: HashMap<u32, u32>) -> Self {
         WalReader {
             relations: Default::default(),
             custom_types: Default::default(),
+            domain_base_types,
             wal,
         }
     }
@@ -89,6 +96,7 @@ impl WalReader {
             wal,
             relations,
             custom_types,
+            domain_base_types,
         } = self;
 
         loop {
@@ -121,7 +129,15 @@ impl WalReader {
 
             match record {
                 WalRecord::Commit { .. } => return Ok((WalEvent::Commit, end)),
-                WalRecord::Relation(mapping) => {
+                WalRecord::Relation(mut mapping) => {
+                    // Values of domain types are delivered in the same text format as values of
+                    // their base type, so we can just treat them as values of the base type
+                    for col in mapping.cols.iter_mut() {
+                        if let Some(base) = domain_base_types.get(&col.type_oid) {
+                            col.type_oid = *base;
+                        }
+                    }
+
                     // Store the relation in the hash map for future use
                     let id = mapping.id;
                     let schema = String::from_utf8(mapping.schema.to_vec()).map_err(|v| {
@@ -381,7 +397,9 @@ impl WalReader {
                         }
                     }
                 }
-                WalRecord::Begin { .. } => {}
+                WalRecord::Begin { final_lsn, .. } => {
+                    return Ok((WalEvent::Begin { final_lsn }, end))
+                }
                 WalRecord::Message {
                     prefix,
                     payload,
@@ -526,6 +544,23 @@ impl wal::TupleData {
                                 DfValue::from(str.parse::<Array>()?)
                                     .coerce_to(&target_type, &DfType::Unknown)?
                             }
+                            Kind::Range(element_type) => {
+                                let dialect = Dialect::DEFAULT_POSTGRESQL;
+                                let subsecond_digits = dialect.default_subsecond_digits();
+
+                                let target_type = DfType::Range(Box::new(match *element_type {
+                                    PGType::INT4 => DfType::Int,
+                                    PGType::INT8 => DfType::BigInt,
+                                    PGType::NUMERIC => DfType::DEFAULT_NUMERIC,
+                                    PGType::TIMESTAMP => DfType::Timestamp { subsecond_digits },
+                                    PGType::TIMESTAMPTZ => DfType::TimestampTz { subsecond_digits },
+                                    PGType::DATE => DfType::Date,
+                                    ref ty => unsupported!("Unsupported range type: {ty}"),
+                                }));
+
+                                DfValue::from(str.parse::<Range>()?)
+                                    .coerce_to(&target_type, &DfType::Unknown)?
+                            }
                             Kind::Enum(variants) => DfValue::from(
                                 variants
                                     .iter()
@@ -0,0 +1,704 @@
+//! Chunked, resumable snapshotting of large tables.
+//!
+//! Tables with a single-column integer primary key and more than
+//! [`snapshot_chunk_size`](database_utils::UpstreamConfig::snapshot_chunk_size) rows are split into
+//! ranges of that key, which are copied concurrently. Each chunk is copied in key order, and as it
+//! is copied its progress is recorded in the authority, so that if snapshotting is interrupted it
+//! can resume where it left off instead of starting again from scratch.
+//!
+//! Progress through a chunk is recorded as a checkpoint before writing each group of
+//! [`ROWS_PER_CHECKPOINT`] rows, identifying both the key up to which all rows have been written
+//! and the range of keys of the rows about to be written. When resuming, rows in that range may
+//! have been only partially written, so they are deleted by key before the rest of the chunk is
+//! copied again.
+//!
+//! When a snapshot is resumed, rows copied before the interruption reflect the upstream table as of
+//! an earlier replication offset than rows copied afterwards. The table's replication offset is set
+//! to the earliest of those offsets, and while catching up to the latest one, the
+//! [`NoriaAdapter`](crate::NoriaAdapter) uses the recorded progress to discard replicated writes to
+//! rows in chunks that were copied after the write happened (see
+//! [`TableSnapshotProgress::is_reflected_in_snapshot`]).
+
+use std::convert::Infallible;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use futures::{pin_mut, Stream, StreamExt};
+use nom_sql::{ColumnConstraint, CreateTableBody, Relation, SqlIdentifier, SqlType, TableKey};
+use readyset_client::consensus::{Authority, AuthorityControl};
+use readyset_client::replication::ReplicationOffset;
+use readyset_client::{Table, TableOperation};
+use readyset_data::DfValue;
+use readyset_errors::{internal_err, ReadySetResult};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// The path in the authority at which snapshot progress is stored
+const SNAPSHOT_PROGRESS_PATH: &str = "/snapshot_progress";
+
+/// How many rows of a chunk to copy between recording checkpoints
+const ROWS_PER_CHECKPOINT: usize = 1 << 16;
+
+/// How many rows to write to ReadySet in a single request while copying a chunk
+const BATCH_SIZE: usize = 1024;
+
+/// The maximum number of keys we're willing to delete from a partially-written checkpoint when
+/// resuming a snapshot. If the keys of a table are so sparse that there are more than this many
+/// possible keys in the range, the table is snapshotted again from scratch instead.
+const MAX_STALE_KEYS: u64 = 1 << 24;
+
+/// Configuration for splitting large tables into chunks while snapshotting
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ChunkingConfig {
+    /// The approximate number of rows in each chunk, or 0 to disable chunking
+    pub(crate) chunk_size: usize,
+    /// The maximum number of chunks of a table to copy at once
+    pub(crate) parallelism: usize,
+}
+
+impl From<&database_utils::UpstreamConfig> for ChunkingConfig {
+    fn from(config: &database_utils::UpstreamConfig) -> Self {
+        Self {
+            chunk_size: config.snapshot_chunk_size,
+            parallelism: config.snapshot_chunk_parallelism.max(1),
+        }
+    }
+}
+
+impl ChunkingConfig {
+    /// Returns the number of chunks to split a table with `nrows` rows into, or `None` if the
+    /// table shouldn't be split
+    pub(crate) fn num_chunks(&self, nrows: u64) -> Option<usize> {
+        let chunk_size = self.chunk_size as u64;
+        (chunk_size != 0 && nrows > chunk_size)
+            .then(|| ((nrows + chunk_size - 1) / chunk_size) as usize)
+    }
+}
+
+/// The state of a chunk which has started, but not finished, being copied
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ChunkCopy {
+    /// The replication offset at which the chunk is being copied
+    pub(crate) offset: ReplicationOffset,
+    /// All rows in the chunk with keys up to and including this value have been written
+    pub(crate) written_through: Option<i64>,
+    /// Rows in the chunk with keys in this range are being written, and may have been only
+    /// partially written
+    pub(crate) writing: (i64, i64),
+}
+
+/// A range of primary key values of a table, copied as a unit while snapshotting
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SnapshotChunk {
+    /// The inclusive lower bound of the range, or `None` if the range is unbounded below
+    pub(crate) lower: Option<i64>,
+    /// The exclusive upper bound of the range, or `None` if the range is unbounded above
+    pub(crate) upper: Option<i64>,
+    /// The replication offset at which all rows in this chunk were copied, if they have been
+    pub(crate) copied_at: Option<ReplicationOffset>,
+    /// The progress of copying this chunk, if it's been started but not finished
+    pub(crate) copying: Option<ChunkCopy>,
+}
+
+impl SnapshotChunk {
+    fn new(lower: Option<i64>, upper: Option<i64>) -> Self {
+        Self {
+            lower,
+            upper,
+            copied_at: None,
+            copying: None,
+        }
+    }
+
+    /// Returns true if the given primary key value falls within this chunk
+    pub(crate) fn contains(&self, key: i64) -> bool {
+        self.lower.iter().all(|lower| key >= *lower) && self.upper.iter().all(|upper| key < *upper)
+    }
+
+    /// Returns a SQL condition selecting the rows in this chunk, given the already-quoted name of
+    /// the key column
+    pub(crate) fn condition(&self, quoted_key_column: &str) -> String {
+        match (self.lower, self.upper) {
+            (None, None) => "TRUE".to_owned(),
+            (Some(lower), None) => format!("{quoted_key_column} >= {lower}"),
+            (None, Some(upper)) => format!("{quoted_key_column} < {upper}"),
+            (Some(lower), Some(upper)) => {
+                format!("{quoted_key_column} >= {lower} AND {quoted_key_column} < {upper}")
+            }
+        }
+    }
+}
+
+/// The progress of a chunked snapshot of a single table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TableSnapshotProgress {
+    pub(crate) table: Relation,
+    /// The index of the base table node in ReadySet that chunks were copied into. If the base
+    /// table is dropped and recreated, the progress no longer reflects its contents.
+    pub(crate) node: usize,
+    /// The name of the primary key column that the table is split on
+    pub(crate) key_column: SqlIdentifier,
+    /// The index of the primary key column within the table's columns
+    pub(crate) key_index: usize,
+    pub(crate) chunks: Vec<SnapshotChunk>,
+}
+
+impl TableSnapshotProgress {
+    /// Split the range of values `min..=max` of the given key column into `num_chunks` chunks of
+    /// (roughly) equal width, none of which have been copied yet.
+    ///
+    /// The first and last chunks are unbounded, to include rows inserted upstream outside the
+    /// range between planning the chunks and copying them.
+    pub(crate) fn new(
+        table: Relation,
+        node: usize,
+        key_column: SqlIdentifier,
+        key_index: usize,
+        min: i64,
+        max: i64,
+        num_chunks: usize,
+    ) -> Self {
+        let span = (max as i128 - min as i128 + 1).max(1);
+        let num_chunks = (num_chunks as i128).clamp(1, span);
+        let width = (span + num_chunks - 1) / num_chunks;
+        let chunks = (0..num_chunks)
+            .map(|i| {
+                SnapshotChunk::new(
+                    (i != 0).then(|| (min as i128 + i * width) as i64),
+                    (i != num_chunks - 1).then(|| (min as i128 + (i + 1) * width) as i64),
+                )
+            })
+            .collect();
+
+        Self {
+            table,
+            node,
+            key_column,
+            key_index,
+            chunks,
+        }
+    }
+
+    /// Returns true if every chunk of the table has been copied
+    pub(crate) fn is_complete(&self) -> bool {
+        self.chunks.iter().all(|c| c.copied_at.is_some())
+    }
+
+    fn copied_at(&self) -> impl Iterator<Item = &ReplicationOffset> {
+        self.chunks.iter().filter_map(|c| c.copied_at.as_ref())
+    }
+
+    /// Returns the earliest replication offset at which any chunk was copied. Once all chunks
+    /// have been copied, this is the replication offset for the table as a whole.
+    pub(crate) fn earliest_offset(&self) -> Option<&ReplicationOffset> {
+        self.copied_at()
+            .reduce(|earliest, offset| if offset < earliest { offset } else { earliest })
+    }
+
+    /// Returns the latest replication offset at which any chunk was copied
+    pub(crate) fn latest_offset(&self) -> Option<&ReplicationOffset> {
+        self.copied_at()
+            .reduce(|latest, offset| if offset > latest { offset } else { latest })
+    }
+
+    /// Returns true if chunks of the table were copied at different replication offsets, meaning
+    /// that replicated writes need to be filtered while catching the table up to the latest one
+    pub(crate) fn needs_catch_up(&self) -> bool {
+        self.earliest_offset() != self.latest_offset()
+    }
+
+    /// Returns the chunks which have yet to be copied, along with their indices
+    pub(crate) fn remaining_chunks(&self) -> Vec<(usize, SnapshotChunk)> {
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.copied_at.is_none())
+            .map(|(i, c)| (i, c.clone()))
+            .collect()
+    }
+
+    /// Prepare to resume copying chunks that were interrupted part-way through.
+    ///
+    /// Each such chunk is split in two: the rows that were fully written before the interruption,
+    /// which are now considered copied, and the rest of the rows, which will be copied again
+    /// from scratch. Returns the ranges of keys of rows which may have been only partially written,
+    /// and need to be deleted before copying resumes, or `None` if there are too many keys in
+    /// those ranges to delete them one by one.
+    fn prepare_resume(&mut self) -> Option<Vec<RangeInclusive<i64>>> {
+        let mut stale_keys = vec![];
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for mut chunk in self.chunks.drain(..) {
+            let Some(copy) = chunk.copying.take() else {
+                chunks.push(chunk);
+                continue;
+            };
+
+            stale_keys.push(copy.writing.0..=copy.writing.1);
+            match copy.written_through.map(|k| k.checked_add(1)) {
+                Some(Some(next)) if chunk.upper != Some(next) => {
+                    chunks.push(SnapshotChunk {
+                        copied_at: Some(copy.offset),
+                        ..SnapshotChunk::new(chunk.lower, Some(next))
+                    });
+                    chunks.push(SnapshotChunk::new(Some(next), chunk.upper));
+                }
+                Some(_) => chunks.push(SnapshotChunk {
+                    copied_at: Some(copy.offset),
+                    ..chunk
+                }),
+                None => chunks.push(chunk),
+            }
+        }
+        self.chunks = chunks;
+
+        let num_stale_keys = stale_keys
+            .iter()
+            .map(|r| (*r.end() as i128 - *r.start() as i128 + 1) as u128)
+            .sum::<u128>();
+        (num_stale_keys <= MAX_STALE_KEYS as u128).then_some(stale_keys)
+    }
+
+    /// Returns true if the effects of the given table operation, replicated at `offset`, are
+    /// already reflected in the snapshotted contents of the table, because it affects a row in a
+    /// chunk that was copied at or after that offset.
+    pub(crate) fn is_reflected_in_snapshot(
+        &self,
+        op: &TableOperation,
+        offset: &ReplicationOffset,
+    ) -> bool {
+        let key = match op {
+            TableOperation::Insert(row)
+            | TableOperation::DeleteRow { row }
+            | TableOperation::InsertOrUpdate { row, .. } => row.get(self.key_index),
+            // Tables are only chunked by single-column primary keys
+            TableOperation::DeleteByKey { key } | TableOperation::Update { key, .. } => key.first(),
+            _ => None,
+        };
+        let Some(key) = key.and_then(|k| i64::try_from(k).ok()) else {
+            return false;
+        };
+
+        self.chunks
+            .iter()
+            .find(|c| c.contains(key))
+            .and_then(|c| c.copied_at.as_ref())
+            .map_or(false, |copied_at| offset <= copied_at)
+    }
+}
+
+/// Returns the name and index of the column a table with the given schema can be split into
+/// chunks on while snapshotting, if any.
+///
+/// Only tables with a single-column integer primary key are split, since ranges of those can be
+/// planned without scanning the table.
+pub(crate) fn chunk_key_column(schema: &CreateTableBody) -> Option<(SqlIdentifier, usize)> {
+    let key_column = match schema.keys.iter().flatten().find_map(|key| match key {
+        TableKey::PrimaryKey { columns, .. } => Some(columns),
+        _ => None,
+    }) {
+        Some(columns) if columns.len() == 1 => columns[0].name.clone(),
+        Some(_) => return None,
+        None => schema
+            .fields
+            .iter()
+            .find(|f| f.constraints.contains(&ColumnConstraint::PrimaryKey))?
+            .column
+            .name
+            .clone(),
+    };
+
+    let (index, field) = schema
+        .fields
+        .iter()
+        .enumerate()
+        .find(|(_, f)| f.column.name == key_column)?;
+
+    matches!(
+        field.sql_type,
+        SqlType::TinyInt(_)
+            | SqlType::UnsignedTinyInt(_)
+            | SqlType::SmallInt(_)
+            | SqlType::UnsignedSmallInt(_)
+            | SqlType::Int(_)
+            | SqlType::UnsignedInt(_)
+            | SqlType::BigInt(_)
+            | SqlType::Int2
+            | SqlType::Int4
+            | SqlType::Int8
+    )
+    .then_some((key_column, index))
+}
+
+/// Persists [`TableSnapshotProgress`] in the authority
+#[derive(Clone)]
+pub(crate) struct SnapshotProgressStore {
+    authority: Arc<Authority>,
+    /// Serializes updates made by concurrent snapshot tasks, since not all authorities detect
+    /// conflicting writes
+    lock: Arc<Mutex<()>>,
+}
+
+impl SnapshotProgressStore {
+    pub(crate) fn new(authority: Arc<Authority>) -> Self {
+        Self {
+            authority,
+            lock: Default::default(),
+        }
+    }
+
+    /// Load the snapshot progress of all tables
+    pub(crate) async fn load(&self) -> ReadySetResult<Vec<TableSnapshotProgress>> {
+        Ok(self
+            .authority
+            .try_read(SNAPSHOT_PROGRESS_PATH)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Record the snapshot progress of a table, replacing any existing progress for that table
+    pub(crate) async fn save(&self, progress: &TableSnapshotProgress) -> ReadySetResult<()> {
+        self.modify(|all| {
+            all.retain(|p| p.table != progress.table);
+            all.push(progress.clone());
+        })
+        .await
+    }
+
+    /// Remove the snapshot progress of the given table
+    pub(crate) async fn remove(&self, table: &Relation) -> ReadySetResult<()> {
+        self.modify(|all| all.retain(|p| p.table != *table)).await
+    }
+
+    /// Remove the snapshot progress of all tables
+    pub(crate) async fn clear(&self) -> ReadySetResult<()> {
+        self.modify(|all| all.clear()).await
+    }
+
+    async fn modify<F>(&self, mut f: F) -> ReadySetResult<()>
+    where
+        F: FnMut(&mut Vec<TableSnapshotProgress>) + Send,
+    {
+        let _guard = self.lock.lock().await;
+        self.authority
+            .read_modify_write(
+                SNAPSHOT_PROGRESS_PATH,
+                |progress: Option<Vec<TableSnapshotProgress>>| {
+                    let mut progress = progress.unwrap_or_default();
+                    f(&mut progress);
+                    Ok::<_, Infallible>(progress)
+                },
+            )
+            .await?
+            .unwrap_or_else(|e| match e {});
+        Ok(())
+    }
+
+    /// Look up the progress of a previous, interrupted snapshot of the given table which can be
+    /// resumed, and prepare to resume it by deleting any rows from the table that may have been
+    /// only partially written.
+    ///
+    /// If there is no such progress, or it can't be resumed, removes any existing progress for the
+    /// table and truncates it, so that it can be snapshotted again from scratch.
+    pub(crate) async fn resume(
+        &self,
+        noria_table: &mut Table,
+        node: usize,
+        key: Option<&(SqlIdentifier, usize)>,
+    ) -> ReadySetResult<Option<TableSnapshotProgress>> {
+        let table = noria_table.table_name().clone();
+        let progress = self.load().await?.into_iter().find(|p| p.table == table);
+
+        if let Some(mut progress) = progress {
+            if progress.node == node
+                && key.map_or(false, |(column, index)| {
+                    progress.key_column == *column && progress.key_index == *index
+                })
+            {
+                if let Some(stale_keys) = progress.prepare_resume() {
+                    info!(
+                        chunks = progress.chunks.len(),
+                        remaining = progress.remaining_chunks().len(),
+                        "Resuming interrupted snapshot"
+                    );
+                    let mut stale_keys = stale_keys.into_iter().flatten().peekable();
+                    while stale_keys.peek().is_some() {
+                        let deletes = stale_keys
+                            .by_ref()
+                            .take(BATCH_SIZE)
+                            .map(|key| TableOperation::DeleteByKey {
+                                key: vec![key.into()],
+                            })
+                            .collect::<Vec<_>>();
+                        noria_table.perform_all(deletes).await?;
+                    }
+                    self.save(&progress).await?;
+                    return Ok(Some(progress));
+                }
+                warn!("Too many partially-written rows to resume interrupted snapshot");
+            }
+            self.remove(&table).await?;
+        }
+
+        // Clear out any rows written by a previous attempt to snapshot the table
+        noria_table.truncate().await?;
+        Ok(None)
+    }
+}
+
+/// Tracks the progress of copying the chunks of a single table, persisting it as the chunks are
+/// copied
+pub(crate) struct ChunkTracker {
+    progress: Mutex<TableSnapshotProgress>,
+    key_index: usize,
+    store: SnapshotProgressStore,
+}
+
+impl ChunkTracker {
+    pub(crate) fn new(progress: TableSnapshotProgress, store: SnapshotProgressStore) -> Self {
+        Self {
+            key_index: progress.key_index,
+            progress: Mutex::new(progress),
+            store,
+        }
+    }
+
+    async fn update<F>(&self, index: usize, f: F) -> ReadySetResult<()>
+    where
+        F: FnOnce(&mut SnapshotChunk),
+    {
+        let mut progress = self.progress.lock().await;
+        f(&mut progress.chunks[index]);
+        self.store.save(&progress).await
+    }
+
+    /// Copy the given rows, which must be all the rows in the chunk at the given index in key
+    /// order, into the table, recording a checkpoint before writing each group of rows and marking
+    /// the chunk as copied at `offset` once all rows have been written. Returns the number of rows
+    /// copied.
+    pub(crate) async fn copy_chunk<S>(
+        &self,
+        index: usize,
+        offset: &ReplicationOffset,
+        rows: S,
+        noria_table: &mut Table,
+    ) -> ReadySetResult<usize>
+    where
+        S: Stream<Item = ReadySetResult<Vec<DfValue>>>,
+    {
+        let groups = rows.chunks(ROWS_PER_CHECKPOINT);
+        pin_mut!(groups);
+
+        let mut written_through = None;
+        let mut cnt = 0;
+        while let Some(group) = groups.next().await {
+            let group = group.into_iter().collect::<ReadySetResult<Vec<_>>>()?;
+            let key = |row: Option<&Vec<DfValue>>| -> ReadySetResult<i64> {
+                row.and_then(|row| row.get(self.key_index))
+                    .and_then(|key| i64::try_from(key).ok())
+                    .ok_or_else(|| internal_err!("Invalid key in chunked snapshot"))
+            };
+            let writing = (key(group.first())?, key(group.last())?);
+
+            self.update(index, |chunk| {
+                chunk.copying = Some(ChunkCopy {
+                    offset: offset.clone(),
+                    written_through,
+                    writing,
+                })
+            })
+            .await?;
+
+            cnt += group.len();
+            for batch in group.chunks(BATCH_SIZE) {
+                noria_table.insert_many(batch.to_vec()).await?;
+            }
+            written_through = Some(writing.1);
+        }
+
+        self.update(index, |chunk| {
+            chunk.copying = None;
+            chunk.copied_at = Some(offset.clone());
+        })
+        .await?;
+
+        Ok(cnt)
+    }
+
+    /// Finish copying the table, setting its replication offset to the earliest offset at which
+    /// any of its chunks were copied, and taking it out of snapshot mode.
+    ///
+    /// If the table doesn't need to be caught up (because all chunks were copied at the same
+    /// replication offset), its progress is then removed from the authority.
+    pub(crate) async fn finish(self, noria_table: &mut Table) -> ReadySetResult<()> {
+        let progress = self.progress.into_inner();
+        let offset = progress
+            .earliest_offset()
+            .ok_or_else(|| internal_err!("Finished snapshotting table without copying chunks"))?;
+        noria_table
+            .perform_all([
+                TableOperation::SetReplicationOffset(offset.clone()),
+                TableOperation::SetSnapshotMode(false),
+            ])
+            .await?;
+
+        if !progress.needs_catch_up() {
+            self.store.remove(&progress.table).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(offset: u128) -> ReplicationOffset {
+        ReplicationOffset {
+            offset,
+            replication_log_name: "binlog".to_owned(),
+        }
+    }
+
+    fn progress(min: i64, max: i64, num_chunks: usize) -> TableSnapshotProgress {
+        TableSnapshotProgress::new(
+            Relation {
+                schema: Some("s".into()),
+                name: "t".into(),
+            },
+            1,
+            "id".into(),
+            0,
+            min,
+            max,
+            num_chunks,
+        )
+    }
+
+    #[test]
+    fn chunks_cover_all_keys() {
+        let progress = progress(1, 100, 3);
+        assert_eq!(progress.chunks.len(), 3);
+        assert_eq!(progress.chunks[0].lower, None);
+        assert_eq!(progress.chunks[2].upper, None);
+        for key in [i64::MIN, -5, 1, 34, 35, 68, 100, 1000, i64::MAX] {
+            assert_eq!(
+                progress.chunks.iter().filter(|c| c.contains(key)).count(),
+                1,
+                "key {key}"
+            );
+        }
+    }
+
+    #[test]
+    fn more_chunks_than_keys() {
+        let progress = progress(5, 6, 10);
+        assert_eq!(progress.chunks.len(), 2);
+        assert!(progress.chunks[0].contains(5));
+        assert!(progress.chunks[1].contains(6));
+    }
+
+    #[test]
+    fn full_key_range() {
+        let progress = progress(i64::MIN, i64::MAX, 4);
+        assert_eq!(progress.chunks.len(), 4);
+        assert!(progress.chunks[0].contains(i64::MIN));
+        assert!(progress.chunks[3].contains(i64::MAX));
+    }
+
+    #[test]
+    fn chunk_condition() {
+        let progress = progress(1, 100, 3);
+        assert_eq!(progress.chunks[0].condition("\"id\""), "\"id\" < 35");
+        assert_eq!(
+            progress.chunks[1].condition("\"id\""),
+            "\"id\" >= 35 AND \"id\" < 69"
+        );
+        assert_eq!(progress.chunks[2].condition("\"id\""), "\"id\" >= 69");
+    }
+
+    #[test]
+    fn num_chunks() {
+        let config = |chunk_size| ChunkingConfig {
+            chunk_size,
+            parallelism: 1,
+        };
+        assert_eq!(config(0).num_chunks(100), None);
+        assert_eq!(config(100).num_chunks(100), None);
+        assert_eq!(config(100).num_chunks(101), Some(2));
+        assert_eq!(config(100).num_chunks(1000), Some(10));
+    }
+
+    #[test]
+    fn prepare_resume_splits_interrupted_chunks() {
+        let mut progress = progress(1, 100, 3);
+        progress.chunks[0].copied_at = Some(offset(10));
+        progress.chunks[1].copying = Some(ChunkCopy {
+            offset: offset(10),
+            written_through: Some(50),
+            writing: (51, 60),
+        });
+        progress.chunks[2].copying = Some(ChunkCopy {
+            offset: offset(10),
+            written_through: None,
+            writing: (69, 80),
+        });
+
+        let stale_keys = progress.prepare_resume().unwrap();
+        assert_eq!(stale_keys, vec![51..=60, 69..=80]);
+        assert_eq!(progress.chunks.len(), 4);
+        assert_eq!(progress.chunks[1].lower, Some(35));
+        assert_eq!(progress.chunks[1].upper, Some(51));
+        assert_eq!(progress.chunks[1].copied_at, Some(offset(10)));
+        assert_eq!(progress.chunks[2].lower, Some(51));
+        assert_eq!(progress.chunks[2].upper, Some(69));
+        assert_eq!(progress.chunks[2].copied_at, None);
+        assert_eq!(progress.chunks[3].lower, Some(69));
+        assert_eq!(progress.chunks[3].copied_at, None);
+        assert!(progress.chunks.iter().all(|c| c.copying.is_none()));
+        assert_eq!(
+            progress
+                .remaining_chunks()
+                .into_iter()
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn prepare_resume_too_many_stale_keys() {
+        let mut progress = progress(0, i64::MAX, 2);
+        progress.chunks[1].copying = Some(ChunkCopy {
+            offset: offset(10),
+            written_through: None,
+            writing: (0, i64::MAX),
+        });
+        assert_eq!(progress.prepare_resume(), None);
+    }
+
+    #[test]
+    fn filters_writes_reflected_in_snapshot() {
+        let mut progress = progress(1, 100, 2);
+        progress.chunks[0].copied_at = Some(offset(10));
+        progress.chunks[1].copied_at = Some(offset(20));
+        assert!(progress.is_complete());
+        assert!(progress.needs_catch_up());
+        assert_eq!(progress.earliest_offset(), Some(&offset(10)));
+        assert_eq!(progress.latest_offset(), Some(&offset(20)));
+
+        let insert = |id: i64| TableOperation::Insert(vec![DfValue::from(id), "x".into()]);
+        // Written after the first chunk was copied, so needs to be applied
+        assert!(!progress.is_reflected_in_snapshot(&insert(1), &offset(15)));
+        // Written before the second chunk was copied, so already reflected in it
+        assert!(progress.is_reflected_in_snapshot(&insert(90), &offset(15)));
+        assert!(!progress.is_reflected_in_snapshot(&insert(90), &offset(21)));
+        assert!(progress.is_reflected_in_snapshot(
+            &TableOperation::DeleteByKey {
+                key: vec![DfValue::from(90)]
+            },
+            &offset(20)
+        ));
+        assert!(!progress.is_reflected_in_snapshot(&TableOperation::Truncate, &offset(15)));
+    }
+}
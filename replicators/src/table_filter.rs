@@ -2,22 +2,27 @@ use std::borrow::Borrow;
 use std::collections::{BTreeMap, BTreeSet};
 
 use nom_locate::LocatedSpan;
-use nom_sql::{replicator_table_list, Dialect, SqlIdentifier};
+use nom_sql::{replicator_table_list, Dialect, Relation, SqlIdentifier};
+use readyset_client::replication::ReplicationTablesChange;
 use readyset_errors::{ReadySetError, ReadySetResult};
 use readyset_util::redacted::RedactedString;
 
 /// A [`TableFilter`] keeps lists of all the tables readyset-server is interested in, as well as a
 /// list of tables that we explicitly want to filter out of replication.
-/// Tables may be filtered from replication in 2 ways:
+/// Tables may be filtered from replication in 4 ways:
 /// 1. All tables will be filtered other than the ones provided to the option --replication_tables,
 ///    if it is used
-/// 2. If we encounter a unrecoverable failure in replication for a table, we can filter out the
+/// 2. Any tables provided to the option --replication-tables-ignore will be filtered
+/// 3. Tables may be added to or removed from replication at runtime, via `ALTER READYSET ADD
+///    TABLES` and `ALTER READYSET DROP TABLES`
+/// 4. If we encounter a unrecoverable failure in replication for a table, we can filter out the
 ///    table to keep the process running without that table, which is better than being stuck until
 ///    we fix why that table isn't replicating.
 ///
-/// NOTE: 2. takes precedence over 1. above. So if a table is explicitly replicated with
-/// --replication_tables, but then experiences an error in replication, we will stop replicating
-/// that table.
+/// NOTE: Denying a table by name (as in 4. above, or by dropping it by name) takes precedence over
+/// everything else. Otherwise, tables that are replicated by name take precedence over any glob
+/// patterns that deny them, so that a single table can be added back after dropping a pattern
+/// that matches it.
 ///
 /// When a replication event happens, the event is filtered based on its
 /// schema/table before being sent to readyset-server.
@@ -28,14 +33,25 @@ use readyset_util::redacted::RedactedString;
 /// lookup.
 #[derive(Debug, Clone)]
 pub(crate) struct TableFilter {
+    /// The SQL dialect to use when parsing lists of tables
+    dialect: Dialect,
+    /// The schema to use for tables that are provided without one
+    default_schema: Option<SqlIdentifier>,
+    /// If true, all tables that aren't denied will be replicated, regardless of the contents of
+    /// `explicitly_replicated` and `replicated_patterns`
+    replicate_all: bool,
     /// A mapping between schema to the list of tables to replicate from that schema.
-    /// Only the tables included in the map will be replicated.
-    /// This is only populated by the --rplication-tables option
+    /// Only the tables included in the map (or matching `replicated_patterns`) will be
+    /// replicated, unless `replicate_all` is set.
     explicitly_replicated: BTreeMap<SqlIdentifier, ReplicateTableSpec>,
+    /// Glob patterns for tables to replicate in addition to the ones in `explicitly_replicated`
+    replicated_patterns: Vec<TablePattern>,
     /// A mapping between schema to the list of tables to *NOT* replicate from that schema.
     /// Any other valid tables will be replicated, where a valid table is either one of the tables
     /// in `explicitly_replicated`, or all tables if that is empty.
     replication_denied: BTreeMap<SqlIdentifier, ReplicateTableSpec>,
+    /// Glob patterns for tables to *NOT* replicate
+    denied_patterns: Vec<TablePattern>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,84 +104,224 @@ impl ReplicateTableSpec {
     }
 }
 
+/// A glob pattern matching tables by both schema and table name, such as `public.user_*`.
+///
+/// Within each of the schema and the table name, `*` matches any sequence of characters and `?`
+/// matches any single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TablePattern {
+    schema: SqlIdentifier,
+    table: SqlIdentifier,
+}
+
+impl TablePattern {
+    fn matches(&self, schema: &str, table: &str) -> bool {
+        glob_matches(self.schema.as_bytes(), schema.as_bytes())
+            && glob_matches(self.table.as_bytes(), table.as_bytes())
+    }
+}
+
+/// Returns true if the given schema or table name contains any glob wildcards
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+/// Match `s` against the glob `pattern`, backtracking to the most recent `*` on a mismatch
+fn glob_matches(pattern: &[u8], s: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // The position of the last `*` we saw in the pattern, along with the position in `s` it's
+    // currently matched up to
+    let mut star = None;
+    while let Some(c) = s.get(i) {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, i));
+                p += 1;
+            }
+            Some(pc) if *pc == b'?' || pc == c => {
+                p += 1;
+                i += 1;
+            }
+            _ => match star {
+                Some((star_p, star_i)) => {
+                    p = star_p + 1;
+                    i = star_i + 1;
+                    star = Some((star_p, star_i + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern.iter().skip(p).all(|c| *c == b'*')
+}
+
 impl TableFilter {
     pub(crate) fn try_new(
         dialect: Dialect,
         filter_table_list: Option<RedactedString>,
+        filter_table_ignore_list: Option<RedactedString>,
         default_schema: Option<&str>,
     ) -> ReadySetResult<TableFilter> {
         let default_schema = default_schema.map(SqlIdentifier::from);
 
-        let mut schemas = BTreeMap::new();
+        let mut filter = TableFilter {
+            dialect,
+            default_schema: default_schema.clone(),
+            replicate_all: false,
+            explicitly_replicated: BTreeMap::new(),
+            replicated_patterns: Vec::new(),
+            replication_denied: BTreeMap::new(),
+            denied_patterns: Vec::new(),
+        };
 
-        let filtered = match filter_table_list {
+        match filter_table_list {
             None => {
                 match default_schema {
                     Some(default) => {
                         // Will load all tables for the default schema
-                        schemas.insert(default, ReplicateTableSpec::empty_all_tables());
-                        return Ok(TableFilter {
-                            explicitly_replicated: schemas,
-                            replication_denied: BTreeMap::new(),
-                        });
+                        filter
+                            .explicitly_replicated
+                            .insert(default, ReplicateTableSpec::empty_all_tables());
                     }
                     None => {
                         // We will learn what the tables are by `update_table_list` at snapshot
                         // time since `for_all_schemas` is true.
-                        return Ok(Self::for_all_tables());
+                        filter.replicate_all = true;
                     }
                 };
             }
-            Some(t) => t,
-        };
-
-        if filtered.as_str() == "*.*" {
-            return Ok(Self::for_all_tables());
+            Some(filtered) if filtered.as_str() == "*.*" => filter.replicate_all = true,
+            Some(filtered) => {
+                for (table_schema, table_name) in filter.parse_table_list(filtered.as_str())? {
+                    if table_name == "*" && !is_pattern(&table_schema) {
+                        filter
+                            .explicitly_replicated
+                            .insert(table_schema, ReplicateTableSpec::empty_all_tables());
+                    } else if is_pattern(&table_schema) || is_pattern(&table_name) {
+                        filter.replicated_patterns.push(TablePattern {
+                            schema: table_schema,
+                            table: table_name,
+                        });
+                    } else {
+                        let tables = filter
+                            .explicitly_replicated
+                            .entry(table_schema)
+                            .or_insert_with(ReplicateTableSpec::empty);
+                        tables.insert(table_name);
+                    }
+                }
+            }
         }
 
-        let filter_list =
-            match replicator_table_list(dialect)(LocatedSpan::new(filtered.as_bytes())) {
-                Ok((rem, tables)) if rem.is_empty() => tables,
-                _ => {
-                    return Err(ReadySetError::ReplicationFailed(
-                        "Unable to parse filtered tables list".to_string(),
-                    ))
-                }
-            };
-
-        for table in filter_list {
-            let table_name = table.name;
-            let table_schema =
-                table
-                    .schema
-                    .or_else(|| default_schema.clone())
-                    .ok_or_else(|| {
-                        ReadySetError::ReplicationFailed(format!(
-                            "No database and no default database for table {table_name}"
-                        ))
-                    })?;
-
-            if table_name == "*" {
-                schemas.insert(table_schema, ReplicateTableSpec::empty_all_tables());
-            } else {
-                let tables = schemas
-                    .entry(table_schema)
-                    .or_insert_with(ReplicateTableSpec::empty);
-                tables.insert(table_name);
+        if let Some(ignored) = filter_table_ignore_list {
+            for (schema, table) in filter.parse_table_list(ignored.as_str())? {
+                filter.deny(schema, table);
             }
         }
 
-        Ok(TableFilter {
-            explicitly_replicated: schemas,
-            replication_denied: BTreeMap::new(),
-        })
+        Ok(filter)
     }
 
     /// Create a new filter that will pass all tables
+    #[cfg(test)]
     fn for_all_tables() -> Self {
         Self {
+            dialect: Dialect::MySQL,
+            default_schema: None,
+            replicate_all: true,
             explicitly_replicated: BTreeMap::new(),
+            replicated_patterns: Vec::new(),
             replication_denied: BTreeMap::new(),
+            denied_patterns: Vec::new(),
+        }
+    }
+
+    /// Parse a comma-separated list of (potentially glob-patterned) tables, in the format
+    /// accepted by --replication-tables, into a list of schemas and table names
+    fn parse_table_list(&self, list: &str) -> ReadySetResult<Vec<(SqlIdentifier, SqlIdentifier)>> {
+        let tables = match replicator_table_list(self.dialect)(LocatedSpan::new(list.as_bytes())) {
+            Ok((rem, tables)) if rem.is_empty() => tables,
+            _ => {
+                return Err(ReadySetError::ReplicationFailed(
+                    "Unable to parse filtered tables list".to_string(),
+                ))
+            }
+        };
+
+        tables
+            .into_iter()
+            .map(|table| self.resolve_schema(table))
+            .collect()
+    }
+
+    /// Return the schema and name of the given table, using the default schema if it doesn't have
+    /// one
+    fn resolve_schema(&self, table: Relation) -> ReadySetResult<(SqlIdentifier, SqlIdentifier)> {
+        let table_name = table.name;
+        let table_schema = table
+            .schema
+            .or_else(|| self.default_schema.clone())
+            .ok_or_else(|| {
+                ReadySetError::ReplicationFailed(format!(
+                    "No database and no default database for table {table_name}"
+                ))
+            })?;
+        Ok((table_schema, table_name))
+    }
+
+    /// Apply a change made at runtime to the set of tables to replicate. Tables that don't have a
+    /// schema are ignored if we don't have a default schema either.
+    pub(crate) fn apply_change(&mut self, change: &ReplicationTablesChange) {
+        let (tables, allow) = match change {
+            ReplicationTablesChange::Add(tables) => (tables, true),
+            ReplicationTablesChange::Drop(tables) => (tables, false),
+        };
+        for table in tables {
+            match self.resolve_schema(table.clone()) {
+                Ok((schema, table)) if allow => self.allow(schema, table),
+                Ok((schema, table)) => self.deny(schema, table),
+                Err(error) => tracing::warn!(%error, "Ignoring change to replicated tables"),
+            }
+        }
+    }
+
+    /// Start replicating the table or glob pattern with the provided schema and name, undoing any
+    /// previous denial of the same table or pattern
+    fn allow(&mut self, schema: SqlIdentifier, table: SqlIdentifier) {
+        tracing::info!(%schema, %table, "allowing replication");
+        if is_pattern(&schema) || is_pattern(&table) {
+            let pattern = TablePattern { schema, table };
+            self.denied_patterns.retain(|p| *p != pattern);
+            for (schema, tables) in self.replication_denied.iter_mut() {
+                if let ReplicateTableSpec::Tables(tables) = tables {
+                    tables.retain(|t| !pattern.matches(schema, t));
+                }
+            }
+            if !self.replicated_patterns.contains(&pattern) {
+                self.replicated_patterns.push(pattern);
+            }
+        } else {
+            if let Some(tables) = self.replication_denied.get_mut(schema.as_str()) {
+                tables.remove(table.clone());
+            }
+            self.explicitly_replicated
+                .entry(schema)
+                .or_insert_with(ReplicateTableSpec::empty)
+                .insert(table);
+        }
+    }
+
+    /// Stop replicating the table or glob pattern with the provided schema and name
+    fn deny(&mut self, schema: SqlIdentifier, table: SqlIdentifier) {
+        if is_pattern(&schema) || is_pattern(&table) {
+            tracing::info!(%schema, %table, "denying replication");
+            let pattern = TablePattern { schema, table };
+            self.replicated_patterns.retain(|p| *p != pattern);
+            if !self.denied_patterns.contains(&pattern) {
+                self.denied_patterns.push(pattern);
+            }
+        } else {
+            self.deny_replication(&schema, &table);
         }
     }
 
@@ -184,22 +340,32 @@ impl TableFilter {
     }
 
     /// Check if a given table should be processed
-    pub(crate) fn should_be_processed<Q1, Q2>(&self, schema: &Q1, table: &Q2) -> bool
-    where
-        Q1: Ord + ?Sized,
-        Q2: Ord + ?Sized,
-        SqlIdentifier: Borrow<Q1> + Borrow<Q2>,
-    {
-        self.explicitly_replicated.is_empty() && !self.is_denied(schema, table)
-            || self.is_explicitly_replicated(schema, table)
+    pub(crate) fn should_be_processed(&self, schema: &str, table: &str) -> bool {
+        if self.is_denied(schema, table) {
+            return false;
+        }
+
+        // Tables replicated by name take precedence over patterns that deny them
+        if matches!(
+            self.explicitly_replicated.get(schema),
+            Some(ReplicateTableSpec::Tables(tables)) if tables.contains(table)
+        ) {
+            return true;
+        }
+
+        !self
+            .denied_patterns
+            .iter()
+            .any(|p| p.matches(schema, table))
+            && (self.replicate_all
+                || self.is_explicitly_replicated(schema, table)
+                || self
+                    .replicated_patterns
+                    .iter()
+                    .any(|p| p.matches(schema, table)))
     }
 
-    pub(crate) fn is_explicitly_replicated<Q1, Q2>(&self, schema: &Q1, table: &Q2) -> bool
-    where
-        Q1: Ord + ?Sized,
-        Q2: Ord + ?Sized,
-        SqlIdentifier: Borrow<Q1> + Borrow<Q2>,
-    {
+    pub(crate) fn is_explicitly_replicated(&self, schema: &str, table: &str) -> bool {
         let res = match self.explicitly_replicated.get(schema) {
             Some(tables) => tables.contains(table),
             None => false,
@@ -214,12 +380,7 @@ impl TableFilter {
         res
     }
 
-    pub(crate) fn is_denied<Q1, Q2>(&self, schema: &Q1, table: &Q2) -> bool
-    where
-        Q1: Ord + ?Sized,
-        Q2: Ord + ?Sized,
-        SqlIdentifier: Borrow<Q1> + Borrow<Q2>,
-    {
+    pub(crate) fn is_denied(&self, schema: &str, table: &str) -> bool {
         let res = match self.replication_denied.get(schema) {
             Some(tables) => tables.contains(table),
             None => false,
@@ -237,11 +398,15 @@ impl TableFilter {
 
 #[cfg(test)]
 mod tests {
-    use super::TableFilter;
+    use nom_sql::Relation;
+    use readyset_client::replication::ReplicationTablesChange;
+
+    use super::{glob_matches, TableFilter};
 
     #[test]
     fn empty_list() {
-        let filter = TableFilter::try_new(nom_sql::Dialect::MySQL, None, Some("noria")).unwrap();
+        let filter =
+            TableFilter::try_new(nom_sql::Dialect::MySQL, None, None, Some("noria")).unwrap();
         // By default should only allow all tables from the default schema
         assert!(filter.should_be_processed("noria", "table"));
         assert!(!filter.should_be_processed("readyset", "table"));
@@ -252,6 +417,7 @@ mod tests {
         let filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             Some("*.*".to_string().into()),
+            None,
             Some("noria"),
         )
         .unwrap();
@@ -261,7 +427,7 @@ mod tests {
 
     #[test]
     fn all_schemas_implicit() {
-        let filter = TableFilter::try_new(nom_sql::Dialect::MySQL, None, None).unwrap();
+        let filter = TableFilter::try_new(nom_sql::Dialect::MySQL, None, None, None).unwrap();
         assert!(filter.should_be_processed("noria", "table"));
         assert!(filter.should_be_processed("readyset", "table"));
     }
@@ -271,6 +437,7 @@ mod tests {
         let filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             Some("t1,t2,t3".to_string().into()),
+            None,
             Some("noria"),
         )
         .unwrap();
@@ -287,6 +454,7 @@ mod tests {
         let filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             Some("t1,noria.t2,readyset.t4,t3".to_string().into()),
+            None,
             Some("noria"),
         )
         .unwrap();
@@ -303,6 +471,7 @@ mod tests {
         let filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             Some("noria.*, readyset.t4, t3".to_string().into()),
+            None,
             Some("noria"),
         )
         .unwrap();
@@ -320,6 +489,7 @@ mod tests {
         let mut filter = TableFilter::try_new(
             nom_sql::Dialect::MySQL,
             Some("noria.*, readyset.t4, t3".to_string().into()),
+            None,
             Some("noria"),
         )
         .unwrap();
@@ -336,4 +506,115 @@ mod tests {
         filter.deny_replication("readyset", "t4");
         assert!(!filter.should_be_processed("readyset", "t4"));
     }
+
+    #[test]
+    fn glob_patterns() {
+        let filter = TableFilter::try_new(
+            nom_sql::Dialect::PostgreSQL,
+            Some("public.user_*, *.audit_log, public.t?".to_string().into()),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(filter.should_be_processed("public", "user_"));
+        assert!(filter.should_be_processed("public", "user_accounts"));
+        assert!(!filter.should_be_processed("public", "users"));
+        assert!(filter.should_be_processed("public", "audit_log"));
+        assert!(filter.should_be_processed("other", "audit_log"));
+        assert!(!filter.should_be_processed("other", "audit_logs"));
+        assert!(filter.should_be_processed("public", "t1"));
+        assert!(!filter.should_be_processed("public", "t12"));
+        assert!(!filter.should_be_processed("public", "t"));
+    }
+
+    #[test]
+    fn ignore_list() {
+        let filter = TableFilter::try_new(
+            nom_sql::Dialect::PostgreSQL,
+            Some("public.*".to_string().into()),
+            Some("public.tmp_*, public.t2".to_string().into()),
+            None,
+        )
+        .unwrap();
+        assert!(filter.should_be_processed("public", "t1"));
+        assert!(!filter.should_be_processed("public", "t2"));
+        assert!(!filter.should_be_processed("public", "tmp_1"));
+        assert!(!filter.should_be_processed("other", "t1"));
+    }
+
+    #[test]
+    fn ignore_list_replicating_all() {
+        let filter = TableFilter::try_new(
+            nom_sql::Dialect::PostgreSQL,
+            None,
+            Some("*.tmp_*".to_string().into()),
+            None,
+        )
+        .unwrap();
+        assert!(filter.should_be_processed("public", "t1"));
+        assert!(filter.should_be_processed("other", "t1"));
+        assert!(!filter.should_be_processed("public", "tmp_1"));
+        assert!(!filter.should_be_processed("other", "tmp_2"));
+    }
+
+    #[test]
+    fn add_and_drop_tables() {
+        let mut filter = TableFilter::try_new(
+            nom_sql::Dialect::MySQL,
+            Some("t1".to_string().into()),
+            None,
+            Some("noria"),
+        )
+        .unwrap();
+        assert!(!filter.should_be_processed("noria", "t2"));
+
+        filter.apply_change(&ReplicationTablesChange::Add(vec!["t2".into()]));
+        assert!(filter.should_be_processed("noria", "t1"));
+        assert!(filter.should_be_processed("noria", "t2"));
+
+        filter.apply_change(&ReplicationTablesChange::Drop(vec!["t1".into()]));
+        assert!(!filter.should_be_processed("noria", "t1"));
+        assert!(filter.should_be_processed("noria", "t2"));
+
+        filter.apply_change(&ReplicationTablesChange::Add(vec![Relation {
+            schema: Some("noria".into()),
+            name: "t*".into(),
+        }]));
+        assert!(filter.should_be_processed("noria", "t1"));
+        assert!(filter.should_be_processed("noria", "t3"));
+        assert!(!filter.should_be_processed("readyset", "t3"));
+    }
+
+    #[test]
+    fn drop_pattern_then_add_table() {
+        let mut filter = TableFilter::for_all_tables();
+        filter.apply_change(&ReplicationTablesChange::Drop(vec![Relation {
+            schema: Some("noria".into()),
+            name: "tmp_*".into(),
+        }]));
+        assert!(!filter.should_be_processed("noria", "tmp_1"));
+        assert!(!filter.should_be_processed("noria", "tmp_2"));
+        assert!(filter.should_be_processed("noria", "t1"));
+
+        // Tables added by name take precedence over denied patterns
+        filter.apply_change(&ReplicationTablesChange::Add(vec![Relation {
+            schema: Some("noria".into()),
+            name: "tmp_1".into(),
+        }]));
+        assert!(filter.should_be_processed("noria", "tmp_1"));
+        assert!(!filter.should_be_processed("noria", "tmp_2"));
+        assert!(filter.should_be_processed("noria", "t1"));
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches(b"*", b""));
+        assert!(glob_matches(b"*", b"abc"));
+        assert!(glob_matches(b"a*c", b"abbbc"));
+        assert!(glob_matches(b"a*b*c", b"aXbYbZc"));
+        assert!(!glob_matches(b"a*c", b"abcd"));
+        assert!(glob_matches(b"a?c", b"abc"));
+        assert!(!glob_matches(b"a?c", b"ac"));
+        assert!(!glob_matches(b"abc", b"ab"));
+    }
 }
@@ -13,6 +13,7 @@ use rand::distributions::Alphanumeric;
 use rand::{Rng, SeedableRng};
 use readyset_client::consensus::{Authority, LocalAuthority, LocalAuthorityStore};
 use readyset_client::recipe::changelist::{Change, ChangeList};
+use readyset_client::replication::{ReplicationTablesChange, GTID_LOG_PREFIX};
 use readyset_client::ReadySetHandle;
 use readyset_data::{Collation, DfValue, Dialect, TinyText};
 use readyset_errors::{ReadySetError, ReadySetResult};
@@ -318,6 +319,7 @@ impl TestHandle {
                 telemetry_sender,
                 server_startup,
                 false, // disable statement logging in tests
+                None,
             )
             .await
             {
@@ -391,6 +393,7 @@ impl TestHandle {
                             .unwrap(),
                         ),
                         always: false,
+                        options: Default::default(),
                     },
                 ],
                 self.dialect,
@@ -557,6 +560,110 @@ async fn mysql_replication() -> ReadySetResult<()> {
     replication_test_inner(&mysql_url()).await
 }
 
+/// Step the upstream's `gtid_mode` to `ON` (or back to `OFF`), returning whether it changed
+async fn set_mysql_gtid_mode(conn: &mut mysql_async::Conn, on: bool) -> ReadySetResult<bool> {
+    let mode: String = conn
+        .query_first("SELECT @@GLOBAL.gtid_mode")
+        .await?
+        .unwrap();
+    if mode.eq_ignore_ascii_case(if on { "ON" } else { "OFF" }) {
+        return Ok(false);
+    }
+
+    // `gtid_mode` can only be changed one step at a time
+    if on {
+        conn.query_drop("SET GLOBAL enforce_gtid_consistency = ON")
+            .await?;
+        for mode in ["OFF_PERMISSIVE", "ON_PERMISSIVE", "ON"] {
+            conn.query_drop(format!("SET GLOBAL gtid_mode = {mode}"))
+                .await?;
+        }
+    } else {
+        for mode in ["ON_PERMISSIVE", "OFF_PERMISSIVE", "OFF"] {
+            conn.query_drop(format!("SET GLOBAL gtid_mode = {mode}"))
+                .await?;
+        }
+        conn.query_drop("SET GLOBAL enforce_gtid_consistency = OFF")
+            .await?;
+    }
+    Ok(true)
+}
+
+/// Resumes replication after the upstream is replaced by a promoted replica, which has executed
+/// the same transactions but has binlog files of its own, so replication can only resume by GTID.
+///
+/// The promotion is emulated by discarding all of the upstream's binlogs and then setting its set
+/// of executed GTIDs back to what it was, as for a replica provisioned from a backup.
+#[tokio::test(flavor = "multi_thread")]
+#[serial_test::serial]
+async fn mysql_gtid_resume_on_promoted_replica() -> ReadySetResult<()> {
+    readyset_tracing::init_test_logging();
+    let url = mysql_url();
+    let mut client = DbConnection::connect(&url).await?;
+    let DbConnection::MySQL(conn) = &mut client else {
+        unreachable!()
+    };
+    let enabled_gtid_mode = set_mysql_gtid_mode(conn, true).await?;
+
+    client.query(CREATE_SCHEMA).await?;
+    client.query(POPULATE_SCHEMA).await?;
+
+    let (mut ctx, shutdown_tx) = TestHandle::start_noria(url.clone(), None).await?;
+    ctx.ready_notify.as_ref().unwrap().notified().await;
+    ctx.check_results("noria_view", "Snapshot", SNAPSHOT_RESULT)
+        .await?;
+
+    let offsets = ctx.controller().await.replication_offsets().await?;
+    assert!(offsets
+        .schema
+        .unwrap()
+        .replication_log_name
+        .starts_with(GTID_LOG_PREFIX));
+
+    ctx.stop_repl().await;
+    let DbConnection::MySQL(conn) = &mut client else {
+        unreachable!()
+    };
+    let executed: String = conn
+        .query_first("SELECT @@GLOBAL.gtid_executed")
+        .await?
+        .unwrap();
+    conn.query_drop("RESET MASTER").await?;
+    conn.query_drop(format!("SET GLOBAL gtid_purged = '{executed}'"))
+        .await?;
+
+    client
+        .query("INSERT INTO `groups` VALUES (5, 'xyz', 4)")
+        .await?;
+    ctx.start_repl(None, TelemetrySender::new_no_op(), false)
+        .await?;
+    ctx.check_results(
+        "noria_view",
+        "Promoted replica",
+        &[
+            &[DfValue::Int(1), tiny(b"abc"), DfValue::Int(2)],
+            &[DfValue::Int(2), tiny(b"bcd"), DfValue::Int(3)],
+            &[DfValue::Int(3), DfValue::None, DfValue::None],
+            &[DfValue::Int(5), tiny(b"xyz"), DfValue::Int(4)],
+            &[DfValue::Int(40), tiny(b"xyz"), DfValue::Int(4)],
+        ],
+    )
+    .await?;
+
+    ctx.stop().await;
+    shutdown_tx.shutdown().await;
+
+    if enabled_gtid_mode {
+        let DbConnection::MySQL(conn) = &mut client else {
+            unreachable!()
+        };
+        set_mysql_gtid_mode(conn, false).await?;
+    }
+    client.stop().await;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial_test::serial]
 #[slow]
@@ -653,6 +760,18 @@ async fn mysql_replication_resnapshot() -> ReadySetResult<()> {
     resnapshot_inner(&mysql_url()).await
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[serial_test::serial]
+async fn pgsql_change_replication_tables() -> ReadySetResult<()> {
+    change_replication_tables_inner(&pgsql_url()).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial_test::serial]
+async fn mysql_change_replication_tables() -> ReadySetResult<()> {
+    change_replication_tables_inner(&mysql_url()).await
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial_test::serial]
 async fn psql14_ddl_replicate_drop_table() {
@@ -1386,6 +1505,128 @@ async fn resnapshot_inner(url: &str) -> ReadySetResult<()> {
     Ok(())
 }
 
+/// Starts a server replicating from `url`, with its own replicator, sharing the authority store
+/// with any previous servers started with it
+async fn start_replicating_server(
+    url: &str,
+    authority_store: Arc<LocalAuthorityStore>,
+    replication_tables: &str,
+) -> (readyset_server::Handle, ShutdownSender, Arc<Authority>) {
+    let authority = Arc::new(Authority::from(LocalAuthority::new_with_store(
+        authority_store,
+    )));
+    let mut builder = Builder::for_tests();
+    builder.set_persistence(readyset_server::PersistenceParameters {
+        mode: readyset_server::DurabilityMode::DeleteOnExit,
+        ..Default::default()
+    });
+    builder.set_replicator_config(Config {
+        upstream_db_url: Some(url.to_string().into()),
+        replication_tables: Some(replication_tables.to_string().into()),
+        replicator_restart_timeout: Duration::from_secs(1),
+        ..Default::default()
+    });
+    let (mut handle, shutdown_tx) = builder.start(Arc::clone(&authority)).await.unwrap();
+    handle.backend_ready().await;
+    (handle, shutdown_tx, authority)
+}
+
+/// Tests that the tables added and dropped with `ALTER READYSET ADD TABLES` and `ALTER READYSET
+/// DROP TABLES` are (no longer) replicated, and stay that way after the leader changes
+async fn change_replication_tables_inner(url: &str) -> ReadySetResult<()> {
+    readyset_tracing::init_test_logging();
+
+    let mut client = DbConnection::connect(url).await?;
+    client
+        .query(
+            "
+            DROP TABLE IF EXISTS rt1 CASCADE; CREATE TABLE rt1 (id int);
+            DROP TABLE IF EXISTS rt2 CASCADE; CREATE TABLE rt2 (id int);
+            DROP TABLE IF EXISTS rt3 CASCADE; CREATE TABLE rt3 (id int);
+            INSERT INTO rt1 VALUES (1);
+            INSERT INTO rt2 VALUES (1);
+            INSERT INTO rt3 VALUES (1);
+            ",
+        )
+        .await?;
+
+    let rel = |name: &str| Relation {
+        schema: Some("public".into()),
+        name: name.into(),
+    };
+
+    let authority_store = Arc::new(LocalAuthorityStore::new());
+    let (mut noria, shutdown_tx, authority) =
+        start_replicating_server(url, Arc::clone(&authority_store), "public.rt1, public.rt3").await;
+    eventually! {
+        noria.table(rel("rt1")).await.is_ok() && noria.table(rel("rt3")).await.is_ok()
+    }
+    noria.table(rel("rt2")).await.unwrap_err();
+
+    noria
+        .change_replication_tables(ReplicationTablesChange::Add(vec![rel("rt2")]))
+        .await?;
+    noria
+        .change_replication_tables(ReplicationTablesChange::Drop(vec![rel("rt3")]))
+        .await?;
+    eventually! {
+        noria.table(rel("rt2")).await.is_ok() && noria.table(rel("rt3")).await.is_err()
+    }
+
+    // Fail over to a new leader, which should replicate the same tables
+    shutdown_tx.shutdown().await;
+    if let Authority::LocalAuthority(l) = authority.as_ref() {
+        l.delete_ephemeral();
+    }
+    drop(noria);
+
+    let (mut noria, shutdown_tx, _authority) =
+        start_replicating_server(url, authority_store, "public.rt1, public.rt3").await;
+    client.query("INSERT INTO rt2 VALUES (2)").await?;
+    eventually! {
+        noria
+            .extend_recipe(
+                ChangeList::from_str(
+                    "CREATE CACHE rt2_ids FROM SELECT id FROM public.rt2",
+                    Dialect::DEFAULT_MYSQL,
+                )
+                .unwrap(),
+            )
+            .await
+            .is_ok()
+    }
+    eventually! {
+        let mut view = noria.view("rt2_ids").await.unwrap().into_reader_handle().unwrap();
+        let mut ids = view
+            .lookup(&[0.into()], true)
+            .await
+            .unwrap()
+            .into_vec();
+        ids.sort();
+        ids == vec![vec![DfValue::from(1)], vec![DfValue::from(2)]]
+    }
+    noria.table(rel("rt1")).await.unwrap();
+    noria.table(rel("rt3")).await.unwrap_err();
+    assert!(noria
+        .non_replicated_relations()
+        .await
+        .unwrap()
+        .contains(&rel("rt3")));
+
+    shutdown_tx.shutdown().await;
+
+    client
+        .query(
+            "DROP TABLE IF EXISTS rt1 CASCADE;
+             DROP TABLE IF EXISTS rt2 CASCADE;
+             DROP TABLE IF EXISTS rt3 CASCADE;",
+        )
+        .await?;
+    client.stop().await;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[serial_test::serial]
 async fn mysql_enum_replication() -> ReadySetResult<()> {
@@ -1567,6 +1808,7 @@ async fn postgresql_ddl_replicate_drop_view_internal(url: &str) {
             .unwrap(),
         ),
         always: false,
+        options: Default::default(),
     };
     ctx.noria
         .extend_recipe(ChangeList::from_change(
@@ -1642,7 +1884,8 @@ async fn postgresql_ddl_replicate_create_view_internal(url: &str) {
                     )
                     .unwrap()
                 ),
-                always: true
+                always: true,
+                options: Default::default(),
             },
             Dialect::DEFAULT_POSTGRESQL
         ))
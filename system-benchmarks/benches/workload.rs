@@ -611,6 +611,8 @@ fn start_adapter_with_options(fallback_cache_options: FallbackCacheOptions) {
         default_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), BENCHMARK_PORT),
         connection_handler: MySqlHandler {
             enable_statement_logging: false,
+            tls_acceptor: None,
+            require_tls: false,
         },
         database_type: DatabaseType::MySQL,
         parse_dialect: nom_sql::Dialect::MySQL,
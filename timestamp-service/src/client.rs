@@ -4,11 +4,11 @@ use readyset_client::internal::LocalNodeIndex;
 
 /// Unique identifier for a client write discernable at both the
 /// debezium connector and the noria client.
-// TODO(andrew): Currently only MySQL is supported by the client.
-// https://app.clubhouse.io/readysettech/story/368
 pub enum WriteId {
     // MySQL global transaction identifier in form: <server-id>:<gtid>
     MySqlGtid(String),
+    // PostgreSQL log sequence number in form: <hi>/<lo>, where both halves are hexadecimal
+    PostgresLsn(String),
 }
 
 /// A key identifying the objects we are maintaining read-your-write
@@ -24,8 +24,6 @@ pub enum WriteKey {
 /// The timestamp client provides users the ability to synchronize
 /// read-your-write state at the noria client and debezium connector.
 // TODO(justin): Integrate with server compnoent of timestamp service.
-// TODO(andrew): TimestampClient only works with MySQL.
-// https://app.clubhouse.io/readysettech/story/368
 #[allow(dead_code)]
 #[derive(Clone, Default)]
 pub struct TimestampClient {}
@@ -33,7 +31,6 @@ pub struct TimestampClient {}
 impl TimestampClient {
     /// Sends a set of write keys, `keys` for a single write, identifier by `write_id` to
     /// the timestamp server. Returns the updated timestamp for the write.
-    // TODO(andrew): Currently only parsing MySql GTID's is supported.
     pub fn append_write(&self, write_id: WriteId, keys: Vec<WriteKey>) -> Result<Timestamp, Error> {
         // TODO: implement to interact with true service
         // https://app.clubhouse.io/readysettech/story/331
@@ -50,6 +47,17 @@ impl TimestampClient {
                 txid.parse()
                     .map_err(|_| anyhow!("GTID Parse Failure: GTID sequence number not a number"))?
             }
+            WriteId::PostgresLsn(lsn) => {
+                // Expecting form: <hi>/<lo>, e.g. 16/B374D848
+                let (hi, lo) = lsn
+                    .split_once('/')
+                    .ok_or_else(|| anyhow!("LSN Parse Failure: LSN is missing a '/'"))?;
+                let hi = u64::from_str_radix(hi, 16)
+                    .map_err(|_| anyhow!("LSN Parse Failure: LSN is not hexadecimal"))?;
+                let lo = u64::from_str_radix(lo, 16)
+                    .map_err(|_| anyhow!("LSN Parse Failure: LSN is not hexadecimal"))?;
+                hi << 32 | lo
+            }
         };
 
         let mut timestamp = Timestamp::default();
@@ -73,3 +81,31 @@ impl TimestampClient {
         Timestamp::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_write_postgres_lsn() {
+        let table = LocalNodeIndex::make(0);
+        let timestamp = TimestampClient::default()
+            .append_write(
+                WriteId::PostgresLsn("16/B374D848".to_owned()),
+                vec![WriteKey::TableIndex(table)],
+            )
+            .unwrap();
+        assert_eq!(timestamp.map[&table], 0x16_B374_D848);
+    }
+
+    #[test]
+    fn append_write_invalid_postgres_lsn() {
+        let client = TimestampClient::default();
+        assert!(client
+            .append_write(WriteId::PostgresLsn("B374D848".to_owned()), vec![])
+            .is_err());
+        assert!(client
+            .append_write(WriteId::PostgresLsn("16/XYZ".to_owned()), vec![])
+            .is_err());
+    }
+}